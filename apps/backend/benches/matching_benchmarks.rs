@@ -1,6 +1,8 @@
 use backend::engine::matcher::Matcher;
 use backend::engine::orderbook::Orderbook;
-use backend::models::domain::{Market, Order, OrderStatus, OrderType, Side};
+use backend::models::domain::{
+    Market, MarketStatus, Order, OrderStatus, OrderType, Side, TimeInForce,
+};
 use chrono::Utc;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use std::hint::black_box;
@@ -16,6 +18,14 @@ fn create_test_market() -> Market {
         min_size: 1000000,
         maker_fee_bps: 10,
         taker_fee_bps: 20,
+        min_price: None,
+        max_price: None,
+        max_open_orders_per_user: None,
+        max_open_notional_per_user: None,
+        min_notional: None,
+        last_price: None,
+        snap_to_tick: false,
+        status: MarketStatus::Open,
     }
 }
 
@@ -28,6 +38,8 @@ fn create_order(user: &str, market_id: &str, side: Side, price: u128, size: u128
         size,
         side,
         order_type: OrderType::Limit,
+        time_in_force: TimeInForce::Gtc,
+        min_fill: None,
         status: OrderStatus::Pending,
         filled_size: 0,
         created_at: Utc::now(),
@@ -152,6 +164,8 @@ fn bench_market_order_execution(c: &mut Criterion) {
                 size: 10_000_000,
                 side: Side::Buy,
                 order_type: OrderType::Market,
+                time_in_force: TimeInForce::Gtc,
+                min_fill: None,
                 status: OrderStatus::Pending,
                 filled_size: 0,
                 created_at: Utc::now(),
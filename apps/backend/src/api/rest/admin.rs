@@ -1,13 +1,21 @@
+use crate::api::rest::extract::JsonBody;
 use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{AdminRequest, AdminResponse};
+use crate::models::domain::{EngineEvent, EngineRequest, FeeSchedule, Market, MAX_TOKEN_DECIMALS};
 use crate::AppState;
 use axum::{extract::State, Json};
+use std::collections::HashSet;
+
+/// Most grants a single bulk faucet request may apply
+const MAX_BULK_FAUCET_GRANTS: usize = 1000;
 
 /// Admin endpoint for test/dev operations
 ///
 /// POST /api/admin
 ///
-/// Handles administrative operations like creating tokens, markets, and funding accounts.
+/// Handles administrative operations like creating tokens, markets, funding accounts
+/// (one at a time or in bulk), setting per-user fee tiers and per-market open order limits,
+/// moving markets between trading phases, and the kill switch that cancels every resting order.
 /// In production, this endpoint should be protected or disabled.
 #[utoipa::path(
     post,
@@ -16,13 +24,15 @@ use axum::{extract::State, Json};
     responses(
         (status = 200, description = "Admin operation successful", body = AdminResponse),
         (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 404, description = "Referenced token or market not found", body = ErrorResponse),
+        (status = 409, description = "Token or market already exists", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "admin"
 )]
 pub async fn admin_handler(
     State(state): State<AppState>,
-    Json(request): Json<AdminRequest>,
+    JsonBody(request): JsonBody<AdminRequest>,
 ) -> Result<Json<AdminResponse>> {
     match request {
         AdminRequest::CreateToken {
@@ -30,6 +40,16 @@ pub async fn admin_handler(
             decimals,
             name,
         } => {
+            if decimals > MAX_TOKEN_DECIMALS {
+                return Err(ExchangeError::InvalidTokenDecimals {
+                    decimals,
+                    max: MAX_TOKEN_DECIMALS,
+                });
+            }
+            if state.db.get_token(&ticker).await.is_ok() {
+                return Err(ExchangeError::TokenAlreadyExists { ticker });
+            }
+
             let token = state.db.create_token(ticker, decimals, name).await?;
 
             Ok(Json(AdminResponse::CreateToken { token }))
@@ -43,11 +63,15 @@ pub async fn admin_handler(
             min_size,
             maker_fee_bps,
             taker_fee_bps,
+            min_price,
+            max_price,
         } => {
             // Parse string values to u128
             let tick_size_u128 = tick_size.parse::<u128>()?;
             let lot_size_u128 = lot_size.parse::<u128>()?;
             let min_size_u128 = min_size.parse::<u128>()?;
+            let min_price_u128 = min_price.map(|p| p.parse::<u128>()).transpose()?;
+            let max_price_u128 = max_price.map(|p| p.parse::<u128>()).transpose()?;
 
             let market = state
                 .db
@@ -59,6 +83,8 @@ pub async fn admin_handler(
                     min_size_u128,
                     maker_fee_bps,
                     taker_fee_bps,
+                    min_price_u128,
+                    max_price_u128,
                 )
                 .await?;
 
@@ -94,5 +120,172 @@ pub async fn admin_handler(
                 new_balance: balance.amount.to_string(),
             }))
         }
+
+        AdminRequest::BulkFaucet { grants } => {
+            if grants.len() > MAX_BULK_FAUCET_GRANTS {
+                return Err(ExchangeError::InvalidParameter {
+                    message: format!(
+                        "At most {} grants per bulk faucet request",
+                        MAX_BULK_FAUCET_GRANTS
+                    ),
+                });
+            }
+
+            let mut parsed = Vec::with_capacity(grants.len());
+            let mut tickers = HashSet::new();
+            for grant in grants {
+                let amount = grant
+                    .amount
+                    .parse::<u128>()
+                    .map_err(|_| ExchangeError::InvalidAmount)?;
+                tickers.insert(grant.token_ticker.clone());
+                parsed.push((grant.user_address, grant.token_ticker, amount));
+            }
+
+            // Fail with a clear error instead of a foreign key violation
+            for ticker in tickers {
+                state.db.get_token(&ticker).await.map_err(|e| match e {
+                    ExchangeError::Database(sqlx::Error::RowNotFound) => {
+                        ExchangeError::TokenNotFound { ticker }
+                    }
+                    e => e,
+                })?;
+            }
+
+            let balances = state.db.add_balances(&parsed).await?;
+
+            Ok(Json(AdminResponse::BulkFaucet {
+                balances: balances.into_iter().map(Into::into).collect(),
+            }))
+        }
+
+        AdminRequest::SetUserFeeTier {
+            user_address,
+            maker_fee_tenth_bps,
+            taker_fee_tenth_bps,
+        } => {
+            // Fail with a clear error instead of a foreign key violation
+            state
+                .db
+                .get_user(&user_address)
+                .await
+                .map_err(|_| ExchangeError::UserNotFound {
+                    address: user_address.clone(),
+                })?;
+
+            let fee_schedule = state
+                .db
+                .set_user_fee_tier(
+                    &user_address,
+                    FeeSchedule {
+                        maker_fee_tenth_bps,
+                        taker_fee_tenth_bps,
+                    },
+                )
+                .await?;
+
+            Ok(Json(AdminResponse::SetUserFeeTier {
+                user_address,
+                fee_schedule,
+            }))
+        }
+
+        AdminRequest::SetMarketOrderLimits {
+            market_id,
+            max_open_orders_per_user,
+            max_open_notional_per_user,
+        } => {
+            let max_open_notional_u128 = max_open_notional_per_user
+                .map(|n| n.parse::<u128>())
+                .transpose()?;
+
+            let market = state
+                .db
+                .set_market_order_limits(
+                    &market_id,
+                    max_open_orders_per_user,
+                    max_open_notional_u128,
+                )
+                .await?;
+            notify_market_changed(&state, &market);
+
+            Ok(Json(AdminResponse::SetMarketOrderLimits {
+                market: market.into(),
+            }))
+        }
+
+        AdminRequest::SetMarketMinNotional {
+            market_id,
+            min_notional,
+        } => {
+            let min_notional_u128 = min_notional.map(|n| n.parse::<u128>()).transpose()?;
+
+            let market = state
+                .db
+                .set_market_min_notional(&market_id, min_notional_u128)
+                .await?;
+            notify_market_changed(&state, &market);
+
+            Ok(Json(AdminResponse::SetMarketMinNotional {
+                market: market.into(),
+            }))
+        }
+
+        AdminRequest::SetMarketSnapToTick {
+            market_id,
+            snap_to_tick,
+        } => {
+            let market = state
+                .db
+                .set_market_snap_to_tick(&market_id, snap_to_tick)
+                .await?;
+            notify_market_changed(&state, &market);
+
+            Ok(Json(AdminResponse::SetMarketSnapToTick {
+                market: market.into(),
+            }))
+        }
+
+        AdminRequest::CancelEverything => {
+            let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+            state
+                .engine
+                .send(EngineRequest::CancelEverything { response_tx })
+                .await?;
+
+            let count = response_rx
+                .await
+                .map_err(|_| ExchangeError::EngineReceiveFailed)??;
+
+            Ok(Json(AdminResponse::CancelEverything { count }))
+        }
+
+        AdminRequest::SetMarketStatus { market_id, status } => {
+            let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+            state
+                .engine
+                .send(EngineRequest::SetMarketStatus {
+                    market_id,
+                    status,
+                    response_tx,
+                })
+                .await?;
+
+            let market = response_rx
+                .await
+                .map_err(|_| ExchangeError::EngineReceiveFailed)??;
+
+            Ok(Json(AdminResponse::SetMarketStatus {
+                market: market.into(),
+            }))
+        }
     }
 }
+
+/// Tell market status subscribers that a market's parameters changed
+fn notify_market_changed(state: &AppState, market: &Market) {
+    let _ = state.event_tx.send(EngineEvent::MarketStatusChanged {
+        market_id: market.id.clone(),
+        status: market.status,
+    });
+}
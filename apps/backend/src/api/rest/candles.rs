@@ -1,42 +1,50 @@
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{CandlesRequest, CandlesResponse};
+use crate::models::domain::CandleInterval;
 use crate::AppState;
 use axum::{extract::State, Json};
 
 /// Get OHLCV candles for a market
 ///
 /// POST /api/candles
+///
+/// Supported intervals: 1m, 5m, 15m, 1h, 4h, 1d, 1w
 #[utoipa::path(
     post,
     path = "/api/candles",
     request_body = CandlesRequest,
     responses(
         (status = 200, description = "Candles retrieved successfully", body = CandlesResponse),
-        (status = 400, description = "Invalid parameters"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "candles"
 )]
 pub async fn candles(
     State(state): State<AppState>,
-    Json(params): Json<CandlesRequest>,
-) -> Result<Json<CandlesResponse>, String> {
-    // Validate interval
-    if !["1m", "5m", "15m", "1h", "1d"].contains(&params.interval.as_str()) {
-        return Err("Invalid interval. Must be one of: 1m, 5m, 15m, 1h, 1d".to_string());
-    }
+    JsonBody(params): JsonBody<CandlesRequest>,
+) -> Result<Json<CandlesResponse>> {
+    let interval: CandleInterval = params
+        .interval
+        .parse()
+        .map_err(|message| ExchangeError::InvalidParameter { message })?;
+
+    // An unknown market is a 404 rather than an empty series
+    state.db.get_market(&params.market_id).await?;
 
     // Query candles through the db layer
     let candles = state
         .db
         .get_candles_for_api(
             &params.market_id,
-            &params.interval,
+            interval,
             params.from,
             params.to,
             params.count_back,
         )
-        .await
-        .map_err(|e| format!("Failed to query candles: {}", e))?;
+        .await?;
 
     Ok(Json(CandlesResponse { candles }))
 }
@@ -1,5 +1,6 @@
 use axum::{extract::State, response::Json};
 
+use crate::api::rest::extract::JsonBody;
 use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{DripRequest, DripResponse};
 use crate::models::domain::EngineEvent;
@@ -20,7 +21,7 @@ use crate::models::domain::EngineEvent;
 )]
 pub async fn drip(
     State(state): State<crate::AppState>,
-    Json(request): Json<DripRequest>,
+    JsonBody(request): JsonBody<DripRequest>,
 ) -> Result<Json<DripResponse>> {
     match request {
         DripRequest::Faucet {
@@ -0,0 +1,79 @@
+use axum::{extract::State, response::Json};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
+use crate::models::api::{EstimateRequest, EstimateResponse};
+use crate::models::domain::{EngineRequest, Order, OrderStatus, TimeInForce};
+use tokio::sync::oneshot;
+
+/// Simulate an order against the live orderbook without placing it
+///
+/// POST /api/estimate
+///
+/// Returns the projected fill size, average price, slippage versus the best
+/// opposite-side price, and the size that would rest on the book.
+#[utoipa::path(
+    post,
+    path = "/api/estimate",
+    request_body = EstimateRequest,
+    responses(
+        (status = 200, description = "Estimate computed", body = EstimateResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Engine request queue is full", body = ErrorResponse)
+    ),
+    tag = "trade"
+)]
+pub async fn estimate(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<EstimateRequest>,
+) -> Result<Json<EstimateResponse>> {
+    let time_in_force = TimeInForce::resolve(request.order_type, request.time_in_force)?;
+
+    // Parse price and size from strings to u128
+    let price_value = request
+        .price
+        .parse::<u128>()
+        .map_err(|_| ExchangeError::InvalidPrice)?;
+    let size_value = request
+        .size
+        .parse::<u128>()
+        .map_err(|_| ExchangeError::InvalidSize)?;
+    let min_fill = request
+        .min_fill
+        .map(|min_fill| min_fill.parse::<u128>())
+        .transpose()
+        .map_err(|_| ExchangeError::InvalidSize)?;
+
+    // Build the order exactly as a placement would, it is never persisted
+    let order = Order {
+        id: Uuid::new_v4(),
+        user_address: request.user_address.unwrap_or_default(),
+        market_id: request.market_id,
+        side: request.side,
+        order_type: request.order_type,
+        time_in_force,
+        min_fill,
+        price: price_value,
+        size: size_value,
+        filled_size: 0,
+        status: OrderStatus::Pending,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    let (response_tx, response_rx) = oneshot::channel();
+    state
+        .engine
+        .send(EngineRequest::Estimate { order, response_tx })
+        .await?;
+
+    let estimate = response_rx
+        .await
+        .map_err(|_| ExchangeError::EngineReceiveFailed)??;
+
+    Ok(Json(estimate))
+}
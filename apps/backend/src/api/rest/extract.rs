@@ -0,0 +1,22 @@
+use axum::extract::{rejection::JsonRejection, FromRequest, Request};
+
+use crate::errors::ExchangeError;
+
+/// JSON request body extractor
+///
+/// Works like `axum::Json`, but a missing, malformed or mistyped body is
+/// rejected with an `ErrorResponse` instead of axum's plain text message
+pub struct JsonBody<T>(pub T);
+
+impl<S, T> FromRequest<S> for JsonBody<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ExchangeError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Self(value))
+    }
+}
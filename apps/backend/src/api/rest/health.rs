@@ -1,19 +1,60 @@
-use crate::models::ApiResponse;
-use axum::response::Json;
+use crate::models::api::{ComponentStatus, HealthStatus};
+use axum::{extract::State, http::StatusCode, response::Json};
+use std::time::Duration;
 
+/// How long each dependency probe may take before it counts as down
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Report the status of each backend dependency
+///
+/// GET /api/health
+///
+/// Returns 200 when every subsystem is up, otherwise 503 with the same body
+/// so callers can see which subsystem is down.
 #[utoipa::path(
     get,
     path = "/api/health",
     responses(
-        (status = 200, description = "Health check response", body = ApiResponse)
+        (status = 200, description = "All subsystems are up", body = HealthStatus),
+        (status = 503, description = "One or more subsystems are down", body = HealthStatus)
     )
 )]
-pub async fn health_check() -> Json<ApiResponse> {
-    Json(ApiResponse {
-        message: "Backend is running!".to_string(),
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-    })
+pub async fn health_check(
+    State(state): State<crate::AppState>,
+) -> (StatusCode, Json<HealthStatus>) {
+    let (postgres, clickhouse) = tokio::join!(
+        probe(sqlx::query("SELECT 1").execute(&state.db.postgres)),
+        probe(state.db.clickhouse.query("SELECT 1").execute()),
+    );
+    let engine = if state.engine.is_closed() {
+        ComponentStatus::Down
+    } else {
+        ComponentStatus::Up
+    };
+
+    let health = HealthStatus::new(postgres, clickhouse, engine);
+    let code = if health.is_healthy() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (code, Json(health))
+}
+
+/// Run a dependency probe, treating errors and timeouts as down
+async fn probe<T, E: std::fmt::Display>(
+    check: impl std::future::Future<Output = Result<T, E>>,
+) -> ComponentStatus {
+    match tokio::time::timeout(PROBE_TIMEOUT, check).await {
+        Ok(Ok(_)) => ComponentStatus::Up,
+        Ok(Err(e)) => {
+            log::warn!("Health probe failed: {}", e);
+            ComponentStatus::Down
+        }
+        Err(_) => {
+            log::warn!("Health probe timed out after {:?}", PROBE_TIMEOUT);
+            ComponentStatus::Down
+        }
+    }
 }
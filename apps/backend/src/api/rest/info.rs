@@ -1,7 +1,10 @@
 use axum::{extract::State, response::Json};
 
-use crate::errors::{ErrorResponse, Result};
+use crate::api::rest::extract::JsonBody;
+use crate::db::Db;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{InfoRequest, InfoResponse};
+use crate::models::domain::Token;
 
 /// Get information about tokens, markets, etc.
 #[utoipa::path(
@@ -18,7 +21,7 @@ use crate::models::api::{InfoRequest, InfoResponse};
 )]
 pub async fn info(
     State(_state): State<crate::AppState>,
-    Json(request): Json<InfoRequest>,
+    JsonBody(request): JsonBody<InfoRequest>,
 ) -> Result<Json<InfoResponse>> {
     match request {
         InfoRequest::TokenDetails { ticker } => {
@@ -31,6 +34,17 @@ pub async fn info(
                 market: market.into(),
             }))
         }
+        InfoRequest::MarketFull { market_id } => {
+            let market = _state.db.get_market(&market_id).await?;
+            let base_token = market_token(&_state.db, &market.base_ticker).await?;
+            let quote_token = market_token(&_state.db, &market.quote_ticker).await?;
+
+            Ok(Json(InfoResponse::MarketFull {
+                market: market.into(),
+                base_token,
+                quote_token,
+            }))
+        }
         InfoRequest::AllMarkets => {
             let markets = _state.db.list_markets().await?;
             Ok(Json(InfoResponse::AllMarkets {
@@ -41,5 +55,25 @@ pub async fn info(
             let tokens = _state.db.list_tokens().await?;
             Ok(Json(InfoResponse::AllTokens { tokens }))
         }
+        InfoRequest::Markets { market_ids } => {
+            let markets = _state.db.get_markets_by_ids(&market_ids).await?;
+            Ok(Json(InfoResponse::Markets {
+                markets: markets.into_iter().map(|m| m.into()).collect(),
+            }))
+        }
+        InfoRequest::Tokens { tickers } => {
+            let tokens = _state.db.get_tokens_by_tickers(&tickers).await?;
+            Ok(Json(InfoResponse::Tokens { tokens }))
+        }
     }
 }
+
+/// Token a market trades, `TokenNotFound` rather than a db error if it's missing
+async fn market_token(db: &Db, ticker: &str) -> Result<Token> {
+    db.get_token(ticker).await.map_err(|e| match e {
+        ExchangeError::Database(sqlx::Error::RowNotFound) => ExchangeError::TokenNotFound {
+            ticker: ticker.to_string(),
+        },
+        e => e,
+    })
+}
@@ -0,0 +1,100 @@
+use axum::{extract::State, response::Json};
+use chrono::Utc;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
+use crate::models::api::{MarketSearchRequest, MarketSearchResponse, MarketSortBy};
+
+/// Page size when the request doesn't set one
+const DEFAULT_SEARCH_LIMIT: u32 = 100;
+/// Largest page a single search may request
+const MAX_SEARCH_LIMIT: u32 = 500;
+
+/// Search markets with filtering, sorting and paging
+///
+/// POST /api/markets/search
+///
+/// Filters by exact quote ticker and base ticker substring, then sorts
+/// alphabetically or by 24h quote volume. `total` counts every match so
+/// callers can page through with `offset`.
+#[utoipa::path(
+    post,
+    path = "/api/markets/search",
+    request_body = MarketSearchRequest,
+    responses(
+        (status = 200, description = "Matching markets", body = MarketSearchResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "info"
+)]
+pub async fn search_markets(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<MarketSearchRequest>,
+) -> Result<Json<MarketSearchResponse>> {
+    let limit = request.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if limit == 0 || limit > MAX_SEARCH_LIMIT {
+        return Err(ExchangeError::InvalidParameter {
+            message: format!("limit must be between 1 and {}", MAX_SEARCH_LIMIT),
+        });
+    }
+    let offset = request.offset.unwrap_or(0) as usize;
+
+    let base_filter = request.base_ticker.map(|base| base.to_lowercase());
+    let mut markets: Vec<_> = state
+        .db
+        .list_markets()
+        .await?
+        .into_iter()
+        .filter(|market| {
+            request
+                .quote_ticker
+                .as_ref()
+                .is_none_or(|quote| &market.quote_ticker == quote)
+        })
+        .filter(|market| {
+            base_filter
+                .as_ref()
+                .is_none_or(|base| market.base_ticker.to_lowercase().contains(base))
+        })
+        .collect();
+
+    match request.sort_by {
+        MarketSortBy::Alphabetical => markets.sort_by(|a, b| a.id.cmp(&b.id)),
+        MarketSortBy::Volume24h => {
+            let now = Utc::now().timestamp();
+            let volumes = state.db.get_trade_volumes(now - 24 * 60 * 60, now).await?;
+            let decimals: HashMap<String, u8> = state
+                .db
+                .list_tokens()
+                .await?
+                .into_iter()
+                .map(|token| (token.ticker, token.decimals))
+                .collect();
+
+            // Quote atoms, so markets are compared by what was paid rather than base units
+            markets.sort_by_cached_key(|market| {
+                let volume = volumes
+                    .get(&market.id)
+                    .map(|volume| {
+                        let base_decimals = decimals.get(&market.base_ticker).copied();
+                        volume.quote_volume(base_decimals.unwrap_or(0))
+                    })
+                    .unwrap_or(0);
+                (Reverse(volume), market.id.clone())
+            });
+        }
+    }
+
+    let total = markets.len();
+    let markets = markets
+        .into_iter()
+        .skip(offset)
+        .take(limit as usize)
+        .map(|market| market.into())
+        .collect();
+
+    Ok(Json(MarketSearchResponse { markets, total }))
+}
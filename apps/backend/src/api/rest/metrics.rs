@@ -0,0 +1,25 @@
+use axum::{extract::State, response::Json};
+
+use crate::models::api::MetricsResponse;
+
+/// Report runtime metrics
+///
+/// GET /api/metrics
+///
+/// Exposes the engine request queue depth and how often a full queue
+/// rejected or delayed a request, plus open WebSocket connections and
+/// how many were dropped by the heartbeat checks.
+#[utoipa::path(
+    get,
+    path = "/api/metrics",
+    responses(
+        (status = 200, description = "Current metrics", body = MetricsResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn metrics(State(state): State<crate::AppState>) -> Json<MetricsResponse> {
+    Json(MetricsResponse {
+        engine_queue: state.engine.metrics(),
+        websocket: state.ws_metrics.metrics(),
+    })
+}
@@ -10,8 +10,14 @@ use crate::models::ApiResponse;
 pub mod admin;
 pub mod candles;
 pub mod drip;
+pub mod estimate;
+pub mod extract;
 pub mod health;
 pub mod info;
+pub mod markets;
+pub mod metrics;
+pub mod stats;
+pub mod tape;
 pub mod trade;
 pub mod user;
 
@@ -25,46 +31,89 @@ pub mod user;
     paths(
         health::health_check,
         info::info,
+        markets::search_markets,
         user::user,
         trade::trade,
+        estimate::estimate,
         drip::drip,
         admin::admin_handler,
         candles::candles,
+        tape::trades,
+        stats::vwap,
+        stats::liquidity,
+        stats::microstructure,
+        stats::historical_book,
+        metrics::metrics,
     ),
     components(
         schemas(
             ApiResponse,
+            // Health types
+            crate::models::api::HealthStatus,
+            crate::models::api::ComponentStatus,
+            crate::models::api::OverallStatus,
             // Unified error response
             crate::errors::ErrorResponse,
+            crate::errors::RejectReason,
             // Info types
             crate::models::api::InfoRequest,
             crate::models::api::InfoResponse,
+            crate::models::api::MarketSearchRequest,
+            crate::models::api::MarketSearchResponse,
+            crate::models::api::MarketSortBy,
             // User types
             crate::models::api::UserRequest,
             crate::models::api::UserResponse,
             // Trade types
             crate::models::api::TradeRequest,
             crate::models::api::TradeResponse,
+            crate::models::api::OrderRefund,
+            // Estimate types
+            crate::models::api::EstimateRequest,
+            crate::models::api::EstimateResponse,
             // Drip types
             crate::models::api::DripRequest,
             crate::models::api::DripResponse,
             // Admin types
             crate::models::api::AdminRequest,
             crate::models::api::AdminResponse,
+            crate::models::api::FaucetGrant,
             // Candles types
             crate::models::api::CandlesRequest,
             crate::models::api::ApiCandle,
             crate::models::api::CandlesResponse,
+            // Trade tape types
+            crate::models::api::TradesRequest,
+            crate::models::api::TradesResponse,
+            // Stats types
+            crate::models::api::VwapRequest,
+            crate::models::api::VwapResponse,
+            crate::models::api::LiquidityRequest,
+            crate::models::api::LiquidityResponse,
+            crate::models::api::ApiLiquidityBucket,
+            crate::models::api::MicrostructureRequest,
+            crate::models::api::MicrostructureResponse,
+            crate::models::api::HistoricalBookRequest,
+            crate::models::api::HistoricalBookResponse,
+            crate::models::api::ApiBookLevel,
+            // Metrics types
+            crate::models::api::MetricsResponse,
+            crate::models::api::EngineQueueMetrics,
+            crate::models::api::WebSocketMetrics,
+            crate::models::domain::QueueFullPolicy,
             // API types (only expose API layer in OpenAPI, not domain)
             crate::models::domain::Token,
+            crate::models::domain::FeeSchedule,
             crate::models::api::ApiMarket,
             crate::models::api::ApiOrder,
             crate::models::api::ApiTrade,
             crate::models::api::ApiBalance,
+            crate::models::api::ApiPosition,
             // Enums are shared between API and domain
             crate::models::domain::Side,
             crate::models::domain::OrderType,
             crate::models::domain::OrderStatus,
+            crate::models::domain::TimeInForce,
         )
     ),
     tags(
@@ -74,7 +123,9 @@ pub mod user;
         (name = "trade", description = "Trading endpoints"),
         (name = "drip", description = "Get free money"),
         (name = "admin", description = "Admin operations (test/dev only)"),
-        (name = "candles", description = "OHLCV candle data")
+        (name = "candles", description = "OHLCV candle data"),
+        (name = "tape", description = "Public trade tape"),
+        (name = "stats", description = "Market analytics")
     )
 )]
 pub struct ApiDoc;
@@ -83,9 +134,17 @@ pub fn create_rest() -> Router<crate::AppState> {
     Router::new()
         .route("/api/health", get(health::health_check))
         .route("/api/info", post(info::info))
+        .route("/api/markets/search", post(markets::search_markets))
         .route("/api/user", post(user::user))
         .route("/api/trade", post(trade::trade))
+        .route("/api/estimate", post(estimate::estimate))
         .route("/api/candles", post(candles::candles))
+        .route("/api/trades", post(tape::trades))
+        .route("/api/vwap", post(stats::vwap))
+        .route("/api/liquidity", post(stats::liquidity))
+        .route("/api/microstructure", post(stats::microstructure))
+        .route("/api/book/historical", post(stats::historical_book))
+        .route("/api/metrics", get(metrics::metrics))
         .route("/api/drip", post(drip::drip))
         .route("/api/admin", post(admin::admin_handler))
         .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
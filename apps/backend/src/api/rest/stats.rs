@@ -0,0 +1,248 @@
+use axum::{extract::State, response::Json};
+use tokio::sync::oneshot;
+
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
+use crate::models::api::{
+    ApiBookLevel, ApiLiquidityBucket, HistoricalBookRequest, HistoricalBookResponse,
+    LiquidityRequest, LiquidityResponse, MicrostructureRequest, MicrostructureResponse,
+    VwapRequest, VwapResponse,
+};
+use crate::models::domain::{EngineRequest, OrderbookLevel};
+
+/// Most buckets a single liquidity profile may request
+const MAX_LIQUIDITY_BUCKETS: u32 = 1000;
+
+/// Levels per side used for microstructure indicators when none are requested
+const DEFAULT_MICROSTRUCTURE_DEPTH: u32 = 10;
+
+/// Most levels per side microstructure indicators may cover
+const MAX_MICROSTRUCTURE_DEPTH: u32 = 1000;
+
+/// Get the volume-weighted average price of a market over a time window
+///
+/// POST /api/vwap
+///
+/// Aggregates trades in [from, to) from ClickHouse. `vwap` is null when
+/// no trades happened in the window.
+#[utoipa::path(
+    post,
+    path = "/api/vwap",
+    request_body = VwapRequest,
+    responses(
+        (status = 200, description = "VWAP computed", body = VwapResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn vwap(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<VwapRequest>,
+) -> Result<Json<VwapResponse>> {
+    if request.from >= request.to {
+        return Err(ExchangeError::InvalidParameter {
+            message: "from must be before to".to_string(),
+        });
+    }
+
+    // Base decimals are needed to scale notional into quote atoms
+    let market = state.db.get_market(&request.market_id).await?;
+    let base_token = state.db.get_token(&market.base_ticker).await?;
+
+    let volume = state
+        .db
+        .get_trade_volume(&request.market_id, request.from, request.to)
+        .await?;
+
+    Ok(Json(VwapResponse {
+        market_id: request.market_id,
+        from: request.from,
+        to: request.to,
+        vwap: volume.vwap().map(|price| price.to_string()),
+        base_volume: volume.base_volume.to_string(),
+        quote_volume: volume.quote_volume(base_token.decimals).to_string(),
+        trade_count: volume.trade_count,
+    }))
+}
+
+/// Get resting liquidity aggregated into price buckets
+///
+/// POST /api/liquidity
+///
+/// Splits [from_price, to_price) into `buckets` contiguous buckets and sums
+/// the resting bid and ask size in each. Unlike the orderbook snapshot this
+/// covers any range, not just the top of the book. An empty range returns
+/// no buckets.
+#[utoipa::path(
+    post,
+    path = "/api/liquidity",
+    request_body = LiquidityRequest,
+    responses(
+        (status = 200, description = "Liquidity profile computed", body = LiquidityResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Engine request queue is full", body = ErrorResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn liquidity(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<LiquidityRequest>,
+) -> Result<Json<LiquidityResponse>> {
+    let from_price = request
+        .from_price
+        .parse::<u128>()
+        .map_err(|_| ExchangeError::InvalidPrice)?;
+    let to_price = request
+        .to_price
+        .parse::<u128>()
+        .map_err(|_| ExchangeError::InvalidPrice)?;
+    if request.buckets > MAX_LIQUIDITY_BUCKETS {
+        return Err(ExchangeError::InvalidParameter {
+            message: format!("buckets must be at most {}", MAX_LIQUIDITY_BUCKETS),
+        });
+    }
+
+    state.db.get_market(&request.market_id).await?;
+
+    let (response_tx, response_rx) = oneshot::channel();
+    state
+        .engine
+        .send(EngineRequest::LiquidityProfile {
+            market_id: request.market_id.clone(),
+            from_price,
+            to_price,
+            buckets: request.buckets,
+            response_tx,
+        })
+        .await?;
+    let profile = response_rx
+        .await
+        .map_err(|_| ExchangeError::EngineReceiveFailed)?;
+
+    Ok(Json(LiquidityResponse {
+        market_id: request.market_id,
+        buckets: profile
+            .into_iter()
+            .map(|bucket| ApiLiquidityBucket {
+                price_low: bucket.price_low.to_string(),
+                price_high: bucket.price_high.to_string(),
+                bid_size: bucket.bid_size.to_string(),
+                ask_size: bucket.ask_size.to_string(),
+            })
+            .collect(),
+    }))
+}
+
+/// Get top-of-book microstructure indicators
+///
+/// POST /api/microstructure
+///
+/// Computed from the engine's live book: best bid and ask, spread (absolute
+/// and in bps of the mid), mid price, and the bid share of resting volume over
+/// the top `depth` levels of each side. Spread and mid are null for a
+/// one-sided book, imbalance is null for an empty one.
+#[utoipa::path(
+    post,
+    path = "/api/microstructure",
+    request_body = MicrostructureRequest,
+    responses(
+        (status = 200, description = "Indicators computed", body = MicrostructureResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Engine request queue is full", body = ErrorResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn microstructure(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<MicrostructureRequest>,
+) -> Result<Json<MicrostructureResponse>> {
+    let depth = request.depth.unwrap_or(DEFAULT_MICROSTRUCTURE_DEPTH);
+    if depth == 0 || depth > MAX_MICROSTRUCTURE_DEPTH {
+        return Err(ExchangeError::InvalidParameter {
+            message: format!("depth must be between 1 and {}", MAX_MICROSTRUCTURE_DEPTH),
+        });
+    }
+
+    state.db.get_market(&request.market_id).await?;
+
+    let (response_tx, response_rx) = oneshot::channel();
+    state
+        .engine
+        .send(EngineRequest::BookIndicators {
+            market_id: request.market_id.clone(),
+            depth: depth as usize,
+            response_tx,
+        })
+        .await?;
+    let indicators = response_rx
+        .await
+        .map_err(|_| ExchangeError::EngineReceiveFailed)?;
+
+    Ok(Json(MicrostructureResponse {
+        market_id: request.market_id,
+        depth,
+        best_bid: indicators.best_bid.map(|p| p.to_string()),
+        best_ask: indicators.best_ask.map(|p| p.to_string()),
+        spread: indicators.spread().map(|s| s.to_string()),
+        spread_bps: indicators.spread_bps(),
+        mid_price: indicators.mid_price().map(|p| p.to_string()),
+        bid_volume: indicators.bid_volume.to_string(),
+        ask_volume: indicators.ask_volume.to_string(),
+        imbalance: indicators.imbalance(),
+    }))
+}
+
+/// Get a market's orderbook as it stood at a past time
+///
+/// POST /api/book/historical
+///
+/// Rebuilt from the persisted orderbook snapshot and the engine request log,
+/// for backtesting. Timestamps before any logged activity return an empty book.
+#[utoipa::path(
+    post,
+    path = "/api/book/historical",
+    request_body = HistoricalBookRequest,
+    responses(
+        (status = 200, description = "Orderbook rebuilt", body = HistoricalBookResponse),
+        (status = 400, description = "Invalid request parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "stats"
+)]
+pub async fn historical_book(
+    State(state): State<crate::AppState>,
+    JsonBody(request): JsonBody<HistoricalBookRequest>,
+) -> Result<Json<HistoricalBookResponse>> {
+    let at = chrono::DateTime::from_timestamp(request.timestamp, 0).ok_or_else(|| {
+        ExchangeError::InvalidParameter {
+            message: format!("timestamp {} is out of range", request.timestamp),
+        }
+    })?;
+
+    let snapshot = state.db.orderbook_at(&request.market_id, at).await?;
+
+    let levels = |levels: Vec<OrderbookLevel>| {
+        levels
+            .into_iter()
+            .map(|level| ApiBookLevel {
+                price: level.price.to_string(),
+                size: level.size.to_string(),
+            })
+            .collect()
+    };
+
+    Ok(Json(HistoricalBookResponse {
+        market_id: snapshot.market_id,
+        timestamp: request.timestamp,
+        bids: levels(snapshot.bids),
+        asks: levels(snapshot.asks),
+        checksum: snapshot.checksum,
+    }))
+}
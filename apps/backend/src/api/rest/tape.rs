@@ -0,0 +1,71 @@
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
+use crate::models::api::{TradesRequest, TradesResponse};
+use crate::models::domain::TradeCursor;
+use crate::AppState;
+use axum::{extract::State, Json};
+
+/// Trades returned when the request sets no limit
+const DEFAULT_TRADES_LIMIT: u32 = 100;
+
+/// Get a market's public trades, most recent first
+///
+/// POST /api/trades
+///
+/// Optionally filtered to one taker side and a minimum size in base atoms,
+/// e.g. only large buys, and to a time range. Filtering happens in the
+/// ClickHouse query. A full page comes with a `next_cursor` for the next,
+/// older page.
+#[utoipa::path(
+    post,
+    path = "/api/trades",
+    request_body = TradesRequest,
+    responses(
+        (status = 200, description = "Trades retrieved successfully", body = TradesResponse),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 404, description = "Market not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "tape"
+)]
+pub async fn trades(
+    State(state): State<AppState>,
+    JsonBody(request): JsonBody<TradesRequest>,
+) -> Result<Json<TradesResponse>> {
+    let min_size = request
+        .min_size
+        .map(|size| size.parse::<u128>())
+        .transpose()?;
+    let cursor = request
+        .cursor
+        .map(|cursor| cursor.parse::<TradeCursor>())
+        .transpose()
+        .map_err(|message| ExchangeError::InvalidParameter { message })?;
+
+    state.db.get_market(&request.market_id).await?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_TRADES_LIMIT).min(1000);
+    let trades = state
+        .db
+        .get_trades_page(
+            &request.market_id,
+            request.side,
+            min_size,
+            request.from,
+            request.to,
+            cursor,
+            limit,
+        )
+        .await?;
+
+    // Only a full page can have more behind it
+    let next_cursor = match trades.last() {
+        Some(last) if trades.len() as u32 == limit => Some(TradeCursor::after(last).to_string()),
+        _ => None,
+    };
+
+    Ok(Json(TradesResponse {
+        trades: trades.into_iter().map(|trade| trade.into()).collect(),
+        next_cursor,
+    }))
+}
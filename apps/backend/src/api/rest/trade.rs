@@ -1,29 +1,64 @@
-use axum::{extract::State, response::Json};
+use axum::{
+    extract::State,
+    response::{IntoResponse, Json, Response},
+};
 use chrono::Utc;
 use uuid::Uuid;
 
+use crate::api::rest::extract::JsonBody;
 use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{TradeRequest, TradeResponse};
-use crate::models::domain::{EngineRequest, Order, OrderStatus};
+use crate::models::domain::{EngineRequest, Order, OrderStatus, TimeInForce};
 use tokio::sync::oneshot;
+use tracing::Instrument;
 
-/// Execute trades (place/cancel orders)
+/// Response header carrying the id that correlates a trade request's logs
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest accepted client_order_id
+const MAX_CLIENT_ORDER_ID_LEN: usize = 64;
+
+/// Execute trades (place/cancel/replace orders)
 #[utoipa::path(
     post,
     path = "/api/trade",
     request_body = TradeRequest,
     responses(
-        (status = 200, description = "Success", body = TradeResponse),
+        (status = 200, description = "Success", body = TradeResponse,
+            headers(("x-request-id" = String, description = "Request id recorded in engine logs"))),
         (status = 400, description = "Invalid request parameters", body = ErrorResponse),
         (status = 401, description = "Invalid signature", body = ErrorResponse),
-        (status = 404, description = "Order not found", body = ErrorResponse),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 404, description = "Order or market not found", body = ErrorResponse),
+        (status = 409, description = "Market not open, or order already filled or cancelled", body = ErrorResponse),
+        (status = 429, description = "Cancel rate limit exceeded", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "Engine request queue is full", body = ErrorResponse)
     ),
     tag = "trade"
 )]
 pub async fn trade(
     State(state): State<crate::AppState>,
-    Json(request): Json<TradeRequest>,
+    JsonBody(request): JsonBody<TradeRequest>,
+) -> Response {
+    // Every trade gets a request id, the engine and executor spans record it too
+    let request_id = Uuid::new_v4();
+    let span = tracing::info_span!("trade", %request_id);
+
+    let result = execute_trade(state, request, request_id)
+        .instrument(span.clone())
+        .await;
+    span.in_scope(|| match &result {
+        Ok(_) => tracing::debug!("Trade request completed"),
+        Err(e) => tracing::debug!(error = %e, "Trade request failed"),
+    });
+
+    ([(REQUEST_ID_HEADER, request_id.to_string())], result).into_response()
+}
+
+async fn execute_trade(
+    state: crate::AppState,
+    request: TradeRequest,
+    request_id: Uuid,
 ) -> Result<Json<TradeResponse>> {
     match request {
         TradeRequest::PlaceOrder {
@@ -31,12 +66,20 @@ pub async fn trade(
             market_id,
             side,
             order_type,
+            time_in_force,
             price,
             size,
             signature: _,
+            client_order_id,
+            min_fill,
         } => {
             // TODO: Verify signature
 
+            let time_in_force = TimeInForce::resolve(order_type, time_in_force)?;
+            if let Some(client_order_id) = &client_order_id {
+                validate_client_order_id(client_order_id)?;
+            }
+
             // Parse price and size from strings to u128
             let price_value = price
                 .parse::<u128>()
@@ -44,6 +87,10 @@ pub async fn trade(
             let size_value = size
                 .parse::<u128>()
                 .map_err(|_| ExchangeError::InvalidSize)?;
+            let min_fill = min_fill
+                .map(|min_fill| min_fill.parse::<u128>())
+                .transpose()
+                .map_err(|_| ExchangeError::InvalidSize)?;
 
             // Create order (validation and locking happens in engine)
             let order = Order {
@@ -52,6 +99,8 @@ pub async fn trade(
                 market_id,
                 side,
                 order_type,
+                time_in_force,
+                min_fill,
                 price: price_value,
                 size: size_value,
                 filled_size: 0,
@@ -63,10 +112,14 @@ pub async fn trade(
             // Send to matching engine - engine handles validation and locking
             let (response_tx, response_rx) = oneshot::channel();
             state
-                .engine_tx
-                .send(EngineRequest::PlaceOrder { order, response_tx })
-                .await
-                .map_err(|_| ExchangeError::EngineSendFailed)?;
+                .engine
+                .send(EngineRequest::PlaceOrder {
+                    order,
+                    client_order_id,
+                    request_id,
+                    response_tx,
+                })
+                .await?;
 
             // Wait for response
             let placed = response_rx
@@ -76,6 +129,9 @@ pub async fn trade(
             Ok(Json(TradeResponse::PlaceOrder {
                 order: placed.order,
                 trades: placed.trades,
+                average_price: placed.average_price,
+                total_quote: placed.total_quote,
+                requested_price: placed.requested_price,
             }))
         }
         TradeRequest::CancelOrder {
@@ -91,23 +147,19 @@ pub async fn trade(
             // Send to matching engine
             let (response_tx, response_rx) = oneshot::channel();
             state
-                .engine_tx
+                .engine
                 .send(EngineRequest::CancelOrder {
                     order_id: order_uuid,
                     user_address,
+                    request_id,
                     response_tx,
                 })
-                .await
-                .map_err(|_| ExchangeError::EngineSendFailed)?;
+                .await?;
 
             // Wait for response
-            let result = response_rx
+            let cancelled = response_rx
                 .await
-                .map_err(|_| ExchangeError::EngineReceiveFailed)?;
-
-            let cancelled = result.map_err(|e| ExchangeError::InvalidParameter {
-                message: e.to_string(),
-            })?;
+                .map_err(|_| ExchangeError::EngineReceiveFailed)??;
 
             Ok(Json(TradeResponse::CancelOrder {
                 order_id: cancelled.order_id,
@@ -126,29 +178,104 @@ pub async fn trade(
             let engine_request = EngineRequest::CancelAllOrders {
                 user_address: user_address.clone(),
                 market_id: market_id.clone(),
+                request_id,
                 response_tx,
             };
 
             // Send to engine
-            state
-                .engine_tx
-                .send(engine_request)
-                .await
-                .map_err(|_| ExchangeError::EngineSendFailed)?;
+            state.engine.send(engine_request).await?;
 
             // Wait for response
-            let result = response_rx
+            let cancelled = response_rx
                 .await
-                .map_err(|_| ExchangeError::EngineReceiveFailed)?;
-
-            let cancelled = result.map_err(|e| ExchangeError::InvalidParameter {
-                message: e.to_string(),
-            })?;
+                .map_err(|_| ExchangeError::EngineReceiveFailed)??;
 
             Ok(Json(TradeResponse::CancelAllOrders {
                 cancelled_order_ids: cancelled.cancelled_order_ids,
                 count: cancelled.count,
+                refunds: cancelled.refunds,
             }))
         }
+
+        TradeRequest::ReplaceOrder {
+            user_address,
+            order_id,
+            market_id,
+            side,
+            order_type,
+            time_in_force,
+            price,
+            size,
+            signature: _,
+        } => {
+            // TODO: Verify signature
+
+            let old_order_id = Uuid::parse_str(&order_id)?;
+            let time_in_force = TimeInForce::resolve(order_type, time_in_force)?;
+
+            // Parse price and size from strings to u128
+            let price_value = price
+                .parse::<u128>()
+                .map_err(|_| ExchangeError::InvalidPrice)?;
+            let size_value = size
+                .parse::<u128>()
+                .map_err(|_| ExchangeError::InvalidSize)?;
+
+            let new_order = Order {
+                id: Uuid::new_v4(),
+                user_address: user_address.clone(),
+                market_id,
+                side,
+                order_type,
+                time_in_force,
+                min_fill: None,
+                price: price_value,
+                size: size_value,
+                filled_size: 0,
+                status: OrderStatus::Pending,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+
+            // Send to matching engine - cancel and placement happen in one engine turn
+            let (response_tx, response_rx) = oneshot::channel();
+            state
+                .engine
+                .send(EngineRequest::ReplaceOrder {
+                    old_order_id,
+                    user_address,
+                    new_order,
+                    request_id,
+                    response_tx,
+                })
+                .await?;
+
+            // Wait for response
+            let replaced = response_rx
+                .await
+                .map_err(|_| ExchangeError::EngineReceiveFailed)??;
+
+            Ok(Json(TradeResponse::ReplaceOrder {
+                cancelled_order_id: replaced.cancelled.order_id,
+                order: replaced.placed.order,
+                trades: replaced.placed.trades,
+                average_price: replaced.placed.average_price,
+                total_quote: replaced.placed.total_quote,
+                requested_price: replaced.placed.requested_price,
+            }))
+        }
+    }
+}
+
+/// Client order ids are opaque, non-empty and at most MAX_CLIENT_ORDER_ID_LEN bytes
+fn validate_client_order_id(client_order_id: &str) -> Result<()> {
+    if client_order_id.is_empty() || client_order_id.len() > MAX_CLIENT_ORDER_ID_LEN {
+        return Err(ExchangeError::InvalidParameter {
+            message: format!(
+                "client_order_id must be between 1 and {} bytes",
+                MAX_CLIENT_ORDER_ID_LEN
+            ),
+        });
     }
+    Ok(())
 }
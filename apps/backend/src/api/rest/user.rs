@@ -1,9 +1,11 @@
 use axum::{extract::State, response::Json};
+use chrono::{DateTime, Utc};
 
-use crate::errors::{ErrorResponse, Result};
+use crate::api::rest::extract::JsonBody;
+use crate::errors::{ErrorResponse, ExchangeError, Result};
 use crate::models::api::{UserRequest, UserResponse};
 
-/// Get user-specific data (orders, balances, trades)
+/// Get user-specific data (orders, a single order, balances, trades, order history, positions)
 #[utoipa::path(
     post,
     path = "/api/user",
@@ -18,7 +20,7 @@ use crate::models::api::{UserRequest, UserResponse};
 )]
 pub async fn user(
     State(state): State<crate::AppState>,
-    Json(request): Json<UserRequest>,
+    JsonBody(request): JsonBody<UserRequest>,
 ) -> Result<Json<UserResponse>> {
     match request {
         UserRequest::Orders {
@@ -51,6 +53,25 @@ pub async fn user(
                 orders: orders.into_iter().map(|o| o.into()).collect(),
             }))
         }
+        UserRequest::Order {
+            user_address,
+            order_id,
+        } => {
+            let order_id = order_id.parse()?;
+
+            // Other users' orders are reported as missing
+            let order = match state.db.get_order(&order_id).await {
+                Ok(order) if order.user_address == user_address => order,
+                Ok(_) | Err(ExchangeError::Database(sqlx::Error::RowNotFound)) => {
+                    return Err(ExchangeError::OrderNotFound)
+                }
+                Err(e) => return Err(e),
+            };
+
+            Ok(Json(UserResponse::Order {
+                order: order.into(),
+            }))
+        }
         UserRequest::Balances { user_address } => {
             let balances = state.db.list_balances_by_user(&user_address).await?;
 
@@ -72,5 +93,44 @@ pub async fn user(
                 trades: trades.into_iter().map(|t| t.into()).collect(),
             }))
         }
+        UserRequest::OrderHistory {
+            user_address,
+            market_id,
+            from,
+            to,
+            limit,
+        } => {
+            let from = from.map(|secs| parse_timestamp(secs, "from")).transpose()?;
+            let to = to.map(|secs| parse_timestamp(secs, "to")).transpose()?;
+
+            let orders = state
+                .db
+                .get_order_history(
+                    &user_address,
+                    market_id.as_deref(),
+                    from,
+                    to,
+                    limit.unwrap_or(100),
+                )
+                .await?;
+
+            Ok(Json(UserResponse::OrderHistory {
+                orders: orders.into_iter().map(|o| o.into()).collect(),
+            }))
+        }
+        UserRequest::Positions { user_address } => {
+            let positions = state.db.get_user_positions(&user_address).await?;
+
+            Ok(Json(UserResponse::Positions {
+                positions: positions.into_iter().map(|p| p.into()).collect(),
+            }))
+        }
     }
 }
+
+/// Unix seconds from a request into a timestamp
+fn parse_timestamp(secs: i64, field: &str) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp(secs, 0).ok_or_else(|| ExchangeError::InvalidParameter {
+        message: format!("{} is not a valid timestamp", field),
+    })
+}
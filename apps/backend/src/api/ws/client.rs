@@ -1,44 +1,126 @@
 //! WebSocket client message handling - processes messages from clients
 
-use axum::extract::ws::{Message, WebSocket};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
 use futures::StreamExt;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 use tokio::time::Instant;
 
-use crate::models::api::{ClientMessage, ServerMessage};
-use crate::models::domain::Subscription;
+use crate::auth;
+use crate::config::WebSocketConfig;
+use crate::models::api::{ClientMessage, ServerMessage, SubscriptionChannel};
+use crate::models::domain::{EngineRequest, Subscription};
 
-use super::SocketState;
+use super::feed::Replay;
+use super::server::{balance_message, engine_event_to_messages, orderbook_message};
+use super::state::SubscriptionSet;
+use super::{AckSender, SocketState};
 
 /// Handle incoming messages from the client
 pub(super) async fn handle_client_messages(
     mut receiver: futures::stream::SplitStream<WebSocket>,
     socket_state: Arc<RwLock<SocketState>>,
-    ack_tx: tokio::sync::mpsc::UnboundedSender<ServerMessage>,
+    ack_tx: AckSender,
+    app_state: crate::AppState,
 ) {
+    let max_message_bytes = app_state.ws_config.max_message_bytes;
+
     while let Some(msg) = receiver.next().await {
+        // Refuse oversized frames before parsing them
+        let frame_len = match &msg {
+            Ok(Message::Text(text)) => text.len(),
+            Ok(Message::Binary(data)) => data.len(),
+            _ => 0,
+        };
+        if frame_len > max_message_bytes {
+            log::warn!(
+                "Client frame of {} bytes exceeds the {} byte limit, disconnecting",
+                frame_len,
+                max_message_bytes
+            );
+            close_with(
+                &socket_state,
+                close_code::SIZE,
+                format!("Message exceeds {} bytes", max_message_bytes),
+            )
+            .await;
+            break;
+        }
+
         match msg {
             Ok(Message::Text(text)) => {
-                // Parse and handle client message
-                if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(&text) {
-                    match &client_msg {
-                        ClientMessage::Subscribe {
-                            channel,
-                            market_id,
-                            user_address,
-                        } => {
-                            if let Some(sub) = Subscription::from_message(&client_msg) {
+                // Parse client message, reporting malformed frames back to the client
+                let client_msg = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        log::debug!("Invalid client message: {}", e);
+                        let _ = ack_tx.send(ServerMessage::Error {
+                            message: format!("Invalid message: {}", e),
+                        });
+                        continue;
+                    }
+                };
+
+                match &client_msg {
+                    ClientMessage::Subscribe {
+                        channel, market_id, ..
+                    } => {
+                        let (authenticated_user, auth_deadline) = {
+                            let state = socket_state.read().await;
+                            (state.authenticated_user.clone(), state.auth_deadline)
+                        };
+                        let resolved =
+                            resolve_subscription(&client_msg, authenticated_user.as_deref())
+                                .and_then(|sub| check_channel_enabled(sub, &app_state.ws_config));
+
+                        // Private channels always need auth; once the deadline (if any)
+                        // has passed, trying without auth ends the connection
+                        if let (Ok(sub), None) = (&resolved, &authenticated_user) {
+                            if sub.user_address().is_some() {
+                                let _ = ack_tx.send(ServerMessage::Error {
+                                    message: "Authentication required for private channels"
+                                        .to_string(),
+                                });
+                                if auth_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                                {
+                                    log::warn!(
+                                        "Unauthenticated private subscription after the auth window, disconnecting"
+                                    );
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
+
+                        match resolved {
+                            Ok(sub) => {
                                 let mut state = socket_state.write().await;
-                                let was_added = state.subscriptions.subscribe(sub);
+                                let was_added = match state.subscriptions.subscribe(sub.clone()) {
+                                    Ok(was_added) => was_added,
+                                    Err(limit_reached) => {
+                                        drop(state);
+                                        log::warn!(
+                                            "Rejected subscription to {:?}: limit of {} reached",
+                                            channel,
+                                            limit_reached.limit
+                                        );
+                                        let _ = ack_tx.send(ServerMessage::Error {
+                                            message: format!(
+                                                "Subscription limit of {} per connection reached",
+                                                limit_reached.limit
+                                            ),
+                                        });
+                                        continue;
+                                    }
+                                };
                                 state.last_subscription_change = Instant::now();
                                 drop(state);
 
-                                // Send acknowledgment
+                                // Send acknowledgment (with the resolved user address)
                                 let ack = ServerMessage::Subscribed {
                                     channel: *channel,
                                     market_id: market_id.clone(),
-                                    user_address: user_address.clone(),
+                                    user_address: sub.user_address().map(str::to_string),
                                 };
                                 let _ = ack_tx.send(ack);
 
@@ -47,27 +129,34 @@ pub(super) async fn handle_client_messages(
                                 } else {
                                     log::debug!("Client already subscribed to {:?}", channel);
                                 }
-                            } else {
-                                log::warn!("Invalid subscription: missing required fields");
+
+                                // Send current state right away instead of waiting for the next change
+                                send_initial_snapshot(&sub, &app_state, &ack_tx).await;
+                            }
+                            Err(message) => {
+                                log::warn!("Rejected subscription: {}", message);
+                                let _ = ack_tx.send(ServerMessage::Error { message });
                             }
                         }
+                    }
 
-                        ClientMessage::Unsubscribe {
-                            channel,
-                            market_id,
-                            user_address,
-                        } => {
-                            if let Some(sub) = Subscription::from_message(&client_msg) {
+                    ClientMessage::Unsubscribe {
+                        channel, market_id, ..
+                    } => {
+                        let authenticated_user =
+                            socket_state.read().await.authenticated_user.clone();
+                        match resolve_subscription(&client_msg, authenticated_user.as_deref()) {
+                            Ok(sub) => {
                                 let mut state = socket_state.write().await;
                                 let was_removed = state.subscriptions.unsubscribe(&sub);
                                 state.last_subscription_change = Instant::now();
                                 drop(state);
 
-                                // Send acknowledgment
+                                // Send acknowledgment (with the resolved user address)
                                 let ack = ServerMessage::Unsubscribed {
                                     channel: *channel,
                                     market_id: market_id.clone(),
-                                    user_address: user_address.clone(),
+                                    user_address: sub.user_address().map(str::to_string),
                                 };
                                 let _ = ack_tx.send(ack);
 
@@ -76,18 +165,74 @@ pub(super) async fn handle_client_messages(
                                 } else {
                                     log::debug!("Client was not subscribed to {:?}", channel);
                                 }
-                            } else {
-                                log::warn!("Invalid unsubscription: missing required fields");
+                            }
+                            Err(message) => {
+                                log::warn!("Rejected unsubscription: {}", message);
+                                let _ = ack_tx.send(ServerMessage::Error { message });
                             }
                         }
+                    }
 
-                        ClientMessage::Ping => {
-                            log::debug!("Received application ping, sending pong");
-                            if ack_tx.send(ServerMessage::Pong).is_err() {
-                                log::error!("Failed to send pong response");
-                            }
+                    ClientMessage::AuthChallenge => {
+                        let nonce = uuid::Uuid::new_v4().to_string();
+                        socket_state.write().await.auth_nonce = Some(nonce.clone());
+                        let _ = ack_tx.send(ServerMessage::AuthChallenge {
+                            message: auth::challenge_message(&nonce),
+                            nonce,
+                        });
+                    }
+
+                    ClientMessage::Auth {
+                        user_address,
+                        signature,
+                    } => {
+                        let response =
+                            authenticate(user_address, signature, &socket_state, &app_state).await;
+                        let _ = ack_tx.send(response);
+                    }
+
+                    ClientMessage::Ping => {
+                        log::debug!("Received application ping, sending pong");
+                        if !ack_tx.send(ServerMessage::Pong) {
+                            log::error!("Failed to send pong response");
                         }
                     }
+
+                    ClientMessage::ListSubscriptions => {
+                        let subscriptions = socket_state.read().await.subscriptions.active();
+                        let _ = ack_tx.send(ServerMessage::Subscriptions { subscriptions });
+                    }
+
+                    ClientMessage::Resume {
+                        channel,
+                        market_id,
+                        after_seq,
+                    } => {
+                        let sub = match channel {
+                            SubscriptionChannel::Trades => Subscription::Trades {
+                                market_id: market_id.clone(),
+                            },
+                            SubscriptionChannel::Orderbook => Subscription::Orderbook {
+                                market_id: market_id.clone(),
+                            },
+                            _ => {
+                                let _ = ack_tx.send(ServerMessage::Error {
+                                    message: "Only trades and orderbook channels can be resumed"
+                                        .to_string(),
+                                });
+                                continue;
+                            }
+                        };
+                        resume(
+                            sub,
+                            *channel,
+                            *after_seq,
+                            &socket_state,
+                            &app_state,
+                            &ack_tx,
+                        )
+                        .await;
+                    }
                 }
             }
             Ok(Message::Pong(_)) => {
@@ -104,5 +249,284 @@ pub(super) async fn handle_client_messages(
             }
             _ => {}
         }
+
+        // A client that doesn't read its replies is disconnected instead of buffered
+        if ack_tx.overflowed() {
+            log::warn!("Client outbound queue is full, disconnecting");
+            close_with(
+                &socket_state,
+                close_code::POLICY,
+                "Too many unread messages".to_string(),
+            )
+            .await;
+            break;
+        }
+    }
+}
+
+/// Subscribe to a market channel and replay the messages sent after `after_seq`
+///
+/// Replies are queued while holding the socket state, so the writer can't send
+/// a later live message ahead of them. When the sequence is no longer buffered,
+/// or the gap is too long to queue, the client gets the current state instead:
+/// the latest buffered trades, or a fresh orderbook snapshot
+async fn resume(
+    sub: Subscription,
+    channel: SubscriptionChannel,
+    after_seq: u64,
+    socket_state: &Arc<RwLock<SocketState>>,
+    app_state: &crate::AppState,
+    ack_tx: &AckSender,
+) {
+    let market_id = match &sub {
+        Subscription::Trades { market_id } | Subscription::Orderbook { market_id } => {
+            market_id.clone()
+        }
+        _ => return,
+    };
+
+    let mut state = socket_state.write().await;
+    if let Err(limit_reached) = state.subscriptions.subscribe(sub.clone()) {
+        drop(state);
+        let _ = ack_tx.send(ServerMessage::Error {
+            message: format!(
+                "Subscription limit of {} per connection reached",
+                limit_reached.limit
+            ),
+        });
+        return;
+    }
+    state.last_subscription_change = Instant::now();
+
+    let (events, mut snapshot, through) = match app_state.market_feed.replay(&market_id, after_seq)
+    {
+        Replay::Events(events) => {
+            let through = events.last().and_then(|e| e.seq).unwrap_or(after_seq);
+            (events, false, through)
+        }
+        Replay::Gap { buffered, latest } => (buffered, true, latest),
+    };
+    state.replayed_through.insert(sub.clone(), through);
+
+    // Render the buffered events as this channel alone would have received them
+    let mut channel_only = SubscriptionSet::new(1);
+    let _ = channel_only.subscribe(sub.clone());
+    let mut messages: Vec<ServerMessage> = events
+        .iter()
+        .flat_map(|e| engine_event_to_messages(&e.event, e.seq, &channel_only))
+        .collect();
+
+    // Leave room in the reply queue for the Resumed ack
+    let room = app_state.ws_config.max_queued_messages.saturating_sub(1);
+    if messages.len() > room {
+        messages.drain(..messages.len() - room);
+        snapshot = true;
+    }
+    let fresh_orderbook = snapshot && channel == SubscriptionChannel::Orderbook;
+    if fresh_orderbook {
+        messages.clear();
+    }
+
+    let _ = ack_tx.send(ServerMessage::Resumed {
+        channel,
+        market_id,
+        replayed: messages.len(),
+        snapshot,
+    });
+    for message in messages {
+        let _ = ack_tx.send(message);
+    }
+    drop(state);
+
+    if fresh_orderbook {
+        send_initial_snapshot(&sub, app_state, ack_tx).await;
+    }
+}
+
+/// Record the close frame the writer sends once this handler stops
+async fn close_with(socket_state: &Arc<RwLock<SocketState>>, code: u16, reason: String) {
+    socket_state.write().await.close_frame = Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    });
+}
+
+/// Bind the connection to a user once it proves it holds the user's key
+/// The signature must cover the connection's outstanding challenge, which it consumes,
+/// and a connection can only ever authenticate as one user
+async fn authenticate(
+    user_address: &str,
+    signature: &str,
+    socket_state: &Arc<RwLock<SocketState>>,
+    app_state: &crate::AppState,
+) -> ServerMessage {
+    let Some(nonce) = socket_state.write().await.auth_nonce.take() else {
+        return ServerMessage::Error {
+            message: "Authentication failed: request an auth challenge first".to_string(),
+        };
+    };
+    let signer = auth::recover_address(&auth::challenge_message(&nonce), signature);
+    if !signer.is_some_and(|signer| auth::same_address(&signer, user_address)) {
+        log::warn!("Rejected auth for {}: bad signature", user_address);
+        return ServerMessage::Error {
+            message: format!(
+                "Authentication failed: signature is not from '{}'",
+                user_address
+            ),
+        };
+    }
+
+    if app_state.db.get_user(user_address).await.is_err() {
+        return ServerMessage::Error {
+            message: format!("Authentication failed: unknown user '{}'", user_address),
+        };
+    }
+
+    let mut state = socket_state.write().await;
+    match &state.authenticated_user {
+        Some(existing) if existing != user_address => ServerMessage::Error {
+            message: format!("Connection is already authenticated as '{}'", existing),
+        },
+        None if state
+            .auth_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline) =>
+        {
+            ServerMessage::Error {
+                message: "Authentication window has expired".to_string(),
+            }
+        }
+        _ => {
+            state.authenticated_user = Some(user_address.to_string());
+            log::debug!("Client authenticated as {}", user_address);
+            ServerMessage::Authenticated {
+                user_address: user_address.to_string(),
+            }
+        }
+    }
+}
+
+/// Resolve a (un)subscription request into a subscription
+/// User channels default to the authenticated user, and an authenticated
+/// connection may not touch another user's private channels
+fn resolve_subscription(
+    msg: &ClientMessage,
+    authenticated_user: Option<&str>,
+) -> Result<Subscription, String> {
+    let scoped;
+    let msg = match (msg, authenticated_user) {
+        (
+            ClientMessage::Subscribe {
+                channel,
+                market_id,
+                user_address: None,
+            },
+            Some(user),
+        ) => {
+            scoped = ClientMessage::Subscribe {
+                channel: *channel,
+                market_id: market_id.clone(),
+                user_address: Some(user.to_string()),
+            };
+            &scoped
+        }
+        (
+            ClientMessage::Unsubscribe {
+                channel,
+                market_id,
+                user_address: None,
+            },
+            Some(user),
+        ) => {
+            scoped = ClientMessage::Unsubscribe {
+                channel: *channel,
+                market_id: market_id.clone(),
+                user_address: Some(user.to_string()),
+            };
+            &scoped
+        }
+        _ => msg,
+    };
+
+    let sub = Subscription::from_message(msg)
+        .ok_or_else(|| "Invalid subscription: missing required fields".to_string())?;
+
+    if let (Some(owner), Some(user)) = (sub.user_address(), authenticated_user) {
+        if owner != user {
+            return Err(format!(
+                "Not authorized to access private channels of '{}'",
+                owner
+            ));
+        }
+    }
+
+    Ok(sub)
+}
+
+/// Refuse subscriptions to channels the server has switched off
+fn check_channel_enabled(
+    sub: Subscription,
+    ws_config: &WebSocketConfig,
+) -> Result<Subscription, String> {
+    if sub == Subscription::AllTrades && !ws_config.all_trades_enabled {
+        return Err("The all_trades channel is disabled on this server".to_string());
+    }
+    Ok(sub)
+}
+
+/// Send the current state for a new subscription through the send task
+/// Orderbook snapshots come from the engine, balances and market statuses from the database
+async fn send_initial_snapshot(
+    sub: &Subscription,
+    app_state: &crate::AppState,
+    ack_tx: &AckSender,
+) {
+    match sub {
+        Subscription::Orderbook { market_id } => {
+            let (response_tx, response_rx) = oneshot::channel();
+            let request = EngineRequest::GetOrderbookSnapshot {
+                market_id: market_id.clone(),
+                group_by: None,
+                response_tx,
+            };
+            if app_state.engine.send(request).await.is_err() {
+                log::error!("Failed to request orderbook snapshot for {}", market_id);
+                return;
+            }
+            match response_rx.await {
+                Ok(snapshot) => {
+                    let seq = app_state.market_feed.latest_seq(market_id);
+                    let _ = ack_tx.send(orderbook_message(&snapshot, true, seq));
+                }
+                Err(_) => log::error!("Engine dropped orderbook snapshot request"),
+            }
+        }
+        Subscription::UserBalances { user_address } => {
+            match app_state.db.list_balances_by_user(user_address).await {
+                Ok(balances) => {
+                    for balance in &balances {
+                        let _ = ack_tx.send(balance_message(balance));
+                    }
+                }
+                Err(e) => log::error!("Failed to load balances for {}: {}", user_address, e),
+            }
+        }
+        Subscription::MarketStatus { market_id } => {
+            let markets = match market_id {
+                Some(market_id) => app_state.db.get_market(market_id).await.map(|m| vec![m]),
+                None => app_state.db.list_markets().await,
+            };
+            match markets {
+                Ok(markets) => {
+                    for market in markets {
+                        let _ = ack_tx.send(ServerMessage::MarketStatus {
+                            market_id: market.id,
+                            status: market.status,
+                        });
+                    }
+                }
+                Err(e) => log::error!("Failed to load market statuses: {}", e),
+            }
+        }
+        _ => {}
     }
 }
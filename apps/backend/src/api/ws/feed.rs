@@ -0,0 +1,149 @@
+//! Per-market sequencing of engine events and a replay buffer for reconnecting clients
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::models::domain::EngineEvent;
+
+/// An engine event as seen by WebSocket connections
+/// Public market events (trades and orderbook updates) carry their market's sequence number
+#[derive(Debug, Clone)]
+pub(crate) struct FeedEvent {
+    pub(crate) seq: Option<u64>,
+    pub(crate) event: EngineEvent,
+}
+
+/// Result of looking up the events a client missed
+pub(crate) enum Replay {
+    /// Every event after the requested sequence, oldest first
+    Events(Vec<FeedEvent>),
+    /// The requested sequence is no longer buffered (or was never issued)
+    /// Holds whatever is still buffered and the market's latest sequence
+    Gap {
+        buffered: Vec<FeedEvent>,
+        latest: u64,
+    },
+}
+
+/// Recent sequenced events of one market
+#[derive(Default)]
+struct MarketLog {
+    latest: u64,
+    events: VecDeque<FeedEvent>,
+}
+
+/// Sequences engine events per market and keeps the most recent ones for replay
+///
+/// A single task reads the engine's event stream, numbers each public market
+/// event, records it, and only then forwards it to connections, so anything a
+/// connection has been sent is already replayable
+#[derive(Clone)]
+pub struct MarketFeed {
+    logs: Arc<Mutex<HashMap<String, MarketLog>>>,
+    capacity: usize,
+    tx: broadcast::Sender<FeedEvent>,
+}
+
+impl MarketFeed {
+    /// Start sequencing the events sent on `event_tx`, keeping `capacity` events per market
+    pub fn new(event_tx: &broadcast::Sender<EngineEvent>, capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(1000);
+        let feed = Self {
+            logs: Arc::new(Mutex::new(HashMap::new())),
+            capacity,
+            tx,
+        };
+
+        let mut event_rx = event_tx.subscribe();
+        let sequencer = feed.clone();
+        tokio::spawn(async move {
+            loop {
+                match event_rx.recv().await {
+                    Ok(event) => {
+                        let event = sequencer.record(event);
+                        let _ = sequencer.tx.send(event);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Market feed lagged, skipped {} engine events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        feed
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<FeedEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Latest sequence number issued for a market, 0 if none yet
+    pub(crate) fn latest_seq(&self, market_id: &str) -> u64 {
+        self.logs
+            .lock()
+            .unwrap()
+            .get(market_id)
+            .map_or(0, |log| log.latest)
+    }
+
+    /// Events of a market after `after_seq`
+    pub(crate) fn replay(&self, market_id: &str, after_seq: u64) -> Replay {
+        let logs = self.logs.lock().unwrap();
+        let Some(log) = logs.get(market_id) else {
+            return if after_seq == 0 {
+                Replay::Events(Vec::new())
+            } else {
+                Replay::Gap {
+                    buffered: Vec::new(),
+                    latest: 0,
+                }
+            };
+        };
+
+        let oldest = log
+            .events
+            .front()
+            .and_then(|e| e.seq)
+            .unwrap_or(log.latest + 1);
+        if after_seq > log.latest || after_seq + 1 < oldest {
+            return Replay::Gap {
+                buffered: log.events.iter().cloned().collect(),
+                latest: log.latest,
+            };
+        }
+
+        Replay::Events(
+            log.events
+                .iter()
+                .filter(|e| e.seq.is_some_and(|seq| seq > after_seq))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Number a public market event and add it to its market's buffer
+    fn record(&self, event: EngineEvent) -> FeedEvent {
+        let market_id = match &event {
+            EngineEvent::TradeExecuted { trade } => trade.market_id.clone(),
+            EngineEvent::OrderbookSnapshot { orderbook } => orderbook.market_id.clone(),
+            _ => return FeedEvent { seq: None, event },
+        };
+
+        let mut logs = self.logs.lock().unwrap();
+        let log = logs.entry(market_id).or_default();
+        log.latest += 1;
+        let event = FeedEvent {
+            seq: Some(log.latest),
+            event,
+        };
+        if self.capacity > 0 {
+            if log.events.len() >= self.capacity {
+                log.events.pop_front();
+            }
+            log.events.push_back(event.clone());
+        }
+        event
+    }
+}
@@ -0,0 +1,58 @@
+//! WebSocket connection health counters
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::models::api::WebSocketMetrics;
+
+/// Connection counters shared by every socket, reported in /api/metrics
+#[derive(Clone, Default)]
+pub struct WsMetrics {
+    active: Arc<AtomicUsize>,
+    pong_timeouts: Arc<AtomicU64>,
+    idle_disconnects: Arc<AtomicU64>,
+}
+
+impl WsMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a connection as active until the returned guard is dropped
+    pub(super) fn connection(&self) -> ConnectionGuard {
+        self.active.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard {
+            active: self.active.clone(),
+        }
+    }
+
+    /// Record a client dropped for not answering pings
+    pub(super) fn record_pong_timeout(&self) {
+        self.pong_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a client dropped for staying unsubscribed too long
+    pub(super) fn record_idle_disconnect(&self) {
+        self.idle_disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current connection count and disconnect counters
+    pub fn metrics(&self) -> WebSocketMetrics {
+        WebSocketMetrics {
+            active_connections: self.active.load(Ordering::Relaxed),
+            pong_timeouts: self.pong_timeouts.load(Ordering::Relaxed),
+            idle_disconnects: self.idle_disconnects.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Keeps a connection in the active count for as long as it is held
+pub(super) struct ConnectionGuard {
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
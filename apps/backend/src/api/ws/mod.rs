@@ -1,4 +1,6 @@
 mod client;
+mod feed;
+mod metrics;
 mod server;
 mod state;
 
@@ -14,49 +16,90 @@ use axum::{
 use futures::StreamExt;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::time::Instant;
 
 use crate::models::api::ServerMessage;
-use state::SocketState;
+pub use feed::MarketFeed;
+pub use metrics::WsMetrics;
+use state::{AckSender, SocketState};
 
 // Configuration constants
 pub(crate) const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 pub(crate) const PONG_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
 pub(crate) const UNSUBSCRIBED_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
 
+/// Subprotocol a client offers to receive deflated messages
+///
+/// Server messages are then sent as binary frames holding the raw DEFLATE
+/// (RFC 1951) of their JSON, each one compressed on its own. This stands in for
+/// permessage-deflate, which tungstenite can't negotiate: it rejects frames
+/// with the RSV1 bit the extension relies on. Client messages stay text
+pub const COMPRESSION_PROTOCOL: &str = "exchange.deflate";
+
 /// Create the WebSocket router
 pub fn create_ws() -> Router<crate::AppState> {
     Router::new().route("/ws", get(ws_handler))
 }
 
 /// WebSocket upgrade handler
+/// Compression is only selected when enabled and offered by the client
 async fn ws_handler(ws: WebSocketUpgrade, State(state): State<crate::AppState>) -> Response {
+    let ws = if state.ws_config.compression {
+        ws.protocols([COMPRESSION_PROTOCOL])
+    } else {
+        ws
+    };
     ws.on_upgrade(move |socket| handle_socket(socket, state))
 }
 
 async fn handle_socket(socket: WebSocket, state: crate::AppState) {
+    let compress = socket
+        .protocol()
+        .is_some_and(|protocol| protocol == COMPRESSION_PROTOCOL);
+
     // sender sends to client, receiver receives from client
     let (sender, receiver) = socket.split();
-    let event_rx = state.event_tx.subscribe();
+    let _connection = state.ws_metrics.connection();
+    let ws_metrics = state.ws_metrics.clone();
+    let feed_rx = state.market_feed.subscribe();
+
+    // Auth for private channels must come within the configured window, if any
+    let auth_deadline = (state.ws_config.auth_timeout_secs > 0).then(|| {
+        Instant::now() + std::time::Duration::from_secs(state.ws_config.auth_timeout_secs)
+    });
 
     // Shared socket state
-    let socket_state = Arc::new(RwLock::new(SocketState::new()));
+    let socket_state = Arc::new(RwLock::new(SocketState::new(
+        state.ws_config.max_subscriptions_per_connection,
+        auth_deadline,
+    )));
 
     // Channel for sending acknowledgments from client handler to server sender
-    let (ack_tx, ack_rx) = tokio::sync::mpsc::unbounded_channel::<ServerMessage>();
+    let (ack_tx, ack_rx) =
+        tokio::sync::mpsc::channel::<ServerMessage>(state.ws_config.max_queued_messages.max(1));
+    let ack_tx = AckSender::new(ack_tx);
 
     // Task 1: Handle incoming messages from client (receiver)
     let recv_task = {
         let socket_state = socket_state.clone();
-        tokio::spawn(
-            async move { client::handle_client_messages(receiver, socket_state, ack_tx).await },
-        )
+        tokio::spawn(async move {
+            client::handle_client_messages(receiver, socket_state, ack_tx, state).await
+        })
     };
 
     // Task 2: Send outgoing messages to client (sender)
     let send_task = {
         let socket_state = socket_state.clone();
         tokio::spawn(async move {
-            server::handle_server_messages(sender, event_rx, socket_state, ack_rx).await
+            server::handle_server_messages(
+                sender,
+                feed_rx,
+                socket_state,
+                ack_rx,
+                ws_metrics,
+                compress,
+            )
+            .await
         })
     };
 
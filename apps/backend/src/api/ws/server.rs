@@ -4,24 +4,29 @@ use axum::{
     body::Bytes,
     extract::ws::{Message, WebSocket},
 };
+use flate2::{write::DeflateEncoder, Compression};
 use futures::SinkExt;
+use std::io::Write;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use tokio::time::interval;
 
 use crate::models::api::{OrderbookData, PriceLevel, ServerMessage};
-use crate::models::domain::{EngineEvent, Subscription};
+use crate::models::domain::{Balance, EngineEvent, OrderbookSnapshot, Subscription};
 
 use super::{
-    state::SubscriptionSet, SocketState, PING_INTERVAL, PONG_TIMEOUT, UNSUBSCRIBED_TIMEOUT,
+    feed::FeedEvent, state::SubscriptionSet, SocketState, WsMetrics, PING_INTERVAL, PONG_TIMEOUT,
+    UNSUBSCRIBED_TIMEOUT,
 };
 
 /// Handle outgoing messages to the client and ping/pong management
 pub(super) async fn handle_server_messages(
     mut sender: futures::stream::SplitSink<WebSocket, Message>,
-    mut event_rx: broadcast::Receiver<EngineEvent>,
+    mut feed_rx: broadcast::Receiver<FeedEvent>,
     socket_state: Arc<RwLock<SocketState>>,
-    mut ack_rx: tokio::sync::mpsc::UnboundedReceiver<ServerMessage>,
+    mut ack_rx: tokio::sync::mpsc::Receiver<ServerMessage>,
+    ws_metrics: WsMetrics,
+    compress: bool,
 ) {
     let mut ping_interval = interval(PING_INTERVAL);
 
@@ -34,12 +39,14 @@ pub(super) async fn handle_server_messages(
                 // 1. Check if last pong was too long ago (dead connection)
                 if state.last_pong.elapsed() > PONG_TIMEOUT {
                     log::warn!("No pong received for {:?}, disconnecting client", state.last_pong.elapsed());
+                    ws_metrics.record_pong_timeout();
                     break;
                 }
 
                 // 2. Check if client has no subscriptions for too long
                 if state.subscriptions.is_empty() && state.last_subscription_change.elapsed() > UNSUBSCRIBED_TIMEOUT {
                     log::info!("Client has no subscriptions for {:?}, disconnecting", state.last_subscription_change.elapsed());
+                    ws_metrics.record_idle_disconnect();
                     break;
                 }
 
@@ -53,10 +60,15 @@ pub(super) async fn handle_server_messages(
                 log::debug!("Sent ping to client");
             }
 
-            // Send acknowledgment messages
-            Some(ack) = ack_rx.recv() => {
-                if let Ok(json) = serde_json::to_string(&ack) {
-                    if sender.send(Message::Text(json.into())).await.is_err() {
+            // Send acknowledgment messages, closing once the client handler has stopped
+            ack = ack_rx.recv() => {
+                let Some(ack) = ack else {
+                    let close_frame = socket_state.write().await.close_frame.take();
+                    let _ = sender.send(Message::Close(close_frame)).await;
+                    break;
+                };
+                if let Some(message) = encode_message(&ack, compress) {
+                    if sender.send(message).await.is_err() {
                         log::error!("Failed to send acknowledgment to client");
                         break;
                     }
@@ -65,14 +77,26 @@ pub(super) async fn handle_server_messages(
             }
 
             // Forward engine events to client
-            Ok(event) = event_rx.recv() => {
+            Ok(feed_event) = feed_rx.recv() => {
                 let state = socket_state.read().await;
-                let messages = engine_event_to_messages(&event, &state.subscriptions);
+                let mut messages =
+                    engine_event_to_messages(&feed_event.event, feed_event.seq, &state.subscriptions);
+                messages.retain(|msg| !already_replayed(msg, &state));
                 drop(state); // Release lock before serialization
 
+                // Replies queued before this event (such as a Resume replay) go out first
+                while let Ok(ack) = ack_rx.try_recv() {
+                    if let Some(message) = encode_message(&ack, compress) {
+                        if sender.send(message).await.is_err() {
+                            log::error!("Failed to send acknowledgment to client");
+                            break;
+                        }
+                    }
+                }
+
                 for server_msg in messages {
-                    if let Ok(json) = serde_json::to_string(&server_msg) {
-                        if sender.send(Message::Text(json.into())).await.is_err() {
+                    if let Some(message) = encode_message(&server_msg, compress) {
+                        if sender.send(message).await.is_err() {
                             log::error!("Failed to send message to client");
                             break;
                         }
@@ -83,12 +107,50 @@ pub(super) async fn handle_server_messages(
     }
 }
 
+/// Serialize a message for the client, deflated into a binary frame when compression was negotiated
+fn encode_message(msg: &ServerMessage, compress: bool) -> Option<Message> {
+    let json = serde_json::to_string(msg).ok()?;
+    if !compress {
+        return Some(Message::Text(json.into()));
+    }
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json.as_bytes()).ok()?;
+    Some(Message::Binary(encoder.finish().ok()?.into()))
+}
+
+/// Whether a market message was already sent to this client by a Resume replay
+fn already_replayed(msg: &ServerMessage, state: &SocketState) -> bool {
+    let (sub, seq) = match msg {
+        ServerMessage::Trade { trade } => (
+            Subscription::Trades {
+                market_id: trade.market_id.clone(),
+            },
+            trade.seq,
+        ),
+        ServerMessage::Orderbook { orderbook } => (
+            Subscription::Orderbook {
+                market_id: orderbook.market_id.clone(),
+            },
+            orderbook.seq,
+        ),
+        _ => return false,
+    };
+    state
+        .replayed_through
+        .get(&sub)
+        .is_some_and(|&through| seq <= through)
+}
+
 /// Convert an EngineEvent to ServerMessage(s) for WebSocket transmission
 /// Returns multiple messages if the event matches multiple subscription types
-fn engine_event_to_messages(
+/// `seq` is the market sequence number the feed gave a trade or orderbook event
+pub(super) fn engine_event_to_messages(
     event: &EngineEvent,
+    seq: Option<u64>,
     subscriptions: &SubscriptionSet,
 ) -> Vec<ServerMessage> {
+    let seq = seq.unwrap_or_default();
     let mut messages = Vec::new();
 
     match event {
@@ -109,23 +171,20 @@ fn engine_event_to_messages(
                 size: trade.size.to_string(),
                 side: trade.side,
                 timestamp: trade.timestamp.timestamp(),
+                seq,
             };
 
-            // Send Trade message if subscribed to market-wide trades
-            if subscriptions.has_subscription(&Subscription::Trades {
-                market_id: trade.market_id.clone(),
-            }) {
+            // Send Trade message once if subscribed to the market's trades or all trades
+            if subscriptions.wants_market_trades(&trade.market_id) {
                 messages.push(ServerMessage::Trade {
                     trade: trade_data.clone(),
                 });
             }
 
             // Send UserFill message if subscribed to user fills (buyer or seller)
-            if subscriptions.has_subscription(&Subscription::UserFills {
-                user_address: trade.buyer_address.clone(),
-            }) || subscriptions.has_subscription(&Subscription::UserFills {
-                user_address: trade.seller_address.clone(),
-            }) {
+            if subscriptions.wants_user_fills(&trade.buyer_address, &trade.market_id)
+                || subscriptions.wants_user_fills(&trade.seller_address, &trade.market_id)
+            {
                 messages.push(ServerMessage::UserFill { trade: trade_data });
             }
         }
@@ -149,40 +208,19 @@ fn engine_event_to_messages(
         }
         EngineEvent::BalanceUpdated { balance } => {
             if subscriptions.wants_event(event) {
-                messages.push(ServerMessage::UserBalance {
-                    user_address: balance.user_address.clone(),
-                    token_ticker: balance.token_ticker.clone(),
-                    available: balance
-                        .amount
-                        .saturating_sub(balance.open_interest)
-                        .to_string(),
-                    locked: balance.open_interest.to_string(),
-                    updated_at: balance.updated_at.timestamp(),
-                });
+                messages.push(balance_message(balance));
             }
         }
         EngineEvent::OrderbookSnapshot { orderbook } => {
             if subscriptions.wants_event(event) {
-                messages.push(ServerMessage::Orderbook {
-                    orderbook: OrderbookData {
-                        market_id: orderbook.market_id.clone(),
-                        bids: orderbook
-                            .bids
-                            .iter()
-                            .map(|level| PriceLevel {
-                                price: level.price.to_string(),
-                                size: level.size.to_string(),
-                            })
-                            .collect(),
-                        asks: orderbook
-                            .asks
-                            .iter()
-                            .map(|level| PriceLevel {
-                                price: level.price.to_string(),
-                                size: level.size.to_string(),
-                            })
-                            .collect(),
-                    },
+                messages.push(orderbook_message(orderbook, false, seq));
+            }
+        }
+        EngineEvent::MarketStatusChanged { market_id, status } => {
+            if subscriptions.wants_event(event) {
+                messages.push(ServerMessage::MarketStatus {
+                    market_id: market_id.clone(),
+                    status: *status,
                 });
             }
         }
@@ -190,3 +228,49 @@ fn engine_event_to_messages(
 
     messages
 }
+
+/// Build the orderbook message sent to clients from an engine snapshot
+/// `is_snapshot` marks the initial book sent on subscribe, as opposed to a later update
+pub(super) fn orderbook_message(
+    orderbook: &OrderbookSnapshot,
+    is_snapshot: bool,
+    seq: u64,
+) -> ServerMessage {
+    ServerMessage::Orderbook {
+        orderbook: OrderbookData {
+            market_id: orderbook.market_id.clone(),
+            bids: orderbook
+                .bids
+                .iter()
+                .map(|level| PriceLevel {
+                    price: level.price.to_string(),
+                    size: level.size.to_string(),
+                })
+                .collect(),
+            asks: orderbook
+                .asks
+                .iter()
+                .map(|level| PriceLevel {
+                    price: level.price.to_string(),
+                    size: level.size.to_string(),
+                })
+                .collect(),
+            checksum: orderbook.checksum,
+            is_snapshot,
+            seq,
+        },
+    }
+}
+
+/// Build the balance message sent to clients from a balance row
+pub(super) fn balance_message(balance: &Balance) -> ServerMessage {
+    ServerMessage::UserBalance {
+        user_address: balance.user_address.clone(),
+        token_ticker: balance.token_ticker.clone(),
+        amount: balance.amount.to_string(),
+        open_interest: balance.open_interest.to_string(),
+        available: balance.available().to_string(),
+        locked: balance.open_interest.to_string(),
+        updated_at: balance.updated_at.timestamp(),
+    }
+}
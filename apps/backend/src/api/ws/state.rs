@@ -1,8 +1,12 @@
 //! WebSocket connection state management
 
-use std::collections::HashSet;
+use axum::extract::ws::CloseFrame;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::mpsc::{self, error::TrySendError};
 use tokio::time::Instant;
 
+use crate::models::api::{ActiveSubscription, ServerMessage};
 use crate::models::domain::EngineEvent;
 use crate::models::domain::Subscription;
 
@@ -15,71 +19,131 @@ pub(crate) struct SocketState {
     pub(crate) subscriptions: SubscriptionSet,
     pub(crate) last_pong: Instant,
     pub(crate) last_subscription_change: Instant,
+    /// User this connection authenticated as, if any
+    pub(crate) authenticated_user: Option<String>,
+    /// When private channels require auth, the time by which it must happen
+    pub(crate) auth_deadline: Option<Instant>,
+    /// Challenge nonce handed out and not yet answered, each one is good for a single Auth
+    pub(crate) auth_nonce: Option<String>,
+    /// Close frame to send when the client handler ends the connection
+    pub(crate) close_frame: Option<CloseFrame>,
+    /// Highest sequence already replayed per resumed market channel, so the live
+    /// copies of those events aren't sent a second time
+    pub(crate) replayed_through: HashMap<Subscription, u64>,
 }
 
 impl SocketState {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(max_subscriptions: usize, auth_deadline: Option<Instant>) -> Self {
         Self {
-            subscriptions: SubscriptionSet::new(),
+            subscriptions: SubscriptionSet::new(max_subscriptions),
             last_pong: Instant::now(),
             last_subscription_change: Instant::now(),
+            authenticated_user: None,
+            auth_deadline,
+            auth_nonce: None,
+            close_frame: None,
+            replayed_through: HashMap::new(),
         }
     }
 }
 
+// ============================================================================
+// AckSender - Bounded queue of replies to the client
+// ============================================================================
+
+/// Sends replies to the connection's writer, remembering if any were dropped
+/// because the client stopped reading
+pub(crate) struct AckSender {
+    tx: mpsc::Sender<ServerMessage>,
+    overflowed: AtomicBool,
+}
+
+impl AckSender {
+    pub(crate) fn new(tx: mpsc::Sender<ServerMessage>) -> Self {
+        Self {
+            tx,
+            overflowed: AtomicBool::new(false),
+        }
+    }
+
+    /// Queue a message, false if it was dropped
+    pub(crate) fn send(&self, message: ServerMessage) -> bool {
+        match self.tx.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.overflowed.store(true, Ordering::Relaxed);
+                false
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    /// Whether a message was dropped because the queue was full
+    pub(crate) fn overflowed(&self) -> bool {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+}
+
 // ============================================================================
 // SubscriptionSet - Manages client subscriptions
 // ============================================================================
 
 /// Manages client subscriptions and determines which events to forward
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(crate) struct SubscriptionSet {
     subs: HashSet<Subscription>,
+    max_subscriptions: usize,
+}
+
+/// A new subscription was refused because the connection holds the maximum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SubscriptionLimitReached {
+    pub(crate) limit: usize,
 }
 
 impl SubscriptionSet {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(max_subscriptions: usize) -> Self {
         Self {
             subs: HashSet::new(),
+            max_subscriptions,
         }
     }
 
-    pub(crate) fn subscribe(&mut self, sub: Subscription) -> bool {
-        self.subs.insert(sub)
+    /// Add a subscription, returning whether it was new
+    /// Re-subscribing to an existing channel always succeeds, even at the limit
+    pub(crate) fn subscribe(
+        &mut self,
+        sub: Subscription,
+    ) -> Result<bool, SubscriptionLimitReached> {
+        if !self.subs.contains(&sub) && self.subs.len() >= self.max_subscriptions {
+            return Err(SubscriptionLimitReached {
+                limit: self.max_subscriptions,
+            });
+        }
+        Ok(self.subs.insert(sub))
     }
 
     pub(crate) fn unsubscribe(&mut self, sub: &Subscription) -> bool {
         self.subs.remove(sub)
     }
 
-    pub(crate) fn has_subscription(&self, sub: &Subscription) -> bool {
-        self.subs.contains(sub)
-    }
-
     pub(crate) fn wants_event(&self, event: &EngineEvent) -> bool {
         match event {
             EngineEvent::TradeExecuted { trade } => {
-                // Send to market trades subscription
-                self.subs.contains(&Subscription::Trades {
-                    market_id: trade.market_id.clone(),
-                })
-                // OR send to buyer's user fills subscription
-                || self.subs.contains(&Subscription::UserFills {
-                    user_address: trade.buyer_address.clone(),
-                })
-                // OR send to seller's user fills subscription
-                || self.subs.contains(&Subscription::UserFills {
-                    user_address: trade.seller_address.clone(),
-                })
+                // Send to market trades or all trades subscription
+                self.wants_market_trades(&trade.market_id)
+                // OR send to buyer's or seller's user fills subscription
+                || self.wants_user_fills(&trade.buyer_address, &trade.market_id)
+                || self.wants_user_fills(&trade.seller_address, &trade.market_id)
             }
-            EngineEvent::OrderPlaced { order } => self.subs.contains(&Subscription::UserOrders {
-                user_address: order.user_address.clone(),
-            }),
-            EngineEvent::OrderCancelled { user_address, .. } => {
-                self.subs.contains(&Subscription::UserOrders {
-                    user_address: user_address.clone(),
-                })
+            EngineEvent::OrderPlaced { order } => {
+                self.wants_user_orders(&order.user_address, &order.market_id)
             }
+            EngineEvent::OrderCancelled {
+                user_address,
+                market_id,
+                ..
+            } => self.wants_user_orders(user_address, market_id),
             EngineEvent::BalanceUpdated { balance } => {
                 self.subs.contains(&Subscription::UserBalances {
                     user_address: balance.user_address.clone(),
@@ -90,9 +154,58 @@ impl SubscriptionSet {
                     market_id: orderbook.market_id.clone(),
                 })
             }
+            EngineEvent::MarketStatusChanged { market_id, .. } => [None, Some(market_id.clone())]
+                .into_iter()
+                .any(|market_id| {
+                    self.subs
+                        .contains(&Subscription::MarketStatus { market_id })
+                }),
         }
     }
 
+    /// Subscribed to public trades in this market, or across all markets
+    pub(crate) fn wants_market_trades(&self, market_id: &str) -> bool {
+        self.subs.contains(&Subscription::AllTrades)
+            || self.subs.contains(&Subscription::Trades {
+                market_id: market_id.to_string(),
+            })
+    }
+
+    /// Subscribed to the user's fills in this market, or across all markets
+    pub(crate) fn wants_user_fills(&self, user_address: &str, market_id: &str) -> bool {
+        [None, Some(market_id.to_string())]
+            .into_iter()
+            .any(|market_id| {
+                self.subs.contains(&Subscription::UserFills {
+                    user_address: user_address.to_string(),
+                    market_id,
+                })
+            })
+    }
+
+    /// Subscribed to the user's order updates in this market, or across all markets
+    fn wants_user_orders(&self, user_address: &str, market_id: &str) -> bool {
+        [None, Some(market_id.to_string())]
+            .into_iter()
+            .any(|market_id| {
+                self.subs.contains(&Subscription::UserOrders {
+                    user_address: user_address.to_string(),
+                    market_id,
+                })
+            })
+    }
+
+    /// Every subscription the connection holds, in a stable order
+    pub(crate) fn active(&self) -> Vec<ActiveSubscription> {
+        let mut active: Vec<_> = self
+            .subs
+            .iter()
+            .filter_map(Subscription::to_active)
+            .collect();
+        active.sort();
+        active
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
         self.subs.is_empty()
     }
@@ -0,0 +1,112 @@
+//! Wallet signatures for authenticating WebSocket connections
+//!
+//! Users are Ethereum addresses. A connection proves it controls one by signing a
+//! server-issued challenge with EIP-191 `personal_sign`, the server recovers the
+//! signer's address from the signature and compares it to the claimed user.
+
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Text a connection signs to answer the challenge `nonce`
+pub fn challenge_message(nonce: &str) -> String {
+    format!("Sign in to the exchange\nNonce: {}", nonce)
+}
+
+/// Address that produced `signature` (65 bytes `r || s || v`, hex) over `message`
+/// None if the signature is malformed or no key recovers from it
+pub fn recover_address(message: &str, signature: &str) -> Option<String> {
+    let bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature)).ok()?;
+    if bytes.len() != 65 {
+        return None;
+    }
+
+    // Wallets use 27/28 for v, raw signers 0/1
+    let v = match bytes[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return None,
+    };
+    let signature = Signature::from_slice(&bytes[..64]).ok()?;
+    let recovery_id = RecoveryId::from_byte(v)?;
+    let key = VerifyingKey::recover_from_prehash(&personal_hash(message), &signature, recovery_id)
+        .ok()?;
+    Some(address_of(&key))
+}
+
+/// Addresses compare case-insensitively, checksummed or not
+pub fn same_address(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+/// A secp256k1 key that signs challenges the way a wallet's `personal_sign` does
+#[derive(Clone)]
+pub struct Wallet {
+    key: SigningKey,
+}
+
+impl Wallet {
+    /// Wallet for a hex private key, with or without the 0x prefix
+    pub fn from_private_key(private_key: &str) -> Option<Self> {
+        let bytes = hex::decode(private_key.strip_prefix("0x").unwrap_or(private_key)).ok()?;
+        let key = SigningKey::from_slice(&bytes).ok()?;
+        Some(Self { key })
+    }
+
+    /// Deterministic wallet derived from a seed phrase, handy for tests and local bots
+    pub fn from_seed(seed: &str) -> Self {
+        let key = SigningKey::from_slice(&keccak256(seed.as_bytes()))
+            .expect("a keccak digest is a valid secp256k1 scalar");
+        Self { key }
+    }
+
+    /// Lowercase 0x-prefixed address of the wallet
+    pub fn address(&self) -> String {
+        address_of(self.key.verifying_key())
+    }
+
+    /// EIP-191 signature over `message`, hex with v as 27/28
+    pub fn sign(&self, message: &str) -> String {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(&personal_hash(message))
+            .expect("signing a 32 byte digest cannot fail");
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte() + 27);
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    /// Signature answering the challenge `nonce`
+    pub fn sign_challenge(&self, nonce: &str) -> String {
+        self.sign(&challenge_message(nonce))
+    }
+}
+
+impl std::fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Never print the key
+        f.debug_struct("Wallet")
+            .field("address", &self.address())
+            .finish()
+    }
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// EIP-191 digest: the message with the "Ethereum Signed Message" prefix and its length
+fn personal_hash(message: &str) -> [u8; 32] {
+    let prefixed = format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+    keccak256(prefixed.as_bytes())
+}
+
+/// Last 20 bytes of the keccak of the uncompressed public key
+fn address_of(key: &VerifyingKey) -> String {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    format!("0x{}", hex::encode(&hash[12..]))
+}
@@ -69,6 +69,30 @@ async fn main() -> Result<()> {
             .min_size
             .parse::<u128>()
             .context("Invalid min_size")?;
+        let min_price = market_config
+            .min_price
+            .as_deref()
+            .map(str::parse::<u128>)
+            .transpose()
+            .context("Invalid min_price")?;
+        let max_price = market_config
+            .max_price
+            .as_deref()
+            .map(str::parse::<u128>)
+            .transpose()
+            .context("Invalid max_price")?;
+        let max_open_notional_per_user = market_config
+            .max_open_notional_per_user
+            .as_deref()
+            .map(str::parse::<u128>)
+            .transpose()
+            .context("Invalid max_open_notional_per_user")?;
+        let min_notional = market_config
+            .min_notional
+            .as_deref()
+            .map(str::parse::<u128>)
+            .transpose()
+            .context("Invalid min_notional")?;
 
         match db
             .create_market(
@@ -79,6 +103,8 @@ async fn main() -> Result<()> {
                 min_size,
                 market_config.maker_fee_bps,
                 market_config.taker_fee_bps,
+                min_price,
+                max_price,
             )
             .await
         {
@@ -91,6 +117,32 @@ async fn main() -> Result<()> {
                 println!("  ⊙ Market {} already exists", market_id);
             }
         }
+
+        if market_config.max_open_orders_per_user.is_some() || max_open_notional_per_user.is_some()
+        {
+            db.set_market_order_limits(
+                &market_id,
+                market_config.max_open_orders_per_user,
+                max_open_notional_per_user,
+            )
+            .await
+            .context("Failed to set market order limits")?;
+            println!("  ✓ Set order limits for market: {}", market_id);
+        }
+
+        if min_notional.is_some() {
+            db.set_market_min_notional(&market_id, min_notional)
+                .await
+                .context("Failed to set market min notional")?;
+            println!("  ✓ Set min notional for market: {}", market_id);
+        }
+
+        if market_config.snap_to_tick {
+            db.set_market_snap_to_tick(&market_id, true)
+                .await
+                .context("Failed to enable snap to tick")?;
+            println!("  ✓ Enabled snap to tick for market: {}", market_id);
+        }
     }
 
     println!("\n✨ Backend initialization complete!");
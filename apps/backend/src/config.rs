@@ -1,11 +1,180 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::models::domain::{MatchPricePolicy, QueueFullPolicy, MAX_FEE_BPS, MAX_TOKEN_DECIMALS};
 
 /// Backend configuration (from apps/backend/config.toml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub markets: Vec<MarketConfig>,
     pub tokens: Vec<TokenConfig>,
+    #[serde(default)]
+    pub engine: EngineConfig,
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+}
+
+/// WebSocket server settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketConfig {
+    /// Subscriptions a single connection may hold at once
+    #[serde(default = "default_max_subscriptions_per_connection")]
+    pub max_subscriptions_per_connection: usize,
+    /// Allow the all_trades channel, which forwards every trade to each subscriber
+    #[serde(default = "default_all_trades_enabled")]
+    pub all_trades_enabled: bool,
+    /// Seconds a connection has to authenticate before private subscriptions disconnect it
+    /// Private channels always require auth, 0 lets a connection authenticate at any time
+    #[serde(default)]
+    pub auth_timeout_secs: u64,
+    /// Largest client frame accepted, bigger ones close the connection with 1009
+    #[serde(default = "default_max_message_bytes")]
+    pub max_message_bytes: usize,
+    /// Replies that may wait to be written before the connection is closed with 1008
+    #[serde(default = "default_max_queued_messages")]
+    pub max_queued_messages: usize,
+    /// Recent trades and orderbook updates kept per market for clients resuming after a reconnect
+    #[serde(default = "default_replay_buffer_size")]
+    pub replay_buffer_size: usize,
+    /// Deflate server messages for clients that negotiate the compression subprotocol
+    #[serde(default)]
+    pub compression: bool,
+}
+
+fn default_max_subscriptions_per_connection() -> usize {
+    100
+}
+
+fn default_all_trades_enabled() -> bool {
+    true
+}
+
+fn default_max_message_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_max_queued_messages() -> usize {
+    256
+}
+
+fn default_replay_buffer_size() -> usize {
+    1000
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            max_subscriptions_per_connection: default_max_subscriptions_per_connection(),
+            all_trades_enabled: default_all_trades_enabled(),
+            auth_timeout_secs: 0,
+            max_message_bytes: default_max_message_bytes(),
+            max_queued_messages: default_max_queued_messages(),
+            replay_buffer_size: default_replay_buffer_size(),
+            compression: false,
+        }
+    }
+}
+
+/// Matching engine settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineConfig {
+    #[serde(default)]
+    pub match_price_policy: MatchPricePolicy,
+    /// Write each state-changing request to the request log before processing it
+    #[serde(default = "default_request_log")]
+    pub request_log: bool,
+    /// Seconds between persisting every market's orderbook for recovery, 0 disables
+    #[serde(default = "default_snapshot_persist_interval_secs")]
+    pub snapshot_persist_interval_secs: u64,
+    /// Persisted orderbooks kept per market, so historical books replay the request
+    /// log from a nearby one. Older ones are pruned, the latest is always kept
+    #[serde(default = "default_snapshot_history")]
+    pub snapshot_history: usize,
+    /// Requests that may wait for the engine before the queue counts as full
+    #[serde(default = "default_request_queue_size")]
+    pub request_queue_size: usize,
+    #[serde(default)]
+    pub queue_full_policy: QueueFullPolicy,
+    /// Seconds a client_order_id keeps returning the order it first placed
+    #[serde(default = "default_idempotency_key_ttl_secs")]
+    pub idempotency_key_ttl_secs: u64,
+    /// Account credited with trading fees and debited for maker rebates
+    #[serde(default = "default_fee_recipient")]
+    pub fee_recipient: String,
+    /// Cancels (including replaces) each user may send per second, 0 disables the limit
+    /// The admin kill switch is never limited
+    #[serde(default)]
+    pub max_cancels_per_sec: u32,
+    /// Requests taking longer than this many milliseconds are logged at WARN, 0 disables
+    #[serde(default = "default_slow_request_threshold_ms")]
+    pub slow_request_threshold_ms: u64,
+    /// Check that every fill conserves base and quote across its buyer, seller and
+    /// fee recipient, failing the request if not. Costs extra reads per fill, and locks
+    /// the fill's balance rows for its whole transaction, so with `market_workers` fills
+    /// in different markets that share a user (always the fee recipient) settle one at a time
+    #[serde(default)]
+    pub check_balance_invariants: bool,
+    /// Match each market on its own worker task so a busy market doesn't hold up the
+    /// others. Requests within a market keep their order
+    #[serde(default)]
+    pub market_workers: bool,
+    /// Hold every request this long before applying it, so tests can line up
+    /// concurrent requests in the queue instead of racing them. Test builds only
+    #[cfg(feature = "fault-injection")]
+    #[serde(skip)]
+    pub match_delay: Option<std::time::Duration>,
+}
+
+fn default_request_log() -> bool {
+    true
+}
+
+fn default_snapshot_persist_interval_secs() -> u64 {
+    30
+}
+
+fn default_snapshot_history() -> usize {
+    120
+}
+
+fn default_request_queue_size() -> usize {
+    100
+}
+
+fn default_idempotency_key_ttl_secs() -> u64 {
+    86_400
+}
+
+fn default_fee_recipient() -> String {
+    "system".to_string()
+}
+
+fn default_slow_request_threshold_ms() -> u64 {
+    50
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            match_price_policy: MatchPricePolicy::default(),
+            request_log: default_request_log(),
+            snapshot_persist_interval_secs: default_snapshot_persist_interval_secs(),
+            snapshot_history: default_snapshot_history(),
+            request_queue_size: default_request_queue_size(),
+            queue_full_policy: QueueFullPolicy::default(),
+            idempotency_key_ttl_secs: default_idempotency_key_ttl_secs(),
+            fee_recipient: default_fee_recipient(),
+            max_cancels_per_sec: 0,
+            slow_request_threshold_ms: default_slow_request_threshold_ms(),
+            check_balance_invariants: false,
+            market_workers: false,
+            #[cfg(feature = "fault-injection")]
+            match_delay: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +186,18 @@ pub struct MarketConfig {
     pub min_size: String,
     pub maker_fee_bps: i32,
     pub taker_fee_bps: i32,
+    #[serde(default)]
+    pub min_price: Option<String>,
+    #[serde(default)]
+    pub max_price: Option<String>,
+    #[serde(default)]
+    pub max_open_orders_per_user: Option<u32>,
+    #[serde(default)]
+    pub max_open_notional_per_user: Option<String>,
+    #[serde(default)]
+    pub min_notional: Option<String>,
+    #[serde(default)]
+    pub snap_to_tick: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,13 +207,199 @@ pub struct TokenConfig {
     pub name: String,
 }
 
+/// One reason a config's tokens or markets can't be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigProblem {
+    /// A market names a token that isn't in `tokens`
+    UnknownToken {
+        market: String,
+        ticker: String,
+    },
+    DuplicateToken {
+        ticker: String,
+    },
+    DuplicateMarket {
+        market: String,
+    },
+    /// Taker fee outside 0 to 10000 bps, maker fee outside -10000 to 10000 bps,
+    /// or a maker rebate larger than the taker fee
+    FeeOutOfRange {
+        market: String,
+        maker_fee_bps: i32,
+        taker_fee_bps: i32,
+    },
+    /// An atom amount that isn't a positive integer
+    InvalidAmount {
+        market: String,
+        field: &'static str,
+        value: String,
+    },
+    EmptyPriceBand {
+        market: String,
+    },
+    TooManyDecimals {
+        ticker: String,
+        decimals: u8,
+    },
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigProblem::UnknownToken { market, ticker } => {
+                write!(f, "Market {} references unknown token {}", market, ticker)
+            }
+            ConfigProblem::DuplicateToken { ticker } => {
+                write!(f, "Token {} is defined more than once", ticker)
+            }
+            ConfigProblem::DuplicateMarket { market } => {
+                write!(f, "Market {} is defined more than once", market)
+            }
+            ConfigProblem::FeeOutOfRange {
+                market,
+                maker_fee_bps,
+                taker_fee_bps,
+            } => write!(
+                f,
+                "Market {} fees out of range (maker {} bps, taker {} bps): taker must be 0 to {} bps, maker -{} to {} bps, and a maker rebate can't exceed the taker fee",
+                market, maker_fee_bps, taker_fee_bps, MAX_FEE_BPS, MAX_FEE_BPS, MAX_FEE_BPS
+            ),
+            ConfigProblem::InvalidAmount {
+                market,
+                field,
+                value,
+            } => write!(
+                f,
+                "Market {} {} must be a positive integer, got {:?}",
+                market, field, value
+            ),
+            ConfigProblem::EmptyPriceBand { market } => {
+                write!(f, "Market {} min_price is greater than max_price", market)
+            }
+            ConfigProblem::TooManyDecimals { ticker, decimals } => write!(
+                f,
+                "Token {} has {} decimals, at most {} are allowed",
+                ticker, decimals, MAX_TOKEN_DECIMALS
+            ),
+        }
+    }
+}
+
 impl Config {
     /// Load backend configuration from config.toml
     /// Uses CARGO_MANIFEST_DIR so the path is consistent regardless of where the binary is run from
     pub fn load() -> Result<Self> {
-        let config_path = format!("{}/config.toml", env!("CARGO_MANIFEST_DIR"));
-        let contents = std::fs::read_to_string(&config_path)?;
+        Self::load_from(format!("{}/config.toml", env!("CARGO_MANIFEST_DIR")))
+    }
+
+    /// Load backend configuration from a TOML file
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
         let config: Config = toml::from_str(&contents)?;
         Ok(config)
     }
+
+    /// Check the tokens and markets without touching the database, returning every problem
+    /// Covers what creating them would reject, so a config that passes can be applied
+    pub fn validate(&self) -> std::result::Result<(), Vec<ConfigProblem>> {
+        let mut problems = Vec::new();
+
+        let mut tickers = HashSet::new();
+        for token in &self.tokens {
+            if !tickers.insert(token.ticker.as_str()) {
+                problems.push(ConfigProblem::DuplicateToken {
+                    ticker: token.ticker.clone(),
+                });
+            }
+            if token.decimals > MAX_TOKEN_DECIMALS {
+                problems.push(ConfigProblem::TooManyDecimals {
+                    ticker: token.ticker.clone(),
+                    decimals: token.decimals,
+                });
+            }
+        }
+
+        let mut market_ids = HashSet::new();
+        for market in &self.markets {
+            let id = format!("{}/{}", market.base_ticker, market.quote_ticker);
+
+            if !market_ids.insert(id.clone()) {
+                problems.push(ConfigProblem::DuplicateMarket { market: id.clone() });
+            }
+
+            for ticker in [&market.base_ticker, &market.quote_ticker] {
+                if !tickers.contains(ticker.as_str()) {
+                    problems.push(ConfigProblem::UnknownToken {
+                        market: id.clone(),
+                        ticker: ticker.clone(),
+                    });
+                }
+            }
+
+            // Same rule as market creation: maker fees may be a rebate, up to the taker fee
+            let (maker, taker) = (market.maker_fee_bps, market.taker_fee_bps);
+            if !(0..=MAX_FEE_BPS).contains(&taker)
+                || !(-MAX_FEE_BPS..=MAX_FEE_BPS).contains(&maker)
+                || maker + taker < 0
+            {
+                problems.push(ConfigProblem::FeeOutOfRange {
+                    market: id.clone(),
+                    maker_fee_bps: maker,
+                    taker_fee_bps: taker,
+                });
+            }
+
+            let sizes = [
+                ("tick_size", &market.tick_size),
+                ("lot_size", &market.lot_size),
+                ("min_size", &market.min_size),
+            ];
+            for (field, value) in sizes {
+                if !matches!(value.parse::<u128>(), Ok(v) if v > 0) {
+                    problems.push(ConfigProblem::InvalidAmount {
+                        market: id.clone(),
+                        field,
+                        value: value.clone(),
+                    });
+                }
+            }
+
+            let optional = [
+                ("min_price", &market.min_price),
+                ("max_price", &market.max_price),
+                (
+                    "max_open_notional_per_user",
+                    &market.max_open_notional_per_user,
+                ),
+                ("min_notional", &market.min_notional),
+            ];
+            for (field, value) in optional {
+                if let Some(value) = value {
+                    if !matches!(value.parse::<u128>(), Ok(v) if v > 0) {
+                        problems.push(ConfigProblem::InvalidAmount {
+                            market: id.clone(),
+                            field,
+                            value: value.clone(),
+                        });
+                    }
+                }
+            }
+
+            let band = (
+                market.min_price.as_deref().map(str::parse::<u128>),
+                market.max_price.as_deref().map(str::parse::<u128>),
+            );
+            if let (Some(Ok(min)), Some(Ok(max))) = band {
+                if min > max {
+                    problems.push(ConfigProblem::EmptyPriceBand { market: id.clone() });
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
 }
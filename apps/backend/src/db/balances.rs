@@ -2,7 +2,10 @@ use crate::db::Db;
 use crate::errors::Result;
 use crate::models::db::BalanceRow;
 use crate::models::domain::Balance;
+use crate::utils::BigDecimalExt;
+use bigdecimal::BigDecimal;
 use chrono::Utc;
+use std::collections::HashSet;
 
 impl Db {
     /// Get balance for a specific user and token
@@ -104,6 +107,31 @@ impl Db {
         self.get_balance(user_address, token_ticker).await
     }
 
+    /// Credit several (user, token, amount) grants in one transaction
+    /// Missing users are created. Returns the resulting balance of each
+    /// distinct user-token pair, in the order they first appear
+    pub async fn add_balances(&self, grants: &[(String, String, u128)]) -> Result<Vec<Balance>> {
+        let mut tx = self.postgres.begin().await?;
+        for (user_address, token_ticker, amount) in grants {
+            sqlx::query("INSERT INTO users (address) VALUES ($1) ON CONFLICT DO NOTHING")
+                .bind(user_address)
+                .execute(&mut *tx)
+                .await?;
+            self.add_balance_tx(&mut tx, user_address, token_ticker, *amount)
+                .await?;
+        }
+        tx.commit().await?;
+
+        let mut seen = HashSet::new();
+        let mut balances = Vec::new();
+        for (user_address, token_ticker, _) in grants {
+            if seen.insert((user_address, token_ticker)) {
+                balances.push(self.get_balance(user_address, token_ticker).await?);
+            }
+        }
+        Ok(balances)
+    }
+
     /// Subtract from existing balance (for withdrawals/debits)
     pub async fn subtract_balance(
         &self,
@@ -332,4 +360,69 @@ impl Db {
 
         Ok(())
     }
+
+    /// Total amount of a token held by a set of users, within a transaction
+    /// Users without a balance row count as zero
+    pub async fn total_balance_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_addresses: &[String],
+        token_ticker: &str,
+    ) -> Result<u128> {
+        let total: BigDecimal = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)
+            FROM balances
+            WHERE token_ticker = $1 AND user_address = ANY($2)
+            "#,
+        )
+        .bind(token_ticker)
+        .bind(user_addresses)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(total.to_u128())
+    }
+
+    /// Lock every user's balance in every token for the rest of the transaction
+    /// Missing rows are created empty first, since `FOR UPDATE` can't lock a row that
+    /// doesn't exist yet. Rows are locked in key order so concurrent callers can't deadlock
+    pub async fn lock_balances_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_addresses: &[String],
+        token_tickers: &[String],
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO balances (user_address, token_ticker, amount, open_interest, updated_at)
+            SELECT user_address, token_ticker, 0, 0, $3
+            FROM UNNEST($1::text[]) AS user_address
+            CROSS JOIN UNNEST($2::text[]) AS token_ticker
+            ORDER BY user_address, token_ticker
+            ON CONFLICT (user_address, token_ticker) DO NOTHING
+            "#,
+        )
+        .bind(user_addresses)
+        .bind(token_tickers)
+        .bind(Utc::now())
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            SELECT 1
+            FROM balances
+            WHERE user_address = ANY($1) AND token_ticker = ANY($2)
+            ORDER BY user_address, token_ticker
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_addresses)
+        .bind(token_tickers)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
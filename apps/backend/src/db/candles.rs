@@ -3,7 +3,7 @@ use crate::errors::{ExchangeError, Result};
 use crate::models::{
     api::ApiCandle,
     db::{CandleRow, ClickHouseTradeRow},
-    domain::{Candle, Trade},
+    domain::{Candle, CandleInterval, Side, Trade, TradeCursor},
 };
 use chrono::{DateTime, Utc};
 
@@ -11,6 +11,9 @@ impl Db {
     /// Insert a trade into ClickHouse for tick data
     /// This will automatically trigger the materialized views to aggregate into candles
     /// The AggregatingMergeTree will handle merging and pre-aggregating the data
+    /// Trade ids are deterministic, so re-inserting an existing trade is a no-op:
+    /// the id is the insert's deduplication token, and ClickHouse skips a repeated
+    /// block before the materialized views would double count it into candles
     pub async fn insert_trade_to_clickhouse(&self, trade: &Trade) -> Result<()> {
         let trade_row = ClickHouseTradeRow {
             id: trade.id.to_string(),
@@ -26,12 +29,14 @@ impl Db {
                 crate::models::domain::Side::Sell => "sell".to_string(),
             },
             timestamp: trade.timestamp.timestamp() as u32,
+            sequence: trade.sequence,
         };
 
         let mut insert = self
             .clickhouse
             .insert::<ClickHouseTradeRow>("trades")
-            .await?;
+            .await?
+            .with_option("insert_deduplication_token", trade_row.id.clone());
         insert.write(&trade_row).await?;
         insert.end().await?;
 
@@ -43,7 +48,7 @@ impl Db {
     pub async fn get_candles(
         &self,
         market_id: &str,
-        interval: &str, // '1m', '5m', '15m', '1h', '1d'
+        interval: CandleInterval,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<Candle>> {
@@ -65,7 +70,7 @@ impl Db {
             ORDER BY timestamp ASC",
             )
             .bind(market_id)
-            .bind(interval)
+            .bind(interval.as_str())
             .bind(start.timestamp() as u32)
             .bind(end.timestamp() as u32)
             .fetch_all::<CandleRow>()
@@ -86,17 +91,106 @@ impl Db {
             .collect())
     }
 
+    /// Candle intervals fed by a materialized view
+    /// Found by looking up the `candles_<interval>_mv` views, so intervals whose
+    /// migration hasn't been applied are reported as missing
+    pub async fn materialized_candle_intervals(&self) -> Result<Vec<CandleInterval>> {
+        let views: Vec<String> = self
+            .clickhouse
+            .query(
+                "SELECT name FROM system.tables
+                WHERE database = 'exchange' AND engine = 'MaterializedView'",
+            )
+            .fetch_all()
+            .await?;
+
+        Ok(CandleInterval::ALL
+            .into_iter()
+            .filter(|interval| views.contains(&format!("candles_{}_mv", interval.as_str())))
+            .collect())
+    }
+
     /// Get candles for API with support for countBack parameter
     /// Returns candles as ApiCandle with timestamp aggregation and optional limit
-    /// Uses -Merge combinators to finalize aggregate states
+    /// Intervals with a materialized view are read straight from it, any other
+    /// interval is aggregated on demand from 1m candles; both give the same bars.
+    /// `from` is rounded down to its bucket start so the bar containing it is included;
+    /// a positive `count_back` takes priority over `from` and returns the N most recent
+    /// bars up to `to`, however far back that reaches at large intervals
     pub async fn get_candles_for_api(
         &self,
         market_id: &str,
-        interval: &str,
+        interval: CandleInterval,
+        from: i64,
+        to: i64,
+        count_back: Option<usize>,
+    ) -> Result<Vec<ApiCandle>> {
+        let materialized = interval == CandleInterval::OneMinute
+            || self
+                .materialized_candle_intervals()
+                .await?
+                .contains(&interval);
+
+        let candles = if materialized {
+            self.get_materialized_candles(market_id, interval, from, to, count_back)
+                .await?
+        } else {
+            self.get_aggregated_candles(market_id, interval, from, to, count_back)
+                .await?
+        };
+
+        // Debug: Log last few candles to diagnose flat candle issue
+        if !candles.is_empty() {
+            let last_candles: Vec<_> = candles
+                .iter()
+                .rev()
+                .take(3)
+                .map(|c| {
+                    format!(
+                        "ts={} O={} H={} L={} C={} V={} flat={}",
+                        c.timestamp,
+                        c.open,
+                        c.high,
+                        c.low,
+                        c.close,
+                        c.volume,
+                        c.open == c.high && c.high == c.low && c.low == c.close
+                    )
+                })
+                .collect();
+            log::info!(
+                "Fetched {} candles for {} @ {} ({}), last 3: {:?}",
+                candles.len(),
+                market_id,
+                interval,
+                if materialized { "view" } else { "aggregated" },
+                last_candles
+            );
+        }
+
+        Ok(candles)
+    }
+
+    /// Candles of an interval read from its materialized view
+    /// Uses -Merge combinators to finalize aggregate states;
+    /// `from`, `to` and `count_back` behave as in `get_candles_for_api`
+    pub async fn get_materialized_candles(
+        &self,
+        market_id: &str,
+        interval: CandleInterval,
         from: i64,
         to: i64,
         count_back: Option<usize>,
     ) -> Result<Vec<ApiCandle>> {
+        let count_back = count_back.filter(|n| *n > 0);
+        let lower_bound = match count_back {
+            Some(_) => String::new(),
+            None => format!(
+                "AND timestamp >= toDateTime({})",
+                interval.bucket_start(from).max(0)
+            ),
+        };
+
         // Build the base query with -Merge combinators
         // Note: We GROUP BY all three key columns even though market_id and interval
         // are in WHERE clause, to ensure proper aggregation of unmerged parts
@@ -111,23 +205,21 @@ impl Db {
             FROM exchange.candles
             WHERE market_id = '{}'
               AND interval = '{}'
-              AND timestamp >= toDateTime({})
+              {}
               AND timestamp <= toDateTime({})
             GROUP BY market_id, interval, timestamp
             ORDER BY timestamp",
-            market_id, interval, from, to
+            market_id,
+            interval.as_str(),
+            lower_bound,
+            to
         );
 
         // Handle countBack: limit to N most recent bars
-        if let Some(count_back) = count_back {
-            if count_back > 0 {
-                // Get the last N bars by ordering DESC and limiting
-                query = format!("{} DESC LIMIT {}", query, count_back);
-            } else {
-                query = format!("{} ASC", query);
-            }
-        } else {
-            query = format!("{} ASC", query);
+        match count_back {
+            // Get the last N bars by ordering DESC and limiting
+            Some(count_back) => query = format!("{} DESC LIMIT {}", query, count_back),
+            None => query = format!("{} ASC", query),
         }
 
         let mut candles: Vec<ApiCandle> = self
@@ -138,52 +230,153 @@ impl Db {
             .map_err(ExchangeError::ClickHouse)?;
 
         // If we used DESC for countBack, reverse to get ascending order
-        if count_back.is_some() && count_back.unwrap() > 0 {
+        if count_back.is_some() {
             candles.reverse();
         }
 
-        // Debug: Log last few candles to diagnose flat candle issue
-        if !candles.is_empty() {
-            let last_candles: Vec<_> = candles
-                .iter()
-                .rev()
-                .take(3)
-                .map(|c| {
-                    format!(
-                        "ts={} O={} H={} L={} C={} V={} flat={}",
-                        c.timestamp,
-                        c.open,
-                        c.high,
-                        c.low,
-                        c.close,
-                        c.volume,
-                        c.open == c.high && c.high == c.low && c.low == c.close
-                    )
-                })
-                .collect();
-            log::info!(
-                "Fetched {} candles for {} @ {}, last 3: {:?}",
-                candles.len(),
-                market_id,
-                interval,
-                last_candles
-            );
+        Ok(candles)
+    }
+
+    /// Candles of an interval rolled up from 1m candles at query time
+    /// Bars are bucketed like `CandleInterval::bucket_start`, so they match what a
+    /// materialized view would hold; `from`, `to` and `count_back` behave as in
+    /// `get_candles_for_api`
+    pub async fn get_aggregated_candles(
+        &self,
+        market_id: &str,
+        interval: CandleInterval,
+        from: i64,
+        to: i64,
+        count_back: Option<usize>,
+    ) -> Result<Vec<ApiCandle>> {
+        let count_back = count_back.filter(|n| *n > 0);
+        let lower_bound = match count_back {
+            Some(_) => String::new(),
+            None => format!(
+                "AND timestamp >= toDateTime({})",
+                interval.bucket_start(from).max(0)
+            ),
+        };
+        // Every minute of the bar starting at or before `to`
+        let upper_bound = interval.bucket_start(to) + interval.seconds();
+        let seconds = interval.seconds();
+        let offset = interval.bucket_start(0).rem_euclid(seconds);
+
+        // Minute bars are finalized first, then folded into the wider buckets
+        let mut query = format!(
+            "SELECT
+                toUInt32(intDiv(ts - {offset}, {seconds}) * {seconds} + {offset}) as timestamp,
+                argMin(o, ts) as open,
+                max(h) as high,
+                min(l) as low,
+                argMax(c, ts) as close,
+                sum(v) as volume
+            FROM (
+                SELECT
+                    toUnixTimestamp(timestamp) as ts,
+                    argMinMerge(open_state) as o,
+                    maxMerge(high_state) as h,
+                    minMerge(low_state) as l,
+                    argMaxMerge(close_state) as c,
+                    sumMerge(volume_state) as v
+                FROM exchange.candles
+                WHERE market_id = '{market_id}'
+                  AND interval = '{minute}'
+                  {lower_bound}
+                  AND timestamp < toDateTime({upper_bound})
+                GROUP BY market_id, interval, timestamp
+            )
+            GROUP BY timestamp
+            ORDER BY timestamp",
+            minute = CandleInterval::OneMinute.as_str(),
+        );
+
+        match count_back {
+            Some(count_back) => query = format!("{} DESC LIMIT {}", query, count_back),
+            None => query = format!("{} ASC", query),
+        }
+
+        let mut candles: Vec<ApiCandle> = self
+            .clickhouse
+            .query(&query)
+            .fetch_all()
+            .await
+            .map_err(ExchangeError::ClickHouse)?;
+
+        if count_back.is_some() {
+            candles.reverse();
         }
 
         Ok(candles)
     }
 
-    /// Get recent trades for a market (tick data)
-    pub async fn get_recent_trades(&self, market_id: &str, limit: u32) -> Result<Vec<Trade>> {
+    /// Get recent trades for a market (tick data), most recent first
+    /// Optionally only trades whose taker was on `side` and of at least `min_size` base atoms
+    pub async fn get_recent_trades(
+        &self,
+        market_id: &str,
+        side: Option<Side>,
+        min_size: Option<u128>,
+        limit: u32,
+    ) -> Result<Vec<Trade>> {
+        self.get_trades_page(market_id, side, min_size, None, None, None, limit)
+            .await
+    }
+
+    /// Get one page of a market's trades, most recent first
+    /// `from` (inclusive) and `to` (exclusive) bound the timestamp in Unix seconds, and
+    /// `before` continues from a previous page; trades within a second are in execution order
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_trades_page(
+        &self,
+        market_id: &str,
+        side: Option<Side>,
+        min_size: Option<u128>,
+        from: Option<i64>,
+        to: Option<i64>,
+        before: Option<TradeCursor>,
+        limit: u32,
+    ) -> Result<Vec<Trade>> {
         let limit = std::cmp::min(limit, 1000);
 
-        let trades = self
-            .clickhouse
-            .query("SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, timestamp FROM trades WHERE market_id = ? ORDER BY timestamp DESC LIMIT ?")
-            .bind(market_id)
-            .bind(limit)
-            .fetch_all::<ClickHouseTradeRow>()
-            .await?;
+        let mut sql = String::from("SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp, sequence FROM trades FINAL WHERE market_id = ?");
+        if side.is_some() {
+            sql.push_str(" AND side = ?");
+        }
+        if min_size.is_some() {
+            sql.push_str(" AND size >= ?");
+        }
+        if from.is_some() {
+            sql.push_str(" AND timestamp >= toDateTime(?)");
+        }
+        if to.is_some() {
+            sql.push_str(" AND timestamp < toDateTime(?)");
+        }
+        if before.is_some() {
+            sql.push_str(" AND (timestamp, sequence, id) < (toDateTime(?), ?, ?)");
+        }
+        sql.push_str(" ORDER BY timestamp DESC, sequence DESC, id DESC LIMIT ?");
+
+        let mut query = self.clickhouse.query(&sql).bind(market_id);
+        if let Some(side) = side {
+            query = query.bind(side.to_string());
+        }
+        if let Some(min_size) = min_size {
+            query = query.bind(min_size);
+        }
+        if let Some(from) = from {
+            query = query.bind(from);
+        }
+        if let Some(to) = to {
+            query = query.bind(to);
+        }
+        if let Some(before) = before {
+            query = query
+                .bind(before.timestamp)
+                .bind(before.sequence)
+                .bind(before.id.to_string());
+        }
+        let trades = query.bind(limit).fetch_all::<ClickHouseTradeRow>().await?;
 
         Ok(trades
             .into_iter()
@@ -198,12 +391,13 @@ impl Db {
                     price: row.price,
                     size: row.size,
                     side: if row.side == "buy" {
-                        crate::models::domain::Side::Buy
+                        Side::Buy
                     } else {
-                        crate::models::domain::Side::Sell
+                        Side::Sell
                     },
                     timestamp: DateTime::from_timestamp(row.timestamp as i64, 0)
                         .unwrap_or(DateTime::UNIX_EPOCH),
+                    sequence: row.sequence,
                 })
             })
             .collect())
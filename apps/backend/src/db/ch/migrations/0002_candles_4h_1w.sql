@@ -0,0 +1,32 @@
+-- 4h and 1w candle intervals
+-- Like the other views these only see trades inserted after they are created,
+-- so buckets for earlier history stay empty
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_4h_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '4h' as interval,
+    toStartOfInterval(t.timestamp, INTERVAL 4 HOUR) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+-- Weeks start on Monday (ISO 8601)
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_1w_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '1w' as interval,
+    toDateTime(toMonday(t.timestamp)) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
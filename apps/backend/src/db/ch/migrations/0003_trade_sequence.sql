@@ -0,0 +1,4 @@
+-- Per-market execution order of trades, assigned by the engine
+-- Timestamps are whole seconds, so trades within a second are ordered by sequence
+-- Trades inserted before this migration read as sequence 0
+ALTER TABLE exchange.trades ADD COLUMN IF NOT EXISTS sequence UInt64 DEFAULT 0;
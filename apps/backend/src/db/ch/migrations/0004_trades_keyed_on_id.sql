@@ -0,0 +1,148 @@
+-- Key trades on their id so a trade inserted twice (e.g. when recovery replays
+-- a match) is stored once. ReplacingMergeTree only collapses duplicates when
+-- parts merge, so reads that must not see them use FINAL.
+--
+-- The candle views fire on every insert, so collapsing rows isn't enough to
+-- keep volume right. Inserts carry the trade id as insert_deduplication_token;
+-- within the deduplication window below a repeated insert is skipped, and a
+-- skipped block is never passed on to the views.
+--
+-- Note the candle views are built from the raw inserted blocks, not from the
+-- deduplicated rows: a duplicate that gets past the insert deduplication (outside
+-- the window, or retried with another token) is counted twice in candles even
+-- though FINAL hides it from trade reads.
+--
+-- ORDER BY can't be altered in place: copy the trades into a new table, swap
+-- it in, and recreate the views so they read from it. Candles already built
+-- are kept as they are.
+
+CREATE TABLE IF NOT EXISTS exchange.trades_by_id (
+    id String,
+    market_id String,
+    buyer_address String,
+    seller_address String,
+    buyer_order_id String,
+    seller_order_id String,
+    price UInt128,
+    size UInt128,
+    side String,
+    timestamp DateTime,
+    sequence UInt64 DEFAULT 0
+) ENGINE = ReplacingMergeTree()
+ORDER BY (market_id, id)
+PRIMARY KEY (market_id, id)
+SETTINGS non_replicated_deduplication_window = 10000;
+
+INSERT INTO exchange.trades_by_id
+SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp, sequence
+FROM exchange.trades;
+
+DROP VIEW IF EXISTS exchange.candles_1m_mv;
+DROP VIEW IF EXISTS exchange.candles_5m_mv;
+DROP VIEW IF EXISTS exchange.candles_15m_mv;
+DROP VIEW IF EXISTS exchange.candles_1h_mv;
+DROP VIEW IF EXISTS exchange.candles_1d_mv;
+DROP VIEW IF EXISTS exchange.candles_4h_mv;
+DROP VIEW IF EXISTS exchange.candles_1w_mv;
+
+RENAME TABLE exchange.trades TO exchange.trades_unkeyed, exchange.trades_by_id TO exchange.trades;
+
+DROP TABLE IF EXISTS exchange.trades_unkeyed;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_1m_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '1m' as interval,
+    toStartOfMinute(t.timestamp) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_5m_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '5m' as interval,
+    toStartOfInterval(t.timestamp, INTERVAL 5 MINUTE) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_15m_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '15m' as interval,
+    toStartOfInterval(t.timestamp, INTERVAL 15 MINUTE) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_1h_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '1h' as interval,
+    toStartOfHour(t.timestamp) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_1d_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '1d' as interval,
+    toStartOfDay(t.timestamp) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_4h_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '4h' as interval,
+    toStartOfInterval(t.timestamp, INTERVAL 4 HOUR) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS exchange.candles_1w_mv
+TO exchange.candles
+AS SELECT
+    t.market_id,
+    '1w' as interval,
+    toDateTime(toMonday(t.timestamp)) as timestamp,
+    argMinState(t.price, t.timestamp) as open_state,
+    maxState(t.price) as high_state,
+    minState(t.price) as low_state,
+    argMaxState(t.price, t.timestamp) as close_state,
+    sumState(t.size) as volume_state
+FROM exchange.trades AS t
+GROUP BY t.market_id, interval, timestamp;
@@ -45,41 +45,155 @@ pub async fn create_client(url: Option<String>) -> anyhow::Result<Client> {
     Ok(client)
 }
 
+/// A versioned ClickHouse schema change
+/// Applied migrations are recorded in `exchange.schema_migrations` and never re-run,
+/// so add a new file for every change instead of editing an applied one
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations in the order they are applied
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        sql: include_str!("migrations/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "candles_4h_1w",
+        sql: include_str!("migrations/0002_candles_4h_1w.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "trade_sequence",
+        sql: include_str!("migrations/0003_trade_sequence.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "trades_keyed_on_id",
+        sql: include_str!("migrations/0004_trades_keyed_on_id.sql"),
+    },
+];
+
 /// Initialize ClickHouse schema (tables and materialized views)
-async fn init_schema(client: &Client) -> anyhow::Result<()> {
-    let schema = include_str!("schema.sql");
-
-    // Split by statements and execute each
-    for statement in schema.split(';') {
-        // Remove comment lines and inline comments
-        let cleaned: String = statement
-            .lines()
-            .filter_map(|line| {
-                let trimmed = line.trim();
-                // Skip empty lines and lines that start with --
-                if trimmed.is_empty() || trimmed.starts_with("--") {
-                    return None;
-                }
-                // Remove inline comments (everything after --)
-                let without_inline_comment = if let Some(pos) = line.find("--") {
-                    &line[..pos]
-                } else {
-                    line
-                };
-                Some(without_inline_comment)
-            })
-            .collect::<Vec<&str>>()
-            .join("\n");
-
-        let trimmed = cleaned.trim();
-        if !trimmed.is_empty() {
+/// Applies pending migrations in version order, safe to run repeatedly
+pub async fn init_schema(client: &Client) -> anyhow::Result<()> {
+    client
+        .query(
+            "CREATE TABLE IF NOT EXISTS exchange.schema_migrations (
+                version UInt32,
+                name String,
+                applied_at DateTime DEFAULT now()
+            ) ENGINE = ReplacingMergeTree()
+            ORDER BY version",
+        )
+        .execute()
+        .await
+        .context("Failed to create schema_migrations table")?;
+
+    let applied: Vec<u32> = client
+        .query("SELECT DISTINCT version FROM exchange.schema_migrations")
+        .fetch_all::<u32>()
+        .await
+        .context("Failed to read applied migrations")?;
+
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        log::info!(
+            "Applying ClickHouse migration {:04}_{}",
+            migration.version,
+            migration.name
+        );
+
+        // Statements use IF NOT EXISTS, so a migration interrupted halfway can be re-run
+        for statement in split_statements(migration.sql) {
             client
-                .query(trimmed)
+                .query(&statement)
                 .execute()
                 .await
-                .with_context(|| format!("Failed to execute: {}", trimmed))?;
+                .with_context(|| format!("Failed to execute: {}", statement))?;
         }
+
+        client
+            .query("INSERT INTO exchange.schema_migrations (version, name) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute()
+            .await
+            .with_context(|| format!("Failed to record migration {}", migration.version))?;
     }
 
     Ok(())
 }
+
+/// Split a SQL script into statements on top-level semicolons
+/// Semicolons inside quoted strings, quoted identifiers and comments are ignored,
+/// and comments are stripped from the returned statements
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Quoted string or identifier, copied verbatim up to the closing quote
+            '\'' | '"' | '`' => {
+                current.push(c);
+                while let Some(next) = chars.next() {
+                    current.push(next);
+                    if next == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            current.push(escaped);
+                        }
+                    } else if next == c {
+                        // A doubled quote is an escaped quote, not the end
+                        match chars.next_if_eq(&c) {
+                            Some(quote) => current.push(quote),
+                            None => break,
+                        }
+                    }
+                }
+            }
+            // Line comment
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            // Block comment
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+                current.push(' ');
+            }
+            ';' => push_statement(&mut statements, &mut current),
+            _ => current.push(c),
+        }
+    }
+    push_statement(&mut statements, &mut current);
+
+    statements
+}
+
+fn push_statement(statements: &mut Vec<String>, current: &mut String) {
+    let statement = current.trim();
+    if !statement.is_empty() {
+        statements.push(statement.to_string());
+    }
+    current.clear();
+}
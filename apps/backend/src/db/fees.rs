@@ -0,0 +1,246 @@
+use crate::db::Db;
+use crate::errors::{ExchangeError, Result};
+use crate::models::domain::{FeeSchedule, Market};
+use crate::utils::BigDecimalExt;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use sqlx::Row;
+
+impl Db {
+    /// Set (or replace) a user's fee tier override
+    pub async fn set_user_fee_tier(
+        &self,
+        user_address: &str,
+        fee_schedule: FeeSchedule,
+    ) -> Result<FeeSchedule> {
+        let valid_range = 0..=FeeSchedule::DENOMINATOR as i32;
+        if !valid_range.contains(&fee_schedule.maker_fee_tenth_bps)
+            || !valid_range.contains(&fee_schedule.taker_fee_tenth_bps)
+        {
+            return Err(ExchangeError::InvalidParameter {
+                message: format!(
+                    "Fee rates must be between 0 and {} tenths of a basis point",
+                    FeeSchedule::DENOMINATOR
+                ),
+            });
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_fee_tiers (user_address, maker_fee_tenth_bps, taker_fee_tenth_bps, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_address)
+            DO UPDATE SET maker_fee_tenth_bps = $2, taker_fee_tenth_bps = $3, updated_at = $4
+            "#,
+        )
+        .bind(user_address)
+        .bind(fee_schedule.maker_fee_tenth_bps)
+        .bind(fee_schedule.taker_fee_tenth_bps)
+        .bind(Utc::now())
+        .execute(&self.postgres)
+        .await?;
+
+        Ok(fee_schedule)
+    }
+
+    /// Get a user's fee tier override, if one is set
+    pub async fn get_user_fee_tier(&self, user_address: &str) -> Result<Option<FeeSchedule>> {
+        let row = sqlx::query(
+            r#"
+            SELECT maker_fee_tenth_bps, taker_fee_tenth_bps
+            FROM user_fee_tiers
+            WHERE user_address = $1
+            "#,
+        )
+        .bind(user_address)
+        .fetch_optional(&self.postgres)
+        .await?;
+
+        Ok(row.map(|row| FeeSchedule {
+            maker_fee_tenth_bps: row.get("maker_fee_tenth_bps"),
+            taker_fee_tenth_bps: row.get("taker_fee_tenth_bps"),
+        }))
+    }
+
+    /// Resolve the fees a user pays on a market
+    /// A fee tier override wins over the market's default fees
+    pub async fn get_fee_schedule(
+        &self,
+        user_address: &str,
+        market: &Market,
+    ) -> Result<FeeSchedule> {
+        Ok(self
+            .get_user_fee_tier(user_address)
+            .await?
+            .unwrap_or_else(|| FeeSchedule::from_market(market)))
+    }
+
+    /// Pay a maker rebate out of the fee recipient's available balance
+    /// Whatever the balance can't cover is owed as a fee liability rather than failing
+    /// the trade, so rebates never depend on the fee recipient being funded
+    pub async fn pay_fee_rebate_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fee_recipient: &str,
+        token_ticker: &str,
+        amount: u128,
+    ) -> Result<()> {
+        let available: Option<BigDecimal> = sqlx::query_scalar(
+            r#"
+            SELECT amount - open_interest
+            FROM balances
+            WHERE user_address = $1 AND token_ticker = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(fee_recipient)
+        .bind(token_ticker)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let paid = available.map_or(0, BigDecimalExt::to_u128).min(amount);
+        if paid > 0 {
+            self.subtract_balance_tx(tx, fee_recipient, token_ticker, paid)
+                .await?;
+        }
+
+        let owed = amount - paid;
+        if owed > 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO fee_liabilities (fee_recipient, token_ticker, amount, updated_at)
+                VALUES ($1, $2, $3::numeric, $4)
+                ON CONFLICT (fee_recipient, token_ticker)
+                DO UPDATE SET
+                    amount = fee_liabilities.amount + $3::numeric,
+                    updated_at = $4
+                "#,
+            )
+            .bind(fee_recipient)
+            .bind(token_ticker)
+            .bind(owed.to_string())
+            .bind(Utc::now())
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Credit a collected fee to the fee recipient, repaying any fee liability first
+    pub async fn collect_fee_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fee_recipient: &str,
+        token_ticker: &str,
+        amount: u128,
+    ) -> Result<()> {
+        let owed = self
+            .fee_liability_tx(tx, fee_recipient, token_ticker)
+            .await?;
+
+        let repaid = owed.min(amount);
+        if repaid > 0 {
+            sqlx::query(
+                r#"
+                UPDATE fee_liabilities
+                SET amount = amount - $3::numeric, updated_at = $4
+                WHERE fee_recipient = $1 AND token_ticker = $2
+                "#,
+            )
+            .bind(fee_recipient)
+            .bind(token_ticker)
+            .bind(repaid.to_string())
+            .bind(Utc::now())
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if amount > repaid {
+            self.add_balance_tx(tx, fee_recipient, token_ticker, amount - repaid)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebates the fee recipient still owes in a token
+    pub async fn get_fee_liability(&self, fee_recipient: &str, token_ticker: &str) -> Result<u128> {
+        let owed: Option<BigDecimal> = sqlx::query_scalar(
+            r#"
+            SELECT amount
+            FROM fee_liabilities
+            WHERE fee_recipient = $1 AND token_ticker = $2
+            "#,
+        )
+        .bind(fee_recipient)
+        .bind(token_ticker)
+        .fetch_optional(&self.postgres)
+        .await?;
+
+        Ok(owed.map_or(0, BigDecimalExt::to_u128))
+    }
+
+    /// Rebates the fee recipient still owes in a token, locked for the transaction
+    pub async fn fee_liability_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fee_recipient: &str,
+        token_ticker: &str,
+    ) -> Result<u128> {
+        let owed: Option<BigDecimal> = sqlx::query_scalar(
+            r#"
+            SELECT amount
+            FROM fee_liabilities
+            WHERE fee_recipient = $1 AND token_ticker = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(fee_recipient)
+        .bind(token_ticker)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(owed.map_or(0, BigDecimalExt::to_u128))
+    }
+
+    /// Lock the fee recipient's liabilities in every token for the rest of the transaction
+    /// Missing rows are created at zero first so a rebate owed concurrently has to wait
+    pub async fn lock_fee_liabilities_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fee_recipient: &str,
+        token_tickers: &[String],
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO fee_liabilities (fee_recipient, token_ticker, amount, updated_at)
+            SELECT $1, token_ticker, 0, $3
+            FROM UNNEST($2::text[]) AS token_ticker
+            ORDER BY token_ticker
+            ON CONFLICT (fee_recipient, token_ticker) DO NOTHING
+            "#,
+        )
+        .bind(fee_recipient)
+        .bind(token_tickers)
+        .bind(Utc::now())
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            SELECT 1
+            FROM fee_liabilities
+            WHERE fee_recipient = $1 AND token_ticker = ANY($2)
+            ORDER BY token_ticker
+            FOR UPDATE
+            "#,
+        )
+        .bind(fee_recipient)
+        .bind(token_tickers)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
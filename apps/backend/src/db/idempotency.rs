@@ -0,0 +1,80 @@
+use crate::db::Db;
+use crate::errors::Result;
+use crate::models::api::OrderPlaced;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use uuid::Uuid;
+
+impl Db {
+    /// Get the response stored for a user's client_order_id, ignoring entries created before `since`
+    pub async fn get_idempotent_order(
+        &self,
+        user_address: &str,
+        client_order_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Option<OrderPlaced>> {
+        let row = sqlx::query(
+            r#"
+            SELECT response::text AS response
+            FROM order_idempotency_keys
+            WHERE user_address = $1 AND client_order_id = $2 AND created_at > $3
+            "#,
+        )
+        .bind(user_address)
+        .bind(client_order_id)
+        .bind(since)
+        .fetch_optional(&self.postgres)
+        .await?;
+
+        row.map(|row| {
+            let response: String = row.get("response");
+            Ok(serde_json::from_str(&response)?)
+        })
+        .transpose()
+    }
+
+    /// Store the response to an order placed with a client_order_id
+    /// Replaces an expired entry for the same key
+    pub async fn store_idempotent_order(
+        &self,
+        user_address: &str,
+        client_order_id: &str,
+        placed: &OrderPlaced,
+    ) -> Result<()> {
+        let order_id = Uuid::parse_str(&placed.order.id)?;
+        let response = serde_json::to_string(placed)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO order_idempotency_keys (user_address, client_order_id, order_id, response)
+            VALUES ($1, $2, $3, $4::text::jsonb)
+            ON CONFLICT (user_address, client_order_id)
+            DO UPDATE SET order_id = $3, response = $4::text::jsonb, created_at = NOW()
+            "#,
+        )
+        .bind(user_address)
+        .bind(client_order_id)
+        .bind(order_id)
+        .bind(response)
+        .execute(&self.postgres)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete idempotency keys created before `before`
+    /// Returns the number of keys removed
+    pub async fn purge_idempotency_keys(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM order_idempotency_keys
+            WHERE created_at <= $1
+            "#,
+        )
+        .bind(before)
+        .execute(&self.postgres)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
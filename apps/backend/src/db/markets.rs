@@ -2,7 +2,10 @@ use bigdecimal::BigDecimal;
 
 use crate::db::Db;
 use crate::errors::{ExchangeError, Result};
-use crate::models::{db::MarketRow, domain::Market};
+use crate::models::{
+    db::MarketRow,
+    domain::{Market, MarketStatus, MAX_FEE_BPS},
+};
 
 impl Db {
     /// Create a new market
@@ -16,7 +19,31 @@ impl Db {
         min_size: u128,
         maker_fee_bps: i32,
         taker_fee_bps: i32,
+        min_price: Option<u128>,
+        max_price: Option<u128>,
     ) -> Result<Market> {
+        // Maker fees may be negative (a rebate), but never more than the taker fee
+        if !(0..=MAX_FEE_BPS).contains(&taker_fee_bps)
+            || !(-MAX_FEE_BPS..=MAX_FEE_BPS).contains(&maker_fee_bps)
+            || maker_fee_bps + taker_fee_bps < 0
+        {
+            return Err(ExchangeError::InvalidParameter {
+                message: format!(
+                    "Invalid fees: taker must be 0 to {} bps, maker -{} to {} bps, and a maker rebate can't exceed the taker fee",
+                    MAX_FEE_BPS, MAX_FEE_BPS, MAX_FEE_BPS
+                ),
+            });
+        }
+
+        // Price band must be non-empty when both bounds are set
+        if let (Some(min), Some(max)) = (min_price, max_price) {
+            if min > max {
+                return Err(ExchangeError::InvalidParameter {
+                    message: format!("min_price {} is greater than max_price {}", min, max),
+                });
+            }
+        }
+
         // Check if both tokens exist before creating the market
         self.get_token(&base_ticker)
             .await
@@ -34,7 +61,7 @@ impl Db {
 
         let row = sqlx::query_as!(
             MarketRow,
-            "INSERT INTO markets (id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps",
+            r#"INSERT INTO markets (id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!""#,
             id,
             base_ticker,
             quote_ticker,
@@ -42,7 +69,9 @@ impl Db {
             BigDecimal::from(lot_size),
             BigDecimal::from(min_size),
             maker_fee_bps,
-            taker_fee_bps
+            taker_fee_bps,
+            min_price.map(BigDecimal::from),
+            max_price.map(BigDecimal::from)
         )
         .fetch_one(&self.postgres)
         .await
@@ -56,13 +85,15 @@ impl Db {
         Ok(row.into())
     }
 
-    /// Get a market by id
+    /// Get a market by id, `MarketNotFound` if there is none
     pub async fn get_market(&self, market_id: &str) -> Result<Market> {
         let row: MarketRow =
-            sqlx::query_as!(MarketRow, "SELECT id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps FROM markets WHERE id = $1", market_id)
-                .fetch_one(&self.postgres)
-                .await
-                .map_err(ExchangeError::from)?;
+            sqlx::query_as!(MarketRow, r#"SELECT id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!" FROM markets WHERE id = $1"#, market_id)
+                .fetch_optional(&self.postgres)
+                .await?
+                .ok_or_else(|| ExchangeError::MarketNotFound {
+                    market_id: market_id.to_string(),
+                })?;
 
         Ok(row.into())
     }
@@ -71,7 +102,7 @@ impl Db {
     pub async fn list_markets(&self) -> Result<Vec<Market>> {
         let rows = sqlx::query_as!(
             MarketRow,
-            "SELECT id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps FROM markets ORDER BY id"
+            r#"SELECT id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!" FROM markets ORDER BY id"#
         )
         .fetch_all(&self.postgres)
         .await
@@ -79,4 +110,132 @@ impl Db {
 
         Ok(rows.into_iter().map(|row| row.into()).collect())
     }
+
+    /// List the markets with the given ids, skipping ids that don't exist
+    pub async fn get_markets_by_ids(&self, market_ids: &[String]) -> Result<Vec<Market>> {
+        let rows = sqlx::query_as!(
+            MarketRow,
+            r#"SELECT id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!" FROM markets WHERE id = ANY($1) ORDER BY id"#,
+            market_ids
+        )
+        .fetch_all(&self.postgres)
+        .await
+        .map_err(ExchangeError::from)?;
+
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
+
+    /// Set or clear the per-user open order limits on a market
+    pub async fn set_market_order_limits(
+        &self,
+        market_id: &str,
+        max_open_orders_per_user: Option<u32>,
+        max_open_notional_per_user: Option<u128>,
+    ) -> Result<Market> {
+        let row = sqlx::query_as!(
+            MarketRow,
+            r#"UPDATE markets SET max_open_orders_per_user = $2, max_open_notional_per_user = $3 WHERE id = $1 RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!""#,
+            market_id,
+            max_open_orders_per_user.map(|n| n as i32),
+            max_open_notional_per_user.map(BigDecimal::from)
+        )
+        .fetch_optional(&self.postgres)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.constraint().is_some() => {
+                ExchangeError::InvalidParameter {
+                    message: "order limits must be greater than zero".to_string(),
+                }
+            }
+            _ => ExchangeError::Database(e),
+        })?
+        .ok_or_else(|| ExchangeError::MarketNotFound {
+            market_id: market_id.to_string(),
+        })?;
+
+        Ok(row.into())
+    }
+
+    /// Set or clear the minimum order value on a market
+    pub async fn set_market_min_notional(
+        &self,
+        market_id: &str,
+        min_notional: Option<u128>,
+    ) -> Result<Market> {
+        let row = sqlx::query_as!(
+            MarketRow,
+            r#"UPDATE markets SET min_notional = $2 WHERE id = $1 RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!""#,
+            market_id,
+            min_notional.map(BigDecimal::from)
+        )
+        .fetch_optional(&self.postgres)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.constraint().is_some() => {
+                ExchangeError::InvalidParameter {
+                    message: "min_notional must be greater than zero".to_string(),
+                }
+            }
+            _ => ExchangeError::Database(e),
+        })?
+        .ok_or_else(|| ExchangeError::MarketNotFound {
+            market_id: market_id.to_string(),
+        })?;
+
+        Ok(row.into())
+    }
+
+    /// Turn rounding of off-tick limit prices on or off for a market
+    pub async fn set_market_snap_to_tick(
+        &self,
+        market_id: &str,
+        snap_to_tick: bool,
+    ) -> Result<Market> {
+        let row = sqlx::query_as!(
+            MarketRow,
+            r#"UPDATE markets SET snap_to_tick = $2 WHERE id = $1 RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!""#,
+            market_id,
+            snap_to_tick
+        )
+        .fetch_optional(&self.postgres)
+        .await?
+        .ok_or_else(|| ExchangeError::MarketNotFound {
+            market_id: market_id.to_string(),
+        })?;
+
+        Ok(row.into())
+    }
+
+    /// Move a market to another trading phase
+    pub async fn set_market_status(&self, market_id: &str, status: MarketStatus) -> Result<Market> {
+        let row = sqlx::query_as!(
+            MarketRow,
+            r#"UPDATE markets SET status = $2::text::market_status WHERE id = $1 RETURNING id, base_ticker, quote_ticker, tick_size, lot_size, min_size, maker_fee_bps, taker_fee_bps, min_price, max_price, max_open_orders_per_user, max_open_notional_per_user, min_notional, last_price, snap_to_tick, status::TEXT AS "status!""#,
+            market_id,
+            status.to_string()
+        )
+        .fetch_optional(&self.postgres)
+        .await?
+        .ok_or_else(|| ExchangeError::MarketNotFound {
+            market_id: market_id.to_string(),
+        })?;
+
+        Ok(row.into())
+    }
+
+    /// Record the price of the market's most recent trade (within a transaction)
+    pub async fn set_market_last_price_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        market_id: &str,
+        price: u128,
+    ) -> Result<()> {
+        sqlx::query("UPDATE markets SET last_price = $2::numeric WHERE id = $1")
+            .bind(market_id)
+            .bind(price.to_string())
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
 }
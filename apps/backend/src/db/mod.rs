@@ -4,8 +4,13 @@ pub mod pg;
 
 pub mod balances;
 pub mod candles;
+pub mod fees;
+pub mod idempotency;
 pub mod markets;
+pub mod orderbook_snapshots;
 pub mod orders;
+pub mod request_log;
+pub mod stats;
 pub mod tokens;
 pub mod trades;
 pub mod users;
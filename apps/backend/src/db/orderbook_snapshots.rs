@@ -0,0 +1,221 @@
+use crate::db::Db;
+use crate::engine::matcher::Matcher;
+use crate::engine::orderbook::Orderbook;
+use crate::engine::MatchingEngine;
+use crate::errors::Result;
+use crate::models::domain::{
+    LoggedRequest, MarketStatus, Order, OrderbookSnapshot, PersistedOrderbook,
+};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+impl Db {
+    /// Save a market's orderbook alongside its earlier snapshots
+    /// Saving again at the same `saved_at` replaces that snapshot
+    pub async fn save_orderbook_snapshot(&self, snapshot: &PersistedOrderbook) -> Result<()> {
+        let orders = serde_json::to_string(&snapshot.orders)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO orderbook_snapshots (market_id, orders, request_sequence, saved_at)
+            VALUES ($1, $2::text::jsonb, $3, $4)
+            ON CONFLICT (market_id, saved_at)
+            DO UPDATE SET orders = $2::text::jsonb, request_sequence = $3, saved_at = $4
+            "#,
+        )
+        .bind(&snapshot.market_id)
+        .bind(orders)
+        .bind(snapshot.request_sequence)
+        .bind(snapshot.saved_at)
+        .execute(&self.postgres)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete all but the `keep` most recent snapshots of each market
+    /// The latest snapshot is always kept. Returns the number of snapshots removed
+    pub async fn prune_orderbook_snapshots(&self, keep: usize) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM orderbook_snapshots
+            WHERE (market_id, saved_at) IN (
+                SELECT market_id, saved_at
+                FROM (
+                    SELECT
+                        market_id,
+                        saved_at,
+                        ROW_NUMBER() OVER (PARTITION BY market_id ORDER BY saved_at DESC) AS age
+                    FROM orderbook_snapshots
+                ) ranked
+                WHERE age > $1
+            )
+            "#,
+        )
+        .bind(keep.max(1) as i64)
+        .execute(&self.postgres)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Load the most recently saved orderbook for a market, if any
+    pub async fn load_latest_orderbook_snapshot(
+        &self,
+        market_id: &str,
+    ) -> Result<Option<PersistedOrderbook>> {
+        self.load_orderbook_snapshot_at(market_id, Utc::now()).await
+    }
+
+    /// Load the most recent orderbook for a market saved by `at`, if any
+    pub async fn load_orderbook_snapshot_at(
+        &self,
+        market_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<PersistedOrderbook>> {
+        let row = sqlx::query(
+            r#"
+            SELECT market_id, orders::text AS orders, request_sequence, saved_at
+            FROM orderbook_snapshots
+            WHERE market_id = $1 AND saved_at <= $2
+            ORDER BY saved_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(market_id)
+        .bind(at)
+        .fetch_optional(&self.postgres)
+        .await?;
+
+        row.map(|row| {
+            let orders: String = row.get("orders");
+            Ok(PersistedOrderbook {
+                market_id: row.get("market_id"),
+                orders: serde_json::from_str(&orders)?,
+                request_sequence: row.get("request_sequence"),
+                saved_at: row.get("saved_at"),
+            })
+        })
+        .transpose()
+    }
+
+    /// Reconstruct a market's orderbook as it stood at `at`
+    ///
+    /// Starts from the latest persisted snapshot saved by then (otherwise from an
+    /// empty book) and replays the request log up to `at` in memory.
+    /// Placements the engine rejected are skipped, since only accepted orders
+    /// were written to the orders table. Before any logged activity the book is empty
+    pub async fn orderbook_at(
+        &self,
+        market_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<OrderbookSnapshot> {
+        let market = self.get_market(market_id).await?;
+
+        let (mut orderbook, from_sequence) =
+            match self.load_orderbook_snapshot_at(market_id, at).await? {
+                Some(snapshot) => (
+                    Orderbook::from_orders(market.id.clone(), snapshot.orders),
+                    snapshot.request_sequence.map_or(0, |sequence| sequence + 1),
+                ),
+                None => (Orderbook::new(market.id.clone()), 0),
+            };
+
+        let entries = self.get_engine_requests_until(from_sequence, at).await?;
+
+        let placed_ids: Vec<_> = entries
+            .iter()
+            .filter_map(|entry| match &entry.request {
+                LoggedRequest::PlaceOrder { order, .. } => Some(order.id),
+                LoggedRequest::ReplaceOrder { new_order, .. } => Some(new_order.id),
+                _ => None,
+            })
+            .collect();
+        let accepted = self.existing_order_ids(&placed_ids).await?;
+
+        let place = |orderbook: &mut Orderbook, order: &Order, pre_open: bool| {
+            // Requests logged while the snapshot was taken may already be on the book
+            if orderbook.get_order(order.id).is_some() {
+                return;
+            }
+            // The log holds the price as submitted, the engine matched it snapped
+            let mut order = order.clone();
+            MatchingEngine::snap_to_tick(&mut order, &market);
+            let matches = if pre_open {
+                Vec::new()
+            } else {
+                Matcher::match_order(&order, orderbook)
+            };
+            // Short of min_fill the engine cancelled the order without executing any of it
+            let matchable: u128 = matches.iter().map(|m| m.size).sum();
+            if order.min_fill.is_some_and(|min_fill| matchable < min_fill) {
+                return;
+            }
+            orderbook.apply_matches(&order, &matches, &market);
+        };
+        // The phase before the first logged status change is taken to be the current one
+        let mut pre_open = market.status == MarketStatus::PreOpen
+            && !entries
+                .iter()
+                .any(|entry| matches!(&entry.request, LoggedRequest::SetMarketStatus { market_id, .. } if *market_id == market.id));
+
+        for entry in entries {
+            match entry.request {
+                LoggedRequest::PlaceOrder { order, .. } => {
+                    if order.market_id == market.id && accepted.contains(&order.id) {
+                        place(&mut orderbook, &order, pre_open);
+                    }
+                }
+                LoggedRequest::CancelOrder {
+                    order_id,
+                    user_address,
+                } => {
+                    if orderbook
+                        .get_order(order_id)
+                        .is_some_and(|order| order.user_address == user_address)
+                    {
+                        orderbook.remove_order(order_id);
+                    }
+                }
+                LoggedRequest::CancelAllOrders {
+                    user_address,
+                    market_id,
+                } => {
+                    if market_id.is_none_or(|id| id == market.id) {
+                        orderbook.remove_all_user_orders(&user_address);
+                    }
+                }
+                LoggedRequest::CancelEverything => {
+                    orderbook.remove_all_orders();
+                }
+                LoggedRequest::SetMarketStatus { market_id, status } => {
+                    if market_id == market.id {
+                        // Opening matches the collected orders again in time priority
+                        if pre_open && status == MarketStatus::Open {
+                            let mut orders = orderbook.remove_all_orders();
+                            orders.sort_by_key(|order| order.created_at);
+                            for order in &orders {
+                                place(&mut orderbook, order, false);
+                            }
+                        }
+                        pre_open = status == MarketStatus::PreOpen;
+                    }
+                }
+                LoggedRequest::ReplaceOrder {
+                    old_order_id,
+                    new_order,
+                    ..
+                } => {
+                    if new_order.market_id == market.id && accepted.contains(&new_order.id) {
+                        orderbook.remove_order(old_order_id);
+                        place(&mut orderbook, &new_order, pre_open);
+                    }
+                }
+            }
+        }
+
+        let mut snapshot = orderbook.snapshot();
+        snapshot.timestamp = at;
+        Ok(snapshot)
+    }
+}
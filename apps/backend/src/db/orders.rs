@@ -1,10 +1,12 @@
 use crate::db::Db;
 use crate::errors::Result;
-use crate::models::domain::{Order, OrderStatus, OrderType, Side};
+use crate::models::domain::{Order, OrderStatus, OrderType, Side, TimeInForce};
 use crate::utils::BigDecimalExt;
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
 use sqlx::Row;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 impl Db {
@@ -22,12 +24,13 @@ impl Db {
         let filled_size_str = order.filled_size.to_string();
         let side_str = order.side.to_string();
         let order_type_str = order.order_type.to_string();
+        let time_in_force_str = order.time_in_force.to_string();
         let status_str = order.status.to_string();
 
         sqlx::query(
             r#"
-            INSERT INTO orders (id, user_address, market_id, price, size, side, type, status, filled_size, created_at, updated_at)
-            VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::side, $7::order_type, $8::order_status, $9::numeric, $10, $11)
+            INSERT INTO orders (id, user_address, market_id, price, size, side, type, time_in_force, status, filled_size, created_at, updated_at)
+            VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::side, $7::order_type, $8::time_in_force, $9::order_status, $10::numeric, $11, $12)
             "#
         )
         .bind(order.id)
@@ -37,6 +40,7 @@ impl Db {
         .bind(size_str)
         .bind(side_str)
         .bind(order_type_str)
+        .bind(time_in_force_str)
         .bind(status_str)
         .bind(filled_size_str)
         .bind(order.created_at)
@@ -102,10 +106,25 @@ impl Db {
         Ok(())
     }
 
+    /// Which of the given order ids were persisted
+    /// Orders rejected by the engine are never written, so this tells them apart
+    pub async fn existing_order_ids(&self, order_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        if order_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let rows = sqlx::query("SELECT id FROM orders WHERE id = ANY($1)")
+            .bind(order_ids)
+            .fetch_all(&self.postgres)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("id")).collect())
+    }
+
     pub async fn get_order(&self, order_id: &Uuid) -> Result<Order> {
         let row = sqlx::query(
             r#"
-            SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, status::TEXT as status, filled_size, created_at, updated_at
+            SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
             FROM orders
             WHERE id = $1
             "#
@@ -119,6 +138,7 @@ impl Db {
         let filled_size: BigDecimal = row.get("filled_size");
         let side_str: String = row.get("side");
         let type_str: String = row.get("type");
+        let time_in_force_str: String = row.get("time_in_force");
         let status_str: String = row.get("status");
 
         Ok(Order {
@@ -129,6 +149,8 @@ impl Db {
             size: size.to_u128(),
             side: side_str.parse().unwrap_or(Side::Buy),
             order_type: type_str.parse().unwrap_or(OrderType::Limit),
+            time_in_force: time_in_force_str.parse().unwrap_or(TimeInForce::Gtc),
+            min_fill: None,
             status: status_str.parse().unwrap_or(OrderStatus::Pending),
             filled_size: filled_size.to_u128(),
             created_at: row.get("created_at"),
@@ -150,7 +172,7 @@ impl Db {
         let query = if let (Some(market), Some(stat)) = (market_id, &status_str) {
             sqlx::query(
                 r#"
-                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, status::TEXT as status, filled_size, created_at, updated_at
+                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
                 FROM orders
                 WHERE user_address = $1 AND market_id = $2 AND status = $3::order_status
                 ORDER BY created_at DESC
@@ -164,7 +186,7 @@ impl Db {
         } else if let Some(market) = market_id {
             sqlx::query(
                 r#"
-                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, status::TEXT as status, filled_size, created_at, updated_at
+                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
                 FROM orders
                 WHERE user_address = $1 AND market_id = $2
                 ORDER BY created_at DESC
@@ -177,7 +199,7 @@ impl Db {
         } else if let Some(stat) = &status_str {
             sqlx::query(
                 r#"
-                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, status::TEXT as status, filled_size, created_at, updated_at
+                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
                 FROM orders
                 WHERE user_address = $1 AND status = $2::order_status
                 ORDER BY created_at DESC
@@ -190,7 +212,7 @@ impl Db {
         } else {
             sqlx::query(
                 r#"
-                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, status::TEXT as status, filled_size, created_at, updated_at
+                SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
                 FROM orders
                 WHERE user_address = $1
                 ORDER BY created_at DESC
@@ -203,32 +225,69 @@ impl Db {
 
         let rows = query.fetch_all(&self.postgres).await?;
 
-        let orders = rows
-            .iter()
-            .map(|row| {
-                let price: BigDecimal = row.get("price");
-                let size: BigDecimal = row.get("size");
-                let filled_size: BigDecimal = row.get("filled_size");
-                let side_str: String = row.get("side");
-                let type_str: String = row.get("type");
-                let status_str: String = row.get("status");
-
-                Order {
-                    id: row.get("id"),
-                    user_address: row.get("user_address"),
-                    market_id: row.get("market_id"),
-                    price: price.to_u128(),
-                    size: size.to_u128(),
-                    side: side_str.parse().unwrap_or(Side::Buy),
-                    order_type: type_str.parse().unwrap_or(OrderType::Limit),
-                    status: status_str.parse().unwrap_or(OrderStatus::Pending),
-                    filled_size: filled_size.to_u128(),
-                    created_at: row.get("created_at"),
-                    updated_at: row.get("updated_at"),
-                }
-            })
-            .collect();
-
-        Ok(orders)
+        Ok(rows.iter().map(order_from_row).collect())
+    }
+
+    /// A user's finished (filled or cancelled) orders, most recently finished first
+    /// Bounds apply to `updated_at`, `from` inclusive and `to` exclusive
+    pub async fn get_order_history(
+        &self,
+        user_address: &str,
+        market_id: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> Result<Vec<Order>> {
+        let limit = std::cmp::min(limit, 1000); // Cap at 1000
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_address, market_id, price, size, side::TEXT as side, type::TEXT as type, time_in_force::TEXT as time_in_force, status::TEXT as status, filled_size, created_at, updated_at
+            FROM orders
+            WHERE user_address = $1
+              AND status IN ('filled', 'cancelled')
+              AND ($2::TEXT IS NULL OR market_id = $2)
+              AND ($3::TIMESTAMPTZ IS NULL OR updated_at >= $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR updated_at < $4)
+            ORDER BY updated_at DESC, id
+            LIMIT $5
+            "#,
+        )
+        .bind(user_address)
+        .bind(market_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit as i64)
+        .fetch_all(&self.postgres)
+        .await?;
+
+        Ok(rows.iter().map(order_from_row).collect())
+    }
+}
+
+/// Build an order from a row selected with the text-cast enum columns
+fn order_from_row(row: &PgRow) -> Order {
+    let price: BigDecimal = row.get("price");
+    let size: BigDecimal = row.get("size");
+    let filled_size: BigDecimal = row.get("filled_size");
+    let side_str: String = row.get("side");
+    let type_str: String = row.get("type");
+    let time_in_force_str: String = row.get("time_in_force");
+    let status_str: String = row.get("status");
+
+    Order {
+        id: row.get("id"),
+        user_address: row.get("user_address"),
+        market_id: row.get("market_id"),
+        price: price.to_u128(),
+        size: size.to_u128(),
+        side: side_str.parse().unwrap_or(Side::Buy),
+        order_type: type_str.parse().unwrap_or(OrderType::Limit),
+        time_in_force: time_in_force_str.parse().unwrap_or(TimeInForce::Gtc),
+        min_fill: None,
+        status: status_str.parse().unwrap_or(OrderStatus::Pending),
+        filled_size: filled_size.to_u128(),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
     }
 }
@@ -0,0 +1,3 @@
+CREATE TYPE time_in_force AS ENUM ('gtc', 'ioc');
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS time_in_force time_in_force NOT NULL DEFAULT 'gtc';
@@ -0,0 +1,4 @@
+-- Optional fixed price band per market, rejects fat-finger limit prices
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS min_price NUMERIC(39, 0) CHECK (min_price > 0); -- in quote token atoms (u128)
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS max_price NUMERIC(39, 0) CHECK (max_price > 0); -- in quote token atoms (u128)
+ALTER TABLE markets ADD CONSTRAINT markets_price_band_check CHECK (min_price IS NULL OR max_price IS NULL OR min_price <= max_price);
@@ -0,0 +1,8 @@
+-- Per-user fee overrides, take precedence over the market's default fees
+-- Rates are in tenths of a basis point (1 = 0.001%) so sub-bps tiers are expressible
+CREATE TABLE IF NOT EXISTS user_fee_tiers (
+    user_address TEXT PRIMARY KEY REFERENCES users(address),
+    maker_fee_tenth_bps INT NOT NULL CHECK (maker_fee_tenth_bps >= 0 AND maker_fee_tenth_bps <= 100000), -- tenths of a basis point (0-100%)
+    taker_fee_tenth_bps INT NOT NULL CHECK (taker_fee_tenth_bps >= 0 AND taker_fee_tenth_bps <= 100000), -- tenths of a basis point (0-100%)
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- Optional per-user caps on resting orders per market, NULL means unlimited
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS max_open_orders_per_user INTEGER CHECK (max_open_orders_per_user > 0);
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS max_open_notional_per_user NUMERIC(39, 0) CHECK (max_open_notional_per_user > 0); -- in quote token atoms (u128)
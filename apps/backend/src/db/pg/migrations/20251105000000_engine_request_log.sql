@@ -0,0 +1,7 @@
+-- Append-only log of state-changing engine requests, written before each is processed
+-- Replaying it in sequence order against a fresh engine reproduces the orderbook
+CREATE TABLE IF NOT EXISTS engine_request_log (
+    sequence BIGSERIAL PRIMARY KEY,
+    request JSONB NOT NULL, -- serialized LoggedRequest
+    logged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- Allow negative maker fees (rebates), paid out of the fee recipient's balance
+-- A rebate may not exceed the taker fee, so each market's fees net to zero or more
+ALTER TABLE markets DROP CONSTRAINT IF EXISTS markets_maker_fee_bps_check;
+ALTER TABLE markets ADD CONSTRAINT markets_maker_fee_bps_check CHECK (maker_fee_bps >= -10000 AND maker_fee_bps <= 10000); -- basis points (-100% to 100%)
+ALTER TABLE markets ADD CONSTRAINT markets_net_fee_check CHECK (maker_fee_bps + taker_fee_bps >= 0);
@@ -0,0 +1,8 @@
+-- Latest persisted orderbook per market, so recovery can load it and replay
+-- only the request log entries after request_sequence
+CREATE TABLE IF NOT EXISTS orderbook_snapshots (
+    market_id TEXT PRIMARY KEY REFERENCES markets(id),
+    orders JSONB NOT NULL, -- serialized resting orders in price-time priority
+    request_sequence BIGINT, -- last request log sequence applied, NULL if the log was empty
+    saved_at TIMESTAMPTZ NOT NULL
+);
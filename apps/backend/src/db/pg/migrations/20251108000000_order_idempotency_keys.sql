@@ -0,0 +1,14 @@
+-- Responses to orders placed with a client_order_id, so a retried placement
+-- returns the original order instead of creating another one
+-- Rows older than the configured TTL are ignored and purged
+CREATE TABLE IF NOT EXISTS order_idempotency_keys (
+    user_address TEXT NOT NULL,
+    client_order_id TEXT NOT NULL,
+    order_id UUID NOT NULL,
+    response JSONB NOT NULL, -- serialized OrderPlaced
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_address, client_order_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_idempotency_keys_created_at
+    ON order_idempotency_keys (created_at);
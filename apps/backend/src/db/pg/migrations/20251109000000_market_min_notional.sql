@@ -0,0 +1,2 @@
+-- Optional minimum order value per market, NULL means no minimum
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS min_notional NUMERIC(39, 0) CHECK (min_notional > 0); -- in quote token atoms (u128)
@@ -0,0 +1,7 @@
+-- Price of each market's most recent trade, NULL until the first trade
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS last_price NUMERIC(39, 0); -- in quote token atoms (u128)
+
+-- Backfill from existing trades
+UPDATE markets SET last_price = (
+    SELECT price FROM trades WHERE trades.market_id = markets.id ORDER BY timestamp DESC LIMIT 1
+);
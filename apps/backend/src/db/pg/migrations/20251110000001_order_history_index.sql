@@ -0,0 +1,2 @@
+-- Order history pages through a user's finished orders by when they finished
+CREATE INDEX IF NOT EXISTS idx_orders_user_updated_at ON orders(user_address, updated_at DESC);
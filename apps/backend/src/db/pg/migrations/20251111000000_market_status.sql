@@ -0,0 +1,4 @@
+-- Trading phase of each market, existing markets keep trading
+CREATE TYPE market_status AS ENUM ('pre_open', 'open', 'halted', 'closed');
+
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS status market_status NOT NULL DEFAULT 'open';
@@ -0,0 +1,13 @@
+-- Per-market execution order of trades, assigned by the engine
+-- Timestamps can tie within a sweep, the sequence never does
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS sequence BIGINT NOT NULL DEFAULT 0;
+
+-- Number existing trades in the order they were most likely executed
+UPDATE trades SET sequence = numbered.sequence
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY market_id ORDER BY timestamp, id) AS sequence
+    FROM trades
+) AS numbered
+WHERE trades.id = numbered.id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_market_sequence ON trades(market_id, sequence);
@@ -0,0 +1,2 @@
+-- Round off-tick limit prices to the nearest tick instead of rejecting them, off by default
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS snap_to_tick BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Post-only orders rest like gtc but are rejected if they would take liquidity
+ALTER TYPE time_in_force ADD VALUE IF NOT EXISTS 'post_only';
@@ -0,0 +1,9 @@
+-- Maker rebates the fee recipient couldn't cover when they were paid
+-- Repaid from the next fees it collects in the same token
+CREATE TABLE IF NOT EXISTS fee_liabilities (
+    fee_recipient TEXT NOT NULL,
+    token_ticker TEXT NOT NULL REFERENCES tokens(ticker),
+    amount NUMERIC(39, 0) NOT NULL CHECK (amount >= 0), -- in token atoms (u128)
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (fee_recipient, token_ticker)
+);
@@ -0,0 +1,5 @@
+-- Keep recent persisted orderbooks per market rather than only the latest, so a
+-- historical book replays the request log from a nearby snapshot. The engine prunes
+-- all but the newest engine.snapshot_history of each market
+ALTER TABLE orderbook_snapshots DROP CONSTRAINT IF EXISTS orderbook_snapshots_pkey;
+ALTER TABLE orderbook_snapshots ADD PRIMARY KEY (market_id, saved_at);
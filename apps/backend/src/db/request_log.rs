@@ -0,0 +1,75 @@
+use crate::db::Db;
+use crate::errors::Result;
+use crate::models::domain::{LoggedRequest, RequestLogEntry};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+
+impl Db {
+    /// Append an engine request to the request log
+    /// Returns the sequence number assigned to it
+    pub async fn append_engine_request(&self, request: &LoggedRequest) -> Result<i64> {
+        let payload = serde_json::to_string(request)?;
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO engine_request_log (request)
+            VALUES ($1::text::jsonb)
+            RETURNING sequence
+            "#,
+        )
+        .bind(payload)
+        .fetch_one(&self.postgres)
+        .await?;
+
+        Ok(row.get("sequence"))
+    }
+
+    /// Get logged engine requests with sequence >= `from_sequence` logged up to `until`, oldest first
+    pub async fn get_engine_requests_until(
+        &self,
+        from_sequence: i64,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<RequestLogEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sequence, request::text AS request, logged_at
+            FROM engine_request_log
+            WHERE sequence >= $1 AND logged_at <= $2
+            ORDER BY sequence
+            "#,
+        )
+        .bind(from_sequence)
+        .bind(until)
+        .fetch_all(&self.postgres)
+        .await?;
+
+        rows.into_iter().map(Self::request_log_entry).collect()
+    }
+
+    /// Get logged engine requests with sequence >= `from_sequence`, oldest first
+    pub async fn get_engine_requests(&self, from_sequence: i64) -> Result<Vec<RequestLogEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sequence, request::text AS request, logged_at
+            FROM engine_request_log
+            WHERE sequence >= $1
+            ORDER BY sequence
+            "#,
+        )
+        .bind(from_sequence)
+        .fetch_all(&self.postgres)
+        .await?;
+
+        rows.into_iter().map(Self::request_log_entry).collect()
+    }
+
+    fn request_log_entry(row: PgRow) -> Result<RequestLogEntry> {
+        let request: String = row.get("request");
+        Ok(RequestLogEntry {
+            sequence: row.get("sequence"),
+            request: serde_json::from_str(&request)?,
+            logged_at: row.get("logged_at"),
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::db::Db;
+use crate::errors::Result;
+use crate::models::{
+    db::{MarketTradeVolumeRow, TradeVolumeRow},
+    domain::TradeVolume,
+};
+
+impl Db {
+    /// Sum trade count, base volume and notional for a market over [from, to)
+    /// Timestamps are Unix seconds. FINAL so a trade inserted twice counts once
+    pub async fn get_trade_volume(
+        &self,
+        market_id: &str,
+        from: i64,
+        to: i64,
+    ) -> Result<TradeVolume> {
+        // Widen to UInt256 before multiplying so price * size can't overflow
+        let row = self
+            .clickhouse
+            .query(
+                "SELECT
+                count() as trade_count,
+                toString(sum(toUInt256(size))) as base_volume,
+                toString(sum(toUInt256(price) * toUInt256(size))) as notional
+            FROM trades FINAL
+            WHERE market_id = ? AND timestamp >= toDateTime(?) AND timestamp < toDateTime(?)",
+            )
+            .bind(market_id)
+            .bind(from)
+            .bind(to)
+            .fetch_one::<TradeVolumeRow>()
+            .await?;
+
+        Ok(TradeVolume {
+            trade_count: row.trade_count,
+            base_volume: row.base_volume.parse()?,
+            notional: row.notional.parse()?,
+        })
+    }
+
+    /// Volume-weighted average price for a market over [from, to)
+    /// Returns None when no trades happened in the window
+    pub async fn get_vwap(&self, market_id: &str, from: i64, to: i64) -> Result<Option<u128>> {
+        Ok(self.get_trade_volume(market_id, from, to).await?.vwap())
+    }
+
+    /// Trade volume of every market that traded over [from, to), keyed by market id
+    pub async fn get_trade_volumes(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Result<HashMap<String, TradeVolume>> {
+        let rows = self
+            .clickhouse
+            .query(
+                "SELECT
+                market_id,
+                count() as trade_count,
+                toString(sum(toUInt256(size))) as base_volume,
+                toString(sum(toUInt256(price) * toUInt256(size))) as notional
+            FROM trades FINAL
+            WHERE timestamp >= toDateTime(?) AND timestamp < toDateTime(?)
+            GROUP BY market_id",
+            )
+            .bind(from)
+            .bind(to)
+            .fetch_all::<MarketTradeVolumeRow>()
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok((
+                    row.market_id,
+                    TradeVolume {
+                        trade_count: row.trade_count,
+                        base_volume: row.base_volume.parse()?,
+                        notional: row.notional.parse()?,
+                    },
+                ))
+            })
+            .collect()
+    }
+}
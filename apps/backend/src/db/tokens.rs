@@ -44,4 +44,17 @@ impl Db {
 
         Ok(rows.into_iter().map(|row| row.into()).collect())
     }
+
+    /// List the tokens with the given tickers, skipping tickers that don't exist
+    pub async fn get_tokens_by_tickers(&self, tickers: &[String]) -> Result<Vec<Token>> {
+        let rows = sqlx::query_as!(
+            TokenRow,
+            "SELECT ticker, decimals, name FROM tokens WHERE ticker = ANY($1) ORDER BY ticker",
+            tickers
+        )
+        .fetch_all(&self.postgres)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.into()).collect())
+    }
 }
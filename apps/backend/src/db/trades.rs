@@ -1,7 +1,8 @@
 use crate::db::Db;
 use crate::errors::Result;
-use crate::models::domain::Trade;
+use crate::models::domain::{Position, Trade};
 use sqlx::Row;
+use std::collections::BTreeMap;
 
 impl Db {
     /// Insert a new trade into the database
@@ -15,8 +16,8 @@ impl Db {
 
         sqlx::query(
             r#"
-            INSERT INTO trades (id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp)
-            VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9::side, $10)
+            INSERT INTO trades (id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp, sequence)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9::side, $10, $11)
             "#
         )
         .bind(trade.id)
@@ -29,12 +30,29 @@ impl Db {
         .bind(size_str)
         .bind(side_str)
         .bind(trade.timestamp)
+        .bind(trade.sequence as i64)
         .execute(&self.postgres)
         .await?;
 
         Ok(())
     }
 
+    /// Sequence of the market's latest trade, 0 before its first (within a transaction)
+    pub async fn last_trade_sequence_tx(
+        &self,
+        tx: &mut crate::db::Transaction<'_, crate::db::Postgres>,
+        market_id: &str,
+    ) -> Result<u64> {
+        let sequence: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(sequence), 0) FROM trades WHERE market_id = $1",
+        )
+        .bind(market_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(sequence as u64)
+    }
+
     /// Insert a new trade into the database (within a transaction)
     pub async fn create_trade_tx(
         &self,
@@ -50,8 +68,8 @@ impl Db {
 
         sqlx::query(
             r#"
-            INSERT INTO trades (id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp)
-            VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9::side, $10)
+            INSERT INTO trades (id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price, size, side, timestamp, sequence)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::numeric, $8::numeric, $9::side, $10, $11)
             "#
         )
         .bind(trade.id)
@@ -64,6 +82,7 @@ impl Db {
         .bind(size_str)
         .bind(side_str)
         .bind(trade.timestamp)
+        .bind(trade.sequence as i64)
         .execute(&mut **tx)
         .await?;
 
@@ -81,10 +100,10 @@ impl Db {
         let query = if let Some(market) = market_id {
             sqlx::query(
                 r#"
-                SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp
+                SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp, sequence
                 FROM trades
                 WHERE (buyer_address = $1 OR seller_address = $1) AND market_id = $2
-                ORDER BY timestamp DESC
+                ORDER BY timestamp DESC, sequence DESC
                 LIMIT $3
                 "#
             )
@@ -94,10 +113,10 @@ impl Db {
         } else {
             sqlx::query(
                 r#"
-                SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp
+                SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp, sequence
                 FROM trades
                 WHERE buyer_address = $1 OR seller_address = $1
-                ORDER BY timestamp DESC
+                ORDER BY timestamp DESC, sequence DESC
                 LIMIT $2
                 "#
             )
@@ -129,6 +148,7 @@ impl Db {
                         crate::models::domain::Side::Sell
                     },
                     timestamp: row.get("timestamp"),
+                    sequence: row.get::<i64, _>("sequence") as u64,
                 }
             })
             .collect();
@@ -141,10 +161,10 @@ impl Db {
 
         let rows = sqlx::query(
             r#"
-            SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp
+            SELECT id, market_id, buyer_address, seller_address, buyer_order_id, seller_order_id, price::TEXT as price, size::TEXT as size, side::TEXT as side, timestamp, sequence
             FROM trades
             WHERE market_id = $1
-            ORDER BY timestamp DESC
+            ORDER BY timestamp DESC, sequence DESC
             LIMIT $2
             "#
         )
@@ -175,10 +195,81 @@ impl Db {
                         crate::models::domain::Side::Sell
                     },
                     timestamp: row.get("timestamp"),
+                    sequence: row.get::<i64, _>("sequence") as u64,
                 }
             })
             .collect();
 
         Ok(trades)
     }
+
+    /// Net position and average entry per market, replayed from the user's fills
+    /// Markets the user has traded back to flat are included with size 0
+    pub async fn get_user_positions(&self, user_address: &str) -> Result<Vec<Position>> {
+        // Average cost depends on fill order, so replay fills oldest first
+        let rows = sqlx::query(
+            r#"
+            SELECT market_id, price::TEXT as price, size::TEXT as size, buyer_address = $1 as is_buy
+            FROM trades
+            WHERE (buyer_address = $1 OR seller_address = $1) AND buyer_address <> seller_address
+            ORDER BY timestamp ASC, sequence ASC
+            "#,
+        )
+        .bind(user_address)
+        .fetch_all(&self.postgres)
+        .await?;
+
+        let mut books: BTreeMap<String, PositionBook> = BTreeMap::new();
+        for row in &rows {
+            let price_str: String = row.get("price");
+            let size_str: String = row.get("size");
+            let is_buy: bool = row.get("is_buy");
+
+            books.entry(row.get("market_id")).or_default().apply_fill(
+                is_buy,
+                price_str.parse().unwrap_or(0),
+                size_str.parse().unwrap_or(0),
+            );
+        }
+
+        Ok(books
+            .into_iter()
+            .map(|(market_id, book)| Position {
+                user_address: user_address.to_string(),
+                market_id,
+                size: book.size,
+                avg_entry_price: book.entry_notional.checked_div(book.size.unsigned_abs()),
+            })
+            .collect())
+    }
+}
+
+/// Running position in one market while replaying fills
+#[derive(Default)]
+struct PositionBook {
+    size: i128,
+    entry_notional: u128, // price * size of the open position, not yet scaled by base decimals
+}
+
+impl PositionBook {
+    fn apply_fill(&mut self, is_buy: bool, price: u128, size: u128) {
+        let open = self.size.unsigned_abs();
+        let adds = self.size == 0 || (self.size > 0) == is_buy;
+
+        if adds {
+            self.entry_notional += price * size;
+        } else if size <= open {
+            // Reducing keeps the average entry of what remains open
+            self.entry_notional -= self.entry_notional * size / open;
+        } else {
+            // Flipped through flat: the remainder opens at this fill's price
+            self.entry_notional = price * (size - open);
+        }
+
+        if is_buy {
+            self.size += size as i128;
+        } else {
+            self.size -= size as i128;
+        }
+    }
 }
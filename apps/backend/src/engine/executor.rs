@@ -2,9 +2,11 @@
 
 use crate::db::Db;
 use crate::errors::Result;
-use crate::models::domain::{Market, Match, Order, OrderStatus, Side, Trade};
+use crate::models::domain::{
+    FeeSchedule, Market, Match, MatchPricePolicy, Order, OrderStatus, OrderType, Side, Trade,
+};
 use chrono::Utc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 pub struct Executor;
@@ -12,34 +14,249 @@ pub struct Executor;
 /// Tracks affected balances that need to be broadcast after request completes
 pub type AffectedBalances = HashSet<(String, String)>; // (user_address, token_ticker)
 
+/// Namespace for deriving trade ids (UUIDv5)
+/// Changing this value changes every derived trade id, so it must stay fixed
+pub const TRADE_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c_2a7e_5b3d_4e8f_9a10_c4d2_e6b8_f173);
+
+/// Deliberate settlement bugs for tests, proving the balance invariant check catches them
+#[cfg(feature = "fault-injection")]
+pub mod fault_injection {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    static BUYER_BASE_SKEW: AtomicU64 = AtomicU64::new(0);
+    static SETTLE_DELAY: Mutex<Option<(String, Duration)>> = Mutex::new(None);
+
+    /// Credit buyers this many extra base atoms on every fill, 0 turns the fault off
+    pub fn set_buyer_base_skew(atoms: u64) {
+        BUYER_BASE_SKEW.store(atoms, Ordering::Relaxed);
+    }
+
+    pub(super) fn buyer_base_skew() -> u128 {
+        BUYER_BASE_SKEW.load(Ordering::Relaxed) as u128
+    }
+
+    /// Stall settlement of every fill in `market_id` by `delay`, None turns the fault off
+    pub fn set_settle_delay(fault: Option<(&str, Duration)>) {
+        *SETTLE_DELAY.lock().unwrap() =
+            fault.map(|(market_id, delay)| (market_id.to_string(), delay));
+    }
+
+    pub(super) fn settle_delay(market_id: &str) -> Option<Duration> {
+        SETTLE_DELAY
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|(faulty, _)| faulty == market_id)
+            .map(|(_, delay)| *delay)
+    }
+}
+
 impl Executor {
+    /// Derive a deterministic trade id for a match
+    /// Replaying the same matching sequence always yields the same ids,
+    /// which keeps recovery/rebuild idempotent
+    pub fn trade_id(buyer_order_id: Uuid, seller_order_id: Uuid, match_index: u32) -> Uuid {
+        let mut name = Vec::with_capacity(36);
+        name.extend_from_slice(buyer_order_id.as_bytes());
+        name.extend_from_slice(seller_order_id.as_bytes());
+        name.extend_from_slice(&match_index.to_be_bytes());
+        Uuid::new_v5(&TRADE_ID_NAMESPACE, &name)
+    }
+
+    /// Price a match executes at under the given policy
+    /// Midpoint applies only when the taker's limit is strictly more aggressive than the maker price,
+    /// rounded to the tick in the taker's favour so it never crosses the maker price
+    pub fn execution_price(
+        policy: MatchPricePolicy,
+        taker_order: &Order,
+        maker_price: u128,
+        tick_size: u128,
+    ) -> u128 {
+        if policy == MatchPricePolicy::Maker || taker_order.order_type == OrderType::Market {
+            return maker_price;
+        }
+
+        match taker_order.side {
+            Side::Buy if taker_order.price > maker_price => {
+                let midpoint = maker_price + (taker_order.price - maker_price) / 2;
+                midpoint - midpoint % tick_size
+            }
+            Side::Sell if taker_order.price < maker_price => {
+                let midpoint = maker_price - (maker_price - taker_order.price) / 2;
+                midpoint.div_ceil(tick_size) * tick_size
+            }
+            _ => maker_price,
+        }
+    }
+
+    /// Quote still locked for a buy order once `filled` of it has executed
+    /// (price * remaining) / 10^base_decimals, the same rounding as the lock on placement.
+    /// Fills release the difference between consecutive remainders, so the rounding
+    /// never strands dust in open_interest
+    fn quote_locked_for_remaining(order: &Order, filled: u128, divisor: u128) -> Option<u128> {
+        order
+            .price
+            .checked_mul(order.size.saturating_sub(filled))
+            .and_then(|v| v.checked_div(divisor))
+    }
+
+    /// What a party receives from `amount` after a fee (or rebate, when negative)
+    fn net_of_fee(amount: u128, fee: i128) -> Result<u128> {
+        u128::try_from(amount as i128 - fee).map_err(|_| {
+            crate::errors::ExchangeError::InvalidParameter {
+                message: "Fee exceeds trade amount".to_string(),
+            }
+        })
+    }
+
+    /// Move a fee to the fee recipient, or pay a rebate out of its balance
+    /// A rebate the balance can't cover becomes a fee liability, repaid from later fees
+    async fn settle_fee(
+        db: &Db,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fee_recipient: &str,
+        token_ticker: &str,
+        fee: i128,
+    ) -> Result<()> {
+        if fee > 0 {
+            db.collect_fee_tx(tx, fee_recipient, token_ticker, fee as u128)
+                .await?;
+        } else if fee < 0 {
+            db.pay_fee_rebate_tx(tx, fee_recipient, token_ticker, fee.unsigned_abs())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Net base and quote held by a fill's parties, for the conservation check
+    /// Rebates the fee recipient owes count against what it holds. The rows must already
+    /// be locked, see `Db::lock_balances_tx` and `Db::lock_fee_liabilities_tx`
+    async fn party_totals(
+        db: &Db,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        parties: &[String],
+        fee_recipient: &str,
+        market: &Market,
+    ) -> Result<(i128, i128)> {
+        let mut totals = [0i128; 2];
+        for (total, token_ticker) in totals
+            .iter_mut()
+            .zip([&market.base_ticker, &market.quote_ticker])
+        {
+            let held = db.total_balance_tx(tx, parties, token_ticker).await?;
+            let owed = db.fee_liability_tx(tx, fee_recipient, token_ticker).await?;
+            *total = held as i128 - owed as i128;
+        }
+        Ok((totals[0], totals[1]))
+    }
+
+    /// Fail a fill that created or destroyed tokens between its parties
+    fn check_conservation(
+        trade: &Trade,
+        market: &Market,
+        before: (i128, i128),
+        after: (i128, i128),
+    ) -> Result<()> {
+        for (token_ticker, before, after) in [
+            (&market.base_ticker, before.0, after.0),
+            (&market.quote_ticker, before.1, after.1),
+        ] {
+            if before != after {
+                tracing::error!(
+                    trade_id = %trade.id,
+                    buyer = %trade.buyer_address,
+                    seller = %trade.seller_address,
+                    price = trade.price,
+                    size = trade.size,
+                    token = %token_ticker,
+                    before,
+                    after,
+                    "Balance invariant violated"
+                );
+                return Err(crate::errors::ExchangeError::BalanceInvariantViolated {
+                    trade_id: trade.id,
+                    token_ticker: token_ticker.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Execute a vector of matches
     /// - Creates trade records
     /// - Updates order fill status
-    /// - Calculates and applies fees
+    /// - Calculates and applies fees (per-user fee tier or market defaults)
     /// - Unlocks and transfers balances
     /// - Persists everything to database atomically
+    /// - With `check_invariants`, verifies each fill conserves base and quote across
+    ///   buyer, seller and fee recipient, failing (and rolling back) if it doesn't
     /// - Returns the executed trades and affected balances
+    #[tracing::instrument(
+        name = "execute",
+        skip_all,
+        fields(taker_order_id = %taker_order.id, matches = matches.len())
+    )]
     pub async fn execute(
         db: Db,
         matches: Vec<Match>,
         taker_order: &Order,
         market: &Market,
+        price_policy: MatchPricePolicy,
+        fee_recipient: &str,
+        check_invariants: bool,
     ) -> Result<(Vec<Trade>, AffectedBalances)> {
         if matches.is_empty() {
             return Ok((vec![], HashSet::new()));
         }
 
+        #[cfg(feature = "fault-injection")]
+        if let Some(delay) = fault_injection::settle_delay(&market.id) {
+            tokio::time::sleep(delay).await;
+        }
+
         // Get base token decimals for proper quote amount calculation
         let base_token = db.get_token(&market.base_ticker).await?;
         let base_decimals_divisor = 10u128.pow(base_token.decimals as u32);
 
+        // Resolve fee schedules up front (reads outside the transaction)
+        // Makers are resolved lazily and cached, a sweep often hits the same user repeatedly
+        let taker_fees = db
+            .get_fee_schedule(&taker_order.user_address, market)
+            .await?;
+        let mut fee_schedules: HashMap<String, FeeSchedule> = HashMap::new();
+
         // Begin transaction for atomic execution
         let mut tx = db.begin_transaction().await?;
         let mut trades = Vec::new();
+        // Trades are numbered in execution order, so same-timestamp fills keep their order
+        let last_sequence = db
+            .last_trade_sequence_tx(&mut tx, &taker_order.market_id)
+            .await?;
+        let mut taker_filled = taker_order.filled_size;
+
+        // The conservation check reads the parties' totals before and after each fill, so
+        // lock every row a fill can move up front. Otherwise a concurrent fill sharing a
+        // party (always the fee recipient) could land between the reads
+        if check_invariants {
+            let mut users: Vec<String> = matches
+                .iter()
+                .map(|m| m.maker_order.user_address.clone())
+                .chain([taker_order.user_address.clone(), fee_recipient.to_string()])
+                .collect();
+            users.sort();
+            users.dedup();
+            let token_tickers = [market.base_ticker.clone(), market.quote_ticker.clone()];
+            db.lock_balances_tx(&mut tx, &users, &token_tickers).await?;
+            db.lock_fee_liabilities_tx(&mut tx, fee_recipient, &token_tickers)
+                .await?;
+        }
 
         // Process each match within transaction
-        for m in &matches {
+        for (match_index, m) in matches.iter().enumerate() {
             let maker_order = &m.maker_order;
 
             // Determine buyer and seller based on sides
@@ -59,56 +276,106 @@ impl Executor {
                     ),
                 };
 
+            // Price the fill executes at (maker price unless the policy improves it)
+            let price = Self::execution_price(price_policy, taker_order, m.price, market.tick_size);
+
             // Create trade record
             let trade = Trade {
-                id: Uuid::new_v4(),
+                id: Self::trade_id(buyer_order_id, seller_order_id, match_index as u32),
                 market_id: taker_order.market_id.clone(),
                 buyer_address: buyer_address.clone(),
                 seller_address: seller_address.clone(),
                 buyer_order_id,
                 seller_order_id,
-                price: m.price,
+                price,
                 size: m.size,
                 side: taker_order.side, // Trade side is the taker's side
                 timestamp: Utc::now(),
+                sequence: last_sequence + match_index as u64 + 1,
+            };
+
+            // Balances of everyone this fill moves tokens between, before it's applied
+            let parties: Vec<String> = [
+                buyer_address.as_str(),
+                seller_address.as_str(),
+                fee_recipient,
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+            let totals_before = if check_invariants {
+                Some(Self::party_totals(&db, &mut tx, &parties, fee_recipient, market).await?)
+            } else {
+                None
             };
 
             // Calculate trade value in quote tokens
             // quote_amount = (price_atoms * size_atoms) / 10^base_decimals
-            let quote_amount = m
-                .price
+            let quote_amount = price
                 .checked_mul(m.size)
                 .and_then(|v| v.checked_div(base_decimals_divisor))
                 .ok_or_else(|| crate::errors::ExchangeError::InvalidParameter {
                     message: "Trade value overflow or calculation error".to_string(),
                 })?;
 
+            // Resolve the maker's fee schedule (per-user tier or market default)
+            let maker_fees = match fee_schedules.get(&maker_order.user_address) {
+                Some(fees) => *fees,
+                None => {
+                    let fees = db
+                        .get_fee_schedule(&maker_order.user_address, market)
+                        .await?;
+                    fee_schedules.insert(maker_order.user_address.clone(), fees);
+                    fees
+                }
+            };
+
             // Calculate fees (charged on what each party receives)
             // Buyer receives base tokens (size), pays taker fee if taker, maker fee if maker
             // Seller receives quote tokens (price * size), pays maker fee if maker, taker fee if taker
-            let (buyer_fee_bps, seller_fee_bps) = match taker_order.side {
+            // A negative fee is a rebate: added to what the party receives and paid by the fee recipient
+            let (buyer_fee, seller_fee) = match taker_order.side {
                 Side::Buy => {
                     // Buyer is taker, seller is maker
-                    (market.taker_fee_bps, market.maker_fee_bps)
+                    (
+                        taker_fees.fee(m.size, false),
+                        maker_fees.fee(quote_amount, true),
+                    )
                 }
                 Side::Sell => {
                     // Seller is taker, buyer is maker
-                    (market.maker_fee_bps, market.taker_fee_bps)
+                    (
+                        maker_fees.fee(m.size, true),
+                        taker_fees.fee(quote_amount, false),
+                    )
                 }
             };
 
-            // Fee on base tokens (for buyer)
-            let buyer_fee = (m.size as i128 * buyer_fee_bps as i128 / 10000) as u128;
-            // Fee on quote tokens (for seller)
-            let seller_fee = (quote_amount as i128 * seller_fee_bps as i128 / 10000) as u128;
-
-            // Fee recipient address (hardcoded in db schema)
-            const FEE_RECIPIENT: &str = "system";
-
             // Calculate amounts to unlock (what was locked when orders were placed)
-            // Buyer locked quote_amount, seller locked size
-            let buyer_unlock_amount = quote_amount;
+            // Buyer locked at their own order price, so any price improvement is
+            // released back to available balance; seller locked size
+            let (buyer_order, buyer_filled_before) = match taker_order.side {
+                Side::Buy => (taker_order, taker_filled),
+                Side::Sell => (maker_order, maker_order.filled_size),
+            };
+            let buyer_unlock_amount = Self::quote_locked_for_remaining(
+                buyer_order,
+                buyer_filled_before,
+                base_decimals_divisor,
+            )
+            .zip(Self::quote_locked_for_remaining(
+                buyer_order,
+                buyer_filled_before + m.size,
+                base_decimals_divisor,
+            ))
+            .map(|(before, after)| before - after)
+            .ok_or_else(|| crate::errors::ExchangeError::InvalidParameter {
+                message: "Unlock amount overflow or calculation error".to_string(),
+            })?;
             let seller_unlock_amount = m.size;
+            taker_filled += m.size;
 
             // Unlock the locked amounts for both parties
             db.unlock_balance_tx(
@@ -129,7 +396,9 @@ impl Executor {
             // Transfer base tokens: seller -> buyer (minus buyer's fee)
             db.subtract_balance_tx(&mut tx, &seller_address, &market.base_ticker, m.size)
                 .await?;
-            let buyer_receives_base = m.size - buyer_fee;
+            let buyer_receives_base = Self::net_of_fee(m.size, buyer_fee)?;
+            #[cfg(feature = "fault-injection")]
+            let buyer_receives_base = buyer_receives_base + fault_injection::buyer_base_skew();
             db.add_balance_tx(
                 &mut tx,
                 &buyer_address,
@@ -138,16 +407,13 @@ impl Executor {
             )
             .await?;
 
-            // Send buyer's fee to fee recipient (base tokens)
-            if buyer_fee > 0 {
-                db.add_balance_tx(&mut tx, FEE_RECIPIENT, &market.base_ticker, buyer_fee)
-                    .await?;
-            }
+            // Settle buyer's fee with fee recipient (base tokens)
+            Self::settle_fee(&db, &mut tx, fee_recipient, &market.base_ticker, buyer_fee).await?;
 
             // Transfer quote tokens: buyer -> seller (minus seller's fee)
             db.subtract_balance_tx(&mut tx, &buyer_address, &market.quote_ticker, quote_amount)
                 .await?;
-            let seller_receives_quote = quote_amount - seller_fee;
+            let seller_receives_quote = Self::net_of_fee(quote_amount, seller_fee)?;
             db.add_balance_tx(
                 &mut tx,
                 &seller_address,
@@ -156,11 +422,15 @@ impl Executor {
             )
             .await?;
 
-            // Send seller's fee to fee recipient (quote tokens)
-            if seller_fee > 0 {
-                db.add_balance_tx(&mut tx, FEE_RECIPIENT, &market.quote_ticker, seller_fee)
-                    .await?;
-            }
+            // Settle seller's fee with fee recipient (quote tokens)
+            Self::settle_fee(
+                &db,
+                &mut tx,
+                fee_recipient,
+                &market.quote_ticker,
+                seller_fee,
+            )
+            .await?;
 
             // Update maker order fill status (in transaction)
             let maker_new_filled = maker_order.filled_size + m.size;
@@ -175,6 +445,12 @@ impl Executor {
             // Insert trade into PostgreSQL (in transaction)
             db.create_trade_tx(&mut tx, &trade).await?;
 
+            if let Some(before) = totals_before {
+                let after =
+                    Self::party_totals(&db, &mut tx, &parties, fee_recipient, market).await?;
+                Self::check_conservation(&trade, market, before, after)?;
+            }
+
             trades.push(trade);
         }
 
@@ -191,8 +467,15 @@ impl Executor {
         db.update_order_fill_tx(&mut tx, taker_order.id, taker_new_filled, taker_status)
             .await?;
 
+        // Last traded price shown with the market details (in transaction)
+        if let Some(last_trade) = trades.last() {
+            db.set_market_last_price_tx(&mut tx, &market.id, last_trade.price)
+                .await?;
+        }
+
         // Commit transaction - all or nothing!
         tx.commit().await?;
+        tracing::debug!(trades = trades.len(), "Executed matches");
 
         // Collect affected balances (to be broadcast by engine after request completes)
         let mut affected_balances = HashSet::new();
@@ -205,9 +488,9 @@ impl Executor {
             affected_balances.insert((trade.seller_address.clone(), market.base_ticker.clone()));
             affected_balances.insert((trade.seller_address.clone(), market.quote_ticker.clone()));
 
-            // System fee recipient balances (base and quote tokens)
-            affected_balances.insert(("system".to_string(), market.base_ticker.clone()));
-            affected_balances.insert(("system".to_string(), market.quote_ticker.clone()));
+            // Fee recipient balances (base and quote tokens)
+            affected_balances.insert((fee_recipient.to_string(), market.base_ticker.clone()));
+            affected_balances.insert((fee_recipient.to_string(), market.quote_ticker.clone()));
         }
 
         // Insert trades into ClickHouse asynchronously (after commit)
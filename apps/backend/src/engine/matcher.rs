@@ -1,7 +1,7 @@
 // matches orders using price-time priority
 
 use crate::engine::orderbook::Orderbook;
-use crate::models::domain::{Match, Order, OrderType, Side};
+use crate::models::domain::{FillEstimate, Match, Order, OrderType, Side};
 
 pub struct Matcher;
 
@@ -59,6 +59,52 @@ impl Matcher {
         matches
     }
 
+    /// Resting orders the taker crosses at their price, best price first
+    /// Unlike `match_order` this includes the taker's own orders and ignores its size
+    pub fn crossing_orders<'a>(
+        taker_order: &'a Order,
+        orderbook: &'a Orderbook,
+    ) -> impl Iterator<Item = &'a Order> + 'a {
+        let level_iter: Box<dyn Iterator<Item = (&u128, &_)>> = match taker_order.side {
+            Side::Buy => Box::new(orderbook.asks.iter()),
+            Side::Sell => Box::new(orderbook.bids.iter().rev()),
+        };
+
+        level_iter
+            .take_while(move |(price, _)| Self::can_match_price(taker_order, **price))
+            .flat_map(|(_, orders)| orders.iter())
+    }
+
+    /// Estimate what an order would do against the orderbook without placing it
+    /// Uses the same matching rules as `match_order` (including self-trade skipping)
+    pub fn estimate_fill(order: &Order, orderbook: &Orderbook) -> FillEstimate {
+        let matches = Self::match_order(order, orderbook);
+
+        let fillable_size: u128 = matches.iter().map(|m| m.size).sum();
+        let notional: u128 = matches.iter().map(|m| m.price * m.size).sum();
+        let average_price = (fillable_size > 0).then(|| notional / fillable_size);
+
+        // Matches are produced level by level, so count price changes
+        let mut levels_consumed = 0;
+        let mut last_price = None;
+        for m in &matches {
+            if last_price != Some(m.price) {
+                levels_consumed += 1;
+                last_price = Some(m.price);
+            }
+        }
+
+        let remaining = order.size - order.filled_size - fillable_size;
+        let would_rest = remaining > 0 && !order.is_immediate_or_cancel();
+
+        FillEstimate {
+            fillable_size,
+            average_price,
+            levels_consumed,
+            would_rest,
+        }
+    }
+
     /// Check if a taker order can match at the given maker price
     fn can_match_price(taker: &Order, maker_price: u128) -> bool {
         match (taker.side, taker.order_type) {
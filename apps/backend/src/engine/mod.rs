@@ -4,53 +4,177 @@
 pub mod executor;
 pub mod matcher;
 pub mod orderbook;
+pub mod queue;
+pub mod rate_limit;
+pub mod workers;
 
+use crate::config::EngineConfig;
 use crate::db::Db;
 use crate::errors::ExchangeError;
-use crate::models::api::{OrderCancelled, OrderPlaced, OrdersCancelled};
-use crate::models::domain::{EngineEvent, EngineRequest, OrderStatus};
+use crate::models::api::{
+    EstimateResponse, OrderCancelled, OrderPlaced, OrderRefund, OrderReplaced, OrdersCancelled,
+};
+use crate::models::domain::{
+    EngineEvent, EngineRequest, FillEstimate, LoggedRequest, Market, MarketStatus, OrderStatus,
+    OrderbookSnapshot, TimeInForce,
+};
 use executor::{AffectedBalances, Executor};
 use matcher::Matcher;
-use orderbook::Orderbooks;
+use orderbook::{Orderbook, Orderbooks};
+use rate_limit::CancelLimiter;
+use workers::AppliedSequence;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+/// Seconds between purges of expired client_order_id entries
+const IDEMPOTENCY_PURGE_INTERVAL_SECS: u64 = 3600;
 
 pub struct MatchingEngine {
     db: Db,
+    config: EngineConfig,
     orderbooks: Arc<RwLock<Orderbooks>>,
+    applied_sequence: Arc<AppliedSequence>,
+    cancel_limiter: CancelLimiter,
 
-    engine_rx: mpsc::Receiver<EngineRequest>,
+    // Taken by `run`, market workers are fed by the dispatcher instead
+    engine_rx: Option<mpsc::Receiver<EngineRequest>>,
     event_tx: broadcast::Sender<EngineEvent>,
 }
 
+/// A request that passed throttling and the request log, ready to be applied
+struct Admitted {
+    request: EngineRequest,
+    sequence: Option<i64>,
+    started: Instant,
+}
+
 impl MatchingEngine {
     pub fn new(
         db: Db,
         engine_rx: mpsc::Receiver<EngineRequest>,
         event_tx: broadcast::Sender<EngineEvent>,
+    ) -> Self {
+        Self::with_config(db, engine_rx, event_tx, EngineConfig::default())
+    }
+
+    pub fn with_config(
+        db: Db,
+        engine_rx: mpsc::Receiver<EngineRequest>,
+        event_tx: broadcast::Sender<EngineEvent>,
+        config: EngineConfig,
     ) -> Self {
         Self {
             db: db.clone(),
+            cancel_limiter: CancelLimiter::new(config.max_cancels_per_sec),
+            config,
             orderbooks: Arc::new(RwLock::new(Orderbooks::new())),
-            engine_rx,
+            applied_sequence: Arc::new(AppliedSequence::default()),
+            engine_rx: Some(engine_rx),
             event_tx,
         }
     }
 
     pub async fn run(mut self) {
+        // Fees are credited to the fee recipient, which must exist as a user
+        let _ = self.db.create_user(self.config.fee_recipient.clone()).await;
+
         // Spawn background task for orderbook snapshots
         let snapshot_handle = self.spawn_snapshot_broadcaster();
 
-        // Main event loop - process incoming requests
-        while let Some(request) = self.engine_rx.recv().await {
-            // Process request and collect affected balances
-            let affected = match request {
-                EngineRequest::PlaceOrder { order, response_tx } => {
-                    let (result, affected) = self.handle_place_order(order).await;
+        let Some(mut engine_rx) = self.engine_rx.take() else {
+            return;
+        };
+
+        if self.config.market_workers {
+            self.run_market_workers(engine_rx).await;
+        } else {
+            // Main event loop - process incoming requests
+            while let Some(request) = engine_rx.recv().await {
+                if let Some(admitted) = self.admit(request).await {
+                    self.process(admitted).await;
+                }
+            }
+        }
+
+        // Cleanup: abort the snapshot broadcaster when engine stops
+        snapshot_handle.abort();
+    }
+
+    /// Throttle and write-ahead log a request, `None` if it was rejected instead
+    async fn admit(&mut self, request: EngineRequest) -> Option<Admitted> {
+        // Timed from here so a stalled request log write counts towards the request
+        let started = Instant::now();
+
+        // Throttle cancels before logging, so replaying the log never meets a throttled request
+        let throttled = request
+            .cancelling_user()
+            .is_some_and(|user_address| !self.cancel_limiter.try_acquire(user_address));
+        if throttled {
+            let limit = self.cancel_limiter.limit();
+            request.reject(ExchangeError::CancelRateLimited { limit });
+            return None;
+        }
+
+        // Write-ahead: a request that cannot be logged is not processed
+        let mut sequence = None;
+        if self.config.request_log {
+            if let Some(logged) = request.to_logged() {
+                match self.db.append_engine_request(&logged).await {
+                    Ok(seq) => {
+                        self.applied_sequence.start(seq);
+                        sequence = Some(seq);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to log engine request: {}", e);
+                        request.reject(e);
+                        return None;
+                    }
+                }
+            }
+        }
+
+        Some(Admitted {
+            request,
+            sequence,
+            started,
+        })
+    }
+
+    /// Apply an admitted request to the books and broadcast the balances it touched
+    async fn process(&mut self, admitted: Admitted) {
+        let Admitted {
+            request,
+            sequence,
+            started,
+        } = admitted;
+        let kind = request.kind();
+
+        #[cfg(feature = "fault-injection")]
+        if let Some(delay) = self.config.match_delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        // Trade requests run inside a span carrying their REST request id
+        let span = match request.request_id() {
+            Some(request_id) => tracing::info_span!("engine_request", %request_id),
+            None => tracing::Span::none(),
+        };
+
+        // Process request and collect affected balances
+        let affected = async {
+            match request {
+                EngineRequest::PlaceOrder {
+                    order,
+                    client_order_id,
+                    response_tx,
+                    ..
+                } => {
+                    let (result, affected) = self.handle_place_order(order, client_order_id).await;
                     let _ = response_tx.send(result);
                     affected
                 }
@@ -58,6 +182,7 @@ impl MatchingEngine {
                     order_id,
                     user_address,
                     response_tx,
+                    ..
                 } => {
                     let (result, affected) = self.handle_cancel_order(order_id, user_address).await;
                     let _ = response_tx.send(result);
@@ -67,41 +192,247 @@ impl MatchingEngine {
                     user_address,
                     market_id,
                     response_tx,
+                    ..
                 } => {
                     let (result, affected) =
                         self.handle_cancel_all_orders(user_address, market_id).await;
                     let _ = response_tx.send(result);
                     affected
                 }
-            };
+                EngineRequest::ReplaceOrder {
+                    old_order_id,
+                    user_address,
+                    new_order,
+                    response_tx,
+                    ..
+                } => {
+                    let (result, affected) = self
+                        .handle_replace_order(old_order_id, user_address, new_order)
+                        .await;
+                    let _ = response_tx.send(result);
+                    affected
+                }
+                EngineRequest::GetOrderbookSnapshot {
+                    market_id,
+                    group_by,
+                    response_tx,
+                } => {
+                    let snapshot = self.orderbooks.read().await.snapshot(&market_id);
+                    let snapshot = match group_by {
+                        Some(group_by) => snapshot.grouped(group_by),
+                        None => snapshot,
+                    };
+                    let _ = response_tx.send(snapshot);
+                    HashSet::new()
+                }
+                EngineRequest::LiquidityProfile {
+                    market_id,
+                    from_price,
+                    to_price,
+                    buckets,
+                    response_tx,
+                } => {
+                    let profile = match self.orderbooks.read().await.get(&market_id) {
+                        Some(orderbook) => {
+                            orderbook.liquidity_profile(from_price, to_price, buckets)
+                        }
+                        None => Orderbook::new(market_id)
+                            .liquidity_profile(from_price, to_price, buckets),
+                    };
+                    let _ = response_tx.send(profile);
+                    HashSet::new()
+                }
+                EngineRequest::BookIndicators {
+                    market_id,
+                    depth,
+                    response_tx,
+                } => {
+                    let indicators = match self.orderbooks.read().await.get(&market_id) {
+                        Some(orderbook) => orderbook.indicators(depth),
+                        None => Orderbook::new(market_id).indicators(depth),
+                    };
+                    let _ = response_tx.send(indicators);
+                    HashSet::new()
+                }
+                EngineRequest::Estimate { order, response_tx } => {
+                    let _ = response_tx.send(self.handle_estimate(order).await);
+                    HashSet::new()
+                }
+                EngineRequest::CancelEverything { response_tx } => {
+                    let (result, affected) = self.handle_cancel_everything().await;
+                    let _ = response_tx.send(result);
+                    affected
+                }
+                EngineRequest::SetMarketStatus {
+                    market_id,
+                    status,
+                    response_tx,
+                } => {
+                    let (result, affected) = self.handle_set_market_status(market_id, status).await;
+                    let _ = response_tx.send(result);
+                    affected
+                }
+            }
+        }
+        .instrument(span)
+        .await;
 
-            // Broadcast consolidated balance updates for all affected users
-            // This ensures only one update per user-token pair per request
-            for (user_address, token_ticker) in affected {
-                if let Ok(balance) = self.db.get_balance(&user_address, &token_ticker).await {
-                    let _ = self.event_tx.send(EngineEvent::BalanceUpdated { balance });
+        if let Some(sequence) = sequence {
+            self.applied_sequence.finish(sequence);
+        }
+
+        // Broadcast consolidated balance updates for all affected users
+        // This ensures only one update per user-token pair per request
+        for (user_address, token_ticker) in affected {
+            if let Ok(balance) = self.db.get_balance(&user_address, &token_ticker).await {
+                let _ = self.event_tx.send(EngineEvent::BalanceUpdated { balance });
+            }
+        }
+
+        let elapsed = started.elapsed();
+        let slow_request_threshold = match self.config.slow_request_threshold_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        };
+        if slow_request_threshold.is_some_and(|threshold| elapsed > threshold) {
+            tracing::warn!(
+                request = kind,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "Slow engine request"
+            );
+        }
+    }
+
+    /// Rebuild engine state by reapplying logged requests with sequence >= `from_sequence`
+    /// Requests are read from `log` and applied against this engine's database, which
+    /// should be a fresh one with the same tokens, markets and funded users.
+    /// Requests that were rejected originally are rejected again, so results are ignored.
+    /// Returns the number of requests applied
+    pub async fn replay_from(
+        &mut self,
+        log: &Db,
+        from_sequence: i64,
+    ) -> Result<usize, ExchangeError> {
+        let entries = log.get_engine_requests(from_sequence).await?;
+
+        for entry in &entries {
+            match entry.request.clone() {
+                LoggedRequest::PlaceOrder {
+                    order,
+                    client_order_id,
+                } => {
+                    let _ = self.handle_place_order(order, client_order_id).await;
+                }
+                LoggedRequest::CancelOrder {
+                    order_id,
+                    user_address,
+                } => {
+                    let _ = self.handle_cancel_order(order_id, user_address).await;
+                }
+                LoggedRequest::CancelAllOrders {
+                    user_address,
+                    market_id,
+                } => {
+                    let _ = self.handle_cancel_all_orders(user_address, market_id).await;
+                }
+                LoggedRequest::ReplaceOrder {
+                    old_order_id,
+                    user_address,
+                    new_order,
+                } => {
+                    let _ = self
+                        .handle_replace_order(old_order_id, user_address, new_order)
+                        .await;
+                }
+                LoggedRequest::CancelEverything => {
+                    let _ = self.handle_cancel_everything().await;
+                }
+                LoggedRequest::SetMarketStatus { market_id, status } => {
+                    let _ = self.handle_set_market_status(market_id, status).await;
                 }
             }
         }
 
-        // Cleanup: abort the snapshot broadcaster when engine stops
-        snapshot_handle.abort();
+        Ok(entries.len())
+    }
+
+    /// Current snapshot of a market's orderbook
+    pub async fn orderbook_snapshot(&self, market_id: &str) -> OrderbookSnapshot {
+        self.orderbooks.read().await.snapshot(market_id)
     }
 
     /// Handle placing a new order
     /// Returns the result and set of affected balances to broadcast
+    #[tracing::instrument(
+        name = "place_order",
+        skip_all,
+        fields(order_id = %order.id, market_id = %order.market_id)
+    )]
     async fn handle_place_order(
+        &mut self,
+        order: crate::models::domain::Order,
+        client_order_id: Option<String>,
+    ) -> (Result<OrderPlaced, ExchangeError>, AffectedBalances) {
+        let Some(client_order_id) = client_order_id else {
+            return self.place_new_order(order).await;
+        };
+
+        // A repeated client_order_id returns the order it placed the first time
+        let ttl = chrono::Duration::seconds(self.config.idempotency_key_ttl_secs as i64);
+        let since = chrono::Utc::now() - ttl;
+        match self
+            .db
+            .get_idempotent_order(&order.user_address, &client_order_id, since)
+            .await
+        {
+            Ok(Some(placed)) => {
+                tracing::debug!(
+                    client_order_id = %client_order_id,
+                    original_order_id = %placed.order.id,
+                    "Returning order for repeated client_order_id"
+                );
+                return (Ok(placed), HashSet::new());
+            }
+            Ok(None) => {}
+            Err(e) => return (Err(e), HashSet::new()),
+        }
+
+        let user_address = order.user_address.clone();
+        let (result, affected) = self.place_new_order(order).await;
+        if let Ok(placed) = &result {
+            if let Err(e) = self
+                .db
+                .store_idempotent_order(&user_address, &client_order_id, placed)
+                .await
+            {
+                log::error!(
+                    "Failed to store client_order_id {} for {}: {}",
+                    client_order_id,
+                    user_address,
+                    e
+                );
+            }
+        }
+        (result, affected)
+    }
+
+    /// Validate, lock and place an order
+    async fn place_new_order(
         &mut self,
         mut order: crate::models::domain::Order,
     ) -> (Result<OrderPlaced, ExchangeError>, AffectedBalances) {
-        let mut affected = HashSet::new();
+        let affected = HashSet::new();
 
         // Validate order against market config
         let market = match self.db.get_market(&order.market_id).await {
             Ok(m) => m,
             Err(e) => return (Err(e), affected),
         };
-        if let Err(e) = Self::validate_order(&order, &market) {
+        let requested_price = Self::snap_to_tick(&mut order, &market);
+        if let Err(e) = self.validate_order(&order, &market).await {
+            return (Err(e), affected);
+        }
+        if let Err(e) = self.check_open_order_limits(&order, &market, None).await {
             return (Err(e), affected);
         }
 
@@ -120,9 +451,25 @@ impl MatchingEngine {
             return (Err(e), affected);
         }
 
-        // Track balance that was locked
-        affected.insert((order.user_address.clone(), token_to_lock.clone()));
+        let (result, affected) = self
+            .place_locked_order(order, &market, token_to_lock, amount_to_lock)
+            .await;
+        let result = result.map(|placed| OrderPlaced {
+            requested_price: requested_price.map(|p| p.to_string()),
+            ..placed
+        });
+        (result, affected)
+    }
 
+    /// Persist, match and finalize a validated order whose full lock is already held
+    /// The lock is released again if the order cannot be persisted or executed
+    async fn place_locked_order(
+        &mut self,
+        order: crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+        token_to_lock: String,
+        amount_to_lock: u128,
+    ) -> (Result<OrderPlaced, ExchangeError>, AffectedBalances) {
         // Persist initial order to database
         // If this fails, unlock balance before returning error
         if let Err(e) = self.db.create_order(&order).await {
@@ -130,20 +477,89 @@ impl MatchingEngine {
                 .db
                 .unlock_balance(&order.user_address, &token_to_lock, amount_to_lock)
                 .await;
+            let affected = HashSet::from([(order.user_address.clone(), token_to_lock)]);
             return (Err(e), affected);
         }
 
+        self.match_locked_order(order, market, token_to_lock, amount_to_lock)
+            .await
+    }
+
+    /// Match and finalize a persisted, unfilled order whose full lock is already held
+    /// The lock is released again if the order cannot be executed
+    async fn match_locked_order(
+        &mut self,
+        mut order: crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+        token_to_lock: String,
+        amount_to_lock: u128,
+    ) -> (Result<OrderPlaced, ExchangeError>, AffectedBalances) {
+        let mut affected = HashSet::new();
+
+        // Track balance that was locked
+        affected.insert((order.user_address.clone(), token_to_lock.clone()));
+
         // Get matches from matcher and apply them
+        // The books are unlocked while fills settle so other markets keep matching,
+        // requests for this market are applied one at a time so its book can't move
         let (matches, trades) = {
-            let mut orderbooks = self.orderbooks.write().await;
-            let orderbook = orderbooks.get_or_create(&order.market_id);
+            let matches = {
+                let mut orderbooks = self.orderbooks.write().await;
+                let orderbook = orderbooks.get_or_create(&order.market_id);
+
+                // Match order against orderbook, pre-open markets only collect orders
+                if market.status == MarketStatus::PreOpen {
+                    Vec::new()
+                } else {
+                    Matcher::match_order(&order, orderbook)
+                }
+            };
 
-            // Match order against orderbook
-            let matches = Matcher::match_order(&order, orderbook);
+            // Short of min_fill nothing executes, so undoing it is the executor's failure
+            // path: release the full lock. The order ends cancelled instead of resting
+            if let Some(min_fill) = order.min_fill {
+                let matchable: u128 = matches.iter().map(|m| m.size).sum();
+                if matchable < min_fill {
+                    tracing::debug!(min_fill, matchable, "Order cancelled below min_fill");
+
+                    order.status = OrderStatus::Cancelled;
+                    if let Err(e) = self.db.update_order_fill(order.id, 0, order.status).await {
+                        return (Err(e), affected);
+                    }
+                    if let Err(e) = self
+                        .db
+                        .unlock_balance(&order.user_address, &token_to_lock, amount_to_lock)
+                        .await
+                    {
+                        return (Err(e), affected);
+                    }
+
+                    return (
+                        Ok(OrderPlaced {
+                            order: order.into(),
+                            trades: vec![],
+                            average_price: "0".to_string(),
+                            total_quote: "0".to_string(),
+                            requested_price: None,
+                        }),
+                        affected,
+                    );
+                }
+            }
 
             // Execute trades if we have matches (also updates order status in DB)
             let (trades, executor_affected) = if !matches.is_empty() {
-                match Executor::execute(self.db.clone(), matches.clone(), &order, &market).await {
+                match Executor::execute(
+                    self.db.clone(),
+                    matches.clone(),
+                    &order,
+                    market,
+                    self.config.match_price_policy,
+                    &self.config.fee_recipient,
+                    self.config.check_balance_invariants,
+                )
+                .await
+                {
                     Ok((trades, exec_affected)) => (trades, exec_affected),
                     Err(e) => {
                         // Execution failed - unlock the full order amount
@@ -162,7 +578,11 @@ impl MatchingEngine {
             affected.extend(executor_affected);
 
             // Update orderbook with executed trades
-            orderbook.apply_trades(&order, &trades, &market);
+            self.orderbooks
+                .write()
+                .await
+                .get_or_create(&order.market_id)
+                .apply_trades(&order, &trades, market);
 
             (matches, trades)
         };
@@ -174,31 +594,29 @@ impl MatchingEngine {
             });
         }
 
-        // Broadcast order fill updates for maker orders
+        // Broadcast a single update per maker order with its final state
+        // Consolidated per order id, like balance updates are per user-token pair
+        let mut maker_orders: Vec<crate::models::domain::Order> = Vec::new();
+        let mut maker_index: HashMap<uuid::Uuid, usize> = HashMap::new();
         for m in &matches {
-            let maker_order = &m.maker_order;
-            let maker_new_filled = maker_order.filled_size + m.size;
-            let maker_status = if maker_new_filled >= maker_order.size {
+            let idx = *maker_index.entry(m.maker_order.id).or_insert_with(|| {
+                maker_orders.push(m.maker_order.clone());
+                maker_orders.len() - 1
+            });
+            maker_orders[idx].filled_size += m.size;
+        }
+
+        let now = chrono::Utc::now();
+        for mut maker_order in maker_orders {
+            maker_order.status = if maker_order.filled_size >= maker_order.size {
                 OrderStatus::Filled
             } else {
                 OrderStatus::PartiallyFilled
             };
-
-            let _ = self.event_tx.send(EngineEvent::OrderPlaced {
-                order: crate::models::domain::Order {
-                    id: maker_order.id,
-                    user_address: maker_order.user_address.clone(),
-                    market_id: maker_order.market_id.clone(),
-                    side: maker_order.side,
-                    order_type: maker_order.order_type,
-                    price: maker_order.price,
-                    size: maker_order.size,
-                    filled_size: maker_new_filled,
-                    status: maker_status,
-                    created_at: maker_order.created_at,
-                    updated_at: chrono::Utc::now(),
-                },
-            });
+            maker_order.updated_at = now;
+            let _ = self
+                .event_tx
+                .send(EngineEvent::OrderPlaced { order: maker_order });
         }
 
         // Update order status for response
@@ -210,90 +628,121 @@ impl MatchingEngine {
             order.status = OrderStatus::PartiallyFilled;
         }
 
-        // Broadcast taker order update if it got filled or partially filled
-        if total_matched > 0 {
-            let _ = self.event_tx.send(EngineEvent::OrderPlaced {
-                order: order.clone(),
-            });
-        }
-
-        // Handle unfilled/partially filled orders based on order type
-        if order.filled_size < order.size {
-            match order.order_type {
-                crate::models::domain::OrderType::Market => {
-                    // Market orders that don't fully fill are cancelled
-                    // (IOC - Immediate or Cancel behavior)
-                    // Market orders that execute (even partially) are marked as Filled
-                    // since they cannot remain on the book
-                    order.status = if total_matched > 0 {
-                        OrderStatus::Filled
-                    } else {
-                        OrderStatus::Cancelled
-                    };
+        // Immediate-or-cancel orders never rest, so finalize any unfilled remainder
+        if order.filled_size < order.size && order.is_immediate_or_cancel() {
+            // Market and IOC limit orders that don't fully fill are cancelled
+            // Orders that execute (even partially) are marked as Filled
+            // since they cannot remain on the book
+            order.status = if total_matched > 0 {
+                OrderStatus::Filled
+            } else {
+                OrderStatus::Cancelled
+            };
 
-                    // Update database with final status
-                    if let Err(e) = self
-                        .db
-                        .update_order_fill(order.id, order.filled_size, order.status)
-                        .await
-                    {
-                        return (Err(e), affected);
-                    }
+            // Update database with final status
+            if let Err(e) = self
+                .db
+                .update_order_fill(order.id, order.filled_size, order.status)
+                .await
+            {
+                return (Err(e), affected);
+            }
 
-                    // Unlock the unfilled portion
-                    let unfilled_size = order.size - order.filled_size;
-                    if unfilled_size > 0 {
-                        let (token_to_unlock, amount_to_unlock) = match order.side {
-                            crate::models::domain::Side::Buy => {
-                                match order.price.checked_mul(unfilled_size) {
-                                    Some(quote_amount) => {
-                                        (market.quote_ticker.clone(), quote_amount)
-                                    }
-                                    None => {
-                                        return (
-                                            Err(ExchangeError::InvalidParameter {
-                                                message: "Unlock amount overflow".to_string(),
-                                            }),
-                                            affected,
-                                        );
-                                    }
-                                }
-                            }
-                            crate::models::domain::Side::Sell => {
-                                (market.base_ticker.clone(), unfilled_size)
-                            }
-                        };
-
-                        if let Err(e) = self
-                            .db
-                            .unlock_balance(&order.user_address, &token_to_unlock, amount_to_unlock)
-                            .await
-                        {
-                            return (Err(e), affected);
-                        }
+            // Unlock the unfilled portion
+            let unfilled_size = order.size - order.filled_size;
+            let (token_to_unlock, amount_to_unlock) = match self
+                .calculate_lock_amount_for_size(&order, market, unfilled_size)
+                .await
+            {
+                Ok(v) => v,
+                Err(e) => return (Err(e), affected),
+            };
 
-                        // Track unlocked balance
-                        affected.insert((order.user_address.clone(), token_to_unlock));
-                    }
-                }
-                crate::models::domain::OrderType::Limit => {
-                    // Limit orders stay on the book
-                    let _ = self.event_tx.send(EngineEvent::OrderPlaced {
-                        order: order.clone(),
-                    });
-                }
+            if let Err(e) = self
+                .db
+                .unlock_balance(&order.user_address, &token_to_unlock, amount_to_unlock)
+                .await
+            {
+                return (Err(e), affected);
             }
+
+            // Track unlocked balance
+            affected.insert((order.user_address.clone(), token_to_unlock));
         }
 
+        // Broadcast a single taker order update with its final state
+        // Skipped only for immediate-or-cancel orders that never matched
+        if total_matched > 0 || !order.is_immediate_or_cancel() {
+            let _ = self.event_tx.send(EngineEvent::OrderPlaced {
+                order: order.clone(),
+            });
+        }
+
+        // Aggregate fills so callers don't have to
+        let (average_price, total_quote) = match self.fill_summary(&trades, market).await {
+            Ok(summary) => summary,
+            Err(e) => return (Err(e), affected),
+        };
+        tracing::debug!(status = ?order.status, trades = trades.len(), "Order processed");
+
         (
             Ok(OrderPlaced {
                 order: order.into(),
                 trades: trades.into_iter().map(|t| t.into()).collect(),
+                average_price: average_price.to_string(),
+                total_quote: total_quote.to_string(),
+                requested_price: None,
             }),
             affected,
         )
     }
 
+    /// Size-weighted average price and total quote amount of a set of fills
+    /// Quote is summed per trade the same way the executor settles it
+    async fn fill_summary(
+        &self,
+        trades: &[crate::models::domain::Trade],
+        market: &crate::models::domain::Market,
+    ) -> Result<(u128, u128), ExchangeError> {
+        let total_size: u128 = trades.iter().map(|t| t.size).sum();
+        if total_size == 0 {
+            return Ok((0, 0));
+        }
+
+        let base_token = self.db.get_token(&market.base_ticker).await?;
+        let divisor = 10u128.pow(base_token.decimals as u32);
+
+        let mut notional: u128 = 0;
+        let mut total_quote: u128 = 0;
+        for trade in trades {
+            let value = trade.price.checked_mul(trade.size).ok_or_else(|| {
+                ExchangeError::InvalidParameter {
+                    message: "Trade value overflow".to_string(),
+                }
+            })?;
+            notional += value;
+            total_quote += value / divisor;
+        }
+
+        Ok((notional / total_size, total_quote))
+    }
+
+    /// Error for cancelling an order of `user_address` that isn't on the book
+    /// The user's own filled or cancelled orders report their status; anything
+    /// else, including another user's order, is reported as not found for security
+    async fn not_resting_error(&self, order_id: uuid::Uuid, user_address: &str) -> ExchangeError {
+        match self.db.get_order(&order_id).await {
+            Ok(order) if order.user_address == user_address => ExchangeError::OrderNotCancellable {
+                order_id,
+                status: order.status,
+            },
+            Ok(_) | Err(ExchangeError::Database(sqlx::Error::RowNotFound)) => {
+                ExchangeError::OrderNotFound
+            }
+            Err(e) => e,
+        }
+    }
+
     /// Handle cancelling an order
     /// Returns the result and set of affected balances to broadcast
     async fn handle_cancel_order(
@@ -304,12 +753,20 @@ impl MatchingEngine {
         let mut affected = HashSet::new();
 
         // Cancel order using orderbooks method (handles search and ownership verification)
-        let cancelled_order = {
-            let mut orderbooks = self.orderbooks.write().await;
-            match orderbooks.cancel_order(order_id, &user_address) {
-                Ok(order) => order,
-                Err(e) => return (Err(e), affected),
+        let cancelled = self
+            .orderbooks
+            .write()
+            .await
+            .cancel_order(order_id, &user_address);
+        let cancelled_order = match cancelled {
+            Ok(order) => order,
+            Err(ExchangeError::OrderNotFound) => {
+                return (
+                    Err(self.not_resting_error(order_id, &user_address).await),
+                    affected,
+                )
             }
+            Err(e) => return (Err(e), affected),
         };
 
         // Get market config to determine which token to unlock
@@ -322,26 +779,13 @@ impl MatchingEngine {
         let unfilled_size = cancelled_order.size - cancelled_order.filled_size;
 
         if unfilled_size > 0 {
-            // Determine which token and amount to unlock based on order side
-            let (token_to_unlock, amount_to_unlock) = match cancelled_order.side {
-                crate::models::domain::Side::Buy => {
-                    // Buy order: unlock quote tokens (price * unfilled_size)
-                    match cancelled_order.price.checked_mul(unfilled_size) {
-                        Some(quote_amount) => (market.quote_ticker, quote_amount),
-                        None => {
-                            return (
-                                Err(ExchangeError::InvalidParameter {
-                                    message: "Unlock amount overflow".to_string(),
-                                }),
-                                affected,
-                            );
-                        }
-                    }
-                }
-                crate::models::domain::Side::Sell => {
-                    // Sell order: unlock base tokens (unfilled_size)
-                    (market.base_ticker, unfilled_size)
-                }
+            // Unlock exactly what was locked for the unfilled remainder
+            let (token_to_unlock, amount_to_unlock) = match self
+                .calculate_lock_amount_for_size(&cancelled_order, &market, unfilled_size)
+                .await
+            {
+                Ok(lock) => lock,
+                Err(e) => return (Err(e), affected),
             };
 
             // Unlock the balance
@@ -374,6 +818,7 @@ impl MatchingEngine {
         let _ = self.event_tx.send(EngineEvent::OrderCancelled {
             order_id,
             user_address: user_address.clone(),
+            market_id: cancelled_order.market_id.clone(),
         });
 
         (
@@ -384,6 +829,157 @@ impl MatchingEngine {
         )
     }
 
+    /// Handle replacing a resting order with a new one in a single engine turn
+    /// The old lock is reused: only the increase is locked up front and only the
+    /// surplus is released, so the replacement never needs the full amount free.
+    /// Returns the result and set of affected balances to broadcast
+    #[tracing::instrument(
+        name = "replace_order",
+        skip_all,
+        fields(%old_order_id, order_id = %new_order.id, market_id = %new_order.market_id)
+    )]
+    async fn handle_replace_order(
+        &mut self,
+        old_order_id: uuid::Uuid,
+        user_address: String,
+        mut new_order: crate::models::domain::Order,
+    ) -> (Result<OrderReplaced, ExchangeError>, AffectedBalances) {
+        let mut affected = HashSet::new();
+
+        // Look the old order up without removing it, a rejected replacement keeps its priority
+        let resting = self
+            .orderbooks
+            .read()
+            .await
+            .find_order(old_order_id)
+            .filter(|order| order.user_address == user_address)
+            .cloned();
+        let Some(old_order) = resting else {
+            return (
+                Err(self.not_resting_error(old_order_id, &user_address).await),
+                affected,
+            );
+        };
+
+        if new_order.user_address != user_address || new_order.market_id != old_order.market_id {
+            return (
+                Err(ExchangeError::InvalidParameter {
+                    message: "Replacement order must be for the same user and market".to_string(),
+                }),
+                affected,
+            );
+        }
+
+        let market = match self.db.get_market(&new_order.market_id).await {
+            Ok(m) => m,
+            Err(e) => return (Err(e), affected),
+        };
+        let requested_price = Self::snap_to_tick(&mut new_order, &market);
+        if let Err(e) = self.validate_order(&new_order, &market).await {
+            return (Err(e), affected);
+        }
+        if let Err(e) = self
+            .check_open_order_limits(&new_order, &market, Some(old_order_id))
+            .await
+        {
+            return (Err(e), affected);
+        }
+
+        // Lock held by the old order's unfilled remainder and lock the new order needs
+        let (old_token, old_amount) = match self
+            .calculate_lock_amount_for_size(
+                &old_order,
+                &market,
+                old_order.size - old_order.filled_size,
+            )
+            .await
+        {
+            Ok(lock) => lock,
+            Err(e) => return (Err(e), affected),
+        };
+        let (new_token, new_amount) = match self.calculate_lock_amount(&new_order, &market).await {
+            Ok(lock) => lock,
+            Err(e) => return (Err(e), affected),
+        };
+
+        // The old lock carries over when both orders lock the same token
+        let (to_lock, to_unlock) = if old_token == new_token {
+            (
+                new_amount.saturating_sub(old_amount),
+                old_amount.saturating_sub(new_amount),
+            )
+        } else {
+            (new_amount, old_amount)
+        };
+
+        // Lock the increase first so a failure leaves the old order untouched
+        if to_lock > 0 {
+            if let Err(e) = self
+                .db
+                .lock_balance(&user_address, &new_token, to_lock)
+                .await
+            {
+                return (Err(e), affected);
+            }
+        }
+
+        // Cancel the old order
+        let cancelled = self
+            .orderbooks
+            .write()
+            .await
+            .cancel_order(old_order_id, &user_address);
+        if let Err(e) = cancelled {
+            let _ = self
+                .db
+                .unlock_balance(&user_address, &new_token, to_lock)
+                .await;
+            return (Err(e), affected);
+        }
+        if let Err(e) = self
+            .db
+            .update_order_fill(old_order_id, old_order.filled_size, OrderStatus::Cancelled)
+            .await
+        {
+            return (Err(e), affected);
+        }
+        let _ = self.event_tx.send(EngineEvent::OrderCancelled {
+            order_id: old_order_id,
+            user_address: user_address.clone(),
+            market_id: old_order.market_id.clone(),
+        });
+
+        // Release whatever the new order doesn't need
+        if to_unlock > 0 {
+            if let Err(e) = self
+                .db
+                .unlock_balance(&user_address, &old_token, to_unlock)
+                .await
+            {
+                return (Err(e), affected);
+            }
+            affected.insert((user_address.clone(), old_token));
+        }
+
+        let (result, placed_affected) = self
+            .place_locked_order(new_order, &market, new_token, new_amount)
+            .await;
+        affected.extend(placed_affected);
+
+        (
+            result.map(|placed| OrderReplaced {
+                cancelled: OrderCancelled {
+                    order_id: old_order_id.to_string(),
+                },
+                placed: OrderPlaced {
+                    requested_price: requested_price.map(|p| p.to_string()),
+                    ..placed
+                },
+            }),
+            affected,
+        )
+    }
+
     /// Handle cancelling all orders for a user
     /// Returns the result and set of affected balances to broadcast
     async fn handle_cancel_all_orders(
@@ -391,19 +987,110 @@ impl MatchingEngine {
         user_address: String,
         market_id: Option<String>,
     ) -> (Result<OrdersCancelled, ExchangeError>, AffectedBalances) {
-        let mut affected = HashSet::new();
         // Cancel all orders for the user using orderbooks method
         let cancelled_orders = {
             let mut orderbooks = self.orderbooks.write().await;
             orderbooks.cancel_all_orders(&user_address, market_id.as_deref())
         };
 
+        let (cancelled, affected) = self.release_cancelled_orders(cancelled_orders).await;
+        (Ok(cancelled), affected)
+    }
+
+    /// Handle the kill switch: cancel every resting order in every market
+    /// Returns the number of orders cancelled and set of affected balances to broadcast
+    async fn handle_cancel_everything(
+        &mut self,
+    ) -> (Result<usize, ExchangeError>, AffectedBalances) {
+        let cancelled_orders = {
+            let mut orderbooks = self.orderbooks.write().await;
+            orderbooks.cancel_everything()
+        };
+        log::warn!(
+            "Kill switch: cancelling {} resting orders",
+            cancelled_orders.len()
+        );
+
+        let (cancelled, affected) = self.release_cancelled_orders(cancelled_orders).await;
+        (Ok(cancelled.count), affected)
+    }
+
+    /// Handle moving a market to another trading phase
+    /// Opening a pre-open market matches the orders it collected
+    /// Returns the updated market and set of affected balances to broadcast
+    async fn handle_set_market_status(
+        &mut self,
+        market_id: String,
+        status: MarketStatus,
+    ) -> (Result<Market, ExchangeError>, AffectedBalances) {
+        let previous = match self.db.get_market(&market_id).await {
+            Ok(market) => market.status,
+            Err(e) => return (Err(e), HashSet::new()),
+        };
+        let market = match self.db.set_market_status(&market_id, status).await {
+            Ok(market) => market,
+            Err(e) => return (Err(e), HashSet::new()),
+        };
+        log::info!("Market {} moved from {} to {}", market_id, previous, status);
+        let _ = self.event_tx.send(EngineEvent::MarketStatusChanged {
+            market_id: market.id.clone(),
+            status: market.status,
+        });
+
+        let affected = if previous == MarketStatus::PreOpen && status == MarketStatus::Open {
+            self.open_market(&market).await
+        } else {
+            HashSet::new()
+        };
+        (Ok(market), affected)
+    }
+
+    /// Match the orders a market collected while pre-open
+    /// Every resting order is taken off the book and matched again in time
+    /// priority, so a later crossing order trades against earlier ones at their
+    /// price. Nothing filled while pre-open, so each still holds its full lock
+    async fn open_market(&mut self, market: &Market) -> AffectedBalances {
+        let mut orders = self
+            .orderbooks
+            .write()
+            .await
+            .get_or_create(&market.id)
+            .remove_all_orders();
+        orders.sort_by_key(|order| order.created_at);
+
+        let mut affected = HashSet::new();
+        for order in orders {
+            let order_id = order.id;
+            let (token, amount) = match self.calculate_lock_amount(&order, market).await {
+                Ok(lock) => lock,
+                Err(e) => {
+                    log::error!("Failed to reopen order {}: {}", order_id, e);
+                    continue;
+                }
+            };
+            let (result, order_affected) =
+                self.match_locked_order(order, market, token, amount).await;
+            if let Err(e) = result {
+                log::error!("Failed to match order {} at open: {}", order_id, e);
+            }
+            affected.extend(order_affected);
+        }
+        affected
+    }
+
+    /// Unlock, persist and broadcast orders already removed from the books
+    /// Continues past individual failures to prevent orphaned locks
+    async fn release_cancelled_orders(
+        &self,
+        cancelled_orders: Vec<crate::models::domain::Order>,
+    ) -> (OrdersCancelled, AffectedBalances) {
+        let mut affected = HashSet::new();
         let mut cancelled_order_ids = Vec::new();
+        let mut refunds = Vec::new();
 
-        // Process each cancelled order
-        // Continue processing even if individual unlocks fail to prevent orphaned locks
         for cancelled_order in cancelled_orders {
             let order_id = cancelled_order.id;
+            let user_address = &cancelled_order.user_address;
 
             // Get market config to determine which token to unlock
             let market = match self.db.get_market(&cancelled_order.market_id).await {
@@ -418,47 +1105,37 @@ impl MatchingEngine {
             let unfilled_size = cancelled_order.size - cancelled_order.filled_size;
 
             if unfilled_size > 0 {
-                // Determine which token and amount to unlock based on order side
-                let (token_to_unlock, unlock_result) = match cancelled_order.side {
-                    crate::models::domain::Side::Buy => {
-                        // Buy order: unlock quote tokens (price * unfilled_size)
-                        match cancelled_order.price.checked_mul(unfilled_size) {
-                            Some(quote_amount) => {
-                                let result = self
-                                    .db
-                                    .unlock_balance(
-                                        &user_address,
-                                        &market.quote_ticker,
-                                        quote_amount,
-                                    )
-                                    .await;
-                                (market.quote_ticker.clone(), result)
-                            }
-                            None => {
-                                log::error!(
-                                    "Overflow calculating unlock amount for order {}",
-                                    order_id
-                                );
-                                continue;
-                            }
-                        }
-                    }
-                    crate::models::domain::Side::Sell => {
-                        // Sell order: unlock base tokens (unfilled_size)
-                        let result = self
-                            .db
-                            .unlock_balance(&user_address, &market.base_ticker, unfilled_size)
-                            .await;
-                        (market.base_ticker.clone(), result)
+                // Unlock exactly what was locked for the unfilled remainder
+                let (token_to_unlock, amount_to_unlock) = match self
+                    .calculate_lock_amount_for_size(&cancelled_order, &market, unfilled_size)
+                    .await
+                {
+                    Ok(lock) => lock,
+                    Err(e) => {
+                        log::error!(
+                            "Failed to calculate unlock amount for order {}: {}",
+                            order_id,
+                            e
+                        );
+                        continue;
                     }
                 };
 
                 // Log unlock failures but continue processing
-                if let Err(e) = unlock_result {
+                if let Err(e) = self
+                    .db
+                    .unlock_balance(user_address, &token_to_unlock, amount_to_unlock)
+                    .await
+                {
                     log::error!("Failed to unlock balance for order {}: {}", order_id, e);
                 } else {
                     // Track unlocked balance
-                    affected.insert((user_address.clone(), token_to_unlock));
+                    affected.insert((user_address.clone(), token_to_unlock.clone()));
+                    refunds.push(OrderRefund {
+                        order_id: order_id.to_string(),
+                        token: token_to_unlock,
+                        amount: amount_to_unlock.to_string(),
+                    });
                 }
             }
 
@@ -480,6 +1157,7 @@ impl MatchingEngine {
             let _ = self.event_tx.send(EngineEvent::OrderCancelled {
                 order_id,
                 user_address: user_address.clone(),
+                market_id: cancelled_order.market_id.clone(),
             });
 
             cancelled_order_ids.push(order_id.to_string());
@@ -488,29 +1166,134 @@ impl MatchingEngine {
         let count = cancelled_order_ids.len();
 
         (
-            Ok(OrdersCancelled {
+            OrdersCancelled {
                 cancelled_order_ids,
                 count,
-            }),
+                refunds,
+            },
             affected,
         )
     }
 
+    /// Handle a dry-run estimate for an order
+    /// Read-only: nothing is locked, matched or persisted
+    async fn handle_estimate(
+        &self,
+        mut order: crate::models::domain::Order,
+    ) -> Result<EstimateResponse, ExchangeError> {
+        let market = self.db.get_market(&order.market_id).await?;
+        Self::snap_to_tick(&mut order, &market);
+        self.validate_order(&order, &market).await?;
+
+        let orderbooks = self.orderbooks.read().await;
+        let empty = Orderbook::new(market.id.clone());
+        let orderbook = orderbooks.get(&market.id).unwrap_or(&empty);
+
+        // Same rules as match_locked_order: pre-open markets only collect orders,
+        // and short of min_fill nothing executes and the order is cancelled
+        let matchable = if market.status == MarketStatus::PreOpen {
+            &empty
+        } else {
+            orderbook
+        };
+        let mut estimate = Matcher::estimate_fill(&order, matchable);
+        if order
+            .min_fill
+            .is_some_and(|min_fill| estimate.fillable_size < min_fill)
+        {
+            estimate = FillEstimate {
+                fillable_size: 0,
+                average_price: None,
+                levels_consumed: 0,
+                would_rest: false,
+            };
+        }
+
+        let policy = self.config.match_price_policy;
+        if policy != crate::models::domain::MatchPricePolicy::Maker && estimate.fillable_size > 0 {
+            // Fills execute at the policy price rather than the resting maker price
+            let notional: u128 = Matcher::match_order(&order, matchable)
+                .iter()
+                .map(|m| {
+                    Executor::execution_price(policy, &order, m.price, market.tick_size) * m.size
+                })
+                .sum();
+            estimate.average_price =
+                (estimate.fillable_size > 0).then(|| notional / estimate.fillable_size);
+        }
+        let best_price = match order.side {
+            crate::models::domain::Side::Buy => orderbook.best_ask(),
+            crate::models::domain::Side::Sell => orderbook.best_bid(),
+        };
+        drop(orderbooks);
+
+        // Positive slippage means a worse average price than the top of book
+        let slippage_bps = match (estimate.average_price, best_price) {
+            (Some(average), Some(best)) if best > 0 => {
+                let diff = match order.side {
+                    crate::models::domain::Side::Buy => average as f64 - best as f64,
+                    crate::models::domain::Side::Sell => best as f64 - average as f64,
+                };
+                Some(diff * 10_000.0 / best as f64)
+            }
+            _ => None,
+        };
+
+        // Mirrors Orderbook::apply_trades: remainders below min size don't rest
+        let remaining = order.size - estimate.fillable_size;
+        let resting_size = if estimate.would_rest && remaining >= market.min_size {
+            remaining
+        } else {
+            0
+        };
+
+        Ok(EstimateResponse {
+            market_id: market.id,
+            fillable_size: estimate.fillable_size.to_string(),
+            average_price: estimate.average_price.map(|p| p.to_string()),
+            best_price: best_price.map(|p| p.to_string()),
+            slippage_bps,
+            levels_consumed: estimate.levels_consumed,
+            resting_size: resting_size.to_string(),
+        })
+    }
+
     /// Spawn a background task that periodically broadcasts orderbook snapshots
-    /// Snapshots are sent every 1s for all active markets
+    /// Snapshots are sent every 1s for all active markets, and every
+    /// `snapshot_persist_interval_secs` the full books are also saved for recovery,
+    /// keeping the newest `snapshot_history` per market.
+    /// Expired idempotency keys are purged once an hour
     fn spawn_snapshot_broadcaster(&self) -> JoinHandle<()> {
         let event_tx = self.event_tx.clone();
         let orderbooks = Arc::clone(&self.orderbooks);
+        let applied_sequence = Arc::clone(&self.applied_sequence);
+        let db = self.db.clone();
+        let persist_every = self.config.snapshot_persist_interval_secs;
+        let snapshot_history = self.config.snapshot_history;
+        let idempotency_key_ttl =
+            chrono::Duration::seconds(self.config.idempotency_key_ttl_secs as i64);
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(1000));
+            let mut ticks: u64 = 0;
             loop {
                 interval.tick().await;
+                ticks += 1;
+                let persist = persist_every > 0 && ticks.is_multiple_of(persist_every);
+
+                // Read the sequence before the books: a request applied in between is
+                // replayed again on recovery, where it is rejected as a duplicate
+                let sequence = applied_sequence.applied();
 
                 // Get snapshots for all markets
-                let snapshots = {
+                let (snapshots, persisted) = {
                     let orderbooks_read = orderbooks.read().await;
-                    orderbooks_read.snapshots()
+                    let persisted = if persist {
+                        orderbooks_read.persisted(sequence)
+                    } else {
+                        Vec::new()
+                    };
+                    (orderbooks_read.snapshots(), persisted)
                 };
 
                 // Broadcast each snapshot
@@ -519,15 +1302,80 @@ impl MatchingEngine {
                         orderbook: snapshot,
                     });
                 }
+
+                for orderbook in persisted {
+                    if let Err(e) = db.save_orderbook_snapshot(&orderbook).await {
+                        log::error!(
+                            "Failed to persist orderbook for {}: {}",
+                            orderbook.market_id,
+                            e
+                        );
+                    }
+                }
+                if persist {
+                    if let Err(e) = db.prune_orderbook_snapshots(snapshot_history).await {
+                        log::error!("Failed to prune orderbook snapshots: {}", e);
+                    }
+                }
+
+                if ticks.is_multiple_of(IDEMPOTENCY_PURGE_INTERVAL_SECS) {
+                    let before = chrono::Utc::now() - idempotency_key_ttl;
+                    if let Err(e) = db.purge_idempotency_keys(before).await {
+                        log::error!("Failed to purge expired idempotency keys: {}", e);
+                    }
+                }
             }
         })
     }
 
+    /// Round an off-tick limit price to the nearest tick on markets that snap prices
+    /// Returns the price as submitted when it was changed, so responses can report it
+    pub(crate) fn snap_to_tick(
+        order: &mut crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+    ) -> Option<u128> {
+        if !market.snap_to_tick
+            || order.order_type != crate::models::domain::OrderType::Limit
+            || order.price.is_multiple_of(market.tick_size)
+        {
+            return None;
+        }
+        let requested = order.price;
+        order.price = market.snap_price_to_tick(requested);
+        tracing::debug!(
+            requested,
+            snapped = order.price,
+            "Snapped limit price to tick"
+        );
+        Some(requested)
+    }
+
     /// Validate order against market configuration
-    fn validate_order(
+    /// Zero sizes and zero limit prices are rejected before any divisibility check,
+    /// since zero is a multiple of every tick and lot size. Market orders skip all
+    /// price checks; their price only sizes the quote locked for a buy
+    async fn validate_order(
+        &self,
         order: &crate::models::domain::Order,
         market: &crate::models::domain::Market,
     ) -> Result<(), ExchangeError> {
+        // Pre-open markets only collect orders that can rest until the open
+        let accepted = match market.status {
+            MarketStatus::Open => true,
+            MarketStatus::PreOpen => {
+                order.order_type == crate::models::domain::OrderType::Limit
+                    && !order.is_immediate_or_cancel()
+                    && order.min_fill.is_none()
+            }
+            MarketStatus::Halted | MarketStatus::Closed => false,
+        };
+        if !accepted {
+            return Err(ExchangeError::MarketNotOpen {
+                market_id: market.id.clone(),
+                status: market.status,
+            });
+        }
+
         // Validate that size is greater than 0
         if order.size == 0 {
             return Err(ExchangeError::InvalidParameter {
@@ -546,34 +1394,184 @@ impl MatchingEngine {
         if order.order_type == crate::models::domain::OrderType::Limit
             && !order.price.is_multiple_of(market.tick_size)
         {
-            return Err(ExchangeError::InvalidParameter {
-                message: format!(
-                    "Price {} is not a multiple of tick size {}",
-                    order.price, market.tick_size
-                ),
+            return Err(ExchangeError::InvalidTickSize {
+                price: order.price,
+                tick_size: market.tick_size,
             });
         }
 
+        // Validate price band for limit orders (rejects fat-finger prices)
+        if order.order_type == crate::models::domain::OrderType::Limit {
+            if let Some(min_price) = market.min_price {
+                if order.price < min_price {
+                    return Err(ExchangeError::InvalidParameter {
+                        message: format!(
+                            "Price {} is below market minimum price {}",
+                            order.price, min_price
+                        ),
+                    });
+                }
+            }
+            if let Some(max_price) = market.max_price {
+                if order.price > max_price {
+                    return Err(ExchangeError::InvalidParameter {
+                        message: format!(
+                            "Price {} is above market maximum price {}",
+                            order.price, max_price
+                        ),
+                    });
+                }
+            }
+        }
+
         // Validate lot size (size must be multiple of lot_size)
         if !order.size.is_multiple_of(market.lot_size) {
-            return Err(ExchangeError::InvalidParameter {
-                message: format!(
-                    "Size {} is not a multiple of lot size {}",
-                    order.size, market.lot_size
-                ),
+            return Err(ExchangeError::InvalidLotSize {
+                size: order.size,
+                lot_size: market.lot_size,
             });
         }
 
+        // A minimum fill must be achievable by the order itself
+        if let Some(min_fill) = order.min_fill {
+            if min_fill == 0 || min_fill > order.size {
+                return Err(ExchangeError::InvalidParameter {
+                    message: format!(
+                        "min_fill {} must be between 1 and the order size {}",
+                        min_fill, order.size
+                    ),
+                });
+            }
+        }
+
         // Validate minimum order size
         if order.size < market.min_size {
-            return Err(ExchangeError::InvalidParameter {
-                message: format!(
-                    "Size {} is below minimum order size {}",
-                    order.size, market.min_size
-                ),
+            return Err(ExchangeError::SizeBelowMinimum {
+                size: order.size,
+                min_size: market.min_size,
             });
         }
 
+        // Validate minimum order value for limit orders (market orders carry no price)
+        if let Some(min_notional) = market.min_notional {
+            if order.order_type == crate::models::domain::OrderType::Limit {
+                // notional = (price_atoms * size_atoms) / 10^base_decimals, same as the quote lock
+                let base_token = self.db.get_token(&market.base_ticker).await?;
+                let divisor = 10u128.pow(base_token.decimals as u32);
+                let notional = order
+                    .price
+                    .checked_mul(order.size)
+                    .map(|v| v / divisor)
+                    .unwrap_or(u128::MAX);
+                if notional < min_notional {
+                    return Err(ExchangeError::NotionalBelowMinimum {
+                        notional,
+                        min_notional,
+                    });
+                }
+            }
+        }
+
+        self.check_crossing(order, market).await
+    }
+
+    /// Reject orders that would trade in a way their owner ruled out
+    /// A post-only order may not cross any resting order. An immediate-or-cancel
+    /// order whose crossed liquidity is all the user's own would only be skipped
+    /// past it, so it is rejected as a self-trade rather than cancelled unfilled.
+    /// Pre-open markets don't match, so nothing crosses there
+    async fn check_crossing(
+        &self,
+        order: &crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+    ) -> Result<(), ExchangeError> {
+        let post_only = order.time_in_force == TimeInForce::PostOnly;
+        if market.status == MarketStatus::PreOpen || !(post_only || order.is_immediate_or_cancel())
+        {
+            return Ok(());
+        }
+
+        let orderbooks = self.orderbooks.read().await;
+        let Some(orderbook) = orderbooks.get(&order.market_id) else {
+            return Ok(());
+        };
+        let mut crossed = Matcher::crossing_orders(order, orderbook).peekable();
+
+        if post_only {
+            return match crossed.peek() {
+                Some(maker) => Err(ExchangeError::PostOnlyWouldCross { price: maker.price }),
+                None => Ok(()),
+            };
+        }
+
+        if crossed.peek().is_some() && crossed.all(|maker| maker.user_address == order.user_address)
+        {
+            return Err(ExchangeError::SelfTradeRejected);
+        }
+        Ok(())
+    }
+
+    /// Enforce the market's per-user caps on resting orders
+    /// Only orders that can rest are checked, the full order size counts towards notional.
+    /// The order being replaced, if any, is left out of the user's open orders
+    async fn check_open_order_limits(
+        &self,
+        order: &crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+        replacing: Option<uuid::Uuid>,
+    ) -> Result<(), ExchangeError> {
+        if order.is_immediate_or_cancel()
+            || (market.max_open_orders_per_user.is_none()
+                && market.max_open_notional_per_user.is_none())
+        {
+            return Ok(());
+        }
+
+        // Price * remaining size summed over the user's resting orders, in unscaled atoms
+        let (open_orders, open_value) = {
+            let orderbooks = self.orderbooks.read().await;
+            orderbooks
+                .get(&market.id)
+                .map(|orderbook| {
+                    orderbook
+                        .user_orders(&order.user_address)
+                        .filter(|o| Some(o.id) != replacing)
+                        .fold((0usize, 0u128), |(count, value), o| {
+                            let remaining = o.size - o.filled_size;
+                            (
+                                count + 1,
+                                value.saturating_add(o.price.saturating_mul(remaining)),
+                            )
+                        })
+                })
+                .unwrap_or_default()
+        };
+
+        if let Some(limit) = market.max_open_orders_per_user {
+            if open_orders >= limit as usize {
+                return Err(ExchangeError::OpenOrderLimitExceeded {
+                    market_id: market.id.clone(),
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = market.max_open_notional_per_user {
+            // notional = (price_atoms * size_atoms) / 10^base_decimals, same as the quote lock
+            let base_token = self.db.get_token(&market.base_ticker).await?;
+            let divisor = 10u128.pow(base_token.decimals as u32);
+            let notional = open_value
+                .checked_add(order.price.saturating_mul(order.size))
+                .map(|v| v / divisor)
+                .unwrap_or(u128::MAX);
+            if notional > limit {
+                return Err(ExchangeError::OpenNotionalLimitExceeded {
+                    market_id: market.id.clone(),
+                    limit,
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -583,6 +1581,18 @@ impl MatchingEngine {
         &self,
         order: &crate::models::domain::Order,
         market: &crate::models::domain::Market,
+    ) -> Result<(String, u128), ExchangeError> {
+        self.calculate_lock_amount_for_size(order, market, order.size)
+            .await
+    }
+
+    /// Calculate which token and amount is locked for `size` of an order
+    /// Used for the full order on placement and for unfilled remainders on unlock
+    async fn calculate_lock_amount_for_size(
+        &self,
+        order: &crate::models::domain::Order,
+        market: &crate::models::domain::Market,
+        size: u128,
     ) -> Result<(String, u128), ExchangeError> {
         match order.side {
             crate::models::domain::Side::Buy => {
@@ -592,7 +1602,7 @@ impl MatchingEngine {
                 let divisor = 10u128.pow(base_token.decimals as u32);
                 let quote_amount = order
                     .price
-                    .checked_mul(order.size)
+                    .checked_mul(size)
                     .and_then(|v| v.checked_div(divisor))
                     .ok_or_else(|| ExchangeError::InvalidParameter {
                         message: "Order value overflow when calculating lock amount".to_string(),
@@ -601,7 +1611,7 @@ impl MatchingEngine {
             }
             crate::models::domain::Side::Sell => {
                 // For sell orders, lock base tokens
-                Ok((market.base_ticker.clone(), order.size))
+                Ok((market.base_ticker.clone(), size))
             }
         }
     }
@@ -5,7 +5,10 @@ use std::collections::HashMap;
 use std::collections::VecDeque;
 
 use crate::errors::{ExchangeError, Result};
-use crate::models::domain::{Market, Order, OrderStatus, OrderbookLevel, OrderbookSnapshot, Side};
+use crate::models::domain::{
+    BookIndicators, LiquidityBucket, Market, Match, Order, OrderStatus, OrderbookLevel,
+    OrderbookSnapshot, PersistedOrderbook, Side,
+};
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -54,6 +57,13 @@ impl Orderbooks {
         Err(ExchangeError::OrderNotFound)
     }
 
+    /// Find a resting order across all markets without removing it
+    pub fn find_order(&self, order_id: Uuid) -> Option<&Order> {
+        self.orderbooks
+            .values()
+            .find_map(|orderbook| orderbook.get_order(order_id))
+    }
+
     /// Cancel all orders for a user, optionally filtered by market
     /// Returns a vector of all cancelled orders
     pub fn cancel_all_orders(&mut self, user_address: &str, market_id: Option<&str>) -> Vec<Order> {
@@ -74,6 +84,19 @@ impl Orderbooks {
         cancelled_orders
     }
 
+    /// Cancel every resting order in every market
+    pub fn cancel_everything(&mut self) -> Vec<Order> {
+        self.orderbooks
+            .values_mut()
+            .flat_map(|orderbook| orderbook.remove_all_orders())
+            .collect()
+    }
+
+    /// Get the orderbook for a market, if it has one
+    pub fn get(&self, market_id: &str) -> Option<&Orderbook> {
+        self.orderbooks.get(market_id)
+    }
+
     /// Generate snapshots for all markets
     pub fn snapshots(&self) -> Vec<OrderbookSnapshot> {
         self.orderbooks
@@ -81,12 +104,44 @@ impl Orderbooks {
             .map(|orderbook| orderbook.snapshot())
             .collect()
     }
+
+    /// Resting orders of every market, for persisting
+    /// `request_sequence` is the last request log entry the books reflect
+    pub fn persisted(&self, request_sequence: Option<i64>) -> Vec<PersistedOrderbook> {
+        let saved_at = Utc::now();
+        self.orderbooks
+            .values()
+            .map(|orderbook| PersistedOrderbook {
+                market_id: orderbook.market_id.clone(),
+                orders: orderbook.orders().cloned().collect(),
+                request_sequence,
+                saved_at,
+            })
+            .collect()
+    }
+
+    /// Replace a market's book with a persisted one
+    pub fn restore(&mut self, persisted: PersistedOrderbook) {
+        let orderbook = Orderbook::from_orders(persisted.market_id.clone(), persisted.orders);
+        self.orderbooks.insert(persisted.market_id, orderbook);
+    }
+
+    /// Generate a snapshot for a single market
+    /// Markets without any orders yet return an empty snapshot
+    pub fn snapshot(&self, market_id: &str) -> OrderbookSnapshot {
+        match self.orderbooks.get(market_id) {
+            Some(orderbook) => orderbook.snapshot(),
+            None => Orderbook::new(market_id.to_string()).snapshot(),
+        }
+    }
 }
 
 pub struct Orderbook {
     pub market_id: String,
-    pub bids: BTreeMap<u128, VecDeque<Order>>, // Descending price (highest first)
+    pub bids: BTreeMap<u128, VecDeque<Order>>, // Ascending keys, iterate in reverse for highest first
     pub asks: BTreeMap<u128, VecDeque<Order>>, // Ascending price (lowest first)
+    // order id -> (side, price) so cancels and fills find their level without scanning
+    order_index: HashMap<Uuid, (Side, u128)>,
 }
 
 impl Orderbook {
@@ -95,7 +150,41 @@ impl Orderbook {
             market_id,
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
+            order_index: HashMap::new(),
+        }
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<u128, VecDeque<Order>> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Number of resting orders across both sides
+    pub fn order_count(&self) -> usize {
+        self.order_index.len()
+    }
+
+    /// Rebuild a book from resting orders, e.g. a persisted snapshot
+    /// Orders are added in the given order, which sets time priority within each level
+    pub fn from_orders(market_id: String, orders: impl IntoIterator<Item = Order>) -> Self {
+        let mut orderbook = Self::new(market_id);
+        for order in orders {
+            orderbook.add_order(order);
         }
+        orderbook
+    }
+
+    /// All resting orders level by level (in time priority within a level), bids first then asks
+    pub fn orders(&self) -> impl Iterator<Item = &Order> {
+        self.bids.values().chain(self.asks.values()).flatten()
+    }
+
+    /// Resting orders owned by a user, bids first then asks
+    pub fn user_orders<'a>(&'a self, user_address: &'a str) -> impl Iterator<Item = &'a Order> {
+        self.orders()
+            .filter(move |order| order.user_address == user_address)
     }
 
     /// Apply executed trades to the orderbook
@@ -108,18 +197,43 @@ impl Orderbook {
         trades: &[crate::models::domain::Trade],
         market: &Market,
     ) {
-        // Update maker orders that were executed
         // Determine maker order ID based on taker side
-        for trade in trades {
+        let fills = trades.iter().map(|trade| {
             let maker_order_id = match taker_order.side {
                 Side::Buy => trade.seller_order_id, // Taker is buyer, maker is seller
                 Side::Sell => trade.buyer_order_id, // Taker is seller, maker is buyer
             };
-            self.update_order_fill(maker_order_id, trade.size);
+            (maker_order_id, trade.size)
+        });
+        self.apply_fills(taker_order, fills, market);
+    }
+
+    /// Apply matches straight from the matcher, without executing them
+    /// Same book changes as `apply_trades`, for rebuilding a book from logged orders
+    pub fn apply_matches(&mut self, taker_order: &Order, matches: &[Match], market: &Market) {
+        let fills = matches.iter().map(|m| (m.maker_order.id, m.size));
+        self.apply_fills(taker_order, fills, market);
+    }
+
+    /// Fill maker orders by (order id, size) and rest whatever is left of the taker
+    fn apply_fills(
+        &mut self,
+        taker_order: &Order,
+        fills: impl Iterator<Item = (Uuid, u128)>,
+        market: &Market,
+    ) {
+        // Update maker orders that were executed
+        let mut total_matched = 0;
+        for (maker_order_id, size) in fills {
+            self.update_order_fill(maker_order_id, size);
+            total_matched += size;
         }
 
         // Add taker order to book if not fully filled
-        let total_matched: u128 = trades.iter().map(|t| t.size).sum();
+        // Immediate-or-cancel orders (market and IOC limit) never rest
+        if taker_order.is_immediate_or_cancel() {
+            return;
+        }
         let remaining_size = taker_order.size - total_matched;
 
         // Only add to book if remaining size meets minimum order size
@@ -139,53 +253,76 @@ impl Orderbook {
 
     /// Update an order's filled amount, remove if fully filled
     fn update_order_fill(&mut self, order_id: Uuid, fill_size: u128) {
-        // Search both bids and asks
-        for (_, orders) in self.bids.iter_mut().chain(self.asks.iter_mut()) {
-            if let Some(pos) = orders.iter().position(|o| o.id == order_id) {
-                let order = &mut orders[pos];
-                order.filled_size += fill_size;
-                order.updated_at = Utc::now();
-
-                // Remove if fully filled
-                if order.filled_size >= order.size {
-                    order.status = OrderStatus::Filled;
-                    orders.remove(pos);
-                }
-                return;
+        let Some(&(side, price)) = self.order_index.get(&order_id) else {
+            return;
+        };
+        let levels = self.levels_mut(side);
+        let Some(orders) = levels.get_mut(&price) else {
+            return;
+        };
+        let Some(pos) = orders.iter().position(|o| o.id == order_id) else {
+            return;
+        };
+
+        let order = &mut orders[pos];
+        order.filled_size += fill_size;
+        order.updated_at = Utc::now();
+
+        // Remove if fully filled
+        if order.filled_size >= order.size {
+            order.status = OrderStatus::Filled;
+            orders.remove(pos);
+            if orders.is_empty() {
+                levels.remove(&price);
             }
+            self.order_index.remove(&order_id);
         }
     }
 
+    /// Lowest ask price, if any
+    pub fn best_ask(&self) -> Option<u128> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Highest bid price, if any
+    pub fn best_bid(&self) -> Option<u128> {
+        self.bids.keys().next_back().copied()
+    }
+
     /// Add an order to the orderbook
     pub fn add_order(&mut self, order: Order) {
-        let levels = match order.side {
-            Side::Buy => &mut self.bids,
-            Side::Sell => &mut self.asks,
-        };
+        self.order_index.insert(order.id, (order.side, order.price));
 
-        levels
+        self.levels_mut(order.side)
             .entry(order.price)
-            .or_insert_with(VecDeque::new)
+            .or_default()
             .push_back(order);
     }
 
+    /// Look up a resting order by ID
+    pub fn get_order(&self, order_id: Uuid) -> Option<&Order> {
+        let (side, price) = self.order_index.get(&order_id)?;
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels.get(price)?.iter().find(|o| o.id == order_id)
+    }
+
     /// Remove an order from the orderbook by ID (for cancellation)
+    /// Drops the price level once its last order is removed
     pub fn remove_order(&mut self, order_id: Uuid) -> Option<Order> {
-        // Search bids
-        for (_, orders) in self.bids.iter_mut() {
-            if let Some(pos) = orders.iter().position(|o| o.id == order_id) {
-                return Some(orders.remove(pos).unwrap());
-            }
-        }
+        let (side, price) = self.order_index.remove(&order_id)?;
+        let levels = self.levels_mut(side);
+        let orders = levels.get_mut(&price)?;
+        let pos = orders.iter().position(|o| o.id == order_id)?;
+        let order = orders.remove(pos);
 
-        // Search asks
-        for (_, orders) in self.asks.iter_mut() {
-            if let Some(pos) = orders.iter().position(|o| o.id == order_id) {
-                return Some(orders.remove(pos).unwrap());
-            }
+        if orders.is_empty() {
+            levels.remove(&price);
         }
 
-        None
+        order
     }
 
     /// Remove all orders for a specific user from this orderbook
@@ -193,37 +330,36 @@ impl Orderbook {
     pub fn remove_all_user_orders(&mut self, user_address: &str) -> Vec<Order> {
         let mut removed_orders = Vec::new();
 
-        // Remove from bids
-        for (_, orders) in self.bids.iter_mut() {
-            let mut i = 0;
-            while i < orders.len() {
-                if orders[i].user_address == user_address {
-                    if let Some(order) = orders.remove(i) {
-                        removed_orders.push(order);
-                    }
-                } else {
-                    i += 1;
-                }
-            }
+        // Bids first, then asks, each level in FIFO order
+        for levels in [&mut self.bids, &mut self.asks] {
+            levels.retain(|_, orders| {
+                let (removed, kept): (VecDeque<Order>, VecDeque<Order>) = orders
+                    .drain(..)
+                    .partition(|order| order.user_address == user_address);
+                removed_orders.extend(removed);
+                *orders = kept;
+                !orders.is_empty()
+            });
         }
 
-        // Remove from asks
-        for (_, orders) in self.asks.iter_mut() {
-            let mut i = 0;
-            while i < orders.len() {
-                if orders[i].user_address == user_address {
-                    if let Some(order) = orders.remove(i) {
-                        removed_orders.push(order);
-                    }
-                } else {
-                    i += 1;
-                }
-            }
+        for order in &removed_orders {
+            self.order_index.remove(&order.id);
         }
 
         removed_orders
     }
 
+    /// Remove every resting order, bids first then asks
+    pub fn remove_all_orders(&mut self) -> Vec<Order> {
+        let removed_orders: Vec<Order> = std::mem::take(&mut self.bids)
+            .into_values()
+            .chain(std::mem::take(&mut self.asks).into_values())
+            .flatten()
+            .collect();
+        self.order_index.clear();
+        removed_orders
+    }
+
     /// Generate a snapshot of the current orderbook state
     pub fn snapshot(&self) -> OrderbookSnapshot {
         // Aggregate bids by price level (highest to lowest)
@@ -255,11 +391,70 @@ impl Orderbook {
             .filter(|level| level.size > 0) // Only include levels with size
             .collect();
 
+        let checksum = OrderbookSnapshot::compute_checksum(&bids, &asks);
+
         OrderbookSnapshot {
             market_id: self.market_id.clone(),
             bids,
             asks,
+            checksum,
             timestamp: Utc::now(),
         }
     }
+
+    /// Resting size per bucket across [from_price, to_price)
+    ///
+    /// The range is split into `buckets` contiguous buckets of near-equal
+    /// width, capped at one bucket per price atom. An empty range returns no buckets
+    pub fn liquidity_profile(
+        &self,
+        from_price: u128,
+        to_price: u128,
+        buckets: u32,
+    ) -> Vec<LiquidityBucket> {
+        if from_price >= to_price || buckets == 0 {
+            return Vec::new();
+        }
+
+        let span = to_price - from_price;
+        let count = (buckets as u128).min(span);
+        let mut profile: Vec<LiquidityBucket> = (0..count)
+            .map(|i| LiquidityBucket {
+                price_low: from_price + span * i / count,
+                price_high: from_price + span * (i + 1) / count,
+                bid_size: 0,
+                ask_size: 0,
+            })
+            .collect();
+
+        for (price, orders) in self.bids.range(from_price..to_price) {
+            let i = bucket_index(&profile, *price);
+            profile[i].bid_size += orders.iter().map(|o| o.size - o.filled_size).sum::<u128>();
+        }
+        for (price, orders) in self.asks.range(from_price..to_price) {
+            let i = bucket_index(&profile, *price);
+            profile[i].ask_size += orders.iter().map(|o| o.size - o.filled_size).sum::<u128>();
+        }
+
+        profile
+    }
+
+    /// Best prices and resting volume over the top `depth` levels of each side
+    pub fn indicators(&self, depth: usize) -> BookIndicators {
+        let level_size = |orders: &VecDeque<Order>| -> u128 {
+            orders.iter().map(|o| o.size - o.filled_size).sum()
+        };
+
+        BookIndicators {
+            best_bid: self.best_bid(),
+            best_ask: self.best_ask(),
+            bid_volume: self.bids.values().rev().take(depth).map(level_size).sum(),
+            ask_volume: self.asks.values().take(depth).map(level_size).sum(),
+        }
+    }
+}
+
+/// Index of the bucket containing `price`, which must lie within the profile's range
+fn bucket_index(profile: &[LiquidityBucket], price: u128) -> usize {
+    profile.partition_point(|bucket| bucket.price_low <= price) - 1
 }
@@ -0,0 +1,69 @@
+use crate::errors::{ExchangeError, Result};
+use crate::models::api::EngineQueueMetrics;
+use crate::models::domain::{EngineRequest, QueueFullPolicy};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Sending side of the engine request queue
+///
+/// Applies the configured policy when the queue is full and counts how
+/// often that happens, so saturation shows up in /api/metrics
+#[derive(Clone)]
+pub struct EngineQueue {
+    tx: mpsc::Sender<EngineRequest>,
+    policy: QueueFullPolicy,
+    rejected: Arc<AtomicU64>,
+    blocked: Arc<AtomicU64>,
+}
+
+impl EngineQueue {
+    pub fn new(tx: mpsc::Sender<EngineRequest>, policy: QueueFullPolicy) -> Self {
+        Self {
+            tx,
+            policy,
+            rejected: Arc::new(AtomicU64::new(0)),
+            blocked: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Queue a request for the engine
+    /// A full queue either fails with EngineBusy or waits, depending on the policy
+    pub async fn send(&self, request: EngineRequest) -> Result<()> {
+        match self.tx.try_send(request) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Closed(_)) => Err(ExchangeError::EngineSendFailed),
+            Err(TrySendError::Full(request)) => match self.policy {
+                QueueFullPolicy::Reject => {
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                    Err(ExchangeError::EngineBusy)
+                }
+                QueueFullPolicy::Block => {
+                    self.blocked.fetch_add(1, Ordering::Relaxed);
+                    self.tx
+                        .send(request)
+                        .await
+                        .map_err(|_| ExchangeError::EngineSendFailed)
+                }
+            },
+        }
+    }
+
+    /// Whether the engine has stopped receiving requests
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Current depth and saturation counters
+    pub fn metrics(&self) -> EngineQueueMetrics {
+        let capacity = self.tx.max_capacity();
+        EngineQueueMetrics {
+            capacity,
+            depth: capacity - self.tx.capacity(),
+            policy: self.policy,
+            rejected: self.rejected.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+        }
+    }
+}
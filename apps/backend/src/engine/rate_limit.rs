@@ -0,0 +1,61 @@
+// Per-user token buckets for cancel requests
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Limits how many cancels each user may send per second
+///
+/// Each user has a bucket holding up to `per_second` tokens, refilled at
+/// `per_second` tokens a second, so a user can burst a full second's worth
+/// of cancels and is then held to the steady rate
+pub struct CancelLimiter {
+    per_second: u32,
+    buckets: HashMap<String, Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl CancelLimiter {
+    /// `per_second` of 0 disables the limit
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.per_second
+    }
+
+    /// Take a token for one cancel by `user_address`, false if the user is over the limit
+    pub fn try_acquire(&mut self, user_address: &str) -> bool {
+        if self.per_second == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let capacity = self.per_second as f64;
+        let bucket = self
+            .buckets
+            .entry(user_address.to_string())
+            .or_insert(Bucket {
+                tokens: capacity,
+                refilled_at: now,
+            });
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * capacity).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// Per-market workers: requests for different markets are applied concurrently
+
+use super::{Admitted, MatchingEngine};
+use crate::models::domain::EngineRequest;
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Sequence of the last request log entry reflected in the books
+///
+/// With market workers requests finish out of order, so the books only reflect
+/// entries below the oldest one still being applied. Recovery replays everything
+/// after this sequence, so it must never run ahead of an unapplied entry
+#[derive(Default)]
+pub struct AppliedSequence {
+    state: Mutex<SequenceState>,
+}
+
+#[derive(Default)]
+struct SequenceState {
+    in_flight: BTreeSet<i64>,
+    highest: i64,
+}
+
+impl AppliedSequence {
+    /// Record a logged request that is about to be applied
+    pub fn start(&self, sequence: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight.insert(sequence);
+        state.highest = state.highest.max(sequence);
+    }
+
+    /// Record that a logged request has been applied
+    pub fn finish(&self, sequence: i64) {
+        self.state.lock().unwrap().in_flight.remove(&sequence);
+    }
+
+    /// Every logged request up to the returned sequence is applied, `None` before any
+    pub fn applied(&self) -> Option<i64> {
+        let state = self.state.lock().unwrap();
+        let applied = match state.in_flight.first() {
+            Some(oldest) => oldest - 1,
+            None => state.highest,
+        };
+        (applied > 0).then_some(applied)
+    }
+}
+
+enum WorkerMessage {
+    Request(Box<Admitted>),
+    // Answered once every earlier request for the market is applied
+    Drain(oneshot::Sender<()>),
+}
+
+struct Worker {
+    tx: mpsc::Sender<WorkerMessage>,
+    handle: JoinHandle<()>,
+}
+
+impl MatchingEngine {
+    /// Route admitted requests to one worker task per market
+    ///
+    /// Each worker applies its market's requests in arrival order. Requests that
+    /// may touch any market wait until every worker has drained, then run here
+    /// while the workers are idle
+    pub(super) async fn run_market_workers(
+        &mut self,
+        mut engine_rx: mpsc::Receiver<EngineRequest>,
+    ) {
+        let mut workers: HashMap<String, Worker> = HashMap::new();
+
+        while let Some(request) = engine_rx.recv().await {
+            let Some(admitted) = self.admit(request).await else {
+                continue;
+            };
+
+            match self.worker_market(&admitted.request).await {
+                Some(market_id) => {
+                    let worker = workers
+                        .entry(market_id)
+                        .or_insert_with(|| self.spawn_worker());
+                    if let Err(mpsc::error::SendError(WorkerMessage::Request(admitted))) = worker
+                        .tx
+                        .send(WorkerMessage::Request(Box::new(admitted)))
+                        .await
+                    {
+                        // The worker only stops if it panicked, apply the request here instead
+                        self.process(*admitted).await;
+                    }
+                }
+                None => {
+                    Self::drain(&workers).await;
+                    self.process(admitted).await;
+                }
+            }
+        }
+
+        // Let every worker finish what it was sent
+        for (_, worker) in workers {
+            drop(worker.tx);
+            let _ = worker.handle.await;
+        }
+    }
+
+    /// Market whose worker should apply `request`, `None` to apply it across all markets
+    /// A cancel goes to the market its order rests in. One that isn't resting may
+    /// still be queued behind its own placement, so it waits for every worker
+    async fn worker_market(&self, request: &EngineRequest) -> Option<String> {
+        match request {
+            EngineRequest::CancelOrder { order_id, .. } => self
+                .orderbooks
+                .read()
+                .await
+                .find_order(*order_id)
+                .map(|order| order.market_id.clone()),
+            request => request.market_id().map(str::to_string),
+        }
+    }
+
+    fn spawn_worker(&self) -> Worker {
+        let mut engine = self.market_worker();
+        let (tx, mut rx) = mpsc::channel(self.config.request_queue_size.max(1));
+
+        let handle = tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                match message {
+                    WorkerMessage::Request(admitted) => engine.process(*admitted).await,
+                    WorkerMessage::Drain(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+
+        Worker { tx, handle }
+    }
+
+    /// Engine sharing this one's books, database and events, fed by the dispatcher
+    fn market_worker(&self) -> MatchingEngine {
+        MatchingEngine {
+            db: self.db.clone(),
+            config: self.config.clone(),
+            orderbooks: self.orderbooks.clone(),
+            applied_sequence: self.applied_sequence.clone(),
+            cancel_limiter: super::CancelLimiter::new(0), // Throttled by the dispatcher
+            engine_rx: None,
+            event_tx: self.event_tx.clone(),
+        }
+    }
+
+    /// Wait until every worker has applied what it was sent
+    async fn drain(workers: &HashMap<String, Worker>) {
+        let mut pending = Vec::with_capacity(workers.len());
+        for worker in workers.values() {
+            let (done_tx, done_rx) = oneshot::channel();
+            if worker.tx.send(WorkerMessage::Drain(done_tx)).await.is_ok() {
+                pending.push(done_rx);
+            }
+        }
+        for done in pending {
+            let _ = done.await;
+        }
+    }
+}
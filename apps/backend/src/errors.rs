@@ -1,8 +1,9 @@
 use axum::{
+    extract::rejection::JsonRejection,
     http::StatusCode,
     response::{IntoResponse, Json, Response},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use utoipa::ToSchema;
 
@@ -12,12 +13,24 @@ pub enum ExchangeError {
     #[error("Token '{ticker}' does not exist")]
     TokenNotFound { ticker: String },
 
+    #[error("Token '{ticker}' already exists")]
+    TokenAlreadyExists { ticker: String },
+
+    #[error("Token decimals {decimals} exceed the maximum of {max}")]
+    InvalidTokenDecimals { decimals: u8, max: u8 },
+
     #[error("Market '{market_id}' does not exist")]
     MarketNotFound { market_id: String },
 
     #[error("Market '{market_id}' already exists")]
     MarketAlreadyExists { market_id: String },
 
+    #[error("Market '{market_id}' is {status} and does not accept this order")]
+    MarketNotOpen {
+        market_id: String,
+        status: crate::models::domain::MarketStatus,
+    },
+
     #[error("Invalid parameter: {message}")]
     InvalidParameter { message: String },
 
@@ -33,14 +46,17 @@ pub enum ExchangeError {
     #[error("Order value overflow or division error")]
     OrderValueOverflow,
 
-    #[error("Price does not meet tick size requirement")]
-    InvalidTickSize,
+    #[error("Price {price} is not a multiple of tick size {tick_size}")]
+    InvalidTickSize { price: u128, tick_size: u128 },
 
-    #[error("Size does not meet lot size requirement")]
-    InvalidLotSize,
+    #[error("Size {size} is not a multiple of lot size {lot_size}")]
+    InvalidLotSize { size: u128, lot_size: u128 },
 
-    #[error("Size is below minimum required")]
-    SizeBelowMinimum,
+    #[error("Size {size} is below minimum order size {min_size}")]
+    SizeBelowMinimum { size: u128, min_size: u128 },
+
+    #[error("Order notional {notional} is below market minimum notional {min_notional}")]
+    NotionalBelowMinimum { notional: u128, min_notional: u128 },
 
     #[error("Insufficient balance for user '{user_address}' token '{token_ticker}': required {required}")]
     InsufficientBalance {
@@ -49,9 +65,27 @@ pub enum ExchangeError {
         required: u128,
     },
 
+    #[error("Open order limit of {limit} reached in market '{market_id}'")]
+    OpenOrderLimitExceeded { market_id: String, limit: u32 },
+
+    #[error("Open notional limit of {limit} exceeded in market '{market_id}'")]
+    OpenNotionalLimitExceeded { market_id: String, limit: u128 },
+
+    #[error("Post-only order would cross the book at {price}")]
+    PostOnlyWouldCross { price: u128 },
+
+    #[error("Order would only trade against the same user's orders")]
+    SelfTradeRejected,
+
     #[error("Order not found")]
     OrderNotFound,
 
+    #[error("Order '{order_id}' is {status} and cannot be cancelled")]
+    OrderNotCancellable {
+        order_id: uuid::Uuid,
+        status: crate::models::domain::OrderStatus,
+    },
+
     #[error("User '{address}' not found")]
     UserNotFound { address: String },
 
@@ -61,9 +95,23 @@ pub enum ExchangeError {
     #[error("Failed to receive response from engine")]
     EngineReceiveFailed,
 
+    #[error("Engine is busy, try again later")]
+    EngineBusy,
+
+    #[error("Cancel rate limit of {limit} per second exceeded")]
+    CancelRateLimited { limit: u32 },
+
     #[error("Failed to unlock balance")]
     UnlockFailed,
 
+    #[error("Trade {trade_id} changed the total {token_ticker} held by its parties from {before} to {after}")]
+    BalanceInvariantViolated {
+        trade_id: uuid::Uuid,
+        token_ticker: String,
+        before: i128,
+        after: i128,
+    },
+
     // Infrastructure errors (5xx) - auto-converted
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
@@ -76,14 +124,49 @@ pub enum ExchangeError {
 
     #[error("UUID parse error: {0}")]
     UuidParseError(#[from] uuid::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Invalid request body: {}", .0.body_text())]
+    InvalidRequestBody(#[from] JsonRejection),
 }
 
 pub type Result<T> = std::result::Result<T, ExchangeError>;
 
-#[derive(Debug, Serialize, ToSchema)]
+/// Body of every REST error response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
+    /// Human readable description of the error
     pub error: String,
+    /// Stable machine readable error code, e.g. "MARKET_NOT_FOUND"
     pub code: String,
+    /// Why an order was rejected, only set for order rejections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<RejectReason>,
+}
+
+/// Why the exchange refused an order, for clients to react to without parsing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RejectReason {
+    /// Price is not a multiple of the market's tick size
+    TickSize,
+    /// Size is not a multiple of the market's lot size
+    LotSize,
+    /// Size is below the market's minimum order size
+    MinSize,
+    /// Price * size is below the market's minimum notional
+    MinNotional,
+    InsufficientBalance,
+    /// The market is halted, closed, or pre-open and can't take this order
+    MarketHalted,
+    /// A post-only order would have taken liquidity
+    PostOnlyCross,
+    /// The order would have traded against the same user's order
+    SelfTrade,
+    /// The user sent more requests than their limit allows
+    RateLimited,
 }
 
 impl ExchangeError {
@@ -91,26 +174,40 @@ impl ExchangeError {
     fn error_code(&self) -> &'static str {
         match self {
             ExchangeError::TokenNotFound { .. } => "TOKEN_NOT_FOUND",
+            ExchangeError::TokenAlreadyExists { .. } => "TOKEN_ALREADY_EXISTS",
+            ExchangeError::InvalidTokenDecimals { .. } => "INVALID_TOKEN_DECIMALS",
             ExchangeError::MarketNotFound { .. } => "MARKET_NOT_FOUND",
             ExchangeError::MarketAlreadyExists { .. } => "MARKET_ALREADY_EXISTS",
+            ExchangeError::MarketNotOpen { .. } => "MARKET_NOT_OPEN",
             ExchangeError::InvalidParameter { .. } => "INVALID_PARAMETER",
             ExchangeError::InvalidPrice => "INVALID_PRICE",
             ExchangeError::InvalidSize => "INVALID_SIZE",
             ExchangeError::InvalidAmount => "INVALID_AMOUNT",
             ExchangeError::OrderValueOverflow => "ORDER_VALUE_OVERFLOW",
-            ExchangeError::InvalidTickSize => "INVALID_TICK_SIZE",
-            ExchangeError::InvalidLotSize => "INVALID_LOT_SIZE",
-            ExchangeError::SizeBelowMinimum => "SIZE_BELOW_MINIMUM",
+            ExchangeError::InvalidTickSize { .. } => "INVALID_TICK_SIZE",
+            ExchangeError::InvalidLotSize { .. } => "INVALID_LOT_SIZE",
+            ExchangeError::SizeBelowMinimum { .. } => "SIZE_BELOW_MINIMUM",
+            ExchangeError::NotionalBelowMinimum { .. } => "NOTIONAL_BELOW_MINIMUM",
             ExchangeError::InsufficientBalance { .. } => "INSUFFICIENT_BALANCE",
+            ExchangeError::OpenOrderLimitExceeded { .. } => "OPEN_ORDER_LIMIT_EXCEEDED",
+            ExchangeError::OpenNotionalLimitExceeded { .. } => "OPEN_NOTIONAL_LIMIT_EXCEEDED",
+            ExchangeError::PostOnlyWouldCross { .. } => "POST_ONLY_WOULD_CROSS",
+            ExchangeError::SelfTradeRejected => "SELF_TRADE_REJECTED",
             ExchangeError::OrderNotFound => "ORDER_NOT_FOUND",
+            ExchangeError::OrderNotCancellable { .. } => "ORDER_NOT_CANCELLABLE",
             ExchangeError::UserNotFound { .. } => "USER_NOT_FOUND",
             ExchangeError::EngineSendFailed => "ENGINE_SEND_FAILED",
             ExchangeError::EngineReceiveFailed => "ENGINE_RECEIVE_FAILED",
+            ExchangeError::EngineBusy => "ENGINE_BUSY",
+            ExchangeError::CancelRateLimited { .. } => "CANCEL_RATE_LIMITED",
             ExchangeError::UnlockFailed => "UNLOCK_FAILED",
+            ExchangeError::BalanceInvariantViolated { .. } => "BALANCE_INVARIANT_VIOLATED",
             ExchangeError::Database(_) => "DATABASE_ERROR",
             ExchangeError::ClickHouse(_) => "CLICKHOUSE_ERROR",
             ExchangeError::ParseError(_) => "PARSE_ERROR",
             ExchangeError::UuidParseError(_) => "UUID_PARSE_ERROR",
+            ExchangeError::Serialization(_) => "SERIALIZATION_ERROR",
+            ExchangeError::InvalidRequestBody(_) => "INVALID_REQUEST_BODY",
         }
     }
 
@@ -123,23 +220,53 @@ impl ExchangeError {
             ExchangeError::OrderNotFound => StatusCode::NOT_FOUND,
             ExchangeError::UserNotFound { .. } => StatusCode::NOT_FOUND,
             ExchangeError::MarketAlreadyExists { .. } => StatusCode::CONFLICT,
+            ExchangeError::MarketNotOpen { .. } => StatusCode::CONFLICT,
+            ExchangeError::OrderNotCancellable { .. } => StatusCode::CONFLICT,
+            ExchangeError::PostOnlyWouldCross { .. } => StatusCode::CONFLICT,
+            ExchangeError::SelfTradeRejected => StatusCode::CONFLICT,
+            ExchangeError::TokenAlreadyExists { .. } => StatusCode::CONFLICT,
+            ExchangeError::InvalidTokenDecimals { .. } => StatusCode::BAD_REQUEST,
             ExchangeError::InvalidParameter { .. } => StatusCode::BAD_REQUEST,
             ExchangeError::InvalidPrice => StatusCode::BAD_REQUEST,
             ExchangeError::InvalidSize => StatusCode::BAD_REQUEST,
             ExchangeError::InvalidAmount => StatusCode::BAD_REQUEST,
             ExchangeError::OrderValueOverflow => StatusCode::BAD_REQUEST,
-            ExchangeError::InvalidTickSize => StatusCode::BAD_REQUEST,
-            ExchangeError::InvalidLotSize => StatusCode::BAD_REQUEST,
-            ExchangeError::SizeBelowMinimum => StatusCode::BAD_REQUEST,
+            ExchangeError::InvalidTickSize { .. } => StatusCode::BAD_REQUEST,
+            ExchangeError::InvalidLotSize { .. } => StatusCode::BAD_REQUEST,
+            ExchangeError::SizeBelowMinimum { .. } => StatusCode::BAD_REQUEST,
+            ExchangeError::NotionalBelowMinimum { .. } => StatusCode::BAD_REQUEST,
             ExchangeError::InsufficientBalance { .. } => StatusCode::BAD_REQUEST,
+            ExchangeError::OpenOrderLimitExceeded { .. } => StatusCode::BAD_REQUEST,
+            ExchangeError::OpenNotionalLimitExceeded { .. } => StatusCode::BAD_REQUEST,
             ExchangeError::ParseError(_) => StatusCode::BAD_REQUEST,
             ExchangeError::UuidParseError(_) => StatusCode::BAD_REQUEST,
+            ExchangeError::InvalidRequestBody(_) => StatusCode::BAD_REQUEST,
             // Server errors
             ExchangeError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ExchangeError::ClickHouse(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ExchangeError::Serialization(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ExchangeError::EngineSendFailed => StatusCode::INTERNAL_SERVER_ERROR,
             ExchangeError::EngineReceiveFailed => StatusCode::INTERNAL_SERVER_ERROR,
+            ExchangeError::EngineBusy => StatusCode::SERVICE_UNAVAILABLE,
+            ExchangeError::CancelRateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
             ExchangeError::UnlockFailed => StatusCode::INTERNAL_SERVER_ERROR,
+            ExchangeError::BalanceInvariantViolated { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Why an order was rejected, None for errors that aren't order rejections
+    pub fn reject_reason(&self) -> Option<RejectReason> {
+        match self {
+            ExchangeError::InvalidTickSize { .. } => Some(RejectReason::TickSize),
+            ExchangeError::InvalidLotSize { .. } => Some(RejectReason::LotSize),
+            ExchangeError::SizeBelowMinimum { .. } => Some(RejectReason::MinSize),
+            ExchangeError::NotionalBelowMinimum { .. } => Some(RejectReason::MinNotional),
+            ExchangeError::InsufficientBalance { .. } => Some(RejectReason::InsufficientBalance),
+            ExchangeError::MarketNotOpen { .. } => Some(RejectReason::MarketHalted),
+            ExchangeError::PostOnlyWouldCross { .. } => Some(RejectReason::PostOnlyCross),
+            ExchangeError::SelfTradeRejected => Some(RejectReason::SelfTrade),
+            ExchangeError::CancelRateLimited { .. } => Some(RejectReason::RateLimited),
+            _ => None,
         }
     }
 }
@@ -159,12 +286,17 @@ impl IntoResponse for ExchangeError {
                 log::error!("ClickHouse error: {}", e);
                 "Internal server error".to_string()
             }
+            ExchangeError::BalanceInvariantViolated { .. } => {
+                log::error!("{}", self);
+                "Internal server error".to_string()
+            }
             _ => self.to_string(),
         };
 
         let body = Json(ErrorResponse {
             error: error_message,
             code: error_code.to_string(),
+            reason: self.reject_reason(),
         });
 
         (status, body).into_response()
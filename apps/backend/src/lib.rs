@@ -1,4 +1,5 @@
 pub mod api;
+pub mod auth;
 pub mod config;
 pub mod db;
 pub mod engine;
@@ -6,14 +7,18 @@ pub mod errors;
 pub mod models;
 pub mod utils;
 
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::broadcast;
 
-use crate::models::domain::{EngineEvent, EngineRequest};
+use crate::engine::queue::EngineQueue;
+use crate::models::domain::EngineEvent;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState {
     pub db: db::Db,
-    pub engine_tx: mpsc::Sender<EngineRequest>,
+    pub engine: EngineQueue,
     pub event_tx: broadcast::Sender<EngineEvent>,
+    pub ws_config: config::WebSocketConfig,
+    pub market_feed: api::ws::MarketFeed,
+    pub ws_metrics: api::ws::WsMetrics,
 }
@@ -4,6 +4,7 @@ use backend::api::rest;
 use backend::api::ws;
 use backend::config::Config;
 use backend::db::Db;
+use backend::engine::queue::EngineQueue;
 use backend::engine::MatchingEngine;
 use backend::models::domain::{EngineEvent, EngineRequest};
 use backend::AppState;
@@ -16,7 +17,16 @@ async fn main() -> anyhow::Result<()> {
     let _ = dotenvy::from_path(".env.defaults");
     let _ = dotenvy::from_path_override(".env");
 
-    env_logger::init();
+    // `--check-config [path]` validates a config and exits before touching any database
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--check-config") {
+        return check_config(args.next());
+    }
+
+    // Collects both `log` records and `tracing` spans, filtered by RUST_LOG
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
 
     // ===============================
     // Load configuration
@@ -29,6 +39,19 @@ async fn main() -> anyhow::Result<()> {
     log::info!("Starting exchange with configuration:");
     log::info!("  Markets: {}", config.markets.len());
     log::info!("  Tokens: {}", config.tokens.len());
+    log::info!(
+        "  Match price policy: {:?}",
+        config.engine.match_price_policy
+    );
+    log::info!(
+        "  Engine request queue: {} ({:?} when full)",
+        config.engine.request_queue_size,
+        config.engine.queue_full_policy
+    );
+    log::info!(
+        "  Max WS subscriptions per connection: {}",
+        config.websocket.max_subscriptions_per_connection
+    );
 
     // ===============================
     // Connect to databases
@@ -41,13 +64,15 @@ async fn main() -> anyhow::Result<()> {
     // ===============================
     // Create engine channels
     // ===============================
-    let (engine_tx, engine_rx) = mpsc::channel::<EngineRequest>(100);
+    let (engine_tx, engine_rx) = mpsc::channel::<EngineRequest>(config.engine.request_queue_size);
+    let engine_queue = EngineQueue::new(engine_tx, config.engine.queue_full_policy);
     let (event_tx, _) = broadcast::channel::<EngineEvent>(1000); // use event_tx to create more listeners
 
     // ===============================
     // Run matching engine
     // ===============================
-    let engine = MatchingEngine::new(db.clone(), engine_rx, event_tx.clone());
+    let engine =
+        MatchingEngine::with_config(db.clone(), engine_rx, event_tx.clone(), config.engine);
 
     tokio::spawn(async move {
         engine.run().await;
@@ -58,10 +83,14 @@ async fn main() -> anyhow::Result<()> {
     // ===============================
     let rest = rest::create_rest();
     let ws = ws::create_ws();
+    let market_feed = ws::MarketFeed::new(&event_tx, config.websocket.replay_buffer_size);
     let state = AppState {
         db,
-        engine_tx,
+        engine: engine_queue,
         event_tx,
+        ws_config: config.websocket,
+        market_feed,
+        ws_metrics: ws::WsMetrics::new(),
     };
 
     let app = Router::new()
@@ -86,3 +115,29 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Validate the tokens and markets of a config file (config.toml by default)
+fn check_config(path: Option<String>) -> anyhow::Result<()> {
+    let config = match &path {
+        Some(path) => Config::load_from(path),
+        None => Config::load(),
+    }
+    .context("Failed to load configuration")?;
+
+    match config.validate() {
+        Ok(()) => {
+            println!(
+                "✅ Config OK: {} tokens, {} markets",
+                config.tokens.len(),
+                config.markets.len()
+            );
+            Ok(())
+        }
+        Err(problems) => {
+            for problem in &problems {
+                println!("  ✗ {}", problem);
+            }
+            anyhow::bail!("Config has {} problem(s)", problems.len())
+        }
+    }
+}
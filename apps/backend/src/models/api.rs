@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::domain::{OrderStatus, OrderType, Side, Token};
+use super::domain::{
+    FeeSchedule, MarketStatus, OrderStatus, OrderType, QueueFullPolicy, Side, TimeInForce, Token,
+};
 
 // ============================================================================
 // REST API TYPES
@@ -16,11 +18,83 @@ pub struct ApiResponse {
     pub timestamp: u64,
 }
 
+/// Status of a single backend dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Up,
+    Down,
+}
+
+/// Overall health, degraded when any dependency is down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverallStatus {
+    Ok,
+    Degraded,
+}
+
+/// Health check response with per-subsystem status
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthStatus {
+    pub status: OverallStatus,
+    pub postgres: ComponentStatus,
+    pub clickhouse: ComponentStatus,
+    pub engine: ComponentStatus,
+    pub timestamp: u64, // Unix timestamp
+}
+
+impl HealthStatus {
+    pub fn new(
+        postgres: ComponentStatus,
+        clickhouse: ComponentStatus,
+        engine: ComponentStatus,
+    ) -> Self {
+        let all_up = [postgres, clickhouse, engine]
+            .iter()
+            .all(|status| *status == ComponentStatus::Up);
+
+        Self {
+            status: if all_up {
+                OverallStatus::Ok
+            } else {
+                OverallStatus::Degraded
+            },
+            postgres,
+            clickhouse,
+            engine,
+            timestamp: chrono::Utc::now().timestamp() as u64,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status == OverallStatus::Ok
+    }
+
+    /// Names of the subsystems that are down
+    pub fn failing_subsystems(&self) -> Vec<&'static str> {
+        [
+            ("postgres", self.postgres),
+            ("clickhouse", self.clickhouse),
+            ("engine", self.engine),
+        ]
+        .into_iter()
+        .filter(|(_, status)| *status == ComponentStatus::Down)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
 /// Response after successfully placing an order
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OrderPlaced {
     pub order: ApiOrder,
     pub trades: Vec<ApiTrade>,
+    pub average_price: String, // u128 as string, size-weighted fill price ("0" if nothing filled)
+    pub total_quote: String,   // u128 as string, quote atoms exchanged across all fills
+    /// Price as submitted when the market snapped it to the tick, order.price holds the snapped price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_price: Option<String>, // u128 as string
 }
 
 /// Response after successfully cancelling an order
@@ -34,6 +108,23 @@ pub struct OrderCancelled {
 pub struct OrdersCancelled {
     pub cancelled_order_ids: Vec<String>, // UUIDs as strings for OpenAPI compatibility
     pub count: usize,
+    #[serde(default)]
+    pub refunds: Vec<OrderRefund>,
+}
+
+/// Response after atomically replacing a resting order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderReplaced {
+    pub cancelled: OrderCancelled,
+    pub placed: OrderPlaced,
+}
+
+/// Locked funds released back to the user when an order was cancelled
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderRefund {
+    pub order_id: String, // UUID as string for OpenAPI compatibility
+    pub token: String,
+    pub amount: String, // u128 as string
 }
 
 // ============================================================================
@@ -44,20 +135,91 @@ pub struct OrdersCancelled {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InfoRequest {
-    TokenDetails { ticker: String },
-    MarketDetails { market_id: String },
+    TokenDetails {
+        ticker: String,
+    },
+    MarketDetails {
+        market_id: String,
+    },
+    /// A market together with its base and quote tokens
+    MarketFull {
+        market_id: String,
+    },
     AllMarkets,
     AllTokens,
+    /// Just the listed markets, ids that don't exist are left out
+    Markets {
+        market_ids: Vec<String>,
+    },
+    /// Just the listed tokens, tickers that don't exist are left out
+    Tokens {
+        tickers: Vec<String>,
+    },
 }
 
 /// Info response with type discriminator
+// Built once per request and serialized straight away, so boxing buys nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InfoResponse {
-    TokenDetails { token: Token },
-    MarketDetails { market: ApiMarket },
-    AllMarkets { markets: Vec<ApiMarket> },
-    AllTokens { tokens: Vec<Token> },
+    TokenDetails {
+        token: Token,
+    },
+    MarketDetails {
+        market: ApiMarket,
+    },
+    MarketFull {
+        market: ApiMarket,
+        base_token: Token,
+        quote_token: Token,
+    },
+    AllMarkets {
+        markets: Vec<ApiMarket>,
+    },
+    AllTokens {
+        tokens: Vec<Token>,
+    },
+    Markets {
+        markets: Vec<ApiMarket>,
+    },
+    Tokens {
+        tokens: Vec<Token>,
+    },
+}
+
+/// Order of market search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum MarketSortBy {
+    /// By market id, A to Z
+    #[default]
+    #[serde(rename = "alphabetical")]
+    Alphabetical,
+    /// By quote volume over the last 24 hours, highest first
+    #[serde(rename = "volume_24h")]
+    Volume24h,
+}
+
+/// Filtered, sorted and paged market listing
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct MarketSearchRequest {
+    #[serde(default)]
+    pub quote_ticker: Option<String>, // Exact match
+    #[serde(default)]
+    pub base_ticker: Option<String>, // Case-insensitive substring
+    #[serde(default)]
+    pub sort_by: MarketSortBy,
+    #[serde(default)]
+    pub limit: Option<u32>, // Defaults to 100, at most 500
+    #[serde(default)]
+    pub offset: Option<u32>,
+}
+
+/// One page of matching markets
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MarketSearchResponse {
+    pub markets: Vec<ApiMarket>,
+    pub total: usize, // Matching markets across all pages
 }
 
 // ============================================================================
@@ -74,6 +236,11 @@ pub enum UserRequest {
         status: Option<String>,
         limit: Option<u32>,
     },
+    /// A single order owned by the user
+    Order {
+        user_address: String,
+        order_id: String,
+    },
     Balances {
         user_address: String,
     },
@@ -82,6 +249,22 @@ pub enum UserRequest {
         market_id: Option<String>,
         limit: Option<u32>,
     },
+    /// Finished (filled or cancelled) orders, most recently finished first
+    OrderHistory {
+        user_address: String,
+        #[serde(default)]
+        market_id: Option<String>,
+        #[serde(default)]
+        from: Option<i64>, // Unix timestamp in seconds, inclusive
+        #[serde(default)]
+        to: Option<i64>, // Unix timestamp in seconds, exclusive
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    /// Net position and average entry per market the user has traded
+    Positions {
+        user_address: String,
+    },
 }
 
 /// User response with type discriminator
@@ -89,8 +272,11 @@ pub enum UserRequest {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum UserResponse {
     Orders { orders: Vec<ApiOrder> },
+    Order { order: ApiOrder },
     Balances { balances: Vec<ApiBalance> },
     Trades { trades: Vec<ApiTrade> },
+    OrderHistory { orders: Vec<ApiOrder> },
+    Positions { positions: Vec<ApiPosition> },
 }
 
 // ============================================================================
@@ -106,9 +292,15 @@ pub enum TradeRequest {
         market_id: String,
         side: Side,
         order_type: OrderType,
+        #[serde(default)]
+        time_in_force: Option<TimeInForce>, // Defaults to gtc for limit, ioc for market orders
         price: String,     // u128 as string
         size: String,      // u128 as string
         signature: String, // Cryptographic signature for authentication
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_order_id: Option<String>, // Idempotency key, a retry with the same key returns the original order
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_fill: Option<String>, // u128 as string, cancel without executing unless this much fills at once
     },
     CancelOrder {
         user_address: String,
@@ -120,6 +312,19 @@ pub enum TradeRequest {
         market_id: Option<String>, // Optional: cancel only for specific market
         signature: String,         // Cryptographic signature for authentication
     },
+    /// Cancel a resting order and place a new one in its market atomically
+    ReplaceOrder {
+        user_address: String,
+        order_id: String, // UUID of the resting order to replace
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        #[serde(default)]
+        time_in_force: Option<TimeInForce>, // Defaults to gtc for limit, ioc for market orders
+        price: String,     // u128 as string
+        size: String,      // u128 as string
+        signature: String, // Cryptographic signature for authentication
+    },
 }
 
 /// Trade response with type discriminator
@@ -129,6 +334,10 @@ pub enum TradeResponse {
     PlaceOrder {
         order: ApiOrder,
         trades: Vec<ApiTrade>,
+        average_price: String, // u128 as string
+        total_quote: String,   // u128 as string
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        requested_price: Option<String>, // u128 as string, set when the price was snapped to the tick
     },
     CancelOrder {
         order_id: String,
@@ -136,9 +345,52 @@ pub enum TradeResponse {
     CancelAllOrders {
         cancelled_order_ids: Vec<String>,
         count: usize,
+        #[serde(default)]
+        refunds: Vec<OrderRefund>,
+    },
+    ReplaceOrder {
+        cancelled_order_id: String,
+        order: ApiOrder,
+        trades: Vec<ApiTrade>,
+        average_price: String, // u128 as string
+        total_quote: String,   // u128 as string
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        requested_price: Option<String>, // u128 as string, set when the price was snapped to the tick
     },
 }
 
+// ============================================================================
+// ESTIMATE API TYPES
+// ============================================================================
+
+/// Request to simulate an order against the live orderbook without placing it
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EstimateRequest {
+    #[serde(default)]
+    pub user_address: Option<String>, // Skips the user's own resting orders, like a real placement
+    pub market_id: String,
+    pub side: Side,
+    pub order_type: OrderType,
+    #[serde(default)]
+    pub time_in_force: Option<TimeInForce>, // Defaults to gtc for limit, ioc for market orders
+    pub price: String, // u128 as string (ignored for market orders)
+    pub size: String,  // u128 as string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fill: Option<String>, // u128 as string, nothing fills unless this much would fill at once
+}
+
+/// Projected outcome of an order if it were placed now
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EstimateResponse {
+    pub market_id: String,
+    pub fillable_size: String,         // u128 as string
+    pub average_price: Option<String>, // u128 as string, None if nothing would fill
+    pub best_price: Option<String>,    // Best opposite-side price (BBO) before the order
+    pub slippage_bps: Option<f64>,     // Average price vs BBO, positive is worse for the taker
+    pub levels_consumed: usize,
+    pub resting_size: String, // u128 as string, size that would rest on the book
+}
+
 // ============================================================================
 // DRIP API TYPES
 // ============================================================================
@@ -188,6 +440,10 @@ pub enum AdminRequest {
         min_size: String,  // u128 as string
         maker_fee_bps: i32,
         taker_fee_bps: i32,
+        #[serde(default)]
+        min_price: Option<String>, // u128 as string
+        #[serde(default)]
+        max_price: Option<String>, // u128 as string
     },
     Faucet {
         user_address: String,
@@ -195,6 +451,46 @@ pub enum AdminRequest {
         amount: String,
         signature: String,
     },
+    /// Apply many faucet grants at once, all or none
+    BulkFaucet { grants: Vec<FaucetGrant> },
+    SetUserFeeTier {
+        user_address: String,
+        maker_fee_tenth_bps: i32, // tenths of a basis point (1 = 0.001%)
+        taker_fee_tenth_bps: i32, // tenths of a basis point (1 = 0.001%)
+    },
+    SetMarketOrderLimits {
+        market_id: String,
+        #[serde(default)]
+        max_open_orders_per_user: Option<u32>,
+        #[serde(default)]
+        max_open_notional_per_user: Option<String>, // u128 as string, quote atoms
+    },
+    SetMarketMinNotional {
+        market_id: String,
+        #[serde(default)]
+        min_notional: Option<String>, // u128 as string, quote atoms
+    },
+    /// Round off-tick limit prices to the nearest tick instead of rejecting them
+    SetMarketSnapToTick {
+        market_id: String,
+        snap_to_tick: bool,
+    },
+    /// Emergency kill switch: cancel every resting order in every market
+    CancelEverything,
+    /// Move a market to another trading phase; opening a pre-open market matches
+    /// the orders it collected
+    SetMarketStatus {
+        market_id: String,
+        status: MarketStatus,
+    },
+}
+
+/// Tokens credited to one user by a bulk faucet
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FaucetGrant {
+    pub user_address: String,
+    pub token_ticker: String,
+    pub amount: String, // u128 as string
 }
 
 /// Admin response with type discriminator
@@ -213,6 +509,28 @@ pub enum AdminResponse {
         amount: String,
         new_balance: String,
     },
+    BulkFaucet {
+        balances: Vec<ApiBalance>, // Balance after the grants, one per user-token pair
+    },
+    SetUserFeeTier {
+        user_address: String,
+        fee_schedule: FeeSchedule,
+    },
+    SetMarketOrderLimits {
+        market: ApiMarket,
+    },
+    SetMarketMinNotional {
+        market: ApiMarket,
+    },
+    SetMarketSnapToTick {
+        market: ApiMarket,
+    },
+    CancelEverything {
+        count: usize, // Orders cancelled across all users and markets
+    },
+    SetMarketStatus {
+        market: ApiMarket,
+    },
 }
 
 // ============================================================================
@@ -223,7 +541,7 @@ pub enum AdminResponse {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CandlesRequest {
     pub market_id: String,
-    pub interval: String, // 1m, 5m, 15m, 1h, 1d
+    pub interval: String, // 1m, 5m, 15m, 1h, 4h, 1d, 1w
     pub from: i64,        // Unix timestamp in seconds
     pub to: i64,          // Unix timestamp in seconds
     #[serde(default)]
@@ -231,7 +549,7 @@ pub struct CandlesRequest {
 }
 
 /// OHLCV candle data
-#[derive(Debug, Serialize, Deserialize, clickhouse::Row, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, clickhouse::Row, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiCandle {
     pub timestamp: u32,
@@ -248,6 +566,163 @@ pub struct CandlesResponse {
     pub candles: Vec<ApiCandle>,
 }
 
+// ============================================================================
+// TRADE TAPE API TYPES
+// ============================================================================
+
+/// Request for a market's public trades, most recent first
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TradesRequest {
+    pub market_id: String,
+    #[serde(default)]
+    pub side: Option<Side>, // Taker's side
+    #[serde(default)]
+    pub min_size: Option<String>, // u128 as string, base atoms
+    #[serde(default)]
+    pub limit: Option<u32>, // Defaults to 100, at most 1000
+    #[serde(default)]
+    pub from: Option<i64>, // Unix timestamp in seconds, inclusive
+    #[serde(default)]
+    pub to: Option<i64>, // Unix timestamp in seconds, exclusive
+    #[serde(default)]
+    pub cursor: Option<String>, // next_cursor of the previous page
+}
+
+/// Response containing public trades
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TradesResponse {
+    pub trades: Vec<ApiTrade>,
+    /// Pass as `cursor` to get the next (older) page, None on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+// ============================================================================
+// STATS API TYPES
+// ============================================================================
+
+/// Request for the volume-weighted average price over a time window
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VwapRequest {
+    pub market_id: String,
+    pub from: i64, // Unix timestamp in seconds, inclusive
+    pub to: i64,   // Unix timestamp in seconds, exclusive
+}
+
+/// Volume-weighted average price and traded volume over a time window
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VwapResponse {
+    pub market_id: String,
+    pub from: i64,
+    pub to: i64,
+    pub vwap: Option<String>, // u128 as string, None if no trades in the window
+    pub base_volume: String,  // u128 as string
+    pub quote_volume: String, // u128 as string
+    pub trade_count: u64,
+}
+
+/// Request for resting liquidity across a price range
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LiquidityRequest {
+    pub market_id: String,
+    pub from_price: String, // u128 as string, inclusive
+    pub to_price: String,   // u128 as string, exclusive
+    pub buckets: u32,
+}
+
+/// Resting liquidity per price bucket, empty when the range is empty
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LiquidityResponse {
+    pub market_id: String,
+    pub buckets: Vec<ApiLiquidityBucket>,
+}
+
+/// Resting bid and ask size within [price_low, price_high)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiLiquidityBucket {
+    pub price_low: String,  // u128 as string
+    pub price_high: String, // u128 as string
+    pub bid_size: String,   // u128 as string
+    pub ask_size: String,   // u128 as string
+}
+
+/// Request for top-of-book microstructure indicators
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MicrostructureRequest {
+    pub market_id: String,
+    #[serde(default)]
+    pub depth: Option<u32>, // Levels per side counted towards imbalance, defaults to 10
+}
+
+/// Spread, mid price and order-book imbalance from the live book
+/// Spread and mid price are null unless both sides have orders
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MicrostructureResponse {
+    pub market_id: String,
+    pub depth: u32,
+    pub best_bid: Option<String>,  // u128 as string
+    pub best_ask: Option<String>,  // u128 as string
+    pub spread: Option<String>,    // u128 as string, best ask - best bid
+    pub spread_bps: Option<f64>,   // Spread relative to the mid price
+    pub mid_price: Option<String>, // u128 as string, rounded down
+    pub bid_volume: String,        // u128 as string, over the top `depth` bid levels
+    pub ask_volume: String,        // u128 as string, over the top `depth` ask levels
+    pub imbalance: Option<f64>,    // bid_volume / (bid_volume + ask_volume), null for an empty book
+}
+
+/// Request for a market's orderbook as it stood at a past time
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HistoricalBookRequest {
+    pub market_id: String,
+    pub timestamp: i64, // Unix timestamp in seconds
+}
+
+/// Orderbook rebuilt from the persisted snapshot and request log, empty before any activity
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HistoricalBookResponse {
+    pub market_id: String,
+    pub timestamp: i64,
+    pub bids: Vec<ApiBookLevel>, // Highest price first
+    pub asks: Vec<ApiBookLevel>, // Lowest price first
+    pub checksum: u32,           // Same checksum as live orderbook snapshots
+}
+
+/// Total resting size at one price
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiBookLevel {
+    pub price: String, // u128 as string
+    pub size: String,  // u128 as string
+}
+
+// ============================================================================
+// METRICS API TYPES
+// ============================================================================
+
+/// Runtime metrics for the backend
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MetricsResponse {
+    pub engine_queue: EngineQueueMetrics,
+    pub websocket: WebSocketMetrics,
+}
+
+/// Engine request queue depth and saturation counters
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EngineQueueMetrics {
+    pub capacity: usize,
+    pub depth: usize, // Requests waiting for the engine
+    pub policy: QueueFullPolicy,
+    pub rejected: u64, // Requests failed with 503 because the queue was full
+    pub blocked: u64,  // Requests that had to wait for a free slot
+}
+
+/// WebSocket connection count and heartbeat disconnects
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebSocketMetrics {
+    pub active_connections: usize,
+    pub pong_timeouts: u64,    // Clients dropped for not answering pings
+    pub idle_disconnects: u64, // Clients dropped for staying unsubscribed
+}
+
 // ============================================================================
 // WEBSOCKET MESSAGE TYPES (Client → Server)
 // ============================================================================
@@ -269,18 +744,39 @@ pub enum ClientMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         user_address: Option<String>,
     },
+    /// Ask for a one-time challenge to sign, answered with `AuthChallenge`
+    AuthChallenge,
+    /// Bind the connection to a user; user channels then default to (and are limited to) it
+    /// `signature` is the user's EIP-191 signature over the latest challenge's message
+    Auth {
+        user_address: String,
+        signature: String, // 0x-prefixed hex, 65 bytes r || s || v
+    },
     Ping,
+    /// Ask for the connection's active subscriptions, answered with `Subscriptions`
+    ListSubscriptions,
+    /// Subscribe to a market's trades or orderbook and replay what was sent after `after_seq`
+    /// Answered with `Resumed`, then the missed messages in order
+    Resume {
+        channel: SubscriptionChannel,
+        market_id: String,
+        after_seq: u64,
+    },
 }
 
 /// Channel types for WebSocket subscriptions
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum SubscriptionChannel {
     Trades,
+    AllTrades, // Trades in every market, no market_id
     Orderbook,
     UserFills,
     UserOrders,
     UserBalances,
+    MarketStatus, // Trading phase changes, market_id optional (None = all markets)
 }
 
 // ============================================================================
@@ -335,18 +831,55 @@ pub enum ServerMessage {
     UserBalance {
         user_address: String,
         token_ticker: String,
-        available: String,
-        locked: String,
-        updated_at: i64, // Unix timestamp
+        amount: String,        // u128 as string, total including locked
+        open_interest: String, // u128 as string, locked by resting orders
+        available: String,     // amount - open_interest
+        locked: String,        // same as open_interest
+        updated_at: i64,       // Unix timestamp
+    },
+
+    // Market status changes, also sent with the current status on subscribe
+    MarketStatus {
+        market_id: String,
+        status: MarketStatus,
     },
 
     // Connection management
+    /// Reply to AuthChallenge: sign `message` (which embeds `nonce`) and send it back in Auth
+    AuthChallenge {
+        nonce: String,
+        message: String,
+    },
+    Authenticated {
+        user_address: String,
+    },
+    Subscriptions {
+        subscriptions: Vec<ActiveSubscription>,
+    },
+    /// Reply to Resume; `snapshot` means the requested sequence was no longer
+    /// buffered, so the messages that follow are the current state, not a gap-free replay
+    Resumed {
+        channel: SubscriptionChannel,
+        market_id: String,
+        replayed: usize,
+        snapshot: bool,
+    },
     Error {
         message: String,
     },
     Pong,
 }
 
+/// A subscription held by the connection, with the arguments it was resolved to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+pub struct ActiveSubscription {
+    pub channel: SubscriptionChannel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_address: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct PriceLevel {
     pub price: String,
@@ -358,6 +891,17 @@ pub struct OrderbookData {
     pub market_id: String,
     pub bids: Vec<PriceLevel>,
     pub asks: Vec<PriceLevel>,
+    /// CRC32 of the top 25 levels per side, as `price:size` pairs joined by `,`
+    /// with bids and asks separated by `|` (see OrderbookSnapshot::compute_checksum)
+    pub checksum: u32,
+    /// True for the book sent in reply to a subscribe: replace any local state with it.
+    /// False for the updates pushed afterwards. Updates currently carry the full book
+    /// as well, but only a message with this flag set is guaranteed to be complete
+    #[serde(default)]
+    pub is_snapshot: bool,
+    /// Market sequence number, shared with the market's trades (see ClientMessage::Resume)
+    #[serde(default)]
+    pub seq: u64,
 }
 
 /// Trade data for WebSocket messages (API layer with String fields)
@@ -373,6 +917,9 @@ pub struct TradeData {
     pub size: String,            // u128 as string
     pub side: Side,              // Taker's side (determines if trade is "buy" or "sell" on tape)
     pub timestamp: i64,          // Unix timestamp for WebSocket compatibility
+    /// Market sequence number, shared with the market's orderbook updates
+    #[serde(default)]
+    pub seq: u64,
 }
 
 // ============================================================================
@@ -390,6 +937,22 @@ pub struct ApiMarket {
     pub min_size: String,  // u128 as string
     pub maker_fee_bps: i32,
     pub taker_fee_bps: i32,
+    #[serde(default)]
+    pub min_price: Option<String>, // u128 as string
+    #[serde(default)]
+    pub max_price: Option<String>, // u128 as string
+    #[serde(default)]
+    pub max_open_orders_per_user: Option<u32>,
+    #[serde(default)]
+    pub max_open_notional_per_user: Option<String>, // u128 as string
+    #[serde(default)]
+    pub min_notional: Option<String>, // u128 as string
+    #[serde(default)]
+    pub last_price: Option<String>, // u128 as string
+    #[serde(default)]
+    pub snap_to_tick: bool,
+    #[serde(default)]
+    pub status: MarketStatus,
 }
 
 /// API representation of Order with String fields for JSON compatibility
@@ -402,6 +965,8 @@ pub struct ApiOrder {
     pub size: String,  // u128 as string
     pub side: Side,
     pub order_type: OrderType,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
     pub status: OrderStatus,
     pub filled_size: String, // u128 as string
     pub created_at: DateTime<Utc>,
@@ -421,6 +986,8 @@ pub struct ApiTrade {
     pub size: String,              // u128 as string
     pub side: super::domain::Side, // Taker's side (determines if trade is "buy" or "sell" on tape)
     pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub sequence: u64, // Execution order within the market
 }
 
 /// API representation of Balance with String fields for JSON compatibility
@@ -428,11 +995,22 @@ pub struct ApiTrade {
 pub struct ApiBalance {
     pub user_address: String,
     pub token_ticker: String,
-    pub amount: String,        // u128 as string
-    pub open_interest: String, // u128 as string
+    pub amount: String,        // u128 as string, total including locked
+    pub open_interest: String, // u128 as string, locked by resting orders
+    pub available: String,     // amount - open_interest
+    pub locked: String,        // same as open_interest
     pub updated_at: DateTime<Utc>,
 }
 
+/// API representation of Position with String fields for JSON compatibility
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiPosition {
+    pub user_address: String,
+    pub market_id: String,
+    pub size: String,                    // i128 as string, negative when short
+    pub avg_entry_price: Option<String>, // u128 as string, null when flat
+}
+
 // Conversion implementations from domain to API types
 impl From<super::domain::Market> for ApiMarket {
     fn from(m: super::domain::Market) -> Self {
@@ -445,6 +1023,14 @@ impl From<super::domain::Market> for ApiMarket {
             min_size: m.min_size.to_string(),
             maker_fee_bps: m.maker_fee_bps,
             taker_fee_bps: m.taker_fee_bps,
+            min_price: m.min_price.map(|p| p.to_string()),
+            max_price: m.max_price.map(|p| p.to_string()),
+            max_open_orders_per_user: m.max_open_orders_per_user,
+            max_open_notional_per_user: m.max_open_notional_per_user.map(|n| n.to_string()),
+            min_notional: m.min_notional.map(|n| n.to_string()),
+            last_price: m.last_price.map(|p| p.to_string()),
+            snap_to_tick: m.snap_to_tick,
+            status: m.status,
         }
     }
 }
@@ -459,6 +1045,7 @@ impl From<super::domain::Order> for ApiOrder {
             size: o.size.to_string(),
             side: o.side,
             order_type: o.order_type,
+            time_in_force: o.time_in_force,
             status: o.status,
             filled_size: o.filled_size.to_string(),
             created_at: o.created_at,
@@ -480,6 +1067,7 @@ impl From<super::domain::Trade> for ApiTrade {
             size: t.size.to_string(),
             side: t.side,
             timestamp: t.timestamp,
+            sequence: t.sequence,
         }
     }
 }
@@ -487,6 +1075,8 @@ impl From<super::domain::Trade> for ApiTrade {
 impl From<super::domain::Balance> for ApiBalance {
     fn from(b: super::domain::Balance) -> Self {
         Self {
+            available: b.available().to_string(),
+            locked: b.open_interest.to_string(),
             user_address: b.user_address,
             token_ticker: b.token_ticker,
             amount: b.amount.to_string(),
@@ -496,38 +1086,99 @@ impl From<super::domain::Balance> for ApiBalance {
     }
 }
 
+impl From<super::domain::Position> for ApiPosition {
+    fn from(p: super::domain::Position) -> Self {
+        Self {
+            user_address: p.user_address,
+            market_id: p.market_id,
+            size: p.size.to_string(),
+            avg_entry_price: p.avg_entry_price.map(|p| p.to_string()),
+        }
+    }
+}
+
 // Reverse conversions from API to domain types (for SDK)
+
+/// A string-encoded field of an API type that doesn't parse
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid {field} {value:?}: {reason}")]
+pub struct FieldParseError {
+    pub field: &'static str,
+    pub value: String,
+    pub reason: String,
+}
+
+/// Parse a u128 sent as a string, naming the field when it is malformed
+pub fn parse_u128_field(value: &str, field: &'static str) -> Result<u128, FieldParseError> {
+    value
+        .parse()
+        .map_err(|e: std::num::ParseIntError| FieldParseError {
+            field,
+            value: value.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Parse a UUID sent as a string, naming the field when it is malformed
+fn parse_uuid_field(value: &str, field: &'static str) -> Result<Uuid, FieldParseError> {
+    Uuid::parse_str(value).map_err(|e| FieldParseError {
+        field,
+        value: value.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+fn parse_optional_u128_field(
+    value: Option<String>,
+    field: &'static str,
+) -> Result<Option<u128>, FieldParseError> {
+    value.map(|v| parse_u128_field(&v, field)).transpose()
+}
+
 impl TryFrom<ApiMarket> for super::domain::Market {
-    type Error = std::num::ParseIntError;
+    type Error = FieldParseError;
 
     fn try_from(m: ApiMarket) -> Result<Self, Self::Error> {
         Ok(Self {
             id: m.id,
             base_ticker: m.base_ticker,
             quote_ticker: m.quote_ticker,
-            tick_size: m.tick_size.parse()?,
-            lot_size: m.lot_size.parse()?,
-            min_size: m.min_size.parse()?,
+            tick_size: parse_u128_field(&m.tick_size, "tick_size")?,
+            lot_size: parse_u128_field(&m.lot_size, "lot_size")?,
+            min_size: parse_u128_field(&m.min_size, "min_size")?,
             maker_fee_bps: m.maker_fee_bps,
             taker_fee_bps: m.taker_fee_bps,
+            min_price: parse_optional_u128_field(m.min_price, "min_price")?,
+            max_price: parse_optional_u128_field(m.max_price, "max_price")?,
+            max_open_orders_per_user: m.max_open_orders_per_user,
+            max_open_notional_per_user: parse_optional_u128_field(
+                m.max_open_notional_per_user,
+                "max_open_notional_per_user",
+            )?,
+            min_notional: parse_optional_u128_field(m.min_notional, "min_notional")?,
+            last_price: parse_optional_u128_field(m.last_price, "last_price")?,
+            snap_to_tick: m.snap_to_tick,
+            status: m.status,
         })
     }
 }
 
 impl TryFrom<ApiOrder> for super::domain::Order {
-    type Error = Box<dyn std::error::Error>;
+    type Error = FieldParseError;
 
     fn try_from(o: ApiOrder) -> Result<Self, Self::Error> {
         Ok(Self {
-            id: Uuid::parse_str(&o.id)?,
+            id: parse_uuid_field(&o.id, "id")?,
             user_address: o.user_address,
             market_id: o.market_id,
-            price: o.price.parse()?,
-            size: o.size.parse()?,
+            price: parse_u128_field(&o.price, "price")?,
+            size: parse_u128_field(&o.size, "size")?,
             side: o.side,
             order_type: o.order_type,
+            time_in_force: o.time_in_force,
+            min_fill: None,
             status: o.status,
-            filled_size: o.filled_size.parse()?,
+            filled_size: parse_u128_field(&o.filled_size, "filled_size")?,
             created_at: o.created_at,
             updated_at: o.updated_at,
         })
@@ -535,33 +1186,55 @@ impl TryFrom<ApiOrder> for super::domain::Order {
 }
 
 impl TryFrom<ApiTrade> for super::domain::Trade {
-    type Error = Box<dyn std::error::Error>;
+    type Error = FieldParseError;
 
     fn try_from(t: ApiTrade) -> Result<Self, Self::Error> {
         Ok(Self {
-            id: Uuid::parse_str(&t.id)?,
+            id: parse_uuid_field(&t.id, "id")?,
             market_id: t.market_id,
             buyer_address: t.buyer_address,
             seller_address: t.seller_address,
-            buyer_order_id: Uuid::parse_str(&t.buyer_order_id)?,
-            seller_order_id: Uuid::parse_str(&t.seller_order_id)?,
-            price: t.price.parse()?,
-            size: t.size.parse()?,
+            buyer_order_id: parse_uuid_field(&t.buyer_order_id, "buyer_order_id")?,
+            seller_order_id: parse_uuid_field(&t.seller_order_id, "seller_order_id")?,
+            price: parse_u128_field(&t.price, "price")?,
+            size: parse_u128_field(&t.size, "size")?,
             side: t.side,
             timestamp: t.timestamp,
+            sequence: t.sequence,
+        })
+    }
+}
+
+impl TryFrom<ApiPosition> for super::domain::Position {
+    type Error = FieldParseError;
+
+    fn try_from(p: ApiPosition) -> Result<Self, Self::Error> {
+        let size = p
+            .size
+            .parse()
+            .map_err(|e: std::num::ParseIntError| FieldParseError {
+                field: "size",
+                value: p.size.clone(),
+                reason: e.to_string(),
+            })?;
+        Ok(Self {
+            user_address: p.user_address,
+            market_id: p.market_id,
+            size,
+            avg_entry_price: parse_optional_u128_field(p.avg_entry_price, "avg_entry_price")?,
         })
     }
 }
 
 impl TryFrom<ApiBalance> for super::domain::Balance {
-    type Error = std::num::ParseIntError;
+    type Error = FieldParseError;
 
     fn try_from(b: ApiBalance) -> Result<Self, Self::Error> {
         Ok(Self {
             user_address: b.user_address,
             token_ticker: b.token_ticker,
-            amount: b.amount.parse()?,
-            open_interest: b.open_interest.parse()?,
+            amount: parse_u128_field(&b.amount, "amount")?,
+            open_interest: parse_u128_field(&b.open_interest, "open_interest")?,
             updated_at: b.updated_at,
         })
     }
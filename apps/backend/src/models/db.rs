@@ -35,6 +35,14 @@ pub struct MarketRow {
     pub min_size: BigDecimal,
     pub maker_fee_bps: i32,
     pub taker_fee_bps: i32,
+    pub min_price: Option<BigDecimal>,
+    pub max_price: Option<BigDecimal>,
+    pub max_open_orders_per_user: Option<i32>,
+    pub max_open_notional_per_user: Option<BigDecimal>,
+    pub min_notional: Option<BigDecimal>,
+    pub last_price: Option<BigDecimal>,
+    pub snap_to_tick: bool,
+    pub status: String, // Custom type 'market_status' in DB
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -47,6 +55,7 @@ pub struct OrderRow {
     pub side: String, // Custom type 'side' in DB, stored as TEXT
     #[sqlx(rename = "type")]
     pub order_type: String, // Custom type 'order_type' in DB
+    pub time_in_force: String, // Custom type 'time_in_force' in DB
     pub status: String, // Custom type 'order_status' in DB
     pub filled_size: BigDecimal,
     pub created_at: DateTime<Utc>,
@@ -65,6 +74,7 @@ pub struct TradeRow {
     pub size: BigDecimal,
     pub side: String, // "buy" or "sell"
     pub timestamp: DateTime<Utc>,
+    pub sequence: i64,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -89,6 +99,25 @@ pub struct ClickHouseTradeRow {
     pub size: u128,
     pub side: String,   // "buy" or "sell"
     pub timestamp: u32, // Unix timestamp
+    pub sequence: u64,  // Execution order within the market
+}
+
+// Used for summing trade volume from ClickHouse
+// Sums are returned as strings since price * size can exceed UInt128
+#[derive(Debug, Clone, Row, Serialize, Deserialize)]
+pub struct TradeVolumeRow {
+    pub trade_count: u64,
+    pub base_volume: String,
+    pub notional: String,
+}
+
+// Used for summing trade volume of every market in one query
+#[derive(Debug, Clone, Row, Serialize, Deserialize)]
+pub struct MarketTradeVolumeRow {
+    pub market_id: String,
+    pub trade_count: u64,
+    pub base_volume: String,
+    pub notional: String,
 }
 
 // Used for querying aggregated candles from ClickHouse
@@ -138,6 +167,14 @@ impl From<MarketRow> for Market {
             min_size: row.min_size.to_u128(),
             maker_fee_bps: row.maker_fee_bps,
             taker_fee_bps: row.taker_fee_bps,
+            min_price: row.min_price.map(|p| p.to_u128()),
+            max_price: row.max_price.map(|p| p.to_u128()),
+            max_open_orders_per_user: row.max_open_orders_per_user.map(|n| n as u32),
+            max_open_notional_per_user: row.max_open_notional_per_user.map(|n| n.to_u128()),
+            min_notional: row.min_notional.map(|n| n.to_u128()),
+            last_price: row.last_price.map(|p| p.to_u128()),
+            snap_to_tick: row.snap_to_tick,
+            status: row.status.parse().unwrap_or_default(),
         }
     }
 }
@@ -155,6 +192,8 @@ impl From<OrderRow> for Order {
                 .order_type
                 .parse()
                 .unwrap_or(crate::models::domain::OrderType::Limit),
+            time_in_force: row.time_in_force.parse().unwrap_or_default(),
+            min_fill: None,
             status: row
                 .status
                 .parse()
@@ -183,6 +222,7 @@ impl From<TradeRow> for Trade {
                 Side::Sell
             },
             timestamp: row.timestamp,
+            sequence: row.sequence as u64,
         }
     }
 }
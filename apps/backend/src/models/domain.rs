@@ -8,7 +8,9 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::errors::ExchangeError;
-use crate::models::api::{OrderCancelled, OrderPlaced, OrdersCancelled};
+use crate::models::api::{
+    EstimateResponse, OrderCancelled, OrderPlaced, OrderReplaced, OrdersCancelled,
+};
 // ============================================================================
 // ENUMS
 // ============================================================================
@@ -36,6 +38,71 @@ pub enum OrderStatus {
     Cancelled,
 }
 
+/// How long an order may stay on the book
+/// Ioc orders cancel any remainder that does not match immediately, post-only
+/// orders are rejected instead of taking liquidity
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeInForce {
+    #[default]
+    Gtc,
+    Ioc,
+    #[serde(rename = "post_only")]
+    PostOnly,
+}
+
+/// Trading phase of a market
+/// Pre-open markets collect resting limit orders without matching them; crossed
+/// orders match in time priority when the market opens. Halted and closed markets
+/// accept no new orders, while resting orders can still be cancelled
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    PreOpen,
+    #[default]
+    Open,
+    Halted,
+    Closed,
+}
+
+/// Bucket width for OHLCV candles
+/// Weekly buckets start on Monday 00:00 UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleInterval {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+    OneWeek,
+}
+
+/// Execution price for a match between a taker and a resting maker order
+/// Maker executes at the resting order's price
+/// Midpoint splits the spread between the maker price and a more aggressive taker limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchPricePolicy {
+    #[default]
+    Maker,
+    Midpoint,
+}
+
+/// What happens to a request when the engine request queue is full
+/// Reject fails it immediately with 503, Block waits for a free slot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueFullPolicy {
+    Reject,
+    #[default]
+    Block,
+}
+
 // ============================================================================
 // ENUM STRING CONVERSIONS
 // ============================================================================
@@ -119,6 +186,152 @@ impl FromStr for OrderStatus {
     }
 }
 
+impl Display for TimeInForce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TimeInForce::Gtc => "gtc",
+                TimeInForce::Ioc => "ioc",
+                TimeInForce::PostOnly => "post_only",
+            }
+        )
+    }
+}
+
+impl Display for MarketStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                MarketStatus::PreOpen => "pre_open",
+                MarketStatus::Open => "open",
+                MarketStatus::Halted => "halted",
+                MarketStatus::Closed => "closed",
+            }
+        )
+    }
+}
+
+impl FromStr for MarketStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pre_open" => Ok(MarketStatus::PreOpen),
+            "open" => Ok(MarketStatus::Open),
+            "halted" => Ok(MarketStatus::Halted),
+            "closed" => Ok(MarketStatus::Closed),
+            _ => Err(format!("Invalid market status: {}", s)),
+        }
+    }
+}
+
+impl FromStr for TimeInForce {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gtc" => Ok(TimeInForce::Gtc),
+            "ioc" => Ok(TimeInForce::Ioc),
+            "post_only" => Ok(TimeInForce::PostOnly),
+            _ => Err(format!("Invalid time in force: {}", s)),
+        }
+    }
+}
+
+impl TimeInForce {
+    /// Resolve the time in force requested for an order, rejecting invalid combinations
+    /// Unset defaults to gtc for limit orders and ioc for market orders, which never rest
+    pub fn resolve(order_type: OrderType, requested: Option<Self>) -> Result<Self, ExchangeError> {
+        match (order_type, requested) {
+            (OrderType::Market, Some(TimeInForce::Gtc | TimeInForce::PostOnly)) => {
+                Err(ExchangeError::InvalidParameter {
+                    message: "market orders cannot rest on the book, time_in_force must be ioc"
+                        .to_string(),
+                })
+            }
+            (OrderType::Market, _) => Ok(TimeInForce::Ioc),
+            (OrderType::Limit, requested) => Ok(requested.unwrap_or_default()),
+        }
+    }
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 7] = [
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::FifteenMinutes,
+        CandleInterval::OneHour,
+        CandleInterval::FourHours,
+        CandleInterval::OneDay,
+        CandleInterval::OneWeek,
+    ];
+
+    /// Key stored in the `interval` column of the candles table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::FifteenMinutes => "15m",
+            CandleInterval::OneHour => "1h",
+            CandleInterval::FourHours => "4h",
+            CandleInterval::OneDay => "1d",
+            CandleInterval::OneWeek => "1w",
+        }
+    }
+
+    /// Bucket width in seconds
+    pub fn seconds(&self) -> i64 {
+        match self {
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 5 * 60,
+            CandleInterval::FifteenMinutes => 15 * 60,
+            CandleInterval::OneHour => 60 * 60,
+            CandleInterval::FourHours => 4 * 60 * 60,
+            CandleInterval::OneDay => 24 * 60 * 60,
+            CandleInterval::OneWeek => 7 * 24 * 60 * 60,
+        }
+    }
+
+    /// Start of the bucket containing `timestamp` (Unix seconds)
+    /// Matches the bucketing done by the ClickHouse materialized views
+    pub fn bucket_start(&self, timestamp: i64) -> i64 {
+        // The Unix epoch was a Thursday, so weeks are offset to start on Monday
+        let offset = match self {
+            CandleInterval::OneWeek => 4 * 24 * 60 * 60,
+            _ => 0,
+        };
+        (timestamp - offset).div_euclid(self.seconds()) * self.seconds() + offset
+    }
+}
+
+impl Display for CandleInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for CandleInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CandleInterval::ALL
+            .into_iter()
+            .find(|interval| interval.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = CandleInterval::ALL.iter().map(|i| i.as_str()).collect();
+                format!(
+                    "Invalid interval: {}. Must be one of: {}",
+                    s,
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
 // ============================================================================
 // DOMAIN TYPES
 // ============================================================================
@@ -141,11 +354,73 @@ pub struct Market {
     pub id: String, // Generated as "base_ticker/quote_ticker"
     pub base_ticker: String,
     pub quote_ticker: String,
-    pub tick_size: u128,    // Minimum price increment in quote atoms
-    pub lot_size: u128,     // Minimum size increment in base atoms
-    pub min_size: u128,     // Minimum order size in base atoms
-    pub maker_fee_bps: i32, // Maker fee in basis points (0-10000)
-    pub taker_fee_bps: i32, // Taker fee in basis points (0-10000)
+    pub tick_size: u128,         // Minimum price increment in quote atoms
+    pub lot_size: u128,          // Minimum size increment in base atoms
+    pub min_size: u128,          // Minimum order size in base atoms
+    pub maker_fee_bps: i32,      // Maker fee in basis points (0-10000)
+    pub taker_fee_bps: i32,      // Taker fee in basis points (0-10000)
+    pub min_price: Option<u128>, // Lowest accepted limit price in quote atoms
+    pub max_price: Option<u128>, // Highest accepted limit price in quote atoms
+    pub max_open_orders_per_user: Option<u32>, // Resting orders a user may hold in this market
+    pub max_open_notional_per_user: Option<u128>, // Resting notional per user in quote atoms
+    pub min_notional: Option<u128>, // Minimum limit order value (price * size) in quote atoms
+    pub last_price: Option<u128>, // Price of the most recent trade in quote atoms
+    pub snap_to_tick: bool, // Round off-tick limit prices to the nearest tick instead of rejecting
+    pub status: MarketStatus,
+}
+
+impl Market {
+    /// Round a price to the nearest multiple of the tick size, halfway rounds up
+    /// An off-tick price never rounds down to zero, below half a tick it snaps to one tick
+    pub fn snap_price_to_tick(&self, price: u128) -> u128 {
+        let remainder = price % self.tick_size;
+        if remainder == 0 {
+            return price;
+        }
+        let down = price - remainder;
+        match down.checked_add(self.tick_size) {
+            Some(up) if down == 0 || remainder >= self.tick_size - remainder => up,
+            _ => down,
+        }
+    }
+}
+
+/// Largest market fee in basis points (100%), maker rebates are capped at the same size
+pub const MAX_FEE_BPS: i32 = 10_000;
+
+/// Most decimals a token may have, beyond this atom amounts stop fitting comfortably in u128
+pub const MAX_TOKEN_DECIMALS: u8 = 18;
+
+/// Maker/taker fee rates in tenths of a basis point (1 = 0.001%)
+/// Resolved per user: a fee tier override if one is set, otherwise the market defaults
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct FeeSchedule {
+    pub maker_fee_tenth_bps: i32,
+    pub taker_fee_tenth_bps: i32,
+}
+
+impl FeeSchedule {
+    /// Tenths of a basis point in 100%
+    pub const DENOMINATOR: i128 = 100_000;
+
+    /// Market default fees, converted from whole basis points
+    pub fn from_market(market: &Market) -> Self {
+        Self {
+            maker_fee_tenth_bps: market.maker_fee_bps * 10,
+            taker_fee_tenth_bps: market.taker_fee_bps * 10,
+        }
+    }
+
+    /// Fee owed on `amount` at the maker or taker rate (rounded toward zero)
+    /// Negative for a rebate, which is credited instead of charged
+    pub fn fee(&self, amount: u128, is_maker: bool) -> i128 {
+        let rate = if is_maker {
+            self.maker_fee_tenth_bps
+        } else {
+            self.taker_fee_tenth_bps
+        };
+        amount as i128 * rate as i128 / Self::DENOMINATOR
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -157,12 +432,26 @@ pub struct Order {
     pub size: u128,
     pub side: Side,
     pub order_type: OrderType,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// Least base atoms that must fill on placement, otherwise nothing executes and the
+    /// order is cancelled. Only checked when the order is placed, so it isn't stored
+    #[serde(default)]
+    pub min_fill: Option<u128>,
     pub status: OrderStatus,
     pub filled_size: u128,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Order {
+    /// Whether any unfilled remainder is cancelled instead of resting on the book
+    /// Market orders are always immediate-or-cancel
+    pub fn is_immediate_or_cancel(&self) -> bool {
+        self.order_type == OrderType::Market || self.time_in_force == TimeInForce::Ioc
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Trade {
     pub id: Uuid,
@@ -175,17 +464,75 @@ pub struct Trade {
     pub size: u128,
     pub side: Side, // Taker's side (determines if trade is "buy" or "sell" on tape)
     pub timestamp: DateTime<Utc>,
+    pub sequence: u64, // Execution order within the market, assigned by the engine
+}
+
+/// Position in a market's trade tape, the next page holds strictly older trades
+/// Sent to clients as "<unix seconds>:<sequence>:<trade id>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeCursor {
+    pub timestamp: i64,
+    pub sequence: u64,
+    pub id: Uuid,
+}
+
+impl TradeCursor {
+    /// Cursor continuing after `trade`
+    pub fn after(trade: &Trade) -> Self {
+        Self {
+            timestamp: trade.timestamp.timestamp(),
+            sequence: trade.sequence,
+            id: trade.id,
+        }
+    }
 }
 
+impl Display for TradeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.timestamp, self.sequence, self.id)
+    }
+}
+
+impl FromStr for TradeCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid trade cursor: {}", s);
+        let mut parts = s.splitn(3, ':');
+        let (Some(timestamp), Some(sequence), Some(id)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            timestamp: timestamp.parse().map_err(|_| invalid())?,
+            sequence: sequence.parse().map_err(|_| invalid())?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// A user's holdings of one token
+/// `open_interest` is the part of `amount` locked by resting orders: quote notional
+/// (price * remaining / 10^base_decimals) for buys and remaining size for sells.
+/// The engine locks it on placement and releases it on fills and cancels,
+/// so available = amount - open_interest
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Balance {
     pub user_address: String,
     pub token_ticker: String,
-    pub amount: u128,
-    pub open_interest: u128,
+    pub amount: u128,        // Total holdings in token atoms, including locked funds
+    pub open_interest: u128, // Locked by resting orders in token atoms
     pub updated_at: DateTime<Utc>,
 }
 
+impl Balance {
+    /// Atoms free to trade or withdraw, holdings not locked by resting orders
+    pub fn available(&self) -> u128 {
+        self.amount.saturating_sub(self.open_interest)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
     pub market_id: String,
@@ -197,6 +544,35 @@ pub struct Candle {
     pub volume: u128,
 }
 
+/// Traded volume of a market over a time window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeVolume {
+    pub trade_count: u64,
+    pub base_volume: u128, // Sum of trade sizes in base atoms
+    pub notional: u128,    // Sum of price * size, not yet scaled by base decimals
+}
+
+impl TradeVolume {
+    /// Volume-weighted average price in quote atoms, None for an empty window
+    pub fn vwap(&self) -> Option<u128> {
+        self.notional.checked_div(self.base_volume)
+    }
+
+    /// Total quote volume in quote atoms
+    pub fn quote_volume(&self, base_decimals: u8) -> u128 {
+        self.notional / 10u128.pow(base_decimals as u32)
+    }
+}
+
+/// A user's net holding in one market, built up from their fills
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub user_address: String,
+    pub market_id: String,
+    pub size: i128, // Net base atoms: positive long, negative short, 0 flat
+    pub avg_entry_price: Option<u128>, // Average cost of the open size in quote atoms, None when flat
+}
+
 // ============================================================================
 // MATCHING ENGINE TYPES
 // ============================================================================
@@ -209,12 +585,21 @@ pub struct Match {
     pub size: u128,
 }
 
+/// Projected outcome of an order against the current book (nothing is mutated)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillEstimate {
+    pub fillable_size: u128,         // Base atoms that would match immediately
+    pub average_price: Option<u128>, // Size-weighted fill price (None if nothing fills)
+    pub levels_consumed: usize,      // Price levels touched, including a partial last level
+    pub would_rest: bool,            // Whether an unfilled remainder would rest on the book
+}
+
 // ============================================================================
 // ORDERBOOK TYPES
 // ============================================================================
 
 /// Represents a price level in the orderbook
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrderbookLevel {
     pub price: u128,
     pub size: u128,
@@ -226,30 +611,390 @@ pub struct OrderbookSnapshot {
     pub market_id: String,
     pub bids: Vec<OrderbookLevel>, // Sorted by price descending (highest first)
     pub asks: Vec<OrderbookLevel>, // Sorted by price ascending (lowest first)
+    pub checksum: u32,             // See OrderbookSnapshot::compute_checksum
     pub timestamp: DateTime<Utc>,
 }
 
+impl OrderbookSnapshot {
+    /// Number of levels per side covered by the checksum
+    pub const CHECKSUM_DEPTH: usize = 25;
+
+    /// CRC32 (IEEE) over the top CHECKSUM_DEPTH levels of each side
+    ///
+    /// Canonical form is `price:size` pairs in atoms, best level first, joined by `,`,
+    /// with bids and asks separated by `|`, e.g. `"100:5,99:3|101:2"`.
+    /// Clients rebuild this string from their local book to verify it matches the server's
+    pub fn compute_checksum(bids: &[OrderbookLevel], asks: &[OrderbookLevel]) -> u32 {
+        fn side(levels: &[OrderbookLevel]) -> String {
+            levels
+                .iter()
+                .take(OrderbookSnapshot::CHECKSUM_DEPTH)
+                .map(|level| format!("{}:{}", level.price, level.size))
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        let canonical = format!("{}|{}", side(bids), side(asks));
+        crc32fast::hash(canonical.as_bytes())
+    }
+
+    /// Coarser book with levels merged into buckets of `group_by` price atoms
+    ///
+    /// Bids round down and asks round up to their bucket, so grouping never
+    /// crosses the book. Sizes within a bucket are summed and the checksum is
+    /// recomputed over the grouped levels. `group_by` of 0 or 1 is a no-op
+    pub fn grouped(self, group_by: u128) -> OrderbookSnapshot {
+        if group_by <= 1 {
+            return self;
+        }
+
+        fn group(levels: &[OrderbookLevel], bucket: impl Fn(u128) -> u128) -> Vec<OrderbookLevel> {
+            let mut grouped: Vec<OrderbookLevel> = Vec::new();
+            // Levels are sorted and bucketing is monotonic, so equal buckets are adjacent
+            for level in levels {
+                let price = bucket(level.price);
+                match grouped.last_mut() {
+                    Some(last) if last.price == price => last.size += level.size,
+                    _ => grouped.push(OrderbookLevel {
+                        price,
+                        size: level.size,
+                    }),
+                }
+            }
+            grouped
+        }
+
+        let bids = group(&self.bids, |price| price / group_by * group_by);
+        let asks = group(&self.asks, |price| {
+            price.div_ceil(group_by).saturating_mul(group_by)
+        });
+        let checksum = Self::compute_checksum(&bids, &asks);
+
+        OrderbookSnapshot {
+            bids,
+            asks,
+            checksum,
+            ..self
+        }
+    }
+}
+
+/// Resting liquidity within one price bucket of a liquidity profile
+/// Covers prices in [price_low, price_high)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidityBucket {
+    pub price_low: u128,
+    pub price_high: u128,
+    pub bid_size: u128,
+    pub ask_size: u128,
+}
+
+/// Top-of-book microstructure over the best `depth` levels of each side
+/// Spread and mid price need both sides, so they are None for a one-sided book
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookIndicators {
+    pub best_bid: Option<u128>,
+    pub best_ask: Option<u128>,
+    pub bid_volume: u128, // Resting size summed over the top `depth` bid levels
+    pub ask_volume: u128, // Resting size summed over the top `depth` ask levels
+}
+
+impl BookIndicators {
+    /// Best ask minus best bid
+    pub fn spread(&self) -> Option<u128> {
+        Some(self.best_ask?.saturating_sub(self.best_bid?))
+    }
+
+    /// Midpoint of the best bid and ask, rounded down
+    pub fn mid_price(&self) -> Option<u128> {
+        let (bid, ask) = (self.best_bid?, self.best_ask?);
+        Some(bid.min(ask) + bid.abs_diff(ask) / 2)
+    }
+
+    /// Spread relative to the mid price, in basis points
+    pub fn spread_bps(&self) -> Option<f64> {
+        let mid = self.mid_price().filter(|mid| *mid > 0)?;
+        Some(self.spread()? as f64 / mid as f64 * 10_000.0)
+    }
+
+    /// Share of the top-of-book volume on the bid side, in [0, 1]
+    /// None when both sides are empty
+    pub fn imbalance(&self) -> Option<f64> {
+        let total = self.bid_volume + self.ask_volume;
+        (total > 0).then(|| self.bid_volume as f64 / total as f64)
+    }
+}
+
 // ============================================================================
 // ENGINE REQUEST/RESPONSE TYPES
 // ============================================================================
 
 /// Requests sent from REST API to matching engine
-/// Each request includes a oneshot channel for synchronous response
+/// Each request includes a oneshot channel for synchronous response.
+/// Trade requests carry the `request_id` of the REST call that created them,
+/// engine and executor spans record it so one order's logs can be correlated
 pub enum EngineRequest {
     PlaceOrder {
         order: Order,
+        client_order_id: Option<String>, // Idempotency key, a repeat returns the original order
+        request_id: Uuid,
         response_tx: oneshot::Sender<Result<OrderPlaced, ExchangeError>>,
     },
     CancelOrder {
         order_id: Uuid,
         user_address: String,
+        request_id: Uuid,
         response_tx: oneshot::Sender<Result<OrderCancelled, ExchangeError>>,
     },
     CancelAllOrders {
         user_address: String,
         market_id: Option<String>,
+        request_id: Uuid,
         response_tx: oneshot::Sender<Result<OrdersCancelled, ExchangeError>>,
     },
+    /// Cancel a resting order and place its replacement in one engine turn
+    /// Only the difference between the two locks is locked or released
+    ReplaceOrder {
+        old_order_id: Uuid,
+        user_address: String,
+        new_order: Order,
+        request_id: Uuid,
+        response_tx: oneshot::Sender<Result<OrderReplaced, ExchangeError>>,
+    },
+    GetOrderbookSnapshot {
+        market_id: String,
+        /// Merge levels into buckets of this many price atoms (see `OrderbookSnapshot::grouped`)
+        group_by: Option<u128>,
+        response_tx: oneshot::Sender<OrderbookSnapshot>,
+    },
+    /// Resting size per price bucket across [from_price, to_price)
+    LiquidityProfile {
+        market_id: String,
+        from_price: u128,
+        to_price: u128,
+        buckets: u32,
+        response_tx: oneshot::Sender<Vec<LiquidityBucket>>,
+    },
+    /// Spread, mid price and imbalance over the top `depth` levels
+    BookIndicators {
+        market_id: String,
+        depth: usize,
+        response_tx: oneshot::Sender<BookIndicators>,
+    },
+    /// Dry-run an order against the live book (nothing is locked or mutated)
+    Estimate {
+        order: Order,
+        response_tx: oneshot::Sender<Result<EstimateResponse, ExchangeError>>,
+    },
+    /// Kill switch: cancel every resting order of every user in every market
+    /// Responds with the number of orders cancelled
+    CancelEverything {
+        response_tx: oneshot::Sender<Result<usize, ExchangeError>>,
+    },
+    /// Move a market to another trading phase, matching collected orders when a
+    /// pre-open market opens. Responds with the updated market
+    SetMarketStatus {
+        market_id: String,
+        status: MarketStatus,
+        response_tx: oneshot::Sender<Result<Market, ExchangeError>>,
+    },
+}
+
+impl EngineRequest {
+    /// Name of the request type, for logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EngineRequest::PlaceOrder { .. } => "place_order",
+            EngineRequest::CancelOrder { .. } => "cancel_order",
+            EngineRequest::CancelAllOrders { .. } => "cancel_all_orders",
+            EngineRequest::ReplaceOrder { .. } => "replace_order",
+            EngineRequest::GetOrderbookSnapshot { .. } => "get_orderbook_snapshot",
+            EngineRequest::LiquidityProfile { .. } => "liquidity_profile",
+            EngineRequest::BookIndicators { .. } => "book_indicators",
+            EngineRequest::Estimate { .. } => "estimate",
+            EngineRequest::CancelEverything { .. } => "cancel_everything",
+            EngineRequest::SetMarketStatus { .. } => "set_market_status",
+        }
+    }
+
+    /// Correlation id of the REST request, `None` for reads
+    pub fn request_id(&self) -> Option<Uuid> {
+        match self {
+            EngineRequest::PlaceOrder { request_id, .. }
+            | EngineRequest::CancelOrder { request_id, .. }
+            | EngineRequest::CancelAllOrders { request_id, .. }
+            | EngineRequest::ReplaceOrder { request_id, .. } => Some(*request_id),
+            EngineRequest::GetOrderbookSnapshot { .. }
+            | EngineRequest::LiquidityProfile { .. }
+            | EngineRequest::BookIndicators { .. }
+            | EngineRequest::Estimate { .. }
+            | EngineRequest::CancelEverything { .. }
+            | EngineRequest::SetMarketStatus { .. } => None,
+        }
+    }
+
+    /// User whose resting order the request cancels, `None` for requests that cancel
+    /// nothing and for the admin kill switch
+    pub fn cancelling_user(&self) -> Option<&str> {
+        match self {
+            EngineRequest::CancelOrder { user_address, .. }
+            | EngineRequest::CancelAllOrders { user_address, .. }
+            | EngineRequest::ReplaceOrder { user_address, .. } => Some(user_address),
+            EngineRequest::PlaceOrder { .. }
+            | EngineRequest::GetOrderbookSnapshot { .. }
+            | EngineRequest::LiquidityProfile { .. }
+            | EngineRequest::BookIndicators { .. }
+            | EngineRequest::Estimate { .. }
+            | EngineRequest::CancelEverything { .. }
+            | EngineRequest::SetMarketStatus { .. } => None,
+        }
+    }
+
+    /// Market the request is confined to, `None` for requests that may touch any market
+    /// Single cancels name only an order, so the engine has to locate it
+    pub fn market_id(&self) -> Option<&str> {
+        match self {
+            EngineRequest::PlaceOrder { order, .. } | EngineRequest::Estimate { order, .. } => {
+                Some(&order.market_id)
+            }
+            EngineRequest::ReplaceOrder { new_order, .. } => Some(&new_order.market_id),
+            EngineRequest::CancelAllOrders { market_id, .. } => market_id.as_deref(),
+            EngineRequest::GetOrderbookSnapshot { market_id, .. }
+            | EngineRequest::LiquidityProfile { market_id, .. }
+            | EngineRequest::BookIndicators { market_id, .. }
+            | EngineRequest::SetMarketStatus { market_id, .. } => Some(market_id),
+            EngineRequest::CancelOrder { .. } | EngineRequest::CancelEverything { .. } => None,
+        }
+    }
+
+    /// Serializable form of a state-changing request, `None` for read-only requests
+    pub fn to_logged(&self) -> Option<LoggedRequest> {
+        match self {
+            EngineRequest::PlaceOrder {
+                order,
+                client_order_id,
+                ..
+            } => Some(LoggedRequest::PlaceOrder {
+                order: order.clone(),
+                client_order_id: client_order_id.clone(),
+            }),
+            EngineRequest::CancelOrder {
+                order_id,
+                user_address,
+                ..
+            } => Some(LoggedRequest::CancelOrder {
+                order_id: *order_id,
+                user_address: user_address.clone(),
+            }),
+            EngineRequest::CancelAllOrders {
+                user_address,
+                market_id,
+                ..
+            } => Some(LoggedRequest::CancelAllOrders {
+                user_address: user_address.clone(),
+                market_id: market_id.clone(),
+            }),
+            EngineRequest::ReplaceOrder {
+                old_order_id,
+                user_address,
+                new_order,
+                ..
+            } => Some(LoggedRequest::ReplaceOrder {
+                old_order_id: *old_order_id,
+                user_address: user_address.clone(),
+                new_order: new_order.clone(),
+            }),
+            EngineRequest::CancelEverything { .. } => Some(LoggedRequest::CancelEverything),
+            EngineRequest::SetMarketStatus {
+                market_id, status, ..
+            } => Some(LoggedRequest::SetMarketStatus {
+                market_id: market_id.clone(),
+                status: *status,
+            }),
+            EngineRequest::GetOrderbookSnapshot { .. }
+            | EngineRequest::LiquidityProfile { .. }
+            | EngineRequest::BookIndicators { .. }
+            | EngineRequest::Estimate { .. } => None,
+        }
+    }
+
+    /// Answer the request with an error without processing it
+    pub fn reject(self, error: ExchangeError) {
+        match self {
+            EngineRequest::PlaceOrder { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::CancelOrder { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::CancelAllOrders { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::ReplaceOrder { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::Estimate { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::CancelEverything { response_tx } => {
+                let _ = response_tx.send(Err(error));
+            }
+            EngineRequest::SetMarketStatus { response_tx, .. } => {
+                let _ = response_tx.send(Err(error));
+            }
+            // Reads cannot fail, dropping the sender closes the channel
+            EngineRequest::GetOrderbookSnapshot { .. }
+            | EngineRequest::LiquidityProfile { .. }
+            | EngineRequest::BookIndicators { .. } => {}
+        }
+    }
+}
+
+/// State-changing engine request as stored in the request log (no response channels)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoggedRequest {
+    PlaceOrder {
+        order: Order,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_order_id: Option<String>,
+    },
+    CancelOrder {
+        order_id: Uuid,
+        user_address: String,
+    },
+    CancelAllOrders {
+        user_address: String,
+        market_id: Option<String>,
+    },
+    ReplaceOrder {
+        old_order_id: Uuid,
+        user_address: String,
+        new_order: Order,
+    },
+    CancelEverything,
+    SetMarketStatus {
+        market_id: String,
+        status: MarketStatus,
+    },
+}
+
+/// Entry of the engine request log
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLogEntry {
+    pub sequence: i64, // Monotonic, assigned when the request is logged
+    pub request: LoggedRequest,
+    pub logged_at: DateTime<Utc>,
+}
+
+/// Resting orders of a market persisted for recovery
+/// Rebuild the book from `orders`, then replay request log entries after `request_sequence`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistedOrderbook {
+    pub market_id: String,
+    pub orders: Vec<Order>,            // Price-time priority, bids then asks
+    pub request_sequence: Option<i64>, // Last request log sequence reflected in `orders`
+    pub saved_at: DateTime<Utc>,
 }
 
 /// Events broadcast from matching engine to WebSocket clients
@@ -265,6 +1010,7 @@ pub enum EngineEvent {
     OrderCancelled {
         order_id: Uuid,
         user_address: String,
+        market_id: String,
     },
     BalanceUpdated {
         balance: Balance,
@@ -272,6 +1018,11 @@ pub enum EngineEvent {
     OrderbookSnapshot {
         orderbook: OrderbookSnapshot,
     },
+    /// A market changed trading phase or had its parameters updated
+    MarketStatusChanged {
+        market_id: String,
+        status: MarketStatus,
+    },
 }
 
 // ============================================================================
@@ -281,12 +1032,34 @@ pub enum EngineEvent {
 /// Represents what real-time data a WebSocket client wants to receive
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum Subscription {
-    Trades { market_id: String },
-    Orderbook { market_id: String },
-    Candles { market_id: String },
-    UserFills { user_address: String },
-    UserOrders { user_address: String },
-    UserBalances { user_address: String },
+    Trades {
+        market_id: String,
+    },
+    /// Trades in every market
+    AllTrades,
+    Orderbook {
+        market_id: String,
+    },
+    Candles {
+        market_id: String,
+    },
+    /// `market_id` limits the fills to one market, `None` covers all markets
+    UserFills {
+        user_address: String,
+        market_id: Option<String>,
+    },
+    /// `market_id` limits the order updates to one market, `None` covers all markets
+    UserOrders {
+        user_address: String,
+        market_id: Option<String>,
+    },
+    UserBalances {
+        user_address: String,
+    },
+    /// `market_id` limits the status changes to one market, `None` covers all markets
+    MarketStatus {
+        market_id: Option<String>,
+    },
 }
 
 impl Subscription {
@@ -308,6 +1081,7 @@ impl Subscription {
                 SubscriptionChannel::Trades => market_id.as_ref().map(|id| Subscription::Trades {
                     market_id: id.clone(),
                 }),
+                SubscriptionChannel::AllTrades => Some(Subscription::AllTrades),
                 SubscriptionChannel::Orderbook => {
                     market_id.as_ref().map(|id| Subscription::Orderbook {
                         market_id: id.clone(),
@@ -316,11 +1090,13 @@ impl Subscription {
                 SubscriptionChannel::UserFills => {
                     user_address.as_ref().map(|addr| Subscription::UserFills {
                         user_address: addr.clone(),
+                        market_id: market_id.clone(),
                     })
                 }
                 SubscriptionChannel::UserOrders => {
                     user_address.as_ref().map(|addr| Subscription::UserOrders {
                         user_address: addr.clone(),
+                        market_id: market_id.clone(),
                     })
                 }
                 SubscriptionChannel::UserBalances => {
@@ -330,8 +1106,78 @@ impl Subscription {
                             user_address: addr.clone(),
                         })
                 }
+                SubscriptionChannel::MarketStatus => Some(Subscription::MarketStatus {
+                    market_id: market_id.clone(),
+                }),
             },
-            ClientMessage::Ping => None,
+            ClientMessage::AuthChallenge
+            | ClientMessage::Auth { .. }
+            | ClientMessage::Ping
+            | ClientMessage::ListSubscriptions
+            | ClientMessage::Resume { .. } => None,
+        }
+    }
+
+    /// The channel and arguments this subscription was made with
+    /// None for candles, which have no client channel
+    pub fn to_active(&self) -> Option<crate::models::api::ActiveSubscription> {
+        use crate::models::api::{ActiveSubscription, SubscriptionChannel};
+
+        let (channel, market_id, user_address) = match self {
+            Subscription::Trades { market_id } => {
+                (SubscriptionChannel::Trades, Some(market_id.clone()), None)
+            }
+            Subscription::AllTrades => (SubscriptionChannel::AllTrades, None, None),
+            Subscription::Orderbook { market_id } => (
+                SubscriptionChannel::Orderbook,
+                Some(market_id.clone()),
+                None,
+            ),
+            Subscription::Candles { .. } => return None,
+            Subscription::UserFills {
+                user_address,
+                market_id,
+            } => (
+                SubscriptionChannel::UserFills,
+                market_id.clone(),
+                Some(user_address.clone()),
+            ),
+            Subscription::UserOrders {
+                user_address,
+                market_id,
+            } => (
+                SubscriptionChannel::UserOrders,
+                market_id.clone(),
+                Some(user_address.clone()),
+            ),
+            Subscription::UserBalances { user_address } => (
+                SubscriptionChannel::UserBalances,
+                None,
+                Some(user_address.clone()),
+            ),
+            Subscription::MarketStatus { market_id } => {
+                (SubscriptionChannel::MarketStatus, market_id.clone(), None)
+            }
+        };
+
+        Some(ActiveSubscription {
+            channel,
+            market_id,
+            user_address,
+        })
+    }
+
+    /// The user a private subscription belongs to (None for market-wide channels)
+    pub fn user_address(&self) -> Option<&str> {
+        match self {
+            Subscription::UserFills { user_address, .. }
+            | Subscription::UserOrders { user_address, .. }
+            | Subscription::UserBalances { user_address } => Some(user_address),
+            Subscription::Trades { .. }
+            | Subscription::AllTrades
+            | Subscription::Orderbook { .. }
+            | Subscription::Candles { .. }
+            | Subscription::MarketStatus { .. } => None,
         }
     }
 }
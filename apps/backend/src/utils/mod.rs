@@ -1,14 +1,5 @@
-use axum::http::StatusCode;
-use axum::Json;
 use bigdecimal::num_bigint::ToBigInt;
 use bigdecimal::{BigDecimal, ToPrimitive};
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ErrorResponse {
-    pub error: String,
-    pub code: String,
-}
 
 /// Trait for converting between BigDecimal and u128
 pub trait BigDecimalExt {
@@ -48,19 +39,3 @@ impl BigDecimalExt for BigDecimal {
         BigDecimal::from(value)
     }
 }
-
-/// Parse a u128 parameter from a string with proper error handling for REST APIs
-pub fn parse_u128_param(
-    s: &str,
-    param_name: &str,
-) -> Result<u128, (StatusCode, Json<ErrorResponse>)> {
-    s.parse::<u128>().map_err(|_| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: format!("Invalid {} format: must be a valid u128", param_name),
-                code: format!("INVALID_{}", param_name.to_uppercase()),
-            }),
-        )
-    })
-}
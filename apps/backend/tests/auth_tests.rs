@@ -0,0 +1,65 @@
+use backend::auth::{challenge_message, recover_address, same_address, Wallet};
+
+/// Key, message and signature from the web3.js `accounts.sign` documentation
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+const SIGNATURE: &str = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+
+#[test]
+fn test_wallet_matches_known_signature() {
+    let wallet = Wallet::from_private_key(KEY).expect("Valid key");
+    assert!(same_address(&wallet.address(), ADDRESS));
+    assert_eq!(wallet.sign("Some data"), SIGNATURE);
+}
+
+#[test]
+fn test_recovers_signer_address() {
+    let signer = recover_address("Some data", SIGNATURE).expect("Signature should recover");
+    assert!(same_address(&signer, ADDRESS));
+
+    // v as 0/1 instead of 27/28
+    let raw_v = format!("{}01", &SIGNATURE[..SIGNATURE.len() - 2]);
+    assert!(same_address(
+        &recover_address("Some data", &raw_v).unwrap(),
+        ADDRESS
+    ));
+}
+
+#[test]
+fn test_signature_is_bound_to_its_message() {
+    let wallet = Wallet::from_seed("alice");
+    let signature = wallet.sign_challenge("nonce-1");
+
+    assert_eq!(
+        recover_address(&challenge_message("nonce-1"), &signature),
+        Some(wallet.address())
+    );
+    // Replayed against another challenge it recovers some other address
+    assert_ne!(
+        recover_address(&challenge_message("nonce-2"), &signature),
+        Some(wallet.address())
+    );
+}
+
+#[test]
+fn test_malformed_signatures_are_rejected() {
+    let message = challenge_message("nonce");
+    assert_eq!(recover_address(&message, "sig"), None);
+    assert_eq!(recover_address(&message, "0x1234"), None);
+    // Unknown recovery byte
+    let bad_v = format!("{}1f", &SIGNATURE[..SIGNATURE.len() - 2]);
+    assert_eq!(recover_address(&message, &bad_v), None);
+}
+
+#[test]
+fn test_seeded_wallets_are_deterministic() {
+    assert_eq!(
+        Wallet::from_seed("bob").address(),
+        Wallet::from_seed("bob").address()
+    );
+    assert_ne!(
+        Wallet::from_seed("bob").address(),
+        Wallet::from_seed("alice").address()
+    );
+    assert!(Wallet::from_private_key("not hex").is_none());
+}
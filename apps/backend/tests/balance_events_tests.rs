@@ -1,3 +1,4 @@
+use backend::auth::Wallet;
 use backend::models::api::{ClientMessage, ServerMessage, SubscriptionChannel};
 use backend::models::domain::{OrderType, Side};
 use exchange_test_utils::{helpers, TestEngine, TestServer};
@@ -15,6 +16,36 @@ async fn send_json<T: serde::Serialize>(ws: &mut WsStream, msg: &T) -> anyhow::R
     Ok(())
 }
 
+/// Helper to authenticate the connection with the wallet, private channels require it
+async fn authenticate(ws: &mut WsStream, wallet: &Wallet) {
+    send_json(ws, &ClientMessage::AuthChallenge)
+        .await
+        .expect("Failed to request challenge");
+    let nonce = match receive_message(ws)
+        .await
+        .expect("Failed to receive challenge")
+    {
+        ServerMessage::AuthChallenge { nonce, .. } => nonce,
+        other => panic!("Expected AuthChallenge message, got: {:?}", other),
+    };
+    send_json(
+        ws,
+        &ClientMessage::Auth {
+            user_address: wallet.address(),
+            signature: wallet.sign_challenge(&nonce),
+        },
+    )
+    .await
+    .expect("Failed to send auth");
+    match receive_message(ws)
+        .await
+        .expect("Failed to receive auth reply")
+    {
+        ServerMessage::Authenticated { .. } => {}
+        other => panic!("Expected Authenticated message, got: {:?}", other),
+    }
+}
+
 /// Helper to receive and parse the next WebSocket message
 async fn receive_message(ws: &mut WsStream) -> anyhow::Result<ServerMessage> {
     loop {
@@ -58,7 +89,8 @@ async fn test_balance_events_on_limit_order_placement() {
         .await
         .expect("Failed to create market");
 
-    let user = "test_user_1".to_string();
+    let user_wallet = Wallet::from_seed("test_user_1");
+    let user = user_wallet.address();
     server
         .test_db
         .db
@@ -79,6 +111,8 @@ async fn test_balance_events_on_limit_order_placement() {
         .await
         .expect("Failed to connect to WebSocket");
 
+    authenticate(&mut ws, &user_wallet).await;
+
     send_json(
         &mut ws,
         &ClientMessage::Subscribe {
@@ -123,7 +157,9 @@ async fn test_balance_events_on_limit_order_placement() {
         ServerMessage::UserBalance {
             user_address,
             token_ticker,
-            available: _,
+            amount,
+            open_interest,
+            available,
             locked,
             updated_at,
         } => {
@@ -132,6 +168,11 @@ async fn test_balance_events_on_limit_order_placement() {
             // Should have locked 50,000_000_000 USDC (price * size)
             let locked_amount = locked.parse::<u128>().unwrap();
             assert!(locked_amount > 0, "Should have locked balance");
+            assert_eq!(open_interest, locked);
+            assert_eq!(
+                available.parse::<u128>().unwrap(),
+                amount.parse::<u128>().unwrap() - locked_amount
+            );
             assert!(updated_at > 0, "updated_at should be set");
         }
         _ => panic!("Expected Balance message, got: {:?}", msg),
@@ -153,7 +194,8 @@ async fn test_balance_events_on_limit_order_fill() {
 
     // Create two users
     let maker = "maker_user".to_string();
-    let taker = "taker_user".to_string();
+    let taker_wallet = Wallet::from_seed("taker_user");
+    let taker = taker_wallet.address();
 
     server
         .test_db
@@ -188,6 +230,8 @@ async fn test_balance_events_on_limit_order_fill() {
         .await
         .expect("Failed to connect to WebSocket");
 
+    authenticate(&mut ws, &taker_wallet).await;
+
     send_json(
         &mut ws,
         &ClientMessage::Subscribe {
@@ -242,6 +286,7 @@ async fn test_balance_events_on_limit_order_fill() {
                 available,
                 locked,
                 updated_at,
+                ..
             })) => {
                 assert_eq!(user_address, taker);
                 assert!(updated_at > 0);
@@ -307,7 +352,8 @@ async fn test_balance_events_on_limit_order_cancellation() {
         .await
         .expect("Failed to create market");
 
-    let user = "test_user_cancel".to_string();
+    let user_wallet = Wallet::from_seed("test_user_cancel");
+    let user = user_wallet.address();
     server
         .test_db
         .db
@@ -328,6 +374,8 @@ async fn test_balance_events_on_limit_order_cancellation() {
         .await
         .expect("Failed to connect to WebSocket");
 
+    authenticate(&mut ws, &user_wallet).await;
+
     send_json(
         &mut ws,
         &ClientMessage::Subscribe {
@@ -380,6 +428,7 @@ async fn test_balance_events_on_limit_order_cancellation() {
                 available: _,
                 locked,
                 updated_at,
+                ..
             })) => {
                 if token_ticker == "USDC" {
                     assert_eq!(user_address, user);
@@ -440,7 +489,8 @@ async fn test_balance_events_on_market_order_partial_fill() {
         .expect("Failed to create market");
 
     let maker = "maker_partial".to_string();
-    let taker = "taker_partial".to_string();
+    let taker_wallet = Wallet::from_seed("taker_partial");
+    let taker = taker_wallet.address();
 
     server
         .test_db
@@ -490,6 +540,8 @@ async fn test_balance_events_on_market_order_partial_fill() {
         .await
         .expect("Failed to connect to WebSocket");
 
+    authenticate(&mut ws, &taker_wallet).await;
+
     send_json(
         &mut ws,
         &ClientMessage::Subscribe {
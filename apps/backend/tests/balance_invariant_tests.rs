@@ -0,0 +1,86 @@
+//! Balance invariant checks in the executor
+//!
+//! Kept in their own test binary: the fault injection switch is process-wide,
+//! so no other test may run alongside it.
+
+use backend::config::EngineConfig;
+use backend::engine::executor::fault_injection;
+use backend::models::domain::{OrderType, Side};
+use exchange_test_utils::{helpers, TestDb, TestEngine};
+
+#[tokio::test]
+async fn test_balance_invariant_check_catches_skewed_settlement() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            check_balance_invariants: true,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let ask = || {
+        TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        )
+    };
+    let bid = || {
+        TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        )
+    };
+
+    // Correct settlement, fees included, passes the check
+    engine
+        .place_order(ask())
+        .await
+        .expect("Failed to place ask");
+    let placed = engine
+        .place_order(bid())
+        .await
+        .expect("Honest fill should pass the invariant check");
+    assert_eq!(placed.trades.len(), 1);
+
+    // Buyers now receive one base atom out of thin air
+    engine
+        .place_order(ask())
+        .await
+        .expect("Failed to place ask");
+    let buyer_base = engine.db.get_balance("buyer", "BTC").await.unwrap();
+    let buyer_quote = engine.db.get_balance("buyer", "USDC").await.unwrap();
+
+    fault_injection::set_buyer_base_skew(1);
+    let result = engine.place_order(bid()).await;
+    fault_injection::set_buyer_base_skew(0);
+
+    let err = result.expect_err("Skewed fill should fail the invariant check");
+    assert!(
+        err.contains("changed the total BTC held by its parties"),
+        "{}",
+        err
+    );
+
+    // The fill was rolled back and the bid's lock released
+    let after_base = engine.db.get_balance("buyer", "BTC").await.unwrap();
+    let after_quote = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(after_base.amount, buyer_base.amount);
+    assert_eq!(after_quote.amount, buyer_quote.amount);
+    assert_eq!(after_quote.open_interest, buyer_quote.open_interest);
+    let trades = engine.db.get_market_trades(&market.id, 10).await.unwrap();
+    assert_eq!(trades.len(), 1, "Only the honest trade should be stored");
+}
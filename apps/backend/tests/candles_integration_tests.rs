@@ -1,7 +1,11 @@
 /// Integration tests for the full trade → ClickHouse → candles flow
 /// These tests verify end-to-end functionality from trade execution to candle generation
-use backend::models::domain::{OrderType, Side};
+use backend::engine::executor::Executor;
+use backend::engine::MatchingEngine;
+use backend::models::domain::{CandleInterval, OrderType, Side, Trade, TradeCursor};
 use exchange_test_utils::{helpers, TestDb, TestEngine};
+use tokio::sync::{broadcast, mpsc};
+use uuid::Uuid;
 
 /// Test that trades are persisted to ClickHouse when engine executes them
 #[tokio::test]
@@ -232,3 +236,548 @@ async fn test_no_trades_means_no_candles() {
 
     assert_eq!(count, 0, "Expected no candles for market with no trades");
 }
+
+/// Test that replaying a match through the executor derives the same trade id,
+/// and that its second ClickHouse insert adds neither a trade row nor candle volume
+#[tokio::test]
+async fn test_replayed_trade_is_idempotent() {
+    let source = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&source, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&source).await;
+
+    let sell_order = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        95000000000, // $95,000
+        1000000,     // 0.01 BTC
+    );
+    engine
+        .place_order(sell_order)
+        .await
+        .expect("Failed to place sell order");
+
+    let buy_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        95000000000,
+        1000000,
+    );
+    let result = engine
+        .place_order(buy_order)
+        .await
+        .expect("Failed to place buy order");
+    assert_eq!(result.trades.len(), 1, "Expected 1 trade to be executed");
+
+    let api_trade = result.trades[0].clone();
+    let buyer_order_id = Uuid::parse_str(&api_trade.buyer_order_id).unwrap();
+    let seller_order_id = Uuid::parse_str(&api_trade.seller_order_id).unwrap();
+    assert_eq!(
+        api_trade.id,
+        Executor::trade_id(buyer_order_id, seller_order_id, 0).to_string()
+    );
+    assert_ne!(
+        Executor::trade_id(buyer_order_id, seller_order_id, 0),
+        Executor::trade_id(buyer_order_id, seller_order_id, 1),
+        "Match index should be part of the trade id"
+    );
+
+    // Wait for the engine's async ClickHouse insert
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    let trade_rows = |db: &backend::db::Db| {
+        db.clickhouse
+            .query("SELECT count() FROM exchange.trades WHERE id = ?")
+            .bind(api_trade.id.clone())
+            .fetch_one::<u64>()
+    };
+    let volume = |db: &backend::db::Db| {
+        db.clickhouse
+            .query(
+                "SELECT sumMerge(volume_state) FROM exchange.candles
+                WHERE market_id = ? AND interval = '1m'",
+            )
+            .bind(market.id.clone())
+            .fetch_one::<u128>()
+    };
+    assert_eq!(trade_rows(&source.db).await.unwrap(), 1);
+    let volume_before = volume(&source.db).await.expect("Failed to query candles");
+    assert_eq!(volume_before, 1000000);
+
+    // Recover into a fresh Postgres that writes to the same ClickHouse: the request
+    // log replays the same match through the executor a second time
+    let target = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_market_with_tokens(&target, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let _funded = TestEngine::new(&target).await;
+    let recovered_db = backend::db::Db {
+        postgres: target.db.postgres.clone(),
+        clickhouse: source.db.clickhouse.clone(),
+    };
+    let (_engine_tx, engine_rx) = mpsc::channel(1);
+    let (event_tx, _event_rx) = broadcast::channel(1000);
+    let mut replayed = MatchingEngine::new(recovered_db, engine_rx, event_tx);
+    replayed
+        .replay_from(&source.db, 0)
+        .await
+        .expect("Failed to replay request log");
+
+    let replayed_trades = target
+        .db
+        .get_user_trades("buyer", Some(&market.id), 10)
+        .await
+        .expect("Failed to read replayed trades");
+    assert_eq!(replayed_trades.len(), 1);
+    assert_eq!(replayed_trades[0].id.to_string(), api_trade.id);
+
+    // Wait for the replay's async ClickHouse insert
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    assert_eq!(
+        trade_rows(&source.db).await.unwrap(),
+        1,
+        "Replaying a trade should not insert a second row"
+    );
+    assert_eq!(
+        volume(&source.db).await.expect("Failed to query candles"),
+        volume_before,
+        "Replaying a trade should not add candle volume"
+    );
+}
+
+/// Test VWAP and volume over a window match a manual calculation
+#[tokio::test]
+async fn test_vwap_matches_manual_calculation() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // 0.01 BTC @ $50,000 and 0.03 BTC @ $51,000
+    let now = chrono::Utc::now();
+    for (i, (price, size)) in [
+        (50_000_000_000u128, 1_000_000u128),
+        (51_000_000_000, 3_000_000),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let trade = Trade {
+            id: Uuid::new_v4(),
+            market_id: market.id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price,
+            size,
+            side: Side::Buy,
+            timestamp: now,
+            sequence: i as u64 + 1,
+        };
+        test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    let from = now.timestamp() - 60;
+    let to = now.timestamp() + 60;
+
+    // (50,000 * 1 + 51,000 * 3) / 4 = $50,750
+    let vwap = test_db
+        .db
+        .get_vwap(&market.id, from, to)
+        .await
+        .expect("Failed to get VWAP");
+    assert_eq!(vwap, Some(50_750_000_000));
+
+    let volume = test_db
+        .db
+        .get_trade_volume(&market.id, from, to)
+        .await
+        .expect("Failed to get trade volume");
+    assert_eq!(volume.trade_count, 2);
+    assert_eq!(volume.base_volume, 4_000_000);
+    // $500 + $1,530 in USDC atoms (BTC has 8 decimals)
+    assert_eq!(volume.quote_volume(8), 2_030_000_000);
+
+    // A window with no trades has no VWAP
+    let empty = test_db
+        .db
+        .get_vwap(&market.id, from - 3600, from)
+        .await
+        .expect("Failed to get VWAP");
+    assert_eq!(empty, None);
+}
+
+/// Test the trade tape filters by taker side and minimum size in ClickHouse
+#[tokio::test]
+async fn test_recent_trades_filter_by_side_and_min_size() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // Small and large trades on both sides, one second apart
+    let now = chrono::Utc::now();
+    let seeded = [
+        (Side::Buy, 1_000_000u128),
+        (Side::Buy, 50_000_000),
+        (Side::Sell, 80_000_000),
+        (Side::Buy, 100_000_000),
+        (Side::Sell, 2_000_000),
+    ];
+    let mut ids = Vec::new();
+    for (i, (side, size)) in seeded.into_iter().enumerate() {
+        let trade = Trade {
+            id: Uuid::new_v4(),
+            market_id: market.id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price: 50_000_000_000,
+            size,
+            side,
+            timestamp: now + chrono::Duration::seconds(i as i64),
+            sequence: i as u64 + 1,
+        };
+        ids.push(trade.id);
+        test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    // Only buys of at least 0.5 BTC, most recent first
+    let large_buys = test_db
+        .db
+        .get_recent_trades(&market.id, Some(Side::Buy), Some(50_000_000), 100)
+        .await
+        .expect("Failed to get trades");
+    let found: Vec<_> = large_buys.iter().map(|t| (t.id, t.side, t.size)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (ids[3], Side::Buy, 100_000_000),
+            (ids[1], Side::Buy, 50_000_000)
+        ]
+    );
+
+    // Each filter works alone
+    let sells = test_db
+        .db
+        .get_recent_trades(&market.id, Some(Side::Sell), None, 100)
+        .await
+        .expect("Failed to get trades");
+    assert_eq!(sells.len(), 2);
+    let large = test_db
+        .db
+        .get_recent_trades(&market.id, None, Some(50_000_000), 100)
+        .await
+        .expect("Failed to get trades");
+    assert_eq!(large.len(), 3);
+    let all = test_db
+        .db
+        .get_recent_trades(&market.id, None, None, 100)
+        .await
+        .expect("Failed to get trades");
+    assert_eq!(all.len(), seeded.len());
+}
+
+/// Test paging through the trade tape with a cursor, including trades sharing a second
+#[tokio::test]
+async fn test_trades_page_cursor_walks_range() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // Three trades per second over four seconds
+    let start = chrono::Utc::now().timestamp() - 60;
+    for i in 0..12 {
+        let trade = Trade {
+            id: Uuid::new_v4(),
+            market_id: market.id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price: 50_000_000_000,
+            size: 1_000_000,
+            side: Side::Buy,
+            timestamp: chrono::DateTime::from_timestamp(start + i / 3, 0).unwrap(),
+            sequence: i as u64 + 1,
+        };
+        test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    // Skip the first second, then walk the rest in pages of 4
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = test_db
+            .db
+            .get_trades_page(
+                &market.id,
+                None,
+                None,
+                Some(start + 1),
+                Some(start + 4),
+                cursor,
+                4,
+            )
+            .await
+            .expect("Failed to get trades page");
+        let full = page.len() == 4;
+        cursor = page.last().map(TradeCursor::after);
+        seen.extend(page);
+        if !full {
+            break;
+        }
+    }
+
+    assert_eq!(seen.len(), 9);
+    let keys: Vec<_> = seen
+        .iter()
+        .map(|t| (t.timestamp.timestamp(), t.sequence))
+        .collect();
+    let mut expected = keys.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    expected.dedup();
+    assert_eq!(keys, expected, "Pages overlap or are out of order");
+    assert!(keys
+        .iter()
+        .all(|(ts, _)| (start + 1..start + 4).contains(ts)));
+}
+
+/// Test interval parsing accepts the supported keys and rejects anything else
+#[test]
+fn test_candle_interval_parsing() {
+    for key in ["1m", "5m", "15m", "1h", "4h", "1d", "1w"] {
+        let interval: CandleInterval = key.parse().expect("Valid interval should parse");
+        assert_eq!(interval.as_str(), key);
+    }
+    assert_eq!("4h".parse(), Ok(CandleInterval::FourHours));
+    assert_eq!("1w".parse(), Ok(CandleInterval::OneWeek));
+
+    for key in ["", "2h", "1H", "1day", "60"] {
+        let err = key
+            .parse::<CandleInterval>()
+            .expect_err("Unknown interval should be rejected");
+        assert!(err.contains("1m, 5m, 15m, 1h, 4h, 1d, 1w"), "{}", err);
+    }
+}
+
+/// Test bucket starts line up with the ClickHouse views
+#[test]
+fn test_candle_interval_bucket_start() {
+    // 2024-01-03T13:45:10Z, a Wednesday
+    let ts = 1_704_289_510;
+    assert_eq!(CandleInterval::OneMinute.bucket_start(ts), 1_704_289_500);
+    // 12:00
+    assert_eq!(CandleInterval::FourHours.bucket_start(ts), 1_704_283_200);
+    // Monday 2024-01-01T00:00:00Z
+    assert_eq!(CandleInterval::OneWeek.bucket_start(ts), 1_704_067_200);
+    assert_eq!(
+        CandleInterval::OneWeek.bucket_start(1_704_067_200),
+        1_704_067_200
+    );
+}
+
+/// Test trades within one 4h window aggregate into a single bar
+#[tokio::test]
+async fn test_four_hour_candle_aggregation() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // 2024-01-01T08:00:00Z starts a 4h bucket
+    let bucket = 1_704_096_000i64;
+    let trades = [
+        (bucket + 10 * 60, 50_000_000_000u128, 1_000_000u128), // 08:10 open
+        (bucket + 90 * 60, 52_000_000_000, 2_000_000),         // 09:30 high
+        (bucket + 165 * 60, 49_000_000_000, 1_000_000),        // 10:45 low
+        (bucket + 239 * 60, 51_000_000_000, 3_000_000),        // 11:59 close
+        (bucket + 4 * 3600, 53_000_000_000, 1_000_000),        // 12:00 next bar
+    ];
+    for (i, (ts, price, size)) in trades.into_iter().enumerate() {
+        let trade = Trade {
+            id: Uuid::new_v4(),
+            market_id: market.id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price,
+            size,
+            side: Side::Buy,
+            timestamp: chrono::DateTime::from_timestamp(ts, 0).unwrap(),
+            sequence: i as u64 + 1,
+        };
+        test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    // `from` inside the first bar still returns that bar
+    let candles = test_db
+        .db
+        .get_candles_for_api(
+            &market.id,
+            CandleInterval::FourHours,
+            bucket + 3600,
+            bucket + 8 * 3600,
+            None,
+        )
+        .await
+        .expect("Failed to get candles");
+    assert_eq!(candles.len(), 2, "Expected two 4h bars");
+
+    let bar = &candles[0];
+    assert_eq!(bar.timestamp as i64, bucket);
+    assert_eq!(bar.open, 50_000_000_000);
+    assert_eq!(bar.high, 52_000_000_000);
+    assert_eq!(bar.low, 49_000_000_000);
+    assert_eq!(bar.close, 51_000_000_000);
+    assert_eq!(bar.volume, 7_000_000);
+    assert_eq!(candles[1].timestamp as i64, bucket + 4 * 3600);
+
+    // countBack takes priority over `from`, even when `from` excludes the bars
+    let candles = test_db
+        .db
+        .get_candles_for_api(
+            &market.id,
+            CandleInterval::FourHours,
+            bucket + 8 * 3600,
+            bucket + 8 * 3600,
+            Some(2),
+        )
+        .await
+        .expect("Failed to get candles");
+    let timestamps: Vec<i64> = candles.iter().map(|c| c.timestamp as i64).collect();
+    assert_eq!(timestamps, vec![bucket, bucket + 4 * 3600]);
+}
+
+/// 5m candles aggregated from 1m candles match the ones served by the 5m view
+#[tokio::test]
+async fn test_aggregated_candles_match_materialized_view() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // 2024-01-01T08:00:00Z starts a 5m bucket
+    let bucket = 1_704_096_000i64;
+    let trades = [
+        (bucket + 15, 50_000_000_000u128, 1_000_000u128), // 08:00:15 open
+        (bucket + 70, 52_000_000_000, 2_000_000),         // 08:01:10 high
+        (bucket + 130, 49_000_000_000, 1_000_000),        // 08:02:10 low
+        (bucket + 299, 51_000_000_000, 3_000_000),        // 08:04:59 close
+        (bucket + 300, 53_000_000_000, 1_000_000),        // 08:05:00 next bar
+        (bucket + 610, 54_000_000_000, 2_000_000),        // 08:10:10 third bar
+    ];
+    for (i, (ts, price, size)) in trades.into_iter().enumerate() {
+        let trade = Trade {
+            id: Uuid::new_v4(),
+            market_id: market.id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price,
+            size,
+            side: Side::Buy,
+            timestamp: chrono::DateTime::from_timestamp(ts, 0).unwrap(),
+            sequence: i as u64 + 1,
+        };
+        test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    let materialized = test_db
+        .db
+        .materialized_candle_intervals()
+        .await
+        .expect("Failed to list materialized intervals");
+    assert!(materialized.contains(&CandleInterval::FiveMinutes));
+
+    let as_tuples = |candles: Vec<backend::models::api::ApiCandle>| {
+        candles
+            .into_iter()
+            .map(|c| (c.timestamp, c.open, c.high, c.low, c.close, c.volume))
+            .collect::<Vec<_>>()
+    };
+
+    // Same range, and the same most recent bars with countBack
+    for (from, to, count_back) in [
+        (bucket + 60, bucket + 11 * 60, None),
+        (bucket + 11 * 60, bucket + 11 * 60, Some(2)),
+    ] {
+        let from_view = test_db
+            .db
+            .get_materialized_candles(
+                &market.id,
+                CandleInterval::FiveMinutes,
+                from,
+                to,
+                count_back,
+            )
+            .await
+            .expect("Failed to read view candles");
+        let aggregated = test_db
+            .db
+            .get_aggregated_candles(
+                &market.id,
+                CandleInterval::FiveMinutes,
+                from,
+                to,
+                count_back,
+            )
+            .await
+            .expect("Failed to aggregate candles");
+        assert_eq!(as_tuples(aggregated), as_tuples(from_view.clone()));
+
+        let expected_bars = if count_back.is_some() { 2 } else { 3 };
+        assert_eq!(from_view.len(), expected_bars);
+    }
+
+    let candles = test_db
+        .db
+        .get_aggregated_candles(
+            &market.id,
+            CandleInterval::FiveMinutes,
+            bucket,
+            bucket,
+            None,
+        )
+        .await
+        .expect("Failed to aggregate candles");
+    let bar = &candles[0];
+    assert_eq!(bar.timestamp as i64, bucket);
+    assert_eq!(bar.open, 50_000_000_000);
+    assert_eq!(bar.high, 52_000_000_000);
+    assert_eq!(bar.low, 49_000_000_000);
+    assert_eq!(bar.close, 51_000_000_000);
+    assert_eq!(bar.volume, 7_000_000);
+}
@@ -1,5 +1,6 @@
 /// Tests to verify ClickHouse schema matches Rust structs
 /// These tests catch schema mismatches that cause runtime panics
+use backend::db::ch;
 use backend::models::db::ClickHouseTradeRow;
 use exchange_test_utils::TestContainers;
 
@@ -22,6 +23,7 @@ async fn test_clickhouse_trades_schema_matches_struct() {
         size: 1000000,
         side: "buy".to_string(),
         timestamp: 1234567890,
+        sequence: 1,
     };
 
     // This will panic if schema doesn't match struct
@@ -62,6 +64,26 @@ async fn test_candles_table_uses_aggregating_merge_tree() {
     );
 }
 
+#[tokio::test]
+async fn test_trades_table_is_keyed_on_id() {
+    let containers = TestContainers::setup()
+        .await
+        .expect("Failed to setup containers");
+    let db = containers.db_clone();
+
+    // Re-inserted trades collapse into one row per (market_id, id)
+    let query = "SELECT engine, sorting_key FROM system.tables WHERE database = 'exchange' AND name = 'trades'";
+    let (engine, sorting_key): (String, String) = db
+        .clickhouse
+        .query(query)
+        .fetch_one::<(String, String)>()
+        .await
+        .expect("Failed to query table engine");
+
+    assert_eq!(engine, "ReplacingMergeTree");
+    assert_eq!(sorting_key, "market_id, id");
+}
+
 #[tokio::test]
 async fn test_trades_table_has_all_required_columns() {
     let containers = TestContainers::setup()
@@ -89,6 +111,7 @@ async fn test_trades_table_has_all_required_columns() {
         "price",
         "size",
         "timestamp",
+        "sequence",
     ];
 
     for col in required {
@@ -163,6 +186,7 @@ async fn test_candles_aggregation_reduces_storage() {
             size: 1000000,
             side: "buy".to_string(),
             timestamp: base_timestamp + i, // Different seconds within same minute
+            sequence: i as u64 + 1,
         };
 
         let mut insert = db
@@ -306,6 +330,7 @@ async fn test_trades_roundtrip() {
         size: 1000000,
         side: "buy".to_string(),
         timestamp: 1234567890,
+        sequence: 1,
     };
 
     // Insert trade
@@ -337,4 +362,72 @@ async fn test_trades_roundtrip() {
     assert_eq!(retrieved.market_id, trade.market_id);
     assert_eq!(retrieved.price, trade.price);
     assert_eq!(retrieved.size, trade.size);
+    assert_eq!(retrieved.sequence, trade.sequence);
+}
+
+/// Test that re-running schema initialization is a no-op
+#[tokio::test]
+async fn test_init_schema_is_idempotent() {
+    let containers = TestContainers::setup()
+        .await
+        .expect("Failed to setup containers");
+    let db = containers.db_clone();
+
+    // Connecting already ran it once
+    for _ in 0..2 {
+        ch::init_schema(&db.clickhouse)
+            .await
+            .expect("Re-running schema initialization should succeed");
+    }
+
+    let tables: Vec<String> = db
+        .clickhouse
+        .query("SELECT name FROM system.tables WHERE database = 'exchange'")
+        .fetch_all::<String>()
+        .await
+        .expect("Failed to query tables");
+    for table in [
+        "trades",
+        "candles",
+        "schema_migrations",
+        "candles_1m_mv",
+        "candles_5m_mv",
+        "candles_15m_mv",
+        "candles_1h_mv",
+        "candles_1d_mv",
+    ] {
+        assert!(tables.contains(&table.to_string()), "Missing {}", table);
+    }
+
+    // Each migration is recorded once
+    let versions: Vec<u32> = db
+        .clickhouse
+        .query("SELECT version FROM exchange.schema_migrations FINAL ORDER BY version")
+        .fetch_all::<u32>()
+        .await
+        .expect("Failed to query applied migrations");
+    let expected: Vec<u32> = ch::MIGRATIONS.iter().map(|m| m.version).collect();
+    assert_eq!(versions, expected);
+}
+
+#[test]
+fn test_split_statements_ignores_semicolons_in_comments_and_strings() {
+    let sql = "-- first; statement
+CREATE TABLE t (s String DEFAULT 'a;b') ENGINE = Memory; /* block; comment */
+SELECT 'it''s; fine', `odd;name` FROM t;
+-- trailing comment only";
+
+    let statements = ch::split_statements(sql);
+    assert_eq!(
+        statements,
+        vec![
+            "CREATE TABLE t (s String DEFAULT 'a;b') ENGINE = Memory",
+            "SELECT 'it''s; fine', `odd;name` FROM t",
+        ]
+    );
+
+    // The embedded migrations split into whole statements
+    let init = ch::split_statements(ch::MIGRATIONS[0].sql);
+    assert_eq!(init.len(), 8);
+    assert!(init.iter().all(|s| s.starts_with("CREATE")));
 }
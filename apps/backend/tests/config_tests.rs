@@ -0,0 +1,170 @@
+use backend::config::{Config, ConfigProblem};
+
+/// Tokens shared by every test config
+const TOKENS: &str = r#"
+[[tokens]]
+ticker = "BTC"
+decimals = 8
+name = "Bitcoin"
+
+[[tokens]]
+ticker = "USDC"
+decimals = 6
+name = "USD Coin"
+"#;
+
+fn config(markets: &str) -> Config {
+    toml::from_str(&format!("{}{}", TOKENS, markets)).expect("Test config should parse")
+}
+
+#[test]
+fn test_shipped_config_is_valid() {
+    let config = Config::load().expect("Failed to load config.toml");
+    assert_eq!(config.validate(), Ok(()));
+}
+
+#[test]
+fn test_market_with_unknown_token() {
+    let config = config(
+        r#"
+[[markets]]
+base_ticker = "ETH"
+quote_ticker = "USDC"
+tick_size = "1000"
+lot_size = "1000"
+min_size = "1000"
+maker_fee_bps = 5
+taker_fee_bps = 10
+"#,
+    );
+
+    let problems = config
+        .validate()
+        .expect_err("ETH is not a configured token");
+    assert_eq!(
+        problems,
+        vec![ConfigProblem::UnknownToken {
+            market: "ETH/USDC".to_string(),
+            ticker: "ETH".to_string(),
+        }]
+    );
+    assert_eq!(
+        problems[0].to_string(),
+        "Market ETH/USDC references unknown token ETH"
+    );
+}
+
+#[test]
+fn test_market_with_out_of_range_fee() {
+    let config = config(
+        r#"
+[[markets]]
+base_ticker = "BTC"
+quote_ticker = "USDC"
+tick_size = "1000"
+lot_size = "1000"
+min_size = "1000"
+maker_fee_bps = 5
+taker_fee_bps = 10001
+"#,
+    );
+
+    assert_eq!(
+        config.validate(),
+        Err(vec![ConfigProblem::FeeOutOfRange {
+            market: "BTC/USDC".to_string(),
+            maker_fee_bps: 5,
+            taker_fee_bps: 10001,
+        }])
+    );
+}
+
+#[test]
+fn test_token_with_too_many_decimals() {
+    let config = config(
+        r#"
+[[tokens]]
+ticker = "WEI"
+decimals = 19
+name = "Too Fine"
+
+[[markets]]
+base_ticker = "BTC"
+quote_ticker = "USDC"
+tick_size = "1000"
+lot_size = "1000"
+min_size = "1000"
+maker_fee_bps = 5
+taker_fee_bps = 10
+"#,
+    );
+
+    let problems = config
+        .validate()
+        .expect_err("19 decimals is above the limit");
+    assert_eq!(
+        problems,
+        vec![ConfigProblem::TooManyDecimals {
+            ticker: "WEI".to_string(),
+            decimals: 19,
+        }]
+    );
+    assert_eq!(
+        problems[0].to_string(),
+        "Token WEI has 19 decimals, at most 18 are allowed"
+    );
+}
+
+#[test]
+fn test_reports_every_problem() {
+    let config = config(
+        r#"
+[[markets]]
+base_ticker = "BTC"
+quote_ticker = "USDC"
+tick_size = "0"
+lot_size = "1000"
+min_size = "lots"
+maker_fee_bps = -20
+taker_fee_bps = 10
+min_price = "5000"
+max_price = "1000"
+
+[[markets]]
+base_ticker = "BTC"
+quote_ticker = "USDC"
+tick_size = "1000"
+lot_size = "1000"
+min_size = "1000"
+maker_fee_bps = 5
+taker_fee_bps = 10
+"#,
+    );
+
+    let market = "BTC/USDC".to_string();
+    assert_eq!(
+        config.validate(),
+        Err(vec![
+            // A rebate larger than the taker fee
+            ConfigProblem::FeeOutOfRange {
+                market: market.clone(),
+                maker_fee_bps: -20,
+                taker_fee_bps: 10,
+            },
+            ConfigProblem::InvalidAmount {
+                market: market.clone(),
+                field: "tick_size",
+                value: "0".to_string(),
+            },
+            ConfigProblem::InvalidAmount {
+                market: market.clone(),
+                field: "min_size",
+                value: "lots".to_string(),
+            },
+            ConfigProblem::EmptyPriceBand {
+                market: market.clone(),
+            },
+            ConfigProblem::DuplicateMarket { market },
+        ])
+    );
+}
@@ -1,5 +1,9 @@
+use backend::engine::orderbook::Orderbook;
+use backend::models::domain::{
+    CandleInterval, Order, OrderStatus, OrderType, PersistedOrderbook, Side, TimeInForce,
+};
 use chrono::{DateTime, Utc};
-use exchange_test_utils::{helpers, TestDb};
+use exchange_test_utils::{helpers, TestDb, TestEngine};
 use std::str::FromStr;
 
 #[tokio::test]
@@ -113,7 +117,7 @@ async fn test_candle_operations() {
 
     let candles = test_db
         .db
-        .get_candles(&market.id, "1m", start_time, end_time)
+        .get_candles(&market.id, CandleInterval::OneMinute, start_time, end_time)
         .await
         .expect("Failed to get candles");
 
@@ -136,7 +140,12 @@ async fn test_candle_operations() {
 
     let narrow_candles = test_db
         .db
-        .get_candles(&market.id, "1m", narrow_start, narrow_end)
+        .get_candles(
+            &market.id,
+            CandleInterval::OneMinute,
+            narrow_start,
+            narrow_end,
+        )
         .await
         .expect("Failed to get narrow candles");
 
@@ -186,13 +195,23 @@ async fn test_multiple_markets_and_candles() {
 
     let btc_candles = test_db
         .db
-        .get_candles(&btc_market.id, "1m", start_time, end_time)
+        .get_candles(
+            &btc_market.id,
+            CandleInterval::OneMinute,
+            start_time,
+            end_time,
+        )
         .await
         .expect("Failed to get BTC candles");
 
     let eth_candles = test_db
         .db
-        .get_candles(&eth_market.id, "1m", start_time, end_time)
+        .get_candles(
+            &eth_market.id,
+            CandleInterval::OneMinute,
+            start_time,
+            end_time,
+        )
         .await
         .expect("Failed to get ETH candles");
 
@@ -269,6 +288,8 @@ async fn test_market_creation_with_missing_tokens() {
             1000000, // min_size
             10,      // maker_fee_bps
             20,      // taker_fee_bps
+            None,    // min_price
+            None,    // max_price
         )
         .await;
 
@@ -290,6 +311,8 @@ async fn test_market_creation_with_missing_tokens() {
             1000000, // min_size
             10,      // maker_fee_bps
             20,      // taker_fee_bps
+            None,    // min_price
+            None,    // max_price
         )
         .await;
 
@@ -298,3 +321,340 @@ async fn test_market_creation_with_missing_tokens() {
     let error = result.unwrap_err();
     assert!(error.to_string().contains("Token 'BTC' does not exist"));
 }
+
+#[tokio::test]
+async fn test_orderbook_snapshot_round_trip() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let order = |user: &str, side: Side, price: u128, size: u128, filled_size: u128| Order {
+        id: uuid::Uuid::new_v4(),
+        user_address: user.to_string(),
+        market_id: market.id.clone(),
+        price,
+        size,
+        side,
+        order_type: OrderType::Limit,
+        time_in_force: TimeInForce::Gtc,
+        min_fill: None,
+        status: OrderStatus::Pending,
+        filled_size,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    let orderbook = Orderbook::from_orders(
+        market.id.clone(),
+        [
+            order("alice", Side::Buy, 49_000_000_000, 2_000_000, 0),
+            order("bob", Side::Buy, 49_000_000_000, 1_000_000, 0),
+            order("alice", Side::Buy, 48_000_000_000, 3_000_000, 1_000_000),
+            order("carol", Side::Sell, 51_000_000_000, 4_000_000, 0),
+        ],
+    );
+
+    assert!(test_db
+        .db
+        .load_latest_orderbook_snapshot(&market.id)
+        .await
+        .expect("Failed to load snapshot")
+        .is_none());
+
+    // A save at the same time replaces the earlier one
+    let mut persisted = PersistedOrderbook {
+        market_id: market.id.clone(),
+        orders: vec![],
+        request_sequence: Some(3),
+        saved_at: Utc::now(),
+    };
+    test_db
+        .db
+        .save_orderbook_snapshot(&persisted)
+        .await
+        .expect("Failed to save snapshot");
+    persisted.orders = orderbook.orders().cloned().collect();
+    persisted.request_sequence = Some(7);
+    test_db
+        .db
+        .save_orderbook_snapshot(&persisted)
+        .await
+        .expect("Failed to save snapshot");
+
+    let loaded = test_db
+        .db
+        .load_latest_orderbook_snapshot(&market.id)
+        .await
+        .expect("Failed to load snapshot")
+        .expect("Snapshot should exist");
+    assert_eq!(loaded.request_sequence, Some(7));
+    assert_eq!(loaded.orders, persisted.orders);
+
+    // The rebuilt book has the same levels as the original
+    let rebuilt = Orderbook::from_orders(loaded.market_id, loaded.orders);
+    let expected = orderbook.snapshot();
+    let actual = rebuilt.snapshot();
+    assert_eq!(actual.bids, expected.bids);
+    assert_eq!(actual.asks, expected.asks);
+    assert_eq!(actual.checksum, expected.checksum);
+    assert_eq!(actual.bids[0].size, 3_000_000);
+    assert_eq!(actual.bids[1].size, 2_000_000);
+}
+
+#[tokio::test]
+async fn test_orderbook_at_starts_from_nearest_snapshot() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let now = Utc::now();
+    let ask = |price: u128| {
+        TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            price,
+            1_000_000,
+        )
+    };
+    for (hours_ago, price) in [(2, 51_000_000_000), (1, 52_000_000_000)] {
+        test_db
+            .db
+            .save_orderbook_snapshot(&PersistedOrderbook {
+                market_id: market.id.clone(),
+                orders: vec![ask(price)],
+                request_sequence: None,
+                saved_at: now - chrono::Duration::hours(hours_ago),
+            })
+            .await
+            .expect("Failed to save snapshot");
+    }
+
+    // Each time starts from the latest snapshot saved by then
+    let book_at = |at: DateTime<Utc>| {
+        let db = test_db.db.clone();
+        let market_id = market.id.clone();
+        async move {
+            db.orderbook_at(&market_id, at)
+                .await
+                .expect("Failed to rebuild book")
+        }
+    };
+    let book = book_at(now - chrono::Duration::minutes(90)).await;
+    assert_eq!(book.asks.len(), 1);
+    assert_eq!(book.asks[0].price, 51_000_000_000);
+    let book = book_at(now).await;
+    assert_eq!(book.asks.len(), 1);
+    assert_eq!(book.asks[0].price, 52_000_000_000);
+
+    // Pruning keeps the newest snapshots, and always the latest
+    let pruned = test_db
+        .db
+        .prune_orderbook_snapshots(0)
+        .await
+        .expect("Failed to prune snapshots");
+    assert_eq!(pruned, 1);
+    assert!(book_at(now - chrono::Duration::minutes(90))
+        .await
+        .asks
+        .is_empty());
+    let latest = test_db
+        .db
+        .load_latest_orderbook_snapshot(&market.id)
+        .await
+        .expect("Failed to load snapshot")
+        .expect("Latest snapshot should be kept");
+    assert_eq!(latest.orders[0].price, 52_000_000_000);
+}
+
+#[tokio::test]
+async fn test_orderbook_at_replays_log_after_snapshot() {
+    let test_db = TestDb::setup()
+        .await
+        .expect("Failed to setup test database");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    let before_snapshot = Utc::now() - chrono::Duration::hours(1);
+
+    // A resting ask saved in a snapshot before anything was logged
+    let snapshot_ask = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        51_000_000_000,
+        2_000_000,
+    );
+    test_db
+        .db
+        .save_orderbook_snapshot(&PersistedOrderbook {
+            market_id: market.id.clone(),
+            orders: vec![snapshot_ask],
+            request_sequence: None,
+            saved_at: Utc::now(),
+        })
+        .await
+        .expect("Failed to save snapshot");
+
+    // A bid, then an ask that partially fills it
+    engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            3_000_000,
+        ))
+        .await
+        .expect("Failed to place bid");
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let intermediate = Utc::now();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    // Between the two orders: the snapshot's ask plus the untouched bid
+    let book = test_db
+        .db
+        .orderbook_at(&market.id, intermediate)
+        .await
+        .expect("Failed to rebuild book");
+    assert_eq!(book.bids.len(), 1);
+    assert_eq!(book.bids[0].price, 50_000_000_000);
+    assert_eq!(book.bids[0].size, 3_000_000);
+    assert_eq!(book.asks.len(), 1);
+    assert_eq!(book.asks[0].price, 51_000_000_000);
+    assert_eq!(book.asks[0].size, 2_000_000);
+
+    // Afterwards the bid is partially filled
+    let book = test_db
+        .db
+        .orderbook_at(&market.id, Utc::now())
+        .await
+        .expect("Failed to rebuild book");
+    assert_eq!(book.bids[0].size, 2_000_000);
+    assert_eq!(book.asks.len(), 1);
+
+    // Before the snapshot and any logged request the book is empty
+    let book = test_db
+        .db
+        .orderbook_at(&market.id, before_snapshot)
+        .await
+        .expect("Failed to rebuild book");
+    assert!(book.bids.is_empty());
+    assert!(book.asks.is_empty());
+}
+
+#[tokio::test]
+async fn test_orderbook_at_replays_snapped_prices() {
+    let test_db = TestDb::setup()
+        .await
+        .expect("Failed to setup test database");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    test_db
+        .db
+        .set_market_snap_to_tick(&market.id, true)
+        .await
+        .expect("Failed to enable snap to tick");
+    let engine = TestEngine::new(&test_db).await;
+
+    // The engine rests $100.0004 at $100.000, the log still holds $100.0004
+    engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            100_000_400,
+            market.min_size,
+        ))
+        .await
+        .expect("Failed to place bid");
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            100_001_000,
+            market.min_size,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    let book = test_db
+        .db
+        .orderbook_at(&market.id, Utc::now())
+        .await
+        .expect("Failed to rebuild book");
+    let live = engine.get_orderbook_snapshot(&market.id).await;
+    assert_eq!(book.bids, live.bids);
+    assert_eq!(book.asks, live.asks);
+    assert_eq!(book.bids[0].price, 100_000_000);
+    assert_eq!(book.asks[0].price, 100_001_000);
+}
+
+#[tokio::test]
+async fn test_orderbook_at_skips_orders_cancelled_below_min_fill() {
+    let test_db = TestDb::setup()
+        .await
+        .expect("Failed to setup test database");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    // Only 1 of the 2 BTC it needs is available, so it neither trades nor rests
+    let mut buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        3_000_000,
+    );
+    buy.min_fill = Some(2_000_000);
+    let placed = engine
+        .place_order(buy)
+        .await
+        .expect("Failed to place min_fill buy");
+    assert!(placed.trades.is_empty());
+
+    let book = test_db
+        .db
+        .orderbook_at(&market.id, Utc::now())
+        .await
+        .expect("Failed to rebuild book");
+    assert!(book.bids.is_empty());
+    assert_eq!(book.asks.len(), 1);
+    assert_eq!(book.asks[0].size, 1_000_000);
+}
@@ -1,5 +1,13 @@
-use backend::models::domain::{OrderStatus, OrderType, Side};
+use backend::config::EngineConfig;
+use backend::engine::MatchingEngine;
+use backend::errors::ExchangeError;
+use backend::models::domain::{
+    Balance, EngineEvent, FeeSchedule, MarketStatus, MatchPricePolicy, OrderStatus, OrderType,
+    Side, TimeInForce,
+};
 use exchange_test_utils::{helpers, TestDb, TestEngine};
+use std::collections::HashMap;
+use tokio::sync::{broadcast, mpsc};
 
 // ============================================================================
 // TESTS
@@ -285,6 +293,54 @@ async fn test_market_order_execution() {
     assert_eq!(placed.order.filled_size, "15000000");
 }
 
+#[tokio::test]
+async fn test_market_order_reports_average_price_and_total_quote() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Two ask levels, one lot each
+    for (seller, price) in [("seller1", 50_000_000_000u128), ("seller2", 50_100_000_000)] {
+        let sell = TestEngine::create_order(
+            seller,
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            price,
+            1_000_000,
+        );
+        engine
+            .place_order(sell)
+            .await
+            .expect("Failed to place sell order");
+    }
+
+    // Market buy sweeps both levels
+    let market_buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Market,
+        0,
+        2_000_000,
+    );
+    let placed = engine
+        .place_order(market_buy)
+        .await
+        .expect("Failed to place market buy");
+
+    assert_eq!(placed.trades.len(), 2);
+    assert_eq!(placed.average_price, "50050000000");
+    // 50,000 * 0.01 + 50,100 * 0.01 in USDC atoms
+    assert_eq!(
+        placed.total_quote,
+        (500_000_000u128 + 501_000_000).to_string()
+    );
+}
+
 #[tokio::test]
 async fn test_order_cancellation() {
     let test_db = TestDb::setup().await.expect("Failed to setup test DB");
@@ -320,6 +376,60 @@ async fn test_order_cancellation() {
     assert_eq!(cancelled.order_id, order_id.to_string());
 }
 
+#[tokio::test]
+async fn test_open_interest_tracks_resting_buy_notional() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Rest 0.03 BTC at $50,000 = $1,500 notional
+    let order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        3_000_000,
+    );
+    let order_id = order.id;
+    engine
+        .place_order(order)
+        .await
+        .expect("Failed to place buy");
+
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 1_500_000_000);
+
+    // A fill of 0.01 BTC releases that part of the notional
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    engine
+        .place_order(sell)
+        .await
+        .expect("Failed to place sell");
+
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 1_000_000_000);
+
+    // Cancelling the remainder releases the rest
+    engine
+        .cancel_order(order_id, "buyer".to_string())
+        .await
+        .expect("Failed to cancel order");
+
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 0);
+}
+
 #[tokio::test]
 async fn test_cannot_cancel_others_order() {
     let test_db = TestDb::setup().await.expect("Failed to setup test DB");
@@ -567,3 +677,1815 @@ async fn test_multiple_orders_complex_matching() {
     assert_eq!(placed.order.status, OrderStatus::Filled);
     assert_eq!(placed.order.filled_size, "400000000");
 }
+
+#[tokio::test]
+async fn test_ioc_limit_order_cancels_remainder() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Single ask for 1 BTC at $100
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        100_000_000, // $100
+        1_000_000,
+    );
+    engine
+        .place_order(sell)
+        .await
+        .expect("Failed to place sell");
+
+    // IOC buy for 3 BTC capped at $100 - only 1 BTC is available
+    let mut buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        3_000_000,
+    );
+    buy.time_in_force = TimeInForce::Ioc;
+
+    let placed = engine
+        .place_order(buy.clone())
+        .await
+        .expect("Failed to place IOC buy");
+
+    // Partially executed IOC orders are final
+    assert_eq!(placed.trades.len(), 1);
+    assert_eq!(placed.order.filled_size, "1000000");
+    assert_eq!(placed.order.status, OrderStatus::Filled);
+    assert_eq!(placed.order.time_in_force, TimeInForce::Ioc);
+
+    let stored = engine.db.get_order(&buy.id).await.expect("Order not found");
+    assert_eq!(stored.status, OrderStatus::Filled);
+    assert_eq!(stored.filled_size, 1_000_000);
+    assert_eq!(stored.time_in_force, TimeInForce::Ioc);
+
+    // Remainder is unlocked rather than held for a resting bid
+    let balance = engine
+        .db
+        .get_balance("buyer", "USDC")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.open_interest, 0);
+
+    // A new ask at the same price finds no resting bid
+    let sell2 = TestEngine::create_order(
+        "seller2",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(sell2)
+        .await
+        .expect("Failed to place sell2");
+    assert_eq!(placed.trades.len(), 0);
+    assert_eq!(placed.order.status, OrderStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_ioc_limit_order_without_match_is_cancelled() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    let mut buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    buy.time_in_force = TimeInForce::Ioc;
+
+    let placed = engine
+        .place_order(buy)
+        .await
+        .expect("Failed to place IOC buy");
+
+    assert_eq!(placed.trades.len(), 0);
+    assert_eq!(placed.order.status, OrderStatus::Cancelled);
+
+    let balance = engine
+        .db
+        .get_balance("buyer", "USDC")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.open_interest, 0);
+}
+
+#[tokio::test]
+async fn test_min_fill_above_liquidity_cancels_without_trading() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Only 1 BTC offered
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    engine
+        .place_order(sell.clone())
+        .await
+        .expect("Failed to place sell");
+
+    // Buy 3 BTC but insist on at least 2 filling
+    let mut buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        3_000_000,
+    );
+    buy.min_fill = Some(2_000_000);
+
+    let placed = engine
+        .place_order(buy.clone())
+        .await
+        .expect("Failed to place min_fill buy");
+
+    assert_eq!(placed.trades.len(), 0);
+    assert_eq!(placed.order.status, OrderStatus::Cancelled);
+    assert_eq!(placed.order.filled_size, "0");
+
+    let stored = engine.db.get_order(&buy.id).await.expect("Order not found");
+    assert_eq!(stored.status, OrderStatus::Cancelled);
+
+    // The whole lock is released and the ask is untouched
+    let balance = engine
+        .db
+        .get_balance("buyer", "USDC")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.open_interest, 0);
+    let maker = engine
+        .db
+        .get_order(&sell.id)
+        .await
+        .expect("Order not found");
+    assert_eq!(maker.filled_size, 0);
+    assert_eq!(maker.status, OrderStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_min_fill_met_executes_and_rests_remainder() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        100_000_000,
+        2_000_000,
+    );
+    engine
+        .place_order(sell)
+        .await
+        .expect("Failed to place sell");
+
+    // 2 of 3 BTC available meets a min_fill of 2, the rest rests like any GTC order
+    let mut buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        3_000_000,
+    );
+    buy.min_fill = Some(2_000_000);
+
+    let placed = engine
+        .place_order(buy)
+        .await
+        .expect("Failed to place min_fill buy");
+
+    assert_eq!(placed.trades.len(), 1);
+    assert_eq!(placed.order.filled_size, "2000000");
+    assert_eq!(placed.order.status, OrderStatus::PartiallyFilled);
+
+    // min_fill above the order size is rejected outright
+    let mut invalid = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    invalid.min_fill = Some(2_000_000);
+    assert!(engine.place_order(invalid).await.is_err());
+}
+
+#[tokio::test]
+async fn test_zero_size_and_zero_limit_price_rejected() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    for order_type in [OrderType::Limit, OrderType::Market] {
+        let zero_size =
+            TestEngine::create_order("buyer", &market.id, Side::Buy, order_type, 100_000_000, 0);
+        let err = engine.place_order(zero_size).await.unwrap_err();
+        assert!(err.contains("size must be greater than 0"), "{}", err);
+    }
+
+    let zero_price = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        0,
+        1_000_000,
+    );
+    let err = engine.place_order(zero_price).await.unwrap_err();
+    assert!(
+        err.contains("Limit order price must be greater than 0"),
+        "{}",
+        err
+    );
+
+    // Nothing was locked for the rejected orders
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 0);
+
+    // A market sell ignores its price entirely
+    let bid = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    engine.place_order(bid).await.expect("Failed to place bid");
+    let market_sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Market,
+        0,
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(market_sell)
+        .await
+        .expect("Market sell without a price should be accepted");
+    assert_eq!(placed.trades.len(), 1);
+    assert_eq!(placed.trades[0].price, "100000000");
+}
+
+#[tokio::test]
+async fn test_price_band_rejects_out_of_range_limit_orders() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_token(&test_db, "BTC", 8, "BTC Token")
+        .await
+        .expect("Failed to create BTC");
+    helpers::create_token(&test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create USDC");
+
+    // Only accept limit prices between $90 and $110
+    let market = test_db
+        .db
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1_000_000,
+            1_000_000,
+            10,
+            20,
+            Some(90_000_000),
+            Some(110_000_000),
+        )
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Below min_price
+    let low = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        10_000_000, // $10
+        1_000_000,
+    );
+    let err = engine.place_order(low).await.unwrap_err();
+    assert!(
+        err.contains("below market minimum price 90000000"),
+        "{}",
+        err
+    );
+
+    // Above max_price
+    let high = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        1_000_000_000, // $1,000
+        1_000_000,
+    );
+    let err = engine.place_order(high).await.unwrap_err();
+    assert!(
+        err.contains("above market maximum price 110000000"),
+        "{}",
+        err
+    );
+
+    // Nothing was locked for rejected orders
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 0);
+
+    // Inside the band
+    let normal = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000, // $100
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(normal)
+        .await
+        .expect("Order inside the band should be accepted");
+    assert_eq!(placed.order.status, OrderStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_open_order_limits_per_user() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // At most 3 resting orders per user
+    test_db
+        .db
+        .set_market_order_limits(&market.id, Some(3), None)
+        .await
+        .expect("Failed to set order limits");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // 0.01 BTC at $100 = $1 notional each
+    let resting = |price: u128, size: u128| {
+        TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            price,
+            size,
+        )
+    };
+
+    let mut order_ids = Vec::new();
+    for _ in 0..3 {
+        let placed = engine
+            .place_order(resting(100_000_000, 1_000_000))
+            .await
+            .expect("Order under the limit should be accepted");
+        order_ids.push(placed.order.id);
+    }
+
+    let err = engine
+        .place_order(resting(100_000_000, 1_000_000))
+        .await
+        .unwrap_err();
+    assert!(err.contains("Open order limit of 3"), "{}", err);
+
+    // Other users are not affected
+    let other = TestEngine::create_order(
+        "alice",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        1_000_000,
+    );
+    engine.place_order(other).await.expect("Limit is per user");
+
+    // Cancelling frees a slot
+    let order_id = order_ids.pop().unwrap().parse().unwrap();
+    engine
+        .cancel_order(order_id, "buyer".to_string())
+        .await
+        .expect("Failed to cancel order");
+    engine
+        .place_order(resting(100_000_000, 1_000_000))
+        .await
+        .expect("Order should be accepted after a cancel");
+
+    // Switch to a $4 notional cap with 3 orders ($3) resting
+    test_db
+        .db
+        .set_market_order_limits(&market.id, None, Some(4_000_000))
+        .await
+        .expect("Failed to set order limits");
+
+    let err = engine
+        .place_order(resting(100_000_000, 2_000_000))
+        .await
+        .unwrap_err();
+    assert!(err.contains("Open notional limit of 4000000"), "{}", err);
+
+    engine
+        .place_order(resting(100_000_000, 1_000_000))
+        .await
+        .expect("Order within the notional cap should be accepted");
+}
+
+#[tokio::test]
+async fn test_min_notional_rejects_small_limit_orders() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // Limit orders must be worth at least $5
+    let market = test_db
+        .db
+        .set_market_min_notional(&market.id, Some(5_000_000))
+        .await
+        .expect("Failed to set min notional");
+    assert_eq!(market.min_notional, Some(5_000_000));
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // 0.01 BTC (the minimum size) at $100 = $1
+    let small = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        market.min_size,
+    );
+    let err = engine.place_order(small).await.unwrap_err();
+    assert!(
+        err.contains("Order notional 1000000 is below market minimum notional 5000000"),
+        "{}",
+        err
+    );
+
+    // Nothing was locked for the rejected order
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 0);
+
+    // 0.05 BTC at $100 = $5
+    let large = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        5 * market.min_size,
+    );
+    let placed = engine
+        .place_order(large)
+        .await
+        .expect("Order at the min notional should be accepted");
+    assert_eq!(placed.order.status, OrderStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_snap_to_tick_accepts_off_tick_limit_prices() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    // Strict by default: $100.0004 is off the 0.001 tick
+    let off_tick = || {
+        TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            100_000_400,
+            market.min_size,
+        )
+    };
+    let err = engine.place_order(off_tick()).await.unwrap_err();
+    assert!(err.contains("tick size"), "{}", err);
+
+    let market = test_db
+        .db
+        .set_market_snap_to_tick(&market.id, true)
+        .await
+        .expect("Failed to enable snap to tick");
+    assert!(market.snap_to_tick);
+
+    // Rounds to the nearest tick and reports the submitted price
+    let placed = engine
+        .place_order(off_tick())
+        .await
+        .expect("Off-tick order should be snapped");
+    assert_eq!(placed.order.price, "100000000");
+    assert_eq!(placed.requested_price.as_deref(), Some("100000400"));
+
+    // Halfway rounds up
+    let placed = engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            100_000_500,
+            market.min_size,
+        ))
+        .await
+        .expect("Off-tick order should be snapped");
+    assert_eq!(placed.order.price, "100001000");
+
+    // The resting order sits at the snapped price
+    let snapshot = engine.get_orderbook_snapshot(&market.id).await;
+    assert_eq!(snapshot.bids[0].price, 100_001_000);
+
+    // On-tick prices are left alone
+    let placed = engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            99_000_000,
+            market.min_size,
+        ))
+        .await
+        .expect("On-tick order should be accepted");
+    assert_eq!(placed.order.price, "99000000");
+    assert_eq!(placed.requested_price, None);
+}
+
+#[tokio::test]
+async fn test_single_order_update_per_order_when_sweeping_levels() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let mut engine = TestEngine::new(&test_db).await;
+
+    // Same maker rests asks on two price levels
+    let mut maker_ids = Vec::new();
+    for price in [100_000_000, 101_000_000] {
+        let sell = TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            price,
+            1_000_000,
+        );
+        maker_ids.push(sell.id);
+        engine
+            .place_order(sell)
+            .await
+            .expect("Failed to place sell");
+    }
+
+    // Drain events from placing the makers
+    while engine.event_rx.try_recv().is_ok() {}
+
+    // Taker sweeps both levels and rests the remainder
+    let buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        101_000_000,
+        3_000_000,
+    );
+    let taker_id = buy.id;
+    let placed = engine.place_order(buy).await.expect("Failed to place buy");
+    assert_eq!(placed.trades.len(), 2);
+
+    // Events are broadcast before the engine responds
+    let mut updates: HashMap<uuid::Uuid, Vec<OrderStatus>> = HashMap::new();
+    while let Ok(event) = engine.event_rx.try_recv() {
+        if let EngineEvent::OrderPlaced { order } = event {
+            updates.entry(order.id).or_default().push(order.status);
+        }
+    }
+
+    assert_eq!(
+        updates.len(),
+        3,
+        "Expected updates for 2 makers and the taker"
+    );
+    for maker_id in &maker_ids {
+        assert_eq!(updates[maker_id], vec![OrderStatus::Filled]);
+    }
+    assert_eq!(updates[&taker_id], vec![OrderStatus::PartiallyFilled]);
+}
+
+#[tokio::test]
+async fn test_fee_tier_discounts_taker_fee() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // buyer2 gets a 0.5 bp taker fee (market default is 20 bps)
+    engine
+        .db
+        .set_user_fee_tier(
+            "buyer2",
+            FeeSchedule {
+                maker_fee_tenth_bps: 0,
+                taker_fee_tenth_bps: 5,
+            },
+        )
+        .await
+        .expect("Failed to set fee tier");
+
+    let mut received = HashMap::new();
+    for (maker, taker) in [("seller1", "buyer1"), ("seller2", "buyer2")] {
+        let before = engine.db.get_balance(taker, "BTC").await.unwrap().amount;
+
+        let sell_order = TestEngine::create_order(
+            maker,
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000, // $50,000
+            1_000_000,      // 0.01 BTC
+        );
+        engine
+            .place_order(sell_order)
+            .await
+            .expect("Failed to place sell order");
+
+        let buy_order = TestEngine::create_order(
+            taker,
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        );
+        let placed = engine
+            .place_order(buy_order)
+            .await
+            .expect("Failed to place buy order");
+        assert_eq!(placed.order.status, OrderStatus::Filled);
+
+        let after = engine.db.get_balance(taker, "BTC").await.unwrap().amount;
+        received.insert(taker, after - before);
+    }
+
+    // Taker fee is charged on the base tokens the buyer receives
+    assert_eq!(received["buyer1"], 1_000_000 - 2_000); // 20 bps
+    assert_eq!(received["buyer2"], 1_000_000 - 50); // 0.5 bp
+    assert!(received["buyer2"] > received["buyer1"]);
+}
+
+#[tokio::test]
+async fn test_negative_maker_fee_pays_rebate() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_token(&test_db, "BTC", 8, "BTC Token")
+        .await
+        .expect("Failed to create BTC");
+    helpers::create_token(&test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create USDC");
+
+    // A rebate larger than the taker fee is rejected
+    let rejected = test_db
+        .db
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1000000,
+            1000000,
+            -11,
+            10,
+            None,
+            None,
+        )
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(ExchangeError::InvalidParameter { .. })
+    ));
+
+    // -2 bps maker (rebate), 10 bps taker
+    let market = test_db
+        .db
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1000000,
+            1000000,
+            -2,
+            10,
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Rebates are paid out of the fee recipient's balance
+    engine
+        .db
+        .add_balance("system", "USDC", 1_000_000)
+        .await
+        .expect("Failed to fund fee recipient");
+
+    let maker_before = engine
+        .db
+        .get_balance("seller", "USDC")
+        .await
+        .unwrap()
+        .amount;
+    let system_btc_before = engine
+        .db
+        .get_balance("system", "BTC")
+        .await
+        .map(|b| b.amount)
+        .unwrap_or(0);
+
+    let sell_order = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000, // $50,000
+        1_000_000,      // 0.01 BTC
+    );
+    engine
+        .place_order(sell_order)
+        .await
+        .expect("Failed to place sell order");
+
+    let buy_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(buy_order)
+        .await
+        .expect("Failed to place buy order");
+    assert_eq!(placed.order.status, OrderStatus::Filled);
+
+    // Maker receives $500 plus a 2 bps rebate ($0.10)
+    let maker_after = engine
+        .db
+        .get_balance("seller", "USDC")
+        .await
+        .unwrap()
+        .amount;
+    assert_eq!(maker_after - maker_before, 500_000_000 + 100_000);
+
+    // Fee recipient paid the rebate in quote and collected the taker fee in base
+    let system_usdc = engine.db.get_balance("system", "USDC").await.unwrap();
+    assert_eq!(system_usdc.amount, 1_000_000 - 100_000);
+    let system_btc = engine.db.get_balance("system", "BTC").await.unwrap();
+    assert_eq!(system_btc.amount - system_btc_before, 1_000); // 10 bps of 0.01 BTC
+}
+
+#[tokio::test]
+async fn test_maker_rebate_with_unfunded_fee_recipient() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_token(&test_db, "BTC", 8, "BTC Token")
+        .await
+        .expect("Failed to create BTC");
+    helpers::create_token(&test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create USDC");
+    // -2 bps maker (rebate), 10 bps taker
+    let market = test_db
+        .db
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1000000,
+            1000000,
+            -2,
+            10,
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            check_balance_invariants: true,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // The fee recipient holds nothing to pay rebates from
+    let balance = |user: &'static str, token: &'static str| {
+        let db = engine.db.clone();
+        async move { db.get_balance(user, token).await.map_or(0, |b| b.amount) }
+    };
+    assert_eq!(balance("system", "USDC").await, 0);
+    let maker_before = balance("seller", "USDC").await;
+
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place sell order");
+    let placed = engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Unfunded fee recipient failed the match");
+    assert_eq!(placed.order.status, OrderStatus::Filled);
+
+    // The maker still gets the $0.10 rebate, owed by the fee recipient
+    assert_eq!(
+        balance("seller", "USDC").await - maker_before,
+        500_000_000 + 100_000
+    );
+    assert_eq!(balance("system", "USDC").await, 0);
+    assert_eq!(
+        engine.db.get_fee_liability("system", "USDC").await.unwrap(),
+        100_000
+    );
+    assert_eq!(balance("system", "BTC").await, 1_000);
+
+    // A selling taker's quote fee ($0.50) repays the liability first.
+    // The buying maker's base rebate comes out of the BTC fee collected above
+    engine
+        .place_order(TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place buy order");
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place sell order");
+
+    assert_eq!(
+        engine.db.get_fee_liability("system", "USDC").await.unwrap(),
+        0
+    );
+    assert_eq!(balance("system", "USDC").await, 500_000 - 100_000);
+    assert_eq!(balance("system", "BTC").await, 1_000 - 200);
+}
+
+#[tokio::test]
+async fn test_fees_credited_to_configured_fee_recipient() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_token(&test_db, "BTC", 8, "BTC Token")
+        .await
+        .expect("Failed to create BTC");
+    helpers::create_token(&test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create USDC");
+
+    // 10 bps maker, 20 bps taker
+    let market = test_db
+        .db
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1000000,
+            1000000,
+            10,
+            20,
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            fee_recipient: "treasury".to_string(),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let balance = |user: &'static str, token: &'static str| {
+        let db = engine.db.clone();
+        async move {
+            db.get_balance(user, token)
+                .await
+                .map(|b| b.amount)
+                .unwrap_or(0)
+        }
+    };
+    let system_usdc_before = balance("system", "USDC").await;
+    let system_btc_before = balance("system", "BTC").await;
+
+    let sell_order = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000, // $50,000
+        1_000_000,      // 0.01 BTC
+    );
+    engine
+        .place_order(sell_order)
+        .await
+        .expect("Failed to place sell order");
+
+    let buy_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(buy_order)
+        .await
+        .expect("Failed to place buy order");
+    assert_eq!(placed.order.status, OrderStatus::Filled);
+
+    // Taker buyer pays 20 bps of 0.01 BTC, maker seller pays 10 bps of $500
+    assert_eq!(balance("treasury", "BTC").await, 2_000);
+    assert_eq!(balance("treasury", "USDC").await, 500_000);
+
+    // Nothing went to the default recipient
+    assert_eq!(balance("system", "BTC").await, system_btc_before);
+    assert_eq!(balance("system", "USDC").await, system_usdc_before);
+}
+
+#[tokio::test]
+async fn test_market_last_price_follows_trades() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    assert_eq!(market.last_price, None);
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Two resting asks, a buy sweeps both and the last fill sets the price
+    for price in [50_000_000_000, 50_100_000_000] {
+        let sell_order = TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            price,
+            1_000_000,
+        );
+        engine
+            .place_order(sell_order)
+            .await
+            .expect("Failed to place sell order");
+    }
+    let market = engine.db.get_market(&market.id).await.unwrap();
+    assert_eq!(market.last_price, None);
+
+    let buy_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_100_000_000,
+        2_000_000,
+    );
+    let placed = engine
+        .place_order(buy_order)
+        .await
+        .expect("Failed to place buy order");
+    assert_eq!(placed.trades.len(), 2);
+
+    let market = engine.db.get_market(&market.id).await.unwrap();
+    assert_eq!(market.last_price, Some(50_100_000_000));
+}
+
+#[tokio::test]
+async fn test_cancel_all_orders_reports_refunds() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    let mut order_ids = Vec::new();
+    for price in [49_000_000_000u128, 48_000_000_000] {
+        let buy_order = TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            price,
+            1_000_000, // 0.01 BTC
+        );
+        order_ids.push(buy_order.id.to_string());
+        engine
+            .place_order(buy_order)
+            .await
+            .expect("Failed to place buy order");
+    }
+
+    let cancelled = engine
+        .cancel_all_orders("buyer".to_string(), Some(market.id.clone()))
+        .await
+        .expect("Failed to cancel all orders");
+    assert_eq!(cancelled.count, 2);
+    assert_eq!(cancelled.refunds.len(), 2);
+
+    // Refund is price * unfilled_size in quote atoms (scaled by BTC's 8 decimals)
+    let refunds: HashMap<_, _> = cancelled
+        .refunds
+        .iter()
+        .map(|r| (r.order_id.clone(), r))
+        .collect();
+    for (order_id, price) in order_ids.iter().zip([49_000_000_000u128, 48_000_000_000]) {
+        let refund = refunds[order_id];
+        assert_eq!(refund.token, "USDC");
+        assert_eq!(refund.amount, (price * 1_000_000 / 100_000_000).to_string());
+    }
+
+    // Everything locked by the two orders was released
+    let balance = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(balance.open_interest, 0);
+}
+
+#[tokio::test]
+async fn test_cancel_everything_flattens_all_markets() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let btc = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create BTC market");
+    let eth = helpers::create_market_with_tokens(&test_db, "ETH", "USDC")
+        .await
+        .expect("Failed to create ETH market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Bids and asks from several users on both markets
+    let orders = [
+        ("alice", &btc.id, Side::Buy, 49_000_000_000u128),
+        ("alice", &eth.id, Side::Sell, 3_100_000_000),
+        ("bob", &btc.id, Side::Sell, 51_000_000_000),
+        ("bob", &eth.id, Side::Buy, 2_900_000_000),
+        ("carol", &btc.id, Side::Buy, 48_000_000_000),
+    ];
+    let mut order_ids = Vec::new();
+    for (user, market_id, side, price) in orders {
+        let order =
+            TestEngine::create_order(user, market_id, side, OrderType::Limit, price, 1_000_000);
+        order_ids.push(order.id);
+        engine
+            .place_order(order)
+            .await
+            .expect("Failed to place order");
+    }
+
+    let locked: Vec<Balance> = {
+        let mut balances = Vec::new();
+        for user in ["alice", "bob", "carol"] {
+            for token in ["BTC", "ETH", "USDC"] {
+                if let Ok(balance) = engine.db.get_balance(user, token).await {
+                    balances.push(balance);
+                }
+            }
+        }
+        balances
+    };
+    assert!(locked.iter().any(|balance| balance.open_interest > 0));
+
+    let count = engine
+        .cancel_everything()
+        .await
+        .expect("Failed to cancel everything");
+    assert_eq!(count, orders.len());
+
+    // Both books are empty and every order is marked cancelled
+    for market_id in [&btc.id, &eth.id] {
+        let snapshot = engine.get_orderbook_snapshot(market_id).await;
+        assert!(snapshot.bids.is_empty() && snapshot.asks.is_empty());
+    }
+    for order_id in &order_ids {
+        let order = engine.db.get_order(order_id).await.unwrap();
+        assert_eq!(order.status, OrderStatus::Cancelled);
+    }
+
+    // Every lock was released without touching totals
+    for before in locked {
+        let after = engine
+            .db
+            .get_balance(&before.user_address, &before.token_ticker)
+            .await
+            .unwrap();
+        assert_eq!(after.open_interest, 0, "{:?}", after);
+        assert_eq!(after.amount, before.amount, "{:?}", after);
+    }
+
+    // Nothing left to cancel
+    assert_eq!(engine.cancel_everything().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_replace_order_moves_bid_and_locks_only_delta() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&test_db).await;
+
+    // Resting bid for 1 BTC at $100 locks 100 USDC
+    let old_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        100_000_000,
+        100_000_000,
+    );
+    let old_order_id = old_order.id;
+    engine
+        .place_order(old_order)
+        .await
+        .expect("Failed to place bid");
+    let before = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(before.open_interest, 100_000_000);
+
+    // Move it up to $101
+    let new_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        101_000_000,
+        100_000_000,
+    );
+    let new_order_id = new_order.id;
+    let replaced = engine
+        .replace_order(old_order_id, new_order)
+        .await
+        .expect("Failed to replace bid");
+
+    assert_eq!(replaced.cancelled.order_id, old_order_id.to_string());
+    assert_eq!(replaced.placed.order.id, new_order_id.to_string());
+    assert_eq!(replaced.placed.order.status, OrderStatus::Pending);
+    assert!(replaced.placed.trades.is_empty());
+
+    let old = engine.db.get_order(&old_order_id).await.unwrap();
+    assert_eq!(old.status, OrderStatus::Cancelled);
+
+    let snapshot = engine.get_orderbook_snapshot(&market.id).await;
+    assert_eq!(snapshot.bids.len(), 1);
+    assert_eq!(snapshot.bids[0].price, 101_000_000);
+
+    // Only the extra 1 USDC was locked, total holdings are untouched
+    let after = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    assert_eq!(after.open_interest - before.open_interest, 1_000_000);
+    assert_eq!(after.amount, before.amount);
+
+    // The old order id no longer resolves
+    let result = engine
+        .replace_order(
+            old_order_id,
+            TestEngine::create_order(
+                "buyer",
+                &market.id,
+                Side::Buy,
+                OrderType::Limit,
+                102_000_000,
+                100_000_000,
+            ),
+        )
+        .await;
+    assert!(result.is_err());
+}
+
+/// Resting ask at $50,000, crossed by a buy limit at $50,200 (0.01 BTC)
+/// Returns the trade price and the buyer's USDC balance before and after
+async fn cross_with_improvement(policy: MatchPricePolicy) -> (String, Balance, Balance) {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            match_price_policy: policy,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let sell_order = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    engine
+        .place_order(sell_order)
+        .await
+        .expect("Failed to place sell order");
+
+    let before = engine.db.get_balance("buyer", "USDC").await.unwrap();
+
+    let buy_order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_200_000_000,
+        1_000_000,
+    );
+    let placed = engine
+        .place_order(buy_order)
+        .await
+        .expect("Failed to place buy order");
+    assert_eq!(placed.order.status, OrderStatus::Filled);
+    assert_eq!(placed.trades.len(), 1);
+
+    let after = engine.db.get_balance("buyer", "USDC").await.unwrap();
+    (placed.trades[0].price.clone(), before, after)
+}
+
+#[tokio::test]
+async fn test_maker_price_policy_refunds_full_improvement() {
+    let (price, before, after) = cross_with_improvement(MatchPricePolicy::Maker).await;
+
+    // Executes at the resting price
+    assert_eq!(price, "50000000000");
+
+    // Locked 502 USDC at the limit, paid 500 USDC, the 2 USDC difference is released
+    assert_eq!(before.amount - after.amount, 500_000_000);
+    assert_eq!(after.open_interest, 0);
+}
+
+#[tokio::test]
+async fn test_midpoint_price_policy_splits_spread() {
+    let (price, before, after) = cross_with_improvement(MatchPricePolicy::Midpoint).await;
+
+    // Executes halfway between the maker price and the taker limit
+    assert_eq!(price, "50100000000");
+
+    // Locked 502 USDC at the limit, paid 501 USDC, the 1 USDC difference is released
+    assert_eq!(before.amount - after.amount, 501_000_000);
+    assert_eq!(after.open_interest, 0);
+}
+
+#[tokio::test]
+async fn test_replay_request_log_rebuilds_orderbook() {
+    let source = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&source, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new(&source).await;
+
+    let requests = [
+        ("seller", Side::Sell, 51_000_000_000, 2_000_000),
+        ("seller1", Side::Sell, 50_000_000_000, 1_000_000),
+        ("buyer", Side::Buy, 49_000_000_000, 1_000_000),
+        ("buyer1", Side::Buy, 48_000_000_000, 3_000_000),
+        // Crosses: fills 0.01 at $50,000 and rests the remainder at $50,500
+        ("buyer2", Side::Buy, 50_500_000_000, 2_000_000),
+    ];
+    let mut order_ids = Vec::new();
+    for (user, side, price, size) in requests {
+        let order = TestEngine::create_order(user, &market.id, side, OrderType::Limit, price, size);
+        order_ids.push(order.id);
+        engine
+            .place_order(order)
+            .await
+            .expect("Failed to place order");
+    }
+    engine
+        .cancel_order(order_ids[2], "buyer".to_string())
+        .await
+        .expect("Failed to cancel order");
+
+    let expected = engine.get_orderbook_snapshot(&market.id).await;
+    assert_eq!(expected.bids.len(), 2);
+
+    // Fresh database with the same tokens, market and funded users
+    let target = TestDb::setup().await.expect("Failed to setup test DB");
+    helpers::create_market_with_tokens(&target, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let _funded = TestEngine::new(&target).await;
+
+    let (_engine_tx, engine_rx) = mpsc::channel(1);
+    let (event_tx, _event_rx) = broadcast::channel(1000);
+    let mut replayed = MatchingEngine::new(target.db.clone(), engine_rx, event_tx);
+    let applied = replayed
+        .replay_from(&source.db, 0)
+        .await
+        .expect("Failed to replay request log");
+    assert_eq!(applied, 6);
+
+    let actual = replayed.orderbook_snapshot(&market.id).await;
+    let levels = |levels: &[backend::models::domain::OrderbookLevel]| {
+        levels
+            .iter()
+            .map(|level| (level.price, level.size))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(levels(&actual.bids), levels(&expected.bids));
+    assert_eq!(levels(&actual.asks), levels(&expected.asks));
+    assert_eq!(actual.checksum, expected.checksum);
+
+    // Balances follow the same path
+    for user in ["buyer2", "seller1"] {
+        for token in ["BTC", "USDC"] {
+            let expected = source.db.get_balance(user, token).await.unwrap();
+            let actual = target.db.get_balance(user, token).await.unwrap();
+            assert_eq!(actual.amount, expected.amount, "{} {}", user, token);
+            assert_eq!(actual.open_interest, expected.open_interest);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_cancels_past_rate_limit_are_throttled() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            max_cancels_per_sec: 3,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let mut order_ids = Vec::new();
+    for i in 0..5u128 {
+        let order = TestEngine::create_order(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            40_000_000_000 + i * 1_000_000,
+            1_000_000,
+        );
+        order_ids.push(order.id);
+        engine
+            .place_order(order)
+            .await
+            .expect("Failed to place order");
+    }
+
+    // A burst of one second's worth goes through, the rest is throttled
+    let mut results = Vec::new();
+    for order_id in &order_ids {
+        results.push(engine.cancel_order(*order_id, "buyer".to_string()).await);
+    }
+    assert!(results[..3].iter().all(|r| r.is_ok()));
+    for result in &results[3..] {
+        let err = result
+            .as_ref()
+            .expect_err("Cancel past the limit should fail");
+        assert!(err.contains("Cancel rate limit of 3"), "{}", err);
+    }
+
+    // Other users have their own allowance
+    let other = TestEngine::create_order(
+        "alice",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        40_000_000_000,
+        1_000_000,
+    );
+    let other_id = other.id;
+    engine.place_order(other).await.expect("Failed to place");
+    engine
+        .cancel_order(other_id, "alice".to_string())
+        .await
+        .expect("Another user's cancel should not be throttled");
+
+    // Placement is not limited
+    let order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        39_000_000_000,
+        1_000_000,
+    );
+    engine
+        .place_order(order)
+        .await
+        .expect("Placement should still work while cancels are throttled");
+
+    // The kill switch bypasses the limit and clears the throttled orders
+    let cancelled = engine
+        .cancel_everything()
+        .await
+        .expect("Kill switch should not be throttled");
+    assert_eq!(cancelled, 3);
+}
+
+#[tokio::test]
+async fn test_pre_open_market_collects_orders_and_matches_at_open() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    let pre_open = engine
+        .set_market_status(&market.id, MarketStatus::PreOpen)
+        .await
+        .expect("Failed to move market to pre-open");
+    assert_eq!(pre_open.status, MarketStatus::PreOpen);
+
+    // Crossed limit orders rest without trading
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    let sell_id = sell.id;
+    let placed = engine
+        .place_order(sell)
+        .await
+        .expect("Failed to place sell");
+    assert!(placed.trades.is_empty());
+
+    let buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        51_000_000_000,
+        1_000_000,
+    );
+    let buy_id = buy.id;
+    let placed = engine.place_order(buy).await.expect("Failed to place buy");
+    assert!(placed.trades.is_empty(), "Pre-open orders must not match");
+    assert_eq!(placed.order.status, OrderStatus::Pending);
+
+    let book = engine.get_orderbook_snapshot(&market.id).await;
+    assert_eq!((book.bids.len(), book.asks.len()), (1, 1));
+
+    // Orders that can't rest are refused until the open
+    let market_buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Market,
+        51_000_000_000,
+        1_000_000,
+    );
+    let err = engine
+        .place_order(market_buy)
+        .await
+        .expect_err("Market orders should be rejected while pre-open");
+    assert!(err.contains("pre_open"), "{}", err);
+
+    // Opening matches the crossed orders at the earlier order's price
+    let mut events = engine.event_tx().subscribe();
+    let open = engine
+        .set_market_status(&market.id, MarketStatus::Open)
+        .await
+        .expect("Failed to open market");
+    assert_eq!(open.status, MarketStatus::Open);
+
+    let trade = loop {
+        match events.recv().await.expect("Event stream closed") {
+            EngineEvent::TradeExecuted { trade } => break trade,
+            _ => continue,
+        }
+    };
+    assert_eq!(trade.seller_order_id, sell_id);
+    assert_eq!(trade.buyer_order_id, buy_id);
+    assert_eq!(trade.price, 50_000_000_000);
+    assert_eq!(trade.size, 1_000_000);
+
+    let book = engine.get_orderbook_snapshot(&market.id).await;
+    assert!(book.bids.is_empty() && book.asks.is_empty());
+    let sell = engine.db.get_order(&sell_id).await.expect("Sell order");
+    assert_eq!(sell.status, OrderStatus::Filled);
+
+    // Halted and closed markets refuse new orders
+    for status in [MarketStatus::Halted, MarketStatus::Closed] {
+        engine
+            .set_market_status(&market.id, status)
+            .await
+            .expect("Failed to change market status");
+        let order = TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        );
+        let err = engine
+            .place_order(order)
+            .await
+            .expect_err("Order should be rejected");
+        assert!(err.contains(&status.to_string()), "{}", err);
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_explains_why_order_is_not_cancellable() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    let sell = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    let sell_id = sell.id;
+    engine
+        .place_order(sell)
+        .await
+        .expect("Failed to place sell");
+    let buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    engine.place_order(buy).await.expect("Failed to place buy");
+
+    // Filled orders say so
+    let err = engine
+        .cancel_order(sell_id, "seller".to_string())
+        .await
+        .expect_err("Filled order should not be cancellable");
+    assert!(err.contains("is filled and cannot be cancelled"), "{}", err);
+
+    // Cancelling twice says the order is already cancelled
+    let resting = TestEngine::create_order(
+        "seller",
+        &market.id,
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    let resting_id = resting.id;
+    engine
+        .place_order(resting)
+        .await
+        .expect("Failed to place order");
+    engine
+        .cancel_order(resting_id, "seller".to_string())
+        .await
+        .expect("First cancel should succeed");
+    let err = engine
+        .cancel_order(resting_id, "seller".to_string())
+        .await
+        .expect_err("Second cancel should fail");
+    assert!(
+        err.contains("is cancelled and cannot be cancelled"),
+        "{}",
+        err
+    );
+
+    // Someone else's order and an unknown id are both just not found
+    let err = engine
+        .cancel_order(sell_id, "buyer".to_string())
+        .await
+        .expect_err("Other user's order should not be cancellable");
+    assert!(err.contains("Order not found"), "{}", err);
+    let err = engine
+        .cancel_order(uuid::Uuid::new_v4(), "seller".to_string())
+        .await
+        .expect_err("Unknown order should not be cancellable");
+    assert!(err.contains("Order not found"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_trades_are_queried_in_execution_order() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new(&test_db).await;
+
+    // Three makers at one price, swept by a single buy in one engine turn
+    let makers = ["seller1", "seller2", "seller3"];
+    for maker in makers {
+        let order = TestEngine::create_order(
+            maker,
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        );
+        engine
+            .place_order(order)
+            .await
+            .expect("Failed to place sell");
+    }
+    let buy = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        3_000_000,
+    );
+    let placed = engine.place_order(buy).await.expect("Failed to place buy");
+    let executed: Vec<_> = placed.trades.iter().map(|t| t.sequence).collect();
+    assert_eq!(executed, vec![1, 2, 3]);
+
+    // Most recent first: the reverse of the order the makers were filled in
+    let expected: Vec<_> = makers.iter().rev().map(|m| m.to_string()).collect();
+    let sellers = |trades: &[backend::models::domain::Trade]| -> Vec<String> {
+        trades.iter().map(|t| t.seller_address.clone()).collect()
+    };
+
+    let trades = test_db
+        .db
+        .get_market_trades(&market.id, 10)
+        .await
+        .expect("Failed to get market trades");
+    assert_eq!(sellers(&trades), expected);
+
+    let trades = test_db
+        .db
+        .get_user_trades("buyer", Some(&market.id), 10)
+        .await
+        .expect("Failed to get user trades");
+    assert_eq!(sellers(&trades), expected);
+
+    // ClickHouse inserts are asynchronous and can land in any order
+    let mut trades = Vec::new();
+    for _ in 0..50 {
+        trades = test_db
+            .db
+            .get_recent_trades(&market.id, None, None, 10)
+            .await
+            .expect("Failed to get recent trades");
+        if trades.len() == makers.len() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(sellers(&trades), expected);
+}
+
+#[tokio::test]
+async fn test_match_delay_lines_up_concurrent_orders() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let delay = std::time::Duration::from_millis(200);
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            match_delay: Some(delay),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    // Both buys are queued before the engine applies the first, so they reach
+    // the single ask in send order rather than whichever task runs first
+    let started = std::time::Instant::now();
+    let (first, second) = tokio::join!(
+        engine.place_order(TestEngine::create_order(
+            "buyer1",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        )),
+        engine.place_order(TestEngine::create_order(
+            "buyer2",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        )),
+    );
+    let first = first.expect("Failed to place first buy");
+    let second = second.expect("Failed to place second buy");
+
+    assert_eq!(first.order.status, OrderStatus::Filled);
+    assert_eq!(first.trades.len(), 1);
+    assert_eq!(first.trades[0].seller_address, "seller");
+    assert_eq!(second.order.status, OrderStatus::Pending);
+    assert!(second.trades.is_empty());
+
+    // Held one after the other, never side by side
+    assert!(started.elapsed() >= delay * 2, "{:?}", started.elapsed());
+}
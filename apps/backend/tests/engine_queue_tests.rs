@@ -0,0 +1,73 @@
+use backend::engine::queue::EngineQueue;
+use backend::errors::ExchangeError;
+use backend::models::domain::{EngineRequest, QueueFullPolicy};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+fn snapshot_request() -> EngineRequest {
+    let (response_tx, _) = oneshot::channel();
+    EngineRequest::GetOrderbookSnapshot {
+        market_id: "BTC/USDC".to_string(),
+        group_by: None,
+        response_tx,
+    }
+}
+
+#[tokio::test]
+async fn test_full_queue_rejects_with_engine_busy() {
+    // Nothing drains the receiver, so the queue fills after two requests
+    let (tx, _rx) = mpsc::channel(2);
+    let queue = EngineQueue::new(tx, QueueFullPolicy::Reject);
+
+    queue.send(snapshot_request()).await.unwrap();
+    queue.send(snapshot_request()).await.unwrap();
+
+    let result = queue.send(snapshot_request()).await;
+    assert!(matches!(result, Err(ExchangeError::EngineBusy)));
+
+    let metrics = queue.metrics();
+    assert_eq!(metrics.capacity, 2);
+    assert_eq!(metrics.depth, 2);
+    assert_eq!(metrics.rejected, 1);
+    assert_eq!(metrics.blocked, 0);
+}
+
+#[tokio::test]
+async fn test_full_queue_blocks_until_slot_frees() {
+    let (tx, mut rx) = mpsc::channel(1);
+    let queue = EngineQueue::new(tx, QueueFullPolicy::Block);
+
+    queue.send(snapshot_request()).await.unwrap();
+
+    // Waits while the queue is full
+    let waiting = {
+        let queue = queue.clone();
+        tokio::spawn(async move { queue.send(snapshot_request()).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished());
+    assert_eq!(queue.metrics().blocked, 1);
+
+    // Draining one request lets it through
+    rx.recv().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(1), waiting)
+        .await
+        .expect("blocked send should complete once a slot frees")
+        .unwrap()
+        .unwrap();
+
+    let metrics = queue.metrics();
+    assert_eq!(metrics.depth, 1);
+    assert_eq!(metrics.rejected, 0);
+}
+
+#[tokio::test]
+async fn test_closed_queue_reports_send_failure() {
+    let (tx, rx) = mpsc::channel(1);
+    let queue = EngineQueue::new(tx, QueueFullPolicy::Reject);
+    drop(rx);
+
+    assert!(queue.is_closed());
+    let result = queue.send(snapshot_request()).await;
+    assert!(matches!(result, Err(ExchangeError::EngineSendFailed)));
+}
@@ -585,3 +585,59 @@ async fn test_e2e_validation_errors_via_api() {
     let error: serde_json::Value = response.json().await.expect("Failed to parse error");
     assert!(error["error"].as_str().unwrap().contains("lot size"));
 }
+
+#[tokio::test]
+async fn test_e2e_repeated_client_order_id_places_one_order() {
+    let server = TestServer::start().await.expect("Failed to start server");
+
+    let market = helpers::create_market_with_tokens(&server.test_db, "SOL", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    drip_tokens_via_api(&server.address, "buyer", "USDC", "64000000000000").await;
+
+    let client = reqwest::Client::new();
+    let request = json!({
+        "type": "place_order",
+        "user_address": "buyer",
+        "market_id": market.id,
+        "side": "buy",
+        "order_type": "limit",
+        "price": "7000000",
+        "size": "5000000",
+        "signature": "test_signature",
+        "client_order_id": "retry-1"
+    });
+
+    // The same request sent twice, as a client would after a timeout
+    let mut order_ids = Vec::new();
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/api/trade", server.address))
+            .json(&request)
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(response.status(), 200);
+
+        let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+        order_ids.push(body["order"]["id"].as_str().unwrap().to_string());
+    }
+    assert_eq!(order_ids[0], order_ids[1]);
+
+    let orders = server
+        .db()
+        .get_user_orders("buyer", Some(&market.id), None, 100)
+        .await
+        .expect("Failed to get orders");
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].id.to_string(), order_ids[0]);
+
+    // Only the first placement locked funds
+    let balance = server
+        .db()
+        .get_balance("buyer", "USDC")
+        .await
+        .expect("Failed to get balance");
+    assert_eq!(balance.open_interest, 350_000); // 0.05 SOL at 7 USDC
+}
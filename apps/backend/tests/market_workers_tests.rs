@@ -0,0 +1,169 @@
+//! Per-market engine workers
+//!
+//! Kept in their own test binary: the fault injection switch is process-wide,
+//! so no other test may run alongside it.
+
+use backend::config::EngineConfig;
+use backend::engine::executor::fault_injection;
+use backend::models::domain::{OrderType, Side};
+use exchange_test_utils::{helpers, TestDb, TestEngine};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SETTLE_DELAY: Duration = Duration::from_millis(1500);
+
+#[tokio::test]
+async fn test_market_workers_match_markets_concurrently() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let engine = Arc::new(
+        TestEngine::new_with_config(
+            &test_db,
+            true,
+            EngineConfig {
+                market_workers: true,
+                ..Default::default()
+            },
+        )
+        .await,
+    );
+    let btc = helpers::create_market(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create BTC market");
+    let eth = helpers::create_market(&test_db, "ETH", "USDC")
+        .await
+        .expect("Failed to create ETH market");
+
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &btc.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place BTC ask");
+
+    // Every BTC fill now stalls while it settles
+    fault_injection::set_settle_delay(Some((&btc.id, SETTLE_DELAY)));
+
+    let slow_engine = Arc::clone(&engine);
+    let btc_id = btc.id.clone();
+    let slow_fill = tokio::spawn(async move {
+        let started = Instant::now();
+        let placed = slow_engine
+            .place_order(TestEngine::create_order(
+                "buyer",
+                &btc_id,
+                Side::Buy,
+                OrderType::Limit,
+                50_000_000_000,
+                1_000_000,
+            ))
+            .await;
+        (placed, started.elapsed())
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // ETH doesn't wait for the stalled BTC fill
+    let started = Instant::now();
+    engine
+        .place_order(TestEngine::create_order(
+            "seller",
+            &eth.id,
+            Side::Sell,
+            OrderType::Limit,
+            3_000_000_000,
+            10_000_000,
+        ))
+        .await
+        .expect("Failed to place ETH ask");
+    let eth_elapsed = started.elapsed();
+
+    // A later BTC request still waits for the fill ahead of it
+    let btc_book = engine.get_orderbook_snapshot(&btc.id).await;
+
+    fault_injection::set_settle_delay(None);
+
+    let (placed, btc_elapsed) = slow_fill.await.expect("BTC fill panicked");
+    let placed = placed.expect("Failed to fill BTC bid");
+    assert_eq!(placed.trades.len(), 1);
+    assert!(btc_elapsed >= SETTLE_DELAY, "{:?}", btc_elapsed);
+    assert!(
+        eth_elapsed < Duration::from_millis(500),
+        "ETH order waited {:?} behind the BTC market",
+        eth_elapsed
+    );
+    assert!(btc_book.asks.is_empty() && btc_book.bids.is_empty());
+}
+
+#[tokio::test]
+async fn test_market_workers_with_balance_invariants_settle_concurrent_fills() {
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let engine = Arc::new(
+        TestEngine::new_with_config(
+            &test_db,
+            true,
+            EngineConfig {
+                market_workers: true,
+                check_balance_invariants: true,
+                ..Default::default()
+            },
+        )
+        .await,
+    );
+    let btc = helpers::create_market(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create BTC market");
+    let eth = helpers::create_market(&test_db, "ETH", "USDC")
+        .await
+        .expect("Failed to create ETH market");
+
+    const FILLS: usize = 10;
+    let markets = [(&btc.id, 50_000_000_000), (&eth.id, 3_000_000_000)];
+    for (market_id, price) in markets {
+        for _ in 0..FILLS {
+            engine
+                .place_order(TestEngine::create_order(
+                    "seller",
+                    market_id,
+                    Side::Sell,
+                    OrderType::Limit,
+                    price,
+                    1_000_000,
+                ))
+                .await
+                .expect("Failed to place ask");
+        }
+    }
+
+    // Both markets' fills share the buyer, the seller and the fee recipient, and
+    // settle at the same time on their own workers
+    let mut fills = Vec::new();
+    for _ in 0..FILLS {
+        for (market_id, price) in markets {
+            let engine = Arc::clone(&engine);
+            let market_id = market_id.clone();
+            fills.push(tokio::spawn(async move {
+                engine
+                    .place_order(TestEngine::create_order(
+                        "buyer",
+                        &market_id,
+                        Side::Buy,
+                        OrderType::Limit,
+                        price,
+                        1_000_000,
+                    ))
+                    .await
+            }));
+        }
+    }
+    for fill in fills {
+        let placed = fill
+            .await
+            .expect("Fill panicked")
+            .expect("Concurrent fill failed the invariant check");
+        assert_eq!(placed.trades.len(), 1);
+    }
+}
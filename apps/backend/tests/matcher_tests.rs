@@ -0,0 +1,139 @@
+use backend::engine::matcher::Matcher;
+use backend::engine::orderbook::Orderbook;
+use backend::models::domain::{Order, OrderStatus, OrderType, Side, TimeInForce};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn create_order(user: &str, side: Side, order_type: OrderType, price: u128, size: u128) -> Order {
+    Order {
+        id: Uuid::new_v4(),
+        user_address: user.to_string(),
+        market_id: "BTC/USDC".to_string(),
+        price,
+        size,
+        side,
+        order_type,
+        time_in_force: TimeInForce::Gtc,
+        min_fill: None,
+        status: OrderStatus::Pending,
+        filled_size: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// Three ask levels: 1 @ $50,000, 2 @ $50,100, 3 @ $50,200 (sizes in 0.01 BTC)
+fn create_ask_book() -> Orderbook {
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+    for (i, (price, size)) in [
+        (50_000_000_000u128, 1_000_000u128),
+        (50_100_000_000, 2_000_000),
+        (50_200_000_000, 3_000_000),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        orderbook.add_order(create_order(
+            &format!("seller{}", i),
+            Side::Sell,
+            OrderType::Limit,
+            price,
+            size,
+        ));
+    }
+    orderbook
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[test]
+fn test_estimate_fill_sweeping_market_buy() {
+    let orderbook = create_ask_book();
+    let before = orderbook.snapshot();
+
+    let order = create_order("buyer", Side::Buy, OrderType::Market, 0, 4_000_000);
+    let estimate = Matcher::estimate_fill(&order, &orderbook);
+
+    // 1 @ 50,000 + 2 @ 50,100 + 1 @ 50,200 = 200,400 / 4 = 50,100
+    assert_eq!(estimate.fillable_size, 4_000_000);
+    assert_eq!(estimate.average_price, Some(50_100_000_000));
+    assert_eq!(estimate.levels_consumed, 3);
+    assert!(!estimate.would_rest);
+
+    // Book is untouched
+    let after = orderbook.snapshot();
+    assert_eq!(before.asks.len(), after.asks.len());
+    for (b, a) in before.asks.iter().zip(after.asks.iter()) {
+        assert_eq!((b.price, b.size), (a.price, a.size));
+    }
+}
+
+#[test]
+fn test_estimate_fill_crossing_limit_buy() {
+    let orderbook = create_ask_book();
+
+    // Crosses the first two levels only, remainder rests at $50,100
+    let order = create_order(
+        "buyer",
+        Side::Buy,
+        OrderType::Limit,
+        50_100_000_000,
+        5_000_000,
+    );
+    let estimate = Matcher::estimate_fill(&order, &orderbook);
+
+    // 1 @ 50,000 + 2 @ 50,100 = 150,200 / 3 = 50,066.666666 (rounded down)
+    assert_eq!(estimate.fillable_size, 3_000_000);
+    assert_eq!(estimate.average_price, Some(50_066_666_666));
+    assert_eq!(estimate.levels_consumed, 2);
+    assert!(estimate.would_rest);
+}
+
+#[test]
+fn test_estimate_fill_no_liquidity() {
+    let orderbook = create_ask_book();
+
+    let order = create_order(
+        "buyer",
+        Side::Buy,
+        OrderType::Limit,
+        49_000_000_000,
+        1_000_000,
+    );
+    let estimate = Matcher::estimate_fill(&order, &orderbook);
+
+    assert_eq!(estimate.fillable_size, 0);
+    assert_eq!(estimate.average_price, None);
+    assert_eq!(estimate.levels_consumed, 0);
+    assert!(estimate.would_rest);
+}
+
+#[test]
+fn test_crossing_orders_include_own_orders_up_to_limit() {
+    let mut orderbook = create_ask_book();
+    orderbook.add_order(create_order(
+        "buyer",
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    ));
+
+    // Crosses the first two levels, the buyer's own ask included
+    let order = create_order("buyer", Side::Buy, OrderType::Limit, 50_100_000_000, 1_000);
+    let crossed: Vec<(&str, u128)> = Matcher::crossing_orders(&order, &orderbook)
+        .map(|maker| (maker.user_address.as_str(), maker.price))
+        .collect();
+
+    assert_eq!(
+        crossed,
+        vec![
+            ("seller0", 50_000_000_000),
+            ("buyer", 50_000_000_000),
+            ("seller1", 50_100_000_000),
+        ]
+    );
+    assert_eq!(Matcher::match_order(&order, &orderbook).len(), 1);
+}
@@ -0,0 +1,358 @@
+use backend::engine::orderbook::Orderbook;
+use backend::models::domain::{
+    BookIndicators, LiquidityBucket, Order, OrderStatus, OrderType, OrderbookLevel,
+    OrderbookSnapshot, Side, TimeInForce,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn create_order(user: &str, side: Side, price: u128, size: u128) -> Order {
+    Order {
+        id: Uuid::new_v4(),
+        user_address: user.to_string(),
+        market_id: "BTC/USDC".to_string(),
+        price,
+        size,
+        side,
+        order_type: OrderType::Limit,
+        time_in_force: TimeInForce::Gtc,
+        min_fill: None,
+        status: OrderStatus::Pending,
+        filled_size: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// Two bid levels and two ask levels around $50,000
+fn create_book() -> Orderbook {
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+    orderbook.add_order(create_order("buyer1", Side::Buy, 49_900_000_000, 1_000_000));
+    orderbook.add_order(create_order("buyer2", Side::Buy, 49_800_000_000, 2_000_000));
+    orderbook.add_order(create_order(
+        "seller1",
+        Side::Sell,
+        50_100_000_000,
+        1_000_000,
+    ));
+    orderbook.add_order(create_order(
+        "seller2",
+        Side::Sell,
+        50_200_000_000,
+        3_000_000,
+    ));
+    orderbook
+}
+
+#[test]
+fn test_checksum_stable_for_same_book_state() {
+    let first = create_book().snapshot();
+    let second = create_book().snapshot();
+
+    // Same levels built from different orders give the same checksum
+    assert_eq!(first.checksum, second.checksum);
+    assert_eq!(create_book().snapshot().checksum, first.checksum);
+}
+
+#[test]
+fn test_checksum_changes_when_level_changes() {
+    let mut orderbook = create_book();
+    let before = orderbook.snapshot().checksum;
+
+    // Adding size to an existing level changes the checksum
+    orderbook.add_order(create_order("buyer3", Side::Buy, 49_900_000_000, 1_000_000));
+    let after_size_change = orderbook.snapshot().checksum;
+    assert_ne!(before, after_size_change);
+
+    // So does a new price level
+    orderbook.add_order(create_order(
+        "seller3",
+        Side::Sell,
+        50_300_000_000,
+        1_000_000,
+    ));
+    assert_ne!(after_size_change, orderbook.snapshot().checksum);
+}
+
+#[test]
+fn test_checksum_matches_canonical_serialization() {
+    let snapshot = create_book().snapshot();
+
+    // Clients recompute over "price:size" pairs, best first, bids|asks
+    let canonical =
+        "49900000000:1000000,49800000000:2000000|50100000000:1000000,50200000000:3000000";
+    assert_eq!(snapshot.checksum, crc32fast::hash(canonical.as_bytes()));
+    assert_eq!(
+        OrderbookSnapshot::compute_checksum(&snapshot.bids, &snapshot.asks),
+        snapshot.checksum
+    );
+}
+
+#[test]
+fn test_checksum_only_covers_top_levels() {
+    let bids: Vec<OrderbookLevel> = (0..OrderbookSnapshot::CHECKSUM_DEPTH as u128 + 1)
+        .map(|i| OrderbookLevel {
+            price: 1_000_000 - i,
+            size: 1,
+        })
+        .collect();
+
+    let mut deeper = bids.clone();
+    deeper.last_mut().unwrap().size = 2;
+
+    // Levels past CHECKSUM_DEPTH don't affect the checksum
+    assert_eq!(
+        OrderbookSnapshot::compute_checksum(&bids, &[]),
+        OrderbookSnapshot::compute_checksum(&deeper, &[])
+    );
+}
+
+#[test]
+fn test_deep_book_insertion_keeps_price_ordering() {
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+
+    // 10k orders at scattered prices, bids below $50,000 and asks above
+    let mut seed: u64 = 42;
+    let mut next_offset = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 33) % 2_000) as u128 * 1_000_000
+    };
+    let mid = 50_000_000_000u128;
+    let mut ids = Vec::new();
+    let start = std::time::Instant::now();
+    for i in 0..10_000 {
+        let order = if i % 2 == 0 {
+            create_order(
+                "buyer",
+                Side::Buy,
+                mid - 1_000_000 - next_offset(),
+                1_000_000,
+            )
+        } else {
+            create_order(
+                "seller",
+                Side::Sell,
+                mid + 1_000_000 + next_offset(),
+                1_000_000,
+            )
+        };
+        ids.push(order.id);
+        orderbook.add_order(order);
+    }
+    println!("Inserted 10k orders in {:?}", start.elapsed());
+    assert_eq!(orderbook.order_count(), 10_000);
+
+    let snapshot = orderbook.snapshot();
+    assert!(snapshot.bids.windows(2).all(|w| w[0].price > w[1].price));
+    assert!(snapshot.asks.windows(2).all(|w| w[0].price < w[1].price));
+    assert_eq!(orderbook.best_bid(), Some(snapshot.bids[0].price));
+    assert_eq!(orderbook.best_ask(), Some(snapshot.asks[0].price));
+    assert!(orderbook.best_bid().unwrap() < orderbook.best_ask().unwrap());
+
+    let total_size: u128 = snapshot
+        .bids
+        .iter()
+        .chain(snapshot.asks.iter())
+        .map(|level| level.size)
+        .sum();
+    assert_eq!(total_size, 10_000 * 1_000_000);
+
+    // Cancelling everything empties the book, including price levels
+    for id in ids {
+        assert!(orderbook.remove_order(id).is_some());
+    }
+    assert_eq!(orderbook.order_count(), 0);
+    assert!(orderbook.bids.is_empty());
+    assert!(orderbook.asks.is_empty());
+    assert_eq!(orderbook.best_bid(), None);
+    assert_eq!(orderbook.best_ask(), None);
+}
+
+#[test]
+fn test_emptied_level_no_longer_best_price() {
+    let mut orderbook = create_book();
+    let best = create_order("seller3", Side::Sell, 50_050_000_000, 1_000_000);
+    let best_id = best.id;
+    orderbook.add_order(best);
+    assert_eq!(orderbook.best_ask(), Some(50_050_000_000));
+
+    // Removing the only order at a level removes the level
+    orderbook
+        .remove_order(best_id)
+        .expect("Order should be resting");
+    assert_eq!(orderbook.best_ask(), Some(50_100_000_000));
+    assert!(orderbook.remove_order(best_id).is_none());
+
+    // FIFO within a level is unchanged
+    let first = create_order("buyer3", Side::Buy, 49_900_000_000, 1_000_000);
+    let first_id = first.id;
+    orderbook.add_order(first);
+    let level = &orderbook.bids[&49_900_000_000];
+    assert_eq!(level.len(), 2);
+    assert_eq!(level[0].user_address, "buyer1");
+    assert_eq!(level[1].id, first_id);
+
+    // Removing a user's orders keeps other orders and drops emptied levels
+    let removed = orderbook.remove_all_user_orders("buyer2");
+    assert_eq!(removed.len(), 1);
+    assert!(!orderbook.bids.contains_key(&49_800_000_000));
+    assert_eq!(orderbook.order_count(), 4);
+}
+
+#[test]
+fn test_grouped_snapshot_buckets_levels() {
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+    for (price, size) in [(100, 1), (101, 2), (102, 3)] {
+        orderbook.add_order(create_order("buyer", Side::Buy, price, size));
+        orderbook.add_order(create_order("seller", Side::Sell, price + 10, size));
+    }
+    // Bids 100-102 and asks 110-112
+    let grouped = orderbook.snapshot().grouped(5);
+
+    // Bids round down: all three fall in the 100 bucket
+    assert_eq!(
+        grouped.bids,
+        vec![OrderbookLevel {
+            price: 100,
+            size: 6
+        }]
+    );
+    // Asks round up: 110 stays, 111 and 112 move to 115
+    assert_eq!(
+        grouped.asks,
+        vec![
+            OrderbookLevel {
+                price: 110,
+                size: 1
+            },
+            OrderbookLevel {
+                price: 115,
+                size: 5
+            },
+        ]
+    );
+    assert_eq!(
+        grouped.checksum,
+        OrderbookSnapshot::compute_checksum(&grouped.bids, &grouped.asks)
+    );
+}
+
+#[test]
+fn test_grouped_snapshot_does_not_cross() {
+    // Best bid 101 and best ask 102 share the 100-105 bucket
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+    orderbook.add_order(create_order("buyer", Side::Buy, 101, 1));
+    orderbook.add_order(create_order("seller", Side::Sell, 102, 1));
+
+    let grouped = orderbook.snapshot().grouped(5);
+    assert_eq!(grouped.bids[0].price, 100);
+    assert_eq!(grouped.asks[0].price, 105);
+
+    // Grouping by 1 leaves the book untouched
+    let ungrouped = orderbook.snapshot().grouped(1);
+    assert_eq!(ungrouped.bids[0].price, 101);
+    assert_eq!(ungrouped.asks[0].price, 102);
+}
+
+#[test]
+fn test_liquidity_profile_buckets_resting_size() {
+    let mut orderbook = create_book();
+    // Second order at an existing level, partially filled
+    let mut partial = create_order("buyer3", Side::Buy, 49_800_000_000, 4_000_000);
+    partial.filled_size = 1_000_000;
+    orderbook.add_order(partial);
+
+    // $49,800 - $50,200 in four $100 buckets
+    let profile = orderbook.liquidity_profile(49_800_000_000, 50_200_000_000, 4);
+
+    let bucket = |price_low: u128, bid_size: u128, ask_size: u128| LiquidityBucket {
+        price_low,
+        price_high: price_low + 100_000_000,
+        bid_size,
+        ask_size,
+    };
+    assert_eq!(
+        profile,
+        vec![
+            bucket(49_800_000_000, 5_000_000, 0),
+            bucket(49_900_000_000, 1_000_000, 0),
+            bucket(50_000_000_000, 0, 0),
+            bucket(50_100_000_000, 0, 1_000_000),
+        ]
+    );
+
+    // The ask at $50,200 sits on the exclusive upper bound
+    let wider = orderbook.liquidity_profile(49_800_000_000, 50_200_000_001, 1);
+    assert_eq!(wider.len(), 1);
+    assert_eq!(wider[0].bid_size, 6_000_000);
+    assert_eq!(wider[0].ask_size, 4_000_000);
+}
+
+#[test]
+fn test_liquidity_profile_empty_range_has_no_buckets() {
+    let orderbook = create_book();
+
+    assert!(orderbook
+        .liquidity_profile(50_000_000_000, 50_000_000_000, 10)
+        .is_empty());
+    assert!(orderbook
+        .liquidity_profile(50_200_000_000, 49_800_000_000, 10)
+        .is_empty());
+    assert!(orderbook
+        .liquidity_profile(49_800_000_000, 50_200_000_000, 0)
+        .is_empty());
+
+    // Never more buckets than price atoms in the range
+    let narrow = orderbook.liquidity_profile(49_900_000_000, 49_900_000_003, 10);
+    assert_eq!(narrow.len(), 3);
+    assert_eq!(narrow[0].bid_size, 1_000_000);
+}
+
+#[test]
+fn test_indicators_over_asymmetric_book() {
+    let mut orderbook = create_book();
+    // Stack the best bid so the top of book leans to the bid side
+    orderbook.add_order(create_order("buyer3", Side::Buy, 49_900_000_000, 5_000_000));
+
+    // Top level only: 6 bid vs 1 ask
+    let top = orderbook.indicators(1);
+    assert_eq!(
+        top,
+        BookIndicators {
+            best_bid: Some(49_900_000_000),
+            best_ask: Some(50_100_000_000),
+            bid_volume: 6_000_000,
+            ask_volume: 1_000_000,
+        }
+    );
+    assert_eq!(top.spread(), Some(200_000_000));
+    assert_eq!(top.mid_price(), Some(50_000_000_000));
+    assert_eq!(top.spread_bps(), Some(40.0));
+    assert_eq!(top.imbalance(), Some(6.0 / 7.0));
+
+    // Both levels: 8 bid vs 4 ask, spread is unchanged
+    let deep = orderbook.indicators(2);
+    assert_eq!(deep.bid_volume, 8_000_000);
+    assert_eq!(deep.ask_volume, 4_000_000);
+    assert_eq!(deep.spread(), Some(200_000_000));
+    assert_eq!(deep.imbalance(), Some(8.0 / 12.0));
+}
+
+#[test]
+fn test_indicators_one_sided_book_has_no_spread() {
+    let mut orderbook = Orderbook::new("BTC/USDC".to_string());
+    orderbook.add_order(create_order("buyer1", Side::Buy, 49_900_000_000, 1_000_000));
+
+    let indicators = orderbook.indicators(10);
+    assert_eq!(indicators.best_ask, None);
+    assert_eq!(indicators.spread(), None);
+    assert_eq!(indicators.mid_price(), None);
+    assert_eq!(indicators.spread_bps(), None);
+    assert_eq!(indicators.imbalance(), Some(1.0));
+
+    // Nothing resting at all
+    let empty = Orderbook::new("BTC/USDC".to_string()).indicators(10);
+    assert_eq!(empty.imbalance(), None);
+}
@@ -1,5 +1,8 @@
-use exchange_test_utils::TestServer;
-use serde_json::Value;
+use backend::config::EngineConfig;
+use backend::models::api::EstimateResponse;
+use backend::models::domain::{MarketStatus, OrderType, Side};
+use exchange_test_utils::{helpers, TestEngine, TestServer};
+use serde_json::{json, Value};
 
 #[tokio::test]
 async fn test_health_endpoint_e2e() {
@@ -14,7 +17,10 @@ async fn test_health_endpoint_e2e() {
     assert_eq!(response.status(), 200);
 
     let body: Value = response.json().await.expect("Failed to parse JSON");
-    assert_eq!(body["message"], "Backend is running!");
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["postgres"], "up");
+    assert_eq!(body["clickhouse"], "up");
+    assert_eq!(body["engine"], "up");
     assert!(body["timestamp"].is_number());
 }
 
@@ -40,6 +46,32 @@ async fn test_health_endpoint_content_type() {
     assert!(content_type.contains("application/json"));
 }
 
+#[tokio::test]
+async fn test_metrics_endpoint_reports_engine_queue() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    let response = reqwest::get(&server.url("/api/metrics"))
+        .await
+        .expect("Failed to make request");
+
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    let queue = &body["engine_queue"];
+    assert!(queue["capacity"].as_u64().unwrap() > 0);
+    assert!(queue["depth"].is_number());
+    assert_eq!(queue["policy"], "reject");
+    assert_eq!(queue["rejected"], 0);
+    assert_eq!(queue["blocked"], 0);
+
+    let websocket = &body["websocket"];
+    assert_eq!(websocket["active_connections"], 0);
+    assert_eq!(websocket["pong_timeouts"], 0);
+    assert_eq!(websocket["idle_disconnects"], 0);
+}
+
 #[tokio::test]
 async fn test_openapi_endpoint_e2e() {
     let server = TestServer::start()
@@ -167,3 +199,979 @@ async fn test_database_accessible_from_api() {
     // 2. We can setup test data via direct DB access
     // 3. The server and test share the same database instance
 }
+
+#[tokio::test]
+async fn test_estimate_matches_actual_fill() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    for user in ["maker", "taker"] {
+        server
+            .db()
+            .create_user(user.to_string())
+            .await
+            .expect("Failed to create user");
+    }
+    server
+        .db()
+        .add_balance("maker", "BTC", 100_000_000)
+        .await
+        .expect("Failed to fund maker");
+    server
+        .db()
+        .add_balance("taker", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund taker");
+
+    // Two ask levels: 0.01 BTC @ $50,000 and 0.02 BTC @ $50,100
+    for (price, size) in [
+        (50_000_000_000u128, 1_000_000u128),
+        (50_100_000_000, 2_000_000),
+    ] {
+        server
+            .engine()
+            .place_order(TestEngine::create_order(
+                "maker",
+                "BTC/USDC",
+                Side::Sell,
+                OrderType::Limit,
+                price,
+                size,
+            ))
+            .await
+            .expect("Failed to place ask");
+    }
+
+    // Estimate a buy that sweeps the first level and part of the second
+    let response = reqwest::Client::new()
+        .post(server.url("/api/estimate"))
+        .json(&json!({
+            "user_address": "taker",
+            "market_id": "BTC/USDC",
+            "side": "buy",
+            "order_type": "limit",
+            "price": "50100000000",
+            "size": "2000000",
+        }))
+        .send()
+        .await
+        .expect("Failed to request estimate");
+    assert_eq!(response.status(), 200);
+    let estimate: EstimateResponse = response.json().await.expect("Invalid estimate");
+
+    assert_eq!(estimate.fillable_size, "2000000");
+    assert_eq!(estimate.average_price.as_deref(), Some("50050000000"));
+    assert_eq!(estimate.best_price.as_deref(), Some("50000000000"));
+    assert_eq!(estimate.levels_consumed, 2);
+    assert_eq!(estimate.resting_size, "0");
+    let slippage = estimate.slippage_bps.expect("Slippage should be set");
+    assert!((slippage - 10.0).abs() < 1e-9, "slippage was {}", slippage);
+
+    // The estimate must not have touched the book
+    let snapshot = server.engine().get_orderbook_snapshot("BTC/USDC").await;
+    assert_eq!(snapshot.asks.len(), 2);
+
+    // Place the same order for real and compare
+    let placed = server
+        .engine()
+        .place_order(TestEngine::create_order(
+            "taker",
+            "BTC/USDC",
+            Side::Buy,
+            OrderType::Limit,
+            50_100_000_000,
+            2_000_000,
+        ))
+        .await
+        .expect("Failed to place order");
+
+    assert_eq!(placed.order.filled_size, estimate.fillable_size);
+    assert_eq!(placed.trades.len(), 2);
+    let (notional, size) = placed.trades.iter().fold((0u128, 0u128), |(n, s), t| {
+        let price: u128 = t.price.parse().unwrap();
+        let size: u128 = t.size.parse().unwrap();
+        (n + price * size, s + size)
+    });
+    assert_eq!(
+        (notional / size).to_string(),
+        estimate.average_price.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_estimate_follows_pre_open_and_min_fill() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    for user in ["maker", "taker"] {
+        server
+            .db()
+            .create_user(user.to_string())
+            .await
+            .expect("Failed to create user");
+    }
+    server
+        .db()
+        .add_balance("maker", "BTC", 100_000_000)
+        .await
+        .expect("Failed to fund maker");
+    server
+        .db()
+        .add_balance("taker", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund taker");
+
+    // One ask level: 0.01 BTC @ $50,000
+    server
+        .engine()
+        .place_order(TestEngine::create_order(
+            "maker",
+            "BTC/USDC",
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    let client = reqwest::Client::new();
+    let estimate = |min_fill: Option<&str>| {
+        let mut body = json!({
+            "user_address": "taker",
+            "market_id": "BTC/USDC",
+            "side": "buy",
+            "order_type": "limit",
+            "price": "50000000000",
+            "size": "2000000",
+        });
+        if let Some(min_fill) = min_fill {
+            body["min_fill"] = json!(min_fill);
+        }
+        client.post(server.url("/api/estimate")).json(&body).send()
+    };
+
+    // Pre-open markets only collect orders, a crossing buy would rest in full
+    server
+        .engine()
+        .set_market_status("BTC/USDC", MarketStatus::PreOpen)
+        .await
+        .expect("Failed to move market to pre-open");
+    let response = estimate(None).await.expect("Failed to request estimate");
+    assert_eq!(response.status(), 200);
+    let pre_open: EstimateResponse = response.json().await.expect("Invalid estimate");
+    assert_eq!(pre_open.fillable_size, "0");
+    assert_eq!(pre_open.average_price, None);
+    assert_eq!(pre_open.levels_consumed, 0);
+    assert_eq!(pre_open.resting_size, "2000000");
+
+    server
+        .engine()
+        .set_market_status("BTC/USDC", MarketStatus::Open)
+        .await
+        .expect("Failed to open market");
+
+    // Only 0.01 BTC is available, short of min_fill nothing fills and nothing rests
+    let response = estimate(Some("1500000"))
+        .await
+        .expect("Failed to request estimate");
+    assert_eq!(response.status(), 200);
+    let short: EstimateResponse = response.json().await.expect("Invalid estimate");
+    assert_eq!(short.fillable_size, "0");
+    assert_eq!(short.average_price, None);
+    assert_eq!(short.levels_consumed, 0);
+    assert_eq!(short.resting_size, "0");
+
+    // Placing it for real agrees
+    let mut order = TestEngine::create_order(
+        "taker",
+        "BTC/USDC",
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        2_000_000,
+    );
+    order.min_fill = Some(1_500_000);
+    let placed = server
+        .engine()
+        .place_order(order)
+        .await
+        .expect("Failed to place order");
+    assert_eq!(placed.order.filled_size, short.fillable_size);
+    assert!(placed.trades.is_empty());
+
+    // A min_fill the book covers fills as usual and rests the remainder
+    let response = estimate(Some("1000000"))
+        .await
+        .expect("Failed to request estimate");
+    assert_eq!(response.status(), 200);
+    let met: EstimateResponse = response.json().await.expect("Invalid estimate");
+    assert_eq!(met.fillable_size, "1000000");
+    assert_eq!(met.average_price.as_deref(), Some("50000000000"));
+    assert_eq!(met.resting_size, "1000000");
+}
+
+#[tokio::test]
+async fn test_invalid_order_flag_combinations_rejected() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    server
+        .db()
+        .create_user("trader".to_string())
+        .await
+        .expect("Failed to create user");
+
+    let client = reqwest::Client::new();
+    let order = json!({
+        "user_address": "trader",
+        "market_id": "BTC/USDC",
+        "side": "buy",
+        "order_type": "market",
+        "time_in_force": "gtc",
+        "price": "50000000000",
+        "size": "1000000",
+    });
+
+    // Market orders never rest, so an explicit gtc is rejected on both endpoints
+    let mut trade = order.clone();
+    trade["type"] = json!("place_order");
+    trade["signature"] = json!("sig");
+    for (path, body) in [("/api/trade", trade), ("/api/estimate", order)] {
+        let response = client
+            .post(server.url(path))
+            .json(&body)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), 400, "{}", path);
+        let error: Value = response.json().await.expect("Invalid error body");
+        assert_eq!(error["code"], "INVALID_PARAMETER");
+        assert!(
+            error["error"]
+                .as_str()
+                .unwrap()
+                .contains("market orders cannot rest on the book"),
+            "{}: {}",
+            path,
+            error
+        );
+    }
+
+    // Without a time in force a market order resolves to ioc
+    let response = client
+        .post(server.url("/api/estimate"))
+        .json(&json!({
+            "market_id": "BTC/USDC",
+            "side": "buy",
+            "order_type": "market",
+            "price": "50000000000",
+            "size": "1000000",
+        }))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_liquidity_profile_endpoint() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    server
+        .db()
+        .create_user("maker".to_string())
+        .await
+        .expect("Failed to create user");
+    server
+        .db()
+        .add_balance("maker", "BTC", 100_000_000)
+        .await
+        .expect("Failed to fund maker");
+    server
+        .db()
+        .add_balance("maker", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund maker");
+
+    // Bids at $49,900 and $49,950, asks at $50,050 and $50,150
+    for (side, price, size) in [
+        (Side::Buy, 49_900_000_000u128, 1_000_000u128),
+        (Side::Buy, 49_950_000_000, 2_000_000),
+        (Side::Sell, 50_050_000_000, 3_000_000),
+        (Side::Sell, 50_150_000_000, 4_000_000),
+    ] {
+        server
+            .engine()
+            .place_order(TestEngine::create_order(
+                "maker",
+                "BTC/USDC",
+                side,
+                OrderType::Limit,
+                price,
+                size,
+            ))
+            .await
+            .expect("Failed to place order");
+    }
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(server.url("/api/liquidity"))
+        .json(&json!({
+            "market_id": "BTC/USDC",
+            "from_price": "49800000000",
+            "to_price": "50200000000",
+            "buckets": 2
+        }))
+        .send()
+        .await
+        .expect("Failed to make request");
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    let buckets = body["buckets"].as_array().expect("buckets array");
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0]["price_low"], "49800000000");
+    assert_eq!(buckets[0]["price_high"], "50000000000");
+    assert_eq!(buckets[0]["bid_size"], "3000000");
+    assert_eq!(buckets[0]["ask_size"], "0");
+    assert_eq!(buckets[1]["bid_size"], "0");
+    assert_eq!(buckets[1]["ask_size"], "7000000");
+
+    // Empty range returns no buckets
+    let response = client
+        .post(server.url("/api/liquidity"))
+        .json(&json!({
+            "market_id": "BTC/USDC",
+            "from_price": "50000000000",
+            "to_price": "50000000000",
+            "buckets": 4
+        }))
+        .send()
+        .await
+        .expect("Failed to make request");
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["buckets"], json!([]));
+}
+
+#[tokio::test]
+async fn test_balances_report_available_and_locked() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_token(&server.test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create token");
+    server
+        .db()
+        .create_user("trader".to_string())
+        .await
+        .expect("Failed to create user");
+    server
+        .db()
+        .add_balance("trader", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund trader");
+    server
+        .db()
+        .lock_balance("trader", "USDC", 2_500_000_000)
+        .await
+        .expect("Failed to lock balance");
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(server.url("/api/user"))
+        .json(&json!({"type": "balances", "user_address": "trader"}))
+        .send()
+        .await
+        .expect("Failed to make request");
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    let balances = body["balances"].as_array().expect("balances array");
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0]["token_ticker"], "USDC");
+    assert_eq!(balances[0]["amount"], "10000000000");
+    assert_eq!(balances[0]["available"], "7500000000");
+    assert_eq!(balances[0]["locked"], "2500000000");
+    assert_eq!(balances[0]["open_interest"], balances[0]["locked"]);
+}
+
+#[tokio::test]
+async fn test_microstructure_endpoint() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    server
+        .db()
+        .create_user("maker".to_string())
+        .await
+        .expect("Failed to create user");
+    server
+        .db()
+        .add_balance("maker", "BTC", 100_000_000)
+        .await
+        .expect("Failed to fund maker");
+    server
+        .db()
+        .add_balance("maker", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund maker");
+
+    let client = reqwest::Client::new();
+    let indicators = |depth: Option<u32>| {
+        client
+            .post(server.url("/api/microstructure"))
+            .json(&json!({ "market_id": "BTC/USDC", "depth": depth }))
+            .send()
+    };
+
+    // Only bids: no spread yet, all volume on the bid side
+    server
+        .engine()
+        .place_order(TestEngine::create_order(
+            "maker",
+            "BTC/USDC",
+            Side::Buy,
+            OrderType::Limit,
+            49_950_000_000,
+            2_000_000,
+        ))
+        .await
+        .expect("Failed to place order");
+    let body: Value = indicators(None)
+        .await
+        .expect("Failed to make request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(body["depth"], 10);
+    assert_eq!(body["best_ask"], Value::Null);
+    assert_eq!(body["spread"], Value::Null);
+    assert_eq!(body["mid_price"], Value::Null);
+    assert_eq!(body["imbalance"], 1.0);
+
+    // Bids at $49,900 and $49,950, asks at $50,050 and $50,150
+    for (side, price, size) in [
+        (Side::Buy, 49_900_000_000u128, 1_000_000u128),
+        (Side::Sell, 50_050_000_000, 3_000_000),
+        (Side::Sell, 50_150_000_000, 4_000_000),
+    ] {
+        server
+            .engine()
+            .place_order(TestEngine::create_order(
+                "maker",
+                "BTC/USDC",
+                side,
+                OrderType::Limit,
+                price,
+                size,
+            ))
+            .await
+            .expect("Failed to place order");
+    }
+
+    let response = indicators(Some(1)).await.expect("Failed to make request");
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["best_bid"], "49950000000");
+    assert_eq!(body["best_ask"], "50050000000");
+    assert_eq!(body["spread"], "100000000");
+    assert_eq!(body["mid_price"], "50000000000");
+    assert_eq!(body["spread_bps"], 20.0);
+    assert_eq!(body["bid_volume"], "2000000");
+    assert_eq!(body["ask_volume"], "3000000");
+    assert_eq!(body["imbalance"], 0.4);
+
+    // Both levels: 3 bid vs 7 ask
+    let body: Value = indicators(Some(2))
+        .await
+        .expect("Failed to make request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(body["imbalance"], 0.3);
+
+    let response = indicators(Some(0)).await.expect("Failed to make request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_error_responses_share_one_shape() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    // (path, raw body, expected status, expected code)
+    let cases = [
+        (
+            "/api/trade",
+            json!({
+                "type": "place_order",
+                "user_address": "trader",
+                "market_id": "BTC/USDC",
+                "side": "buy",
+                "order_type": "limit",
+                "price": "cheap",
+                "size": "1000000",
+                "signature": "sig",
+            })
+            .to_string(),
+            400,
+            "INVALID_PRICE",
+        ),
+        (
+            "/api/trade",
+            json!({
+                "type": "cancel_order",
+                "user_address": "trader",
+                "order_id": "not-a-uuid",
+                "signature": "sig",
+            })
+            .to_string(),
+            400,
+            "UUID_PARSE_ERROR",
+        ),
+        (
+            "/api/drip",
+            json!({
+                "type": "faucet",
+                "user_address": "trader",
+                "token_ticker": "USDC",
+                "amount": "lots",
+                "signature": "sig",
+            })
+            .to_string(),
+            400,
+            "INVALID_AMOUNT",
+        ),
+        // Bodies axum itself rejects: malformed JSON, unknown variant, missing fields
+        (
+            "/api/trade",
+            "{not json".to_string(),
+            400,
+            "INVALID_REQUEST_BODY",
+        ),
+        (
+            "/api/user",
+            json!({"type": "everything"}).to_string(),
+            400,
+            "INVALID_REQUEST_BODY",
+        ),
+        (
+            "/api/candles",
+            json!({"market_id": "BTC/USDC"}).to_string(),
+            400,
+            "INVALID_REQUEST_BODY",
+        ),
+    ];
+
+    let client = reqwest::Client::new();
+    for (path, body, status, code) in cases {
+        let response = client
+            .post(server.url(path))
+            .header("content-type", "application/json")
+            .body(body.clone())
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), status, "{} {}", path, body);
+
+        let error: Value = response.json().await.expect("Error body is not JSON");
+        let fields = error.as_object().expect("Error body is not an object");
+        assert_eq!(fields.len(), 2, "{}: {}", path, error);
+        assert_eq!(error["code"], code, "{}: {}", path, error);
+        assert!(
+            !error["error"].as_str().unwrap().is_empty(),
+            "{}: {}",
+            path,
+            error
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_market_is_not_found_everywhere() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    let cases = [
+        (
+            "/api/info",
+            json!({"type": "market_details", "market_id": "FAKE/MARKET"}),
+        ),
+        (
+            "/api/candles",
+            json!({"market_id": "FAKE/MARKET", "interval": "1m", "from": 0, "to": 60}),
+        ),
+        (
+            "/api/trade",
+            json!({
+                "type": "place_order",
+                "user_address": "trader",
+                "market_id": "FAKE/MARKET",
+                "side": "buy",
+                "order_type": "limit",
+                "price": "1000000",
+                "size": "1000000",
+                "signature": "sig",
+            }),
+        ),
+    ];
+
+    let client = reqwest::Client::new();
+    for (path, body) in cases {
+        let response = client
+            .post(server.url(path))
+            .json(&body)
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), 404, "{} {}", path, body);
+
+        let error: Value = response.json().await.expect("Error body is not JSON");
+        assert_eq!(error["code"], "MARKET_NOT_FOUND", "{}: {}", path, error);
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_rate_limit_returns_429() {
+    let server = TestServer::start_with_engine_config(EngineConfig {
+        max_cancels_per_sec: 2,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+
+    let client = reqwest::Client::new();
+    let cancel = || {
+        client
+            .post(server.url("/api/trade"))
+            .json(&json!({
+                "type": "cancel_order",
+                "user_address": "trader",
+                "order_id": uuid::Uuid::new_v4().to_string(),
+                "signature": "sig",
+            }))
+            .send()
+    };
+
+    // The burst allowance is spent on unknown orders, which are not found
+    for _ in 0..2 {
+        let response = cancel().await.expect("Request failed");
+        assert_eq!(response.status(), 404);
+        let error: Value = response.json().await.expect("Invalid error body");
+        assert_eq!(error["code"], "ORDER_NOT_FOUND", "{}", error);
+    }
+
+    let response = cancel().await.expect("Request failed");
+    assert_eq!(response.status(), 429);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "CANCEL_RATE_LIMITED", "{}", error);
+    assert_eq!(error["reason"], "RATE_LIMITED", "{}", error);
+
+    // Cancel-all draws on the same allowance
+    let response = client
+        .post(server.url("/api/trade"))
+        .json(&json!({
+            "type": "cancel_all_orders",
+            "user_address": "trader",
+            "signature": "sig",
+        }))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 429);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "CANCEL_RATE_LIMITED", "{}", error);
+}
+
+#[tokio::test]
+async fn test_cancelling_finished_order_returns_409() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    for (user, token, amount) in [
+        ("seller", "BTC", 100_000_000u128),
+        ("buyer", "USDC", 10_000_000_000),
+    ] {
+        server
+            .db()
+            .create_user(user.to_string())
+            .await
+            .expect("Failed to create user");
+        server
+            .db()
+            .add_balance(user, token, amount)
+            .await
+            .expect("Failed to fund user");
+    }
+
+    let place = |user: &str, side: Side, size: u128| {
+        server.engine().place_order(TestEngine::create_order(
+            user,
+            "BTC/USDC",
+            side,
+            OrderType::Limit,
+            50_000_000_000,
+            size,
+        ))
+    };
+    let filled = place("seller", Side::Sell, 1_000_000)
+        .await
+        .expect("Failed to place ask");
+    place("buyer", Side::Buy, 1_000_000)
+        .await
+        .expect("Failed to place bid");
+    let resting = place("seller", Side::Sell, 1_000_000)
+        .await
+        .expect("Failed to place ask");
+
+    let client = reqwest::Client::new();
+    let cancel = |order_id: String| {
+        client
+            .post(server.url("/api/trade"))
+            .json(&json!({
+                "type": "cancel_order",
+                "user_address": "seller",
+                "order_id": order_id,
+                "signature": "sig",
+            }))
+            .send()
+    };
+
+    let response = cancel(filled.order.id.clone())
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 409);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "ORDER_NOT_CANCELLABLE", "{}", error);
+
+    // Cancelled once, then no longer cancellable
+    let response = cancel(resting.order.id.clone())
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 200);
+    let response = cancel(resting.order.id.clone())
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 409);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "ORDER_NOT_CANCELLABLE", "{}", error);
+
+    let response = cancel(uuid::Uuid::new_v4().to_string())
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 404);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "ORDER_NOT_FOUND", "{}", error);
+}
+
+#[tokio::test]
+async fn test_order_rejections_carry_reason() {
+    let server = TestServer::start_with_engine_config(EngineConfig {
+        max_cancels_per_sec: 1,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+
+    helpers::create_token(&server.test_db, "BTC", 8, "BTC Token")
+        .await
+        .expect("Failed to create BTC");
+    helpers::create_token(&server.test_db, "USDC", 6, "USDC Token")
+        .await
+        .expect("Failed to create USDC");
+    // Lot size below min size so each size check can fail on its own
+    let market = server
+        .db()
+        .create_market(
+            "BTC".to_string(),
+            "USDC".to_string(),
+            1000,
+            1000,
+            1_000_000,
+            10,
+            20,
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create market");
+    server
+        .db()
+        .set_market_min_notional(&market.id, Some(5_000_000))
+        .await
+        .expect("Failed to set min notional");
+    server
+        .db()
+        .create_user("trader".to_string())
+        .await
+        .expect("Failed to create user");
+
+    let client = reqwest::Client::new();
+    let place = |price: &str, size: &str| {
+        client
+            .post(server.url("/api/trade"))
+            .json(&json!({
+                "type": "place_order",
+                "user_address": "trader",
+                "market_id": market.id,
+                "side": "buy",
+                "order_type": "limit",
+                "price": price,
+                "size": size,
+                "signature": "sig",
+            }))
+            .send()
+    };
+
+    // (price, size, expected reason); the trader holds no balance at all
+    let cases = [
+        ("50000000500", "1000000", "TICK_SIZE"),
+        ("50000000000", "1000500", "LOT_SIZE"),
+        ("50000000000", "500000", "MIN_SIZE"),
+        ("1000", "1000000", "MIN_NOTIONAL"),
+        ("50000000000", "1000000", "INSUFFICIENT_BALANCE"),
+    ];
+    for (price, size, reason) in cases {
+        let response = place(price, size).await.expect("Request failed");
+        assert_eq!(response.status(), 400, "{}", reason);
+        let error: Value = response.json().await.expect("Invalid error body");
+        assert_eq!(error["reason"], reason, "{}", error);
+        assert!(!error["error"].as_str().unwrap().is_empty());
+    }
+
+    // A funded maker rests an ask the following orders cross
+    server
+        .db()
+        .create_user("maker".to_string())
+        .await
+        .expect("Failed to create user");
+    for (user, token, amount) in [
+        ("maker", "BTC", 100_000_000u128),
+        ("maker", "USDC", 100_000_000_000),
+        ("trader", "USDC", 100_000_000_000),
+    ] {
+        server
+            .db()
+            .add_balance(user, token, amount)
+            .await
+            .expect("Failed to fund user");
+    }
+    server
+        .engine()
+        .place_order(TestEngine::create_order(
+            "maker",
+            &market.id,
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000_000,
+            1_000_000,
+        ))
+        .await
+        .expect("Failed to place ask");
+
+    // (user, time in force, expected code, expected reason)
+    let crossing_cases = [
+        (
+            "trader",
+            "post_only",
+            "POST_ONLY_WOULD_CROSS",
+            "POST_ONLY_CROSS",
+        ),
+        ("maker", "ioc", "SELF_TRADE_REJECTED", "SELF_TRADE"),
+    ];
+    for (user, time_in_force, code, reason) in crossing_cases {
+        let response = client
+            .post(server.url("/api/trade"))
+            .json(&json!({
+                "type": "place_order",
+                "user_address": user,
+                "market_id": market.id,
+                "side": "buy",
+                "order_type": "limit",
+                "price": "50000000000",
+                "size": "1000000",
+                "time_in_force": time_in_force,
+                "signature": "sig",
+            }))
+            .send()
+            .await
+            .expect("Request failed");
+        assert_eq!(response.status(), 409, "{}", reason);
+        let error: Value = response.json().await.expect("Invalid error body");
+        assert_eq!(error["code"], code, "{}", error);
+        assert_eq!(error["reason"], reason, "{}", error);
+    }
+
+    // Cancels past the limit are throttled, the first one isn't a rejection
+    let cancel = || {
+        client
+            .post(server.url("/api/trade"))
+            .json(&json!({
+                "type": "cancel_order",
+                "user_address": "trader",
+                "order_id": uuid::Uuid::new_v4().to_string(),
+                "signature": "sig",
+            }))
+            .send()
+    };
+    let response = cancel().await.expect("Request failed");
+    assert_eq!(response.status(), 404);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert!(error.get("reason").is_none(), "{}", error);
+    let response = cancel().await.expect("Request failed");
+    assert_eq!(response.status(), 429);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["reason"], "RATE_LIMITED", "{}", error);
+
+    // Halted markets refuse every order
+    let response = client
+        .post(server.url("/api/admin"))
+        .json(&json!({
+            "type": "set_market_status",
+            "market_id": market.id,
+            "status": "halted",
+        }))
+        .send()
+        .await
+        .expect("Failed to halt market");
+    assert!(response.status().is_success());
+    let response = place("50000000000", "1000000")
+        .await
+        .expect("Request failed");
+    assert_eq!(response.status(), 409);
+    let error: Value = response.json().await.expect("Invalid error body");
+    assert_eq!(error["code"], "MARKET_NOT_OPEN");
+    assert_eq!(error["reason"], "MARKET_HALTED");
+}
@@ -0,0 +1,166 @@
+use backend::api::rest::trade::REQUEST_ID_HEADER;
+use backend::config::EngineConfig;
+use backend::models::domain::{OrderType, Side};
+use exchange_test_utils::{helpers, TestDb, TestEngine, TestServer};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Log output of every span and event in this test binary
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Install the capturing subscriber once for every test in this binary
+fn captured_logs() -> CapturedLogs {
+    static LOGS: OnceLock<CapturedLogs> = OnceLock::new();
+    LOGS.get_or_init(|| {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        tracing_subscriber::fmt()
+            .with_env_filter("backend=debug")
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .init();
+        logs
+    })
+    .clone()
+}
+
+#[tokio::test]
+async fn test_trade_request_id_in_header_and_logs() {
+    let logs = captured_logs();
+
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    server
+        .db()
+        .create_user("trader".to_string())
+        .await
+        .expect("Failed to create user");
+    server
+        .db()
+        .add_balance("trader", "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to fund trader");
+
+    let response = reqwest::Client::new()
+        .post(server.url("/api/trade"))
+        .json(&json!({
+            "type": "place_order",
+            "user_address": "trader",
+            "market_id": "BTC/USDC",
+            "side": "buy",
+            "order_type": "limit",
+            "price": "50000000000",
+            "size": "1000000",
+            "signature": "sig",
+        }))
+        .send()
+        .await
+        .expect("Failed to place order");
+    assert_eq!(response.status(), 200);
+
+    let request_id = response
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .expect("Missing request id header")
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(uuid::Uuid::parse_str(&request_id).is_ok());
+
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    let order_id = body["order"]["id"].as_str().unwrap().to_string();
+
+    // The engine's log line for the order carries the REST request id
+    let logs = logs.contents();
+    assert!(
+        logs.lines()
+            .any(|line| line.contains(&request_id) && line.contains(&order_id)),
+        "No log line links request {} to order {}:\n{}",
+        request_id,
+        order_id,
+        logs
+    );
+}
+
+#[tokio::test]
+async fn test_slow_engine_request_is_logged() {
+    let logs = captured_logs();
+
+    let test_db = TestDb::setup().await.expect("Failed to setup test DB");
+    let market = helpers::create_market_with_tokens(&test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let engine = TestEngine::new_with_config(
+        &test_db,
+        true,
+        EngineConfig {
+            slow_request_threshold_ms: 50,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // Stall the database: hold the buyer's balance rows locked for a while
+    let mut tx = test_db
+        .db
+        .postgres
+        .begin()
+        .await
+        .expect("Failed to begin transaction");
+    sqlx::query("SELECT amount FROM balances WHERE user_address = 'buyer' FOR UPDATE")
+        .fetch_all(&mut *tx)
+        .await
+        .expect("Failed to lock balances");
+    let release = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tx.commit().await.expect("Failed to release balances");
+    });
+
+    let order = TestEngine::create_order(
+        "buyer",
+        &market.id,
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    engine
+        .place_order(order)
+        .await
+        .expect("Order should succeed once the lock is released");
+    release.await.unwrap();
+
+    let logs = logs.contents();
+    assert!(
+        logs.lines().any(|line| line.contains("WARN")
+            && line.contains("Slow engine request")
+            && line.contains("place_order")),
+        "No slow request warning:\n{}",
+        logs
+    );
+}
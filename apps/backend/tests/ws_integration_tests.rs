@@ -1,9 +1,19 @@
-use backend::models::api::{ClientMessage, ServerMessage, SubscriptionChannel};
-use backend::models::domain::{OrderType, Side};
+use backend::api::ws::COMPRESSION_PROTOCOL;
+use backend::auth::Wallet;
+use backend::config::WebSocketConfig;
+use backend::models::api::{ActiveSubscription, ClientMessage, ServerMessage, SubscriptionChannel};
+use backend::models::domain::{MarketStatus, OrderType, Side};
 use exchange_test_utils::{helpers, TestEngine, TestServer};
+use flate2::read::DeflateDecoder;
 use futures::{SinkExt, StreamExt};
 use serde_json::json;
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio::time::{timeout, Duration};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::{Error as WsError, ProtocolError, SubProtocolError};
+use tokio_tungstenite::tungstenite::http::{header::SEC_WEBSOCKET_PROTOCOL, HeaderValue};
 use tokio_tungstenite::tungstenite::Message;
 
 // Type alias for WebSocket stream to reduce verbosity
@@ -17,6 +27,33 @@ async fn send_json<T: serde::Serialize>(ws: &mut WsStream, msg: &T) -> anyhow::R
     Ok(())
 }
 
+/// Helper to fetch a challenge and sign it with the wallet
+async fn signed_auth(ws: &mut WsStream, wallet: &Wallet) -> ClientMessage {
+    send_json(ws, &ClientMessage::AuthChallenge)
+        .await
+        .expect("Failed to request challenge");
+    let challenge =
+        receive_message_of_type(ws, |m| matches!(m, ServerMessage::AuthChallenge { .. }), 5)
+            .await
+            .expect("Should receive a challenge");
+    let ServerMessage::AuthChallenge { nonce, .. } = challenge else {
+        unreachable!()
+    };
+    ClientMessage::Auth {
+        user_address: wallet.address(),
+        signature: wallet.sign_challenge(&nonce),
+    }
+}
+
+/// Helper to authenticate the connection, private channels require it
+async fn authenticate(ws: &mut WsStream, wallet: &Wallet) {
+    let auth = signed_auth(ws, wallet).await;
+    send_json(ws, &auth).await.expect("Failed to send auth");
+    receive_message_of_type(ws, |m| matches!(m, ServerMessage::Authenticated { .. }), 5)
+        .await
+        .expect("Should be authenticated");
+}
+
 // ============================================================================
 // Connection Tests
 // ============================================================================
@@ -90,6 +127,52 @@ async fn test_ws_multiple_concurrent_connections() {
     }
 }
 
+/// Poll /api/metrics until the active WebSocket count matches
+async fn wait_for_active_connections(server: &TestServer, expected: u64) -> serde_json::Value {
+    timeout(Duration::from_secs(5), async {
+        loop {
+            let body: serde_json::Value = reqwest::get(&server.url("/api/metrics"))
+                .await
+                .expect("Failed to fetch metrics")
+                .json()
+                .await
+                .expect("Failed to parse metrics");
+            if body["websocket"]["active_connections"] == expected {
+                return body;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("active_connections never reached {}", expected))
+}
+
+#[tokio::test]
+async fn test_ws_metrics_track_active_connections() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    let mut connections = Vec::new();
+    for _ in 0..3 {
+        let (ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+            .await
+            .expect("Failed to connect to WebSocket");
+        connections.push(ws);
+    }
+
+    let body = wait_for_active_connections(&server, 3).await;
+    assert_eq!(body["websocket"]["pong_timeouts"], 0);
+    assert_eq!(body["websocket"]["idle_disconnects"], 0);
+
+    for ws in connections.iter_mut() {
+        ws.close(None).await.expect("Failed to close connection");
+    }
+
+    // Every guard is released once the sockets are torn down
+    wait_for_active_connections(&server, 0).await;
+}
+
 // ============================================================================
 // Subscription Tests
 // ============================================================================
@@ -162,22 +245,63 @@ async fn test_ws_subscribe_to_user_updates() {
     let server = TestServer::start()
         .await
         .expect("Failed to start test server");
+    let wallet = Wallet::from_seed("user_updates");
+
+    server
+        .test_db
+        .db
+        .create_user(wallet.address())
+        .await
+        .expect("Failed to create user");
 
     let ws_url = server.ws_url.clone();
     let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
         .await
         .expect("Failed to connect to WebSocket");
 
-    // Subscribe to user balances
+    // No auth timeout is configured, private channels are still refused without auth
     let subscribe_msg = ClientMessage::Subscribe {
         channel: SubscriptionChannel::UserBalances,
         market_id: None,
-        user_address: Some("0x1234567890abcdef".to_string()),
+        user_address: Some(wallet.address()),
     };
+    send_json(&mut ws, &subscribe_msg)
+        .await
+        .expect("Failed to send subscribe message");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| {
+            matches!(
+                m,
+                ServerMessage::Subscribed { .. } | ServerMessage::Error { .. }
+            )
+        },
+        5,
+    )
+    .await
+    .expect("Should receive a reply");
+    assert!(
+        matches!(msg, ServerMessage::Error { ref message } if message.contains("Authentication required")),
+        "{:?}",
+        msg
+    );
 
+    // Without a deadline the connection stays open and can authenticate
+    authenticate(&mut ws, &wallet).await;
     send_json(&mut ws, &subscribe_msg)
         .await
         .expect("Failed to send subscribe message");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+    assert!(matches!(
+        msg,
+        ServerMessage::Subscribed { user_address: Some(ref user), .. } if *user == wallet.address()
+    ));
 
     ws.close(None).await.expect("Failed to close connection");
 }
@@ -226,6 +350,7 @@ async fn test_ws_multiple_subscriptions_same_connection() {
     let server = TestServer::start()
         .await
         .expect("Failed to start test server");
+    let wallet = Wallet::from_seed("user123");
 
     // Setup: Create test markets
     helpers::create_market_with_tokens(&server.test_db, "BTC", "USD")
@@ -234,11 +359,18 @@ async fn test_ws_multiple_subscriptions_same_connection() {
     helpers::create_market_with_tokens(&server.test_db, "ETH", "USD")
         .await
         .expect("Failed to create ETH/USD market");
+    server
+        .test_db
+        .db
+        .create_user(wallet.address())
+        .await
+        .expect("Failed to create user");
 
     let ws_url = server.ws_url.clone();
     let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
         .await
         .expect("Failed to connect to WebSocket");
+    authenticate(&mut ws, &wallet).await;
 
     // Subscribe to multiple channels
     let subscriptions = vec![
@@ -255,32 +387,1225 @@ async fn test_ws_multiple_subscriptions_same_connection() {
         ClientMessage::Subscribe {
             channel: SubscriptionChannel::UserBalances,
             market_id: None,
-            user_address: Some("0xuser123".to_string()),
+            user_address: Some(wallet.address()),
         },
     ];
 
-    for sub in subscriptions {
-        send_json(&mut ws, &sub)
+    for sub in subscriptions {
+        send_json(&mut ws, &sub)
+            .await
+            .expect("Failed to send subscribe message");
+    }
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_list_subscriptions() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let wallet = Wallet::from_seed("user123");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USD")
+        .await
+        .expect("Failed to create BTC/USD market");
+    server
+        .test_db
+        .db
+        .create_user(wallet.address())
+        .await
+        .expect("Failed to create user");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+    authenticate(&mut ws, &wallet).await;
+
+    // Nothing subscribed yet
+    send_json(&mut ws, &ClientMessage::ListSubscriptions)
+        .await
+        .expect("Failed to request subscriptions");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::Subscriptions { .. }),
+        2,
+    )
+    .await
+    .expect("Should receive subscription list");
+    assert!(
+        matches!(msg, ServerMessage::Subscriptions { subscriptions } if subscriptions.is_empty())
+    );
+
+    for sub in [
+        ClientMessage::Subscribe {
+            channel: SubscriptionChannel::Trades,
+            market_id: Some("BTC/USD".to_string()),
+            user_address: None,
+        },
+        ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserOrders,
+            market_id: Some("BTC/USD".to_string()),
+            user_address: Some(wallet.address()),
+        },
+    ] {
+        send_json(&mut ws, &sub)
+            .await
+            .expect("Failed to send subscribe message");
+    }
+
+    send_json(&mut ws, &ClientMessage::ListSubscriptions)
+        .await
+        .expect("Failed to request subscriptions");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::Subscriptions { .. }),
+        2,
+    )
+    .await
+    .expect("Should receive subscription list");
+    let ServerMessage::Subscriptions { subscriptions } = msg else {
+        unreachable!()
+    };
+    assert_eq!(
+        subscriptions,
+        vec![
+            ActiveSubscription {
+                channel: SubscriptionChannel::Trades,
+                market_id: Some("BTC/USD".to_string()),
+                user_address: None,
+            },
+            ActiveSubscription {
+                channel: SubscriptionChannel::UserOrders,
+                market_id: Some("BTC/USD".to_string()),
+                user_address: Some(wallet.address()),
+            },
+        ]
+    );
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_subscription_limit_per_connection() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        max_subscriptions_per_connection: 2,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+    let alice = Wallet::from_seed("alice");
+
+    server
+        .test_db
+        .db
+        .create_user(alice.address())
+        .await
+        .expect("Failed to create user");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+    authenticate(&mut ws, &alice).await;
+
+    let trades = ClientMessage::Subscribe {
+        channel: SubscriptionChannel::Trades,
+        market_id: Some("BTC/USDC".to_string()),
+        user_address: None,
+    };
+    let fills = ClientMessage::Subscribe {
+        channel: SubscriptionChannel::UserFills,
+        market_id: None,
+        user_address: Some(alice.address()),
+    };
+    for sub in [&trades, &fills] {
+        send_json(&mut ws, sub).await.expect("Failed to subscribe");
+        receive_message_of_type(
+            &mut ws,
+            |m| matches!(m, ServerMessage::Subscribed { .. }),
+            5,
+        )
+        .await
+        .expect("Should be subscribed under the limit");
+    }
+
+    // A third channel is refused
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserOrders,
+            market_id: None,
+            user_address: Some(alice.address()),
+        },
+    )
+    .await
+    .expect("Failed to send subscribe");
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    assert!(
+        matches!(msg, ServerMessage::Error { ref message } if message.contains("limit of 2")),
+        "{:?}",
+        msg
+    );
+
+    // Re-subscribing to a held channel is not a new subscription
+    send_json(&mut ws, &trades)
+        .await
+        .expect("Failed to subscribe");
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Existing subscription should be acknowledged");
+
+    // Existing subscriptions keep receiving events
+    let event = TestEngine::create_trade_event("BTC/USDC", Side::Buy, 50_000_000_000, 1_000_000);
+    server.inject_event(event);
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Trade { .. }), 5)
+        .await
+        .expect("Should still receive trades");
+    assert!(matches!(msg, ServerMessage::Trade { trade } if trade.market_id == "BTC/USDC"));
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+// ============================================================================
+// Event Receiving Tests
+// ============================================================================
+// Note: Testing event delivery requires triggering events through the matching engine
+// which would require complex order setup. For now, we test the subscription infrastructure.
+// Event delivery is indirectly tested through the subscription mechanism.
+
+// ============================================================================
+// Ping/Pong Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_ws_server_sends_pings() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    let ws_url = server.ws_url.clone();
+    let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    // Wait for server ping (happens every 30 seconds, but we'll wait up to 35 seconds)
+    let result = timeout(Duration::from_secs(35), async {
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Ping(_))) => {
+                    // Received ping! Automatically respond with pong
+                    return Ok(());
+                }
+                Some(Ok(Message::Text(text))) => {
+                    // Ignore text messages
+                    println!("Received text message: {}", text);
+                }
+                Some(Ok(msg)) => {
+                    println!("Received other message: {:?}", msg);
+                }
+                Some(Err(e)) => {
+                    return Err(anyhow::anyhow!("WebSocket error: {}", e));
+                }
+                None => {
+                    return Err(anyhow::anyhow!("Connection closed before ping"));
+                }
+            }
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {
+            println!("Successfully received ping from server");
+        }
+        Ok(Err(e)) => {
+            panic!("Error while waiting for ping: {}", e);
+        }
+        Err(_) => {
+            println!("No ping received within 35 seconds (server sends every 30s)");
+            // This is not necessarily a failure - the test might complete before ping interval
+        }
+    }
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_client_can_send_application_ping() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    let ws_url = server.ws_url.clone();
+    let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    // Send application-level ping
+    send_json(&mut ws, &ClientMessage::Ping)
+        .await
+        .expect("Failed to send ping");
+
+    // The server logs the ping but doesn't respond with Pong in current implementation
+    // This test just verifies the message is accepted
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_orderbook_snapshot_on_subscribe() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    // Populate the book with a single ask
+    let maker = "maker_snapshot".to_string();
+    server
+        .test_db
+        .db
+        .create_user(maker.clone())
+        .await
+        .expect("Failed to create maker");
+    server
+        .test_db
+        .db
+        .add_balance(&maker, "BTC", 10_000_000)
+        .await
+        .expect("Failed to add BTC");
+    let order = TestEngine::create_order(
+        &maker,
+        "BTC/USDC",
+        Side::Sell,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    server
+        .test_engine
+        .place_order(order)
+        .await
+        .expect("Failed to place order");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::Orderbook,
+            market_id: Some("BTC/USDC".to_string()),
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+
+    // Snapshot arrives right away rather than with the next 1s broadcast
+    let orderbook = timeout(Duration::from_millis(200), async {
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Text(text))) => {
+                    let msg: ServerMessage =
+                        serde_json::from_str(&text).expect("Invalid server message");
+                    if let ServerMessage::Orderbook { orderbook } = msg {
+                        return orderbook;
+                    }
+                }
+                Some(Ok(_)) => continue,
+                other => panic!("Connection ended unexpectedly: {:?}", other),
+            }
+        }
+    })
+    .await
+    .expect("Orderbook snapshot should arrive within 200ms of subscribing");
+
+    assert_eq!(orderbook.market_id, "BTC/USDC");
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.asks.len(), 1);
+    assert_eq!(orderbook.asks[0].price, "50000000000");
+    assert_eq!(orderbook.asks[0].size, "1000000");
+    assert!(orderbook.is_snapshot);
+
+    // Updates driven by later book changes are not flagged as snapshots
+    let order = TestEngine::create_order(
+        &maker,
+        "BTC/USDC",
+        Side::Sell,
+        OrderType::Limit,
+        51_000_000_000,
+        1_000_000,
+    );
+    server
+        .test_engine
+        .place_order(order)
+        .await
+        .expect("Failed to place order");
+    let update = receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::Orderbook { orderbook } if orderbook.asks.len() == 2),
+        5,
+    )
+    .await
+    .expect("Should receive orderbook update");
+    if let ServerMessage::Orderbook { orderbook } = update {
+        assert!(!orderbook.is_snapshot);
+    }
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+// ============================================================================
+// Authentication Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_ws_auth_scopes_user_channels() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let bob = Wallet::from_seed("bob");
+    let alice = Wallet::from_seed("alice");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    for user in [alice.address(), bob.address()] {
+        server
+            .test_db
+            .db
+            .create_user(user)
+            .await
+            .expect("Failed to create user");
+    }
+    server
+        .test_db
+        .db
+        .add_balance(&alice.address(), "USDC", 1_000_000_000)
+        .await
+        .expect("Failed to add USDC");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    let auth = signed_auth(&mut ws, &alice).await;
+    send_json(&mut ws, &auth)
+        .await
+        .expect("Failed to authenticate");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Authenticated { .. }),
+        5,
+    )
+    .await
+    .expect("Should be authenticated");
+    assert!(
+        matches!(msg, ServerMessage::Authenticated { user_address } if *user_address == alice.address())
+    );
+
+    // No explicit address: scoped to the authenticated user
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserBalances,
+            market_id: None,
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+    assert!(matches!(
+        msg,
+        ServerMessage::Subscribed { user_address: Some(ref user), .. } if *user == alice.address()
+    ));
+
+    // Consume the balance snapshot sent on subscribe
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::UserBalance { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive balance snapshot");
+
+    // Placing an order locks USDC, alice gets her own update
+    let order = TestEngine::create_order(
+        &alice.address(),
+        "BTC/USDC",
+        Side::Buy,
+        OrderType::Limit,
+        50_000_000_000,
+        1_000_000,
+    );
+    server
+        .test_engine
+        .place_order(order)
+        .await
+        .expect("Failed to place order");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::UserBalance { locked, .. } if locked != "0"),
+        5,
+    )
+    .await
+    .expect("Should receive own balance update");
+    assert!(matches!(
+        msg,
+        ServerMessage::UserBalance { ref user_address, ref token_ticker, .. }
+            if *user_address == alice.address() && token_ticker == "USDC"
+    ));
+
+    // Another user's private channel is rejected
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserOrders,
+            market_id: None,
+            user_address: Some(bob.address()),
+        },
+    )
+    .await
+    .expect("Failed to send subscribe");
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    assert!(matches!(msg, ServerMessage::Error { message } if message.contains(&bob.address())));
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_auth_requires_signed_challenge() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let alice = Wallet::from_seed("alice");
+    let mallory = Wallet::from_seed("mallory");
+    server
+        .test_db
+        .db
+        .create_user(alice.address())
+        .await
+        .expect("Failed to create user");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    let expect_auth_error = |expected: &'static str| {
+        move |msg: ServerMessage| {
+            assert!(
+                matches!(msg, ServerMessage::Error { ref message } if message.contains(expected)),
+                "{:?}",
+                msg
+            )
+        }
+    };
+    let auth_reply = |m: &ServerMessage| {
+        matches!(
+            m,
+            ServerMessage::Authenticated { .. } | ServerMessage::Error { .. }
+        )
+    };
+
+    // Knowing the address is not enough without a challenge
+    send_json(
+        &mut ws,
+        &ClientMessage::Auth {
+            user_address: alice.address(),
+            signature: "0x".to_string(),
+        },
+    )
+    .await
+    .expect("Failed to send auth");
+    expect_auth_error("request an auth challenge")(
+        receive_message_of_type(&mut ws, auth_reply, 5)
+            .await
+            .expect("Should receive a reply"),
+    );
+
+    // Another key's signature over the challenge doesn't pass for alice
+    let ClientMessage::Auth { signature, .. } = signed_auth(&mut ws, &mallory).await else {
+        unreachable!()
+    };
+    send_json(
+        &mut ws,
+        &ClientMessage::Auth {
+            user_address: alice.address(),
+            signature: signature.clone(),
+        },
+    )
+    .await
+    .expect("Failed to send auth");
+    expect_auth_error("signature is not from")(
+        receive_message_of_type(&mut ws, auth_reply, 5)
+            .await
+            .expect("Should receive a reply"),
+    );
+
+    // A signed challenge is good once
+    let auth = signed_auth(&mut ws, &alice).await;
+    send_json(&mut ws, &auth)
+        .await
+        .expect("Failed to send auth");
+    let msg = receive_message_of_type(&mut ws, auth_reply, 5)
+        .await
+        .expect("Should receive a reply");
+    assert!(
+        matches!(msg, ServerMessage::Authenticated { ref user_address } if *user_address == alice.address()),
+        "{:?}",
+        msg
+    );
+    send_json(&mut ws, &auth)
+        .await
+        .expect("Failed to send auth");
+    expect_auth_error("request an auth challenge")(
+        receive_message_of_type(&mut ws, auth_reply, 5)
+            .await
+            .expect("Should receive a reply"),
+    );
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_private_channels_require_auth_within_window() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        auth_timeout_secs: 30,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+    let alice = Wallet::from_seed("alice");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    server
+        .test_db
+        .db
+        .create_user(alice.address())
+        .await
+        .expect("Failed to create user");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    // Public channels stay open without auth
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::Trades,
+            market_id: Some("BTC/USDC".to_string()),
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+
+    // Private channels are refused until the connection authenticates
+    let user_balances = ClientMessage::Subscribe {
+        channel: SubscriptionChannel::UserBalances,
+        market_id: None,
+        user_address: Some(alice.address()),
+    };
+    send_json(&mut ws, &user_balances)
+        .await
+        .expect("Failed to subscribe");
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    assert!(
+        matches!(msg, ServerMessage::Error { ref message } if message.contains("Authentication required")),
+        "{:?}",
+        msg
+    );
+
+    // Authenticating within the window unlocks them
+    let auth = signed_auth(&mut ws, &alice).await;
+    send_json(&mut ws, &auth)
+        .await
+        .expect("Failed to authenticate");
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Authenticated { .. }),
+        5,
+    )
+    .await
+    .expect("Should be authenticated");
+
+    send_json(&mut ws, &user_balances)
+        .await
+        .expect("Failed to subscribe");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| {
+            matches!(
+                m,
+                ServerMessage::Subscribed { .. } | ServerMessage::Error { .. }
+            )
+        },
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+    assert!(
+        matches!(
+            msg,
+            ServerMessage::Subscribed {
+                channel: SubscriptionChannel::UserBalances,
+                ..
+            }
+        ),
+        "{:?}",
+        msg
+    );
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_private_subscription_after_auth_window_disconnects() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        auth_timeout_secs: 1,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+    let alice = Wallet::from_seed("alice");
+
+    server
+        .test_db
+        .db
+        .create_user(alice.address())
+        .await
+        .expect("Failed to create user");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+    tokio::time::sleep(Duration::from_millis(1_100)).await;
+
+    // Too late to authenticate, even with a valid signature
+    let auth = signed_auth(&mut ws, &alice).await;
+    send_json(&mut ws, &auth)
+        .await
+        .expect("Failed to send auth");
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    assert!(
+        matches!(msg, ServerMessage::Error { ref message } if message.contains("expired")),
+        "{:?}",
+        msg
+    );
+
+    // A private subscription now gets an error and the connection is closed
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserBalances,
+            market_id: None,
+            user_address: Some(alice.address()),
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    let closed = receive_message_of_type(&mut ws, |_| true, 5).await;
+    assert!(
+        matches!(closed, Err(ref e) if e.to_string().contains("Connection closed")),
+        "{:?}",
+        closed
+    );
+}
+
+#[tokio::test]
+async fn test_ws_user_orders_filtered_by_market() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let alice = Wallet::from_seed("alice");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create BTC market");
+    helpers::create_token(&server.test_db, "ETH", 8, "ETH Token")
+        .await
+        .expect("Failed to create ETH");
+    helpers::create_market(&server.test_db, "ETH", "USDC")
+        .await
+        .expect("Failed to create ETH market");
+    server
+        .test_db
+        .db
+        .create_user(alice.address())
+        .await
+        .expect("Failed to create user");
+    server
+        .test_db
+        .db
+        .add_balance(&alice.address(), "USDC", 10_000_000_000)
+        .await
+        .expect("Failed to add USDC");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    authenticate(&mut ws, &alice).await;
+
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::UserOrders,
+            market_id: Some("BTC/USDC".to_string()),
+            user_address: Some(alice.address()),
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+    assert!(matches!(
+        msg,
+        ServerMessage::Subscribed { market_id: Some(ref market), .. } if market == "BTC/USDC"
+    ));
+
+    // Order in the other market first, then one in the subscribed market
+    let mut order_ids = Vec::new();
+    for (market_id, price) in [
+        ("ETH/USDC", 3_000_000_000u128),
+        ("BTC/USDC", 50_000_000_000),
+    ] {
+        let order = TestEngine::create_order(
+            &alice.address(),
+            market_id,
+            Side::Buy,
+            OrderType::Limit,
+            price,
+            1_000_000,
+        );
+        order_ids.push(order.id.to_string());
+        server
+            .test_engine
+            .place_order(order)
+            .await
+            .expect("Failed to place order");
+    }
+
+    // Events arrive in order, so the first update must already be the BTC order
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::UserOrder { .. }), 5)
+        .await
+        .expect("Should receive order update");
+    assert!(
+        matches!(msg, ServerMessage::UserOrder { ref order_id, .. } if *order_id == order_ids[1]),
+        "Unexpected update: {:?}",
+        msg
+    );
+
+    // Cancelling the ETH order is filtered out too
+    server
+        .test_engine
+        .cancel_order(order_ids[0].parse().unwrap(), alice.address())
+        .await
+        .expect("Failed to cancel order");
+    assert!(
+        receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::UserOrder { .. }), 1,)
+            .await
+            .is_err()
+    );
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_all_trades_covers_every_market() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create BTC market");
+    helpers::create_token(&server.test_db, "ETH", 8, "ETH Token")
+        .await
+        .expect("Failed to create ETH");
+    helpers::create_market(&server.test_db, "ETH", "USDC")
+        .await
+        .expect("Failed to create ETH market");
+
+    let db = &server.test_db.db;
+    for user in ["alice", "bob"] {
+        db.create_user(user.to_string())
+            .await
+            .expect("Failed to create user");
+    }
+    db.add_balance("alice", "USDC", 100_000_000_000)
+        .await
+        .expect("Failed to add USDC");
+    db.add_balance("bob", "BTC", 100_000_000)
+        .await
+        .expect("Failed to add BTC");
+    db.add_balance("bob", "ETH", 100_000_000)
+        .await
+        .expect("Failed to add ETH");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::AllTrades,
+            market_id: None,
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+
+    // One trade in each market
+    for (market_id, price) in [
+        ("BTC/USDC", 50_000_000_000u128),
+        ("ETH/USDC", 3_000_000_000),
+    ] {
+        for (user, side) in [("bob", Side::Sell), ("alice", Side::Buy)] {
+            let order =
+                TestEngine::create_order(user, market_id, side, OrderType::Limit, price, 1_000_000);
+            server
+                .test_engine
+                .place_order(order)
+                .await
+                .expect("Failed to place order");
+        }
+    }
+
+    let mut markets = Vec::new();
+    for _ in 0..2 {
+        let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Trade { .. }), 5)
+            .await
+            .expect("Should receive trade");
+        if let ServerMessage::Trade { trade } = msg {
+            markets.push(trade.market_id);
+        }
+    }
+    assert_eq!(markets, vec!["BTC/USDC", "ETH/USDC"]);
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_market_status_change_is_pushed() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let market = helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::MarketStatus,
+            market_id: Some(market.id.clone()),
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+
+    // The current status arrives straight away
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::MarketStatus { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive current market status");
+    assert!(matches!(
+        msg,
+        ServerMessage::MarketStatus {
+            status: MarketStatus::Open,
+            ..
+        }
+    ));
+
+    // Halt the market through the admin endpoint
+    let response = reqwest::Client::new()
+        .post(server.url("/api/admin"))
+        .json(&json!({
+            "type": "set_market_status",
+            "market_id": market.id,
+            "status": "halted",
+        }))
+        .send()
+        .await
+        .expect("Failed to send admin request");
+    assert!(response.status().is_success());
+
+    let msg = receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::MarketStatus { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive the status change");
+    match msg {
+        ServerMessage::MarketStatus { market_id, status } => {
+            assert_eq!(market_id, market.id);
+            assert_eq!(status, MarketStatus::Halted);
+        }
+        other => panic!("Unexpected message: {:?}", other),
+    }
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+#[tokio::test]
+async fn test_ws_all_trades_can_be_disabled() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        all_trades_enabled: false,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::AllTrades,
+            market_id: None,
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 5)
+        .await
+        .expect("Should receive an error");
+    assert!(
+        matches!(msg, ServerMessage::Error { ref message } if message.contains("disabled")),
+        "{:?}",
+        msg
+    );
+
+    ws.close(None).await.expect("Failed to close connection");
+}
+
+// ============================================================================
+// Error Handling Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_ws_resume_replays_missed_trades() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+    let db = &server.test_db.db;
+    for user in ["alice", "bob"] {
+        db.create_user(user.to_string())
+            .await
+            .expect("Failed to create user");
+    }
+    db.add_balance("alice", "USDC", 1_000_000_000_000)
+        .await
+        .expect("Failed to add USDC");
+    db.add_balance("bob", "BTC", 100_000_000)
+        .await
+        .expect("Failed to add BTC");
+
+    let server = &server;
+    let trade_at = |price: u128| async move {
+        for (user, side) in [("bob", Side::Sell), ("alice", Side::Buy)] {
+            let order = TestEngine::create_order(
+                user,
+                "BTC/USDC",
+                side,
+                OrderType::Limit,
+                price,
+                1_000_000,
+            );
+            server
+                .test_engine
+                .place_order(order)
+                .await
+                .expect("Failed to place order");
+        }
+    };
+    let trades_sub = ClientMessage::Subscribe {
+        channel: SubscriptionChannel::Trades,
+        market_id: Some("BTC/USDC".to_string()),
+        user_address: None,
+    };
+
+    // Watch one trade, remembering its sequence number
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect to WebSocket");
+    send_json(&mut ws, &trades_sub)
+        .await
+        .expect("Failed to subscribe");
+    receive_message_of_type(
+        &mut ws,
+        |m| matches!(m, ServerMessage::Subscribed { .. }),
+        5,
+    )
+    .await
+    .expect("Should receive subscription ack");
+
+    trade_at(50_000_000_000).await;
+    let last_seq =
+        match receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Trade { .. }), 5)
+            .await
+            .expect("Should receive trade")
+        {
+            ServerMessage::Trade { trade } => trade.seq,
+            other => panic!("Expected trade, got {:?}", other),
+        };
+    assert!(last_seq > 0, "Trades should carry a market sequence number");
+    ws.close(None).await.expect("Failed to close connection");
+
+    // Trades while disconnected
+    let missed_prices = [51_000_000_000u128, 52_000_000_000, 53_000_000_000];
+    for price in missed_prices {
+        trade_at(price).await;
+    }
+
+    // Reconnect and resume from the last trade seen
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to reconnect to WebSocket");
+    send_json(
+        &mut ws,
+        &ClientMessage::Resume {
+            channel: SubscriptionChannel::Trades,
+            market_id: "BTC/USDC".to_string(),
+            after_seq: last_seq,
+        },
+    )
+    .await
+    .expect("Failed to resume");
+
+    match receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Resumed { .. }), 5)
+        .await
+        .expect("Should receive resume ack")
+    {
+        ServerMessage::Resumed {
+            replayed, snapshot, ..
+        } => {
+            assert_eq!(replayed, missed_prices.len());
+            assert!(!snapshot, "Buffered sequence should replay, not snapshot");
+        }
+        other => panic!("Expected resume ack, got {:?}", other),
+    }
+
+    let mut prices = Vec::new();
+    let mut seq = last_seq;
+    for _ in missed_prices {
+        let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Trade { .. }), 5)
             .await
-            .expect("Failed to send subscribe message");
+            .expect("Should receive replayed trade");
+        if let ServerMessage::Trade { trade } = msg {
+            assert!(
+                trade.seq > seq,
+                "Replayed trades should be in sequence order"
+            );
+            seq = trade.seq;
+            prices.push(trade.price.parse::<u128>().unwrap());
+        }
     }
+    assert_eq!(prices, missed_prices);
 
-    ws.close(None).await.expect("Failed to close connection");
-}
+    // The resumed subscription stays live
+    trade_at(54_000_000_000).await;
+    match receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Trade { .. }), 5)
+        .await
+        .expect("Should receive live trade")
+    {
+        ServerMessage::Trade { trade } => {
+            assert!(trade.seq > seq);
+            assert_eq!(trade.price, "54000000000");
+        }
+        other => panic!("Expected trade, got {:?}", other),
+    }
 
-// ============================================================================
-// Event Receiving Tests
-// ============================================================================
-// Note: Testing event delivery requires triggering events through the matching engine
-// which would require complex order setup. For now, we test the subscription infrastructure.
-// Event delivery is indirectly tested through the subscription mechanism.
+    // A sequence the server never issued gets the current state instead
+    send_json(
+        &mut ws,
+        &ClientMessage::Resume {
+            channel: SubscriptionChannel::Trades,
+            market_id: "BTC/USDC".to_string(),
+            after_seq: seq + 1_000,
+        },
+    )
+    .await
+    .expect("Failed to resume");
+    match receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Resumed { .. }), 5)
+        .await
+        .expect("Should receive resume ack")
+    {
+        ServerMessage::Resumed { snapshot, .. } => assert!(snapshot),
+        other => panic!("Expected resume ack, got {:?}", other),
+    }
 
-// ============================================================================
-// Ping/Pong Tests
-// ============================================================================
+    ws.close(None).await.expect("Failed to close connection");
+}
 
 #[tokio::test]
-async fn test_ws_server_sends_pings() {
+async fn test_ws_handles_invalid_json() {
     let server = TestServer::start()
         .await
         .expect("Failed to start test server");
@@ -290,97 +1615,150 @@ async fn test_ws_server_sends_pings() {
         .await
         .expect("Failed to connect to WebSocket");
 
-    // Wait for server ping (happens every 30 seconds, but we'll wait up to 35 seconds)
-    let result = timeout(Duration::from_secs(35), async {
-        loop {
-            match ws.next().await {
-                Some(Ok(Message::Ping(_))) => {
-                    // Received ping! Automatically respond with pong
-                    return Ok(());
-                }
-                Some(Ok(Message::Text(text))) => {
-                    // Ignore text messages
-                    println!("Received text message: {}", text);
-                }
-                Some(Ok(msg)) => {
-                    println!("Received other message: {:?}", msg);
-                }
-                Some(Err(e)) => {
-                    return Err(anyhow::anyhow!("WebSocket error: {}", e));
-                }
-                None => {
-                    return Err(anyhow::anyhow!("Connection closed before ping"));
-                }
-            }
-        }
-    })
-    .await;
+    // Send invalid JSON
+    ws.send(Message::Text("{invalid json}".to_string().into()))
+        .await
+        .expect("Failed to send message");
 
-    match result {
-        Ok(Ok(())) => {
-            println!("Successfully received ping from server");
-        }
-        Ok(Err(e)) => {
-            panic!("Error while waiting for ping: {}", e);
-        }
-        Err(_) => {
-            println!("No ping received within 35 seconds (server sends every 30s)");
-            // This is not necessarily a failure - the test might complete before ping interval
-        }
+    // Server reports the malformed frame back to the client
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 2)
+        .await
+        .expect("Expected error frame for invalid JSON");
+    if let ServerMessage::Error { message } = msg {
+        assert!(message.starts_with("Invalid message"), "{}", message);
     }
 
+    // Connection should remain usable
+    send_json(&mut ws, &ClientMessage::Ping)
+        .await
+        .expect("Failed to send ping after invalid JSON");
+    receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Pong), 2)
+        .await
+        .expect("Expected pong after invalid JSON");
+
     ws.close(None).await.expect("Failed to close connection");
 }
 
 #[tokio::test]
-async fn test_ws_client_can_send_application_ping() {
-    let server = TestServer::start()
-        .await
-        .expect("Failed to start test server");
+async fn test_ws_oversized_frame_closes_connection() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        max_message_bytes: 256,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
 
-    let ws_url = server.ws_url.clone();
-    let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
         .await
         .expect("Failed to connect to WebSocket");
 
-    // Send application-level ping
+    // Frames under the limit are handled as usual
     send_json(&mut ws, &ClientMessage::Ping)
         .await
         .expect("Failed to send ping");
+    receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Pong), 2)
+        .await
+        .expect("Expected pong for a small frame");
 
-    // The server logs the ping but doesn't respond with Pong in current implementation
-    // This test just verifies the message is accepted
+    let oversized = json!({"type": "ping", "padding": "x".repeat(1024)}).to_string();
+    ws.send(Message::Text(oversized.into()))
+        .await
+        .expect("Failed to send oversized frame");
 
-    ws.close(None).await.expect("Failed to close connection");
+    // The server closes with "message too big"
+    let close_frame = timeout(Duration::from_secs(5), async {
+        while let Some(msg) = ws.next().await {
+            if let Ok(Message::Close(frame)) = msg {
+                return frame;
+            }
+        }
+        None
+    })
+    .await
+    .expect("Timed out waiting for close")
+    .expect("Close frame should carry a code");
+    assert_eq!(u16::from(close_frame.code), 1009);
+    assert!(
+        close_frame.reason.contains("256 bytes"),
+        "{}",
+        close_frame.reason
+    );
 }
 
-// ============================================================================
-// Error Handling Tests
-// ============================================================================
-
 #[tokio::test]
-async fn test_ws_handles_invalid_json() {
-    let server = TestServer::start()
-        .await
-        .expect("Failed to start test server");
-
-    let ws_url = server.ws_url.clone();
-    let (mut ws, _) = tokio_tungstenite::connect_async(&ws_url)
+async fn test_ws_compression_sends_deflated_binary_frames() {
+    let server = TestServer::start_with_ws_config(WebSocketConfig {
+        compression: true,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to start test server");
+
+    let mut request = server
+        .ws_url
+        .as_str()
+        .into_client_request()
+        .expect("Invalid WebSocket URL");
+    request.headers_mut().insert(
+        SEC_WEBSOCKET_PROTOCOL,
+        HeaderValue::from_static(COMPRESSION_PROTOCOL),
+    );
+    let (mut ws, response) = tokio_tungstenite::connect_async(request)
         .await
         .expect("Failed to connect to WebSocket");
+    assert_eq!(
+        response.headers()[SEC_WEBSOCKET_PROTOCOL],
+        COMPRESSION_PROTOCOL
+    );
 
-    // Send invalid JSON
-    ws.send(Message::Text("{invalid json}".to_string().into()))
+    send_json(&mut ws, &ClientMessage::Ping)
         .await
-        .expect("Failed to send message");
+        .expect("Failed to send ping");
+    let frame = timeout(Duration::from_secs(2), ws.next())
+        .await
+        .expect("Timed out waiting for pong")
+        .expect("Connection closed")
+        .expect("WebSocket error");
+    let Message::Binary(data) = frame else {
+        panic!("Expected a binary frame, got {:?}", frame);
+    };
+    let mut json = String::new();
+    DeflateDecoder::new(&data[..])
+        .read_to_string(&mut json)
+        .expect("Frame is not deflated");
+    let msg: ServerMessage = serde_json::from_str(&json).expect("Invalid server message");
+    assert!(matches!(msg, ServerMessage::Pong), "{:?}", msg);
+}
 
-    // Connection should remain open (server just ignores invalid messages)
-    // Send a valid message to verify
-    send_json(&mut ws, &ClientMessage::Ping)
+#[tokio::test]
+async fn test_ws_compression_is_off_by_default() {
+    let server = TestServer::start()
         .await
-        .expect("Failed to send ping after invalid JSON");
+        .expect("Failed to start test server");
 
-    ws.close(None).await.expect("Failed to close connection");
+    // Offering the subprotocol to a server without compression gets none selected
+    let mut request = server
+        .ws_url
+        .as_str()
+        .into_client_request()
+        .expect("Invalid WebSocket URL");
+    request.headers_mut().insert(
+        SEC_WEBSOCKET_PROTOCOL,
+        HeaderValue::from_static(COMPRESSION_PROTOCOL),
+    );
+    let error = tokio_tungstenite::connect_async(request)
+        .await
+        .expect_err("Server should not select the compression subprotocol");
+    assert!(
+        matches!(
+            error,
+            WsError::Protocol(ProtocolError::SecWebSocketSubProtocolError(
+                SubProtocolError::NoSubProtocol
+            ))
+        ),
+        "{}",
+        error
+    );
 }
 
 #[tokio::test]
@@ -404,6 +1782,14 @@ async fn test_ws_handles_unknown_message_type() {
         .await
         .expect("Failed to send message");
 
+    // Unknown message types are reported as errors
+    let msg = receive_message_of_type(&mut ws, |m| matches!(m, ServerMessage::Error { .. }), 2)
+        .await
+        .expect("Expected error frame for unknown message type");
+    if let ServerMessage::Error { message } = msg {
+        assert!(message.contains("UnknownMessageType"), "{}", message);
+    }
+
     // Connection should remain open
     send_json(&mut ws, &ClientMessage::Ping)
         .await
@@ -456,9 +1842,100 @@ async fn test_ws_rapid_subscribe_unsubscribe() {
     ws.close(None).await.expect("Failed to close connection");
 }
 
-// Note: test_ws_handles_many_events removed - would require event injection
-// which is not available without exposing event_tx. Event delivery at scale
-// would be better tested in a separate load testing suite.
+// ============================================================================
+// Load / Fan-out Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_ws_handles_many_events() {
+    const CLIENTS: usize = 25;
+    const EVENTS: usize = 5_000;
+    // Stay well under the broadcast channel capacity so no subscriber lags
+    const BATCH: usize = 250;
+
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    // Connect and subscribe every client before injecting anything
+    let mut clients = Vec::with_capacity(CLIENTS);
+    for _ in 0..CLIENTS {
+        let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+            .await
+            .expect("Failed to connect to WebSocket");
+        send_json(
+            &mut ws,
+            &ClientMessage::Subscribe {
+                channel: SubscriptionChannel::Trades,
+                market_id: Some("BTC/USDC".to_string()),
+                user_address: None,
+            },
+        )
+        .await
+        .expect("Failed to subscribe");
+        receive_message_of_type(
+            &mut ws,
+            |m| matches!(m, ServerMessage::Subscribed { .. }),
+            5,
+        )
+        .await
+        .expect("Should receive subscription ack");
+        clients.push(ws);
+    }
+
+    // Each client counts the trades it receives on its own task
+    let received: Vec<Arc<AtomicUsize>> = (0..CLIENTS)
+        .map(|_| Arc::new(AtomicUsize::new(0)))
+        .collect();
+    let mut readers = Vec::with_capacity(CLIENTS);
+    for (mut ws, count) in clients.into_iter().zip(received.iter().cloned()) {
+        readers.push(tokio::spawn(async move {
+            while count.load(Ordering::SeqCst) < EVENTS {
+                match ws.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(ServerMessage::Trade { .. }) = serde_json::from_str(&text) {
+                            count.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                    Some(Ok(_)) => continue,
+                    _ => break,
+                }
+            }
+            let _ = ws.close(None).await;
+        }));
+    }
+
+    // Inject in batches, waiting for every subscriber to catch up before the next one
+    for batch in 0..EVENTS / BATCH {
+        for _ in 0..BATCH {
+            let event =
+                TestEngine::create_trade_event("BTC/USDC", Side::Buy, 50_000_000_000, 1_000_000);
+            assert!(server.inject_event(event) > CLIENTS);
+        }
+
+        let expected = (batch + 1) * BATCH;
+        timeout(Duration::from_secs(10), async {
+            while received
+                .iter()
+                .any(|count| count.load(Ordering::SeqCst) < expected)
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("Subscribers stalled before receiving {} trades", expected));
+    }
+
+    for reader in readers {
+        timeout(Duration::from_secs(5), reader)
+            .await
+            .expect("Reader task did not finish")
+            .expect("Reader task panicked");
+    }
+    for count in &received {
+        assert_eq!(count.load(Ordering::SeqCst), EVENTS);
+    }
+}
 
 // ============================================================================
 // Multi-User Trading Tests with Full Event Verification
@@ -523,8 +2000,10 @@ async fn test_two_users_limit_order_matching_full_events() {
         .expect("Failed to create market");
 
     // Create users
-    let maker = "maker_full_test".to_string();
-    let taker = "taker_full_test".to_string();
+    let maker_wallet = Wallet::from_seed("maker_full_test");
+    let maker = maker_wallet.address();
+    let taker_wallet = Wallet::from_seed("taker_full_test");
+    let taker = taker_wallet.address();
 
     server
         .test_db
@@ -559,6 +2038,8 @@ async fn test_two_users_limit_order_matching_full_events() {
         .await
         .expect("Failed to connect maker WebSocket");
 
+    authenticate(&mut ws_maker, &maker_wallet).await;
+
     send_json(
         &mut ws_maker,
         &ClientMessage::Subscribe {
@@ -576,6 +2057,8 @@ async fn test_two_users_limit_order_matching_full_events() {
         .expect("Failed to connect taker WebSocket");
 
     // Taker subscribes to user balances and fills
+    authenticate(&mut ws_taker, &taker_wallet).await;
+
     send_json(
         &mut ws_taker,
         &ClientMessage::Subscribe {
@@ -625,6 +2108,22 @@ async fn test_two_users_limit_order_matching_full_events() {
     // Wait for subscription acknowledgments
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
+    // Consume the snapshots sent on subscribe
+    receive_message_of_type(
+        &mut ws_maker,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "BTC"),
+        2,
+    )
+    .await
+    .expect("Maker should receive initial BTC balance");
+    receive_message_of_type(
+        &mut ws_taker,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "USDC"),
+        2,
+    )
+    .await
+    .expect("Taker should receive initial USDC balance");
+
     // Maker places sell order
     let maker_order = TestEngine::create_order(
         &maker,
@@ -777,8 +2276,10 @@ async fn test_two_users_partial_fill_with_cancellation() {
         .await
         .expect("Failed to create market");
 
-    let maker = "maker_partial".to_string();
-    let taker = "taker_partial".to_string();
+    let maker_wallet = Wallet::from_seed("maker_partial");
+    let maker = maker_wallet.address();
+    let taker_wallet = Wallet::from_seed("taker_partial");
+    let taker = taker_wallet.address();
 
     server
         .test_db
@@ -819,6 +2320,8 @@ async fn test_two_users_partial_fill_with_cancellation() {
         .expect("Failed to connect");
 
     // Subscribe to user events (balances, fills, orders)
+    authenticate(&mut ws_maker, &maker_wallet).await;
+
     send_json(
         &mut ws_maker,
         &ClientMessage::Subscribe {
@@ -830,6 +2333,8 @@ async fn test_two_users_partial_fill_with_cancellation() {
     .await
     .expect("Failed to subscribe");
 
+    authenticate(&mut ws_taker, &taker_wallet).await;
+
     send_json(
         &mut ws_taker,
         &ClientMessage::Subscribe {
@@ -877,6 +2382,22 @@ async fn test_two_users_partial_fill_with_cancellation() {
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
+    // Consume the snapshots sent on subscribe
+    receive_message_of_type(
+        &mut ws_maker,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "BTC"),
+        2,
+    )
+    .await
+    .expect("Maker should receive initial BTC balance");
+    receive_message_of_type(
+        &mut ws_taker,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "USDC"),
+        2,
+    )
+    .await
+    .expect("Taker should receive initial USDC balance");
+
     // Maker places sell order for 0.01 BTC at $50/BTC
     // Price: 50 USDC per BTC = 50 * 10^6 = 50_000_000 (USDC atoms per whole BTC)
     let maker_order = TestEngine::create_order(
@@ -1045,7 +2566,8 @@ async fn test_market_order_immediate_execution_all_events() {
         .expect("Failed to create market");
 
     let maker = "maker_market".to_string();
-    let taker = "taker_market".to_string();
+    let taker_wallet = Wallet::from_seed("taker_market");
+    let taker = taker_wallet.address();
 
     server
         .test_db
@@ -1106,6 +2628,8 @@ async fn test_market_order_immediate_execution_all_events() {
     .expect("Failed to subscribe");
 
     // Taker subscribes to user balances
+    authenticate(&mut ws_taker, &taker_wallet).await;
+
     send_json(
         &mut ws_taker,
         &ClientMessage::Subscribe {
@@ -1119,6 +2643,15 @@ async fn test_market_order_immediate_execution_all_events() {
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
+    // Consume the snapshots sent on subscribe
+    receive_message_of_type(
+        &mut ws_taker,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "USDC"),
+        2,
+    )
+    .await
+    .expect("Taker should receive initial USDC balance");
+
     // Maker places limit sell order for 0.02 BTC at $50/BTC
     // Note: Engine will automatically lock the required balance
     let maker_order = TestEngine::create_order(
@@ -1135,10 +2668,10 @@ async fn test_market_order_immediate_execution_all_events() {
         .await
         .expect("Failed to place");
 
-    // Wait for orderbook update showing the ask
+    // Wait for orderbook update showing the ask (skipping the empty snapshot sent on subscribe)
     let _orderbook_before = receive_message_of_type(
         &mut ws_global,
-        |msg| matches!(msg, ServerMessage::Orderbook { .. }),
+        |msg| matches!(msg, ServerMessage::Orderbook { orderbook } if !orderbook.asks.is_empty()),
         5,
     )
     .await
@@ -1250,7 +2783,8 @@ async fn test_multiple_orders_and_cancellations_event_flow() {
         .await
         .expect("Failed to create market");
 
-    let user = "multi_order_user".to_string();
+    let user_wallet = Wallet::from_seed("multi_order_user");
+    let user = user_wallet.address();
     server
         .test_db
         .db
@@ -1270,6 +2804,8 @@ async fn test_multiple_orders_and_cancellations_event_flow() {
         .await
         .expect("Failed to connect");
 
+    authenticate(&mut ws, &user_wallet).await;
+
     send_json(
         &mut ws,
         &ClientMessage::Subscribe {
@@ -1305,6 +2841,22 @@ async fn test_multiple_orders_and_cancellations_event_flow() {
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
+    // Consume the snapshots sent on subscribe
+    receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::UserBalance { token_ticker, .. } if token_ticker == "USDC"),
+        2,
+    )
+    .await
+    .expect("User should receive initial USDC balance");
+    receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::Orderbook { .. }),
+        2,
+    )
+    .await
+    .expect("Should receive initial orderbook snapshot");
+
     // Place 3 limit buy orders at different prices for 0.01 ETH each
     // Prices: $3000, $3100, $3200 per ETH
     let mut order_ids = Vec::new();
@@ -1404,3 +2956,104 @@ async fn test_multiple_orders_and_cancellations_event_flow() {
 
     ws.close(None).await.ok();
 }
+
+#[tokio::test]
+async fn test_trade_stream_carries_taker_side() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+
+    helpers::create_market_with_tokens(&server.test_db, "BTC", "USDC")
+        .await
+        .expect("Failed to create market");
+
+    let maker = "maker_side".to_string();
+    let taker = "taker_side".to_string();
+    for user in [&maker, &taker] {
+        server
+            .test_db
+            .db
+            .create_user(user.clone())
+            .await
+            .expect("Failed to create user");
+    }
+    server
+        .test_db
+        .db
+        .add_balance(&maker, "BTC", 5_000_000)
+        .await
+        .expect("Failed to add BTC");
+    server
+        .test_db
+        .db
+        .add_balance(&taker, "USDC", 200_000_000_000)
+        .await
+        .expect("Failed to add USDC");
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&server.ws_url)
+        .await
+        .expect("Failed to connect");
+    send_json(
+        &mut ws,
+        &ClientMessage::Subscribe {
+            channel: SubscriptionChannel::Trades,
+            market_id: Some("BTC/USDC".to_string()),
+            user_address: None,
+        },
+    )
+    .await
+    .expect("Failed to subscribe");
+    receive_message_of_type(
+        &mut ws,
+        |msg| matches!(msg, ServerMessage::Subscribed { .. }),
+        2,
+    )
+    .await
+    .expect("Should receive subscription confirmation");
+
+    server
+        .test_engine
+        .place_order(TestEngine::create_order(
+            &maker,
+            "BTC/USDC",
+            Side::Sell,
+            OrderType::Limit,
+            50_000_000,
+            2_000_000,
+        ))
+        .await
+        .expect("Failed to place maker sell");
+    server
+        .test_engine
+        .place_order(TestEngine::create_order(
+            &taker,
+            "BTC/USDC",
+            Side::Buy,
+            OrderType::Market,
+            50_000_000,
+            2_000_000,
+        ))
+        .await
+        .expect("Failed to place market buy");
+
+    let msg = receive_message_of_type(&mut ws, |msg| matches!(msg, ServerMessage::Trade { .. }), 5)
+        .await
+        .expect("Should receive trade");
+
+    let json = serde_json::to_value(&msg).expect("Failed to serialize trade");
+    assert_eq!(json["trade"]["side"], "buy");
+    match msg {
+        ServerMessage::Trade { trade } => {
+            assert_eq!(
+                trade.side,
+                Side::Buy,
+                "Trade side should be the taker's side"
+            );
+            assert_eq!(trade.buyer_address, taker);
+            assert_eq!(trade.seller_address, maker);
+        }
+        other => panic!("Expected trade, got {:?}", other),
+    }
+
+    ws.close(None).await.ok();
+}
@@ -1,10 +1,12 @@
+use backend::models::domain::{OrderType, Side};
 use serde::{Deserialize, Serialize};
 
 /// Bots configuration (from apps/bots/config.toml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub exchange: ExchangeConfig,
-    pub markets: MarketsConfig,
+    #[serde(default)]
+    pub markets: Vec<MarketBotConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,62 +14,94 @@ pub struct ExchangeConfig {
     pub url: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarketsConfig {
-    #[serde(default)]
-    pub btc_usdc: Option<BtcUsdcMarketConfig>,
-    #[serde(default)]
-    pub bp_usdc: Option<BpUsdcMarketConfig>,
-}
-
 // ===========================
-// BTC/USDC Market Configuration
+// Per-Market Bot Configuration
 // ===========================
 
+/// One bot strategy running against one market
+/// Selected by the `strategy` key of each `[[markets]]` entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BtcUsdcMarketConfig {
-    pub enabled: bool,
-    #[serde(default)]
-    pub orderbook_mirror: Option<BtcOrderbookMirrorConfig>,
-    #[serde(default)]
-    pub trade_mirror: Option<BtcTradeMirrorConfig>,
-    pub hyperliquid: HyperliquidConfig,
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum MarketBotConfig {
+    OrderbookMirror(OrderbookMirrorBotConfig),
+    TradeMirror(TradeMirrorBotConfig),
+    Lmsr(LmsrBotConfig),
+    SyntheticTrader(SyntheticTraderBotConfig),
+    Twap(TwapBotConfig),
+}
+
+impl MarketBotConfig {
+    /// Market this bot trades on (e.g., "BTC/USDC")
+    pub fn market_id(&self) -> &str {
+        match self {
+            MarketBotConfig::OrderbookMirror(c) => &c.market_id,
+            MarketBotConfig::TradeMirror(c) => &c.market_id,
+            MarketBotConfig::Lmsr(c) => &c.market_id,
+            MarketBotConfig::SyntheticTrader(c) => &c.market_id,
+            MarketBotConfig::Twap(c) => &c.market_id,
+        }
+    }
+
+    /// Strategy name as written in config.toml
+    pub fn strategy(&self) -> &'static str {
+        match self {
+            MarketBotConfig::OrderbookMirror(_) => "orderbook_mirror",
+            MarketBotConfig::TradeMirror(_) => "trade_mirror",
+            MarketBotConfig::Lmsr(_) => "lmsr",
+            MarketBotConfig::SyntheticTrader(_) => "synthetic_trader",
+            MarketBotConfig::Twap(_) => "twap",
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        match self {
+            MarketBotConfig::OrderbookMirror(c) => c.enabled,
+            MarketBotConfig::TradeMirror(c) => c.enabled,
+            MarketBotConfig::Lmsr(c) => c.enabled,
+            MarketBotConfig::SyntheticTrader(c) => c.enabled,
+            MarketBotConfig::Twap(c) => c.enabled,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BtcOrderbookMirrorConfig {
+pub struct OrderbookMirrorBotConfig {
+    pub market_id: String,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
     pub user_address: String,
     pub depth_levels: usize,
     pub update_interval_ms: u64,
+    #[serde(default = "default_price_drift_bps")]
+    pub price_drift_bps: u32, // Resting orders within this of a mirrored level are left alone
+    #[serde(default = "default_size_drift_bps")]
+    pub size_drift_bps: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BtcTradeMirrorConfig {
-    pub enabled: bool,
-    pub user_address: String,
+fn default_price_drift_bps() -> u32 {
+    1
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HyperliquidConfig {
-    pub ws_url: String,
+fn default_size_drift_bps() -> u32 {
+    2_500
 }
 
-// ===========================
-// BP/USDC Market Configuration
-// ===========================
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BpUsdcMarketConfig {
+pub struct TradeMirrorBotConfig {
+    pub market_id: String,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
-    #[serde(default)]
-    pub lmsr: Option<LmsrConfig>,
-    #[serde(default)]
-    pub synthetic_trader: Option<SyntheticTraderConfig>,
+    pub user_address: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LmsrConfig {
+pub struct LmsrBotConfig {
+    pub market_id: String,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
     pub user_address: String,
     pub liquidity_param: f64, // b parameter in LMSR (controls market depth)
@@ -77,7 +111,9 @@ pub struct LmsrConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SyntheticTraderConfig {
+pub struct SyntheticTraderBotConfig {
+    pub market_id: String,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
     pub user_address: String,
     pub min_interval_ms: u64, // Min time between trades
@@ -87,6 +123,21 @@ pub struct SyntheticTraderConfig {
     pub buy_probability: f64, // Probability of buy vs sell (0.0-1.0)
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwapBotConfig {
+    pub market_id: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub user_address: String,
+    pub side: Side,
+    pub total_size: String, // u128 as string, base atoms to work
+    pub duration_ms: u64,   // Time to spread the slices over
+    pub num_slices: u32,
+    pub order_type: OrderType,
+    #[serde(default)]
+    pub limit_offset_bps: Option<u32>, // Limit slices reach this far past the best opposite price
+}
+
 impl Config {
     /// Load bots configuration from config.toml
     /// Uses CARGO_MANIFEST_DIR so the path is consistent regardless of where the binary is run from
@@ -100,4 +151,18 @@ impl Config {
         let settings = builder.build()?;
         settings.try_deserialize()
     }
+
+    /// Parse bots configuration from a TOML string
+    pub fn from_toml_str(toml: &str) -> Result<Self, config::ConfigError> {
+        let builder = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml));
+
+        let settings = builder.build()?;
+        settings.try_deserialize()
+    }
+
+    /// Bots that should be started, in config order
+    pub fn enabled_bots(&self) -> impl Iterator<Item = &MarketBotConfig> {
+        self.markets.iter().filter(|bot| bot.enabled())
+    }
 }
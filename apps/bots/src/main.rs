@@ -1,14 +1,16 @@
-mod config;
-mod markets;
-mod utils;
-
 use anyhow::{Context, Result};
-use config::Config;
-use exchange_sdk::ExchangeClient;
-use markets::bp_usdc::{LmsrConfig, LmsrMarketMakerBot, SyntheticTraderBot, SyntheticTraderConfig};
-use markets::btc_usdc::{
+use exchange_bots::config::{Config, MarketBotConfig};
+use exchange_bots::markets::bp_usdc::{
+    LmsrConfig, LmsrMarketMakerBot, SyntheticTraderBot, SyntheticTraderConfig,
+};
+use exchange_bots::markets::btc_usdc::{
     OrderbookMirrorBot, OrderbookMirrorConfig, TradeMirrorBot, TradeMirrorConfig,
 };
+use exchange_bots::markets::twap::{TwapConfig, TwapExecutorBot};
+use exchange_bots::utils::requote::DriftThreshold;
+use exchange_sdk::{ExchangeClient, SharedExchange};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -31,128 +33,20 @@ async fn main() -> Result<()> {
 
     info!("📡 Exchange URL: {}", exchange_url);
 
-    // Start bots in parallel
+    // Start bots in parallel, all listening on one shutdown signal
+    let (shutdown_tx, _) = broadcast::channel(1);
     let mut handles = vec![];
 
-    // ===========================
-    // BTC/USDC Market Bots
-    // ===========================
-
-    if let Some(btc_config) = &config.markets.btc_usdc {
-        if btc_config.enabled {
-            info!("🟡 BTC/USDC market enabled");
-
-            // Orderbook mirror bot
-            if let Some(ob_config) = &btc_config.orderbook_mirror {
-                if ob_config.enabled {
-                    let bot_config = OrderbookMirrorConfig {
-                        market_id: "BTC/USDC".to_string(),
-                        user_address: ob_config.user_address.clone(),
-                        depth_levels: ob_config.depth_levels,
-                        update_interval_ms: ob_config.update_interval_ms,
-                    };
-
-                    info!("📖 Initializing orderbook mirror bot for BTC/USDC");
-                    let client = ExchangeClient::new(&exchange_url);
-                    let mut bot = OrderbookMirrorBot::new(bot_config, client)
-                        .await
-                        .context("Failed to initialize orderbook mirror bot")?;
-
-                    let handle = tokio::spawn(async move {
-                        if let Err(e) = bot.start().await {
-                            tracing::error!("❌ Orderbook bot error: {}", e);
-                        }
-                    });
-                    handles.push(handle);
-                }
-            }
+    // Every bot talks to the same backend, so they share one connection pool
+    let exchange = SharedExchange::new(&exchange_url);
 
-            // Trade mirror bot
-            if let Some(tm_config) = &btc_config.trade_mirror {
-                if tm_config.enabled {
-                    let bot_config = TradeMirrorConfig {
-                        market_id: "BTC/USDC".to_string(),
-                        user_address: tm_config.user_address.clone(),
-                    };
-
-                    info!("💱 Initializing trade mirror bot for BTC/USDC");
-                    let client = ExchangeClient::new(&exchange_url);
-                    let mut bot = TradeMirrorBot::new(bot_config, client)
-                        .await
-                        .context("Failed to initialize trade mirror bot")?;
-
-                    let handle = tokio::spawn(async move {
-                        if let Err(e) = bot.start().await {
-                            tracing::error!("❌ Trade bot error: {}", e);
-                        }
-                    });
-                    handles.push(handle);
-                }
-            }
-        }
-    }
-
-    // ===========================
-    // BP/USDC Market Bots
-    // ===========================
-
-    if let Some(bp_config) = &config.markets.bp_usdc {
-        if bp_config.enabled {
-            info!("🔵 BP/USDC market enabled");
-
-            // LMSR market maker bot
-            if let Some(lmsr_config) = &bp_config.lmsr {
-                if lmsr_config.enabled {
-                    let bot_config = LmsrConfig {
-                        user_address: lmsr_config.user_address.clone(),
-                        liquidity_param: lmsr_config.liquidity_param,
-                        initial_probability: lmsr_config.initial_probability,
-                        update_interval_ms: lmsr_config.update_interval_ms,
-                        spread_bps: lmsr_config.spread_bps,
-                    };
-
-                    info!("📊 Initializing LMSR market maker for BP/USDC");
-                    let client = ExchangeClient::new(&exchange_url);
-                    let mut bot = LmsrMarketMakerBot::new(bot_config, client)
-                        .await
-                        .context("Failed to initialize LMSR market maker")?;
-
-                    let handle = tokio::spawn(async move {
-                        if let Err(e) = bot.start().await {
-                            tracing::error!("❌ LMSR bot error: {}", e);
-                        }
-                    });
-                    handles.push(handle);
-                }
-            }
-
-            // Synthetic trader bot
-            if let Some(trader_config) = &bp_config.synthetic_trader {
-                if trader_config.enabled {
-                    let bot_config = SyntheticTraderConfig {
-                        user_address: trader_config.user_address.clone(),
-                        min_interval_ms: trader_config.min_interval_ms,
-                        max_interval_ms: trader_config.max_interval_ms,
-                        min_size: trader_config.min_size,
-                        max_size: trader_config.max_size,
-                        buy_probability: trader_config.buy_probability,
-                    };
-
-                    info!("🎲 Initializing synthetic trader for BP/USDC");
-                    let client = ExchangeClient::new(&exchange_url);
-                    let mut bot = SyntheticTraderBot::new(bot_config, client)
-                        .await
-                        .context("Failed to initialize synthetic trader")?;
-
-                    let handle = tokio::spawn(async move {
-                        if let Err(e) = bot.start().await {
-                            tracing::error!("❌ Synthetic trader error: {}", e);
-                        }
-                    });
-                    handles.push(handle);
-                }
-            }
-        }
+    for bot_config in config.enabled_bots() {
+        info!(
+            "🚀 Initializing {} bot for {}",
+            bot_config.strategy(),
+            bot_config.market_id()
+        );
+        handles.push(spawn_bot(bot_config, exchange.client(), shutdown_tx.subscribe()).await?);
     }
 
     // ===========================
@@ -166,10 +60,158 @@ async fn main() -> Result<()> {
 
     info!("✅ All enabled bots are running ({} total)", handles.len());
 
-    // Wait for any bot to finish
+    // Wait for SIGINT/SIGTERM, then have every bot pull its quotes before exiting
+    shutdown_signal().await;
+    info!("🛑 Shutdown signal received, cancelling resting orders...");
+    let _ = shutdown_tx.send(());
+
     for handle in handles {
         let _ = handle.await;
     }
 
+    info!("👋 All bots stopped");
+
     Ok(())
 }
+
+/// Resolves on Ctrl+C, or SIGTERM on unix (e.g. `docker stop`)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("❌ Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("❌ Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Initialize the bot described by `bot_config` and run it on its own task until `shutdown` fires
+async fn spawn_bot(
+    bot_config: &MarketBotConfig,
+    client: ExchangeClient,
+    shutdown: broadcast::Receiver<()>,
+) -> Result<JoinHandle<()>> {
+    let handle = match bot_config {
+        MarketBotConfig::OrderbookMirror(c) => {
+            let bot_config = OrderbookMirrorConfig {
+                market_id: c.market_id.clone(),
+                user_address: c.user_address.clone(),
+                depth_levels: c.depth_levels,
+                update_interval_ms: c.update_interval_ms,
+                drift: DriftThreshold {
+                    price_bps: c.price_drift_bps,
+                    size_bps: c.size_drift_bps,
+                },
+            };
+            let mut bot = OrderbookMirrorBot::new(bot_config, client)
+                .await
+                .context("Failed to initialize orderbook mirror bot")?;
+
+            tokio::spawn(async move {
+                if let Err(e) = bot.run(shutdown).await {
+                    tracing::error!("❌ Orderbook bot error: {}", e);
+                }
+            })
+        }
+        MarketBotConfig::TradeMirror(c) => {
+            let bot_config = TradeMirrorConfig {
+                market_id: c.market_id.clone(),
+                user_address: c.user_address.clone(),
+            };
+            let mut bot = TradeMirrorBot::new(bot_config, client)
+                .await
+                .context("Failed to initialize trade mirror bot")?;
+
+            tokio::spawn(async move {
+                if let Err(e) = bot.run(shutdown).await {
+                    tracing::error!("❌ Trade bot error: {}", e);
+                }
+            })
+        }
+        MarketBotConfig::Lmsr(c) => {
+            let bot_config = LmsrConfig {
+                market_id: c.market_id.clone(),
+                user_address: c.user_address.clone(),
+                liquidity_param: c.liquidity_param,
+                initial_probability: c.initial_probability,
+                update_interval_ms: c.update_interval_ms,
+                spread_bps: c.spread_bps,
+            };
+            let mut bot = LmsrMarketMakerBot::new(bot_config, client)
+                .await
+                .context("Failed to initialize LMSR market maker")?;
+
+            tokio::spawn(async move {
+                if let Err(e) = bot.run(shutdown).await {
+                    tracing::error!("❌ LMSR bot error: {}", e);
+                }
+            })
+        }
+        MarketBotConfig::SyntheticTrader(c) => {
+            let bot_config = SyntheticTraderConfig {
+                market_id: c.market_id.clone(),
+                user_address: c.user_address.clone(),
+                min_interval_ms: c.min_interval_ms,
+                max_interval_ms: c.max_interval_ms,
+                min_size: c.min_size,
+                max_size: c.max_size,
+                buy_probability: c.buy_probability,
+            };
+            let mut bot = SyntheticTraderBot::new(bot_config, client)
+                .await
+                .context("Failed to initialize synthetic trader")?;
+
+            tokio::spawn(async move {
+                if let Err(e) = bot.run(shutdown).await {
+                    tracing::error!("❌ Synthetic trader error: {}", e);
+                }
+            })
+        }
+        MarketBotConfig::Twap(c) => {
+            let bot_config = TwapConfig {
+                market_id: c.market_id.clone(),
+                user_address: c.user_address.clone(),
+                side: c.side,
+                total_size: c
+                    .total_size
+                    .parse()
+                    .context("TWAP total_size must be an integer number of base atoms")?,
+                duration_ms: c.duration_ms,
+                num_slices: c.num_slices,
+                order_type: c.order_type,
+                limit_offset_bps: c.limit_offset_bps,
+            };
+            let mut bot = TwapExecutorBot::new(bot_config, client)
+                .await
+                .context("Failed to initialize TWAP executor")?;
+
+            tokio::spawn(async move {
+                if let Err(e) = bot.run(shutdown).await {
+                    tracing::error!("❌ TWAP executor error: {}", e);
+                }
+            })
+        }
+    };
+
+    Ok(handle)
+}
@@ -4,12 +4,14 @@ use backend::models::domain::{Market, OrderType, Side};
 use exchange_sdk::ExchangeClient;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// Configuration for the LMSR market maker bot
 #[derive(Clone, Debug)]
 pub struct LmsrConfig {
+    pub market_id: String, // e.g., "BP/USDC"
     pub user_address: String,
     pub liquidity_param: f64,     // b parameter in LMSR
     pub initial_probability: f64, // Starting probability [0, 1]
@@ -39,12 +41,15 @@ pub struct LmsrMarketMakerBot {
 
 impl LmsrMarketMakerBot {
     pub async fn new(config: LmsrConfig, exchange_client: ExchangeClient) -> Result<Self> {
-        info!("LMSR Market Maker bot initialized for BP/USDC");
+        info!("LMSR Market Maker bot initialized for {}", config.market_id);
 
         // Fetch market configuration and auto-faucet initial funds
-        let market =
-            bot_helpers::fetch_market_and_faucet(&exchange_client, "BP/USDC", &config.user_address)
-                .await?;
+        let market = bot_helpers::fetch_market_and_faucet(
+            &exchange_client,
+            &config.market_id,
+            &config.user_address,
+        )
+        .await?;
 
         // Initialize cumulative shares based on initial probability
         // For p = 0.5, we want q_yes = q_no = 0
@@ -77,9 +82,37 @@ impl LmsrMarketMakerBot {
         })
     }
 
+    /// Run the bot until `shutdown` fires, then pull its resting orders
+    pub async fn run(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let result = tokio::select! {
+            result = self.start() => result,
+            _ = shutdown.recv() => Ok(()),
+        };
+
+        self.shutdown().await?;
+        result
+    }
+
+    /// Stop quoting and cancel every order this bot has on its market
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!(
+            "Shutting down LMSR market maker for {}",
+            self.config.market_id
+        );
+        bot_helpers::cancel_all_on_shutdown(
+            &self.exchange_client,
+            &self.config.user_address,
+            &self.config.market_id,
+            "lmsr_market_maker",
+        )
+        .await?;
+        self.active_orders.clear();
+        Ok(())
+    }
+
     /// Start the bot
     pub async fn start(&mut self) -> Result<()> {
-        info!("Starting LMSR market maker for BP/USDC");
+        info!("Starting LMSR market maker for {}", self.config.market_id);
         info!(
             "Update interval: {}ms, Spread: {} bps",
             self.config.update_interval_ms, self.config.spread_bps
@@ -209,7 +242,7 @@ impl LmsrMarketMakerBot {
             .exchange_client
             .place_order_decimal(
                 self.config.user_address.clone(),
-                "BP/USDC".to_string(),
+                self.config.market_id.clone(),
                 side,
                 OrderType::Limit,
                 format!("{:.6}", price),
@@ -237,7 +270,7 @@ impl LmsrMarketMakerBot {
             .exchange_client
             .cancel_all_orders(
                 self.config.user_address.clone(),
-                Some("BP/USDC".to_string()),
+                Some(self.config.market_id.clone()),
                 "lmsr_market_maker".to_string(),
             )
             .await
@@ -4,11 +4,13 @@ use backend::models::domain::{Market, OrderType, Side};
 use exchange_sdk::ExchangeClient;
 use rand::{Rng, SeedableRng};
 use std::time::Duration;
+use tokio::sync::broadcast;
 use tracing::{info, warn};
 
 /// Configuration for the synthetic trader bot
 #[derive(Clone, Debug)]
 pub struct SyntheticTraderConfig {
+    pub market_id: String, // e.g., "BP/USDC"
     pub user_address: String,
     pub min_interval_ms: u64, // Minimum time between trades
     pub max_interval_ms: u64, // Maximum time between trades
@@ -34,12 +36,15 @@ impl SyntheticTraderBot {
         config: SyntheticTraderConfig,
         exchange_client: ExchangeClient,
     ) -> Result<Self> {
-        info!("Synthetic Trader bot initialized for BP/USDC");
+        info!("Synthetic Trader bot initialized for {}", config.market_id);
 
         // Fetch market configuration and auto-faucet initial funds
-        let market =
-            bot_helpers::fetch_market_and_faucet(&exchange_client, "BP/USDC", &config.user_address)
-                .await?;
+        let market = bot_helpers::fetch_market_and_faucet(
+            &exchange_client,
+            &config.market_id,
+            &config.user_address,
+        )
+        .await?;
 
         info!(
             "Trade intervals: {}-{}ms, Size range: {}-{} BP",
@@ -53,9 +58,36 @@ impl SyntheticTraderBot {
         })
     }
 
+    /// Run the bot until `shutdown` fires, then pull its resting orders
+    pub async fn run(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let result = tokio::select! {
+            result = self.start() => result,
+            _ = shutdown.recv() => Ok(()),
+        };
+
+        self.shutdown().await?;
+        result
+    }
+
+    /// Stop quoting and cancel every order this bot has on its market
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!(
+            "Shutting down synthetic trader for {}",
+            self.config.market_id
+        );
+        bot_helpers::cancel_all_on_shutdown(
+            &self.exchange_client,
+            &self.config.user_address,
+            &self.config.market_id,
+            "synthetic_trader",
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Start the bot
     pub async fn start(&mut self) -> Result<()> {
-        info!("Starting synthetic trader for BP/USDC");
+        info!("Starting synthetic trader for {}", self.config.market_id);
 
         // Wait for LMSR market maker to place initial orders
         // This ensures synthetic trader is always the taker, not maker
@@ -110,7 +142,7 @@ impl SyntheticTraderBot {
             .exchange_client
             .place_order_decimal(
                 self.config.user_address.clone(),
-                "BP/USDC".to_string(),
+                self.config.market_id.clone(),
                 side,
                 OrderType::Limit, // Changed from Market to Limit
                 limit_price.to_string(),
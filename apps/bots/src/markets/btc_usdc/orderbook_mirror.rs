@@ -1,10 +1,13 @@
 use super::hyperliquid::{HlMessage, HyperliquidClient, Orderbook};
 use crate::utils::bot_helpers;
+use crate::utils::requote::{self, DesiredQuote, DriftThreshold, RestingQuote};
 use anyhow::Result;
 use backend::models::domain::{Market, OrderType, Side};
 use exchange_sdk::ExchangeClient;
-use std::collections::HashMap;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
@@ -15,6 +18,7 @@ pub struct OrderbookMirrorConfig {
     pub user_address: String,    // Bot's wallet address
     pub depth_levels: usize,     // How many levels to mirror (e.g., 5)
     pub update_interval_ms: u64, // Min time between order updates
+    pub drift: DriftThreshold,   // How far a resting order may drift before it's replaced
 }
 
 /// Orderbook mirror bot - maintains liquidity by copying Hyperliquid's orderbook
@@ -22,10 +26,12 @@ pub struct OrderbookMirrorBot {
     config: OrderbookMirrorConfig,
     exchange_client: ExchangeClient,
     orderbook: Orderbook,
-    active_orders: HashMap<String, Uuid>, // price_side -> order_id
+    resting_orders: Vec<RestingQuote>,
 
     // Market configuration fetched from backend
     market: Market,
+    base_decimals: u8,
+    quote_decimals: u8,
 }
 
 impl OrderbookMirrorBot {
@@ -50,15 +56,55 @@ impl OrderbookMirrorBot {
         let coin = market.base_ticker.clone();
         let orderbook = Orderbook::new(coin.clone());
 
+        // Replaces are sent in atoms, so keep the token decimals around
+        let base_decimals = exchange_client
+            .get_token(&market.base_ticker)
+            .await?
+            .decimals;
+        let quote_decimals = exchange_client
+            .get_token(&market.quote_ticker)
+            .await?
+            .decimals;
+
         Ok(Self {
             config,
             exchange_client,
             orderbook,
-            active_orders: HashMap::new(),
+            resting_orders: Vec::new(),
             market,
+            base_decimals,
+            quote_decimals,
         })
     }
 
+    /// Run the bot until `shutdown` fires, then pull its resting orders
+    pub async fn run(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let result = tokio::select! {
+            result = self.start() => result,
+            _ = shutdown.recv() => Ok(()),
+        };
+
+        self.shutdown().await?;
+        result
+    }
+
+    /// Stop quoting and cancel every order this bot has on its market
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!(
+            "Shutting down orderbook mirror for {}",
+            self.config.market_id
+        );
+        bot_helpers::cancel_all_on_shutdown(
+            &self.exchange_client,
+            &self.config.user_address,
+            &self.config.market_id,
+            "orderbook_mirror",
+        )
+        .await?;
+        self.resting_orders.clear();
+        Ok(())
+    }
+
     /// Start the bot
     pub async fn start(&mut self) -> Result<()> {
         info!(
@@ -116,97 +162,129 @@ impl OrderbookMirrorBot {
     async fn sync_orderbook(&mut self) -> Result<()> {
         let (bids, asks) = self.orderbook.get_top_levels(self.config.depth_levels);
 
-        // Separate old orders by side to prevent crossing
-        let mut old_bids = Vec::new();
-        let mut old_asks = Vec::new();
-
-        for (key, order_id) in self.active_orders.drain() {
-            if key.ends_with("_buy") {
-                old_bids.push(order_id);
-            } else {
-                old_asks.push(order_id);
+        let desired: Vec<DesiredQuote> = asks
+            .iter()
+            .map(|level| (Side::Sell, level))
+            .chain(bids.iter().map(|level| (Side::Buy, level)))
+            .map(|(side, level)| DesiredQuote {
+                side,
+                price: level.price,
+                size: level.quantity,
+            })
+            .collect();
+
+        // Only orders that drifted off the mirrored levels get touched
+        let plan = requote::diff_quotes(&self.resting_orders, &desired, self.config.drift);
+        if plan.message_count() == 0 {
+            return Ok(());
+        }
+        self.resting_orders = plan.keep;
+
+        // Settle asks before bids so the book never crosses mid-sync
+        for side in [Side::Sell, Side::Buy] {
+            let cancels = plan
+                .cancel
+                .iter()
+                .filter(|quote| quote.side == side)
+                .map(|quote| quote.order_id)
+                .collect();
+            self.cancel_orders_list(cancels).await;
+
+            for (order_id, quote) in plan.replace.iter().filter(|(_, quote)| quote.side == side) {
+                self.replace_quote(*order_id, quote).await;
+            }
+            for quote in plan.place.iter().filter(|quote| quote.side == side) {
+                self.place_quote(quote).await;
             }
         }
 
-        // Strategy to avoid crossing while maintaining liquidity:
-        // 1. Cancel old asks first (keeps bid side liquid)
-        // 2. Place new asks (now we have bids + new asks)
-        // 3. Cancel old bids (keeps ask side liquid)
-        // 4. Place new bids (now we have complete new book)
-
-        // Step 1: Cancel old asks
-        self.cancel_orders_list(old_asks).await;
-
-        // Step 2: Place new ask orders
-        for level in asks {
-            let price = level.price.to_string();
-            let size = level.quantity.to_string();
+        Ok(())
+    }
 
-            match self
-                .exchange_client
-                .place_order_decimal(
-                    self.config.user_address.clone(),
-                    self.config.market_id.clone(),
-                    Side::Sell,
-                    OrderType::Limit,
-                    price.clone(),
-                    size.clone(),
-                    "orderbook_mirror".to_string(),
-                )
-                .await
-            {
-                Ok(result) => {
-                    let order_id = result.order.id;
-                    let key = format!("{}_{}", price, "sell");
-                    self.active_orders.insert(key, order_id);
-                }
-                Err(e) => {
-                    let err_msg = e.to_string();
-                    warn!("Failed to place ask order at {}: {}", price, err_msg);
+    /// Move a resting order onto a new level in one request, placing fresh if it's gone
+    async fn replace_quote(&mut self, order_id: Uuid, quote: &DesiredQuote) {
+        let Some((price, size)) = self.quote_atoms(quote) else {
+            warn!("Skipping unrepresentable quote at {}", quote.price);
+            self.cancel_orders_list(vec![order_id]).await;
+            return;
+        };
 
-                    // Try to auto-faucet if it's a balance error
-                    self.auto_faucet_on_error(&err_msg).await;
-                }
+        match self
+            .exchange_client
+            .replace_order(
+                self.config.user_address.clone(),
+                order_id.to_string(),
+                self.config.market_id.clone(),
+                quote.side,
+                OrderType::Limit,
+                price,
+                size,
+                "orderbook_mirror".to_string(),
+            )
+            .await
+        {
+            Ok(result) => self.resting_orders.push(RestingQuote {
+                order_id: result.placed.order.id,
+                side: quote.side,
+                price: quote.price,
+                size: quote.size,
+            }),
+            Err(e) => {
+                // Usually the order filled since the last sync
+                warn!("Failed to replace order {}: {}", order_id, e);
+                self.place_quote(quote).await;
             }
         }
+    }
 
-        // Step 3: Cancel old bids
-        self.cancel_orders_list(old_bids).await;
-
-        // Step 4: Place new bid orders
-        for level in bids {
-            let price = level.price.to_string();
-            let size = level.quantity.to_string();
-
-            match self
-                .exchange_client
-                .place_order_decimal(
-                    self.config.user_address.clone(),
-                    self.config.market_id.clone(),
-                    Side::Buy,
-                    OrderType::Limit,
-                    price.clone(),
-                    size.clone(),
-                    "orderbook_mirror".to_string(),
-                )
-                .await
-            {
-                Ok(result) => {
-                    let order_id = result.order.id;
-                    let key = format!("{}_{}", price, "buy");
-                    self.active_orders.insert(key, order_id);
-                }
-                Err(e) => {
-                    let err_msg = e.to_string();
-                    warn!("Failed to place bid order at {}: {}", price, err_msg);
+    /// Place a new order at a mirrored level
+    async fn place_quote(&mut self, quote: &DesiredQuote) {
+        match self
+            .exchange_client
+            .place_order_decimal(
+                self.config.user_address.clone(),
+                self.config.market_id.clone(),
+                quote.side,
+                OrderType::Limit,
+                quote.price.to_string(),
+                quote.size.to_string(),
+                "orderbook_mirror".to_string(),
+            )
+            .await
+        {
+            Ok(result) => self.resting_orders.push(RestingQuote {
+                order_id: result.order.id,
+                side: quote.side,
+                price: quote.price,
+                size: quote.size,
+            }),
+            Err(e) => {
+                let err_msg = e.to_string();
+                warn!(
+                    "Failed to place {} order at {}: {}",
+                    quote.side, quote.price, err_msg
+                );
 
-                    // Try to auto-faucet if it's a balance error
-                    self.auto_faucet_on_error(&err_msg).await;
-                }
+                // Try to auto-faucet if it's a balance error
+                self.auto_faucet_on_error(&err_msg).await;
             }
         }
+    }
 
-        Ok(())
+    /// Convert a quote to price and size atoms, size rounded down to the lot
+    fn quote_atoms(&self, quote: &DesiredQuote) -> Option<(String, String)> {
+        let to_atoms = |value: Decimal, decimals: u8| {
+            value
+                .checked_mul(Decimal::from(10u128.pow(decimals as u32)))
+                .and_then(|atoms| atoms.to_u128())
+        };
+
+        let price = to_atoms(quote.price, self.quote_decimals)?;
+        let size = ExchangeClient::round_size_to_lot(
+            to_atoms(quote.size, self.base_decimals)?,
+            self.market.lot_size,
+        );
+        (size >= self.market.min_size).then(|| (price.to_string(), size.to_string()))
     }
 
     /// Cancel a list of orders by ID
@@ -264,7 +342,7 @@ impl OrderbookMirrorBot {
             }
         }
 
-        self.active_orders.clear();
+        self.resting_orders.clear();
         Ok(())
     }
 
@@ -3,6 +3,7 @@ use crate::utils::bot_helpers;
 use anyhow::Result;
 use backend::models::domain::{Market, OrderType, Side};
 use exchange_sdk::ExchangeClient;
+use tokio::sync::broadcast;
 use tracing::{error, info, warn};
 
 /// Configuration for the trade mirror bot
@@ -43,6 +44,30 @@ impl TradeMirrorBot {
         })
     }
 
+    /// Run the bot until `shutdown` fires, then pull its resting orders
+    pub async fn run(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let result = tokio::select! {
+            result = self.start() => result,
+            _ = shutdown.recv() => Ok(()),
+        };
+
+        self.shutdown().await?;
+        result
+    }
+
+    /// Stop quoting and cancel every order this bot has on its market
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!("Shutting down trade mirror for {}", self.config.market_id);
+        bot_helpers::cancel_all_on_shutdown(
+            &self.exchange_client,
+            &self.config.user_address,
+            &self.config.market_id,
+            "trade_mirror",
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Start the bot
     pub async fn start(&mut self) -> Result<()> {
         info!(
@@ -1,2 +1,3 @@
 pub mod bp_usdc;
 pub mod btc_usdc;
+pub mod twap;
@@ -0,0 +1,322 @@
+use crate::utils::bot_helpers;
+use anyhow::{bail, Result};
+use backend::models::domain::{Market, OrderType, Side, TimeInForce, Trade};
+use exchange_sdk::{ExchangeClient, ServerMessage, SubscribeTarget, WebSocketClient};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Configuration for the TWAP executor bot
+#[derive(Clone, Debug)]
+pub struct TwapConfig {
+    pub market_id: String, // e.g., "BTC/USDC"
+    pub user_address: String,
+    pub side: Side,
+    pub total_size: u128, // Base atoms to work, must be a multiple of the lot size
+    pub duration_ms: u64, // Time to spread the slices over
+    pub num_slices: u32,
+    pub order_type: OrderType,
+    pub limit_offset_bps: Option<u32>, // Limit slices: how far past the best opposite price to reach
+}
+
+/// One scheduled child order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TwapSlice {
+    pub offset: Duration, // Time after the start to place it
+    pub size: u128,
+}
+
+/// Evenly sized slices of a parent order, spaced evenly over the duration
+///
+/// Sizes are whole lots; when the lots don't divide evenly the earlier
+/// slices take one extra lot. Slices that would be empty are dropped, so a
+/// small order placed over many slices still fires at the original cadence
+#[derive(Clone, Debug)]
+pub struct TwapSchedule {
+    slices: Vec<TwapSlice>,
+}
+
+impl TwapSchedule {
+    pub fn new(
+        total_size: u128,
+        lot_size: u128,
+        duration: Duration,
+        num_slices: u32,
+    ) -> Result<Self> {
+        if num_slices == 0 {
+            bail!("num_slices must be at least 1");
+        }
+        if lot_size == 0 || !total_size.is_multiple_of(lot_size) {
+            bail!(
+                "total_size {} must be a multiple of the lot size {}",
+                total_size,
+                lot_size
+            );
+        }
+
+        let total_lots = total_size / lot_size;
+        let per_slice = total_lots / num_slices as u128;
+        let extra = total_lots % num_slices as u128;
+        let interval = duration / num_slices;
+
+        let slices = (0..num_slices)
+            .map(|i| {
+                let lots = per_slice + u128::from((i as u128) < extra);
+                TwapSlice {
+                    offset: interval * i,
+                    size: lots * lot_size,
+                }
+            })
+            .filter(|slice| slice.size > 0)
+            .collect();
+
+        Ok(Self { slices })
+    }
+
+    pub fn slices(&self) -> &[TwapSlice] {
+        &self.slices
+    }
+
+    pub fn total_size(&self) -> u128 {
+        self.slices.iter().map(|slice| slice.size).sum()
+    }
+}
+
+/// TWAP executor bot - works a large order by placing evenly sized child orders over time
+///
+/// - Follows a `TwapSchedule` from the moment it starts
+/// - Limit slices are IOC, priced `limit_offset_bps` past the best opposite price
+/// - Tracks fills on the market's public trades channel, which carries both addresses,
+///   and stops early once the total is filled
+pub struct TwapExecutorBot {
+    config: TwapConfig,
+    exchange_client: ExchangeClient,
+    market: Market,
+    schedule: TwapSchedule,
+
+    // Trade ids already counted, fills arrive both in order responses and over WebSocket
+    seen_trades: HashSet<String>,
+    filled: u128,
+}
+
+impl TwapExecutorBot {
+    pub async fn new(config: TwapConfig, exchange_client: ExchangeClient) -> Result<Self> {
+        info!("TWAP executor initialized for {}", config.market_id);
+
+        let market = bot_helpers::fetch_market_and_faucet(
+            &exchange_client,
+            &config.market_id,
+            &config.user_address,
+        )
+        .await?;
+
+        let schedule = TwapSchedule::new(
+            config.total_size,
+            market.lot_size,
+            Duration::from_millis(config.duration_ms),
+            config.num_slices,
+        )?;
+        if let Some(smallest) = schedule.slices().iter().map(|slice| slice.size).min() {
+            if smallest < market.min_size {
+                warn!(
+                    "Smallest TWAP slice {} is below the market minimum {}, those orders will be rejected",
+                    smallest, market.min_size
+                );
+            }
+        }
+
+        info!(
+            "TWAP: {:?} {} over {}ms in {} slices ({:?})",
+            config.side,
+            config.total_size,
+            config.duration_ms,
+            schedule.slices().len(),
+            config.order_type
+        );
+
+        Ok(Self {
+            config,
+            exchange_client,
+            market,
+            schedule,
+            seen_trades: HashSet::new(),
+            filled: 0,
+        })
+    }
+
+    /// Run the bot until the order is worked or `shutdown` fires, then pull its resting orders
+    pub async fn run(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let result = tokio::select! {
+            result = self.start() => result,
+            _ = shutdown.recv() => Ok(()),
+        };
+
+        self.shutdown().await?;
+        result
+    }
+
+    /// Stop working the order and cancel anything left on the book
+    pub async fn shutdown(&mut self) -> Result<()> {
+        info!(
+            "Shutting down TWAP executor for {} ({} of {} filled)",
+            self.config.market_id, self.filled, self.config.total_size
+        );
+        bot_helpers::cancel_all_on_shutdown(
+            &self.exchange_client,
+            &self.config.user_address,
+            &self.config.market_id,
+            "twap",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Work the order through the schedule
+    pub async fn start(&mut self) -> Result<()> {
+        let mut fills = WebSocketClient::new(self.exchange_client.ws_url())
+            .connect()
+            .await?;
+        fills.subscribe(SubscribeTarget::trades(&self.config.market_id))?;
+
+        let started = Instant::now();
+        let slices = self.schedule.slices().to_vec();
+
+        for (i, slice) in slices.iter().enumerate() {
+            // Count fills while waiting for the slice to come due
+            let due = started + slice.offset;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(due) => break,
+                    message = fills.recv() => match message {
+                        Some(message) => self.record_fill_message(message),
+                        None => bail!("Trades stream closed"),
+                    },
+                }
+            }
+
+            let remaining = self.config.total_size.saturating_sub(self.filled);
+            if remaining == 0 {
+                info!("✅ TWAP fully filled after {} slices", i);
+                return Ok(());
+            }
+
+            let size = slice.size.min(remaining);
+            if let Err(e) = self.place_slice(size).await {
+                warn!("TWAP slice {} of {} failed: {}", i + 1, slices.len(), e);
+                bot_helpers::auto_faucet_on_error(
+                    &self.exchange_client,
+                    &self.config.user_address,
+                    &self.market,
+                    &e.to_string(),
+                )
+                .await;
+            }
+        }
+
+        info!(
+            "TWAP schedule complete: {} of {} filled",
+            self.filled, self.config.total_size
+        );
+        Ok(())
+    }
+
+    /// Place one child order and count its immediate fills
+    async fn place_slice(&mut self, size: u128) -> Result<()> {
+        let placed = match self.config.order_type {
+            OrderType::Market => {
+                self.exchange_client
+                    .place_order(
+                        self.config.user_address.clone(),
+                        self.config.market_id.clone(),
+                        self.config.side,
+                        OrderType::Market,
+                        "0".to_string(),
+                        size.to_string(),
+                        "twap".to_string(),
+                    )
+                    .await?
+            }
+            OrderType::Limit => {
+                let price = self.limit_price(size).await?;
+                self.exchange_client
+                    .place_order_with_time_in_force(
+                        self.config.user_address.clone(),
+                        self.config.market_id.clone(),
+                        self.config.side,
+                        OrderType::Limit,
+                        TimeInForce::Ioc,
+                        price.to_string(),
+                        size.to_string(),
+                        "twap".to_string(),
+                    )
+                    .await?
+            }
+        };
+
+        for trade in &placed.trades {
+            self.record_trade(trade);
+        }
+        info!(
+            "⏱ TWAP slice {:?} {} → {} filled so far",
+            self.config.side, size, self.filled
+        );
+        Ok(())
+    }
+
+    /// Best opposite price moved `limit_offset_bps` towards the other side, on a tick
+    async fn limit_price(&self, size: u128) -> Result<u128> {
+        let estimate = self
+            .exchange_client
+            .estimate_order(
+                Some(self.config.user_address.clone()),
+                self.config.market_id.clone(),
+                self.config.side,
+                OrderType::Market,
+                "0".to_string(),
+                size.to_string(),
+            )
+            .await?;
+        let Some(best) = estimate.best_price else {
+            bail!("no opposite liquidity to price a limit slice");
+        };
+        let best: u128 = best.parse()?;
+
+        let offset_bps = self.config.limit_offset_bps.unwrap_or(0) as u128;
+        let tick = self.market.tick_size;
+        let price = match self.config.side {
+            Side::Buy => best * (10_000 + offset_bps) / 10_000 / tick * tick,
+            Side::Sell => {
+                (best * 10_000u128.saturating_sub(offset_bps) / 10_000).div_ceil(tick) * tick
+            }
+        };
+        Ok(price.max(tick))
+    }
+
+    fn record_fill_message(&mut self, message: serde_json::Value) {
+        let Ok(ServerMessage::Trade { trade }) = serde_json::from_value(message) else {
+            return;
+        };
+        let ours = match self.config.side {
+            Side::Buy => trade.buyer_address == self.config.user_address,
+            Side::Sell => trade.seller_address == self.config.user_address,
+        };
+        if trade.market_id != self.config.market_id || !ours {
+            return;
+        }
+        if let Ok(size) = trade.size.parse::<u128>() {
+            self.count(trade.id, size);
+        }
+    }
+
+    fn record_trade(&mut self, trade: &Trade) {
+        self.count(trade.id.to_string(), trade.size);
+    }
+
+    fn count(&mut self, trade_id: String, size: u128) {
+        if self.seen_trades.insert(trade_id) {
+            self.filled += size;
+        }
+    }
+}
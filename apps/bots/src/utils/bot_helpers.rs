@@ -41,6 +41,30 @@ pub async fn fetch_market_and_faucet(
     Ok(market)
 }
 
+/// Cancel every resting order a bot holds on a market, returning how many were cancelled
+/// Unlike the bots' own cancel helpers this surfaces errors, so shutdown failures are visible
+pub async fn cancel_all_on_shutdown(
+    client: &ExchangeClient,
+    user_address: &str,
+    market_id: &str,
+    signature: &str,
+) -> Result<usize> {
+    let result = client
+        .cancel_all_orders(
+            user_address.to_string(),
+            Some(market_id.to_string()),
+            signature.to_string(),
+        )
+        .await?;
+
+    info!(
+        "🛑 Cancelled {} resting orders for {} on {}",
+        result.count, user_address, market_id
+    );
+
+    Ok(result.count)
+}
+
 /// Auto-faucet funds if we detect insufficient balance error
 /// Returns true if faucet was triggered
 pub async fn auto_faucet_on_error(
@@ -1 +1,2 @@
 pub mod bot_helpers;
+pub mod requote;
@@ -0,0 +1,134 @@
+//! Requoting by diff: only touch resting orders whose level drifted
+//!
+//! Instead of cancelling every quote and placing the book again, a bot diffs
+//! the levels it wants against the orders it has resting. Orders close enough
+//! to a wanted level are kept, drifted ones are moved with an atomic replace,
+//! and only the leftovers are cancelled or placed.
+
+use backend::models::domain::Side;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// A level the bot wants to quote, in display units
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesiredQuote {
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// An order the bot has resting, at the level it was placed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestingQuote {
+    pub order_id: Uuid,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal, // Size as placed, partial fills aren't tracked
+}
+
+/// How far a resting order may be from a wanted level and still count as quoting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriftThreshold {
+    pub price_bps: u32,
+    pub size_bps: u32,
+}
+
+impl DriftThreshold {
+    /// Whether `resting` is close enough to `desired` to leave it alone
+    pub fn within(&self, resting: &RestingQuote, desired: &DesiredQuote) -> bool {
+        resting.side == desired.side
+            && Self::drift_bps(resting.price, desired.price) <= Decimal::from(self.price_bps)
+            && Self::drift_bps(resting.size, desired.size) <= Decimal::from(self.size_bps)
+    }
+
+    /// Distance from `target` in basis points of `target`
+    fn drift_bps(value: Decimal, target: Decimal) -> Decimal {
+        if target.is_zero() {
+            return if value.is_zero() {
+                Decimal::ZERO
+            } else {
+                Decimal::MAX
+            };
+        }
+        (value - target).abs() * Decimal::from(10_000) / target.abs()
+    }
+}
+
+/// Orders to send so the resting quotes match the desired ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequotePlan {
+    pub keep: Vec<RestingQuote>,
+    pub replace: Vec<(Uuid, DesiredQuote)>, // Resting order moved to a new level in one request
+    pub cancel: Vec<RestingQuote>,
+    pub place: Vec<DesiredQuote>,
+}
+
+impl RequotePlan {
+    /// Requests the plan sends, kept orders cost nothing
+    pub fn message_count(&self) -> usize {
+        self.replace.len() + self.cancel.len() + self.place.len()
+    }
+}
+
+/// Diff resting orders against the wanted levels
+///
+/// Each wanted level keeps the closest resting order on its side within the
+/// threshold. Left over orders and levels on the same side are paired up as
+/// replaces, best price first; whatever remains is cancelled or placed. Every
+/// resting order ends up in exactly one of keep, replace or cancel.
+pub fn diff_quotes(
+    resting: &[RestingQuote],
+    desired: &[DesiredQuote],
+    threshold: DriftThreshold,
+) -> RequotePlan {
+    let mut plan = RequotePlan::default();
+
+    for side in [Side::Sell, Side::Buy] {
+        let mut unmatched: Vec<&RestingQuote> = best_first(
+            resting.iter().filter(|quote| quote.side == side).collect(),
+            side,
+            |quote| quote.price,
+        );
+        let wanted = best_first(
+            desired.iter().filter(|quote| quote.side == side).collect(),
+            side,
+            |quote| quote.price,
+        );
+
+        // Keep the closest order within the threshold for each wanted level
+        let mut unfilled = Vec::new();
+        for level in wanted {
+            let closest = unmatched
+                .iter()
+                .enumerate()
+                .filter(|(_, quote)| threshold.within(quote, level))
+                .min_by_key(|(_, quote)| (quote.price - level.price).abs())
+                .map(|(index, _)| index);
+            match closest {
+                Some(index) => plan.keep.push(unmatched.remove(index).clone()),
+                None => unfilled.push(level),
+            }
+        }
+
+        // Move drifted orders onto the uncovered levels, then settle the remainder
+        let mut unmatched = unmatched.into_iter();
+        for level in unfilled {
+            match unmatched.next() {
+                Some(quote) => plan.replace.push((quote.order_id, level.clone())),
+                None => plan.place.push(level.clone()),
+            }
+        }
+        plan.cancel.extend(unmatched.cloned());
+    }
+
+    plan
+}
+
+/// Sort quotes with the most aggressive price first: highest bid, lowest ask
+fn best_first<T>(mut quotes: Vec<&T>, side: Side, price: impl Fn(&T) -> Decimal) -> Vec<&T> {
+    quotes.sort_by_key(|quote| price(quote));
+    if side == Side::Buy {
+        quotes.reverse();
+    }
+    quotes
+}
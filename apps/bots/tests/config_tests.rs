@@ -0,0 +1,126 @@
+/// Config parsing tests for the multi-market bot runner
+use backend::models::domain::{OrderType, Side};
+use exchange_bots::config::{Config, MarketBotConfig};
+
+const TWO_MARKETS: &str = r#"
+[exchange]
+url = "http://localhost:8888"
+
+[[markets]]
+strategy = "orderbook_mirror"
+market_id = "ETH/USDC"
+user_address = "eth_maker"
+depth_levels = 5
+update_interval_ms = 1000
+
+[[markets]]
+strategy = "trade_mirror"
+market_id = "ETH/USDC"
+user_address = "eth_taker"
+
+[[markets]]
+strategy = "lmsr"
+market_id = "RAIN/USDC"
+user_address = "rain_lmsr"
+liquidity_param = 500.0
+initial_probability = 0.3
+update_interval_ms = 3000
+spread_bps = 25
+
+[[markets]]
+strategy = "synthetic_trader"
+market_id = "RAIN/USDC"
+enabled = false
+user_address = "rain_trader"
+min_interval_ms = 1000
+max_interval_ms = 2000
+min_size = 1.0
+max_size = 5.0
+buy_probability = 0.5
+"#;
+
+#[test]
+fn test_config_builds_bots_for_each_market() {
+    let config = Config::from_toml_str(TWO_MARKETS).expect("Failed to parse config");
+
+    assert_eq!(config.exchange.url, "http://localhost:8888");
+    assert_eq!(config.markets.len(), 4);
+
+    // Disabled entries are parsed but not started
+    let bots: Vec<(&str, &str)> = config
+        .enabled_bots()
+        .map(|bot| (bot.market_id(), bot.strategy()))
+        .collect();
+    assert_eq!(
+        bots,
+        vec![
+            ("ETH/USDC", "orderbook_mirror"),
+            ("ETH/USDC", "trade_mirror"),
+            ("RAIN/USDC", "lmsr"),
+        ]
+    );
+
+    // Strategy-specific settings land in the matching variant
+    match &config.markets[2] {
+        MarketBotConfig::Lmsr(lmsr) => {
+            assert_eq!(lmsr.user_address, "rain_lmsr");
+            assert_eq!(lmsr.liquidity_param, 500.0);
+            assert_eq!(lmsr.spread_bps, 25);
+        }
+        other => panic!("Expected lmsr bot, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_config_rejects_unknown_strategy() {
+    let toml = r#"
+[exchange]
+url = "http://localhost:8888"
+
+[[markets]]
+strategy = "arbitrage"
+market_id = "ETH/USDC"
+user_address = "arb"
+"#;
+
+    assert!(Config::from_toml_str(toml).is_err());
+}
+
+#[test]
+fn test_repo_config_parses() {
+    let path = format!("{}/config.toml", env!("CARGO_MANIFEST_DIR"));
+    let toml = std::fs::read_to_string(path).expect("Failed to read config.toml");
+    let config = Config::from_toml_str(&toml).expect("Failed to parse config.toml");
+
+    assert!(config.enabled_bots().count() > 0);
+}
+
+#[test]
+fn test_config_parses_twap_bot() {
+    let toml = r#"
+[exchange]
+url = "http://localhost:8888"
+
+[[markets]]
+strategy = "twap"
+market_id = "ETH/USDC"
+user_address = "eth_twap"
+side = "sell"
+total_size = "500000000"
+duration_ms = 60000
+num_slices = 12
+order_type = "market"
+"#;
+
+    let config = Config::from_toml_str(toml).expect("Failed to parse config");
+    match &config.markets[0] {
+        MarketBotConfig::Twap(twap) => {
+            assert_eq!(twap.side, Side::Sell);
+            assert_eq!(twap.order_type, OrderType::Market);
+            assert_eq!(twap.total_size, "500000000");
+            assert_eq!(twap.num_slices, 12);
+            assert_eq!(twap.limit_offset_bps, None);
+        }
+        other => panic!("Expected twap bot, got {:?}", other),
+    }
+}
@@ -596,6 +596,7 @@ async fn test_lmsr_bot_places_orders() {
 
     // Create LMSR bot with 50% initial probability
     let config = LmsrConfig {
+        market_id: "BP/USDC".to_string(),
         user_address: "lmsr_bot".to_string(),
         liquidity_param: 1000.0,
         initial_probability: 0.5,
@@ -678,6 +679,7 @@ async fn test_synthetic_trader_initialization() {
 
     // Create synthetic trader
     let trader_config = SyntheticTraderConfig {
+        market_id: "BP/USDC".to_string(),
         user_address: "synthetic_trader_test".to_string(),
         min_interval_ms: 500,
         max_interval_ms: 1000,
@@ -725,6 +727,7 @@ async fn test_bp_market_bots_initialization() {
 
     // Create LMSR market maker
     let lmsr_config = LmsrConfig {
+        market_id: "BP/USDC".to_string(),
         user_address: "lmsr_e2e".to_string(),
         liquidity_param: 1000.0,
         initial_probability: 0.5,
@@ -738,6 +741,7 @@ async fn test_bp_market_bots_initialization() {
 
     // Create synthetic trader
     let trader_config = SyntheticTraderConfig {
+        market_id: "BP/USDC".to_string(),
         user_address: "trader_e2e".to_string(),
         min_interval_ms: 1000,
         max_interval_ms: 2000,
@@ -786,3 +790,70 @@ async fn test_bp_market_bots_initialization() {
         );
     }
 }
+
+/// Test a running bot cancels its resting orders when shutdown is signalled
+#[tokio::test]
+async fn test_bot_shutdown_cancels_resting_orders() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    setup_bp_market(&server)
+        .await
+        .expect("Failed to setup BP market");
+
+    let client = ExchangeClient::new(&server.base_url);
+
+    let config = LmsrConfig {
+        market_id: "BP/USDC".to_string(),
+        user_address: "lmsr_shutdown".to_string(),
+        liquidity_param: 1000.0,
+        initial_probability: 0.5,
+        update_interval_ms: 60000, // Only the initial quotes
+        spread_bps: 50,
+    };
+
+    let mut bot = LmsrMarketMakerBot::new(config.clone(), client.clone())
+        .await
+        .expect("Failed to create LMSR bot");
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+    let handle = tokio::spawn(async move { bot.run(shutdown_rx).await });
+
+    // Wait for the bot to start quoting
+    let mut resting = 0;
+    for _ in 0..50 {
+        resting = client
+            .get_orders(&config.user_address, Some(config.market_id.clone()))
+            .await
+            .expect("Failed to get orders")
+            .iter()
+            .filter(|o| o.status == OrderStatus::Pending)
+            .count();
+        if resting > 0 {
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+    assert!(
+        resting > 0,
+        "Bot should have resting quotes before shutdown"
+    );
+
+    shutdown_tx.send(()).expect("Failed to send shutdown");
+    handle
+        .await
+        .expect("Bot task panicked")
+        .expect("Bot shutdown failed");
+
+    // cancel_all_orders ran for the bot's market, so nothing is left resting
+    let orders = client
+        .get_orders(&config.user_address, Some(config.market_id.clone()))
+        .await
+        .expect("Failed to get orders");
+    assert!(!orders.is_empty());
+    assert!(
+        orders.iter().all(|o| o.status == OrderStatus::Cancelled),
+        "All bot orders should be cancelled after shutdown: {:?}",
+        orders
+    );
+}
@@ -0,0 +1,132 @@
+/// Diff tests for requoting only the levels that drifted
+use backend::models::domain::Side;
+use exchange_bots::utils::requote::{
+    diff_quotes, DesiredQuote, DriftThreshold, RequotePlan, RestingQuote,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const THRESHOLD: DriftThreshold = DriftThreshold {
+    price_bps: 5,
+    size_bps: 1_000,
+};
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn resting(side: Side, price: &str, size: &str) -> RestingQuote {
+    RestingQuote {
+        order_id: Uuid::new_v4(),
+        side,
+        price: dec(price),
+        size: dec(size),
+    }
+}
+
+fn desired(side: Side, price: &str, size: &str) -> DesiredQuote {
+    DesiredQuote {
+        side,
+        price: dec(price),
+        size: dec(size),
+    }
+}
+
+#[test]
+fn test_diff_quotes_touches_only_drifted_levels() {
+    let ask_kept = resting(Side::Sell, "100.01", "1.0");
+    let ask_drifted = resting(Side::Sell, "100.50", "1.0");
+    let bid_kept = resting(Side::Buy, "99.99", "2.0");
+    let bid_resized = resting(Side::Buy, "99.90", "1.0");
+    let bid_stale = resting(Side::Buy, "99.00", "1.0");
+
+    let plan = diff_quotes(
+        &[
+            ask_kept.clone(),
+            ask_drifted.clone(),
+            bid_kept.clone(),
+            bid_resized.clone(),
+            bid_stale.clone(),
+        ],
+        &[
+            // 100.02 is 1bp from the resting 100.01, 1.05 is 5% off its size
+            desired(Side::Sell, "100.02", "1.05"),
+            desired(Side::Sell, "100.20", "1.0"),
+            desired(Side::Sell, "100.30", "1.0"),
+            desired(Side::Buy, "99.99", "2.0"),
+            // Same price but double the size
+            desired(Side::Buy, "99.90", "2.0"),
+        ],
+        THRESHOLD,
+    );
+
+    assert_eq!(
+        plan,
+        RequotePlan {
+            keep: vec![ask_kept, bid_kept],
+            replace: vec![
+                (ask_drifted.order_id, desired(Side::Sell, "100.20", "1.0")),
+                (bid_resized.order_id, desired(Side::Buy, "99.90", "2.0")),
+            ],
+            cancel: vec![bid_stale],
+            place: vec![desired(Side::Sell, "100.30", "1.0")],
+        }
+    );
+    assert_eq!(plan.message_count(), 4);
+}
+
+#[test]
+fn test_diff_quotes_is_a_no_op_when_nothing_drifted() {
+    let current = vec![
+        resting(Side::Sell, "100.00", "1.0"),
+        resting(Side::Buy, "99.00", "1.0"),
+    ];
+    let wanted: Vec<DesiredQuote> = current
+        .iter()
+        .map(|quote| DesiredQuote {
+            side: quote.side,
+            price: quote.price,
+            size: quote.size,
+        })
+        .collect();
+
+    let plan = diff_quotes(&current, &wanted, THRESHOLD);
+
+    assert_eq!(plan.message_count(), 0);
+    assert_eq!(plan.keep, current);
+}
+
+#[test]
+fn test_diff_quotes_keeps_closest_order_for_each_level() {
+    // Both orders are within 5bp of 100.00, the nearer one is kept
+    let near = resting(Side::Sell, "100.01", "1.0");
+    let far = resting(Side::Sell, "100.04", "1.0");
+
+    let plan = diff_quotes(
+        &[far.clone(), near.clone()],
+        &[desired(Side::Sell, "100.00", "1.0")],
+        THRESHOLD,
+    );
+
+    assert_eq!(plan.keep, vec![near]);
+    assert_eq!(plan.cancel, vec![far]);
+    assert!(plan.replace.is_empty());
+    assert!(plan.place.is_empty());
+}
+
+#[test]
+fn test_diff_quotes_never_keeps_across_sides() {
+    let bid = resting(Side::Buy, "100.00", "1.0");
+
+    let plan = diff_quotes(
+        std::slice::from_ref(&bid),
+        &[desired(Side::Sell, "100.00", "1.0")],
+        THRESHOLD,
+    );
+
+    assert!(plan.keep.is_empty());
+    assert!(plan.replace.is_empty());
+    assert_eq!(plan.cancel, vec![bid]);
+    assert_eq!(plan.place, vec![desired(Side::Sell, "100.00", "1.0")]);
+}
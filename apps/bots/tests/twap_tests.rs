@@ -0,0 +1,52 @@
+/// Slicing tests for the TWAP executor schedule
+use exchange_bots::markets::twap::{TwapSchedule, TwapSlice};
+use std::time::Duration;
+
+#[test]
+fn test_twap_slices_sum_to_total_at_even_cadence() {
+    // 10 lots over 4 slices: the first two slices take the extra lots
+    let schedule = TwapSchedule::new(100_000, 10_000, Duration::from_secs(60), 4).unwrap();
+
+    assert_eq!(
+        schedule.slices(),
+        &[
+            TwapSlice {
+                offset: Duration::from_secs(0),
+                size: 30_000,
+            },
+            TwapSlice {
+                offset: Duration::from_secs(15),
+                size: 30_000,
+            },
+            TwapSlice {
+                offset: Duration::from_secs(30),
+                size: 20_000,
+            },
+            TwapSlice {
+                offset: Duration::from_secs(45),
+                size: 20_000,
+            },
+        ]
+    );
+    assert_eq!(schedule.total_size(), 100_000);
+}
+
+#[test]
+fn test_twap_drops_empty_slices_but_keeps_cadence() {
+    // 2 lots over 5 slices: only the first two fire, still 2s apart
+    let schedule = TwapSchedule::new(20, 10, Duration::from_secs(10), 5).unwrap();
+
+    let offsets: Vec<Duration> = schedule.slices().iter().map(|s| s.offset).collect();
+    assert_eq!(
+        offsets,
+        vec![Duration::from_secs(0), Duration::from_secs(2)]
+    );
+    assert_eq!(schedule.total_size(), 20);
+}
+
+#[test]
+fn test_twap_rejects_invalid_schedules() {
+    assert!(TwapSchedule::new(100, 10, Duration::from_secs(10), 0).is_err());
+    assert!(TwapSchedule::new(105, 10, Duration::from_secs(10), 2).is_err());
+    assert!(TwapSchedule::new(100, 0, Duration::from_secs(10), 2).is_err());
+}
@@ -3,13 +3,23 @@
 import { useEffect } from "react";
 import { useTurnkey } from "@turnkey/react-wallet-kit";
 import { useExchangeStore } from "@/lib/store";
+import { useExchangeClient } from "@/lib/hooks/useExchangeClient";
 import { Button } from "@/components/ui/button";
 import { Copy, CheckCircle2, LogOut, Wallet } from "lucide-react";
 import { toast } from "sonner";
 import { useState } from "react";
 
+/**
+ * Join Turnkey's raw r/s/v signature into the 65-byte hex form the exchange expects
+ */
+function toSignatureHex({ r, s, v }: { r: string; s: string; v: string }): string {
+  const hex = (value: string, length: number) => value.replace(/^0x/, "").padStart(length, "0");
+  return `0x${hex(r, 64)}${hex(s, 64)}${hex(v, 2)}`;
+}
+
 export function AuthButton() {
-  const { handleLogin, wallets, authState, logout } = useTurnkey();
+  const { handleLogin, wallets, authState, logout, signMessage } = useTurnkey();
+  const client = useExchangeClient();
   const userAddress = useExchangeStore((state) => state.userAddress);
   const isAuthenticated = useExchangeStore((state) => state.isAuthenticated);
   const setUser = useExchangeStore((state) => state.setUser);
@@ -22,20 +32,30 @@ export function AuthButton() {
       // User is authenticated in Turnkey but not in our store
       const firstWallet = wallets[0];
       if (firstWallet && firstWallet.accounts && firstWallet.accounts.length > 0) {
-        const address = firstWallet.accounts[0]?.address;
-        if (!address) return;
+        const account = firstWallet.accounts[0];
+        const address = account?.address;
+        if (!account || !address) return;
         setUser(address);
         toast.success("Wallet connected successfully");
+
+        // Private WebSocket channels need proof that we hold the address
+        client
+          .authenticate(address, async (message) =>
+            toSignatureHex(await signMessage({ message, walletAccount: account }))
+          )
+          .catch((error) => console.error("WebSocket sign-in failed:", error));
       }
     } else if (authState === "unauthenticated" && isAuthenticated) {
       // User logged out from Turnkey, sync our store
+      client.signOut();
       clearUser();
     }
-  }, [authState, wallets, isAuthenticated, setUser, clearUser]);
+  }, [authState, wallets, isAuthenticated, setUser, clearUser, client, signMessage]);
 
   const handleLogout = () => {
     // Call Turnkey logout and clear local state
     logout();
+    client.signOut();
     clearUser();
     toast.success("Wallet disconnected");
   };
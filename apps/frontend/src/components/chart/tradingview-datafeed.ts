@@ -29,6 +29,7 @@ const resolutionMap: Record<string, string> = {
   "5": "5m",
   "15": "15m",
   "60": "1h",
+  "240": "4h",
   D: "1d",
   "1D": "1d",
 };
@@ -39,6 +40,7 @@ const resolutionToSeconds: Record<string, number> = {
   "5": 300, // 5 minutes
   "15": 900, // 15 minutes
   "60": 3600, // 1 hour
+  "240": 14400, // 4 hours
   D: 86400, // 1 day
   "1D": 86400,
 };
@@ -54,7 +56,7 @@ interface BarSubscription {
 
 export class ExchangeDatafeed implements IBasicDataFeed {
   private configurationData = {
-    supported_resolutions: ["1", "5", "15", "60", "D"] as ResolutionString[],
+    supported_resolutions: ["1", "5", "15", "60", "240", "D"] as ResolutionString[],
     exchanges: [{ value: "Exchange", name: "Exchange", desc: "Exchange" }],
     symbols_types: [{ name: "crypto", value: "crypto" }],
   };
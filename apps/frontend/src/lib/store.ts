@@ -206,6 +206,8 @@ export const useExchangeStore = create<ExchangeState>()(
             token_ticker: tokenTicker,
             user_address: existing?.user_address || state.userAddress || "",
             amount: totalAmount,
+            available,
+            locked,
             open_interest: locked,
             updated_at: new Date(),
             amountDisplay: amountValue.toFixed(token.decimals),
@@ -5,7 +5,7 @@ from .sdk import ExchangeClient
 
 # Individual clients
 from .client import ExchangeClient as RestClient
-from .websocket import WebSocketClient
+from .websocket import MessageSigner, WebSocketClient
 
 # Services
 from .cache import CacheService
@@ -66,6 +66,7 @@ __all__ = [
     # Individual clients
     "RestClient",
     "WebSocketClient",
+    "MessageSigner",
     # Services
     "CacheService",
     "EnhancementService",
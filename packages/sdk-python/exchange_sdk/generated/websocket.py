@@ -10,9 +10,30 @@ from pydantic import BaseModel, ConfigDict, Field, RootModel
 
 
 class ClientMessage3(BaseModel):
+    type: Literal['auth_challenge']
+
+
+class ClientMessage4(BaseModel):
+    signature: str
+    type: Literal['auth']
+    user_address: str
+
+
+class ClientMessage5(BaseModel):
     type: Literal['ping']
 
 
+class ClientMessage6(BaseModel):
+    type: Literal['list_subscriptions']
+
+
+class MarketStatus(Enum):
+    pre_open = 'pre_open'
+    open = 'open'
+    halted = 'halted'
+    closed = 'closed'
+
+
 class PriceLevel(BaseModel):
     price: str
     size: str
@@ -37,8 +58,10 @@ class ServerMessage7(BaseModel):
 
 
 class ServerMessage8(BaseModel):
+    amount: str
     available: str
     locked: str
+    open_interest: str
     token_ticker: str
     type: Literal['user_balance']
     updated_at: int
@@ -46,11 +69,28 @@ class ServerMessage8(BaseModel):
 
 
 class ServerMessage9(BaseModel):
+    market_id: str
+    status: MarketStatus
+    type: Literal['market_status']
+
+
+class ServerMessage10(BaseModel):
+    message: str
+    nonce: str
+    type: Literal['auth_challenge']
+
+
+class ServerMessage11(BaseModel):
+    type: Literal['authenticated']
+    user_address: str
+
+
+class ServerMessage14(BaseModel):
     message: str
     type: Literal['error']
 
 
-class ServerMessage10(BaseModel):
+class ServerMessage15(BaseModel):
     type: Literal['pong']
 
 
@@ -61,10 +101,12 @@ class Side(Enum):
 
 class SubscriptionChannel(Enum):
     trades = 'trades'
+    all_trades = 'all_trades'
     orderbook = 'orderbook'
     user_fills = 'user_fills'
     user_orders = 'user_orders'
     user_balances = 'user_balances'
+    market_status = 'market_status'
 
 
 class TradeData(BaseModel):
@@ -75,11 +117,22 @@ class TradeData(BaseModel):
     price: str
     seller_address: str
     seller_order_id: str
+    seq: int | None = Field(
+        0,
+        description="Market sequence number, shared with the market's orderbook updates",
+        ge=0,
+    )
     side: Side
     size: str
     timestamp: int
 
 
+class ActiveSubscription(BaseModel):
+    channel: SubscriptionChannel
+    market_id: str | None = None
+    user_address: str | None = None
+
+
 class ClientMessage1(BaseModel):
     channel: SubscriptionChannel
     market_id: str | None = None
@@ -94,14 +147,53 @@ class ClientMessage2(BaseModel):
     user_address: str | None = None
 
 
-class ClientMessage(RootModel[ClientMessage1 | ClientMessage2 | ClientMessage3]):
-    root: ClientMessage1 | ClientMessage2 | ClientMessage3
+class ClientMessage7(BaseModel):
+    after_seq: int = Field(..., ge=0)
+    channel: SubscriptionChannel
+    market_id: str
+    type: Literal['resume']
+
+
+class ClientMessage(
+    RootModel[
+        ClientMessage1
+        | ClientMessage2
+        | ClientMessage3
+        | ClientMessage4
+        | ClientMessage5
+        | ClientMessage6
+        | ClientMessage7
+    ]
+):
+    root: (
+        ClientMessage1
+        | ClientMessage2
+        | ClientMessage3
+        | ClientMessage4
+        | ClientMessage5
+        | ClientMessage6
+        | ClientMessage7
+    )
 
 
 class OrderbookData(BaseModel):
     asks: list[PriceLevel]
     bids: list[PriceLevel]
+    checksum: int = Field(
+        ...,
+        description='CRC32 of the top 25 levels per side, as `price:size` pairs joined by `,`\nwith bids and asks separated by `|` (see OrderbookSnapshot::compute_checksum)',
+        ge=0,
+    )
+    is_snapshot: bool | None = Field(
+        False,
+        description='True for the book sent in reply to a subscribe: replace any local state with it.\nFalse for the updates pushed afterwards. Updates currently carry the full book\nas well, but only a message with this flag set is guaranteed to be complete',
+    )
     market_id: str
+    seq: int | None = Field(
+        0,
+        description="Market sequence number, shared with the market's trades (see ClientMessage::Resume)",
+        ge=0,
+    )
 
 
 class ServerMessage1(BaseModel):
@@ -133,6 +225,19 @@ class ServerMessage6(BaseModel):
     type: Literal['user_fill']
 
 
+class ServerMessage12(BaseModel):
+    subscriptions: list[ActiveSubscription]
+    type: Literal['subscriptions']
+
+
+class ServerMessage13(BaseModel):
+    channel: SubscriptionChannel
+    market_id: str
+    replayed: int = Field(..., ge=0)
+    snapshot: bool
+    type: Literal['resumed']
+
+
 class ServerMessage(
     RootModel[
         ServerMessage1
@@ -145,6 +250,11 @@ class ServerMessage(
         | ServerMessage8
         | ServerMessage9
         | ServerMessage10
+        | ServerMessage11
+        | ServerMessage12
+        | ServerMessage13
+        | ServerMessage14
+        | ServerMessage15
     ]
 ):
     root: (
@@ -158,6 +268,11 @@ class ServerMessage(
         | ServerMessage8
         | ServerMessage9
         | ServerMessage10
+        | ServerMessage11
+        | ServerMessage12
+        | ServerMessage13
+        | ServerMessage14
+        | ServerMessage15
     )
 
 
@@ -3,7 +3,7 @@
 import asyncio
 from typing import Optional, Callable, Any
 from .client import ExchangeClient as RestClient
-from .websocket import WebSocketClient
+from .websocket import MessageSigner, WebSocketClient
 from .cache import CacheService
 from .enhancement import (
     EnhancementService,
@@ -254,6 +254,12 @@ class ExchangeClient:
     # Convenience Methods - WebSocket (delegate to ws)
     # ========================================================================
 
+    async def authenticate(self, user_address: str, sign_message: MessageSigner) -> None:
+        """Sign in to a user's private channels with their wallet."""
+        if not self.ws:
+            raise ValueError("WebSocket client not initialized")
+        await self.ws.authenticate(user_address, sign_message)
+
     def on_trades(self, market_id: str, handler: Callable[[EnhancedTrade], None]) -> Callable[[], None]:
         """
         Stream trades for a market.
@@ -2,7 +2,7 @@
 
 import asyncio
 import json
-from typing import Any, Callable, Optional
+from typing import Any, Awaitable, Callable, Optional, Union
 import websockets
 from websockets.client import WebSocketClientProtocol
 
@@ -12,6 +12,9 @@ from .cache import CacheService
 from .enhancement import EnhancementService, EnhancedTrade, EnhancedOrderbookLevel, WsTradeData
 from .logger import Logger
 
+# Signs a message with the user's wallet (EIP-191 personal_sign), returning the 0x-prefixed signature
+MessageSigner = Callable[[str], Union[str, Awaitable[str]]]
+
 
 class WebSocketClient:
     """
@@ -61,8 +64,14 @@ class WebSocketClient:
         self._receive_task: Optional[asyncio.Task] = None
         self._last_pong_time = 0.0
         self._pong_timeout = 60.0  # seconds
+        self._auth_timeout = 30.0  # seconds per sign-in step
         self._should_reconnect = True
 
+        # User to sign in as on every (re)connect, before subscriptions are sent
+        self._session: Optional[tuple[str, MessageSigner]] = None
+        self._authenticated_user: Optional[str] = None
+        self._is_authenticating = False
+
         # Message queue for when disconnected
         self._message_queue: list[dict[str, Any]] = []
 
@@ -90,17 +99,22 @@ class WebSocketClient:
 
             self.logger.info("WebSocket connected")
 
+            # Start ping and receive tasks
+            self._ping_task = asyncio.create_task(self._ping_loop())
+            self._receive_task = asyncio.create_task(self._receive_loop())
+
+            # Sign in first, private channels need it
+            if self._session:
+                try:
+                    await self._sign_in(*self._session)
+                except Exception as e:
+                    self.logger.error(f"WebSocket authentication failed: {e}")
+
             # Re-subscribe to all active subscriptions
             await self._resubscribe_all()
 
             # Send queued messages
-            while self._message_queue:
-                msg = self._message_queue.pop(0)
-                await self._send(msg)
-
-            # Start ping and receive tasks
-            self._ping_task = asyncio.create_task(self._ping_loop())
-            self._receive_task = asyncio.create_task(self._receive_loop())
+            await self._flush_queue()
 
         except Exception as e:
             self.logger.error(f"Failed to connect: {e}")
@@ -125,6 +139,7 @@ class WebSocketClient:
             pass
         finally:
             self._is_connected = False
+            self._authenticated_user = None
             if self._should_reconnect:
                 await self._schedule_reconnect()
 
@@ -203,6 +218,14 @@ class WebSocketClient:
             self.logger.debug(f"Re-subscribed to {key}")
 
     async def _send(self, message: dict[str, Any]) -> None:
+        """Send message to server, holding it while sign-in is in progress."""
+        if self._is_authenticating:
+            self._message_queue.append(message)
+            return
+
+        await self._send_now(message)
+
+    async def _send_now(self, message: dict[str, Any]) -> None:
         """Send message to server."""
         if not self._is_connected or not self._ws or self._ws.closed:
             self._message_queue.append(message)
@@ -214,6 +237,79 @@ class WebSocketClient:
             self.logger.error(f"Failed to send message: {e}")
             self._message_queue.append(message)
 
+    async def _flush_queue(self) -> None:
+        """Send messages queued while disconnected or signing in."""
+        queued, self._message_queue = self._message_queue, []
+        for msg in queued:
+            await self._send(msg)
+
+    async def authenticate(self, user_address: str, sign_message: MessageSigner) -> None:
+        """
+        Sign in as a user, required before subscribing to private user channels.
+
+        The server issues a one-time challenge for `sign_message` to sign with the user's
+        wallet. Messages sent meanwhile are held until sign-in completes, and the session
+        is re-established after every reconnect.
+
+        Args:
+            user_address: User address to sign in as
+            sign_message: Sync or async function signing the challenge message
+        """
+        previous = self._authenticated_user
+        self._session = (user_address, sign_message)
+        if previous == user_address:
+            return
+
+        if previous is not None:
+            # A connection signs in only once, reconnect to switch users
+            if self._ws:
+                await self._ws.close()
+            return
+
+        if self._is_connected:
+            try:
+                await self._sign_in(user_address, sign_message)
+            finally:
+                await self._flush_queue()
+
+    async def _sign_in(self, user_address: str, sign_message: MessageSigner) -> None:
+        """Answer a server-issued challenge with the user's signature."""
+        self._is_authenticating = True
+        try:
+            challenge = self._next_reply("auth_challenge")
+            await self._send_now({"type": "auth_challenge"})
+            reply = await asyncio.wait_for(challenge, self._auth_timeout)
+
+            signature = sign_message(reply["message"])
+            if asyncio.iscoroutine(signature):
+                signature = await signature
+
+            authenticated = self._next_reply("authenticated")
+            await self._send_now({"type": "auth", "user_address": user_address, "signature": signature})
+            await asyncio.wait_for(authenticated, self._auth_timeout)
+
+            self._authenticated_user = user_address
+            self.logger.info(f"Authenticated as {user_address}")
+        finally:
+            self._is_authenticating = False
+
+    def _next_reply(self, message_type: str) -> "asyncio.Future[dict[str, Any]]":
+        """Future for the next message of a type, failing on an error message."""
+        future: asyncio.Future[dict[str, Any]] = asyncio.get_event_loop().create_future()
+
+        def on_reply(message: dict[str, Any]) -> None:
+            if not future.done():
+                future.set_result(message)
+
+        def on_error(message: dict[str, Any]) -> None:
+            if not future.done():
+                future.set_exception(WebSocketError(message.get("message", "Authentication failed")))
+
+        remove_reply = self.on(message_type, on_reply)
+        remove_error = self.on("error", on_error)
+        future.add_done_callback(lambda _: (remove_reply(), remove_error()))
+        return future
+
     def _get_subscription_key(self, channel: str, market_id: Optional[str] = None, user_address: Optional[str] = None) -> str:
         """Get subscription key for tracking."""
         identifier = market_id or user_address or "global"
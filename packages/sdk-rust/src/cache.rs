@@ -1,20 +1,31 @@
 //! Cache service for storing and retrieving markets and tokens
 //!
 //! Provides in-memory caching of market and token data to avoid
-//! repeated REST API calls.
+//! repeated REST API calls. Lookups confirmed absent by the backend are
+//! remembered briefly (negative caching) so repeated misses don't each
+//! cost a round-trip.
 
 use backend::models::{api::ApiMarket, domain::Token};
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
+use crate::error::SdkResult;
 use crate::logger::Logger;
 
+/// Default time a confirmed-absent market/token is remembered as not found
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(5);
+
 /// Cache statistics
 #[derive(Debug, Clone)]
 pub struct CacheStats {
     pub markets: usize,
     pub tokens: usize,
     pub initialized: bool,
+    /// Lookups answered as "not found" from the negative cache
+    pub negative_hits: usize,
 }
 
 /// Thread-safe cache service for markets and tokens
@@ -22,6 +33,10 @@ pub struct CacheStats {
 pub struct CacheService {
     tokens: Arc<RwLock<HashMap<String, Token>>>,
     markets: Arc<RwLock<HashMap<String, ApiMarket>>>,
+    missing_tokens: Arc<RwLock<HashMap<String, Instant>>>,
+    missing_markets: Arc<RwLock<HashMap<String, Instant>>>,
+    negative_ttl: Duration,
+    negative_hits: Arc<AtomicUsize>,
     initialized: Arc<RwLock<bool>>,
     logger: Arc<dyn Logger>,
 }
@@ -32,18 +47,30 @@ impl CacheService {
         Self {
             tokens: Arc::new(RwLock::new(HashMap::new())),
             markets: Arc::new(RwLock::new(HashMap::new())),
+            missing_tokens: Arc::new(RwLock::new(HashMap::new())),
+            missing_markets: Arc::new(RwLock::new(HashMap::new())),
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            negative_hits: Arc::new(AtomicUsize::new(0)),
             initialized: Arc::new(RwLock::new(false)),
             logger,
         }
     }
 
+    /// Set how long confirmed-absent markets/tokens are remembered
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
     // ===== Tokens =====
 
     /// Set tokens in the cache
     pub fn set_tokens(&self, tokens: Vec<Token>) {
         let mut cache = self.tokens.write().unwrap();
         cache.clear();
+        let mut missing = self.missing_tokens.write().unwrap();
         for token in tokens.iter() {
+            missing.remove(&token.ticker);
             cache.insert(token.ticker.clone(), token.clone());
         }
         self.logger
@@ -65,13 +92,68 @@ impl CacheService {
         self.tokens.read().unwrap().contains_key(ticker)
     }
 
+    /// Add or replace a single token
+    pub fn insert_token(&self, token: Token) {
+        self.missing_tokens.write().unwrap().remove(&token.ticker);
+        self.tokens
+            .write()
+            .unwrap()
+            .insert(token.ticker.clone(), token);
+    }
+
+    /// Remember that the backend has no token with this ticker
+    pub fn mark_token_missing(&self, ticker: &str) {
+        self.missing_tokens
+            .write()
+            .unwrap()
+            .insert(ticker.to_string(), Instant::now());
+    }
+
+    /// Check if a token is negatively cached (counts as a negative hit)
+    pub fn is_token_missing(&self, ticker: &str) -> bool {
+        self.check_missing(&self.missing_tokens, ticker)
+    }
+
+    /// Get a token, fetching it on a miss
+    /// `fetch` is skipped while the ticker is negatively cached;
+    /// a fetch returning `None` negatively caches it
+    pub async fn get_token_or_fetch<F, Fut>(
+        &self,
+        ticker: &str,
+        fetch: F,
+    ) -> SdkResult<Option<Token>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = SdkResult<Option<Token>>>,
+    {
+        if let Some(token) = self.get_token(ticker) {
+            return Ok(Some(token));
+        }
+        if self.is_token_missing(ticker) {
+            return Ok(None);
+        }
+
+        match fetch().await? {
+            Some(token) => {
+                self.insert_token(token.clone());
+                Ok(Some(token))
+            }
+            None => {
+                self.mark_token_missing(ticker);
+                Ok(None)
+            }
+        }
+    }
+
     // ===== Markets =====
 
     /// Set markets in the cache
     pub fn set_markets(&self, markets: Vec<ApiMarket>) {
         let mut cache = self.markets.write().unwrap();
         cache.clear();
+        let mut missing = self.missing_markets.write().unwrap();
         for market in markets.iter() {
+            missing.remove(&market.id);
             cache.insert(market.id.clone(), market.clone());
         }
         self.logger
@@ -93,6 +175,76 @@ impl CacheService {
         self.markets.read().unwrap().contains_key(market_id)
     }
 
+    /// Add or replace a single market
+    pub fn insert_market(&self, market: ApiMarket) {
+        self.missing_markets.write().unwrap().remove(&market.id);
+        self.markets
+            .write()
+            .unwrap()
+            .insert(market.id.clone(), market);
+    }
+
+    /// Remember that the backend has no market with this ID
+    pub fn mark_market_missing(&self, market_id: &str) {
+        self.missing_markets
+            .write()
+            .unwrap()
+            .insert(market_id.to_string(), Instant::now());
+    }
+
+    /// Check if a market is negatively cached (counts as a negative hit)
+    pub fn is_market_missing(&self, market_id: &str) -> bool {
+        self.check_missing(&self.missing_markets, market_id)
+    }
+
+    /// Get a market, fetching it on a miss
+    /// `fetch` is skipped while the ID is negatively cached;
+    /// a fetch returning `None` negatively caches it
+    pub async fn get_market_or_fetch<F, Fut>(
+        &self,
+        market_id: &str,
+        fetch: F,
+    ) -> SdkResult<Option<ApiMarket>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = SdkResult<Option<ApiMarket>>>,
+    {
+        if let Some(market) = self.get_market(market_id) {
+            return Ok(Some(market));
+        }
+        if self.is_market_missing(market_id) {
+            return Ok(None);
+        }
+
+        match fetch().await? {
+            Some(market) => {
+                self.insert_market(market.clone());
+                Ok(Some(market))
+            }
+            None => {
+                self.mark_market_missing(market_id);
+                Ok(None)
+            }
+        }
+    }
+
+    // ===== Negative Cache =====
+
+    /// Check a negative cache entry, expiring it once older than the TTL
+    fn check_missing(&self, missing: &RwLock<HashMap<String, Instant>>, key: &str) -> bool {
+        let fresh = match missing.read().unwrap().get(key) {
+            Some(marked_at) => marked_at.elapsed() < self.negative_ttl,
+            None => return false,
+        };
+
+        if fresh {
+            self.negative_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            missing.write().unwrap().remove(key);
+        }
+        fresh
+    }
+
     // ===== Cache State =====
 
     /// Check if cache is ready (initialized and has data)
@@ -118,6 +270,9 @@ impl CacheService {
     pub fn clear(&self) {
         self.tokens.write().unwrap().clear();
         self.markets.write().unwrap().clear();
+        self.missing_tokens.write().unwrap().clear();
+        self.missing_markets.write().unwrap().clear();
+        self.negative_hits.store(0, Ordering::Relaxed);
         *self.initialized.write().unwrap() = false;
         self.logger.debug("Cache cleared");
     }
@@ -128,6 +283,7 @@ impl CacheService {
             markets: self.markets.read().unwrap().len(),
             tokens: self.tokens.read().unwrap().len(),
             initialized: *self.initialized.read().unwrap(),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
         }
     }
 }
@@ -155,6 +311,14 @@ mod tests {
             min_size: "1000000".to_string(),
             maker_fee_bps: 10,
             taker_fee_bps: 20,
+            min_price: None,
+            max_price: None,
+            max_open_orders_per_user: None,
+            max_open_notional_per_user: None,
+            min_notional: None,
+            last_price: None,
+            snap_to_tick: false,
+            status: crate::MarketStatus::Open,
         }
     }
 
@@ -237,4 +401,55 @@ mod tests {
         assert_eq!(stats.markets, 1);
         assert!(stats.initialized);
     }
+
+    #[tokio::test]
+    async fn test_negative_cache_skips_backend_for_unknown_market() {
+        let cache = CacheService::new(Arc::new(NoopLogger));
+        let backend_calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let market = cache
+                .get_market_or_fetch("DOGE/USDC", || async {
+                    backend_calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(None)
+                })
+                .await
+                .unwrap();
+            assert!(market.is_none());
+        }
+
+        assert_eq!(backend_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get_stats().negative_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_expires() {
+        let cache = CacheService::new(Arc::new(NoopLogger)).with_negative_ttl(Duration::ZERO);
+        let backend_calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let token = cache
+                .get_token_or_fetch("DOGE", || async {
+                    backend_calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(None)
+                })
+                .await
+                .unwrap();
+            assert!(token.is_none());
+        }
+
+        assert_eq!(backend_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.get_stats().negative_hits, 0);
+    }
+
+    #[test]
+    fn test_set_markets_clears_negative_entry() {
+        let cache = CacheService::new(Arc::new(NoopLogger));
+
+        cache.mark_market_missing("BTC/USDC");
+        cache.set_markets(vec![create_test_market("BTC/USDC", "BTC", "USDC")]);
+
+        assert!(!cache.is_market_missing("BTC/USDC"));
+        assert!(cache.has_market("BTC/USDC"));
+    }
 }
@@ -0,0 +1,276 @@
+//! Live candle series built from WebSocket events
+//!
+//! The server streams trades rather than candles, so `CandleWindow` buckets
+//! trades into candles the same way the ClickHouse views do. Seed it with
+//! `ExchangeClient::get_candles` for history, then feed it live messages.
+
+use backend::models::api::{ApiCandle, ServerMessage};
+use backend::models::domain::CandleInterval;
+use std::collections::VecDeque;
+
+/// Candles kept by `WebSocketHandle::subscribe_candles`
+pub const DEFAULT_CANDLE_WINDOW: usize = 500;
+
+/// Rolling window of the most recent candles for one market and interval
+///
+/// Holds up to `capacity` candles: the closed ones followed by the live
+/// (in-progress) one. A trade or candle for a later bucket closes the live
+/// candle and opens a new one; events for buckets before the live one are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct CandleWindow {
+    market_id: String,
+    interval: CandleInterval,
+    capacity: usize,
+    closed: VecDeque<ApiCandle>,
+    live: Option<ApiCandle>,
+}
+
+impl CandleWindow {
+    pub fn new(market_id: impl Into<String>, interval: CandleInterval, capacity: usize) -> Self {
+        Self {
+            market_id: market_id.into(),
+            interval,
+            capacity: capacity.max(1),
+            closed: VecDeque::new(),
+            live: None,
+        }
+    }
+
+    pub fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    pub fn interval(&self) -> CandleInterval {
+        self.interval
+    }
+
+    /// Seed the window with historical candles, oldest first
+    pub fn extend_history(&mut self, candles: impl IntoIterator<Item = ApiCandle>) {
+        for candle in candles {
+            self.apply_candle(candle);
+        }
+    }
+
+    /// Update the window from a server message
+    /// Returns whether the window changed; messages for other markets are ignored
+    pub fn apply_message(&mut self, message: &ServerMessage) -> bool {
+        match message {
+            ServerMessage::Trade { trade } if trade.market_id == self.market_id => {
+                match (trade.price.parse(), trade.size.parse()) {
+                    (Ok(price), Ok(size)) => self.apply_trade(price, size, trade.timestamp),
+                    _ => false,
+                }
+            }
+            ServerMessage::Candle {
+                market_id,
+                timestamp,
+                open,
+                high,
+                low,
+                close,
+                volume,
+            } if *market_id == self.market_id => {
+                let candle = (|| {
+                    Some(ApiCandle {
+                        timestamp: u32::try_from(self.interval.bucket_start(*timestamp)).ok()?,
+                        open: open.parse().ok()?,
+                        high: high.parse().ok()?,
+                        low: low.parse().ok()?,
+                        close: close.parse().ok()?,
+                        volume: volume.parse().ok()?,
+                    })
+                })();
+                candle.is_some_and(|candle| self.apply_candle(candle))
+            }
+            _ => false,
+        }
+    }
+
+    /// Fold a trade into the candle for its bucket (timestamp in Unix seconds)
+    pub fn apply_trade(&mut self, price: u128, size: u128, timestamp: i64) -> bool {
+        let Ok(bucket) = u32::try_from(self.interval.bucket_start(timestamp)) else {
+            return false;
+        };
+
+        match &mut self.live {
+            Some(live) if live.timestamp == bucket => {
+                live.high = live.high.max(price);
+                live.low = live.low.min(price);
+                live.close = price;
+                live.volume = live.volume.saturating_add(size);
+                true
+            }
+            Some(live) if live.timestamp > bucket => false,
+            _ => {
+                self.open(ApiCandle {
+                    timestamp: bucket,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: size,
+                });
+                true
+            }
+        }
+    }
+
+    /// Replace the live candle with a complete one for the same bucket, or open it
+    pub fn apply_candle(&mut self, candle: ApiCandle) -> bool {
+        match &mut self.live {
+            Some(live) if live.timestamp == candle.timestamp => *live = candle,
+            Some(live) if live.timestamp > candle.timestamp => return false,
+            _ => self.open(candle),
+        }
+        true
+    }
+
+    /// Close the live candle, if any, and start `candle`
+    fn open(&mut self, candle: ApiCandle) {
+        if let Some(live) = self.live.replace(candle) {
+            self.closed.push_back(live);
+        }
+        while self.closed.len() >= self.capacity {
+            self.closed.pop_front();
+        }
+    }
+
+    /// Candles that will no longer change, oldest first
+    pub fn closed(&self) -> impl Iterator<Item = &ApiCandle> {
+        self.closed.iter()
+    }
+
+    /// The in-progress candle
+    pub fn live(&self) -> Option<&ApiCandle> {
+        self.live.as_ref()
+    }
+
+    /// The full series to render: closed candles then the live one
+    pub fn candles(&self) -> impl Iterator<Item = &ApiCandle> {
+        self.closed.iter().chain(self.live.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.closed.len() + usize::from(self.live.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::models::api::TradeData;
+    use backend::models::domain::Side;
+
+    fn trade_message(market_id: &str, price: u128, size: u128, timestamp: i64) -> ServerMessage {
+        ServerMessage::Trade {
+            trade: TradeData {
+                id: format!("trade-{}", timestamp),
+                market_id: market_id.to_string(),
+                buyer_address: "buyer".to_string(),
+                seller_address: "seller".to_string(),
+                buyer_order_id: "buy-order".to_string(),
+                seller_order_id: "sell-order".to_string(),
+                price: price.to_string(),
+                size: size.to_string(),
+                side: Side::Buy,
+                timestamp,
+                seq: 0,
+            },
+        }
+    }
+
+    fn ohlcv(candle: &ApiCandle) -> (u32, u128, u128, u128, u128, u128) {
+        (
+            candle.timestamp,
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.volume,
+        )
+    }
+
+    #[test]
+    fn test_window_rolls_closed_candles_and_updates_live_one() {
+        let mut window = CandleWindow::new("BTC/USDC", CandleInterval::OneMinute, 3);
+
+        // Minute 0: three trades
+        assert!(window.apply_message(&trade_message("BTC/USDC", 100, 1, 0)));
+        assert!(window.apply_message(&trade_message("BTC/USDC", 120, 2, 10)));
+        assert!(window.apply_message(&trade_message("BTC/USDC", 90, 3, 59)));
+        assert_eq!(window.closed().count(), 0);
+        assert_eq!(ohlcv(window.live().unwrap()), (0, 100, 120, 90, 90, 6));
+
+        // Other markets don't touch the window
+        assert!(!window.apply_message(&trade_message("ETH/USDC", 1, 1, 61)));
+
+        // Minute 1 closes minute 0
+        assert!(window.apply_message(&trade_message("BTC/USDC", 95, 1, 60)));
+        // A late trade for a closed candle is dropped
+        assert!(!window.apply_message(&trade_message("BTC/USDC", 500, 1, 30)));
+        // Minute 2, then minute 4 leaves a gap like the stored candles do
+        assert!(window.apply_message(&trade_message("BTC/USDC", 97, 1, 150)));
+        assert!(window.apply_message(&trade_message("BTC/USDC", 99, 4, 250)));
+        assert!(window.apply_message(&trade_message("BTC/USDC", 101, 1, 255)));
+
+        // Capacity 3: two closed candles plus the live one, the oldest fell off
+        let closed: Vec<_> = window.closed().map(ohlcv).collect();
+        assert_eq!(
+            closed,
+            vec![(60, 95, 95, 95, 95, 1), (120, 97, 97, 97, 97, 1)]
+        );
+        assert_eq!(ohlcv(window.live().unwrap()), (240, 99, 101, 99, 101, 5));
+        assert_eq!(window.candles().count(), 3);
+        assert_eq!(window.len(), 3);
+    }
+
+    #[test]
+    fn test_window_accepts_history_and_candle_messages() {
+        let mut window = CandleWindow::new("BTC/USDC", CandleInterval::FiveMinutes, 10);
+        window.extend_history([
+            ApiCandle {
+                timestamp: 0,
+                open: 10,
+                high: 12,
+                low: 9,
+                close: 11,
+                volume: 7,
+            },
+            ApiCandle {
+                timestamp: 300,
+                open: 11,
+                high: 11,
+                low: 11,
+                close: 11,
+                volume: 1,
+            },
+        ]);
+        assert_eq!(window.closed().count(), 1);
+
+        // A trade extends the last historical candle, which is still live
+        assert!(window.apply_trade(13, 2, 400));
+        assert_eq!(ohlcv(window.live().unwrap()), (300, 11, 13, 11, 13, 3));
+
+        // A complete candle for the live bucket replaces it, a later one opens the next
+        let candle = |timestamp: i64, close: u128| ServerMessage::Candle {
+            market_id: "BTC/USDC".to_string(),
+            timestamp,
+            open: "11".to_string(),
+            high: "14".to_string(),
+            low: "10".to_string(),
+            close: close.to_string(),
+            volume: "5".to_string(),
+        };
+        assert!(window.apply_message(&candle(300, 12)));
+        assert_eq!(ohlcv(window.live().unwrap()), (300, 11, 14, 10, 12, 5));
+        assert!(window.apply_message(&candle(600, 14)));
+
+        let closes: Vec<_> = window.candles().map(|c| (c.timestamp, c.close)).collect();
+        assert_eq!(closes, vec![(0, 11), (300, 12), (600, 14)]);
+    }
+}
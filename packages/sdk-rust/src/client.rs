@@ -1,15 +1,183 @@
-use crate::error::{SdkError, SdkResult};
+use crate::error::{parse_u128_field, SdkError, SdkResult};
+use crate::format::{format_price_with, format_size_with, FormatOptions};
+use crate::validation::{self, ValidationIssue};
+use crate::websocket::{SubscribeTarget, WebSocketClient, WebSocketHandle};
+use backend::auth::{same_address, Wallet};
 use backend::models::{api::*, domain::*};
 use reqwest::Client;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often `wait_for_order` re-checks an order over REST
+const ORDER_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Trades fetched per request by `export_trades_csv`, the server's maximum
+const EXPORT_PAGE_SIZE: u32 = 1000;
+
+/// Next message from an optional WebSocket, pending forever when there is none
+async fn next_update(updates: &mut Option<WebSocketHandle>) -> Option<serde_json::Value> {
+    match updates {
+        Some(handle) => handle.recv().await,
+        None => std::future::pending().await,
+    }
+}
 
 /// REST API client for the exchange
 #[derive(Clone)]
 pub struct ExchangeClient {
     base_url: String,
     client: Client,
+    wallet: Option<Wallet>,
+}
+
+/// Builder for an `ExchangeClient` with custom HTTP settings
+#[derive(Default)]
+pub struct ExchangeClientBuilder {
+    base_url: Option<String>,
+    http_client: Option<Client>,
+}
+
+impl ExchangeClientBuilder {
+    /// Base URL of the exchange API (required)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Use a preconfigured HTTP client for all requests (proxies, root certs, pooling, headers)
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Build the client, failing if no base URL was set
+    pub fn build(self) -> SdkResult<ExchangeClient> {
+        let base_url = self
+            .base_url
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| SdkError::Configuration("base URL is required".to_string()))?;
+
+        Ok(ExchangeClient {
+            base_url,
+            client: self.http_client.unwrap_or_default(),
+            wallet: None,
+        })
+    }
+}
+
+/// Hands out `ExchangeClient`s for one backend that share a connection pool
+///
+/// Every client made by `client()` reuses the same `reqwest::Client`, so many
+/// bots talking to one exchange keep a single set of keep-alive connections
+/// instead of opening a pool each. Both types are `Send + Sync` and cheap to
+/// clone (the HTTP client is reference counted), so clients can be moved into
+/// separate tasks or threads and used concurrently.
+#[derive(Clone)]
+pub struct SharedExchange {
+    base_url: String,
+    http_client: Client,
+}
+
+impl SharedExchange {
+    /// Share a default HTTP client among all clients for `base_url`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, Client::new())
+    }
+
+    /// Share a preconfigured HTTP client (timeouts, pool limits, headers) among all clients
+    pub fn with_http_client(base_url: impl Into<String>, http_client: Client) -> Self {
+        Self {
+            base_url: base_url.into(),
+            http_client,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// A client using the shared connection pool
+    pub fn client(&self) -> ExchangeClient {
+        ExchangeClient {
+            base_url: self.base_url.clone(),
+            client: self.http_client.clone(),
+            wallet: None,
+        }
+    }
+}
+
+/// Market definition for `ExchangeClient::admin_create_market_with_spec`
+///
+/// Only the tickers are required. Everything else defaults to the most
+/// permissive settings (1-atom tick, lot and minimum size, no fees, no price
+/// band), which suits test setup for tokens of any decimals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminCreateMarketSpec {
+    pub base_ticker: String,
+    pub quote_ticker: String,
+    pub tick_size: u128,
+    pub lot_size: u128,
+    pub min_size: u128,
+    pub maker_fee_bps: i32,
+    pub taker_fee_bps: i32,
+    pub min_price: Option<u128>,
+    pub max_price: Option<u128>,
+}
+
+impl AdminCreateMarketSpec {
+    pub const DEFAULT_TICK_SIZE: u128 = 1;
+    pub const DEFAULT_LOT_SIZE: u128 = 1;
+    pub const DEFAULT_MIN_SIZE: u128 = 1;
+    pub const DEFAULT_FEE_BPS: i32 = 0;
+
+    pub fn new(base_ticker: impl Into<String>, quote_ticker: impl Into<String>) -> Self {
+        Self {
+            base_ticker: base_ticker.into(),
+            quote_ticker: quote_ticker.into(),
+            tick_size: Self::DEFAULT_TICK_SIZE,
+            lot_size: Self::DEFAULT_LOT_SIZE,
+            min_size: Self::DEFAULT_MIN_SIZE,
+            maker_fee_bps: Self::DEFAULT_FEE_BPS,
+            taker_fee_bps: Self::DEFAULT_FEE_BPS,
+            min_price: None,
+            max_price: None,
+        }
+    }
+
+    /// Minimum price increment in quote atoms
+    pub fn tick_size(mut self, tick_size: u128) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    /// Minimum size increment in base atoms
+    pub fn lot_size(mut self, lot_size: u128) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+
+    /// Minimum order size in base atoms
+    pub fn min_size(mut self, min_size: u128) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Maker and taker fees in basis points (a negative maker fee is a rebate)
+    pub fn fees(mut self, maker_fee_bps: i32, taker_fee_bps: i32) -> Self {
+        self.maker_fee_bps = maker_fee_bps;
+        self.taker_fee_bps = taker_fee_bps;
+        self
+    }
+
+    /// Accepted limit price range in quote atoms, either side may be open
+    pub fn price_band(mut self, min_price: Option<u128>, max_price: Option<u128>) -> Self {
+        self.min_price = min_price;
+        self.max_price = max_price;
+        self
+    }
 }
 
 impl ExchangeClient {
@@ -18,11 +186,43 @@ impl ExchangeClient {
         Self {
             base_url: base_url.into(),
             client: Client::new(),
+            wallet: None,
+        }
+    }
+
+    /// Sign in to private WebSocket channels with `wallet`, e.g. for `wait_for_order`
+    pub fn with_wallet(mut self, wallet: Wallet) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    /// Start building a client with custom settings
+    pub fn builder() -> ExchangeClientBuilder {
+        ExchangeClientBuilder::default()
+    }
+
+    /// Health check with per-subsystem status
+    /// A degraded server (503) still returns Ok so callers can see which subsystem is down
+    pub async fn health(&self) -> SdkResult<HealthStatus> {
+        let url = format!("{}/api/health", self.base_url);
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+
+        if status.is_success() || status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            let body = response.text().await?;
+            serde_json::from_str(&body).map_err(|e| {
+                SdkError::InvalidResponse(format!("Failed to parse health status: {}", e))
+            })
+        } else {
+            Err(SdkError::ApiError {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            })
         }
     }
 
-    /// Health check
-    pub async fn health(&self) -> SdkResult<String> {
+    /// Health check returning the raw response body
+    pub async fn health_raw(&self) -> SdkResult<String> {
         let url = format!("{}/api/health", self.base_url);
         let response = self.client.get(&url).send().await?;
 
@@ -70,6 +270,29 @@ impl ExchangeClient {
         }
     }
 
+    /// Get a market together with its base and quote tokens in one request
+    pub async fn get_market_full(&self, market_id: &str) -> SdkResult<crate::MarketFull> {
+        let request = InfoRequest::MarketFull {
+            market_id: market_id.to_string(),
+        };
+        let response = self.post_info(request).await?;
+
+        match response {
+            InfoResponse::MarketFull {
+                market,
+                base_token,
+                quote_token,
+            } => Ok(crate::MarketFull {
+                market: market.try_into().map_err(|e| {
+                    SdkError::InvalidResponse(format!("Failed to parse market: {}", e))
+                })?,
+                base_token,
+                quote_token,
+            }),
+            _ => Err(SdkError::InvalidResponse("Expected MarketFull".to_string())),
+        }
+    }
+
     /// Get all markets
     pub async fn get_markets(&self) -> SdkResult<Vec<Market>> {
         let request = InfoRequest::AllMarkets;
@@ -85,6 +308,33 @@ impl ExchangeClient {
         }
     }
 
+    /// Get just the markets with the given ids in one request
+    /// Ids that don't exist are left out rather than failing the request
+    pub async fn get_markets_by_ids(&self, market_ids: &[&str]) -> SdkResult<Vec<Market>> {
+        let request = InfoRequest::Markets {
+            market_ids: market_ids.iter().map(|id| id.to_string()).collect(),
+        };
+        let response = self.post_info(request).await?;
+
+        match response {
+            InfoResponse::Markets { markets } => markets
+                .into_iter()
+                .map(|m| m.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse markets: {}", e))),
+            _ => Err(SdkError::InvalidResponse("Expected Markets".to_string())),
+        }
+    }
+
+    /// Search markets by quote ticker and base ticker substring, sorted and paged
+    /// Leave fields of `MarketSearchRequest::default()` unset to skip that filter
+    pub async fn search_markets(
+        &self,
+        request: MarketSearchRequest,
+    ) -> SdkResult<MarketSearchResponse> {
+        self.post_market_search(request).await
+    }
+
     /// Get all tokens
     pub async fn get_tokens(&self) -> SdkResult<Vec<Token>> {
         let request = InfoRequest::AllTokens;
@@ -96,6 +346,20 @@ impl ExchangeClient {
         }
     }
 
+    /// Get just the tokens with the given tickers in one request
+    /// Tickers that don't exist are left out rather than failing the request
+    pub async fn get_tokens_by_tickers(&self, tickers: &[&str]) -> SdkResult<Vec<Token>> {
+        let request = InfoRequest::Tokens {
+            tickers: tickers.iter().map(|t| t.to_string()).collect(),
+        };
+        let response = self.post_info(request).await?;
+
+        match response {
+            InfoResponse::Tokens { tokens } => Ok(tokens),
+            _ => Err(SdkError::InvalidResponse("Expected Tokens".to_string())),
+        }
+    }
+
     // ===== User Endpoints =====
 
     /// Get user orders
@@ -122,6 +386,22 @@ impl ExchangeClient {
         }
     }
 
+    /// Get a single order owned by the user
+    pub async fn get_order(&self, user_address: &str, order_id: &str) -> SdkResult<Order> {
+        let request = UserRequest::Order {
+            user_address: user_address.to_string(),
+            order_id: order_id.to_string(),
+        };
+        let response = self.post_user(request).await?;
+
+        match response {
+            UserResponse::Order { order } => order
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse order: {}", e))),
+            _ => Err(SdkError::InvalidResponse("Expected Order".to_string())),
+        }
+    }
+
     /// Get user balances
     pub async fn get_balances(&self, user_address: &str) -> SdkResult<Vec<Balance>> {
         let request = UserRequest::Balances {
@@ -162,6 +442,56 @@ impl ExchangeClient {
         }
     }
 
+    /// Get a user's net position and average entry price in each market they have traded
+    pub async fn get_positions(&self, user_address: &str) -> SdkResult<Vec<Position>> {
+        let request = UserRequest::Positions {
+            user_address: user_address.to_string(),
+        };
+        let response = self.post_user(request).await?;
+
+        match response {
+            UserResponse::Positions { positions } => positions
+                .into_iter()
+                .map(|p| p.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    SdkError::InvalidResponse(format!("Failed to parse positions: {}", e))
+                }),
+            _ => Err(SdkError::InvalidResponse("Expected Positions".to_string())),
+        }
+    }
+
+    /// Get a user's finished (filled or cancelled) orders, most recently finished first
+    /// `from` (inclusive) and `to` (exclusive) bound `updated_at` in Unix seconds
+    pub async fn get_order_history(
+        &self,
+        user_address: &str,
+        market_id: Option<String>,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: Option<u32>,
+    ) -> SdkResult<Vec<Order>> {
+        let request = UserRequest::OrderHistory {
+            user_address: user_address.to_string(),
+            market_id,
+            from,
+            to,
+            limit,
+        };
+        let response = self.post_user(request).await?;
+
+        match response {
+            UserResponse::OrderHistory { orders } => orders
+                .into_iter()
+                .map(|o| o.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse orders: {}", e))),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected OrderHistory".to_string(),
+            )),
+        }
+    }
+
     // ===== Trade Endpoints =====
 
     /// Round a size to the nearest multiple of lot_size (rounds down)
@@ -185,6 +515,18 @@ impl ExchangeClient {
         Ok(rounded.to_string())
     }
 
+    /// Check an order against a cached market config without a round-trip
+    /// Runs the backend's tick, lot, price band, min size and min notional checks
+    /// and returns every one that fails. Balances and per-user limits aren't checked
+    pub fn validate_order_local(
+        market: &crate::MarketFull,
+        order_type: OrderType,
+        price: u128,
+        size: u128,
+    ) -> Result<(), Vec<ValidationIssue>> {
+        validation::validate_order(market, order_type, price, size)
+    }
+
     /// Place an order
     #[allow(clippy::too_many_arguments)]
     pub async fn place_order(
@@ -196,35 +538,187 @@ impl ExchangeClient {
         price: String,
         size: String,
         signature: String,
+    ) -> SdkResult<crate::OrderPlaced> {
+        self.submit_order(
+            user_address,
+            market_id,
+            side,
+            order_type,
+            None,
+            price,
+            size,
+            signature,
+            None,
+        )
+        .await
+    }
+
+    /// Place an order with an explicit time in force
+    /// Use `TimeInForce::Ioc` to cancel any unfilled remainder of a limit order.
+    /// Market orders only accept `TimeInForce::Ioc`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order_with_time_in_force(
+        &self,
+        user_address: String,
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        time_in_force: TimeInForce,
+        price: String,
+        size: String,
+        signature: String,
+    ) -> SdkResult<crate::OrderPlaced> {
+        self.submit_order(
+            user_address,
+            market_id,
+            side,
+            order_type,
+            Some(time_in_force),
+            price,
+            size,
+            signature,
+            None,
+        )
+        .await
+    }
+
+    /// Place an order under a client-chosen idempotency key
+    /// Retrying with the same `client_order_id` returns the order placed by the first
+    /// attempt instead of placing another, so a timed-out request is safe to resend
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order_with_client_order_id(
+        &self,
+        user_address: String,
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        price: String,
+        size: String,
+        client_order_id: String,
+        signature: String,
+    ) -> SdkResult<crate::OrderPlaced> {
+        self.submit_order(
+            user_address,
+            market_id,
+            side,
+            order_type,
+            None,
+            price,
+            size,
+            signature,
+            Some(client_order_id),
+        )
+        .await
+    }
+
+    /// Send a place order request, leaving `time_in_force` to the server default when unset
+    #[allow(clippy::too_many_arguments)]
+    async fn submit_order(
+        &self,
+        user_address: String,
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        time_in_force: Option<TimeInForce>,
+        price: String,
+        size: String,
+        signature: String,
+        client_order_id: Option<String>,
     ) -> SdkResult<crate::OrderPlaced> {
         let request = TradeRequest::PlaceOrder {
             user_address,
             market_id,
             side,
             order_type,
+            time_in_force,
             price,
             size,
             signature,
+            client_order_id,
+            min_fill: None,
         };
         let response = self.post_trade(request).await?;
 
         match response {
-            TradeResponse::PlaceOrder { order, trades } => Ok(crate::OrderPlaced {
-                order: order.try_into().map_err(|e| {
-                    SdkError::InvalidResponse(format!("Failed to parse order: {}", e))
-                })?,
-                trades: trades
-                    .into_iter()
-                    .map(|t| t.try_into())
-                    .collect::<Result<Vec<_>, _>>()
-                    .map_err(|e| {
-                        SdkError::InvalidResponse(format!("Failed to parse trades: {}", e))
-                    })?,
-            }),
+            TradeResponse::PlaceOrder {
+                order,
+                trades,
+                total_quote,
+                requested_price,
+                ..
+            } => Self::order_placed(order, trades, total_quote, requested_price),
             _ => Err(SdkError::InvalidResponse("Expected PlaceOrder".to_string())),
         }
     }
 
+    /// Cancel a resting order and place its replacement atomically
+    /// Only the difference in locked balance between the two orders is locked or released
+    #[allow(clippy::too_many_arguments)]
+    pub async fn replace_order(
+        &self,
+        user_address: String,
+        order_id: String,
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        price: String,
+        size: String,
+        signature: String,
+    ) -> SdkResult<crate::OrderReplaced> {
+        let request = TradeRequest::ReplaceOrder {
+            user_address,
+            order_id,
+            market_id,
+            side,
+            order_type,
+            time_in_force: None,
+            price,
+            size,
+            signature,
+        };
+        let response = self.post_trade(request).await?;
+
+        match response {
+            TradeResponse::ReplaceOrder {
+                cancelled_order_id,
+                order,
+                trades,
+                total_quote,
+                requested_price,
+                ..
+            } => Ok(crate::OrderReplaced {
+                cancelled_order_id,
+                placed: Self::order_placed(order, trades, total_quote, requested_price)?,
+            }),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected ReplaceOrder".to_string(),
+            )),
+        }
+    }
+
+    /// Convert the API fields of a placed order into the SDK's domain types
+    fn order_placed(
+        order: ApiOrder,
+        trades: Vec<ApiTrade>,
+        total_quote: String,
+        requested_price: Option<String>,
+    ) -> SdkResult<crate::OrderPlaced> {
+        Ok(crate::OrderPlaced {
+            order: order
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse order: {}", e)))?,
+            trades: trades
+                .into_iter()
+                .map(|t| t.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse trades: {}", e)))?,
+            total_quote: parse_u128_field(&total_quote, "total_quote")?,
+            requested_price: requested_price
+                .map(|p| parse_u128_field(&p, "requested_price"))
+                .transpose()?,
+        })
+    }
+
     /// Place an order with automatic size rounding to lot_size
     #[allow(clippy::too_many_arguments)]
     pub async fn place_order_with_rounding(
@@ -329,6 +823,73 @@ impl ExchangeClient {
         .await
     }
 
+    /// Place an order with price and size in display units (e.g. "95000.00" USDC, "0.01" BTC)
+    /// The price is rounded to the tick without crossing further (buys down, sells up) and
+    /// the size down to the lot. An order the market would still reject is not sent
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order_display(
+        &self,
+        user_address: &str,
+        market_id: &str,
+        side: Side,
+        order_type: OrderType,
+        price_display: &str,
+        size_display: &str,
+        signature: &str,
+    ) -> SdkResult<crate::OrderPlaced> {
+        let market = self.get_market_full(market_id).await?;
+        let (price, size) =
+            Self::display_order_atoms(&market, side, order_type, price_display, size_display)?;
+
+        self.place_order(
+            user_address.to_string(),
+            market_id.to_string(),
+            side,
+            order_type,
+            price.to_string(),
+            size.to_string(),
+            signature.to_string(),
+        )
+        .await
+    }
+
+    /// Convert a display price and size to atoms on the market's tick and lot grid
+    fn display_order_atoms(
+        market: &crate::MarketFull,
+        side: Side,
+        order_type: OrderType,
+        price_display: &str,
+        size_display: &str,
+    ) -> SdkResult<(u128, u128)> {
+        let to_atoms = |value: &str, decimals: u8, round_up: bool| {
+            let atoms = Decimal::from_str(value)
+                .ok()
+                .and_then(|v| v.checked_mul(Decimal::from(10u128.pow(decimals as u32))))
+                .map(|v| if round_up { v.ceil() } else { v.floor() });
+            atoms.and_then(|v| v.to_u128())
+        };
+
+        let round_up = side == Side::Sell;
+        let price = to_atoms(price_display, market.quote_token.decimals, round_up)
+            .ok_or_else(|| SdkError::InvalidOrder(format!("Invalid price: {}", price_display)))?;
+        let size = to_atoms(size_display, market.base_token.decimals, false)
+            .ok_or_else(|| SdkError::InvalidOrder(format!("Invalid size: {}", size_display)))?;
+
+        let tick_size = market.market.tick_size;
+        let price = if round_up {
+            price.div_ceil(tick_size) * tick_size
+        } else {
+            price - price % tick_size
+        };
+        let size = Self::round_size_to_lot(size, market.market.lot_size);
+
+        validation::validate_order(market, order_type, price, size).map_err(|issues| {
+            let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+            SdkError::InvalidOrder(issues.join("; "))
+        })?;
+        Ok((price, size))
+    }
+
     /// Cancel an order
     pub async fn cancel_order(
         &self,
@@ -369,9 +930,11 @@ impl ExchangeClient {
             TradeResponse::CancelAllOrders {
                 cancelled_order_ids,
                 count,
+                refunds,
             } => Ok(OrdersCancelled {
                 cancelled_order_ids,
                 count,
+                refunds,
             }),
             _ => Err(SdkError::InvalidResponse(
                 "Expected CancelAllOrders".to_string(),
@@ -379,6 +942,153 @@ impl ExchangeClient {
         }
     }
 
+    /// Wait until an order is filled or cancelled and return its final state
+    /// Listens for `UserOrders` updates over WebSocket when the client holds the user's
+    /// wallet, and re-checks over REST on an interval, so a missed update or an
+    /// unavailable WebSocket only adds latency.
+    /// Returns `SdkError::Timeout` if the order is still open when `timeout` elapses
+    pub async fn wait_for_order(
+        &self,
+        user_address: &str,
+        order_id: &str,
+        timeout: Duration,
+    ) -> SdkResult<Order> {
+        let deadline = Instant::now() + timeout;
+
+        // Subscribe before the first check so no transition falls in between
+        let mut updates = match &self.wallet {
+            Some(wallet) if same_address(&wallet.address(), user_address) => {
+                self.user_orders_stream(wallet, user_address).await.ok()
+            }
+            _ => None,
+        };
+
+        // First tick fires immediately, covering orders that are already terminal
+        let mut poll = tokio::time::interval(ORDER_POLL_INTERVAL);
+
+        loop {
+            let check = tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => return Err(SdkError::Timeout),
+                _ = poll.tick() => true,
+                update = next_update(&mut updates) => match update {
+                    Some(msg) => {
+                        msg["type"] == "user_order"
+                            && msg["order_id"] == order_id
+                            && (msg["status"] == "filled" || msg["status"] == "cancelled")
+                    }
+                    // Stream closed, carry on polling
+                    None => {
+                        updates = None;
+                        false
+                    }
+                },
+            };
+
+            if check {
+                let order = self.get_order(user_address, order_id).await?;
+                if matches!(order.status, OrderStatus::Filled | OrderStatus::Cancelled) {
+                    return Ok(order);
+                }
+            }
+        }
+    }
+
+    /// Signed-in WebSocket subscribed to a user's order updates
+    async fn user_orders_stream(
+        &self,
+        wallet: &Wallet,
+        user_address: &str,
+    ) -> SdkResult<WebSocketHandle> {
+        let mut handle = WebSocketClient::new(self.ws_url()).connect().await?;
+        handle.sign_in(wallet).await?;
+        handle.subscribe(SubscribeTarget::user_orders(user_address))?;
+        Ok(handle)
+    }
+
+    /// Place a limit order and cancel whatever is still open once `fill_deadline` elapses
+    /// Take liquidity or give up: the order is watched like `wait_for_order`, and if it
+    /// fills just as the deadline hits the cancel is rejected and the filled state returned
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order_with_deadline(
+        &self,
+        user_address: String,
+        market_id: String,
+        side: Side,
+        price: String,
+        size: String,
+        signature: String,
+        fill_deadline: Duration,
+    ) -> SdkResult<crate::OrderDeadlineResult> {
+        let placed = self
+            .place_order(
+                user_address.clone(),
+                market_id,
+                side,
+                OrderType::Limit,
+                price,
+                size,
+                signature.clone(),
+            )
+            .await?;
+        let order_id = placed.order.id.to_string();
+
+        let (order, auto_cancelled) = match self
+            .wait_for_order(&user_address, &order_id, fill_deadline)
+            .await
+        {
+            Ok(order) => (order, false),
+            Err(SdkError::Timeout) => {
+                let cancelled = self
+                    .cancel_order(user_address.clone(), order_id.clone(), signature)
+                    .await;
+                let order = self.get_order(&user_address, &order_id).await?;
+                match cancelled {
+                    Ok(_) => (order, true),
+                    // Lost the race to a fill, the order is already terminal
+                    Err(_)
+                        if matches!(order.status, OrderStatus::Filled | OrderStatus::Cancelled) =>
+                    {
+                        (order, false)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(crate::OrderDeadlineResult {
+            placed,
+            order,
+            auto_cancelled,
+        })
+    }
+
+    // ===== Estimate Endpoint =====
+
+    /// Estimate how an order would fill against the current book without placing it
+    /// Pass `user_address` to skip your own resting orders, as a real placement would
+    pub async fn estimate_order(
+        &self,
+        user_address: Option<String>,
+        market_id: String,
+        side: Side,
+        order_type: OrderType,
+        price: String,
+        size: String,
+    ) -> SdkResult<EstimateResponse> {
+        let request = EstimateRequest {
+            user_address,
+            market_id,
+            side,
+            order_type,
+            time_in_force: None,
+            price,
+            size,
+            min_fill: None,
+        };
+        self.post_estimate(request).await
+    }
+
     // ===== Drip/Faucet Endpoint =====
 
     /// Request testnet tokens from faucet
@@ -431,6 +1141,132 @@ impl ExchangeClient {
         Ok(response.candles)
     }
 
+    // ===== Trade Tape Endpoints =====
+
+    /// Get a market's public trades, most recent first
+    /// Optionally only trades whose taker was on `side` and of at least `min_size` base atoms
+    pub async fn get_market_trades(
+        &self,
+        market_id: &str,
+        side: Option<Side>,
+        min_size: Option<u128>,
+        limit: Option<u32>,
+    ) -> SdkResult<Vec<Trade>> {
+        let request = TradesRequest {
+            market_id: market_id.to_string(),
+            side,
+            min_size: min_size.map(|size| size.to_string()),
+            limit,
+            from: None,
+            to: None,
+            cursor: None,
+        };
+        let response = self.post_trades(request).await?;
+
+        response
+            .trades
+            .into_iter()
+            .map(|t| t.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse trades: {}", e)))
+    }
+
+    /// Get one page of a market's trades in [from, to), most recent first
+    /// Returns the trades and the cursor for the next (older) page, None on the last page
+    pub async fn get_market_trades_page(
+        &self,
+        market_id: &str,
+        from: Option<i64>,
+        to: Option<i64>,
+        cursor: Option<String>,
+        limit: Option<u32>,
+    ) -> SdkResult<(Vec<Trade>, Option<String>)> {
+        let request = TradesRequest {
+            market_id: market_id.to_string(),
+            side: None,
+            min_size: None,
+            limit,
+            from,
+            to,
+            cursor,
+        };
+        let response = self.post_trades(request).await?;
+
+        let trades = response
+            .trades
+            .into_iter()
+            .map(|t| t.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse trades: {}", e)))?;
+        Ok((trades, response.next_cursor))
+    }
+
+    /// Write a market's trades in [from, to) to `writer` as CSV, most recent first
+    ///
+    /// Columns are `timestamp,price_display,size_display,side`, with the timestamp
+    /// in RFC 3339 and prices and sizes in display units without thousands
+    /// separators. Trades are fetched and written a page at a time, so large
+    /// ranges never sit in memory at once. Returns the number of rows written.
+    pub async fn export_trades_csv<W: std::io::Write>(
+        &self,
+        market_id: &str,
+        from: i64,
+        to: i64,
+        mut writer: W,
+    ) -> SdkResult<usize> {
+        let market = self.get_market_full(market_id).await?;
+        let options = FormatOptions {
+            thousands_separator: String::new(),
+            ..FormatOptions::us()
+        };
+
+        writeln!(writer, "timestamp,price_display,size_display,side")?;
+        let mut rows = 0;
+        let mut cursor = None;
+        loop {
+            let (trades, next_cursor) = self
+                .get_market_trades_page(
+                    market_id,
+                    Some(from),
+                    Some(to),
+                    cursor,
+                    Some(EXPORT_PAGE_SIZE),
+                )
+                .await?;
+
+            for trade in &trades {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    trade.timestamp.to_rfc3339(),
+                    format_price_with(&options, trade.price, market.quote_token.decimals),
+                    format_size_with(&options, trade.size, market.base_token.decimals),
+                    trade.side
+                )?;
+            }
+            rows += trades.len();
+
+            match next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        writer.flush()?;
+        Ok(rows)
+    }
+
+    /// Get the volume-weighted average price and traded volume over [from, to)
+    /// `vwap` is None when no trades happened in the window
+    pub async fn get_vwap(&self, market_id: &str, from: i64, to: i64) -> SdkResult<VwapResponse> {
+        let request = VwapRequest {
+            market_id: market_id.to_string(),
+            from,
+            to,
+        };
+        self.post_vwap(request).await
+    }
+
     // ===== Admin Endpoints (Test/Dev Only) =====
 
     /// Create a token (admin)
@@ -466,15 +1302,32 @@ impl ExchangeClient {
         min_size: u128,
         maker_fee_bps: i32,
         taker_fee_bps: i32,
+    ) -> SdkResult<Market> {
+        self.admin_create_market_with_spec(
+            AdminCreateMarketSpec::new(base_ticker, quote_ticker)
+                .tick_size(tick_size)
+                .lot_size(lot_size)
+                .min_size(min_size)
+                .fees(maker_fee_bps, taker_fee_bps),
+        )
+        .await
+    }
+
+    /// Create a market from a spec, unset fields take the spec defaults (admin)
+    pub async fn admin_create_market_with_spec(
+        &self,
+        spec: AdminCreateMarketSpec,
     ) -> SdkResult<Market> {
         let request = backend::models::api::AdminRequest::CreateMarket {
-            base_ticker,
-            quote_ticker,
-            tick_size: tick_size.to_string(),
-            lot_size: lot_size.to_string(),
-            min_size: min_size.to_string(),
-            maker_fee_bps,
-            taker_fee_bps,
+            base_ticker: spec.base_ticker,
+            quote_ticker: spec.quote_ticker,
+            tick_size: spec.tick_size.to_string(),
+            lot_size: spec.lot_size.to_string(),
+            min_size: spec.min_size.to_string(),
+            maker_fee_bps: spec.maker_fee_bps,
+            taker_fee_bps: spec.taker_fee_bps,
+            min_price: spec.min_price.map(|p| p.to_string()),
+            max_price: spec.max_price.map(|p| p.to_string()),
         };
         let response = self.post_admin(request).await?;
 
@@ -488,6 +1341,109 @@ impl ExchangeClient {
         }
     }
 
+    /// Set or clear a market's per-user open order limits (admin)
+    pub async fn admin_set_market_order_limits(
+        &self,
+        market_id: String,
+        max_open_orders_per_user: Option<u32>,
+        max_open_notional_per_user: Option<u128>,
+    ) -> SdkResult<Market> {
+        let request = backend::models::api::AdminRequest::SetMarketOrderLimits {
+            market_id,
+            max_open_orders_per_user,
+            max_open_notional_per_user: max_open_notional_per_user.map(|n| n.to_string()),
+        };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::SetMarketOrderLimits { market } => market
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse market: {}", e))),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected SetMarketOrderLimits".to_string(),
+            )),
+        }
+    }
+
+    /// Set or clear a market's minimum limit order value (admin)
+    pub async fn admin_set_market_min_notional(
+        &self,
+        market_id: String,
+        min_notional: Option<u128>,
+    ) -> SdkResult<Market> {
+        let request = backend::models::api::AdminRequest::SetMarketMinNotional {
+            market_id,
+            min_notional: min_notional.map(|n| n.to_string()),
+        };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::SetMarketMinNotional { market } => market
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse market: {}", e))),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected SetMarketMinNotional".to_string(),
+            )),
+        }
+    }
+
+    /// Turn rounding of off-tick limit prices to the nearest tick on or off (admin)
+    pub async fn admin_set_market_snap_to_tick(
+        &self,
+        market_id: String,
+        snap_to_tick: bool,
+    ) -> SdkResult<Market> {
+        let request = backend::models::api::AdminRequest::SetMarketSnapToTick {
+            market_id,
+            snap_to_tick,
+        };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::SetMarketSnapToTick { market } => market
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse market: {}", e))),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected SetMarketSnapToTick".to_string(),
+            )),
+        }
+    }
+
+    /// Move a market to another trading phase (admin)
+    /// Opening a pre-open market matches the orders it collected
+    pub async fn admin_set_market_status(
+        &self,
+        market_id: String,
+        status: MarketStatus,
+    ) -> SdkResult<Market> {
+        let request = backend::models::api::AdminRequest::SetMarketStatus { market_id, status };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::SetMarketStatus { market } => market
+                .try_into()
+                .map_err(|e| SdkError::InvalidResponse(format!("Failed to parse market: {}", e))),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected SetMarketStatus".to_string(),
+            )),
+        }
+    }
+
+    /// Cancel every resting order in every market (admin kill switch)
+    /// Returns the number of orders cancelled
+    pub async fn admin_cancel_everything(&self) -> SdkResult<usize> {
+        let response = self
+            .post_admin(backend::models::api::AdminRequest::CancelEverything)
+            .await?;
+
+        match response {
+            backend::models::api::AdminResponse::CancelEverything { count } => Ok(count),
+            _ => Err(SdkError::InvalidResponse(
+                "Expected CancelEverything".to_string(),
+            )),
+        }
+    }
+
     /// Faucet via admin endpoint
     pub async fn admin_faucet(
         &self,
@@ -509,6 +1465,48 @@ impl ExchangeClient {
         }
     }
 
+    /// Credit many users at once via the admin endpoint
+    /// All grants are applied in one transaction, so either every grant lands or none does.
+    /// Returns the resulting balance of each user-token pair
+    pub async fn admin_bulk_faucet(&self, grants: Vec<FaucetGrant>) -> SdkResult<Vec<Balance>> {
+        let request = backend::models::api::AdminRequest::BulkFaucet { grants };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::BulkFaucet { balances } => balances
+                .into_iter()
+                .map(|b| b.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(SdkError::from),
+            _ => Err(SdkError::InvalidResponse("Expected BulkFaucet".to_string())),
+        }
+    }
+
+    /// Set a user's fee tier override (admin)
+    /// Rates are in tenths of a basis point (1 = 0.001%)
+    pub async fn admin_set_user_fee_tier(
+        &self,
+        user_address: String,
+        maker_fee_tenth_bps: i32,
+        taker_fee_tenth_bps: i32,
+    ) -> SdkResult<backend::models::domain::FeeSchedule> {
+        let request = backend::models::api::AdminRequest::SetUserFeeTier {
+            user_address,
+            maker_fee_tenth_bps,
+            taker_fee_tenth_bps,
+        };
+        let response = self.post_admin(request).await?;
+
+        match response {
+            backend::models::api::AdminResponse::SetUserFeeTier { fee_schedule, .. } => {
+                Ok(fee_schedule)
+            }
+            _ => Err(SdkError::InvalidResponse(
+                "Expected SetUserFeeTier".to_string(),
+            )),
+        }
+    }
+
     // ===== Internal Helper Methods =====
 
     async fn post_info(&self, request: InfoRequest) -> SdkResult<InfoResponse> {
@@ -518,20 +1516,34 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
+        }
+    }
+
+    /// WebSocket endpoint served alongside the REST API
+    pub fn ws_url(&self) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        let base = if let Some(host) = base.strip_prefix("https://") {
+            format!("wss://{}", host)
+        } else if let Some(host) = base.strip_prefix("http://") {
+            format!("ws://{}", host)
+        } else {
+            base.to_string()
+        };
+        format!("{}/ws", base)
+    }
+
+    async fn post_market_search(
+        &self,
+        request: MarketSearchRequest,
+    ) -> SdkResult<MarketSearchResponse> {
+        let url = format!("{}/api/markets/search", self.base_url);
+        let response = self.client.post(&url).json(&request).send().await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(SdkError::from_response(response).await)
         }
     }
 
@@ -542,20 +1554,7 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
         }
     }
 
@@ -566,20 +1565,18 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
+        }
+    }
+
+    async fn post_estimate(&self, request: EstimateRequest) -> SdkResult<EstimateResponse> {
+        let url = format!("{}/api/estimate", self.base_url);
+        let response = self.client.post(&url).json(&request).send().await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(SdkError::from_response(response).await)
         }
     }
 
@@ -590,20 +1587,7 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
         }
     }
 
@@ -617,20 +1601,7 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
         }
     }
 
@@ -641,20 +1612,311 @@ impl ExchangeClient {
         if response.status().is_success() {
             Ok(response.json().await?)
         } else {
-            let error: serde_json::Value = response.json().await?;
-            Err(SdkError::ApiError {
-                status: error
-                    .get("code")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("500")
-                    .parse()
-                    .unwrap_or(500),
-                message: error
-                    .get("error")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string(),
-            })
+            Err(SdkError::from_response(response).await)
+        }
+    }
+
+    async fn post_trades(&self, request: TradesRequest) -> SdkResult<TradesResponse> {
+        let url = format!("{}/api/trades", self.base_url);
+        let response = self.client.post(&url).json(&request).send().await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(SdkError::from_response(response).await)
+        }
+    }
+
+    async fn post_vwap(&self, request: VwapRequest) -> SdkResult<VwapResponse> {
+        let url = format!("{}/api/vwap", self.base_url);
+        let response = self.client.post(&url).json(&request).send().await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(SdkError::from_response(response).await)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve keep-alive health responses, counting the connections opened
+    async fn spawn_counting_server() -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    // One small request per read is enough for these GETs
+                    while matches!(stream.read(&mut buf).await, Ok(n) if n > 0) {
+                        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                        if stream.write_all(response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (format!("http://{}", addr), connections)
+    }
+
+    #[tokio::test]
+    async fn test_shared_exchange_clients_share_connections() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedExchange>();
+        assert_send_sync::<ExchangeClient>();
+
+        let (url, connections) = spawn_counting_server().await;
+        let shared = SharedExchange::new(&url);
+        for _ in 0..5 {
+            let client = shared.client();
+            assert_eq!(client.health_raw().await.unwrap(), "ok");
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // Independent clients each open their own connection
+        let (url, connections) = spawn_counting_server().await;
+        for _ in 0..5 {
+            let client = ExchangeClient::new(&url);
+            assert_eq!(client.health_raw().await.unwrap(), "ok");
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_builder_requires_base_url() {
+        let result = ExchangeClient::builder()
+            .with_http_client(Client::new())
+            .build();
+        assert!(matches!(result, Err(SdkError::Configuration(_))));
+
+        let result = ExchangeClient::builder().base_url("  ").build();
+        assert!(matches!(result, Err(SdkError::Configuration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_builder_uses_supplied_http_client() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Minimal server: capture the request head, reply with a fixed body
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let http_client = Client::builder().user_agent("my-bot/1.0").build().unwrap();
+        let client = ExchangeClient::builder()
+            .base_url(format!("http://{}", addr))
+            .with_http_client(http_client)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.health_raw().await.unwrap(), "ok");
+
+        let request = server.await.unwrap().to_lowercase();
+        assert!(request.starts_with("get /api/health "), "{}", request);
+        assert!(request.contains("user-agent: my-bot/1.0"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_is_connection_error() {
+        // Bind then drop a listener to get a local port nothing is listening on
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let client = ExchangeClient::new(format!("http://{}", addr));
+
+        let err = client.health_raw().await.unwrap_err();
+        assert!(matches!(err, SdkError::ConnectionError(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_api_error() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\nConnection: close\r\n\r\nboom",
+                )
+                .await
+                .unwrap();
+        });
+
+        let client = ExchangeClient::new(format!("http://{}", addr));
+        let err = client.health_raw().await.unwrap_err();
+        match err {
+            SdkError::ApiError { status, message } => {
+                assert_eq!(status, 500);
+                assert_eq!(message, "boom");
+            }
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    fn display_market() -> crate::MarketFull {
+        crate::MarketFull {
+            market: Market {
+                id: "BTC/USDC".to_string(),
+                base_ticker: "BTC".to_string(),
+                quote_ticker: "USDC".to_string(),
+                tick_size: 10_000,
+                lot_size: 10_000,
+                min_size: 10_000,
+                maker_fee_bps: 10,
+                taker_fee_bps: 20,
+                min_price: None,
+                max_price: None,
+                max_open_orders_per_user: None,
+                max_open_notional_per_user: None,
+                min_notional: None,
+                last_price: None,
+                snap_to_tick: false,
+                status: MarketStatus::Open,
+            },
+            base_token: Token {
+                ticker: "BTC".to_string(),
+                decimals: 6,
+                name: "Bitcoin".to_string(),
+            },
+            quote_token: Token {
+                ticker: "USDC".to_string(),
+                decimals: 6,
+                name: "USD Coin".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_display_order_atoms_round_to_grid() {
+        let market = display_market();
+        let atoms = |side, price, size| {
+            ExchangeClient::display_order_atoms(&market, side, OrderType::Limit, price, size)
+        };
+
+        assert_eq!(
+            atoms(Side::Buy, "95000.00", "0.01").unwrap(),
+            (95_000_000_000, 10_000)
+        );
+        // Buys round down and sells round up, so neither crosses further than asked
+        assert_eq!(
+            atoms(Side::Buy, "95000.0149999", "0.019").unwrap(),
+            (95_000_010_000, 10_000)
+        );
+        assert_eq!(
+            atoms(Side::Sell, "95000.0000001", "0.019").unwrap(),
+            (95_000_010_000, 10_000)
+        );
+
+        // Rounds below the minimum size, or isn't a number at all
+        assert!(matches!(
+            atoms(Side::Buy, "95000", "0.009"),
+            Err(SdkError::InvalidOrder(ref message)) if message.contains("minimum")
+        ));
+        assert!(matches!(
+            atoms(Side::Buy, "abc", "0.01"),
+            Err(SdkError::InvalidOrder(ref message)) if message.contains("price")
+        ));
+        assert!(matches!(
+            atoms(Side::Sell, "95000", "-1"),
+            Err(SdkError::InvalidOrder(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_order_exposes_reason() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await.unwrap();
+            let body = r#"{"error":"Price 1500 is not a multiple of tick size 1000","code":"INVALID_TICK_SIZE","reason":"TICK_SIZE"}"#;
+            let response = format!(
+                "HTTP/1.1 400 Bad Request\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let client = ExchangeClient::new(format!("http://{}", addr));
+        let err = client
+            .place_order(
+                "alice".to_string(),
+                "BTC/USDC".to_string(),
+                Side::Buy,
+                OrderType::Limit,
+                "1500".to_string(),
+                "1000000".to_string(),
+                "sig".to_string(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.reject_reason(), Some(crate::RejectReason::TickSize));
+        match err {
+            SdkError::Rejected {
+                status, message, ..
+            } => {
+                assert_eq!(status, 400);
+                assert!(message.contains("tick size"), "{}", message);
+            }
+            other => panic!("Expected Rejected, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_request_is_timeout() {
+        // Accept the connection but never respond
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        });
+
+        let client = ExchangeClient::builder()
+            .base_url(format!("http://{}", addr))
+            .with_http_client(
+                Client::builder()
+                    .timeout(std::time::Duration::from_millis(200))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let err = client.health_raw().await.unwrap_err();
+        assert!(matches!(err, SdkError::Timeout), "{:?}", err);
+        server.abort();
+    }
+}
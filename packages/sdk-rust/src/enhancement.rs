@@ -7,9 +7,10 @@ use backend::models::{
     domain::OrderbookLevel,
 };
 use chrono::{DateTime, Utc};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
-use crate::{cache::CacheService, format::*, SdkError, SdkResult};
+use crate::{cache::CacheService, client::ExchangeClient, format::*, SdkError, SdkResult};
 
 /// Enhanced trade with display values
 #[derive(Debug, Clone)]
@@ -84,37 +85,75 @@ pub struct EnhancedOrderbookLevel {
 /// Service for enhancing raw data with display values
 pub struct EnhancementService {
     cache: Arc<CacheService>,
+    // Token decimals by ticker, filled by `prime` or on first lookup from the cache
+    decimals: RwLock<HashMap<String, u8>>,
 }
 
 impl EnhancementService {
     /// Create a new enhancement service
     pub fn new(cache: Arc<CacheService>) -> Self {
-        Self { cache }
+        Self {
+            cache,
+            decimals: RwLock::new(HashMap::new()),
+        }
     }
 
-    /// Enhance a trade with display values
-    pub fn enhance_trade(&self, trade: ApiTrade) -> SdkResult<EnhancedTrade> {
-        let market = self
-            .cache
-            .get_market(&trade.market_id)
-            .ok_or_else(|| SdkError::Enhancement(format!("Market {} not found in cache. Available markets: {}. Call get_markets() first to populate cache.",
-                trade.market_id,
-                self.cache.get_all_markets().iter().map(|m| m.id.as_str()).collect::<Vec<_>>().join(", ")
-            )))?;
-
-        let base_token = self.cache.get_token(&market.base_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.base_ticker
-            ))
-        })?;
+    /// Preload the decimals of every token with a single tokens fetch
+    /// Also refreshes the shared cache's tokens. Returns how many tokens were loaded
+    pub async fn prime(&self, client: &ExchangeClient) -> SdkResult<usize> {
+        let tokens = client.get_tokens().await?;
+        let count = tokens.len();
+        {
+            let mut decimals = self.decimals.write().unwrap();
+            for token in &tokens {
+                decimals.insert(token.ticker.clone(), token.decimals);
+            }
+        }
+        self.cache.set_tokens(tokens);
+        Ok(count)
+    }
 
-        let quote_token = self.cache.get_token(&market.quote_ticker).ok_or_else(|| {
+    /// Decimals of a token, from the decimals cache or else the shared cache
+    /// A token neither knows about is an error rather than a guessed default
+    fn decimals(&self, ticker: &str) -> SdkResult<u8> {
+        if let Some(decimals) = self.decimals.read().unwrap().get(ticker) {
+            return Ok(*decimals);
+        }
+
+        let token = self.cache.get_token(ticker).ok_or_else(|| {
             SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.quote_ticker
+                "Decimals for token {} are unknown. Call prime() or get_tokens() first.",
+                ticker
             ))
         })?;
+        self.decimals
+            .write()
+            .unwrap()
+            .insert(ticker.to_string(), token.decimals);
+        Ok(token.decimals)
+    }
+
+    /// Base and quote decimals of a market
+    /// Uses the cached market when present, otherwise the tickers in its "BASE/QUOTE" id
+    fn market_decimals(&self, market_id: &str) -> SdkResult<(u8, u8)> {
+        let (base_ticker, quote_ticker) = match self.cache.get_market(market_id) {
+            Some(market) => (market.base_ticker, market.quote_ticker),
+            None => match market_id.split_once('/') {
+                Some((base, quote)) => (base.to_string(), quote.to_string()),
+                None => {
+                    return Err(SdkError::Enhancement(format!(
+                        "Market {} not found in cache. Call get_markets() first.",
+                        market_id
+                    )))
+                }
+            },
+        };
+        Ok((self.decimals(&base_ticker)?, self.decimals(&quote_ticker)?))
+    }
+
+    /// Enhance a trade with display values
+    pub fn enhance_trade(&self, trade: ApiTrade) -> SdkResult<EnhancedTrade> {
+        let (base_decimals, quote_decimals) = self.market_decimals(&trade.market_id)?;
 
         // Parse price and size
         let price = trade
@@ -138,35 +177,16 @@ impl EnhancementService {
             size,
             side: trade.side.to_string(),
             timestamp: trade.timestamp,
-            price_display: format_price(price, quote_token.decimals),
-            size_display: format_size(size, base_token.decimals),
-            price_value: to_display_value(price, quote_token.decimals),
-            size_value: to_display_value(size, base_token.decimals),
+            price_display: format_price(price, quote_decimals),
+            size_display: format_size(size, base_decimals),
+            price_value: to_display_value(price, quote_decimals),
+            size_value: to_display_value(size, base_decimals),
         })
     }
 
     /// Enhance an order with display values
     pub fn enhance_order(&self, order: ApiOrder, market_id: &str) -> SdkResult<EnhancedOrder> {
-        let market = self.cache.get_market(market_id).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Market {} not found in cache. Call get_markets() first.",
-                market_id
-            ))
-        })?;
-
-        let base_token = self.cache.get_token(&market.base_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.base_ticker
-            ))
-        })?;
-
-        let quote_token = self.cache.get_token(&market.quote_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.quote_ticker
-            ))
-        })?;
+        let (base_decimals, quote_decimals) = self.market_decimals(market_id)?;
 
         // Parse values
         let price = order
@@ -196,23 +216,18 @@ impl EnhancementService {
             status: order.status.to_string(),
             created_at: order.created_at,
             updated_at: order.updated_at,
-            price_display: format_price(price, quote_token.decimals),
-            size_display: format_size(size, base_token.decimals),
-            filled_display: format_size(filled_size, base_token.decimals),
-            price_value: to_display_value(price, quote_token.decimals),
-            size_value: to_display_value(size, base_token.decimals),
-            filled_value: to_display_value(filled_size, base_token.decimals),
+            price_display: format_price(price, quote_decimals),
+            size_display: format_size(size, base_decimals),
+            filled_display: format_size(filled_size, base_decimals),
+            price_value: to_display_value(price, quote_decimals),
+            size_value: to_display_value(size, base_decimals),
+            filled_value: to_display_value(filled_size, base_decimals),
         })
     }
 
     /// Enhance a balance with display values
     pub fn enhance_balance(&self, balance: ApiBalance) -> SdkResult<EnhancedBalance> {
-        let token = self.cache.get_token(&balance.token_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                balance.token_ticker
-            ))
-        })?;
+        let decimals = self.decimals(&balance.token_ticker)?;
 
         // Parse values
         let amount = balance
@@ -231,10 +246,10 @@ impl EnhancementService {
             amount,
             open_interest,
             updated_at: balance.updated_at,
-            amount_display: format_size(amount, token.decimals),
-            locked_display: format_size(open_interest, token.decimals),
-            amount_value: to_display_value(amount, token.decimals),
-            locked_value: to_display_value(open_interest, token.decimals),
+            amount_display: format_size(amount, decimals),
+            locked_display: format_size(open_interest, decimals),
+            amount_value: to_display_value(amount, decimals),
+            locked_value: to_display_value(open_interest, decimals),
         })
     }
 
@@ -244,34 +259,15 @@ impl EnhancementService {
         level: &OrderbookLevel,
         market_id: &str,
     ) -> SdkResult<EnhancedOrderbookLevel> {
-        let market = self.cache.get_market(market_id).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Market {} not found in cache. Call get_markets() first.",
-                market_id
-            ))
-        })?;
-
-        let base_token = self.cache.get_token(&market.base_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.base_ticker
-            ))
-        })?;
-
-        let quote_token = self.cache.get_token(&market.quote_ticker).ok_or_else(|| {
-            SdkError::Enhancement(format!(
-                "Token {} not found in cache. Call get_tokens() first.",
-                market.quote_ticker
-            ))
-        })?;
+        let (base_decimals, quote_decimals) = self.market_decimals(market_id)?;
 
         Ok(EnhancedOrderbookLevel {
             price: level.price,
             size: level.size,
-            price_display: format_price(level.price, quote_token.decimals),
-            size_display: format_size(level.size, base_token.decimals),
-            price_value: to_display_value(level.price, quote_token.decimals),
-            size_value: to_display_value(level.size, base_token.decimals),
+            price_display: format_price(level.price, quote_decimals),
+            size_display: format_size(level.size, base_decimals),
+            price_value: to_display_value(level.price, quote_decimals),
+            size_value: to_display_value(level.size, base_decimals),
         })
     }
 }
@@ -281,6 +277,8 @@ mod tests {
     use super::*;
     use crate::logger::NoopLogger;
     use backend::models::{api::ApiMarket, domain::Token};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     fn setup_cache() -> Arc<CacheService> {
         let cache = Arc::new(CacheService::new(Arc::new(NoopLogger)));
@@ -309,6 +307,14 @@ mod tests {
             min_size: "1000000".to_string(),
             maker_fee_bps: 10,
             taker_fee_bps: 20,
+            min_price: None,
+            max_price: None,
+            max_open_orders_per_user: None,
+            max_open_notional_per_user: None,
+            min_notional: None,
+            last_price: None,
+            snap_to_tick: false,
+            status: crate::MarketStatus::Open,
         }]);
 
         cache.mark_initialized();
@@ -331,6 +337,7 @@ mod tests {
             size: "100000000".to_string(),    // 1 BTC (8 decimals)
             side: backend::models::domain::Side::Buy,
             timestamp: Utc::now(),
+            sequence: 1,
         };
 
         let enhanced = enhancer.enhance_trade(trade).unwrap();
@@ -350,6 +357,8 @@ mod tests {
             token_ticker: "BTC".to_string(),
             amount: "100000000".to_string(),       // 1 BTC
             open_interest: "50000000".to_string(), // 0.5 BTC locked
+            available: "50000000".to_string(),
+            locked: "50000000".to_string(),
             updated_at: Utc::now(),
         };
 
@@ -357,4 +366,79 @@ mod tests {
         assert_eq!(enhanced.amount_value, 1.0);
         assert_eq!(enhanced.locked_value, 0.5);
     }
+
+    fn trade(id: &str) -> ApiTrade {
+        ApiTrade {
+            id: id.to_string(),
+            market_id: "BTC/USDC".to_string(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: "order1".to_string(),
+            seller_order_id: "order2".to_string(),
+            price: "50000000000".to_string(),
+            size: "100000000".to_string(),
+            side: backend::models::domain::Side::Buy,
+            timestamp: Utc::now(),
+            sequence: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prime_fetches_tokens_once_for_a_batch() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        // Answers every request with the token list and counts them
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            let body = r#"{"type":"all_tokens","tokens":[{"ticker":"BTC","decimals":8,"name":"Bitcoin"},{"ticker":"USDC","decimals":6,"name":"USD Coin"}]}"#;
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        // Nothing cached up front, not even the market
+        let cache = Arc::new(CacheService::new(Arc::new(NoopLogger)));
+        let enhancer = EnhancementService::new(cache);
+        let client = ExchangeClient::new(format!("http://{}", addr));
+
+        assert_eq!(enhancer.prime(&client).await.unwrap(), 2);
+        let enhanced: Vec<_> = (0..5)
+            .map(|i| {
+                enhancer
+                    .enhance_trade(trade(&format!("trade{}", i)))
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(enhanced
+            .iter()
+            .all(|t| t.price_value == 50000.0 && t.size_value == 1.0));
+    }
+
+    #[test]
+    fn test_missing_token_decimals_is_an_error() {
+        let cache = Arc::new(CacheService::new(Arc::new(NoopLogger)));
+        cache.set_tokens(vec![Token {
+            ticker: "USDC".to_string(),
+            decimals: 6,
+            name: "USD Coin".to_string(),
+        }]);
+        let enhancer = EnhancementService::new(cache);
+
+        // BTC decimals were never loaded
+        let result = enhancer.enhance_trade(trade("trade1"));
+        assert!(matches!(result, Err(SdkError::Enhancement(message)) if message.contains("BTC")));
+    }
 }
@@ -1,21 +1,45 @@
+use backend::errors::{ErrorResponse, RejectReason};
+use backend::models::api::FieldParseError;
 use thiserror::Error;
 
 pub type SdkResult<T> = Result<T, SdkError>;
 
 #[derive(Debug, Error)]
 pub enum SdkError {
+    /// Request failed for a reason other than connecting or timing out
     #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(reqwest::Error),
 
     #[error("WebSocket error: {0}")]
     WebSocketError(String),
 
+    /// Some markets of a multi-market subscription could not be sent or weren't acknowledged
+    #[error("Subscription failed for {failed:?}: {}", errors.join("; "))]
+    PartialSubscription {
+        failed: Vec<String>, // Market ids without a subscription
+        errors: Vec<String>,
+    },
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// The server answered with an error status
     #[error("API error ({status}): {message}")]
     ApiError { status: u16, message: String },
 
+    /// The exchange refused an order, `reason` says why in a form code can match on
+    #[error("Order rejected ({reason:?}): {message}")]
+    Rejected {
+        status: u16,
+        reason: RejectReason,
+        message: String,
+    },
+
+    /// The requested resource doesn't exist (404), `code` says which kind, e.g. "MARKET_NOT_FOUND"
+    #[error("Not found ({code}): {message}")]
+    NotFound { code: String, message: String },
+
+    /// The transport failed before any HTTP response (e.g. connection refused)
     #[error("Connection error: {0}")]
     ConnectionError(String),
 
@@ -25,6 +49,110 @@ pub enum SdkError {
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
 
+    /// The order was not sent because the market would reject it
+    #[error("Invalid order: {0}")]
+    InvalidOrder(String),
+
     #[error("Enhancement error: {0}")]
     Enhancement(String),
+
+    #[error("Invalid configuration: {0}")]
+    Configuration(String),
+
+    /// Writing exported data failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<reqwest::Error> for SdkError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            SdkError::Timeout
+        } else if err.is_connect() {
+            SdkError::ConnectionError(err.to_string())
+        } else {
+            SdkError::HttpError(err)
+        }
+    }
+}
+
+impl SdkError {
+    /// Error for a non-success API response, read from its `ErrorResponse` body
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let (code, message, reason) = match response.json::<ErrorResponse>().await {
+            Ok(body) => (body.code, body.error, body.reason),
+            Err(_) => ("UNKNOWN".to_string(), "Unknown error".to_string(), None),
+        };
+
+        match reason {
+            Some(reason) => SdkError::Rejected {
+                status,
+                reason,
+                message,
+            },
+            None if status == 404 => SdkError::NotFound { code, message },
+            None => SdkError::ApiError { status, message },
+        }
+    }
+
+    /// Why the exchange rejected an order, None for any other error
+    pub fn reject_reason(&self) -> Option<RejectReason> {
+        match self {
+            SdkError::Rejected { reason, .. } => Some(*reason),
+            _ => None,
+        }
+    }
+}
+
+impl From<FieldParseError> for SdkError {
+    fn from(err: FieldParseError) -> Self {
+        SdkError::InvalidResponse(err.to_string())
+    }
+}
+
+/// Parse a u128 the backend sent as a string
+/// A malformed value is an `InvalidResponse` naming the field
+pub fn parse_u128_field(value: &str, field: &'static str) -> SdkResult<u128> {
+    Ok(backend::models::api::parse_u128_field(value, field)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::models::api::ApiOrder;
+    use backend::models::domain::{Order, OrderStatus, OrderType, Side, TimeInForce};
+
+    #[test]
+    fn test_parse_u128_field_names_field() {
+        assert_eq!(parse_u128_field("42", "size").unwrap(), 42);
+
+        let err = parse_u128_field("-1", "size").unwrap_err();
+        assert!(matches!(err, SdkError::InvalidResponse(ref message) if message.contains("size")));
+    }
+
+    #[test]
+    fn test_malformed_order_price_names_price_field() {
+        let order = ApiOrder {
+            id: "6f1c7c3e-3b7a-4a53-9f51-1a2b3c4d5e6f".to_string(),
+            user_address: "alice".to_string(),
+            market_id: "BTC/USDC".to_string(),
+            price: "50000.5".to_string(),
+            size: "1000000".to_string(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::Gtc,
+            status: OrderStatus::Pending,
+            filled_size: "0".to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        let err: SdkError = Order::try_from(order).unwrap_err().into();
+        let SdkError::InvalidResponse(message) = err else {
+            panic!("Expected InvalidResponse, got {:?}", err);
+        };
+        assert!(message.contains("price"), "{}", message);
+        assert!(message.contains("50000.5"), "{}", message);
+    }
 }
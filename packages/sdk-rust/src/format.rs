@@ -1,7 +1,89 @@
 //! Formatting utilities for converting between atoms and display values
 //!
 //! These utilities convert raw u128 values (atoms) to human-readable
-//! display values and vice versa.
+//! display values and vice versa. The plain `format_*` functions render US
+//! style numbers; the `format_*_with` variants take `FormatOptions` for other
+//! separators and currency symbols.
+
+/// Separators and currency symbols used to render numbers
+///
+/// The default is US style ("1,234.56") with no currency symbol.
+///
+/// # Example
+/// ```
+/// use exchange_sdk::{format_price_with, FormatOptions};
+/// let usd = FormatOptions::us().with_prefix("$");
+/// assert_eq!(format_price_with(&usd, 1_234_560_000, 6), "$1,234.56");
+/// let eur = FormatOptions::european().with_suffix(" €");
+/// assert_eq!(format_price_with(&eur, 1_234_560_000, 6), "1.234,56 €");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Inserted between groups of three integer digits, empty for no grouping
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+    /// Placed before the digits, after any minus sign
+    pub prefix: String,
+    /// Placed after the digits
+    pub suffix: String,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::us()
+    }
+}
+
+impl FormatOptions {
+    /// "1,234.56"
+    pub fn us() -> Self {
+        Self {
+            thousands_separator: ",".to_string(),
+            decimal_separator: ".".to_string(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    /// "1.234,56"
+    pub fn european() -> Self {
+        Self {
+            thousands_separator: ".".to_string(),
+            decimal_separator: ",".to_string(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Render a plain `[-]digits[.digits]` string with these separators and symbols
+    fn render(&self, plain: &str) -> String {
+        let (sign, unsigned) = match plain.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", plain),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut number = group_digits(integer, &self.thousands_separator);
+        if let Some(fraction) = fraction {
+            number.push_str(&self.decimal_separator);
+            number.push_str(fraction);
+        }
+        format!("{}{}{}{}", sign, self.prefix, number, self.suffix)
+    }
+}
 
 /// Convert atoms (u128) to display value (f64)
 ///
@@ -20,6 +102,47 @@ pub fn to_display_value(atoms: u128, decimals: u8) -> f64 {
     whole_part + fractional_part
 }
 
+/// Convert atoms (u128) to a display value rounded half-up to `display_decimals`
+///
+/// Rounding happens on the integer atoms, and the result is the f64 closest to
+/// the rounded decimal, so it doesn't pick up float noise from tokens with many
+/// decimals. A `display_decimals` at or above `decimals` keeps every digit.
+///
+/// # Example
+/// ```
+/// use exchange_sdk::to_display_value_rounded;
+/// // 123_456_789 atoms with 8 decimals = 1.23456789
+/// assert_eq!(to_display_value_rounded(123_456_789, 8, 2), 1.23);
+/// assert_eq!(to_display_value_rounded(123_456_789, 8, 4), 1.2346);
+/// ```
+pub fn to_display_value_rounded(atoms: u128, decimals: u8, display_decimals: u8) -> f64 {
+    let (units, places) = if display_decimals >= decimals {
+        (atoms, decimals)
+    } else {
+        let step = 10u128.pow((decimals - display_decimals) as u32);
+        let remainder = atoms % step;
+        let units = atoms / step;
+        if remainder >= step - remainder {
+            (units + 1, display_decimals)
+        } else {
+            (units, display_decimals)
+        }
+    };
+
+    if places == 0 {
+        return units as f64;
+    }
+    let divisor = 10u128.pow(places as u32);
+    format!(
+        "{}.{:0width$}",
+        units / divisor,
+        units % divisor,
+        width = places as usize
+    )
+    .parse()
+    .unwrap_or_else(|_| to_display_value(units, places))
+}
+
 /// Convert display value (f64) to atoms (u128)
 ///
 /// # Example
@@ -44,32 +167,38 @@ pub fn to_atoms(value: f64, decimals: u8) -> u128 {
 /// assert_eq!(format_number(1000000.0, 2), "1,000,000");
 /// ```
 pub fn format_number(value: f64, max_decimals: u8) -> String {
+    format_number_with(&FormatOptions::default(), value, max_decimals)
+}
+
+/// Format a number like `format_number`, with custom separators and symbols
+///
+/// # Example
+/// ```
+/// use exchange_sdk::{format_number_with, FormatOptions};
+/// assert_eq!(format_number_with(&FormatOptions::european(), 1234.5678, 2), "1.234,57");
+/// ```
+pub fn format_number_with(options: &FormatOptions, value: f64, max_decimals: u8) -> String {
     // Format with fixed decimals
     let formatted = format!("{:.prec$}", value, prec = max_decimals as usize);
 
-    // Split into integer and decimal parts
-    let parts: Vec<&str> = formatted.split('.').collect();
-    let integer = parts[0];
-    let decimal = parts.get(1).copied();
-
-    // Add commas to integer part
-    let with_commas = add_commas(integer);
-
     // Trim trailing zeros from decimal
-    if let Some(dec) = decimal {
-        let trimmed = dec.trim_end_matches('0');
-        if trimmed.is_empty() {
-            with_commas
-        } else {
-            format!("{}.{}", with_commas, trimmed)
+    let plain = match formatted.split_once('.') {
+        Some((integer, decimal)) => {
+            let trimmed = decimal.trim_end_matches('0');
+            if trimmed.is_empty() {
+                integer.to_string()
+            } else {
+                format!("{}.{}", integer, trimmed)
+            }
         }
-    } else {
-        with_commas
-    }
+        None => formatted,
+    };
+
+    options.render(&plain)
 }
 
-/// Add commas to an integer string
-fn add_commas(s: &str) -> String {
+/// Insert a separator between groups of three digits of an integer string
+fn group_digits(s: &str, separator: &str) -> String {
     let bytes: Vec<char> = s.chars().collect();
     let mut result = String::new();
     let len = bytes.len();
@@ -78,7 +207,7 @@ fn add_commas(s: &str) -> String {
         result.push(*c);
         let pos = len - i - 1;
         if pos > 0 && pos.is_multiple_of(3) {
-            result.push(',');
+            result.push_str(separator);
         }
     }
 
@@ -97,18 +226,19 @@ fn add_commas(s: &str) -> String {
 /// assert_eq!(format_price(123_456_789, 6), "123.456789");
 /// ```
 pub fn format_price(atoms: u128, decimals: u8) -> String {
+    format_price_with(&FormatOptions::default(), atoms, decimals)
+}
+
+/// Format a price like `format_price`, with custom separators and symbols
+pub fn format_price_with(options: &FormatOptions, atoms: u128, decimals: u8) -> String {
     let value = to_display_value(atoms, decimals);
 
     // For high-value prices (>= 1000), always show exactly 2 decimals
     if value >= 1000.0 {
-        let formatted = format!("{:.2}", value);
-        let parts: Vec<&str> = formatted.split('.').collect();
-        let integer = parts[0];
-        let decimal = parts.get(1).unwrap_or(&"00");
-        format!("{}.{}", add_commas(integer), decimal)
+        options.render(&format!("{:.2}", value))
     } else {
         // Otherwise use token decimals, capped at 8 for readability
-        format_number(value, decimals.min(8))
+        format_number_with(options, value, decimals.min(8))
     }
 }
 
@@ -120,8 +250,13 @@ pub fn format_price(atoms: u128, decimals: u8) -> String {
 /// assert_eq!(format_size(123_456_789, 6), "123.456789");
 /// ```
 pub fn format_size(atoms: u128, decimals: u8) -> String {
+    format_size_with(&FormatOptions::default(), atoms, decimals)
+}
+
+/// Format a size like `format_size`, with custom separators and symbols
+pub fn format_size_with(options: &FormatOptions, atoms: u128, decimals: u8) -> String {
     let value = to_display_value(atoms, decimals);
-    format_number(value, decimals.min(8))
+    format_number_with(options, value, decimals.min(8))
 }
 
 #[cfg(test)]
@@ -136,6 +271,18 @@ mod tests {
         assert_eq!(to_display_value(1, 6), 0.000001);
     }
 
+    #[test]
+    fn test_to_display_value_rounded() {
+        assert_eq!(to_display_value_rounded(123_456_789, 8, 2), 1.23);
+        assert_eq!(to_display_value_rounded(123_456_789, 8, 4), 1.2346);
+        // Halves round up
+        assert_eq!(to_display_value_rounded(125, 3, 2), 0.13);
+        assert_eq!(to_display_value_rounded(995, 3, 2), 1.0);
+        // Asking for at least the token's precision keeps every digit
+        assert_eq!(to_display_value_rounded(123_456_789, 8, 8), 1.23456789);
+        assert_eq!(to_display_value_rounded(123_456_789, 6, 10), 123.456789);
+    }
+
     #[test]
     fn test_to_atoms() {
         assert_eq!(to_atoms(1.0, 6), 1_000_000);
@@ -153,10 +300,11 @@ mod tests {
     }
 
     #[test]
-    fn test_add_commas() {
-        assert_eq!(add_commas("1000"), "1,000");
-        assert_eq!(add_commas("1000000"), "1,000,000");
-        assert_eq!(add_commas("123"), "123");
+    fn test_group_digits() {
+        assert_eq!(group_digits("1000", ","), "1,000");
+        assert_eq!(group_digits("1000000", ","), "1,000,000");
+        assert_eq!(group_digits("123", ","), "123");
+        assert_eq!(group_digits("1000000", " "), "1 000 000");
     }
 
     #[test]
@@ -169,4 +317,39 @@ mod tests {
     fn test_format_size() {
         assert_eq!(format_size(123_456_789, 6), "123.456789");
     }
+
+    #[test]
+    fn test_format_with_us_grouping() {
+        let us = FormatOptions::us();
+        assert_eq!(format_number_with(&us, 1234567.891, 2), "1,234,567.89");
+        assert_eq!(format_price_with(&us, 1_234_560_000, 6), "1,234.56");
+        assert_eq!(format_size_with(&us, 123_456_789, 6), "123.456789");
+        assert_eq!(format_number_with(&us, -1234.5, 1), "-1,234.5");
+    }
+
+    #[test]
+    fn test_format_with_european_grouping() {
+        let eu = FormatOptions::european();
+        assert_eq!(format_number_with(&eu, 1234567.891, 2), "1.234.567,89");
+        assert_eq!(format_price_with(&eu, 1_000_000_000, 6), "1.000,00");
+        assert_eq!(format_price_with(&eu, 123_456_789, 6), "123,456789");
+        assert_eq!(format_number_with(&eu, 1000000.0, 2), "1.000.000");
+
+        // Grouping can be switched off
+        let plain = FormatOptions {
+            thousands_separator: String::new(),
+            ..FormatOptions::european()
+        };
+        assert_eq!(format_number_with(&plain, 1234567.5, 1), "1234567,5");
+    }
+
+    #[test]
+    fn test_format_with_currency_symbols() {
+        let usd = FormatOptions::us().with_prefix("$");
+        assert_eq!(format_price_with(&usd, 1_234_560_000, 6), "$1,234.56");
+        assert_eq!(format_number_with(&usd, -5.25, 2), "-$5.25");
+
+        let eur = FormatOptions::european().with_suffix(" €");
+        assert_eq!(format_price_with(&eur, 1_234_560_000, 6), "1.234,56 €");
+    }
 }
@@ -9,6 +9,7 @@
 //! - Caching for markets and tokens
 //! - Enhancement service for display values
 //! - Formatting utilities
+//! - Local orderbook mirror
 //! - Configurable logging
 //!
 //! # Example
@@ -36,26 +37,43 @@
 //! ```
 
 pub mod cache;
+pub mod candles;
 pub mod client;
 pub mod enhancement;
 pub mod error;
 pub mod format;
 pub mod logger;
+pub mod orderbook;
+pub mod validation;
 pub mod websocket;
 
 pub use cache::{CacheService, CacheStats};
-pub use client::ExchangeClient;
+pub use candles::{CandleWindow, DEFAULT_CANDLE_WINDOW};
+pub use client::{AdminCreateMarketSpec, ExchangeClient, ExchangeClientBuilder, SharedExchange};
 pub use enhancement::{
     EnhancedBalance, EnhancedOrder, EnhancedOrderbookLevel, EnhancedTrade, EnhancementService,
 };
-pub use error::{SdkError, SdkResult};
-pub use format::{format_number, format_price, format_size, to_atoms, to_display_value};
+pub use error::{parse_u128_field, SdkError, SdkResult};
+pub use format::{
+    format_number, format_number_with, format_price, format_price_with, format_size,
+    format_size_with, to_atoms, to_display_value, to_display_value_rounded, FormatOptions,
+};
 pub use logger::{ConsoleLogger, LogLevel, Logger, NoopLogger};
-pub use websocket::{WebSocketClient, WebSocketHandle};
+pub use orderbook::{BookUpdate, LocalOrderbook};
+pub use validation::ValidationIssue;
+pub use websocket::{
+    MarketChannelSub, SubscribeTarget, TradesGroup, UserChannelSub, WebSocketClient,
+    WebSocketHandle,
+};
 
 // Re-export backend types for convenience
+pub use backend::auth::Wallet;
+pub use backend::errors::RejectReason;
 pub use backend::models::api::{
-    ApiCandle, CandlesRequest, CandlesResponse, ClientMessage, OrderCancelled, SubscriptionChannel,
+    ActiveSubscription, ApiCandle, CandlesRequest, CandlesResponse, ClientMessage, ComponentStatus,
+    EstimateRequest, EstimateResponse, FaucetGrant, HealthStatus, MarketSearchRequest,
+    MarketSearchResponse, MarketSortBy, OrderCancelled, OverallStatus, ServerMessage,
+    SubscriptionChannel, TradesRequest, TradesResponse, VwapRequest, VwapResponse,
 };
 pub use backend::models::domain::*;
 
@@ -64,4 +82,137 @@ pub use backend::models::domain::*;
 pub struct OrderPlaced {
     pub order: Order,
     pub trades: Vec<Trade>,
+    total_quote: u128,
+    /// Price as submitted when the market snapped it to the tick, order.price holds the snapped price
+    pub requested_price: Option<u128>,
+}
+
+impl OrderPlaced {
+    /// Base atoms filled on placement, summed over the trades
+    pub fn filled_size(&self) -> u128 {
+        self.trades.iter().map(|trade| trade.size).sum()
+    }
+
+    /// Size-weighted average fill price in quote atoms, None if nothing filled
+    pub fn average_price(&self) -> Option<u128> {
+        let filled = self.filled_size();
+        if filled == 0 {
+            return None;
+        }
+        let notional: u128 = self
+            .trades
+            .iter()
+            .map(|trade| trade.price * trade.size)
+            .sum();
+        Some(notional / filled)
+    }
+
+    /// Total quote atoms exchanged across all fills
+    pub fn total_quote(&self) -> u128 {
+        self.total_quote
+    }
+
+    /// Whether the whole order filled on placement
+    pub fn is_fully_filled(&self) -> bool {
+        self.filled_size() >= self.order.size
+    }
+}
+
+/// SDK-specific result of a limit order placed with a fill deadline
+#[derive(Debug, Clone)]
+pub struct OrderDeadlineResult {
+    pub placed: OrderPlaced,
+    /// Final state of the order, filled_size holds any partial fill
+    pub order: Order,
+    /// Whether the deadline passed and the remainder was cancelled
+    pub auto_cancelled: bool,
+}
+
+/// SDK-specific result of an atomic cancel and replace
+#[derive(Debug, Clone)]
+pub struct OrderReplaced {
+    pub cancelled_order_id: String,
+    pub placed: OrderPlaced,
+}
+
+/// SDK-specific market with its base and quote tokens
+#[derive(Debug, Clone)]
+pub struct MarketFull {
+    pub market: Market,
+    pub base_token: Token,
+    pub quote_token: Token,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn trade(price: u128, size: u128) -> Trade {
+        Trade {
+            id: Uuid::new_v4(),
+            market_id: "BTC/USDC".to_string(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: Uuid::new_v4(),
+            seller_order_id: Uuid::new_v4(),
+            price,
+            size,
+            side: Side::Buy,
+            timestamp: Utc::now(),
+            sequence: 1,
+        }
+    }
+
+    fn placed(size: u128, trades: Vec<Trade>) -> OrderPlaced {
+        let filled_size = trades.iter().map(|t| t.size).sum();
+        OrderPlaced {
+            order: Order {
+                id: Uuid::new_v4(),
+                user_address: "buyer".to_string(),
+                market_id: "BTC/USDC".to_string(),
+                price: 50_100_000_000,
+                size,
+                side: Side::Buy,
+                order_type: OrderType::Limit,
+                time_in_force: TimeInForce::default(),
+                min_fill: None,
+                status: if filled_size >= size {
+                    OrderStatus::Filled
+                } else {
+                    OrderStatus::PartiallyFilled
+                },
+                filled_size,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            trades,
+            total_quote: 0,
+            requested_price: None,
+        }
+    }
+
+    #[test]
+    fn test_order_placed_aggregates_fills() {
+        // 0.01 BTC at $50,000 and 0.03 BTC at $50,100
+        let partial = placed(
+            5_000_000,
+            vec![
+                trade(50_000_000_000, 1_000_000),
+                trade(50_100_000_000, 3_000_000),
+            ],
+        );
+        assert_eq!(partial.filled_size(), 4_000_000);
+        assert_eq!(partial.average_price(), Some(50_075_000_000));
+        assert!(!partial.is_fully_filled());
+
+        let full = placed(4_000_000, partial.trades.clone());
+        assert!(full.is_fully_filled());
+
+        let resting = placed(1_000_000, Vec::new());
+        assert_eq!(resting.filled_size(), 0);
+        assert_eq!(resting.average_price(), None);
+        assert!(!resting.is_fully_filled());
+    }
 }
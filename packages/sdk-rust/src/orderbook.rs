@@ -0,0 +1,240 @@
+//! Local mirror of a market's orderbook built from WebSocket events
+//!
+//! The server answers an orderbook subscription with a snapshot, then pushes
+//! the book again whenever it changes. `LocalOrderbook` applies those messages,
+//! checks each one against its checksum and reports when it needs a fresh
+//! snapshot; `WebSocketHandle::sync_orderbook` requests it automatically.
+
+use backend::models::api::{OrderbookData, PriceLevel, ServerMessage};
+use backend::models::domain::{OrderbookLevel, OrderbookSnapshot};
+
+/// What applying a message did to a `LocalOrderbook`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookUpdate {
+    /// Not for this book, or an update arriving while a snapshot is pending
+    Ignored,
+    /// The book changed and matches the server's checksum
+    Applied,
+    /// The book can't be trusted until a fresh snapshot arrives
+    ResyncNeeded,
+}
+
+/// Client-side copy of one market's orderbook
+///
+/// Starts out waiting for a snapshot. Updates received before it are dropped,
+/// since only the subscribe reply is guaranteed to be a complete book. A
+/// message whose levels don't hash to its checksum puts the book back into
+/// the waiting state and asks for a resync.
+#[derive(Debug, Clone)]
+pub struct LocalOrderbook {
+    market_id: String,
+    bids: Vec<OrderbookLevel>,
+    asks: Vec<OrderbookLevel>,
+    checksum: u32,
+    synced: bool,
+}
+
+impl LocalOrderbook {
+    pub fn new(market_id: impl Into<String>) -> Self {
+        Self {
+            market_id: market_id.into(),
+            bids: Vec::new(),
+            asks: Vec::new(),
+            checksum: 0,
+            synced: false,
+        }
+    }
+
+    pub fn market_id(&self) -> &str {
+        &self.market_id
+    }
+
+    /// Whether the book reflects a verified server state
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Drop the current state and wait for a snapshot, e.g. after reconnecting
+    pub fn invalidate(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.checksum = 0;
+        self.synced = false;
+    }
+
+    /// Update the book from a server message
+    pub fn apply_message(&mut self, message: &ServerMessage) -> BookUpdate {
+        match message {
+            ServerMessage::Orderbook { orderbook } => self.apply(orderbook),
+            _ => BookUpdate::Ignored,
+        }
+    }
+
+    /// Update the book from an orderbook snapshot or update
+    pub fn apply(&mut self, orderbook: &OrderbookData) -> BookUpdate {
+        if orderbook.market_id != self.market_id || (!orderbook.is_snapshot && !self.synced) {
+            return BookUpdate::Ignored;
+        }
+
+        let levels = parse_levels(&orderbook.bids).zip(parse_levels(&orderbook.asks));
+        match levels {
+            Some((bids, asks))
+                if OrderbookSnapshot::compute_checksum(&bids, &asks) == orderbook.checksum =>
+            {
+                self.bids = bids;
+                self.asks = asks;
+                self.checksum = orderbook.checksum;
+                self.synced = true;
+                BookUpdate::Applied
+            }
+            _ => {
+                self.invalidate();
+                BookUpdate::ResyncNeeded
+            }
+        }
+    }
+
+    /// Highest bid
+    pub fn best_bid(&self) -> Option<&OrderbookLevel> {
+        self.bids.first()
+    }
+
+    /// Lowest ask
+    pub fn best_ask(&self) -> Option<&OrderbookLevel> {
+        self.asks.first()
+    }
+
+    /// Bids (highest first) and asks (lowest first)
+    pub fn levels(&self) -> (&[OrderbookLevel], &[OrderbookLevel]) {
+        (&self.bids, &self.asks)
+    }
+
+    /// Checksum of the last applied message
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+}
+
+/// Levels in atoms, or None if any price or size doesn't parse
+fn parse_levels(levels: &[PriceLevel]) -> Option<Vec<OrderbookLevel>> {
+    levels
+        .iter()
+        .map(|level| {
+            Some(OrderbookLevel {
+                price: level.price.parse().ok()?,
+                size: level.size.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book_message(
+        market_id: &str,
+        bids: &[(u128, u128)],
+        asks: &[(u128, u128)],
+        is_snapshot: bool,
+    ) -> ServerMessage {
+        let levels = |levels: &[(u128, u128)]| -> Vec<OrderbookLevel> {
+            levels
+                .iter()
+                .map(|&(price, size)| OrderbookLevel { price, size })
+                .collect()
+        };
+        let as_api = |levels: &[OrderbookLevel]| -> Vec<PriceLevel> {
+            levels
+                .iter()
+                .map(|level| PriceLevel {
+                    price: level.price.to_string(),
+                    size: level.size.to_string(),
+                })
+                .collect()
+        };
+        let (bids, asks) = (levels(bids), levels(asks));
+        ServerMessage::Orderbook {
+            orderbook: OrderbookData {
+                market_id: market_id.to_string(),
+                bids: as_api(&bids),
+                asks: as_api(&asks),
+                checksum: OrderbookSnapshot::compute_checksum(&bids, &asks),
+                is_snapshot,
+                seq: 0,
+            },
+        }
+    }
+
+    fn pairs(levels: &[OrderbookLevel]) -> Vec<(u128, u128)> {
+        levels
+            .iter()
+            .map(|level| (level.price, level.size))
+            .collect()
+    }
+
+    #[test]
+    fn test_book_follows_snapshot_then_updates() {
+        let mut book = LocalOrderbook::new("BTC/USDC");
+
+        // Updates before the first snapshot are dropped
+        let early = book_message("BTC/USDC", &[(99, 1)], &[], false);
+        assert_eq!(book.apply_message(&early), BookUpdate::Ignored);
+        assert!(!book.is_synced());
+
+        let snapshot = book_message("BTC/USDC", &[(99, 5), (98, 2)], &[(101, 3)], true);
+        assert_eq!(book.apply_message(&snapshot), BookUpdate::Applied);
+        assert!(book.is_synced());
+
+        // Other markets are ignored
+        let other = book_message("ETH/USDC", &[(1, 1)], &[], false);
+        assert_eq!(book.apply_message(&other), BookUpdate::Ignored);
+
+        let updates = [
+            book_message(
+                "BTC/USDC",
+                &[(99, 5), (98, 2)],
+                &[(100, 1), (101, 3)],
+                false,
+            ),
+            book_message("BTC/USDC", &[(98, 2)], &[(100, 1), (101, 3)], false),
+            book_message("BTC/USDC", &[(98, 4)], &[(101, 3)], false),
+        ];
+        for update in &updates {
+            assert_eq!(book.apply_message(update), BookUpdate::Applied);
+        }
+
+        let (bids, asks) = book.levels();
+        assert_eq!(pairs(bids), vec![(98, 4)]);
+        assert_eq!(pairs(asks), vec![(101, 3)]);
+        assert_eq!(book.best_bid().map(|level| level.price), Some(98));
+        assert_eq!(book.best_ask().map(|level| level.price), Some(101));
+        let ServerMessage::Orderbook { orderbook } = &updates[2] else {
+            unreachable!()
+        };
+        assert_eq!(book.checksum(), orderbook.checksum);
+    }
+
+    #[test]
+    fn test_checksum_mismatch_waits_for_fresh_snapshot() {
+        let mut book = LocalOrderbook::new("BTC/USDC");
+        let snapshot = book_message("BTC/USDC", &[(99, 5)], &[(101, 3)], true);
+        assert_eq!(book.apply_message(&snapshot), BookUpdate::Applied);
+
+        let mut corrupt = book_message("BTC/USDC", &[(99, 4)], &[(101, 3)], false);
+        if let ServerMessage::Orderbook { orderbook } = &mut corrupt {
+            orderbook.checksum ^= 1;
+        }
+        assert_eq!(book.apply_message(&corrupt), BookUpdate::ResyncNeeded);
+        assert!(!book.is_synced());
+        assert_eq!(book.best_bid(), None);
+
+        // Updates are dropped until the resync snapshot arrives
+        let update = book_message("BTC/USDC", &[(99, 4)], &[(101, 3)], false);
+        assert_eq!(book.apply_message(&update), BookUpdate::Ignored);
+
+        let resync = book_message("BTC/USDC", &[(99, 4)], &[(101, 2)], true);
+        assert_eq!(book.apply_message(&resync), BookUpdate::Applied);
+        assert_eq!(pairs(book.levels().1), vec![(101, 2)]);
+    }
+}
@@ -0,0 +1,408 @@
+//! Client-side order validation
+//!
+//! Mirrors the backend's order checks against a market's config so UIs can
+//! flag a bad order before sending it. Passing here doesn't guarantee the
+//! backend accepts the order: balances and per-user limits are only known
+//! server-side, and the market config may have changed since it was cached.
+
+use std::fmt;
+
+use crate::{MarketFull, MarketStatus, OrderType};
+
+/// One reason the backend would reject an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// The market is not accepting this kind of order in its current phase
+    MarketNotOpen {
+        status: MarketStatus,
+    },
+    ZeroSize,
+    /// Limit orders need a price
+    ZeroPrice,
+    NotTickMultiple {
+        price: u128,
+        tick_size: u128,
+    },
+    PriceBelowMinimum {
+        price: u128,
+        min_price: u128,
+    },
+    PriceAboveMaximum {
+        price: u128,
+        max_price: u128,
+    },
+    NotLotMultiple {
+        size: u128,
+        lot_size: u128,
+    },
+    SizeBelowMinimum {
+        size: u128,
+        min_size: u128,
+    },
+    /// Order value in quote atoms is under the market's minimum
+    NotionalBelowMinimum {
+        notional: u128,
+        min_notional: u128,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationIssue::MarketNotOpen { status } => {
+                write!(f, "Market is {} and does not accept this order", status)
+            }
+            ValidationIssue::ZeroSize => write!(f, "Order size must be greater than 0"),
+            ValidationIssue::ZeroPrice => write!(f, "Limit order price must be greater than 0"),
+            ValidationIssue::NotTickMultiple { price, tick_size } => {
+                write!(
+                    f,
+                    "Price {} is not a multiple of tick size {}",
+                    price, tick_size
+                )
+            }
+            ValidationIssue::PriceBelowMinimum { price, min_price } => {
+                write!(
+                    f,
+                    "Price {} is below market minimum price {}",
+                    price, min_price
+                )
+            }
+            ValidationIssue::PriceAboveMaximum { price, max_price } => {
+                write!(
+                    f,
+                    "Price {} is above market maximum price {}",
+                    price, max_price
+                )
+            }
+            ValidationIssue::NotLotMultiple { size, lot_size } => {
+                write!(
+                    f,
+                    "Size {} is not a multiple of lot size {}",
+                    size, lot_size
+                )
+            }
+            ValidationIssue::SizeBelowMinimum { size, min_size } => {
+                write!(f, "Size {} is below minimum order size {}", size, min_size)
+            }
+            ValidationIssue::NotionalBelowMinimum {
+                notional,
+                min_notional,
+            } => write!(
+                f,
+                "Order notional {} is below market minimum notional {}",
+                notional, min_notional
+            ),
+        }
+    }
+}
+
+/// Run the backend's order checks locally, returning every check that fails
+///
+/// `price` and `size` are in quote and base atoms. Price checks only apply to
+/// limit orders, as on the backend; orders are assumed good-til-cancelled.
+/// On markets that snap prices to the tick, the snapped price is checked.
+pub fn validate_order(
+    market: &MarketFull,
+    order_type: OrderType,
+    price: u128,
+    size: u128,
+) -> Result<(), Vec<ValidationIssue>> {
+    let config = &market.market;
+    let is_limit = order_type == OrderType::Limit;
+    let price = if is_limit && config.snap_to_tick {
+        config.snap_price_to_tick(price)
+    } else {
+        price
+    };
+    let mut issues = Vec::new();
+
+    let accepted = match config.status {
+        MarketStatus::Open => true,
+        MarketStatus::PreOpen => is_limit,
+        MarketStatus::Halted | MarketStatus::Closed => false,
+    };
+    if !accepted {
+        issues.push(ValidationIssue::MarketNotOpen {
+            status: config.status,
+        });
+    }
+
+    if size == 0 {
+        issues.push(ValidationIssue::ZeroSize);
+    }
+
+    if is_limit {
+        if price == 0 {
+            issues.push(ValidationIssue::ZeroPrice);
+        }
+        if !price.is_multiple_of(config.tick_size) {
+            issues.push(ValidationIssue::NotTickMultiple {
+                price,
+                tick_size: config.tick_size,
+            });
+        }
+        if let Some(min_price) = config.min_price.filter(|&min| price < min) {
+            issues.push(ValidationIssue::PriceBelowMinimum { price, min_price });
+        }
+        if let Some(max_price) = config.max_price.filter(|&max| price > max) {
+            issues.push(ValidationIssue::PriceAboveMaximum { price, max_price });
+        }
+    }
+
+    if !size.is_multiple_of(config.lot_size) {
+        issues.push(ValidationIssue::NotLotMultiple {
+            size,
+            lot_size: config.lot_size,
+        });
+    }
+
+    if size < config.min_size {
+        issues.push(ValidationIssue::SizeBelowMinimum {
+            size,
+            min_size: config.min_size,
+        });
+    }
+
+    // notional = (price_atoms * size_atoms) / 10^base_decimals, same as the backend
+    if let Some(min_notional) = config.min_notional.filter(|_| is_limit) {
+        let divisor = 10u128.pow(market.base_token.decimals as u32);
+        let notional = price
+            .checked_mul(size)
+            .map(|v| v / divisor)
+            .unwrap_or(u128::MAX);
+        if notional < min_notional {
+            issues.push(ValidationIssue::NotionalBelowMinimum {
+                notional,
+                min_notional,
+            });
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Market, Token};
+
+    fn market() -> MarketFull {
+        MarketFull {
+            market: Market {
+                id: "BTC/USDC".to_string(),
+                base_ticker: "BTC".to_string(),
+                quote_ticker: "USDC".to_string(),
+                tick_size: 1_000,
+                lot_size: 1_000,
+                min_size: 10_000,
+                maker_fee_bps: 10,
+                taker_fee_bps: 20,
+                min_price: None,
+                max_price: None,
+                max_open_orders_per_user: None,
+                max_open_notional_per_user: None,
+                min_notional: None,
+                last_price: None,
+                snap_to_tick: false,
+                status: MarketStatus::Open,
+            },
+            base_token: Token {
+                ticker: "BTC".to_string(),
+                decimals: 6,
+                name: "Bitcoin".to_string(),
+            },
+            quote_token: Token {
+                ticker: "USDC".to_string(),
+                decimals: 6,
+                name: "USD Coin".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_valid_order_passes() {
+        assert_eq!(
+            validate_order(&market(), OrderType::Limit, 50_000_000_000, 1_000_000),
+            Ok(())
+        );
+        // Market orders carry no price
+        assert_eq!(
+            validate_order(&market(), OrderType::Market, 0, 1_000_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_tick_size_violation() {
+        assert_eq!(
+            validate_order(&market(), OrderType::Limit, 50_000_000_500, 1_000_000),
+            Err(vec![ValidationIssue::NotTickMultiple {
+                price: 50_000_000_500,
+                tick_size: 1_000,
+            }])
+        );
+        // Market order prices aren't checked
+        assert_eq!(
+            validate_order(&market(), OrderType::Market, 50_000_000_500, 1_000_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_snap_to_tick_checks_snapped_price() {
+        let mut market = market();
+        market.market.snap_to_tick = true;
+        market.market.min_price = Some(50_000_000_000);
+
+        // 50_000_000_500 snaps up to 50_000_001_000
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 50_000_000_500, 1_000_000),
+            Ok(())
+        );
+        // 49_999_999_400 snaps down to 49_999_999_000, which is under the band
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 49_999_999_400, 1_000_000),
+            Err(vec![ValidationIssue::PriceBelowMinimum {
+                price: 49_999_999_000,
+                min_price: 50_000_000_000,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_lot_size_violation() {
+        assert_eq!(
+            validate_order(&market(), OrderType::Limit, 50_000_000_000, 1_000_500),
+            Err(vec![ValidationIssue::NotLotMultiple {
+                size: 1_000_500,
+                lot_size: 1_000,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_min_size_violation() {
+        assert_eq!(
+            validate_order(&market(), OrderType::Limit, 50_000_000_000, 5_000),
+            Err(vec![ValidationIssue::SizeBelowMinimum {
+                size: 5_000,
+                min_size: 10_000,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_zero_size_and_price() {
+        assert_eq!(
+            validate_order(&market(), OrderType::Limit, 0, 0),
+            Err(vec![
+                ValidationIssue::ZeroSize,
+                ValidationIssue::ZeroPrice,
+                ValidationIssue::SizeBelowMinimum {
+                    size: 0,
+                    min_size: 10_000,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_min_notional_violation() {
+        let mut market = market();
+        market.market.min_notional = Some(10_000_000); // 10 USDC
+
+        // 5 USDC * 1 BTC = 5 USDC of notional
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 5_000_000, 1_000_000),
+            Err(vec![ValidationIssue::NotionalBelowMinimum {
+                notional: 5_000_000,
+                min_notional: 10_000_000,
+            }])
+        );
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 10_000_000, 1_000_000),
+            Ok(())
+        );
+        assert_eq!(
+            validate_order(&market, OrderType::Market, 0, 1_000_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_price_band_violation() {
+        let mut market = market();
+        market.market.min_price = Some(1_000_000);
+        market.market.max_price = Some(100_000_000);
+
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 1_000, 1_000_000),
+            Err(vec![ValidationIssue::PriceBelowMinimum {
+                price: 1_000,
+                min_price: 1_000_000,
+            }])
+        );
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 200_000_000, 1_000_000),
+            Err(vec![ValidationIssue::PriceAboveMaximum {
+                price: 200_000_000,
+                max_price: 100_000_000,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_reports_every_failing_check() {
+        let mut market = market();
+        market.market.status = MarketStatus::Halted;
+
+        let issues = validate_order(&market, OrderType::Limit, 50_000_000_500, 5_500)
+            .expect_err("Order should fail validation");
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::MarketNotOpen {
+                    status: MarketStatus::Halted,
+                },
+                ValidationIssue::NotTickMultiple {
+                    price: 50_000_000_500,
+                    tick_size: 1_000,
+                },
+                ValidationIssue::NotLotMultiple {
+                    size: 5_500,
+                    lot_size: 1_000,
+                },
+                ValidationIssue::SizeBelowMinimum {
+                    size: 5_500,
+                    min_size: 10_000,
+                },
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "Price 50000000500 is not a multiple of tick size 1000"
+        );
+    }
+
+    #[test]
+    fn test_pre_open_rejects_market_orders() {
+        let mut market = market();
+        market.market.status = MarketStatus::PreOpen;
+
+        assert_eq!(
+            validate_order(&market, OrderType::Limit, 50_000_000_000, 1_000_000),
+            Ok(())
+        );
+        assert_eq!(
+            validate_order(&market, OrderType::Market, 0, 1_000_000),
+            Err(vec![ValidationIssue::MarketNotOpen {
+                status: MarketStatus::PreOpen,
+            }])
+        );
+    }
+}
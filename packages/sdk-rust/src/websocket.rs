@@ -1,16 +1,33 @@
+use crate::candles::{CandleWindow, DEFAULT_CANDLE_WINDOW};
 use crate::error::{SdkError, SdkResult};
-use backend::models::api::{ClientMessage, SubscriptionChannel};
-use futures_util::{SinkExt, StreamExt};
+use crate::orderbook::{BookUpdate, LocalOrderbook};
+use backend::api::ws::COMPRESSION_PROTOCOL;
+use backend::auth::Wallet;
+use backend::models::api::{ClientMessage, ServerMessage, SubscriptionChannel};
+use backend::models::domain::CandleInterval;
+use flate2::read::DeflateDecoder;
+use futures_util::{SinkExt, Stream, StreamExt};
+use std::io::Read;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio::time::{interval, Instant};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::{Error as WsError, ProtocolError, SubProtocolError};
+use tokio_tungstenite::tungstenite::http::{header::SEC_WEBSOCKET_PROTOCOL, HeaderValue};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+/// How long `sign_in` waits for each of the server's auth replies
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// WebSocket client for real-time data streams
 pub struct WebSocketClient {
     url: String,
     ping_interval: Duration,
     pong_timeout: Duration,
+    compression: bool,
 }
 
 impl WebSocketClient {
@@ -20,6 +37,7 @@ impl WebSocketClient {
             url: url.into(),
             ping_interval: Duration::from_secs(30),
             pong_timeout: Duration::from_secs(60),
+            compression: false,
         }
     }
 
@@ -33,14 +51,20 @@ impl WebSocketClient {
             url: url.into(),
             ping_interval,
             pong_timeout,
+            compression: false,
         }
     }
 
+    /// Ask the server to deflate its messages, off by default
+    /// Servers without compression enabled still connect, uncompressed
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
     /// Connect to the WebSocket server and return a handle for communication
     pub async fn connect(&self) -> SdkResult<WebSocketHandle> {
-        let (ws_stream, _) = connect_async(&self.url)
-            .await
-            .map_err(|e| SdkError::WebSocketError(e.to_string()))?;
+        let (ws_stream, compressed) = self.open().await?;
 
         let (write, read) = ws_stream.split();
 
@@ -97,34 +121,43 @@ impl WebSocketClient {
         tokio::spawn(async move {
             let mut read = read;
             while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        // Parse as serde_json::Value first since ServerMessage only has Serialize
-                        match serde_json::from_str::<serde_json::Value>(&text) {
-                            Ok(value) => {
-                                // Check if this is a pong message
-                                if let Some(msg_type) = value.get("type").and_then(|v| v.as_str()) {
-                                    if msg_type == "pong" {
-                                        let _ = tx_pong_notify.send(());
-                                    }
-                                }
-
-                                // Convert to ServerMessage representation
-                                if tx_to_user.send(value).is_err() {
-                                    break; // Receiver dropped
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to parse server message: {}", e);
-                            }
+                let text = match msg {
+                    Ok(Message::Text(text)) => text.to_string(),
+                    // With compression negotiated every server message is deflated JSON
+                    Ok(Message::Binary(data)) if compressed => {
+                        let mut text = String::new();
+                        if let Err(e) = DeflateDecoder::new(&data[..]).read_to_string(&mut text) {
+                            eprintln!("Failed to inflate server message: {}", e);
+                            continue;
                         }
+                        text
                     }
                     Ok(Message::Close(_)) => break,
                     Err(e) => {
                         eprintln!("WebSocket error: {}", e);
                         break;
                     }
-                    _ => {}
+                    _ => continue,
+                };
+
+                // Parse as serde_json::Value first since ServerMessage only has Serialize
+                match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(value) => {
+                        // Check if this is a pong message
+                        if let Some(msg_type) = value.get("type").and_then(|v| v.as_str()) {
+                            if msg_type == "pong" {
+                                let _ = tx_pong_notify.send(());
+                            }
+                        }
+
+                        // Convert to ServerMessage representation
+                        if tx_to_user.send(value).is_err() {
+                            break; // Receiver dropped
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to parse server message: {}", e);
+                    }
                 }
             }
         });
@@ -132,49 +165,447 @@ impl WebSocketClient {
         Ok(WebSocketHandle {
             tx: tx_to_ws,
             rx: rx_from_ws,
+            compressed,
         })
     }
+
+    /// Open the connection, offering the compression subprotocol when enabled
+    /// A server that doesn't select it fails the handshake, so that case retries
+    /// without it. Returns whether compression was negotiated
+    async fn open(&self) -> SdkResult<(WebSocketStream<MaybeTlsStream<TcpStream>>, bool)> {
+        if self.compression {
+            let mut request = self
+                .url
+                .as_str()
+                .into_client_request()
+                .map_err(|e| SdkError::WebSocketError(e.to_string()))?;
+            request.headers_mut().insert(
+                SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::from_static(COMPRESSION_PROTOCOL),
+            );
+            match connect_async(request).await {
+                Ok((ws_stream, _)) => return Ok((ws_stream, true)),
+                Err(WsError::Protocol(ProtocolError::SecWebSocketSubProtocolError(
+                    SubProtocolError::NoSubProtocol,
+                ))) => {}
+                Err(e) => return Err(SdkError::WebSocketError(e.to_string())),
+            }
+        }
+
+        let (ws_stream, _) = connect_async(&self.url)
+            .await
+            .map_err(|e| SdkError::WebSocketError(e.to_string()))?;
+        Ok((ws_stream, false))
+    }
+}
+
+/// Arguments for a market-scoped channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketChannelSub {
+    pub market_id: String,
+}
+
+/// Arguments for a user-scoped channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserChannelSub {
+    pub user_address: String,
+    pub market_id: Option<String>, // Fills and orders only: limit to one market
+}
+
+/// A channel together with the arguments it requires
+///
+/// Market channels always carry a market id and user channels always carry
+/// a user address, so a subscription can't be sent with the wrong arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscribeTarget {
+    Trades(MarketChannelSub),
+    AllTrades,
+    Orderbook(MarketChannelSub),
+    UserFills(UserChannelSub),
+    UserOrders(UserChannelSub),
+    UserBalances(UserChannelSub),
+    /// Trading phase changes, for one market or every market
+    MarketStatus(Option<String>),
+}
+
+impl SubscribeTarget {
+    /// Public trades for a market
+    pub fn trades(market_id: impl Into<String>) -> Self {
+        Self::Trades(MarketChannelSub {
+            market_id: market_id.into(),
+        })
+    }
+
+    /// Public trades across every market
+    pub fn all_trades() -> Self {
+        Self::AllTrades
+    }
+
+    /// Orderbook updates for a market
+    pub fn orderbook(market_id: impl Into<String>) -> Self {
+        Self::Orderbook(MarketChannelSub {
+            market_id: market_id.into(),
+        })
+    }
+
+    /// Fills for a user across all markets
+    pub fn user_fills(user_address: impl Into<String>) -> Self {
+        Self::UserFills(UserChannelSub {
+            user_address: user_address.into(),
+            market_id: None,
+        })
+    }
+
+    /// Fills for a user in one market
+    pub fn user_fills_in_market(
+        user_address: impl Into<String>,
+        market_id: impl Into<String>,
+    ) -> Self {
+        Self::UserFills(UserChannelSub {
+            user_address: user_address.into(),
+            market_id: Some(market_id.into()),
+        })
+    }
+
+    /// Order updates for a user across all markets
+    pub fn user_orders(user_address: impl Into<String>) -> Self {
+        Self::UserOrders(UserChannelSub {
+            user_address: user_address.into(),
+            market_id: None,
+        })
+    }
+
+    /// Order updates for a user in one market
+    pub fn user_orders_in_market(
+        user_address: impl Into<String>,
+        market_id: impl Into<String>,
+    ) -> Self {
+        Self::UserOrders(UserChannelSub {
+            user_address: user_address.into(),
+            market_id: Some(market_id.into()),
+        })
+    }
+
+    /// Balance updates for a user
+    pub fn user_balances(user_address: impl Into<String>) -> Self {
+        Self::UserBalances(UserChannelSub {
+            user_address: user_address.into(),
+            market_id: None,
+        })
+    }
+
+    /// Status changes of one market, starting with its current status
+    pub fn market_status(market_id: impl Into<String>) -> Self {
+        Self::MarketStatus(Some(market_id.into()))
+    }
+
+    /// Status changes of every market, starting with each one's current status
+    pub fn all_market_statuses() -> Self {
+        Self::MarketStatus(None)
+    }
+
+    /// The channel this target subscribes to
+    pub fn channel(&self) -> SubscriptionChannel {
+        match self {
+            Self::Trades(_) => SubscriptionChannel::Trades,
+            Self::AllTrades => SubscriptionChannel::AllTrades,
+            Self::Orderbook(_) => SubscriptionChannel::Orderbook,
+            Self::UserFills(_) => SubscriptionChannel::UserFills,
+            Self::UserOrders(_) => SubscriptionChannel::UserOrders,
+            Self::UserBalances(_) => SubscriptionChannel::UserBalances,
+            Self::MarketStatus(_) => SubscriptionChannel::MarketStatus,
+        }
+    }
+
+    fn args(&self) -> (Option<String>, Option<String>) {
+        match self {
+            Self::Trades(sub) | Self::Orderbook(sub) => (Some(sub.market_id.clone()), None),
+            Self::AllTrades => (None, None),
+            Self::MarketStatus(market_id) => (market_id.clone(), None),
+            Self::UserFills(sub) | Self::UserOrders(sub) | Self::UserBalances(sub) => {
+                (sub.market_id.clone(), Some(sub.user_address.clone()))
+            }
+        }
+    }
+
+    /// Subscribe message for this target
+    pub fn subscribe_message(&self) -> ClientMessage {
+        let (market_id, user_address) = self.args();
+        ClientMessage::Subscribe {
+            channel: self.channel(),
+            market_id,
+            user_address,
+        }
+    }
+
+    /// Unsubscribe message for this target
+    pub fn unsubscribe_message(&self) -> ClientMessage {
+        let (market_id, user_address) = self.args();
+        ClientMessage::Unsubscribe {
+            channel: self.channel(),
+            market_id,
+            user_address,
+        }
+    }
+}
+
+/// Markets whose trades were subscribed together with `subscribe_trades_multi`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradesGroup {
+    market_ids: Vec<String>,
+}
+
+impl TradesGroup {
+    /// Markets in the group, in subscription order
+    pub fn market_ids(&self) -> &[String] {
+        &self.market_ids
+    }
 }
 
 /// Handle for sending and receiving WebSocket messages
+///
+/// Also implements `Stream`, yielding server messages until the connection closes
 pub struct WebSocketHandle {
     tx: mpsc::UnboundedSender<ClientMessage>,
     rx: mpsc::UnboundedReceiver<serde_json::Value>,
+    compressed: bool,
 }
 
 impl WebSocketHandle {
+    /// Whether the server agreed to deflate its messages on this connection
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
     /// Subscribe to a channel
-    pub fn subscribe(
-        &self,
-        channel: SubscriptionChannel,
-        market_id: Option<String>,
-        user_address: Option<String>,
-    ) -> SdkResult<()> {
+    pub fn subscribe(&self, target: SubscribeTarget) -> SdkResult<()> {
         self.tx
-            .send(ClientMessage::Subscribe {
-                channel,
-                market_id,
-                user_address,
-            })
+            .send(target.subscribe_message())
             .map_err(|e| SdkError::WebSocketError(e.to_string()))
     }
 
     /// Unsubscribe from a channel
-    pub fn unsubscribe(
+    pub fn unsubscribe(&self, target: SubscribeTarget) -> SdkResult<()> {
+        self.tx
+            .send(target.unsubscribe_message())
+            .map_err(|e| SdkError::WebSocketError(e.to_string()))
+    }
+
+    /// Subscribe to several markets' trades, one subscribe message per market
+    /// Markets that couldn't be sent are reported as a `PartialSubscription`;
+    /// use `confirm_trades_multi` to wait for the server's acknowledgments
+    pub fn subscribe_trades_multi(&self, market_ids: &[String]) -> SdkResult<TradesGroup> {
+        self.send_trades_multi(market_ids, SubscribeTarget::subscribe_message)?;
+        Ok(TradesGroup {
+            market_ids: market_ids.to_vec(),
+        })
+    }
+
+    /// Unsubscribe from every market in a group
+    pub fn unsubscribe_trades_multi(&self, group: &TradesGroup) -> SdkResult<()> {
+        self.send_trades_multi(&group.market_ids, SubscribeTarget::unsubscribe_message)
+    }
+
+    /// Send one trades message per market, collecting the markets whose send failed
+    fn send_trades_multi(
         &self,
-        channel: SubscriptionChannel,
-        market_id: Option<String>,
-        user_address: Option<String>,
+        market_ids: &[String],
+        message: fn(&SubscribeTarget) -> ClientMessage,
     ) -> SdkResult<()> {
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        for market_id in market_ids {
+            let target = SubscribeTarget::trades(market_id.clone());
+            if let Err(e) = self.tx.send(message(&target)) {
+                failed.push(market_id.clone());
+                errors.push(e.to_string());
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(SdkError::PartialSubscription { failed, errors })
+        }
+    }
+
+    /// Wait until the server has answered every subscribe sent for a group
+    ///
+    /// The server answers each subscribe in order with `Subscribed` or `Error`.
+    /// Returns the messages received meanwhile (acks and any early trades); markets
+    /// without an ack by `timeout` are reported as a `PartialSubscription` with the
+    /// server's error messages
+    pub async fn confirm_trades_multi(
+        &mut self,
+        group: &TradesGroup,
+        timeout: Duration,
+    ) -> SdkResult<Vec<ServerMessage>> {
+        let mut pending: Vec<&String> = group.market_ids.iter().collect();
+        let mut errors = Vec::new();
+
+        let collected = self
+            .collect_until(
+                |msg| {
+                    match msg {
+                        ServerMessage::Subscribed {
+                            channel: SubscriptionChannel::Trades,
+                            market_id: Some(market_id),
+                            ..
+                        } => pending.retain(|pending| *pending != market_id),
+                        ServerMessage::Error { message } => errors.push(message.clone()),
+                        _ => {}
+                    }
+                    pending.len() <= errors.len()
+                },
+                timeout,
+            )
+            .await;
+
+        if pending.is_empty() {
+            Ok(collected)
+        } else {
+            Err(SdkError::PartialSubscription {
+                failed: pending.into_iter().cloned().collect(),
+                errors,
+            })
+        }
+    }
+
+    /// Subscribe to the trades that drive live candles for a market
+    /// Feed received messages to the returned window with `CandleWindow::apply_message`
+    pub fn subscribe_candles(
+        &self,
+        market_id: impl Into<String>,
+        interval: CandleInterval,
+    ) -> SdkResult<CandleWindow> {
+        let market_id = market_id.into();
+        self.subscribe(SubscribeTarget::trades(market_id.clone()))?;
+        Ok(CandleWindow::new(
+            market_id,
+            interval,
+            DEFAULT_CANDLE_WINDOW,
+        ))
+    }
+
+    /// Subscribe to a market's orderbook and return a mirror waiting for the snapshot
+    /// Feed received messages to it with `sync_orderbook`
+    pub fn subscribe_orderbook(&self, market_id: impl Into<String>) -> SdkResult<LocalOrderbook> {
+        let market_id = market_id.into();
+        self.subscribe(SubscribeTarget::orderbook(market_id.clone()))?;
+        Ok(LocalOrderbook::new(market_id))
+    }
+
+    /// Apply a server message to an orderbook mirror
+    /// When the mirror falls out of sync, a fresh snapshot is requested by subscribing again
+    pub fn sync_orderbook(
+        &self,
+        book: &mut LocalOrderbook,
+        message: &ServerMessage,
+    ) -> SdkResult<BookUpdate> {
+        let update = book.apply_message(message);
+        if update == BookUpdate::ResyncNeeded {
+            self.subscribe(SubscribeTarget::orderbook(book.market_id()))?;
+        }
+        Ok(update)
+    }
+
+    /// Reset an orderbook mirror and request a snapshot on this connection
+    /// Use after reconnecting, since the old connection's state may have gaps
+    pub fn resync_orderbook(&self, book: &mut LocalOrderbook) -> SdkResult<()> {
+        book.invalidate();
+        self.subscribe(SubscribeTarget::orderbook(book.market_id()))
+    }
+
+    /// Subscribe to a market's trades or orderbook and replay the messages sent after `after_seq`
+    /// Pass the `seq` of the last trade or orderbook message seen before the disconnect;
+    /// the server answers with `ServerMessage::Resumed`, then the missed messages in order
+    pub fn resume(&self, target: SubscribeTarget, after_seq: u64) -> SdkResult<()> {
+        let market_id = match &target {
+            SubscribeTarget::Trades(sub) | SubscribeTarget::Orderbook(sub) => sub.market_id.clone(),
+            _ => {
+                return Err(SdkError::WebSocketError(
+                    "only trades and orderbook channels can be resumed".to_string(),
+                ))
+            }
+        };
         self.tx
-            .send(ClientMessage::Unsubscribe {
-                channel,
+            .send(ClientMessage::Resume {
+                channel: target.channel(),
                 market_id,
+                after_seq,
+            })
+            .map_err(|e| SdkError::WebSocketError(e.to_string()))
+    }
+
+    /// Ask the server for a challenge to sign, answered with `ServerMessage::AuthChallenge`
+    pub fn request_auth_challenge(&self) -> SdkResult<()> {
+        self.tx
+            .send(ClientMessage::AuthChallenge)
+            .map_err(|e| SdkError::WebSocketError(e.to_string()))
+    }
+
+    /// Authenticate the connection as a user with a signature over the latest challenge
+    /// User channels subscribed afterwards default to this user and
+    /// other users' private channels are rejected
+    pub fn authenticate(&self, user_address: String, signature: String) -> SdkResult<()> {
+        self.tx
+            .send(ClientMessage::Auth {
                 user_address,
+                signature,
             })
             .map_err(|e| SdkError::WebSocketError(e.to_string()))
     }
 
+    /// Authenticate as the wallet's address: fetch a challenge, sign it and wait for the reply
+    /// Private channels require this. Call it before subscribing, other messages
+    /// received in the meantime are discarded
+    pub async fn sign_in(&mut self, wallet: &Wallet) -> SdkResult<()> {
+        self.request_auth_challenge()?;
+        let nonce = match self.auth_reply().await? {
+            ServerMessage::AuthChallenge { nonce, .. } => nonce,
+            other => return Err(SdkError::InvalidResponse(format!("{:?}", other))),
+        };
+
+        self.authenticate(wallet.address(), wallet.sign_challenge(&nonce))?;
+        match self.auth_reply().await? {
+            ServerMessage::Authenticated { .. } => Ok(()),
+            other => Err(SdkError::InvalidResponse(format!("{:?}", other))),
+        }
+    }
+
+    /// Next challenge, authenticated or error message, skipping anything else
+    async fn auth_reply(&mut self) -> SdkResult<ServerMessage> {
+        let reply = self
+            .collect_until(
+                |msg| {
+                    matches!(
+                        msg,
+                        ServerMessage::AuthChallenge { .. }
+                            | ServerMessage::Authenticated { .. }
+                            | ServerMessage::Error { .. }
+                    )
+                },
+                AUTH_TIMEOUT,
+            )
+            .await
+            .pop();
+        match reply {
+            Some(ServerMessage::Error { message }) => Err(SdkError::WebSocketError(message)),
+            Some(
+                msg @ (ServerMessage::AuthChallenge { .. } | ServerMessage::Authenticated { .. }),
+            ) => Ok(msg),
+            _ => Err(SdkError::Timeout),
+        }
+    }
+
+    /// Ask the server for this connection's active subscriptions
+    /// The reply arrives as a `ServerMessage::Subscriptions`, useful to verify state after a reconnect
+    pub fn list_subscriptions(&self) -> SdkResult<()> {
+        self.tx
+            .send(ClientMessage::ListSubscriptions)
+            .map_err(|e| SdkError::WebSocketError(e.to_string()))
+    }
+
     /// Send a ping
     pub fn ping(&self) -> SdkResult<()> {
         self.tx
@@ -191,6 +622,49 @@ impl WebSocketHandle {
     pub fn try_recv(&mut self) -> Option<serde_json::Value> {
         self.rx.try_recv().ok()
     }
+
+    /// Collect server messages until one matches `predicate` or `timeout` elapses
+    ///
+    /// Returns everything collected, including the matching message. Pongs and
+    /// messages that don't parse as a `ServerMessage` are skipped; a closed
+    /// connection ends collection early.
+    pub async fn collect_until<F>(
+        &mut self,
+        mut predicate: F,
+        timeout: Duration,
+    ) -> Vec<ServerMessage>
+    where
+        F: FnMut(&ServerMessage) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        let mut collected = Vec::new();
+
+        while let Ok(Some(value)) = tokio::time::timeout_at(deadline, self.rx.recv()).await {
+            let Ok(msg) = serde_json::from_value::<ServerMessage>(value) else {
+                continue;
+            };
+            if matches!(msg, ServerMessage::Pong) {
+                continue;
+            }
+
+            let done = predicate(&msg);
+            collected.push(msg);
+            if done {
+                break;
+            }
+        }
+
+        collected
+    }
+}
+
+impl Stream for WebSocketHandle {
+    type Item = serde_json::Value;
+
+    /// Yields `None` once the server closes the connection
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
 }
 
 #[cfg(test)]
@@ -202,4 +676,186 @@ mod tests {
         let client = WebSocketClient::new("ws://localhost:8001/ws");
         assert_eq!(client.url, "ws://localhost:8001/ws");
     }
+
+    #[tokio::test]
+    async fn test_handle_stream_ends_on_close() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Minimal server: send two messages, then close
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for msg in [r#"{"type":"pong"}"#, r#"{"type":"error","message":"test"}"#] {
+                ws.send(Message::Text(msg.into())).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let client = WebSocketClient::new(format!("ws://{}", addr));
+        let mut handle = client.connect().await.unwrap();
+
+        let first = handle.next().await.expect("first message");
+        assert_eq!(first["type"], "pong");
+        let second = handle.next().await.expect("second message");
+        assert_eq!(second["type"], "error");
+
+        let end = tokio::time::timeout(Duration::from_secs(1), handle.next())
+            .await
+            .expect("stream should end after close");
+        assert!(end.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_collect_until_skips_pongs_and_stops_on_match() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            for msg in [
+                r#"{"type":"pong"}"#,
+                r#"{"type":"error","message":"first"}"#,
+                r#"{"type":"pong"}"#,
+                r#"{"type":"error","message":"second"}"#,
+                r#"{"type":"error","message":"after"}"#,
+            ] {
+                ws.send(Message::Text(msg.into())).await.unwrap();
+            }
+            // Keep the connection open so collection ends on the predicate
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let client = WebSocketClient::new(format!("ws://{}", addr));
+        let mut handle = client.connect().await.unwrap();
+
+        let collected = handle
+            .collect_until(
+                |msg| matches!(msg, ServerMessage::Error { message } if message == "second"),
+                Duration::from_secs(2),
+            )
+            .await;
+        let messages: Vec<_> = collected
+            .iter()
+            .map(|msg| match msg {
+                ServerMessage::Error { message } => message.as_str(),
+                other => panic!("unexpected message {:?}", other),
+            })
+            .collect();
+        assert_eq!(messages, vec!["first", "second"]);
+
+        // Times out with whatever arrived when nothing matches
+        let rest = handle
+            .collect_until(|_| false, Duration::from_millis(200))
+            .await;
+        assert_eq!(rest.len(), 1);
+    }
+
+    #[test]
+    fn test_subscribe_targets_map_to_client_messages() {
+        let cases = [
+            (
+                SubscribeTarget::trades("BTC/USDC"),
+                serde_json::json!({"type": "subscribe", "channel": "trades", "market_id": "BTC/USDC"}),
+            ),
+            (
+                SubscribeTarget::all_trades(),
+                serde_json::json!({"type": "subscribe", "channel": "all_trades"}),
+            ),
+            (
+                SubscribeTarget::orderbook("BTC/USDC"),
+                serde_json::json!({"type": "subscribe", "channel": "orderbook", "market_id": "BTC/USDC"}),
+            ),
+            (
+                SubscribeTarget::user_fills("alice"),
+                serde_json::json!({"type": "subscribe", "channel": "user_fills", "user_address": "alice"}),
+            ),
+            (
+                SubscribeTarget::user_orders("alice"),
+                serde_json::json!({"type": "subscribe", "channel": "user_orders", "user_address": "alice"}),
+            ),
+            (
+                SubscribeTarget::user_orders_in_market("alice", "BTC/USDC"),
+                serde_json::json!({"type": "subscribe", "channel": "user_orders", "market_id": "BTC/USDC", "user_address": "alice"}),
+            ),
+            (
+                SubscribeTarget::user_balances("alice"),
+                serde_json::json!({"type": "subscribe", "channel": "user_balances", "user_address": "alice"}),
+            ),
+            (
+                SubscribeTarget::market_status("BTC/USDC"),
+                serde_json::json!({"type": "subscribe", "channel": "market_status", "market_id": "BTC/USDC"}),
+            ),
+            (
+                SubscribeTarget::all_market_statuses(),
+                serde_json::json!({"type": "subscribe", "channel": "market_status"}),
+            ),
+        ];
+
+        for (target, expected) in cases {
+            let message = serde_json::to_value(target.subscribe_message()).unwrap();
+            assert_eq!(message, expected, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn test_unsubscribe_target_keeps_channel_args() {
+        let message =
+            serde_json::to_value(SubscribeTarget::user_orders("bob").unsubscribe_message())
+                .unwrap();
+        assert_eq!(
+            message,
+            serde_json::json!({"type": "unsubscribe", "channel": "user_orders", "user_address": "bob"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_trades_multi_reports_rejected_markets() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Acknowledge the first and last subscribe, reject the middle one
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut received = 0;
+            while received < 3 {
+                let Some(Ok(Message::Text(_))) = ws.next().await else {
+                    continue;
+                };
+                received += 1;
+            }
+            for msg in [
+                r#"{"type":"subscribed","channel":"trades","market_id":"BTC/USDC"}"#,
+                r#"{"type":"error","message":"Subscription limit of 2 per connection reached"}"#,
+                r#"{"type":"subscribed","channel":"trades","market_id":"SOL/USDC"}"#,
+            ] {
+                ws.send(Message::Text(msg.into())).await.unwrap();
+            }
+            // Keep the connection open until the client is done
+            while ws.next().await.is_some() {}
+        });
+
+        let client = WebSocketClient::new(format!("ws://{}", addr));
+        let mut handle = client.connect().await.unwrap();
+
+        let markets = ["BTC/USDC", "ETH/USDC", "SOL/USDC"].map(String::from);
+        let group = handle.subscribe_trades_multi(&markets).unwrap();
+        assert_eq!(group.market_ids(), &markets);
+
+        match handle
+            .confirm_trades_multi(&group, Duration::from_secs(2))
+            .await
+        {
+            Err(SdkError::PartialSubscription { failed, errors }) => {
+                assert_eq!(failed, vec!["ETH/USDC".to_string()]);
+                assert_eq!(
+                    errors,
+                    vec!["Subscription limit of 2 per connection reached".to_string()]
+                );
+            }
+            other => panic!("expected PartialSubscription, got {:?}", other),
+        }
+    }
 }
@@ -4,6 +4,7 @@
 mod helpers;
 
 use backend::models::domain::{OrderType, Side};
+use exchange_sdk::SdkError;
 use helpers::TestExchange;
 
 // ============================================================================
@@ -355,7 +356,8 @@ async fn test_health_endpoint() {
         .await
         .expect("Health check should succeed");
 
-    assert!(!health.is_empty());
+    assert!(health.is_healthy());
+    assert!(health.failing_subsystems().is_empty());
 }
 
 #[tokio::test]
@@ -383,3 +385,21 @@ async fn test_concurrent_balance_queries() {
         assert!(result.is_ok(), "Balance query should succeed");
     }
 }
+
+#[tokio::test]
+async fn test_unknown_market_is_not_found() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    let err = fixture
+        .client
+        .get_market("FAKE/MARKET")
+        .await
+        .expect_err("Unknown market should fail");
+
+    match err {
+        SdkError::NotFound { code, .. } => assert_eq!(code, "MARKET_NOT_FOUND"),
+        other => panic!("Expected NotFound, got {:?}", other),
+    }
+}
@@ -1,3 +1,6 @@
+#![allow(dead_code, clippy::wrong_self_convention)]
+
+use backend::config::WebSocketConfig;
 use exchange_sdk::ExchangeClient;
 use exchange_test_utils::TestServer;
 
@@ -67,6 +70,23 @@ impl TestExchange {
         quote_decimals: u32,
     ) -> anyhow::Result<Self> {
         let server = TestServer::start().await?;
+        Self::on_server(server, base, quote, base_decimals, quote_decimals).await
+    }
+
+    /// Create the default BTC/USDC exchange with custom WebSocket settings
+    pub async fn with_ws_config(ws_config: WebSocketConfig) -> anyhow::Result<Self> {
+        let server = TestServer::start_with_ws_config(ws_config).await?;
+        Self::on_server(server, "BTC", "USDC", 6, 6).await
+    }
+
+    /// Set up the tokens and market on a started server
+    async fn on_server(
+        server: TestServer,
+        base: &str,
+        quote: &str,
+        base_decimals: u32,
+        quote_decimals: u32,
+    ) -> anyhow::Result<Self> {
         let client = ExchangeClient::new(&server.base_url);
 
         // Setup tokens via admin API
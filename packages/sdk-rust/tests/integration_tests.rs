@@ -2,7 +2,9 @@
 ///
 /// These are simple tests that verify basic SDK functionality.
 /// More comprehensive tests are in trading_tests.rs, websocket_tests.rs, and error_tests.rs.
-use exchange_sdk::ExchangeClient;
+use exchange_sdk::{
+    ComponentStatus, ExchangeClient, MarketSearchRequest, MarketSortBy, OverallStatus,
+};
 use exchange_test_utils::TestServer;
 
 #[tokio::test]
@@ -37,3 +39,88 @@ async fn test_get_tokens_empty() {
     let tokens = client.get_tokens().await.expect("Failed to get tokens");
     assert_eq!(tokens.len(), 0);
 }
+
+#[tokio::test]
+async fn test_health_reports_degraded_subsystem() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    let health = client.health().await.expect("Failed to get health");
+    assert_eq!(health.status, OverallStatus::Ok);
+    assert_eq!(health.postgres, ComponentStatus::Up);
+
+    // Take Postgres down; the pool is shared with the server
+    server.db().postgres.close().await;
+
+    // 503 is surfaced as a parsed status rather than an ApiError
+    let health = client.health().await.expect("Failed to get health");
+    assert_eq!(health.status, OverallStatus::Degraded);
+    assert_eq!(health.postgres, ComponentStatus::Down);
+    assert_eq!(health.clickhouse, ComponentStatus::Up);
+    assert_eq!(health.engine, ComponentStatus::Up);
+    assert_eq!(health.failing_subsystems(), vec!["postgres"]);
+
+    // The raw form still reports the failure as an error
+    assert!(client.health_raw().await.is_err());
+}
+
+#[tokio::test]
+async fn test_search_markets_filters_and_sorts() {
+    let server = TestServer::start()
+        .await
+        .expect("Failed to start test server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    for (ticker, decimals) in [
+        ("BTC", 8),
+        ("ETH", 18),
+        ("SOL", 9),
+        ("USDC", 6),
+        ("USDT", 6),
+    ] {
+        client
+            .admin_create_token(ticker.to_string(), decimals, ticker.to_string())
+            .await
+            .expect("Failed to create token");
+    }
+    for (base, quote) in [
+        ("SOL", "USDC"),
+        ("BTC", "USDC"),
+        ("ETH", "USDT"),
+        ("ETH", "USDC"),
+    ] {
+        client
+            .admin_create_market(base.to_string(), quote.to_string(), 1, 1, 1, 0, 0)
+            .await
+            .expect("Failed to create market");
+    }
+
+    // Quote filter keeps only USDC markets, alphabetical by id
+    let result = client
+        .search_markets(MarketSearchRequest {
+            quote_ticker: Some("USDC".to_string()),
+            sort_by: MarketSortBy::Alphabetical,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to search markets");
+    let ids: Vec<&str> = result.markets.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["BTC/USDC", "ETH/USDC", "SOL/USDC"]);
+    assert_eq!(result.total, 3);
+
+    // Base substring is case-insensitive and paging keeps the full total
+    let result = client
+        .search_markets(MarketSearchRequest {
+            base_ticker: Some("et".to_string()),
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to search markets");
+    let ids: Vec<&str> = result.markets.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["ETH/USDT"]);
+    assert_eq!(result.total, 2);
+}
@@ -1,5 +1,5 @@
 /// Quick test of admin endpoint
-use exchange_sdk::ExchangeClient;
+use exchange_sdk::{AdminCreateMarketSpec, ExchangeClient, FaucetGrant, SdkError};
 use exchange_test_utils::TestServer;
 
 #[tokio::test]
@@ -19,6 +19,63 @@ async fn test_admin_create_token() {
     assert!(result.is_ok(), "Should create token successfully");
 }
 
+#[tokio::test]
+async fn test_admin_create_token_rejects_bad_decimals_and_duplicates() {
+    let server = TestServer::start().await.expect("Failed to start server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    let err = client
+        .admin_create_token("BIG".to_string(), 30, "Too Precise".to_string())
+        .await
+        .expect_err("30 decimals should be rejected");
+    assert!(
+        matches!(err, SdkError::ApiError { status: 400, ref message } if message.contains("decimals")),
+        "{:?}",
+        err
+    );
+
+    client
+        .admin_create_token("BTC".to_string(), 8, "Bitcoin".to_string())
+        .await
+        .expect("Failed to create BTC");
+    let err = client
+        .admin_create_token("BTC".to_string(), 6, "Bitcoin Again".to_string())
+        .await
+        .expect_err("Duplicate ticker should be rejected");
+    assert!(
+        matches!(err, SdkError::ApiError { status: 409, .. }),
+        "{:?}",
+        err
+    );
+
+    // The original token is left untouched
+    let token = client.get_token("BTC").await.expect("Failed to get BTC");
+    assert_eq!(token.decimals, 8);
+}
+
+#[tokio::test]
+async fn test_admin_create_market_requires_existing_tokens() {
+    let server = TestServer::start().await.expect("Failed to start server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    client
+        .admin_create_token("USDC".to_string(), 6, "USD Coin".to_string())
+        .await
+        .expect("Failed to create USDC");
+
+    let err = client
+        .admin_create_market_with_spec(AdminCreateMarketSpec::new("NOPE", "USDC"))
+        .await
+        .expect_err("Market with an unknown base token should be rejected");
+    match err {
+        SdkError::NotFound { code, message } => {
+            assert_eq!(code, "TOKEN_NOT_FOUND");
+            assert!(message.contains("NOPE"), "{}", message);
+        }
+        other => panic!("Expected NotFound, got {:?}", other),
+    }
+}
+
 #[tokio::test]
 async fn test_admin_create_market() {
     let server = TestServer::start().await.expect("Failed to start server");
@@ -55,3 +112,98 @@ async fn test_admin_create_market() {
 
     assert!(result.is_ok(), "Should create market successfully");
 }
+
+#[tokio::test]
+async fn test_admin_create_market_with_spec_defaults() {
+    let server = TestServer::start().await.expect("Failed to start server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    for (ticker, name) in [("BTC", "Bitcoin"), ("USDC", "USD Coin")] {
+        client
+            .admin_create_token(ticker.to_string(), 8, name.to_string())
+            .await
+            .expect("Failed to create token");
+    }
+
+    let market = client
+        .admin_create_market_with_spec(AdminCreateMarketSpec::new("BTC", "USDC"))
+        .await
+        .expect("Failed to create market");
+
+    assert_eq!(market.id, "BTC/USDC");
+    assert_eq!(market.tick_size, AdminCreateMarketSpec::DEFAULT_TICK_SIZE);
+    assert_eq!(market.lot_size, AdminCreateMarketSpec::DEFAULT_LOT_SIZE);
+    assert_eq!(market.min_size, AdminCreateMarketSpec::DEFAULT_MIN_SIZE);
+    assert_eq!(market.maker_fee_bps, AdminCreateMarketSpec::DEFAULT_FEE_BPS);
+    assert_eq!(market.taker_fee_bps, AdminCreateMarketSpec::DEFAULT_FEE_BPS);
+    assert_eq!(market.min_price, None);
+    assert_eq!(market.max_price, None);
+}
+
+#[tokio::test]
+async fn test_admin_bulk_faucet() {
+    let server = TestServer::start().await.expect("Failed to start server");
+    let client = ExchangeClient::new(&server.base_url);
+
+    for (ticker, name) in [("BTC", "Bitcoin"), ("USDC", "USD Coin")] {
+        client
+            .admin_create_token(ticker.to_string(), 8, name.to_string())
+            .await
+            .expect("Failed to create token");
+    }
+
+    let grant = |user: &str, ticker: &str, amount: u128| FaucetGrant {
+        user_address: user.to_string(),
+        token_ticker: ticker.to_string(),
+        amount: amount.to_string(),
+    };
+    let balances = client
+        .admin_bulk_faucet(vec![
+            grant("alice", "BTC", 100),
+            grant("alice", "USDC", 200),
+            grant("bob", "BTC", 300),
+            grant("carol", "USDC", 400),
+            grant("carol", "USDC", 50), // Same pair twice adds up
+        ])
+        .await
+        .expect("Bulk faucet failed");
+
+    let returned: Vec<_> = balances
+        .iter()
+        .map(|b| (b.user_address.as_str(), b.token_ticker.as_str(), b.amount))
+        .collect();
+    assert_eq!(
+        returned,
+        vec![
+            ("alice", "BTC", 100),
+            ("alice", "USDC", 200),
+            ("bob", "BTC", 300),
+            ("carol", "USDC", 450),
+        ]
+    );
+
+    // Every user was created and funded
+    for (user, ticker, amount) in returned {
+        let stored = client
+            .get_balances(user)
+            .await
+            .expect("Failed to get balances");
+        assert!(
+            stored
+                .iter()
+                .any(|b| b.token_ticker == ticker && b.amount == amount),
+            "{} {} {:?}",
+            user,
+            ticker,
+            stored
+        );
+    }
+
+    // An unknown token rejects the whole request
+    let result = client
+        .admin_bulk_faucet(vec![grant("dave", "BTC", 1), grant("dave", "NOPE", 1)])
+        .await;
+    assert!(result.is_err());
+    let dave = client.get_balances("dave").await.unwrap_or_default();
+    assert!(dave.is_empty());
+}
@@ -4,8 +4,10 @@
 /// using ONLY the public REST and WebSocket APIs (no direct DB access for verification).
 mod helpers;
 
-use backend::models::domain::{OrderType, Side};
+use backend::models::domain::{OrderStatus, OrderType, Side, Trade};
+use exchange_sdk::{AdminCreateMarketSpec, SdkError};
 use helpers::TestExchange;
+use std::time::Duration;
 
 // ============================================================================
 // Basic Trading Workflows
@@ -212,6 +214,158 @@ async fn test_partial_fill() {
     );
 }
 
+#[tokio::test]
+async fn test_positions_track_net_size_and_average_entry() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("maker", 10_000_000, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create maker");
+    fixture
+        .create_user_with_balance("trader", 0, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create trader");
+
+    // (resting seller, price, size), each bought by the other user
+    let fills = [
+        ("maker", "50000000000", "2000000"),
+        ("maker", "52000000000", "2000000"),
+        ("trader", "55000000000", "1000000"),
+    ];
+    for (seller, price, size) in fills {
+        let buyer = if seller == "maker" { "trader" } else { "maker" };
+        for (user, side) in [(seller, Side::Sell), (buyer, Side::Buy)] {
+            fixture
+                .client
+                .place_order(
+                    user.to_string(),
+                    fixture.market_id.clone(),
+                    side,
+                    OrderType::Limit,
+                    price.to_string(),
+                    size.to_string(),
+                    "test_sig".to_string(),
+                )
+                .await
+                .expect("Failed to place order");
+        }
+    }
+
+    // Bought 2 BTC at $50k and 2 at $52k, then sold 1: 3 BTC left at a $51k average
+    let positions = fixture
+        .client
+        .get_positions("trader")
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].market_id, fixture.market_id);
+    assert_eq!(positions[0].size, 3_000_000);
+    assert_eq!(positions[0].avg_entry_price, Some(51_000_000_000));
+
+    // The other side of those fills is short by the same amount
+    let positions = fixture
+        .client
+        .get_positions("maker")
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(positions[0].size, -3_000_000);
+    assert_eq!(positions[0].avg_entry_price, Some(51_000_000_000));
+
+    // Selling the rest leaves the trader flat
+    for (user, side) in [("trader", Side::Sell), ("maker", Side::Buy)] {
+        fixture
+            .client
+            .place_order(
+                user.to_string(),
+                fixture.market_id.clone(),
+                side,
+                OrderType::Limit,
+                "55000000000".to_string(),
+                "3000000".to_string(),
+                "test_sig".to_string(),
+            )
+            .await
+            .expect("Failed to place order");
+    }
+    let positions = fixture
+        .client
+        .get_positions("trader")
+        .await
+        .expect("Failed to get positions");
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].size, 0);
+    assert_eq!(positions[0].avg_entry_price, None);
+}
+
+#[tokio::test]
+async fn test_place_order_display_converts_to_atoms() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    // A 6-decimal market with a 0.01 USDC tick and 0.01 SOL lot
+    fixture
+        .client
+        .admin_create_token("SOL".to_string(), 6, "SOL Token".to_string())
+        .await
+        .expect("Failed to create SOL");
+    let market = fixture
+        .client
+        .admin_create_market_with_spec(
+            AdminCreateMarketSpec::new("SOL", "USDC")
+                .tick_size(10_000)
+                .lot_size(10_000)
+                .min_size(10_000),
+        )
+        .await
+        .expect("Failed to create market");
+    fixture
+        .create_user_with_balance("buyer", 0, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create buyer");
+
+    let placed = fixture
+        .client
+        .place_order_display(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            "95000.00",
+            "0.01",
+            "test_sig",
+        )
+        .await
+        .expect("Failed to place order");
+    assert_eq!(placed.order.price, 95_000_000_000);
+    assert_eq!(placed.order.size, 10_000);
+
+    // Less than one lot rounds to nothing and never reaches the exchange
+    let err = fixture
+        .client
+        .place_order_display(
+            "buyer",
+            &market.id,
+            Side::Buy,
+            OrderType::Limit,
+            "95000.00",
+            "0.001",
+            "test_sig",
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SdkError::InvalidOrder(_)), "{:?}", err);
+    let orders = fixture
+        .client
+        .get_orders("buyer", Some(market.id.clone()))
+        .await
+        .expect("Failed to get orders");
+    assert_eq!(orders.len(), 1);
+}
+
 #[tokio::test]
 async fn test_order_cancellation() {
     let fixture = TestExchange::new()
@@ -268,6 +422,380 @@ async fn test_order_cancellation() {
     assert_eq!(pending_orders.len(), 0);
 }
 
+#[tokio::test]
+async fn test_order_history_lists_filled_and_cancelled_orders() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("seller", 10_000_000, 0)
+        .await
+        .expect("Failed to create seller");
+    fixture
+        .create_user_with_balance("buyer", 0, 200_000_000_000)
+        .await
+        .expect("Failed to create buyer");
+
+    let place = |side: Side, price: &str| {
+        let user = match side {
+            Side::Sell => "seller",
+            Side::Buy => "buyer",
+        };
+        fixture.client.place_order(
+            user.to_string(),
+            fixture.market_id.clone(),
+            side,
+            OrderType::Limit,
+            price.to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+    };
+
+    // Filled: a resting sell taken by a buy
+    let filled = place(Side::Sell, "50000000000")
+        .await
+        .expect("Failed to place sell order");
+    place(Side::Buy, "50000000000")
+        .await
+        .expect("Failed to place buy order");
+
+    // Cancelled
+    let cancelled = place(Side::Sell, "51000000000")
+        .await
+        .expect("Failed to place sell order");
+    fixture
+        .client
+        .cancel_order(
+            "seller".to_string(),
+            cancelled.order.id.to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to cancel order");
+
+    // Still resting, so not history
+    let open = place(Side::Sell, "52000000000")
+        .await
+        .expect("Failed to place sell order");
+
+    let history = fixture
+        .client
+        .get_order_history("seller", Some(fixture.market_id.clone()), None, None, None)
+        .await
+        .expect("Failed to get order history");
+
+    let statuses: Vec<_> = history.iter().map(|o| (o.id, o.status)).collect();
+    assert_eq!(statuses.len(), 2, "{:?}", statuses);
+    assert!(statuses.contains(&(filled.order.id, OrderStatus::Filled)));
+    assert!(statuses.contains(&(cancelled.order.id, OrderStatus::Cancelled)));
+    assert!(history.iter().all(|o| o.id != open.order.id));
+
+    // Most recently finished first
+    assert!(history
+        .windows(2)
+        .all(|pair| pair[0].updated_at >= pair[1].updated_at));
+
+    // A window ending before the first order finished is empty
+    let before = filled.order.created_at.timestamp();
+    let empty = fixture
+        .client
+        .get_order_history("seller", None, None, Some(before), Some(10))
+        .await
+        .expect("Failed to get order history");
+    assert!(empty.is_empty());
+}
+
+#[tokio::test]
+async fn test_wait_for_order_returns_filled_order() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("alice", 10_000_000, 0)
+        .await
+        .expect("Failed to create alice");
+    fixture
+        .create_user_with_balance("bob", 0, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create bob");
+
+    let resting = fixture
+        .client
+        .place_order(
+            "alice".to_string(),
+            fixture.market_id.clone(),
+            Side::Sell,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place alice's order");
+    let order_id = resting.order.id.to_string();
+
+    // Still open when the timeout elapses
+    let err = fixture
+        .client
+        .wait_for_order("alice", &order_id, Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SdkError::Timeout), "{:?}", err);
+
+    // Start waiting, then cross the order
+    let client = fixture.client.clone();
+    let waiting_id = order_id.clone();
+    let waiter = tokio::spawn(async move {
+        client
+            .wait_for_order("alice", &waiting_id, Duration::from_secs(10))
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    fixture
+        .client
+        .place_order(
+            "bob".to_string(),
+            fixture.market_id.clone(),
+            Side::Buy,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place bob's order");
+
+    let filled = waiter
+        .await
+        .expect("Waiter panicked")
+        .expect("Order should reach a terminal state");
+    assert_eq!(filled.id, resting.order.id);
+    assert_eq!(filled.status, OrderStatus::Filled);
+    assert_eq!(filled.filled_size, 1_000_000);
+
+    // Already terminal, returns straight away
+    let again = tokio::time::timeout(
+        Duration::from_secs(1),
+        fixture
+            .client
+            .wait_for_order("alice", &order_id, Duration::from_secs(10)),
+    )
+    .await
+    .expect("Terminal order should return immediately")
+    .expect("Failed to get order");
+    assert_eq!(again.status, OrderStatus::Filled);
+}
+
+#[tokio::test]
+async fn test_place_order_with_deadline_cancels_unfilled_order() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("alice", 10_000_000, 0)
+        .await
+        .expect("Failed to create alice");
+    fixture
+        .create_user_with_balance("bob", 0, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create bob");
+
+    // Nothing to cross, the whole order is cancelled at the deadline
+    let result = fixture
+        .client
+        .place_order_with_deadline(
+            "bob".to_string(),
+            fixture.market_id.clone(),
+            Side::Buy,
+            "40000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+            Duration::from_millis(300),
+        )
+        .await
+        .expect("Failed to place order with deadline");
+    assert!(result.auto_cancelled);
+    assert_eq!(result.order.id, result.placed.order.id);
+    assert_eq!(result.order.status, OrderStatus::Cancelled);
+    assert_eq!(result.order.filled_size, 0);
+
+    let open = fixture
+        .client
+        .get_orders("bob", Some(fixture.market_id.clone()))
+        .await
+        .expect("Failed to get orders");
+    assert!(open.is_empty());
+
+    // Half the size crosses on placement, only the remainder is cancelled
+    fixture
+        .client
+        .place_order(
+            "alice".to_string(),
+            fixture.market_id.clone(),
+            Side::Sell,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place alice's order");
+
+    let result = fixture
+        .client
+        .place_order_with_deadline(
+            "bob".to_string(),
+            fixture.market_id.clone(),
+            Side::Buy,
+            "50000000000".to_string(),
+            "2000000".to_string(),
+            "test_sig".to_string(),
+            Duration::from_millis(300),
+        )
+        .await
+        .expect("Failed to place order with deadline");
+    assert!(result.auto_cancelled);
+    assert_eq!(result.placed.filled_size(), 1_000_000);
+    assert_eq!(result.order.status, OrderStatus::Cancelled);
+    assert_eq!(result.order.filled_size, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_market_full_includes_both_tokens() {
+    let fixture = TestExchange::with_market_and_decimals("BTC", "USDC", 8, 6)
+        .await
+        .expect("Failed to create test exchange");
+
+    let full = fixture
+        .client
+        .get_market_full(&fixture.market_id)
+        .await
+        .expect("Failed to get market with tokens");
+
+    assert_eq!(full.market.id, fixture.market_id);
+    assert_eq!(full.base_token.ticker, "BTC");
+    assert_eq!(full.base_token.decimals, 8);
+    assert_eq!(full.quote_token.ticker, "USDC");
+    assert_eq!(full.quote_token.decimals, 6);
+}
+
+#[tokio::test]
+async fn test_markets_by_ids_returns_only_requested() {
+    let fixture = TestExchange::with_market("BTC", "USDC")
+        .await
+        .expect("Failed to create test exchange");
+
+    let mut market_ids = vec![fixture.market_id.clone()];
+    for base in ["ETH", "SOL"] {
+        fixture
+            .client
+            .admin_create_token(base.to_string(), 6, format!("{} Token", base))
+            .await
+            .expect("Failed to create token");
+        let market = fixture
+            .client
+            .admin_create_market(
+                base.to_string(),
+                "USDC".to_string(),
+                1000,
+                1000000,
+                1000000,
+                10,
+                20,
+            )
+            .await
+            .expect("Failed to create market");
+        market_ids.push(market.id);
+    }
+
+    let markets = fixture
+        .client
+        .get_markets_by_ids(&[&market_ids[0], &market_ids[2], "DOGE/USDC"])
+        .await
+        .expect("Failed to get markets by ids");
+    let mut returned: Vec<_> = markets.iter().map(|m| m.id.clone()).collect();
+    returned.sort();
+    let mut expected = vec![market_ids[0].clone(), market_ids[2].clone()];
+    expected.sort();
+    assert_eq!(returned, expected);
+
+    let tokens = fixture
+        .client
+        .get_tokens_by_tickers(&["ETH", "USDC"])
+        .await
+        .expect("Failed to get tokens by tickers");
+    let tickers: Vec<_> = tokens.iter().map(|t| t.ticker.as_str()).collect();
+    assert_eq!(tickers, vec!["ETH", "USDC"]);
+}
+
+#[tokio::test]
+async fn test_export_trades_csv() {
+    let fixture = TestExchange::with_market_and_decimals("BTC", "USDC", 8, 6)
+        .await
+        .expect("Failed to create test exchange");
+
+    // Seed the tape directly so timestamps are known; the last trade is outside the range
+    let seeded = [
+        (
+            1,
+            1_700_000_000,
+            50_000_000_000u128,
+            150_000_000u128,
+            Side::Buy,
+        ),
+        (2, 1_700_000_060, 50_100_500_000, 2_500_000, Side::Sell),
+        (3, 1_700_000_200, 49_000_000_000, 100_000_000, Side::Buy),
+    ];
+    for (n, timestamp, price, size, side) in seeded {
+        let trade = Trade {
+            id: format!("00000000-0000-0000-0000-{:012}", n)
+                .parse()
+                .unwrap(),
+            market_id: fixture.market_id.clone(),
+            buyer_address: "buyer".to_string(),
+            seller_address: "seller".to_string(),
+            buyer_order_id: format!("00000000-0000-0000-0001-{:012}", n)
+                .parse()
+                .unwrap(),
+            seller_order_id: format!("00000000-0000-0000-0002-{:012}", n)
+                .parse()
+                .unwrap(),
+            price,
+            size,
+            side,
+            timestamp: chrono::DateTime::from_timestamp(timestamp, 0).unwrap(),
+            sequence: n,
+        };
+        fixture
+            .server
+            .test_db
+            .db
+            .insert_trade_to_clickhouse(&trade)
+            .await
+            .expect("Failed to insert trade");
+    }
+
+    let mut csv = Vec::new();
+    let rows = fixture
+        .client
+        .export_trades_csv(&fixture.market_id, 1_700_000_000, 1_700_000_100, &mut csv)
+        .await
+        .expect("Failed to export trades");
+
+    assert_eq!(rows, 2);
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "timestamp,price_display,size_display,side\n\
+         2023-11-14T22:14:20+00:00,50100.50,0.025,sell\n\
+         2023-11-14T22:13:20+00:00,50000.00,1.5,buy\n"
+    );
+}
+
 #[tokio::test]
 async fn test_market_info_endpoints() {
     let fixture = TestExchange::new()
@@ -3,9 +3,14 @@
 /// These tests verify real-time event streams using only the WebSocket API.
 mod helpers;
 
-use backend::models::domain::{OrderType, Side};
-use exchange_sdk::{SubscriptionChannel, WebSocketClient};
+use backend::config::WebSocketConfig;
+use backend::models::domain::{OrderType, OrderbookLevel, Side};
+use exchange_sdk::{
+    BookUpdate, LocalOrderbook, ServerMessage, SubscribeTarget, Wallet, WebSocketClient,
+};
 use helpers::TestExchange;
+use std::collections::HashSet;
+use std::time::Duration;
 
 // ============================================================================
 // WebSocket Subscription Tests
@@ -35,11 +40,7 @@ async fn test_websocket_trade_events() {
         .expect("Failed to connect to WebSocket");
 
     ws_handle
-        .subscribe(
-            SubscriptionChannel::Trades,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .subscribe(SubscribeTarget::trades(fixture.market_id.clone()))
         .expect("Failed to subscribe to trades");
 
     // Wait for subscription confirmation
@@ -131,11 +132,7 @@ async fn test_websocket_orderbook_events() {
         .expect("Failed to connect to WebSocket");
 
     ws_handle
-        .subscribe(
-            SubscriptionChannel::Orderbook,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .subscribe(SubscribeTarget::orderbook(fixture.market_id.clone()))
         .expect("Failed to subscribe to orderbook");
 
     // Wait for subscription confirmation
@@ -179,18 +176,105 @@ async fn test_websocket_orderbook_events() {
     assert!(orderbook_received, "Failed to receive orderbook event");
 }
 
+#[tokio::test]
+async fn test_websocket_local_orderbook_mirrors_server_book() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("maker", 10_000_000, 100_000_000_000)
+        .await
+        .expect("Failed to create maker");
+    fixture
+        .create_user_with_balance("taker", 0, 100_000_000_000)
+        .await
+        .expect("Failed to create taker");
+
+    let ws_client = WebSocketClient::new(&fixture.server.ws_url);
+    let mut ws_handle = ws_client
+        .connect()
+        .await
+        .expect("Failed to connect to WebSocket");
+    let mut book = ws_handle
+        .subscribe_orderbook(fixture.market_id.clone())
+        .expect("Failed to subscribe to orderbook");
+
+    let place = |user: &str, side: Side, price: &str, size: &str| {
+        fixture.client.place_order(
+            user.to_string(),
+            fixture.market_id.clone(),
+            side,
+            OrderType::Limit,
+            price.to_string(),
+            size.to_string(),
+            "test_sig".to_string(),
+        )
+    };
+    place("maker", Side::Sell, "50000000000", "1000000")
+        .await
+        .expect("Failed to place ask");
+    place("maker", Side::Sell, "51000000000", "2000000")
+        .await
+        .expect("Failed to place ask");
+
+    // As after a reconnect: drop local state and take a fresh snapshot
+    ws_handle
+        .resync_orderbook(&mut book)
+        .expect("Failed to request snapshot");
+
+    place("maker", Side::Buy, "49000000000", "1000000")
+        .await
+        .expect("Failed to place bid");
+    // Takes the best ask
+    place("taker", Side::Buy, "50000000000", "1000000")
+        .await
+        .expect("Failed to place taker order");
+
+    let expected_bids = vec![(49_000_000_000u128, 1_000_000u128)];
+    let expected_asks = vec![(51_000_000_000u128, 2_000_000u128)];
+    let matches_expected = |book: &LocalOrderbook| {
+        let pairs = |levels: &[OrderbookLevel]| -> Vec<(u128, u128)> {
+            levels.iter().map(|l| (l.price, l.size)).collect()
+        };
+        let (bids, asks) = book.levels();
+        book.is_synced() && pairs(bids) == expected_bids && pairs(asks) == expected_asks
+    };
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while !matches_expected(&book) {
+        let value = tokio::time::timeout_at(deadline, ws_handle.recv())
+            .await
+            .expect("Local book never matched the server's final state")
+            .expect("Connection closed");
+        if let Ok(msg) = serde_json::from_value::<ServerMessage>(value) {
+            let update = ws_handle
+                .sync_orderbook(&mut book, &msg)
+                .expect("Failed to sync orderbook");
+            assert_ne!(update, BookUpdate::ResyncNeeded);
+        }
+    }
+
+    assert_eq!(book.best_bid().map(|l| l.price), Some(49_000_000_000));
+    assert_eq!(book.best_ask().map(|l| l.price), Some(51_000_000_000));
+}
+
 #[tokio::test]
 async fn test_websocket_user_events() {
     let fixture = TestExchange::new()
         .await
         .expect("Failed to create test exchange");
+    let alice_wallet = Wallet::from_seed("alice");
+    let alice = alice_wallet.address();
+    let bob_wallet = Wallet::from_seed("bob");
+    let bob = bob_wallet.address();
 
     fixture
-        .create_user_with_balance("alice", 10_000_000, 0)
+        .create_user_with_balance(&alice, 10_000_000, 0)
         .await
         .expect("Failed to create alice");
     fixture
-        .create_user_with_balance("bob", 0, 100_000_000_000_000_000) // 100M USDC (enough for trade + fees)
+        .create_user_with_balance(&bob, 0, 100_000_000_000_000_000) // 100M USDC (enough for trade + fees)
         .await
         .expect("Failed to create bob");
 
@@ -202,11 +286,12 @@ async fn test_websocket_user_events() {
         .expect("Failed to connect to WebSocket");
 
     alice_ws
-        .subscribe(
-            SubscriptionChannel::UserOrders,
-            None,
-            Some("alice".to_string()),
-        )
+        .sign_in(&alice_wallet)
+        .await
+        .expect("Failed to sign in");
+
+    alice_ws
+        .subscribe(SubscribeTarget::user_orders(&alice))
         .expect("Failed to subscribe to user orders");
 
     // Wait for subscription confirmation
@@ -216,7 +301,7 @@ async fn test_websocket_user_events() {
     fixture
         .client
         .place_order(
-            "alice".to_string(),
+            alice.clone(),
             fixture.market_id.clone(),
             Side::Sell,
             OrderType::Limit,
@@ -251,7 +336,7 @@ async fn test_websocket_user_events() {
     fixture
         .client
         .place_order(
-            "bob".to_string(),
+            bob.clone(),
             fixture.market_id.clone(),
             Side::Buy,
             OrderType::Limit,
@@ -290,13 +375,17 @@ async fn test_websocket_user_fills() {
     let fixture = TestExchange::new()
         .await
         .expect("Failed to create test exchange");
+    let alice_wallet = Wallet::from_seed("alice");
+    let alice = alice_wallet.address();
+    let bob_wallet = Wallet::from_seed("bob");
+    let bob = bob_wallet.address();
 
     fixture
-        .create_user_with_balance("alice", 10_000_000, 0)
+        .create_user_with_balance(&alice, 10_000_000, 0)
         .await
         .expect("Failed to create alice");
     fixture
-        .create_user_with_balance("bob", 0, 100_000_000_000_000_000) // 100M USDC
+        .create_user_with_balance(&bob, 0, 100_000_000_000_000_000) // 100M USDC
         .await
         .expect("Failed to create bob");
 
@@ -308,11 +397,12 @@ async fn test_websocket_user_fills() {
         .expect("Failed to connect to WebSocket");
 
     bob_ws
-        .subscribe(
-            SubscriptionChannel::UserFills,
-            None,
-            Some("bob".to_string()),
-        )
+        .sign_in(&bob_wallet)
+        .await
+        .expect("Failed to sign in");
+
+    bob_ws
+        .subscribe(SubscribeTarget::user_fills(&bob))
         .expect("Failed to subscribe to user fills");
 
     // Wait for subscription confirmation
@@ -322,7 +412,7 @@ async fn test_websocket_user_fills() {
     fixture
         .client
         .place_order(
-            "alice".to_string(),
+            alice.clone(),
             fixture.market_id.clone(),
             Side::Sell,
             OrderType::Limit,
@@ -337,7 +427,7 @@ async fn test_websocket_user_fills() {
     fixture
         .client
         .place_order(
-            "bob".to_string(),
+            bob.clone(),
             fixture.market_id.clone(),
             Side::Buy,
             OrderType::Limit,
@@ -359,8 +449,8 @@ async fn test_websocket_user_fills() {
         {
             if msg["type"] == "user_fill" {
                 // Verify it's Bob's fill
-                assert_eq!(msg["trade"]["buyer_address"], "bob");
-                assert_eq!(msg["trade"]["seller_address"], "alice");
+                assert_eq!(msg["trade"]["buyer_address"], bob);
+                assert_eq!(msg["trade"]["seller_address"], alice);
                 assert_eq!(msg["trade"]["price"], "50000000000");
                 assert_eq!(msg["trade"]["size"], "1000000");
                 fill_received = true;
@@ -376,9 +466,11 @@ async fn test_websocket_user_balances() {
     let fixture = TestExchange::new()
         .await
         .expect("Failed to create test exchange");
+    let trader_wallet = Wallet::from_seed("trader");
+    let trader = trader_wallet.address();
 
     fixture
-        .create_user_with_balance("trader", 10_000_000, 100_000_000_000_000_000)
+        .create_user_with_balance(&trader, 10_000_000, 100_000_000_000_000_000)
         .await
         .expect("Failed to create trader");
 
@@ -390,11 +482,12 @@ async fn test_websocket_user_balances() {
         .expect("Failed to connect to WebSocket");
 
     trader_ws
-        .subscribe(
-            SubscriptionChannel::UserBalances,
-            None,
-            Some("trader".to_string()),
-        )
+        .sign_in(&trader_wallet)
+        .await
+        .expect("Failed to sign in");
+
+    trader_ws
+        .subscribe(SubscribeTarget::user_balances(&trader))
         .expect("Failed to subscribe to user balances");
 
     // Wait for subscription confirmation
@@ -404,7 +497,7 @@ async fn test_websocket_user_balances() {
     fixture
         .client
         .place_order(
-            "trader".to_string(),
+            trader.clone(),
             fixture.market_id.clone(),
             Side::Buy,
             OrderType::Limit,
@@ -425,7 +518,7 @@ async fn test_websocket_user_balances() {
                 .flatten()
         {
             if msg["type"] == "user_balance" {
-                assert_eq!(msg["user_address"], "trader");
+                assert_eq!(msg["user_address"], trader);
                 // Check that balance is locked (non-zero locked amount for USDC)
                 if msg["token_ticker"] == "USDC" && msg["locked"] != "0" {
                     balance_locked_received = true;
@@ -440,14 +533,148 @@ async fn test_websocket_user_balances() {
     );
 }
 
+#[tokio::test]
+async fn test_websocket_collect_trade_events() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+    let alice_wallet = Wallet::from_seed("alice");
+    let alice = alice_wallet.address();
+    let bob_wallet = Wallet::from_seed("bob");
+    let bob = bob_wallet.address();
+
+    fixture
+        .create_user_with_balance(&alice, 10_000_000, 0)
+        .await
+        .expect("Failed to create alice");
+    fixture
+        .create_user_with_balance(&bob, 0, 100_000_000_000_000_000)
+        .await
+        .expect("Failed to create bob");
+
+    let ws_client = WebSocketClient::new(&fixture.server.ws_url);
+    let mut ws_handle = ws_client
+        .connect()
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    ws_handle
+        .sign_in(&bob_wallet)
+        .await
+        .expect("Failed to sign in");
+
+    for target in [
+        SubscribeTarget::trades(fixture.market_id.clone()),
+        SubscribeTarget::orderbook(fixture.market_id.clone()),
+        SubscribeTarget::user_balances(&bob),
+    ] {
+        ws_handle.subscribe(target).expect("Failed to subscribe");
+    }
+
+    let mut confirmations = 0;
+    ws_handle
+        .collect_until(
+            |msg| {
+                if matches!(msg, ServerMessage::Subscribed { .. }) {
+                    confirmations += 1;
+                }
+                confirmations == 3
+            },
+            Duration::from_secs(5),
+        )
+        .await;
+    assert_eq!(
+        confirmations, 3,
+        "Failed to receive subscription confirmations"
+    );
+
+    fixture
+        .client
+        .place_order(
+            alice.clone(),
+            fixture.market_id.clone(),
+            Side::Sell,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place alice's order");
+
+    // Drain snapshots and the resting order's book update
+    ws_handle
+        .collect_until(|_| false, Duration::from_millis(500))
+        .await;
+
+    fixture
+        .client
+        .place_order(
+            bob.clone(),
+            fixture.market_id.clone(),
+            Side::Buy,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place bob's order");
+
+    let expected: HashSet<String> = [
+        "trade".to_string(),
+        "orderbook".to_string(),
+        format!("balance:{}", fixture.base_ticker),
+        format!("balance:{}", fixture.quote_ticker),
+    ]
+    .into();
+    let kind = |msg: &ServerMessage| match msg {
+        ServerMessage::Trade { .. } => Some("trade".to_string()),
+        ServerMessage::Orderbook { .. } => Some("orderbook".to_string()),
+        ServerMessage::UserBalance { token_ticker, .. } => {
+            Some(format!("balance:{}", token_ticker))
+        }
+        _ => None,
+    };
+
+    let mut seen = HashSet::new();
+    let events = ws_handle
+        .collect_until(
+            |msg| {
+                seen.extend(kind(msg));
+                seen.is_superset(&expected)
+            },
+            Duration::from_secs(5),
+        )
+        .await;
+
+    let kinds: HashSet<String> = events.iter().filter_map(kind).collect();
+    assert!(
+        kinds.is_superset(&expected),
+        "Missing events, got {:?}",
+        kinds
+    );
+    let trade = events
+        .iter()
+        .find_map(|msg| match msg {
+            ServerMessage::Trade { trade } => Some(trade),
+            _ => None,
+        })
+        .expect("Trade event");
+    assert_eq!(trade.buyer_address, bob);
+    assert_eq!(trade.seller_address, alice);
+}
+
 #[tokio::test]
 async fn test_websocket_multiple_subscriptions() {
     let fixture = TestExchange::new()
         .await
         .expect("Failed to create test exchange");
+    let trader_wallet = Wallet::from_seed("trader");
+    let trader = trader_wallet.address();
 
     fixture
-        .create_user_with_balance("trader", 10_000_000, 100_000_000_000_000_000) // 10 BTC + 100M USDC
+        .create_user_with_balance(&trader, 10_000_000, 100_000_000_000_000_000) // 10 BTC + 100M USDC
         .await
         .expect("Failed to create trader");
 
@@ -459,27 +686,20 @@ async fn test_websocket_multiple_subscriptions() {
         .expect("Failed to connect to WebSocket");
 
     ws_handle
-        .subscribe(
-            SubscriptionChannel::Trades,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .subscribe(SubscribeTarget::trades(fixture.market_id.clone()))
         .expect("Failed to subscribe to trades");
 
     ws_handle
-        .subscribe(
-            SubscriptionChannel::Orderbook,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .subscribe(SubscribeTarget::orderbook(fixture.market_id.clone()))
         .expect("Failed to subscribe to orderbook");
 
     ws_handle
-        .subscribe(
-            SubscriptionChannel::UserBalances,
-            None,
-            Some("trader".to_string()),
-        )
+        .sign_in(&trader_wallet)
+        .await
+        .expect("Failed to sign in");
+
+    ws_handle
+        .subscribe(SubscribeTarget::user_balances(&trader))
         .expect("Failed to subscribe to user balances");
 
     // Wait for subscription confirmations
@@ -505,6 +725,90 @@ async fn test_websocket_multiple_subscriptions() {
     );
 }
 
+#[tokio::test]
+async fn test_websocket_trades_multi_subscribe_and_unsubscribe() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    // Two more markets quoted in the fixture's quote token
+    let mut market_ids = vec![fixture.market_id.clone()];
+    for base in ["ETH", "SOL"] {
+        fixture
+            .client
+            .admin_create_token(base.to_string(), 6, format!("{} Token", base))
+            .await
+            .expect("Failed to create token");
+        let market = fixture
+            .client
+            .admin_create_market(
+                base.to_string(),
+                fixture.quote_ticker.clone(),
+                1000,
+                1000000,
+                1000000,
+                10,
+                20,
+            )
+            .await
+            .expect("Failed to create market");
+        market_ids.push(market.id);
+    }
+
+    let ws_client = WebSocketClient::new(&fixture.server.ws_url);
+    let mut ws_handle = ws_client
+        .connect()
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    let group = ws_handle
+        .subscribe_trades_multi(&market_ids)
+        .expect("Failed to subscribe to trades");
+
+    let messages = ws_handle
+        .confirm_trades_multi(&group, Duration::from_secs(5))
+        .await
+        .expect("Every market should be acknowledged");
+    let acked: HashSet<String> = messages
+        .iter()
+        .filter_map(|msg| match msg {
+            ServerMessage::Subscribed {
+                market_id: Some(market_id),
+                ..
+            } => Some(market_id.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(acked, market_ids.iter().cloned().collect());
+
+    ws_handle
+        .unsubscribe_trades_multi(&group)
+        .expect("Failed to unsubscribe from trades");
+
+    let mut unsubscribed = HashSet::new();
+    let messages = ws_handle
+        .collect_until(
+            |msg| {
+                if let ServerMessage::Unsubscribed {
+                    market_id: Some(market_id),
+                    ..
+                } = msg
+                {
+                    unsubscribed.insert(market_id.clone());
+                }
+                unsubscribed.len() == market_ids.len()
+            },
+            Duration::from_secs(5),
+        )
+        .await;
+    assert_eq!(
+        unsubscribed,
+        market_ids.iter().cloned().collect(),
+        "Missing unsubscribe acks in {:?}",
+        messages
+    );
+}
+
 #[tokio::test]
 async fn test_websocket_unsubscribe() {
     let fixture = TestExchange::new()
@@ -519,22 +823,14 @@ async fn test_websocket_unsubscribe() {
 
     // Subscribe
     ws_handle
-        .subscribe(
-            SubscriptionChannel::Trades,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .subscribe(SubscribeTarget::trades(fixture.market_id.clone()))
         .expect("Failed to subscribe");
 
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
     // Unsubscribe
     ws_handle
-        .unsubscribe(
-            SubscriptionChannel::Trades,
-            Some(fixture.market_id.clone()),
-            None,
-        )
+        .unsubscribe(SubscribeTarget::trades(fixture.market_id.clone()))
         .expect("Failed to unsubscribe");
 
     // Wait for unsubscribe confirmation
@@ -587,3 +883,82 @@ async fn test_websocket_ping_pong() {
     }
     assert!(pong_received, "Did not receive pong response");
 }
+
+#[tokio::test]
+async fn test_websocket_compressed_messages_round_trip() {
+    let fixture = TestExchange::with_ws_config(WebSocketConfig {
+        compression: true,
+        ..Default::default()
+    })
+    .await
+    .expect("Failed to create test exchange");
+
+    fixture
+        .create_user_with_balance("trader", 10_000_000, 0)
+        .await
+        .expect("Failed to create trader");
+
+    let mut ws_handle = WebSocketClient::new(&fixture.server.ws_url)
+        .with_compression(true)
+        .connect()
+        .await
+        .expect("Failed to connect to WebSocket");
+    assert!(ws_handle.is_compressed(), "Compression was not negotiated");
+
+    ws_handle
+        .subscribe(SubscribeTarget::orderbook(fixture.market_id.clone()))
+        .expect("Failed to subscribe to orderbook");
+    ws_handle.ping().expect("Failed to send ping");
+
+    fixture
+        .client
+        .place_order(
+            "trader".to_string(),
+            fixture.market_id.clone(),
+            Side::Sell,
+            OrderType::Limit,
+            "50000000000".to_string(),
+            "1000000".to_string(),
+            "test_sig".to_string(),
+        )
+        .await
+        .expect("Failed to place order");
+
+    // Replies and pushed updates alike arrive inflated back into their JSON
+    let mut pending = HashSet::from(["subscribed", "pong", "orderbook"]);
+    while !pending.is_empty() {
+        let msg = tokio::time::timeout(Duration::from_secs(2), ws_handle.recv())
+            .await
+            .expect("Timed out waiting for a compressed message")
+            .expect("Connection closed");
+        match msg["type"].as_str() {
+            // Wait for the update carrying the new ask
+            Some("orderbook") if msg["orderbook"]["asks"][0]["price"] != "50000000000" => {}
+            Some(kind) => {
+                pending.remove(kind);
+            }
+            None => panic!("Message without a type: {}", msg),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_compression_falls_back_when_server_declines() {
+    let fixture = TestExchange::new()
+        .await
+        .expect("Failed to create test exchange");
+
+    let mut ws_handle = WebSocketClient::new(&fixture.server.ws_url)
+        .with_compression(true)
+        .connect()
+        .await
+        .expect("Failed to connect to WebSocket");
+    assert!(!ws_handle.is_compressed());
+
+    ws_handle.ping().expect("Failed to send ping");
+    let msg = tokio::time::timeout(Duration::from_secs(2), ws_handle.recv())
+        .await
+        .expect("Timed out waiting for pong")
+        .expect("Connection closed");
+    assert_eq!(msg["type"], "pong");
+}
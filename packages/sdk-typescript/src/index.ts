@@ -19,6 +19,10 @@
  * client.ws.on('trade', (message) => {
  *   console.log('New trade:', message.trade);
  * });
+ *
+ * // Private channels need a wallet signature first
+ * await client.authenticate(address, (message) => wallet.signMessage(message));
+ * client.onUserOrders(address, (order) => console.log(order));
  * ```
  */
 
@@ -33,6 +37,7 @@ import type {
   EnhancedOrderbookLevel,
 } from "./rest";
 import { WebSocketClient } from "./websocket";
+import type { MessageSigner } from "./websocket";
 import type { OrderbookLevel, TradeData, ServerMessage } from "./types/websocket";
 import { CacheService } from "./cache";
 import { EnhancementService } from "./enhancement";
@@ -41,6 +46,7 @@ import type { Logger, LogLevel } from "./logger";
 
 export { RestClient } from "./rest";
 export { WebSocketClient } from "./websocket";
+export type { MessageSigner } from "./websocket";
 export { CacheService } from "./cache";
 export { EnhancementService } from "./enhancement";
 export { ConsoleLogger, NoopLogger } from "./logger";
@@ -318,6 +324,20 @@ export class ExchangeClient {
   // Convenience Methods - WebSocket (delegate to ws)
   // ============================================================================
 
+  /**
+   * Sign in to the user's private channels with their wallet
+   */
+  authenticate(userAddress: string, signMessage: MessageSigner) {
+    return this.ws.authenticate(userAddress, signMessage);
+  }
+
+  /**
+   * Sign out of the user's private channels
+   */
+  signOut(): void {
+    this.ws.signOut();
+  }
+
   /**
    * Stream trades for a market
    */
@@ -19,14 +19,38 @@ export type ClientMessage =
       type: "unsubscribe";
       user_address?: string | null;
     }
+  | {
+      type: "auth_challenge";
+    }
+  | {
+      signature: string;
+      type: "auth";
+      user_address: string;
+    }
   | {
       type: "ping";
+    }
+  | {
+      type: "list_subscriptions";
+    }
+  | {
+      after_seq: number;
+      channel: SubscriptionChannel;
+      market_id: string;
+      type: "resume";
     };
 /**
  * Channel types for WebSocket subscriptions
  */
 
-export type SubscriptionChannel = "trades" | "orderbook" | "user_fills" | "user_orders" | "user_balances";
+export type SubscriptionChannel =
+  | "trades"
+  | "all_trades"
+  | "orderbook"
+  | "user_fills"
+  | "user_orders"
+  | "user_balances"
+  | "market_status";
 
 export type ServerMessage =
   | {
@@ -70,13 +94,40 @@ export type ServerMessage =
       type: "user_order";
     }
   | {
+      amount: string;
       available: string;
       locked: string;
+      open_interest: string;
       token_ticker: string;
       type: "user_balance";
       updated_at: number;
       user_address: string;
     }
+  | {
+      market_id: string;
+      status: MarketStatus;
+      type: "market_status";
+    }
+  | {
+      message: string;
+      nonce: string;
+      type: "auth_challenge";
+    }
+  | {
+      type: "authenticated";
+      user_address: string;
+    }
+  | {
+      subscriptions: ActiveSubscription[];
+      type: "subscriptions";
+    }
+  | {
+      channel: SubscriptionChannel;
+      market_id: string;
+      replayed: number;
+      snapshot: boolean;
+      type: "resumed";
+    }
   | {
       message: string;
       type: "error";
@@ -86,6 +137,14 @@ export type ServerMessage =
     };
 
 export type Side = "buy" | "sell";
+/**
+ * Trading phase of a market
+ * Pre-open markets collect resting limit orders without matching them; crossed
+ * orders match in time priority when the market opens. Halted and closed markets
+ * accept no new orders, while resting orders can still be cancelled
+ */
+
+export type MarketStatus = "pre_open" | "open" | "halted" | "closed";
 
 /**
  * Trade data for WebSocket messages (API layer with String fields)
@@ -99,6 +158,10 @@ export interface TradeData {
   price: string;
   seller_address: string;
   seller_order_id: string;
+  /**
+   * Market sequence number, shared with the market's orderbook updates
+   */
+  seq?: number;
   side: Side;
   size: string;
   timestamp: number;
@@ -107,10 +170,34 @@ export interface TradeData {
 export interface OrderbookData {
   asks: PriceLevel[];
   bids: PriceLevel[];
+  /**
+   * CRC32 of the top 25 levels per side, as `price:size` pairs joined by `,`
+   * with bids and asks separated by `|` (see OrderbookSnapshot::compute_checksum)
+   */
+  checksum: number;
+  /**
+   * True for the book sent in reply to a subscribe: replace any local state with it.
+   * False for the updates pushed afterwards. Updates currently carry the full book
+   * as well, but only a message with this flag set is guaranteed to be complete
+   */
+  is_snapshot?: boolean;
   market_id: string;
+  /**
+   * Market sequence number, shared with the market's trades (see ClientMessage::Resume)
+   */
+  seq?: number;
 }
 
 export interface PriceLevel {
   price: string;
   size: string;
 }
+/**
+ * A subscription held by the connection, with the arguments it was resolved to
+ */
+
+export interface ActiveSubscription {
+  channel: SubscriptionChannel;
+  market_id?: string | null;
+  user_address?: string | null;
+}
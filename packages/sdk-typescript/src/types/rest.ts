@@ -17,7 +17,9 @@ export interface paths {
          * Admin endpoint for test/dev operations
          * @description POST /api/admin
          *
-         *     Handles administrative operations like creating tokens, markets, and funding accounts.
+         *     Handles administrative operations like creating tokens, markets, funding accounts
+         *     (one at a time or in bulk), setting per-user fee tiers and per-market open order limits,
+         *     moving markets between trading phases, and the kill switch that cancels every resting order.
          *     In production, this endpoint should be protected or disabled.
          */
         post: operations["admin_handler"];
@@ -27,6 +29,29 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/book/historical": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Get a market's orderbook as it stood at a past time
+         * @description POST /api/book/historical
+         *
+         *     Rebuilt from the persisted orderbook snapshot and the engine request log,
+         *     for backtesting. Timestamps before any logged activity return an empty book.
+         */
+        post: operations["historical_book"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/candles": {
         parameters: {
             query?: never;
@@ -39,6 +64,8 @@ export interface paths {
         /**
          * Get OHLCV candles for a market
          * @description POST /api/candles
+         *
+         *     Supported intervals: 1m, 5m, 15m, 1h, 4h, 1d, 1w
          */
         post: operations["candles"];
         delete?: never;
@@ -64,6 +91,29 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/estimate": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Simulate an order against the live orderbook without placing it
+         * @description POST /api/estimate
+         *
+         *     Returns the projected fill size, average price, slippage versus the best
+         *     opposite-side price, and the size that would rest on the book.
+         */
+        post: operations["estimate"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/health": {
         parameters: {
             query?: never;
@@ -71,6 +121,13 @@ export interface paths {
             path?: never;
             cookie?: never;
         };
+        /**
+         * Report the status of each backend dependency
+         * @description GET /api/health
+         *
+         *     Returns 200 when every subsystem is up, otherwise 503 with the same body
+         *     so callers can see which subsystem is down.
+         */
         get: operations["health_check"];
         put?: never;
         post?: never;
@@ -97,6 +154,104 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/liquidity": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Get resting liquidity aggregated into price buckets
+         * @description POST /api/liquidity
+         *
+         *     Splits [from_price, to_price) into `buckets` contiguous buckets and sums
+         *     the resting bid and ask size in each. Unlike the orderbook snapshot this
+         *     covers any range, not just the top of the book. An empty range returns
+         *     no buckets.
+         */
+        post: operations["liquidity"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/markets/search": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Search markets with filtering, sorting and paging
+         * @description POST /api/markets/search
+         *
+         *     Filters by exact quote ticker and base ticker substring, then sorts
+         *     alphabetically or by 24h quote volume. `total` counts every match so
+         *     callers can page through with `offset`.
+         */
+        post: operations["search_markets"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/metrics": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Report runtime metrics
+         * @description GET /api/metrics
+         *
+         *     Exposes the engine request queue depth and how often a full queue
+         *     rejected or delayed a request, plus open WebSocket connections and
+         *     how many were dropped by the heartbeat checks.
+         */
+        get: operations["metrics"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/microstructure": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Get top-of-book microstructure indicators
+         * @description POST /api/microstructure
+         *
+         *     Computed from the engine's live book: best bid and ask, spread (absolute
+         *     and in bps of the mid), mid price, and the bid share of resting volume over
+         *     the top `depth` levels of each side. Spread and mid are null for a
+         *     one-sided book, imbalance is null for an empty one.
+         */
+        post: operations["microstructure"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/trade": {
         parameters: {
             query?: never;
@@ -106,7 +261,7 @@ export interface paths {
         };
         get?: never;
         put?: never;
-        /** Execute trades (place/cancel orders) */
+        /** Execute trades (place/cancel/replace orders) */
         post: operations["trade"];
         delete?: never;
         options?: never;
@@ -114,6 +269,31 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/trades": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Get a market's public trades, most recent first
+         * @description POST /api/trades
+         *
+         *     Optionally filtered to one taker side and a minimum size in base atoms,
+         *     e.g. only large buys, and to a time range. Filtering happens in the
+         *     ClickHouse query. A full page comes with a `next_cursor` for the next,
+         *     older page.
+         */
+        post: operations["trades"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/user": {
         parameters: {
             query?: never;
@@ -123,7 +303,7 @@ export interface paths {
         };
         get?: never;
         put?: never;
-        /** Get user-specific data (orders, balances, trades) */
+        /** Get user-specific data (orders, a single order, balances, trades, order history, positions) */
         post: operations["user"];
         delete?: never;
         options?: never;
@@ -131,6 +311,29 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/vwap": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Get the volume-weighted average price of a market over a time window
+         * @description POST /api/vwap
+         *
+         *     Aggregates trades in [from, to) from ClickHouse. `vwap` is null when
+         *     no trades happened in the window.
+         */
+        post: operations["vwap"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
 }
 export type webhooks = Record<string, never>;
 export interface components {
@@ -148,6 +351,8 @@ export interface components {
             lot_size: string;
             /** Format: int32 */
             maker_fee_bps: number;
+            max_price?: string | null;
+            min_price?: string | null;
             min_size: string;
             quote_ticker: string;
             /** Format: int32 */
@@ -162,6 +367,43 @@ export interface components {
             /** @enum {string} */
             type: "faucet";
             user_address: string;
+        } | {
+            grants: components["schemas"]["FaucetGrant"][];
+            /** @enum {string} */
+            type: "bulk_faucet";
+        } | {
+            /** Format: int32 */
+            maker_fee_tenth_bps: number;
+            /** Format: int32 */
+            taker_fee_tenth_bps: number;
+            /** @enum {string} */
+            type: "set_user_fee_tier";
+            user_address: string;
+        } | {
+            market_id: string;
+            max_open_notional_per_user?: string | null;
+            /** Format: int32 */
+            max_open_orders_per_user?: number | null;
+            /** @enum {string} */
+            type: "set_market_order_limits";
+        } | {
+            market_id: string;
+            min_notional?: string | null;
+            /** @enum {string} */
+            type: "set_market_min_notional";
+        } | {
+            market_id: string;
+            snap_to_tick: boolean;
+            /** @enum {string} */
+            type: "set_market_snap_to_tick";
+        } | {
+            /** @enum {string} */
+            type: "cancel_everything";
+        } | {
+            market_id: string;
+            status: components["schemas"]["MarketStatus"];
+            /** @enum {string} */
+            type: "set_market_status";
         };
         /** @description Admin response with type discriminator */
         AdminResponse: {
@@ -179,16 +421,52 @@ export interface components {
             /** @enum {string} */
             type: "faucet";
             user_address: string;
+        } | {
+            balances: components["schemas"]["ApiBalance"][];
+            /** @enum {string} */
+            type: "bulk_faucet";
+        } | {
+            fee_schedule: components["schemas"]["FeeSchedule"];
+            /** @enum {string} */
+            type: "set_user_fee_tier";
+            user_address: string;
+        } | {
+            market: components["schemas"]["ApiMarket"];
+            /** @enum {string} */
+            type: "set_market_order_limits";
+        } | {
+            market: components["schemas"]["ApiMarket"];
+            /** @enum {string} */
+            type: "set_market_min_notional";
+        } | {
+            market: components["schemas"]["ApiMarket"];
+            /** @enum {string} */
+            type: "set_market_snap_to_tick";
+        } | {
+            count: number;
+            /** @enum {string} */
+            type: "cancel_everything";
+        } | {
+            market: components["schemas"]["ApiMarket"];
+            /** @enum {string} */
+            type: "set_market_status";
         };
         /** @description API representation of Balance with String fields for JSON compatibility */
         ApiBalance: {
             amount: string;
+            available: string;
+            locked: string;
             open_interest: string;
             token_ticker: string;
             /** Format: date-time */
             updated_at: string;
             user_address: string;
         };
+        /** @description Total resting size at one price */
+        ApiBookLevel: {
+            price: string;
+            size: string;
+        };
         /** @description OHLCV candle data */
         ApiCandle: {
             close: number;
@@ -199,15 +477,31 @@ export interface components {
             timestamp: number;
             volume: number;
         };
+        /** @description Resting bid and ask size within [price_low, price_high) */
+        ApiLiquidityBucket: {
+            ask_size: string;
+            bid_size: string;
+            price_high: string;
+            price_low: string;
+        };
         /** @description API representation of Market with String fields for JSON compatibility */
         ApiMarket: {
             base_ticker: string;
             id: string;
+            last_price?: string | null;
             lot_size: string;
             /** Format: int32 */
             maker_fee_bps: number;
+            max_open_notional_per_user?: string | null;
+            /** Format: int32 */
+            max_open_orders_per_user?: number | null;
+            max_price?: string | null;
+            min_notional?: string | null;
+            min_price?: string | null;
             min_size: string;
             quote_ticker: string;
+            snap_to_tick?: boolean;
+            status?: components["schemas"]["MarketStatus"];
             /** Format: int32 */
             taker_fee_bps: number;
             tick_size: string;
@@ -224,10 +518,18 @@ export interface components {
             side: components["schemas"]["Side"];
             size: string;
             status: components["schemas"]["OrderStatus"];
+            time_in_force?: components["schemas"]["TimeInForce"];
             /** Format: date-time */
             updated_at: string;
             user_address: string;
         };
+        /** @description API representation of Position with String fields for JSON compatibility */
+        ApiPosition: {
+            avg_entry_price?: string | null;
+            market_id: string;
+            size: string;
+            user_address: string;
+        };
         ApiResponse: {
             message: string;
             /** Format: int64 */
@@ -242,6 +544,8 @@ export interface components {
             price: string;
             seller_address: string;
             seller_order_id: string;
+            /** Format: int64 */
+            sequence?: number;
             side: components["schemas"]["Side"];
             size: string;
             /** Format: date-time */
@@ -261,6 +565,11 @@ export interface components {
         CandlesResponse: {
             candles: components["schemas"]["ApiCandle"][];
         };
+        /**
+         * @description Status of a single backend dependency
+         * @enum {string}
+         */
+        ComponentStatus: "up" | "down";
         /** @description Drip request with type discriminator */
         DripRequest: {
             amount: string;
@@ -279,9 +588,86 @@ export interface components {
             type: "faucet";
             user_address: string;
         };
+        /** @description Engine request queue depth and saturation counters */
+        EngineQueueMetrics: {
+            /** Format: int64 */
+            blocked: number;
+            capacity: number;
+            depth: number;
+            policy: components["schemas"]["QueueFullPolicy"];
+            /** Format: int64 */
+            rejected: number;
+        };
+        /** @description Body of every REST error response */
         ErrorResponse: {
+            /** @description Stable machine readable error code, e.g. "MARKET_NOT_FOUND" */
             code: string;
+            /** @description Human readable description of the error */
             error: string;
+            reason?: null | components["schemas"]["RejectReason"];
+        };
+        /** @description Request to simulate an order against the live orderbook without placing it */
+        EstimateRequest: {
+            market_id: string;
+            min_fill?: string | null;
+            order_type: components["schemas"]["OrderType"];
+            price: string;
+            side: components["schemas"]["Side"];
+            size: string;
+            time_in_force?: null | components["schemas"]["TimeInForce"];
+            user_address?: string | null;
+        };
+        /** @description Projected outcome of an order if it were placed now */
+        EstimateResponse: {
+            average_price?: string | null;
+            best_price?: string | null;
+            fillable_size: string;
+            levels_consumed: number;
+            market_id: string;
+            resting_size: string;
+            /** Format: double */
+            slippage_bps?: number | null;
+        };
+        /** @description Tokens credited to one user by a bulk faucet */
+        FaucetGrant: {
+            amount: string;
+            token_ticker: string;
+            user_address: string;
+        };
+        /**
+         * @description Maker/taker fee rates in tenths of a basis point (1 = 0.001%)
+         *     Resolved per user: a fee tier override if one is set, otherwise the market defaults
+         */
+        FeeSchedule: {
+            /** Format: int32 */
+            maker_fee_tenth_bps: number;
+            /** Format: int32 */
+            taker_fee_tenth_bps: number;
+        };
+        /** @description Health check response with per-subsystem status */
+        HealthStatus: {
+            clickhouse: components["schemas"]["ComponentStatus"];
+            engine: components["schemas"]["ComponentStatus"];
+            postgres: components["schemas"]["ComponentStatus"];
+            status: components["schemas"]["OverallStatus"];
+            /** Format: int64 */
+            timestamp: number;
+        };
+        /** @description Request for a market's orderbook as it stood at a past time */
+        HistoricalBookRequest: {
+            market_id: string;
+            /** Format: int64 */
+            timestamp: number;
+        };
+        /** @description Orderbook rebuilt from the persisted snapshot and request log, empty before any activity */
+        HistoricalBookResponse: {
+            asks: components["schemas"]["ApiBookLevel"][];
+            bids: components["schemas"]["ApiBookLevel"][];
+            /** Format: int32 */
+            checksum: number;
+            market_id: string;
+            /** Format: int64 */
+            timestamp: number;
         };
         /** @description Info request with type discriminator */
         InfoRequest: {
@@ -292,12 +678,24 @@ export interface components {
             market_id: string;
             /** @enum {string} */
             type: "market_details";
+        } | {
+            market_id: string;
+            /** @enum {string} */
+            type: "market_full";
         } | {
             /** @enum {string} */
             type: "all_markets";
         } | {
             /** @enum {string} */
             type: "all_tokens";
+        } | {
+            market_ids: string[];
+            /** @enum {string} */
+            type: "markets";
+        } | {
+            tickers: string[];
+            /** @enum {string} */
+            type: "tokens";
         };
         /** @description Info response with type discriminator */
         InfoResponse: {
@@ -308,6 +706,12 @@ export interface components {
             market: components["schemas"]["ApiMarket"];
             /** @enum {string} */
             type: "market_details";
+        } | {
+            base_token: components["schemas"]["Token"];
+            market: components["schemas"]["ApiMarket"];
+            quote_token: components["schemas"]["Token"];
+            /** @enum {string} */
+            type: "market_full";
         } | {
             markets: components["schemas"]["ApiMarket"][];
             /** @enum {string} */
@@ -316,27 +720,138 @@ export interface components {
             tokens: components["schemas"]["Token"][];
             /** @enum {string} */
             type: "all_tokens";
+        } | {
+            markets: components["schemas"]["ApiMarket"][];
+            /** @enum {string} */
+            type: "markets";
+        } | {
+            tokens: components["schemas"]["Token"][];
+            /** @enum {string} */
+            type: "tokens";
         };
-        /** @enum {string} */
-        OrderStatus: "pending" | "filled" | "partially_filled" | "cancelled";
-        /** @enum {string} */
-        OrderType: "limit" | "market";
-        /** @enum {string} */
-        Side: "buy" | "sell";
-        Token: {
+        /** @description Request for resting liquidity across a price range */
+        LiquidityRequest: {
             /** Format: int32 */
-            decimals: number;
-            name: string;
-            ticker: string;
+            buckets: number;
+            from_price: string;
+            market_id: string;
+            to_price: string;
         };
-        /** @description Trade request with type discriminator */
-        TradeRequest: {
+        /** @description Resting liquidity per price bucket, empty when the range is empty */
+        LiquidityResponse: {
+            buckets: components["schemas"]["ApiLiquidityBucket"][];
             market_id: string;
+        };
+        /** @description Filtered, sorted and paged market listing */
+        MarketSearchRequest: {
+            base_ticker?: string | null;
+            /** Format: int32 */
+            limit?: number | null;
+            /** Format: int32 */
+            offset?: number | null;
+            quote_ticker?: string | null;
+            sort_by?: components["schemas"]["MarketSortBy"];
+        };
+        /** @description One page of matching markets */
+        MarketSearchResponse: {
+            markets: components["schemas"]["ApiMarket"][];
+            total: number;
+        };
+        /**
+         * @description Order of market search results
+         * @enum {string}
+         */
+        MarketSortBy: "alphabetical" | "volume_24h";
+        /**
+         * @description Trading phase of a market
+         *     Pre-open markets collect resting limit orders without matching them; crossed
+         *     orders match in time priority when the market opens. Halted and closed markets
+         *     accept no new orders, while resting orders can still be cancelled
+         * @enum {string}
+         */
+        MarketStatus: "pre_open" | "open" | "halted" | "closed";
+        /** @description Runtime metrics for the backend */
+        MetricsResponse: {
+            engine_queue: components["schemas"]["EngineQueueMetrics"];
+            websocket: components["schemas"]["WebSocketMetrics"];
+        };
+        /** @description Request for top-of-book microstructure indicators */
+        MicrostructureRequest: {
+            /** Format: int32 */
+            depth?: number | null;
+            market_id: string;
+        };
+        /**
+         * @description Spread, mid price and order-book imbalance from the live book
+         *     Spread and mid price are null unless both sides have orders
+         */
+        MicrostructureResponse: {
+            ask_volume: string;
+            best_ask?: string | null;
+            best_bid?: string | null;
+            bid_volume: string;
+            /** Format: int32 */
+            depth: number;
+            /** Format: double */
+            imbalance?: number | null;
+            market_id: string;
+            mid_price?: string | null;
+            spread?: string | null;
+            /** Format: double */
+            spread_bps?: number | null;
+        };
+        /** @description Locked funds released back to the user when an order was cancelled */
+        OrderRefund: {
+            amount: string;
+            order_id: string;
+            token: string;
+        };
+        /** @enum {string} */
+        OrderStatus: "pending" | "filled" | "partially_filled" | "cancelled";
+        /** @enum {string} */
+        OrderType: "limit" | "market";
+        /**
+         * @description Overall health, degraded when any dependency is down
+         * @enum {string}
+         */
+        OverallStatus: "ok" | "degraded";
+        /**
+         * @description What happens to a request when the engine request queue is full
+         *     Reject fails it immediately with 503, Block waits for a free slot
+         * @enum {string}
+         */
+        QueueFullPolicy: "reject" | "block";
+        /**
+         * @description Why the exchange refused an order, for clients to react to without parsing messages
+         * @enum {string}
+         */
+        RejectReason: "TICK_SIZE" | "LOT_SIZE" | "MIN_SIZE" | "MIN_NOTIONAL" | "INSUFFICIENT_BALANCE" | "MARKET_HALTED" | "POST_ONLY_CROSS" | "SELF_TRADE" | "RATE_LIMITED";
+        /** @enum {string} */
+        Side: "buy" | "sell";
+        /**
+         * @description How long an order may stay on the book
+         *     Ioc orders cancel any remainder that does not match immediately, post-only
+         *     orders are rejected instead of taking liquidity
+         * @enum {string}
+         */
+        TimeInForce: "gtc" | "ioc" | "post_only";
+        Token: {
+            /** Format: int32 */
+            decimals: number;
+            name: string;
+            ticker: string;
+        };
+        /** @description Trade request with type discriminator */
+        TradeRequest: {
+            client_order_id?: string | null;
+            market_id: string;
+            min_fill?: string | null;
             order_type: components["schemas"]["OrderType"];
             price: string;
             side: components["schemas"]["Side"];
             signature: string;
             size: string;
+            time_in_force?: null | components["schemas"]["TimeInForce"];
             /** @enum {string} */
             type: "place_order";
             user_address: string;
@@ -352,10 +867,25 @@ export interface components {
             /** @enum {string} */
             type: "cancel_all_orders";
             user_address: string;
+        } | {
+            market_id: string;
+            order_id: string;
+            order_type: components["schemas"]["OrderType"];
+            price: string;
+            side: components["schemas"]["Side"];
+            signature: string;
+            size: string;
+            time_in_force?: null | components["schemas"]["TimeInForce"];
+            /** @enum {string} */
+            type: "replace_order";
+            user_address: string;
         };
         /** @description Trade response with type discriminator */
         TradeResponse: {
+            average_price: string;
             order: components["schemas"]["ApiOrder"];
+            requested_price?: string | null;
+            total_quote: string;
             trades: components["schemas"]["ApiTrade"][];
             /** @enum {string} */
             type: "place_order";
@@ -366,8 +896,37 @@ export interface components {
         } | {
             cancelled_order_ids: string[];
             count: number;
+            refunds?: components["schemas"]["OrderRefund"][];
             /** @enum {string} */
             type: "cancel_all_orders";
+        } | {
+            average_price: string;
+            cancelled_order_id: string;
+            order: components["schemas"]["ApiOrder"];
+            requested_price?: string | null;
+            total_quote: string;
+            trades: components["schemas"]["ApiTrade"][];
+            /** @enum {string} */
+            type: "replace_order";
+        };
+        /** @description Request for a market's public trades, most recent first */
+        TradesRequest: {
+            cursor?: string | null;
+            /** Format: int64 */
+            from?: number | null;
+            /** Format: int32 */
+            limit?: number | null;
+            market_id: string;
+            min_size?: string | null;
+            side?: null | components["schemas"]["Side"];
+            /** Format: int64 */
+            to?: number | null;
+        };
+        /** @description Response containing public trades */
+        TradesResponse: {
+            /** @description Pass as `cursor` to get the next (older) page, None on the last page */
+            next_cursor?: string | null;
+            trades: components["schemas"]["ApiTrade"][];
         };
         /** @description User request with type discriminator */
         UserRequest: {
@@ -378,6 +937,11 @@ export interface components {
             /** @enum {string} */
             type: "orders";
             user_address: string;
+        } | {
+            order_id: string;
+            /** @enum {string} */
+            type: "order";
+            user_address: string;
         } | {
             /** @enum {string} */
             type: "balances";
@@ -389,12 +953,31 @@ export interface components {
             /** @enum {string} */
             type: "trades";
             user_address: string;
+        } | {
+            /** Format: int64 */
+            from?: number | null;
+            /** Format: int32 */
+            limit?: number | null;
+            market_id?: string | null;
+            /** Format: int64 */
+            to?: number | null;
+            /** @enum {string} */
+            type: "order_history";
+            user_address: string;
+        } | {
+            /** @enum {string} */
+            type: "positions";
+            user_address: string;
         };
         /** @description User response with type discriminator */
         UserResponse: {
             orders: components["schemas"]["ApiOrder"][];
             /** @enum {string} */
             type: "orders";
+        } | {
+            order: components["schemas"]["ApiOrder"];
+            /** @enum {string} */
+            type: "order";
         } | {
             balances: components["schemas"]["ApiBalance"][];
             /** @enum {string} */
@@ -403,6 +986,43 @@ export interface components {
             trades: components["schemas"]["ApiTrade"][];
             /** @enum {string} */
             type: "trades";
+        } | {
+            orders: components["schemas"]["ApiOrder"][];
+            /** @enum {string} */
+            type: "order_history";
+        } | {
+            positions: components["schemas"]["ApiPosition"][];
+            /** @enum {string} */
+            type: "positions";
+        };
+        /** @description Request for the volume-weighted average price over a time window */
+        VwapRequest: {
+            /** Format: int64 */
+            from: number;
+            market_id: string;
+            /** Format: int64 */
+            to: number;
+        };
+        /** @description Volume-weighted average price and traded volume over a time window */
+        VwapResponse: {
+            base_volume: string;
+            /** Format: int64 */
+            from: number;
+            market_id: string;
+            quote_volume: string;
+            /** Format: int64 */
+            to: number;
+            /** Format: int64 */
+            trade_count: number;
+            vwap?: string | null;
+        };
+        /** @description WebSocket connection count and heartbeat disconnects */
+        WebSocketMetrics: {
+            active_connections: number;
+            /** Format: int64 */
+            idle_disconnects: number;
+            /** Format: int64 */
+            pong_timeouts: number;
         };
     };
     responses: never;
@@ -444,6 +1064,75 @@ export interface operations {
                     "application/json": components["schemas"]["ErrorResponse"];
                 };
             };
+            /** @description Referenced token or market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Token or market already exists */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
+    historical_book: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["HistoricalBookRequest"];
+            };
+        };
+        responses: {
+            /** @description Orderbook rebuilt */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["HistoricalBookResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
             /** @description Internal server error */
             500: {
                 headers: {
@@ -482,14 +1171,27 @@ export interface operations {
                 headers: {
                     [name: string]: unknown;
                 };
-                content?: never;
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
             };
             /** @description Internal server error */
             500: {
                 headers: {
                     [name: string]: unknown;
                 };
-                content?: never;
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
             };
         };
     };
@@ -553,6 +1255,66 @@ export interface operations {
             };
         };
     };
+    estimate: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["EstimateRequest"];
+            };
+        };
+        responses: {
+            /** @description Estimate computed */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["EstimateResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Engine request queue is full */
+            503: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
     health_check: {
         parameters: {
             query?: never;
@@ -562,13 +1324,22 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Health check response */
+            /** @description All subsystems are up */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["ApiResponse"];
+                    "application/json": components["schemas"]["HealthStatus"];
+                };
+            };
+            /** @description One or more subsystems are down */
+            503: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["HealthStatus"];
                 };
             };
         };
@@ -624,7 +1395,7 @@ export interface operations {
             };
         };
     };
-    trade: {
+    liquidity: {
         parameters: {
             query?: never;
             header?: never;
@@ -633,17 +1404,17 @@ export interface operations {
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["TradeRequest"];
+                "application/json": components["schemas"]["LiquidityRequest"];
             };
         };
         responses: {
-            /** @description Success */
+            /** @description Liquidity profile computed */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["TradeResponse"];
+                    "application/json": components["schemas"]["LiquidityResponse"];
                 };
             };
             /** @description Invalid request parameters */
@@ -655,8 +1426,8 @@ export interface operations {
                     "application/json": components["schemas"]["ErrorResponse"];
                 };
             };
-            /** @description Invalid signature */
-            401: {
+            /** @description Market not found */
+            404: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -664,8 +1435,8 @@ export interface operations {
                     "application/json": components["schemas"]["ErrorResponse"];
                 };
             };
-            /** @description Order not found */
-            404: {
+            /** @description Internal server error */
+            500: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -673,8 +1444,8 @@ export interface operations {
                     "application/json": components["schemas"]["ErrorResponse"];
                 };
             };
-            /** @description Internal server error */
-            500: {
+            /** @description Engine request queue is full */
+            503: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -684,7 +1455,7 @@ export interface operations {
             };
         };
     };
-    user: {
+    search_markets: {
         parameters: {
             query?: never;
             header?: never;
@@ -693,11 +1464,271 @@ export interface operations {
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["UserRequest"];
+                "application/json": components["schemas"]["MarketSearchRequest"];
             };
         };
         responses: {
-            /** @description Success */
+            /** @description Matching markets */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MarketSearchResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
+    metrics: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Current metrics */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MetricsResponse"];
+                };
+            };
+        };
+    };
+    microstructure: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["MicrostructureRequest"];
+            };
+        };
+        responses: {
+            /** @description Indicators computed */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MicrostructureResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Engine request queue is full */
+            503: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
+    trade: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TradeRequest"];
+            };
+        };
+        responses: {
+            /** @description Success */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TradeResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Invalid signature */
+            401: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Order or market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not open, or order already filled or cancelled */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Cancel rate limit exceeded */
+            429: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Engine request queue is full */
+            503: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
+    trades: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TradesRequest"];
+            };
+        };
+        responses: {
+            /** @description Trades retrieved successfully */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TradesResponse"];
+                };
+            };
+            /** @description Invalid parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
+    user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UserRequest"];
+            };
+        };
+        responses: {
+            /** @description Success */
             200: {
                 headers: {
                     [name: string]: unknown;
@@ -735,4 +1766,55 @@ export interface operations {
             };
         };
     };
+    vwap: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["VwapRequest"];
+            };
+        };
+        responses: {
+            /** @description VWAP computed */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VwapResponse"];
+                };
+            };
+            /** @description Invalid request parameters */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Market not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ErrorResponse"];
+                };
+            };
+        };
+    };
 }
@@ -20,6 +20,16 @@ export interface WebSocketClientConfig {
 
 type MessageType = ServerMessage["type"];
 
+/**
+ * Signs a message with the user's wallet (EIP-191 personal_sign), returning the 0x-prefixed signature
+ */
+export type MessageSigner = (message: string) => Promise<string>;
+
+interface Session {
+  userAddress: string;
+  signMessage: MessageSigner;
+}
+
 // Subscription key for tracking active subscriptions
 type SubscriptionKey = string;
 
@@ -34,6 +44,12 @@ export class WebSocketClient {
   private pingTimer: ReturnType<typeof setInterval> | null = null;
   private lastPongTime: number = Date.now();
   private pongTimeout: number = 60000; // 60 seconds
+  private authTimeout: number = 30000; // 30 seconds per sign-in step
+
+  // User to sign in as on every (re)connect, before subscriptions are sent
+  private session: Session | null = null;
+  private authenticatedUser: string | null = null;
+  private isAuthenticating = false;
 
   private messageQueue: ClientMessage[] = [];
   private handlers = new Map<MessageType, Set<MessageHandler>>();
@@ -71,17 +87,11 @@ export class WebSocketClient {
         this.reconnectAttempt = 0;
         this.lastPongTime = Date.now();
 
-        // Re-subscribe to all active subscriptions after reconnect
-        this.resubscribeAll();
-
-        // Send queued messages
-        while (this.messageQueue.length > 0) {
-          const msg = this.messageQueue.shift()!;
-          this.send(msg);
-        }
-
         // Start ping timer
         this.startPingTimer();
+
+        // Sign in, then re-subscribe and send queued messages
+        void this.restoreSession();
       };
 
       this.ws.onmessage = (event) => {
@@ -99,6 +109,7 @@ export class WebSocketClient {
 
       this.ws.onclose = () => {
         this.isConnected = false;
+        this.authenticatedUser = null;
         this.stopPingTimer();
         this.scheduleReconnect();
       };
@@ -124,9 +135,48 @@ export class WebSocketClient {
     }
 
     this.isConnected = false;
+    this.authenticatedUser = null;
     this.messageQueue = [];
   }
 
+  /**
+   * Sign in as a user, required before subscribing to user_fills, user_orders or user_balances
+   * The server issues a one-time challenge for `signMessage` to sign with the user's wallet.
+   * Messages sent meanwhile are held until sign-in completes, and the session is
+   * re-established after every reconnect
+   */
+  async authenticate(userAddress: string, signMessage: MessageSigner): Promise<void> {
+    const previous = this.authenticatedUser;
+    this.session = { userAddress, signMessage };
+    if (previous === userAddress) {
+      return;
+    }
+
+    if (previous !== null) {
+      // A connection signs in only once, reconnect to switch users
+      this.ws?.close();
+      return;
+    }
+
+    if (this.isReady()) {
+      try {
+        await this.signIn(this.session);
+      } finally {
+        this.flushQueue();
+      }
+    }
+  }
+
+  /**
+   * Forget the signed-in user, reconnecting so the server drops it as well
+   */
+  signOut(): void {
+    this.session = null;
+    if (this.authenticatedUser !== null) {
+      this.ws?.close();
+    }
+  }
+
   /**
    * Subscribe to a channel (with reference counting to prevent duplicates)
    */
@@ -243,6 +293,16 @@ export class WebSocketClient {
   // ===== Private Methods =====
 
   private send(message: ClientMessage): void {
+    // Hold everything but the sign-in handshake until it completes
+    if (this.isAuthenticating) {
+      this.messageQueue.push(message);
+      return;
+    }
+
+    this.sendNow(message);
+  }
+
+  private sendNow(message: ClientMessage): void {
     if (!this.isConnected || this.ws?.readyState !== WebSocket.OPEN) {
       this.messageQueue.push(message);
       return;
@@ -256,6 +316,73 @@ export class WebSocketClient {
     }
   }
 
+  private flushQueue(): void {
+    this.messageQueue.splice(0).forEach((msg) => this.send(msg));
+  }
+
+  /**
+   * Sign in on a fresh connection, then re-subscribe to all active subscriptions
+   */
+  private async restoreSession(): Promise<void> {
+    if (this.session) {
+      try {
+        await this.signIn(this.session);
+      } catch (error) {
+        this.logger.error("WebSocket authentication failed", error);
+      }
+    }
+
+    this.resubscribeAll();
+    this.flushQueue();
+  }
+
+  /**
+   * Answer a server-issued challenge with the user's signature
+   */
+  private async signIn({ userAddress, signMessage }: Session): Promise<void> {
+    this.isAuthenticating = true;
+    try {
+      const challenge = this.nextReply("auth_challenge");
+      this.sendNow({ type: "auth_challenge" });
+      const { message } = await challenge;
+      const signature = await signMessage(message);
+
+      const authenticated = this.nextReply("authenticated");
+      this.sendNow({ type: "auth", user_address: userAddress, signature });
+      await authenticated;
+
+      this.authenticatedUser = userAddress;
+      this.logger.info(`Authenticated as ${userAddress}`);
+    } finally {
+      this.isAuthenticating = false;
+    }
+  }
+
+  /**
+   * Wait for the next message of a type, failing on an error message or after authTimeout
+   */
+  private nextReply<T extends MessageType>(type: T): Promise<Extract<ServerMessage, { type: T }>> {
+    return new Promise((resolve, reject) => {
+      const done = () => {
+        clearTimeout(timer);
+        removeReply();
+        removeError();
+      };
+      const timer = setTimeout(() => {
+        done();
+        reject(new WebSocketError(`Timed out waiting for ${type}`));
+      }, this.authTimeout);
+      const removeReply = this.on(type, (msg) => {
+        done();
+        resolve(msg);
+      });
+      const removeError = this.on("error", (msg) => {
+        done();
+        reject(new WebSocketError(msg.message));
+      });
+    });
+  }
+
   private handleMessage(message: ServerMessage): void {
     // Handle pong messages automatically
     if (message.type === "pong") {
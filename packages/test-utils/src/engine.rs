@@ -1,8 +1,12 @@
 use crate::db::TestDb;
 use crate::helpers;
+use backend::config::EngineConfig;
 use backend::db::Db;
 use backend::engine::MatchingEngine;
-use backend::models::domain::{EngineEvent, EngineRequest, Order, OrderStatus, OrderType, Side};
+use backend::models::domain::{
+    EngineEvent, EngineRequest, Market, MarketStatus, Order, OrderStatus, OrderType,
+    OrderbookSnapshot, Side, TimeInForce, Trade,
+};
 use chrono::Utc;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use uuid::Uuid;
@@ -27,6 +31,15 @@ impl TestEngine {
 
     /// Create a new TestEngine, optionally creating common test users
     pub async fn new_with_users(test_db: &TestDb, create_users: bool) -> Self {
+        Self::new_with_config(test_db, create_users, EngineConfig::default()).await
+    }
+
+    /// Create a new TestEngine with explicit engine settings
+    pub async fn new_with_config(
+        test_db: &TestDb,
+        create_users: bool,
+        config: EngineConfig,
+    ) -> Self {
         // Create common test users for engine tests only
         if create_users {
             let users = vec![
@@ -93,7 +106,8 @@ impl TestEngine {
         let (engine_tx, engine_rx) = mpsc::channel::<EngineRequest>(100);
         let (event_tx, event_rx) = broadcast::channel::<EngineEvent>(1000);
 
-        let engine = MatchingEngine::new(test_db.db.clone(), engine_rx, event_tx.clone());
+        let engine =
+            MatchingEngine::with_config(test_db.db.clone(), engine_rx, event_tx.clone(), config);
 
         // Spawn engine in background
         tokio::spawn(async move {
@@ -113,15 +127,38 @@ impl TestEngine {
         self.event_tx.clone()
     }
 
+    /// Broadcast a synthetic event to all subscribers, as if the engine emitted it
+    ///
+    /// Bypasses matching and the database entirely, so WebSocket fan-out can be
+    /// exercised with arbitrary event volumes. Returns the number of receivers
+    /// the event was delivered to (0 if there are none).
+    pub fn inject_event(&self, event: EngineEvent) -> usize {
+        self.event_tx.send(event).unwrap_or(0)
+    }
+
     /// Helper to place an order and get the response
     pub async fn place_order(
         &self,
         order: Order,
+    ) -> Result<backend::models::api::OrderPlaced, String> {
+        self.place_order_with_client_order_id(order, None).await
+    }
+
+    /// Helper to place an order under an optional idempotency key
+    pub async fn place_order_with_client_order_id(
+        &self,
+        order: Order,
+        client_order_id: Option<String>,
     ) -> Result<backend::models::api::OrderPlaced, String> {
         let (response_tx, response_rx) = oneshot::channel();
 
         self.engine_tx
-            .send(EngineRequest::PlaceOrder { order, response_tx })
+            .send(EngineRequest::PlaceOrder {
+                order,
+                client_order_id,
+                request_id: Uuid::new_v4(),
+                response_tx,
+            })
             .await
             .map_err(|e| format!("Failed to send order: {}", e))?;
 
@@ -143,6 +180,7 @@ impl TestEngine {
             .send(EngineRequest::CancelOrder {
                 order_id,
                 user_address,
+                request_id: Uuid::new_v4(),
                 response_tx,
             })
             .await
@@ -154,6 +192,118 @@ impl TestEngine {
             .map_err(|e| format!("Order cancellation failed: {}", e))
     }
 
+    /// Helper to atomically cancel an order and place its replacement
+    pub async fn replace_order(
+        &self,
+        old_order_id: Uuid,
+        new_order: Order,
+    ) -> Result<backend::models::api::OrderReplaced, String> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.engine_tx
+            .send(EngineRequest::ReplaceOrder {
+                old_order_id,
+                user_address: new_order.user_address.clone(),
+                new_order,
+                request_id: Uuid::new_v4(),
+                response_tx,
+            })
+            .await
+            .map_err(|e| format!("Failed to send replace request: {}", e))?;
+
+        response_rx
+            .await
+            .map_err(|e| format!("Failed to receive response: {}", e))?
+            .map_err(|e| format!("Order replacement failed: {}", e))
+    }
+
+    /// Helper to cancel all of a user's orders, optionally for a single market
+    pub async fn cancel_all_orders(
+        &self,
+        user_address: String,
+        market_id: Option<String>,
+    ) -> Result<backend::models::api::OrdersCancelled, String> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.engine_tx
+            .send(EngineRequest::CancelAllOrders {
+                user_address,
+                market_id,
+                request_id: Uuid::new_v4(),
+                response_tx,
+            })
+            .await
+            .map_err(|e| format!("Failed to send cancel all request: {}", e))?;
+
+        response_rx
+            .await
+            .map_err(|e| format!("Failed to receive response: {}", e))?
+            .map_err(|e| format!("Cancel all failed: {}", e))
+    }
+
+    /// Helper to trigger the kill switch, cancelling every resting order
+    pub async fn cancel_everything(&self) -> Result<usize, String> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.engine_tx
+            .send(EngineRequest::CancelEverything { response_tx })
+            .await
+            .map_err(|e| format!("Failed to send cancel everything request: {}", e))?;
+
+        response_rx
+            .await
+            .map_err(|e| format!("Failed to receive response: {}", e))?
+            .map_err(|e| format!("Cancel everything failed: {}", e))
+    }
+
+    /// Helper to move a market to another trading phase
+    pub async fn set_market_status(
+        &self,
+        market_id: &str,
+        status: MarketStatus,
+    ) -> Result<Market, String> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.engine_tx
+            .send(EngineRequest::SetMarketStatus {
+                market_id: market_id.to_string(),
+                status,
+                response_tx,
+            })
+            .await
+            .map_err(|e| format!("Failed to send market status request: {}", e))?;
+
+        response_rx
+            .await
+            .map_err(|e| format!("Failed to receive response: {}", e))?
+            .map_err(|e| format!("Market status change failed: {}", e))
+    }
+
+    /// Helper to fetch the engine's current orderbook snapshot for a market
+    pub async fn get_orderbook_snapshot(&self, market_id: &str) -> OrderbookSnapshot {
+        self.get_grouped_orderbook_snapshot(market_id, None).await
+    }
+
+    /// Helper to fetch the orderbook snapshot with levels grouped into price buckets
+    pub async fn get_grouped_orderbook_snapshot(
+        &self,
+        market_id: &str,
+        group_by: Option<u128>,
+    ) -> OrderbookSnapshot {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.engine_tx
+            .send(EngineRequest::GetOrderbookSnapshot {
+                market_id: market_id.to_string(),
+                group_by,
+                response_tx,
+            })
+            .await
+            .expect("Engine stopped");
+
+        response_rx.await.expect("Engine dropped snapshot request")
+    }
+
     /// Helper to create a test order
     pub fn create_order(
         user_address: &str,
@@ -171,10 +321,33 @@ impl TestEngine {
             size,
             side,
             order_type,
+            time_in_force: TimeInForce::Gtc,
+            min_fill: None,
             status: OrderStatus::Pending,
             filled_size: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
     }
+
+    /// Helper to create a synthetic trade event for injection
+    ///
+    /// Buyer/seller and order ids are placeholders; nothing is persisted.
+    pub fn create_trade_event(market_id: &str, side: Side, price: u128, size: u128) -> EngineEvent {
+        EngineEvent::TradeExecuted {
+            trade: Trade {
+                id: Uuid::new_v4(),
+                market_id: market_id.to_string(),
+                buyer_address: "buyer".to_string(),
+                seller_address: "seller".to_string(),
+                buyer_order_id: Uuid::new_v4(),
+                seller_order_id: Uuid::new_v4(),
+                price,
+                size,
+                side,
+                timestamp: Utc::now(),
+                sequence: 0,
+            },
+        }
+    }
 }
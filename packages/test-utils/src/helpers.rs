@@ -46,6 +46,8 @@ pub async fn create_market(
             1000000, // min_size
             10,      // maker_fee_bps
             20,      // taker_fee_bps
+            None,    // min_price
+            None,    // max_price
         )
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create test market: {}", e))
@@ -117,6 +119,7 @@ pub async fn create_candle(
             side: backend::models::domain::Side::Buy,
             timestamp: chrono::DateTime::from_timestamp(*ts as i64, 0)
                 .unwrap_or(chrono::DateTime::UNIX_EPOCH),
+            sequence: i as u64 + 1,
         };
 
         test_db
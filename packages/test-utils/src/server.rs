@@ -2,7 +2,10 @@ use crate::db::TestDb;
 use crate::engine::TestEngine;
 use axum::Router;
 use backend::api::{rest, ws};
+use backend::config::{EngineConfig, WebSocketConfig};
 use backend::db::Db;
+use backend::engine::queue::EngineQueue;
+use backend::models::domain::{EngineEvent, QueueFullPolicy};
 use backend::AppState;
 use tower_http::cors::CorsLayer;
 
@@ -31,20 +34,42 @@ impl TestServer {
     ///
     /// The server runs in the background and will shutdown when dropped.
     pub async fn start() -> anyhow::Result<Self> {
+        Self::start_with_ws_config(WebSocketConfig::default()).await
+    }
+
+    /// Start a test server with explicit WebSocket settings
+    pub async fn start_with_ws_config(ws_config: WebSocketConfig) -> anyhow::Result<Self> {
+        Self::start_with_config(ws_config, EngineConfig::default()).await
+    }
+
+    /// Start a test server with explicit engine settings
+    pub async fn start_with_engine_config(engine_config: EngineConfig) -> anyhow::Result<Self> {
+        Self::start_with_config(WebSocketConfig::default(), engine_config).await
+    }
+
+    async fn start_with_config(
+        ws_config: WebSocketConfig,
+        engine_config: EngineConfig,
+    ) -> anyhow::Result<Self> {
         // Setup database
         let test_db = TestDb::setup().await?;
 
         // Setup matching engine using TestEngine (without creating users)
         // Integration tests will create their own users
-        let test_engine = TestEngine::new_with_users(&test_db, false).await;
+        let test_engine = TestEngine::new_with_config(&test_db, false, engine_config).await;
 
         // Create REST and WebSocket routes
         let rest = rest::create_rest();
         let ws = ws::create_ws();
+        let event_tx = test_engine.event_tx();
+        let market_feed = ws::MarketFeed::new(&event_tx, ws_config.replay_buffer_size);
         let state = AppState {
             db: test_engine.db.clone(),
-            engine_tx: test_engine.engine_tx.clone(),
-            event_tx: test_engine.event_tx(),
+            engine: EngineQueue::new(test_engine.engine_tx.clone(), QueueFullPolicy::default()),
+            event_tx,
+            ws_config,
+            market_feed,
+            ws_metrics: ws::WsMetrics::new(),
         };
         let app = Router::new()
             .merge(rest)
@@ -122,4 +147,20 @@ impl TestServer {
     pub fn engine(&self) -> &TestEngine {
         &self.test_engine
     }
+
+    /// Broadcast a synthetic engine event to every connected WebSocket client
+    ///
+    /// See [`TestEngine::inject_event`]. Useful for load and fan-out tests:
+    ///
+    /// ```no_run
+    /// # use exchange_test_utils::{TestEngine, TestServer};
+    /// # use backend::models::domain::Side;
+    /// # fn example(server: &TestServer) {
+    /// let event = TestEngine::create_trade_event("BTC/USDC", Side::Buy, 50_000_000_000, 1_000_000);
+    /// server.inject_event(event);
+    /// # }
+    /// ```
+    pub fn inject_event(&self, event: EngineEvent) -> usize {
+        self.test_engine.inject_event(event)
+    }
 }